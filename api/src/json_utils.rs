@@ -1,7 +1,18 @@
 use {
     crate::jsonrpc::JsonRpcError,
+    alloy::{
+        hex,
+        primitives::Bytes,
+        sol_types::{Revert, SolError},
+    },
+    move_core_types::{
+        account_address::AccountAddress,
+        language_storage::StructTag,
+        value::{MoveStructLayout, MoveTypeLayout, MoveValue},
+    },
+    moved_shared::error::{Error, InvalidTransactionCause, UserError},
     serde::de::DeserializeOwned,
-    std::{any, fmt},
+    std::any,
 };
 
 pub fn get_field(x: &serde_json::Value, name: &str) -> serde_json::Value {
@@ -27,8 +38,90 @@ pub fn deserialize<T: DeserializeOwned>(x: &serde_json::Value) -> Result<T, Json
     })
 }
 
-pub fn transaction_error<E: fmt::Debug>(e: E) -> JsonRpcError {
-    JsonRpcError::without_data(3, format!("Execution reverted: {e:?}"))
+/// Maps a failed execution to the error geth returns for it, so that clients can handle it.
+///
+/// EVM reverts use the `revert_code` of the method and carry the revert data, from which clients
+/// decode custom errors. Move aborts use it too and carry where the abort happened instead, along
+/// with the output of the EVM execution that failed before the abort if there is one.
+pub fn transaction_error(e: Error, revert_code: i64) -> JsonRpcError {
+    match e {
+        Error::User(UserError::EvmReverted(output)) => {
+            let message = match Revert::abi_decode(&output, true) {
+                Ok(revert) => format!("execution reverted: {}", revert.reason),
+                Err(_) => "execution reverted".into(),
+            };
+            JsonRpcError {
+                code: revert_code,
+                data: serde_json::to_value(Bytes::from(output))
+                    .expect("Must be able to JSON-serialize bytes"),
+                message,
+            }
+        }
+        Error::User(UserError::MoveAbort(abort)) => {
+            let mut data = serde_json::json!({
+                "module": abort.module.short_str_lossless(),
+                "abortCode": abort.code,
+                "reasonName": abort.reason_name,
+                "description": abort.description,
+            });
+            let mut message = format!("execution reverted: {abort}");
+            if let Some(output) = abort.evm_output {
+                if let Ok(revert) = Revert::abi_decode(&output, true) {
+                    message = format!("{message} after EVM revert: {}", revert.reason);
+                }
+                data["evmOutput"] = serde_json::to_value(Bytes::from(output))
+                    .expect("Must be able to JSON-serialize bytes");
+            }
+            JsonRpcError {
+                code: revert_code,
+                data,
+                message,
+            }
+        }
+        Error::User(UserError::BlockNotFound(block_number)) => {
+            JsonRpcError::block_not_found(block_number)
+        }
+        Error::User(e) => JsonRpcError::without_data(-32000, format!("execution failed: {e}")),
+        Error::InvalidTransaction(cause) => {
+            JsonRpcError::without_data(-32000, invalid_transaction_message(cause))
+        }
+        Error::InvariantViolation(e) => {
+            JsonRpcError::without_data(-32603, format!("Internal error: {e}"))
+        }
+    }
+}
+
+/// Uses the messages of geth for the causes that clients recognize.
+fn invalid_transaction_message(cause: InvalidTransactionCause) -> String {
+    match cause {
+        InvalidTransactionCause::IncorrectNonce { expected, given } if given < expected => {
+            format!("nonce too low: next nonce {expected}, tx nonce {given}")
+        }
+        InvalidTransactionCause::IncorrectNonce { expected, given } => {
+            format!("nonce too high: next nonce {expected}, tx nonce {given}")
+        }
+        InvalidTransactionCause::FailedToPayL1Fee | InvalidTransactionCause::FailedToPayL2Fee => {
+            "insufficient funds for gas * price + value".into()
+        }
+        InvalidTransactionCause::InsufficientIntrinsicGas => "intrinsic gas too low".into(),
+        InvalidTransactionCause::InsufficientFunds { balance, cost } => {
+            format!("insufficient funds for gas * price + value: have {balance} want {cost}")
+        }
+        InvalidTransactionCause::FeeBelowBaseFee { base_fee, given } => {
+            format!(
+                "max fee per gas less than block base fee: maxFeePerGas: {given}, baseFee: {base_fee}"
+            )
+        }
+        InvalidTransactionCause::FeeBelowMinimum { min, given } => {
+            format!("transaction underpriced: fee per gas {given} below minimum {min}")
+        }
+        InvalidTransactionCause::PriorityFeeBelowMinimum { min, given } => {
+            format!("transaction underpriced: priority fee per gas {given} below minimum {min}")
+        }
+        InvalidTransactionCause::UnsupportedType => "transaction type not supported".into(),
+        InvalidTransactionCause::InvalidSigner => "invalid sender".into(),
+        cause => cause.to_string(),
+    }
 }
 
 pub fn parse_params_0(request: serde_json::Value) -> Result<(), JsonRpcError> {
@@ -74,3 +167,254 @@ where
         _ => Err(JsonRpcError::parse_error(request, "Too many params")),
     }
 }
+
+pub fn parse_params_4<T1, T2, T3, T4>(
+    request: serde_json::Value,
+) -> Result<(T1, T2, T3, T4), JsonRpcError>
+where
+    T1: DeserializeOwned,
+    T2: DeserializeOwned,
+    T3: DeserializeOwned,
+    T4: DeserializeOwned,
+{
+    let params = get_params_list(&request);
+    match params {
+        [] | [_] | [_, _] | [_, _, _] => {
+            Err(JsonRpcError::parse_error(request, "Not enough params"))
+        }
+        [a, b, c, d] => Ok((
+            deserialize(a)?,
+            deserialize(b)?,
+            deserialize(c)?,
+            deserialize(d)?,
+        )),
+        _ => Err(JsonRpcError::parse_error(request, "Too many params")),
+    }
+}
+
+/// Renders a Move `value` as JSON the way the Aptos API does: integers wider than 32 bits and
+/// byte vectors are strings, and structs are objects of their fields.
+///
+/// Without the annotated `layout` of the value, structs are arrays of their fields instead.
+pub fn move_value_to_json(value: MoveValue, layout: Option<&MoveTypeLayout>) -> serde_json::Value {
+    match value {
+        MoveValue::Bool(b) => b.into(),
+        MoveValue::U8(x) => x.into(),
+        MoveValue::U16(x) => x.into(),
+        MoveValue::U32(x) => x.into(),
+        MoveValue::U64(x) => x.to_string().into(),
+        MoveValue::U128(x) => x.to_string().into(),
+        MoveValue::U256(x) => x.to_string().into(),
+        MoveValue::Signer(address) | MoveValue::Address(address) => address.to_hex_literal().into(),
+        MoveValue::Vector(xs) => {
+            let inner_layout = match layout {
+                Some(MoveTypeLayout::Vector(inner_layout)) => Some(inner_layout.as_ref()),
+                _ => None,
+            };
+            let is_bytes = match inner_layout {
+                Some(inner_layout) => inner_layout == &MoveTypeLayout::U8,
+                None => matches!(xs.first(), Some(MoveValue::U8(_))),
+            };
+            if is_bytes {
+                let bytes: Vec<u8> = xs
+                    .into_iter()
+                    .filter_map(|x| match x {
+                        MoveValue::U8(x) => Some(x),
+                        _ => None,
+                    })
+                    .collect();
+                return hex::encode_prefixed(bytes).into();
+            }
+
+            xs.into_iter()
+                .map(|x| move_value_to_json(x, inner_layout))
+                .collect::<Vec<_>>()
+                .into()
+        }
+        MoveValue::Struct(inner) => {
+            let (variant, fields) = inner.into_optional_variant_and_fields();
+            let Some(MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+                type_,
+                fields: field_layouts,
+            })) = layout
+            else {
+                let fields: Vec<_> = fields
+                    .into_iter()
+                    .map(|x| move_value_to_json(x, None))
+                    .collect();
+                return match variant {
+                    Some(variant) => serde_json::json!({ "variant": variant, "fields": fields }),
+                    None => fields.into(),
+                };
+            };
+
+            if is_string(type_) {
+                if let Some(MoveValue::Vector(bytes)) = fields.into_iter().next() {
+                    let bytes: Vec<u8> = bytes
+                        .into_iter()
+                        .filter_map(|x| match x {
+                            MoveValue::U8(x) => Some(x),
+                            _ => None,
+                        })
+                        .collect();
+                    return String::from_utf8_lossy(&bytes).into_owned().into();
+                }
+                return serde_json::Value::Null;
+            }
+
+            fields
+                .into_iter()
+                .zip(field_layouts)
+                .map(|(x, field)| {
+                    (
+                        field.name.to_string(),
+                        move_value_to_json(x, Some(&field.layout)),
+                    )
+                })
+                .collect::<serde_json::Map<_, _>>()
+                .into()
+        }
+    }
+}
+
+fn is_string(tag: &StructTag) -> bool {
+    tag.address == AccountAddress::ONE
+        && tag.module.as_str() == "string"
+        && tag.name.as_str() == "String"
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        move_core_types::{
+            ident_str,
+            language_storage::ModuleId,
+            value::{MoveFieldLayout, MoveStruct},
+        },
+        moved_shared::error::MoveAbort,
+        std::str::FromStr,
+        test_case::test_case,
+    };
+
+    #[test]
+    fn test_revert_carries_its_data_and_reason() {
+        let output = Revert {
+            reason: "Not enough tokens".into(),
+        }
+        .abi_encode();
+
+        let err = transaction_error(UserError::EvmReverted(output.clone()).into(), 3);
+
+        assert_eq!(err.code, 3);
+        assert_eq!(err.message, "execution reverted: Not enough tokens");
+        assert_eq!(err.data, serde_json::to_value(Bytes::from(output)).unwrap());
+    }
+
+    #[test]
+    fn test_revert_with_custom_error_carries_its_data() {
+        let output = vec![0xde, 0xad, 0xbe, 0xef];
+
+        let err = transaction_error(UserError::EvmReverted(output).into(), -32000);
+
+        assert_eq!(err.code, -32000);
+        assert_eq!(err.message, "execution reverted");
+        assert_eq!(err.data, serde_json::json!("0xdeadbeef"));
+    }
+
+    #[test]
+    fn test_move_abort_carries_its_location() {
+        let abort = MoveAbort {
+            module: ModuleId::new(AccountAddress::ONE, ident_str!("coin").into()),
+            code: 0x1_0006,
+            reason_name: Some("EINSUFFICIENT_BALANCE".into()),
+            description: Some("Not enough coins to complete transaction".into()),
+            evm_output: None,
+        };
+
+        let err = transaction_error(UserError::MoveAbort(abort).into(), 3);
+
+        assert_eq!(err.code, 3);
+        assert_eq!(
+            err.message,
+            "execution reverted: EINSUFFICIENT_BALANCE in 0x1::coin"
+        );
+        assert_eq!(
+            err.data,
+            serde_json::json!({
+                "module": "0x1::coin",
+                "abortCode": 0x1_0006,
+                "reasonName": "EINSUFFICIENT_BALANCE",
+                "description": "Not enough coins to complete transaction",
+            })
+        );
+    }
+
+    #[test]
+    fn test_move_abort_after_evm_revert_carries_revert_reason() {
+        let output = Revert {
+            reason: "Not enough tokens".into(),
+        }
+        .abi_encode();
+        let abort = MoveAbort {
+            module: ModuleId::new(AccountAddress::ONE, ident_str!("swap").into()),
+            code: 1,
+            reason_name: None,
+            description: None,
+            evm_output: Some(output.clone()),
+        };
+
+        let err = transaction_error(UserError::MoveAbort(abort).into(), 3);
+
+        assert_eq!(
+            err.message,
+            "execution reverted: Abort code 1 in 0x1::swap after EVM revert: Not enough tokens"
+        );
+        assert_eq!(
+            err.data["evmOutput"],
+            serde_json::to_value(Bytes::from(output)).unwrap()
+        );
+    }
+
+    #[test_case(
+        InvalidTransactionCause::IncorrectNonce { expected: 2, given: 1 },
+        "nonce too low: next nonce 2, tx nonce 1"
+    )]
+    #[test_case(
+        InvalidTransactionCause::IncorrectNonce { expected: 2, given: 3 },
+        "nonce too high: next nonce 2, tx nonce 3"
+    )]
+    #[test_case(
+        InvalidTransactionCause::FailedToPayL2Fee,
+        "insufficient funds for gas * price + value"
+    )]
+    fn test_invalid_transaction_uses_geth_message(cause: InvalidTransactionCause, expected: &str) {
+        let err = transaction_error(cause.into(), 3);
+
+        assert_eq!(err.code, -32000);
+        assert_eq!(err.message, expected);
+    }
+
+    #[test]
+    fn test_struct_renders_as_object_of_fields() {
+        let layout = MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: StructTag::from_str("0x1::coin::Deposit").unwrap(),
+            fields: vec![
+                MoveFieldLayout::new(ident_str!("amount").into(), MoveTypeLayout::U64),
+                MoveFieldLayout::new(
+                    ident_str!("memo").into(),
+                    MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+                ),
+            ],
+        });
+        let value = MoveValue::Struct(MoveStruct::Runtime(vec![
+            MoveValue::U64(5),
+            MoveValue::Vector(vec![MoveValue::U8(0xab)]),
+        ]));
+
+        assert_eq!(
+            move_value_to_json(value, Some(&layout)),
+            serde_json::json!({ "amount": "5", "memo": "0xab" })
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use std::fmt;
+use {
+    crate::schema::GetBlockResponse, alloy::rpc::types::Log, moved_app::StateUnavailable, std::fmt,
+};
 
 #[derive(Debug, serde::Serialize)]
 pub struct JsonRpcError {
@@ -27,14 +29,48 @@ impl JsonRpcError {
     pub fn block_not_found<T: fmt::Display>(block_number: T) -> Self {
         Self::without_data(-32001, format!("Block not found: {block_number}"))
     }
+
+    pub fn unsupported_fork(message: impl Into<String>) -> Self {
+        Self::without_data(-38005, message)
+    }
+
+    pub fn state_unavailable(reason: StateUnavailable) -> Self {
+        let message = match reason {
+            StateUnavailable::Pruned { height, horizon } => format!(
+                "State of block {height} is pruned, the earliest block with state is {horizon}"
+            ),
+            StateUnavailable::NotCanonical {
+                hash,
+                require_canonical: true,
+            } => format!("Block {hash} is not currently canonical"),
+            StateUnavailable::NotCanonical {
+                hash,
+                require_canonical: false,
+            } => format!("State of block {hash} is not kept, as the block is not canonical"),
+        };
+
+        Self::without_data(-32000, message)
+    }
 }
 
 #[derive(Debug, serde::Serialize)]
-pub struct JsonRpcResponse {
+pub struct JsonRpcResponse<T = serde_json::Value> {
     pub id: serde_json::Value,
     pub jsonrpc: serde_json::Value,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub result: Option<serde_json::Value>,
+    pub result: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<JsonRpcError>,
 }
+
+/// The result of a method.
+///
+/// Methods whose results can be megabytes of JSON keep them typed, so that they are serialized
+/// straight into the response body instead of being built as a JSON value first.
+#[derive(Debug, serde::Serialize)]
+#[serde(untagged)]
+pub enum JsonRpcResult {
+    Value(serde_json::Value),
+    Block(Option<GetBlockResponse>),
+    Logs(Vec<Log>),
+}
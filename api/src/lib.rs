@@ -4,3 +4,4 @@ pub mod method_name;
 pub mod methods;
 pub mod request;
 pub mod schema;
+pub mod simulate;
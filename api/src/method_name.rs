@@ -2,16 +2,26 @@ use {crate::jsonrpc::JsonRpcError, std::str::FromStr};
 
 #[derive(Debug)]
 pub enum MethodName {
+    ForkChoiceUpdatedV2,
     ForkChoiceUpdatedV3,
+    GetPayloadV2,
     GetPayloadV3,
+    GetPayloadV4,
+    NewPayloadV2,
     NewPayloadV3,
+    NewPayloadV4,
     SendRawTransaction,
     ChainId,
     GetBalance,
     GetBlockByHash,
     GetBlockByNumber,
+    GetBlockTransactionCountByHash,
+    GetBlockTransactionCountByNumber,
     GetTransactionByHash,
     GetNonce,
+    GetCode,
+    GetStorageAt,
+    GetLogs,
     BlockNumber,
     FeeHistory,
     EstimateGas,
@@ -19,6 +29,30 @@ pub enum MethodName {
     TransactionReceipt,
     GetProof,
     GasPrice,
+    MaxPriorityFeePerGas,
+    GetRejectedTransactions,
+    GetL1Origin,
+    GetBridgedTokens,
+    GetTokenMapping,
+    GetTrieNodes,
+    GetStorageAccounts,
+    EstimateL1Fee,
+    UmiTransactionReceipt,
+    ResolveAddress,
+    GetTransactionProof,
+    GetReceiptProof,
+    GetPayloadExpiry,
+    SimulateTransaction,
+    Syncing,
+    NetVersion,
+    NetListening,
+    NetPeerCount,
+    ClientVersion,
+    TraceTransaction,
+    GetModule,
+    View,
+    ListResources,
+    GetTableItem,
 }
 
 impl MethodName {
@@ -26,10 +60,23 @@ impl MethodName {
         !self.is_engine_api()
     }
 
+    /// Whether the method can be served without the block building process, only reading the
+    /// blockchain state.
+    pub fn is_query_api(&self) -> bool {
+        !self.is_engine_api() && !matches!(self, Self::SendRawTransaction)
+    }
+
     pub fn is_engine_api(&self) -> bool {
         matches!(
             self,
-            Self::ForkChoiceUpdatedV3 | Self::GetPayloadV3 | Self::NewPayloadV3
+            Self::ForkChoiceUpdatedV2
+                | Self::ForkChoiceUpdatedV3
+                | Self::GetPayloadV2
+                | Self::GetPayloadV3
+                | Self::GetPayloadV4
+                | Self::NewPayloadV2
+                | Self::NewPayloadV3
+                | Self::NewPayloadV4
         )
     }
 }
@@ -39,15 +86,25 @@ impl FromStr for MethodName {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
+            "engine_forkchoiceUpdatedV2" => Self::ForkChoiceUpdatedV2,
             "engine_forkchoiceUpdatedV3" => Self::ForkChoiceUpdatedV3,
+            "engine_getPayloadV2" => Self::GetPayloadV2,
             "engine_getPayloadV3" => Self::GetPayloadV3,
+            "engine_getPayloadV4" => Self::GetPayloadV4,
+            "engine_newPayloadV2" => Self::NewPayloadV2,
             "engine_newPayloadV3" => Self::NewPayloadV3,
+            "engine_newPayloadV4" => Self::NewPayloadV4,
             "eth_chainId" => Self::ChainId,
             "eth_getBalance" => Self::GetBalance,
             "eth_getTransactionCount" => Self::GetNonce,
+            "eth_getCode" => Self::GetCode,
+            "eth_getStorageAt" => Self::GetStorageAt,
+            "eth_getLogs" => Self::GetLogs,
             "eth_getTransactionByHash" => Self::GetTransactionByHash,
             "eth_getBlockByHash" => Self::GetBlockByHash,
             "eth_getBlockByNumber" => Self::GetBlockByNumber,
+            "eth_getBlockTransactionCountByHash" => Self::GetBlockTransactionCountByHash,
+            "eth_getBlockTransactionCountByNumber" => Self::GetBlockTransactionCountByNumber,
             "eth_feeHistory" => Self::FeeHistory,
             "eth_blockNumber" => Self::BlockNumber,
             "eth_sendRawTransaction" => Self::SendRawTransaction,
@@ -56,6 +113,30 @@ impl FromStr for MethodName {
             "eth_getTransactionReceipt" => Self::TransactionReceipt,
             "eth_getProof" => Self::GetProof,
             "eth_gasPrice" => Self::GasPrice,
+            "eth_maxPriorityFeePerGas" => Self::MaxPriorityFeePerGas,
+            "umi_getRejectedTransactions" => Self::GetRejectedTransactions,
+            "umi_getL1Origin" => Self::GetL1Origin,
+            "umi_getBridgedTokens" => Self::GetBridgedTokens,
+            "umi_getTokenMapping" => Self::GetTokenMapping,
+            "umi_getTrieNodes" => Self::GetTrieNodes,
+            "umi_getStorageAccounts" => Self::GetStorageAccounts,
+            "umi_estimateL1Fee" => Self::EstimateL1Fee,
+            "umi_getTransactionReceipt" => Self::UmiTransactionReceipt,
+            "umi_resolveAddress" => Self::ResolveAddress,
+            "umi_getTransactionProof" => Self::GetTransactionProof,
+            "umi_getReceiptProof" => Self::GetReceiptProof,
+            "umi_getPayloadExpiry" => Self::GetPayloadExpiry,
+            "move_simulateTransaction" | "umi_simulateTransaction" => Self::SimulateTransaction,
+            "eth_syncing" => Self::Syncing,
+            "net_version" => Self::NetVersion,
+            "net_listening" => Self::NetListening,
+            "net_peerCount" => Self::NetPeerCount,
+            "web3_clientVersion" => Self::ClientVersion,
+            "debug_traceTransaction" => Self::TraceTransaction,
+            "move_getModule" => Self::GetModule,
+            "move_view" => Self::View,
+            "move_listResources" => Self::ListResources,
+            "move_getTableItem" => Self::GetTableItem,
             other => {
                 return Err(JsonRpcError::without_data(
                     -32601,
@@ -2,19 +2,25 @@ use {
     crate::{
         json_utils::{parse_params_2, transaction_error},
         jsonrpc::JsonRpcError,
+        simulate,
     },
-    moved_app::{ApplicationReader, Dependencies},
+    alloy::{eips::BlockId, rpc::types::TransactionRequest},
+    moved_app::{ApplicationReader, DependenciesThreadSafe},
 };
 
 pub async fn execute(
     request: serde_json::Value,
-    app: &ApplicationReader<impl Dependencies>,
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
 ) -> Result<serde_json::Value, JsonRpcError> {
-    let (transaction, block_number) = parse_params_2(request)?;
+    let (transaction, block_id): (TransactionRequest, BlockId) = parse_params_2(request)?;
+    let block_number = simulate::block_number(app, block_id)?;
 
-    let response = app
-        .call(transaction, block_number)
-        .map_err(transaction_error)?;
+    app.ensure_state_retained(block_number)
+        .map_err(JsonRpcError::state_unavailable)?;
+
+    let response = simulate::with_timeout(app, move |app| app.call(transaction, block_number))
+        .await?
+        .map_err(|e| transaction_error(e, 3))?;
 
     Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
 }
@@ -28,7 +34,6 @@ mod tests {
             eips::BlockNumberOrTag,
             hex::FromHex,
             primitives::{Address, Bytes},
-            rpc::types::TransactionRequest,
         },
         moved_app::CommandActor,
         moved_shared::primitives::U64,
@@ -142,4 +147,26 @@ mod tests {
             execute(request, &reader).await.unwrap();
         }).await;
     }
+
+    #[tokio::test]
+    async fn test_execute_rejects_finalized_block_before_it_is_marked() {
+        let (reader, _app) = create_app();
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_call",
+            "params": [
+                {
+                    "from": "0x8fd379246834eac74b8419ffda202cf8051f7a03",
+                    "to": "0x8fd379246834eac74b8419ffda202cf8051f7a03",
+                    "input": "0x"
+                },
+                "finalized",
+            ],
+            "id": 1
+        });
+
+        let error = execute(request, &reader).await.unwrap_err();
+
+        assert_eq!(error.code, -32001);
+    }
 }
@@ -0,0 +1,8 @@
+use crate::jsonrpc::JsonRpcError;
+
+/// Name and version of the node software, in the `name/version` format of other clients.
+const CLIENT_VERSION: &str = concat!("op-move/v", env!("CARGO_PKG_VERSION"));
+
+pub async fn execute() -> Result<serde_json::Value, JsonRpcError> {
+    Ok(CLIENT_VERSION.into())
+}
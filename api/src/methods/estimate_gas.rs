@@ -1,19 +1,27 @@
 use {
-    crate::{json_utils, json_utils::transaction_error, jsonrpc::JsonRpcError},
-    alloy::{eips::BlockNumberOrTag, rpc::types::TransactionRequest},
-    moved_app::{ApplicationReader, Dependencies},
+    crate::{json_utils, json_utils::transaction_error, jsonrpc::JsonRpcError, simulate},
+    alloy::{
+        eips::{BlockId, BlockNumberOrTag},
+        rpc::types::TransactionRequest,
+    },
+    moved_app::{ApplicationReader, DependenciesThreadSafe},
 };
 
 const BASE_FEE: u64 = 21_000;
 
 pub async fn execute(
     request: serde_json::Value,
-    app: &ApplicationReader<impl Dependencies>,
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
 ) -> Result<serde_json::Value, JsonRpcError> {
-    let (transaction, block_number) = parse_params(request)?;
+    let (transaction, block_id) = parse_params(request)?;
+    let block_number = simulate::block_number(app, block_id)?;
+    app.ensure_state_retained(block_number)
+        .map_err(JsonRpcError::state_unavailable)?;
+
     let response = std::cmp::max(
-        app.estimate_gas(transaction, block_number)
-            .map_err(transaction_error)?,
+        simulate::with_timeout(app, move |app| app.estimate_gas(transaction, block_number))
+            .await?
+            .map_err(|e| transaction_error(e, -32000))?,
         BASE_FEE,
     );
 
@@ -22,9 +30,7 @@ pub async fn execute(
         .expect("Must be able to JSON-serialize response"))
 }
 
-fn parse_params(
-    request: serde_json::Value,
-) -> Result<(TransactionRequest, BlockNumberOrTag), JsonRpcError> {
+fn parse_params(request: serde_json::Value) -> Result<(TransactionRequest, BlockId), JsonRpcError> {
     let params = json_utils::get_params_list(&request);
     match params {
         [] => Err(JsonRpcError {
@@ -34,11 +40,11 @@ fn parse_params(
         }),
         [a] => {
             let transaction: TransactionRequest = json_utils::deserialize(a)?;
-            Ok((transaction, BlockNumberOrTag::Latest))
+            Ok((transaction, BlockNumberOrTag::Latest.into()))
         }
         [a, b] => {
             let transaction: TransactionRequest = json_utils::deserialize(a)?;
-            let block_number: BlockNumberOrTag = json_utils::deserialize(b)?;
+            let block_number: BlockId = json_utils::deserialize(b)?;
             Ok((transaction, block_number))
         }
         _ => Err(JsonRpcError {
@@ -72,7 +78,7 @@ mod tests {
         });
 
         let (_, block_number) = parse_params(request.clone()).unwrap();
-        assert_eq!(block_number, BlockNumberOrTag::Latest);
+        assert_eq!(block_number, BlockNumberOrTag::Latest.into());
     }
 
     #[test_case("0x1")]
@@ -99,11 +105,11 @@ mod tests {
             Address::from_str("0x0000000000000000000000000000000000000001").unwrap()
         );
         match block {
-            "latest" => assert_eq!(block_number, BlockNumberOrTag::Latest),
-            "pending" => assert_eq!(block_number, BlockNumberOrTag::Pending),
+            "latest" => assert_eq!(block_number, BlockNumberOrTag::Latest.into()),
+            "pending" => assert_eq!(block_number, BlockNumberOrTag::Pending.into()),
             _ => assert_eq!(
                 block_number,
-                BlockNumberOrTag::Number(U64::from_str(block).unwrap().into_limbs()[0])
+                BlockNumberOrTag::Number(U64::from_str(block).unwrap().into_limbs()[0]).into()
             ),
         }
     }
@@ -141,4 +147,41 @@ mod tests {
             assert_eq!(actual_response, expected_response);
         }).await;
     }
+
+    #[tokio::test]
+    async fn test_execute_is_limited_by_gas_cap() {
+        let (state_channel, rx) = mpsc::channel(10);
+        let (mut reader, mut app) = create_app();
+        // Covers the intrinsic gas of the transaction, but not the 19_185 its execution uses
+        reader.genesis_config.simulation.gas_cap = 10_000;
+        let state_actor = CommandActor::new(rx, &mut app);
+
+        moved_app::run(state_actor, async move {
+            deposit_eth("0x8fd379246834eac74b8419ffda202cf8051f7a03", &state_channel).await;
+            state_channel.reserve_many(10).await.unwrap();
+
+            let request: serde_json::Value = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_estimateGas",
+                "params": [
+                    {
+                        "from": "0x8fd379246834eac74b8419ffda202cf8051f7a03",
+                        "input": "0x01fd01a11ceb0b0600000009010002020204030614051a0e07283d0865200a8501050c8a01490dd3010200000001080000020001000003000200000400030000050403000105010101030002060c0301070307636f756e74657207436f756e7465720e636f756e7465725f657869737473096765745f636f756e7409696e6372656d656e74077075626c69736801690000000000000000000000008fd379246834eac74b8419ffda202cf8051f7a0300020106030001000003030b00290002010100010003050b002b00100014020201040100050b0b002a000f000c010a0114060100000000000000160b0115020301040003050b000b0112002d0002000000",
+                        "gas": "0xffffff"
+                    },
+                ],
+                "id": 1
+            });
+
+            let error = execute(request, &reader).await.unwrap_err();
+
+            assert_eq!(error.code, -32000);
+            assert!(
+                error.message.starts_with("execution failed"),
+                "Should run out of gas during execution, got: {}",
+                error.message
+            );
+        })
+        .await;
+    }
 }
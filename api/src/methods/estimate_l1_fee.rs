@@ -0,0 +1,98 @@
+use {
+    crate::{json_utils, jsonrpc::JsonRpcError},
+    alloy::{consensus::transaction::TxEnvelope, rlp::Decodable},
+    moved_app::{ApplicationReader, Dependencies},
+    moved_shared::primitives::Bytes,
+};
+
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let tx_data = parse_params(request)?;
+
+    let response = app.l1_fee(&tx_data);
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+/// Parses the raw transaction, as it would be passed to `eth_sendRawTransaction`.
+fn parse_params(request: serde_json::Value) -> Result<Bytes, JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    match params {
+        [] => Err(JsonRpcError::parse_error(request, "Not enough params")),
+        [x] => {
+            let bytes: Bytes = json_utils::deserialize(x)?;
+            TxEnvelope::decode(&mut bytes.as_ref()).map_err(|e| {
+                JsonRpcError::without_data(-32602, format!("RLP decode failed: {e:?}"))
+            })?;
+            Ok(bytes)
+        }
+        _ => Err(JsonRpcError::parse_error(request, "Too many params")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::methods::{
+            send_raw_transaction::tests::example_request as example_send_request,
+            tests::{create_app, deposit_eth},
+        },
+        moved_app::CommandActor,
+        moved_shared::primitives::U256,
+        tokio::sync::mpsc,
+    };
+
+    fn example_request() -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "umi_estimateL1Fee",
+            "params": example_send_request()["params"]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_before_l1_attributes_is_free() {
+        let (reader, _app) = create_app();
+
+        let response = execute(example_request(), &reader).await.unwrap();
+
+        assert_eq!(response, serde_json::to_value(U256::ZERO).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_execute_prices_with_latest_l1_attributes() {
+        let (state_channel, rx) = mpsc::channel(10);
+        let (mut reader, mut app) = create_app();
+        let l1_fee = U256::from(1_000);
+        reader.l1_fee = l1_fee;
+        let state_actor = CommandActor::new(rx, &mut app);
+
+        moved_app::run(state_actor, async move {
+            deposit_eth("0x8fd379246834eac74b8419ffda202cf8051f7a03", &state_channel).await;
+            state_channel.reserve_many(10).await.unwrap();
+
+            let response = execute(example_request(), &reader).await.unwrap();
+
+            assert_eq!(response, serde_json::to_value(l1_fee).unwrap());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_invalid_transaction() {
+        let (reader, _app) = create_app();
+
+        let request = serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "umi_estimateL1Fee",
+            "params": ["0x01"]
+        });
+
+        assert!(execute(request, &reader).await.is_err());
+    }
+}
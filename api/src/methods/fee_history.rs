@@ -10,7 +10,9 @@ pub async fn execute(
 ) -> Result<serde_json::Value, JsonRpcError> {
     let (block_count, block_number, reward_percentiles) = parse_params(request)?;
 
-    let response = app.fee_history(block_count, block_number, reward_percentiles);
+    let response = app
+        .fee_history(block_count, block_number, reward_percentiles)
+        .ok_or(JsonRpcError::block_not_found(block_number))?;
 
     Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
 }
@@ -44,6 +46,13 @@ fn parse_params(
                     message: "Incorrect reward percentile".into(),
                 });
             }
+            if reward_percentiles.windows(2).any(|pair| pair[0] > pair[1]) {
+                return Err(JsonRpcError {
+                    code: -32602,
+                    data: 0.into(),
+                    message: "Reward percentiles must be in ascending order".into(),
+                });
+            }
             Ok((block_count, block_number, Some(reward_percentiles)))
         }
         _ => Err(JsonRpcError {
@@ -67,8 +76,8 @@ fn parse_block_count(value: &serde_json::Value) -> Result<u64, JsonRpcError> {
 #[cfg(test)]
 mod tests {
     use {
-        super::*, crate::methods::tests::create_app, moved_shared::primitives::U64,
-        std::str::FromStr, test_case::test_case,
+        super::*, crate::methods::tests::create_app, alloy::rpc::types::FeeHistory,
+        moved_shared::primitives::U64, std::str::FromStr, test_case::test_case,
     };
 
     #[test_case("0x1")]
@@ -145,9 +154,18 @@ mod tests {
         });
         let err = parse_params(request).unwrap_err();
         assert_eq!(err.message, "Incorrect reward percentile");
+
+        // Unordered reward percentiles
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_feeHistory",
+            "params": ["0x1", "latest", [50, 10]],
+            "id": 1
+        });
+        let err = parse_params(request).unwrap_err();
+        assert_eq!(err.message, "Reward percentiles must be in ascending order");
     }
 
-    #[test_case("0x1")]
     #[test_case("latest")]
     #[test_case("pending")]
     #[tokio::test]
@@ -161,16 +179,36 @@ mod tests {
                 "0x2",
                 block,
                 [
-                    20.0
+                    20.0,
+                    80.0
                 ],
             ],
             "id": 1
         });
 
-        let expected_response: serde_json::Value =
-            serde_json::json!({"gasUsedRatio": [], "oldestBlock": "0x0"});
         let response = execute(request, &reader).await.unwrap();
+        let history: FeeHistory = serde_json::from_value(response).unwrap();
+
+        // Only the genesis block exists, which has no transactions to pay tips
+        assert_eq!(history.oldest_block, 0);
+        assert_eq!(history.gas_used_ratio.len(), 1);
+        assert_eq!(history.base_fee_per_gas.len(), 2);
+        assert_eq!(history.reward, Some(vec![vec![0, 0]]));
+    }
+
+    #[tokio::test]
+    async fn test_execute_unknown_block() {
+        let (reader, _app) = create_app();
+
+        let request: serde_json::Value = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_feeHistory",
+            "params": ["0x2", "0x1", [20.0]],
+            "id": 1
+        });
+
+        let err = execute(request, &reader).await.unwrap_err();
 
-        assert_eq!(response, expected_response);
+        assert_eq!(err.message, "Block not found: 0x1");
     }
 }
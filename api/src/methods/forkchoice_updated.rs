@@ -3,28 +3,78 @@ use {
         json_utils,
         jsonrpc::JsonRpcError,
         schema::{
-            ForkchoiceStateV1, ForkchoiceUpdatedResponseV1, PayloadAttributesV3, PayloadId,
-            PayloadStatusV1, Status,
+            ForkchoiceStateV1, ForkchoiceUpdatedResponseV1, PayloadAttributesV2,
+            PayloadAttributesV3, PayloadId, PayloadStatusV1, Status,
         },
     },
-    moved_app::{Command, CommandQueue, Payload, ToPayloadIdInput},
+    moved_app::{
+        ApplicationReader, Command, CommandQueue, Dependencies, Payload, ToPayloadIdInput,
+    },
     moved_blockchain::payload::NewPayloadId,
+    serde::de::DeserializeOwned,
+    std::time::Instant,
 };
 
+/// Same as [`execute_v3`], but for blocks from before the Ecotone upgrade, whose payload
+/// attributes have no parent beacon block root.
+pub async fn execute_v2(
+    request: serde_json::Value,
+    queue: CommandQueue,
+    payload_id: &impl NewPayloadId,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (forkchoice_state, payload_attributes): (_, Option<PayloadAttributesV2>) =
+        parse_params(request)?;
+    let hardforks = &app.genesis_config.hardforks;
+    if payload_attributes
+        .as_ref()
+        .is_some_and(|attributes| hardforks.is_ecotone_active(attributes.timestamp.to()))
+    {
+        return Err(JsonRpcError::unsupported_fork(
+            "Unsupported fork, the payload is due after Ecotone",
+        ));
+    }
+    let response = inner_execute(
+        forkchoice_state,
+        payload_attributes.map(Payload::from),
+        queue,
+        payload_id,
+    )
+    .await?;
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
 pub async fn execute_v3(
     request: serde_json::Value,
     queue: CommandQueue,
     payload_id: &impl NewPayloadId,
+    app: &ApplicationReader<impl Dependencies>,
 ) -> Result<serde_json::Value, JsonRpcError> {
-    let (forkchoice_state, payload_attributes) = parse_params_v3(request)?;
-    let response =
-        inner_execute_v3(forkchoice_state, payload_attributes, queue, payload_id).await?;
+    let (forkchoice_state, payload_attributes): (_, Option<PayloadAttributesV3>) =
+        parse_params(request)?;
+    // Spec: https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#specification-1
+    let hardforks = &app.genesis_config.hardforks;
+    if payload_attributes
+        .as_ref()
+        .is_some_and(|attributes| !hardforks.is_ecotone_active(attributes.timestamp.to()))
+    {
+        return Err(JsonRpcError::unsupported_fork(
+            "Unsupported fork, the payload is due before Ecotone",
+        ));
+    }
+    let response = inner_execute(
+        forkchoice_state,
+        payload_attributes.map(Payload::from),
+        queue,
+        payload_id,
+    )
+    .await?;
     Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
 }
 
-fn parse_params_v3(
+fn parse_params<A: DeserializeOwned>(
     request: serde_json::Value,
-) -> Result<(ForkchoiceStateV1, Option<PayloadAttributesV3>), JsonRpcError> {
+) -> Result<(ForkchoiceStateV1, Option<A>), JsonRpcError> {
     let params = json_utils::get_params_list(&request);
     match params {
         [] => Err(JsonRpcError {
@@ -38,7 +88,7 @@ fn parse_params_v3(
         }
         [x, y] => {
             let fc_state: ForkchoiceStateV1 = json_utils::deserialize(x)?;
-            let payload_attributes: Option<PayloadAttributesV3> = json_utils::deserialize(y)?;
+            let payload_attributes: Option<A> = json_utils::deserialize(y)?;
             Ok((fc_state, payload_attributes))
         }
         _ => Err(JsonRpcError {
@@ -49,9 +99,9 @@ fn parse_params_v3(
     }
 }
 
-async fn inner_execute_v3(
+async fn inner_execute(
     forkchoice_state: ForkchoiceStateV1,
-    payload_attributes: Option<PayloadAttributesV3>,
+    payload_attributes: Option<Payload>,
     queue: CommandQueue,
     payload_id_generator: &impl NewPayloadId,
 ) -> Result<ForkchoiceUpdatedResponseV1, JsonRpcError> {
@@ -65,15 +115,22 @@ async fn inner_execute_v3(
         validation_error: None,
     };
 
+    queue
+        .send(Command::ForkchoiceUpdate {
+            safe_block_hash: forkchoice_state.safe_block_hash,
+            finalized_block_hash: forkchoice_state.finalized_block_hash,
+        })
+        .await;
+
     // If `payload_attributes` are present then tell state to start producing a new block
-    let payload_id = if let Some(attrs) = payload_attributes {
-        let payload_attributes = Payload::from(attrs);
+    let payload_id = if let Some(payload_attributes) = payload_attributes {
         let payload_id = payload_id_generator.new_payload_id(
             payload_attributes.to_payload_id_input(&forkchoice_state.head_block_hash),
         );
         let msg = Command::StartBlockBuild {
             payload_attributes,
             payload_id,
+            requested_at: Instant::now(),
         };
         queue.send(msg).await;
         Some(PayloadId(payload_id))
@@ -91,7 +148,7 @@ async fn inner_execute_v3(
 pub(super) mod tests {
     use {
         super::*,
-        crate::methods::tests::create_app,
+        crate::methods::tests::{create_app, create_app_before_ecotone},
         alloy::primitives::hex,
         moved_shared::primitives::{Address, B256, Bytes, U64},
     };
@@ -153,7 +210,7 @@ pub(super) mod tests {
             }
         "#).unwrap();
 
-        let params = parse_params_v3(request).unwrap();
+        let params = parse_params::<PayloadAttributesV3>(request).unwrap();
 
         let expected_params = (
             ForkchoiceStateV1 {
@@ -183,6 +240,7 @@ pub(super) mod tests {
                     "7ef8f8a0de86bef815fc910df65a9459ccb2b9a35fa8596dfcfed1ff01bbf28891d86d5e94deaddeaddeaddeaddeaddeaddeaddeaddead00019442000000000000000000000000000000000000158080830f424080b8a4440a5e2000000558000c5fc50000000000000000000000006660735b00000000000001a9000000000000000000000000000000000000000000000000000000000000000700000000000000000000000000000000000000000000000000000000000000017ae3f74f0134521a7d62a387ac75a5153bcd1aab1c7e003e9b9e15a5d8846363000000000000000000000000e25583099ba105d9ec0a67f5ae86d90e50036425"
                 ))],
                 gas_limit: U64::from_be_slice(&hex!("01c9c380")),
+                eip1559_params: None,
             }),
         );
 
@@ -204,7 +262,7 @@ pub(super) mod tests {
             }
         "#).unwrap();
 
-        let params = parse_params_v3(request).unwrap();
+        let params = parse_params::<PayloadAttributesV3>(request).unwrap();
 
         let expected_params = (
             ForkchoiceStateV1 {
@@ -226,7 +284,7 @@ pub(super) mod tests {
 
     #[tokio::test]
     async fn test_execute_v3() {
-        let (_reader, mut app) = create_app();
+        let (reader, mut app) = create_app();
         let (queue, state) = moved_app::create(&mut app, 10);
 
         moved_app::run(state, async move {
@@ -243,11 +301,77 @@ pub(super) mod tests {
                 }
             "#).unwrap();
 
-            let response = execute_v3(request, queue, &0x03421ee50df45cacu64)
+            let response = execute_v3(request, queue, &0x03421ee50df45cacu64, &reader)
                 .await
                 .unwrap();
 
             assert_eq!(response, expected_response);
         }).await;
     }
+
+    #[tokio::test]
+    async fn test_execute_v2_without_parent_beacon_block_root() {
+        let (reader, mut app) = create_app_before_ecotone();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            let mut request = example_request();
+            request["method"] = "engine_forkchoiceUpdatedV2".into();
+            request["params"][1]
+                .as_object_mut()
+                .unwrap()
+                .remove("parentBeaconBlockRoot");
+
+            let response: ForkchoiceUpdatedResponseV1 = serde_json::from_value(
+                execute_v2(request, queue, &0x03421ee50df45cacu64, &reader)
+                    .await
+                    .unwrap(),
+            )
+            .unwrap();
+
+            assert_eq!(response.payload_status.status, Status::Valid);
+            assert_eq!(
+                response.payload_id,
+                Some(PayloadId::new(0x03421ee50df45cac))
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v2_rejects_payload_due_after_ecotone() {
+        let (reader, mut app) = create_app();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            let mut request = example_request();
+            request["method"] = "engine_forkchoiceUpdatedV2".into();
+            request["params"][1]
+                .as_object_mut()
+                .unwrap()
+                .remove("parentBeaconBlockRoot");
+
+            let error = execute_v2(request, queue, &0x03421ee50df45cacu64, &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(error.code, -38005);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v3_rejects_payload_due_before_ecotone() {
+        let (reader, mut app) = create_app_before_ecotone();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            let error = execute_v3(example_request(), queue, &0x03421ee50df45cacu64, &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(error.code, -38005);
+        })
+        .await;
+    }
 }
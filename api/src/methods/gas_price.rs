@@ -1,18 +1,30 @@
-use crate::jsonrpc::JsonRpcError;
+use {
+    crate::jsonrpc::JsonRpcError,
+    moved_app::{ApplicationReader, Dependencies},
+    moved_shared::primitives::U256,
+};
 
-pub async fn execute() -> Result<serde_json::Value, JsonRpcError> {
-    Ok(serde_json::to_value("0x3b9aca00").expect("Must be able to JSON-serialize response"))
+pub async fn execute(
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let response = U256::from(app.gas_price());
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {super::*, crate::methods::tests::create_app};
 
     #[tokio::test]
-    async fn test_execute() {
-        // TODO: Return the actual gas price, currently hardcoded to 1,000,000,000
-        let expected_response: serde_json::Value = serde_json::from_str(r#""0x3b9aca00""#).unwrap();
-        let response = execute().await.unwrap();
-        assert_eq!(response, expected_response);
+    async fn test_execute_without_tips_suggests_base_fee_with_minimum_tip() {
+        let (reader, _app) = create_app();
+        let min_priority_fee = reader.genesis_config.gas_price_oracle.min_priority_fee;
+
+        let response = execute(&reader).await.unwrap();
+        let gas_price: U256 = serde_json::from_value(response).unwrap();
+
+        // The genesis block has no base fee
+        assert_eq!(gas_price, U256::from(min_priority_fee));
     }
 }
@@ -1,5 +1,6 @@
 use {
     crate::{json_utils::parse_params_2, jsonrpc::JsonRpcError},
+    alloy::{eips::BlockId, primitives::Address},
     moved_app::{ApplicationReader, Dependencies},
 };
 
@@ -7,7 +8,10 @@ pub async fn execute(
     request: serde_json::Value,
     app: &ApplicationReader<impl Dependencies>,
 ) -> Result<serde_json::Value, JsonRpcError> {
-    let (address, block_number) = parse_params_2(request)?;
+    let (address, block_number): (Address, BlockId) = parse_params_2(request)?;
+
+    app.ensure_state_retained(block_number)
+        .map_err(JsonRpcError::state_unavailable)?;
 
     let response = app
         .balance_by_height(address, block_number)
@@ -21,10 +25,12 @@ pub async fn execute(
 mod tests {
     use {
         super::*,
-        crate::methods::tests::create_app_with_mock_state_queries,
+        crate::methods::tests::{create_app, create_app_with_mock_state_queries},
         alloy::{eips::BlockNumberOrTag, hex},
         move_core_types::account_address::AccountAddress,
-        moved_shared::primitives::{Address, U64},
+        moved_blockchain::block::{Block, BlockRepository, Header},
+        moved_shared::primitives::{Address, B256, U64, U256},
+        serde_json::json,
         std::str::FromStr,
         test_case::test_case,
     };
@@ -108,4 +114,70 @@ mod tests {
 
         assert_eq!(response, expected_response);
     }
+
+    #[tokio::test]
+    async fn test_execute_at_block_hash() {
+        let (reader, _app) = create_app();
+        let genesis_hash = reader
+            .block_by_height(BlockNumberOrTag::Earliest, false)
+            .unwrap()
+            .block
+            .header
+            .hash;
+        let request = |block: serde_json::Value| {
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getBalance",
+                "params": [
+                    "0x0000000000000000000000000000000000000001",
+                    block,
+                ],
+                "id": 1
+            })
+        };
+
+        let response = execute(request(json!({ "blockHash": genesis_hash })), &reader)
+            .await
+            .unwrap();
+        let expected_response = execute(request(json!("0x0")), &reader).await.unwrap();
+        assert_eq!(response, expected_response);
+
+        let unknown_hash = json!({ "blockHash": B256::repeat_byte(0xff) });
+        let error = execute(request(unknown_hash), &reader).await.unwrap_err();
+        assert_eq!(error.code, -32001);
+    }
+
+    #[tokio::test]
+    async fn test_execute_at_hash_of_non_canonical_block_fails() {
+        let (reader, mut app) = create_app();
+        let fork_hash = B256::repeat_byte(0xaa);
+        let fork_block = Block::new(Header::default(), Vec::new())
+            .with_hash(fork_hash)
+            .with_value(U256::ZERO);
+        app.block_repository
+            .add(&mut app.storage, fork_block)
+            .unwrap();
+        let request = |block: serde_json::Value| {
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getBalance",
+                "params": [
+                    "0x0000000000000000000000000000000000000001",
+                    block,
+                ],
+                "id": 1
+            })
+        };
+
+        let error = execute(request(json!({ "blockHash": fork_hash })), &reader)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, -32000);
+        assert!(error.message.contains("not kept"));
+
+        let block = json!({ "blockHash": fork_hash, "requireCanonical": true });
+        let error = execute(request(block), &reader).await.unwrap_err();
+        assert_eq!(error.code, -32000);
+        assert!(error.message.contains("not currently canonical"));
+    }
 }
@@ -6,14 +6,12 @@ use {
 pub async fn execute(
     request: serde_json::Value,
     app: &ApplicationReader<impl Dependencies>,
-) -> Result<serde_json::Value, JsonRpcError> {
+) -> Result<Option<GetBlockResponse>, JsonRpcError> {
     let (block_hash, include_transactions) = parse_params_2(request)?;
 
-    let response = app
+    Ok(app
         .block_by_hash(block_hash, include_transactions)
-        .map(GetBlockResponse::from);
-
-    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+        .map(GetBlockResponse::from))
 }
 
 #[cfg(test)]
@@ -61,12 +59,11 @@ mod tests {
             "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
             "nonce": "0x0000000000000000",
             "uncles": [],
-            "transactions": [],
-            "withdrawals": []
+            "transactions": []
         }"#).unwrap();
 
         let response = execute(request, &reader).await.unwrap();
 
-        assert_eq!(response, expected_response);
+        assert_eq!(serde_json::to_value(response).unwrap(), expected_response);
     }
 }
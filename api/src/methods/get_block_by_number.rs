@@ -6,14 +6,12 @@ use {
 pub async fn execute(
     request: serde_json::Value,
     app: &ApplicationReader<impl Dependencies>,
-) -> Result<serde_json::Value, JsonRpcError> {
+) -> Result<Option<GetBlockResponse>, JsonRpcError> {
     let (number, include_transactions) = parse_params_2(request)?;
 
-    let response = app
+    Ok(app
         .block_by_height(number, include_transactions)
-        .map(GetBlockResponse::from);
-
-    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+        .map(GetBlockResponse::from))
 }
 
 #[cfg(test)]
@@ -24,6 +22,7 @@ mod tests {
         alloy::eips::BlockNumberOrTag::{self, *},
         moved_app::{Command, CommandActor, TestDependencies},
         moved_shared::primitives::U64,
+        std::time::Instant,
         test_case::test_case,
         tokio::sync::mpsc,
     };
@@ -37,8 +36,9 @@ mod tests {
         })
     }
 
-    pub fn get_block_number_from_response(response: serde_json::Value) -> String {
-        response
+    pub fn get_block_number_from_response(response: Option<GetBlockResponse>) -> String {
+        serde_json::to_value(response)
+            .unwrap()
             .as_object()
             .unwrap()
             .get("number") // Block number
@@ -72,13 +72,12 @@ mod tests {
             "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
             "nonce": "0x0000000000000000",
             "uncles": [],
-            "transactions": [],
-            "withdrawals": []
+            "transactions": []
         }"#).unwrap();
 
         let response = execute(request, &reader).await.unwrap();
 
-        assert_eq!(response, expected_response);
+        assert_eq!(serde_json::to_value(response).unwrap(), expected_response);
     }
 
     #[tokio::test]
@@ -96,6 +95,7 @@ mod tests {
             let msg = Command::StartBlockBuild {
                 payload_attributes: Default::default(),
                 payload_id: U64::from(0x03421ee50df45cacu64),
+                requested_at: Instant::now(),
             };
             state_channel.send(msg).await.unwrap();
 
@@ -111,9 +111,7 @@ mod tests {
         .await;
     }
 
-    #[test_case(Safe; "safe")]
     #[test_case(Pending; "pending")]
-    #[test_case(Finalized; "finalized")]
     #[tokio::test]
     async fn test_latest_block_height_is_same_as_tag(tag: BlockNumberOrTag) {
         let (state_channel, rx) = mpsc::channel(10);
@@ -124,6 +122,7 @@ mod tests {
             let msg = Command::StartBlockBuild {
                 payload_attributes: Default::default(),
                 payload_id: U64::from(0x03421ee50df45cacu64),
+                requested_at: Instant::now(),
             };
             state_channel.send(msg).await.unwrap();
 
@@ -135,4 +134,61 @@ mod tests {
         })
         .await;
     }
+
+    #[test_case(Safe; "safe")]
+    #[test_case(Finalized; "finalized")]
+    #[tokio::test]
+    async fn test_tag_without_forkchoice_update_has_no_block(tag: BlockNumberOrTag) {
+        let (reader, _app) = create_app();
+
+        let request = example_request(tag);
+        let response = execute(request, &reader).await.unwrap();
+
+        assert_eq!(response, None);
+    }
+
+    #[tokio::test]
+    async fn test_safe_and_finalized_blocks_follow_forkchoice_update() {
+        let (state_channel, rx) = mpsc::channel(10);
+        let (reader, mut app) = create_app();
+        let state: CommandActor<TestDependencies> = CommandActor::new(rx, &mut app);
+
+        moved_app::run(state, async move {
+            let msg = Command::StartBlockBuild {
+                payload_attributes: Default::default(),
+                payload_id: U64::from(0x03421ee50df45cacu64),
+                requested_at: Instant::now(),
+            };
+            state_channel.send(msg).await.unwrap();
+
+            state_channel.reserve_many(10).await.unwrap();
+
+            let genesis_hash = reader
+                .block_by_height(Number(0), false)
+                .unwrap()
+                .block
+                .header
+                .hash;
+            let head_hash = reader
+                .block_by_height(Latest, false)
+                .unwrap()
+                .block
+                .header
+                .hash;
+            let msg = Command::ForkchoiceUpdate {
+                safe_block_hash: head_hash,
+                finalized_block_hash: genesis_hash,
+            };
+            state_channel.send(msg).await.unwrap();
+
+            state_channel.reserve_many(10).await.unwrap();
+
+            let response = execute(example_request(Safe), &reader).await.unwrap();
+            assert_eq!(get_block_number_from_response(response), "0x1");
+
+            let response = execute(example_request(Finalized), &reader).await.unwrap();
+            assert_eq!(get_block_number_from_response(response), "0x0");
+        })
+        .await;
+    }
 }
@@ -0,0 +1,100 @@
+use {
+    crate::{json_utils::parse_params_1, jsonrpc::JsonRpcError},
+    alloy::eips::BlockNumberOrTag,
+    moved_app::{ApplicationReader, Dependencies},
+    moved_shared::primitives::{B256, U64},
+};
+
+pub async fn execute_by_hash(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let block_hash: B256 = parse_params_1(request)?;
+
+    let response = app.transaction_count_by_hash(block_hash).map(U64::from);
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+pub async fn execute_by_number(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let number: BlockNumberOrTag = parse_params_1(request)?;
+
+    let response = app.transaction_count_by_height(number).map(U64::from);
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::methods::tests::{create_app, deposit_eth},
+        alloy::primitives::hex,
+        moved_app::CommandActor,
+        test_case::test_case,
+        tokio::sync::mpsc,
+    };
+
+    const GENESIS_HASH: B256 = B256::new(hex!(
+        "e56ec7ba741931e8c55b7f654a6e56ed61cf8b8279bf5e3ef6ac86a11eb33a9d"
+    ));
+
+    fn example_request(method: &str, param: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": [param]
+        })
+    }
+
+    #[test_case(GENESIS_HASH, Some(0); "genesis")]
+    #[test_case(B256::ZERO, None; "unknown block")]
+    #[tokio::test]
+    async fn test_execute_by_hash(hash: B256, expected: Option<u64>) {
+        let (reader, _app) = create_app();
+        let request = example_request(
+            "eth_getBlockTransactionCountByHash",
+            hash.to_string().into(),
+        );
+
+        let response = execute_by_hash(request, &reader).await.unwrap();
+
+        assert_eq!(
+            response,
+            serde_json::to_value(expected.map(U64::from)).unwrap()
+        );
+    }
+
+    #[test_case(BlockNumberOrTag::Earliest, Some(0); "earliest")]
+    #[test_case(BlockNumberOrTag::Latest, Some(1); "latest")]
+    #[test_case(BlockNumberOrTag::Number(1), Some(1); "number")]
+    #[test_case(BlockNumberOrTag::Number(2), None; "unknown block")]
+    #[tokio::test]
+    async fn test_execute_by_number_after_deposit(tag: BlockNumberOrTag, expected: Option<u64>) {
+        let (reader, mut app) = create_app();
+        let (state_channel, rx) = mpsc::channel(10);
+        let state_actor = CommandActor::new(rx, &mut app);
+
+        moved_app::run(state_actor, async move {
+            deposit_eth("0x8fd379246834eac74b8419ffda202cf8051f7a03", &state_channel).await;
+            state_channel.reserve_many(10).await.unwrap();
+
+            let request = example_request(
+                "eth_getBlockTransactionCountByNumber",
+                serde_json::to_value(tag).unwrap(),
+            );
+
+            let response = execute_by_number(request, &reader).await.unwrap();
+
+            assert_eq!(
+                response,
+                serde_json::to_value(expected.map(U64::from)).unwrap()
+            );
+        })
+        .await;
+    }
+}
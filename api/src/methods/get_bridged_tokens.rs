@@ -0,0 +1,51 @@
+use {
+    crate::{jsonrpc::JsonRpcError, schema::BridgedTokenResponse},
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+pub async fn execute(
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let response: Vec<_> = app
+        .bridged_tokens()
+        .iter()
+        .map(|token| BridgedTokenResponse::new(token, app.bridged_token_metadata(token)))
+        .collect();
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app, moved_shared::primitives::B256};
+
+    #[tokio::test]
+    async fn test_execute_lists_tokens_of_genesis_config() {
+        let (reader, _app) = create_app();
+
+        let response = execute(&reader).await.unwrap();
+        let tokens: Vec<BridgedTokenResponse> = serde_json::from_value(response).unwrap();
+        let symbols: Vec<_> = tokens.iter().map(|token| token.symbol.as_str()).collect();
+
+        assert_eq!(symbols, ["USDC.e", "AAVE"]);
+        assert!(tokens.iter().all(|token| token.metadata_address.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_execute_lists_metadata_addresses_if_deployed() {
+        let (mut reader, _app) = create_app();
+        reader.genesis_config.token_metadata = true;
+
+        let response = execute(&reader).await.unwrap();
+        let tokens: Vec<BridgedTokenResponse> = serde_json::from_value(response).unwrap();
+
+        for (token, expected) in tokens.iter().zip(reader.bridged_tokens()) {
+            assert_eq!(
+                token.metadata_address,
+                Some(B256::new(
+                    moved_genesis::token_metadata_address(expected).into_bytes()
+                ))
+            );
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use {
+    crate::{json_utils, jsonrpc::JsonRpcError},
+    alloy::{
+        eips::{BlockId, BlockNumberOrTag},
+        primitives::Address,
+    },
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+/// Returns the EVM bytecode at an address, or a one byte marker for an account that Move packages
+/// are published at. The code of any other account is empty.
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (address, block_number) = parse_params(request)?;
+
+    app.ensure_state_retained(block_number)
+        .map_err(JsonRpcError::state_unavailable)?;
+
+    let response = app
+        .account_by_height(address, block_number)
+        .ok_or(JsonRpcError::block_not_found(block_number))?;
+
+    Ok(serde_json::to_value(response.code).expect("Must be able to JSON-serialize response"))
+}
+
+fn parse_params(request: serde_json::Value) -> Result<(Address, BlockId), JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    match params {
+        [] => Err(JsonRpcError {
+            code: -32602,
+            data: request,
+            message: "Not enough params".into(),
+        }),
+        [a] => {
+            let address: Address = json_utils::deserialize(a)?;
+            Ok((address, BlockNumberOrTag::Latest.into()))
+        }
+        [a, b] => {
+            let address: Address = json_utils::deserialize(a)?;
+            let block_number: BlockId = json_utils::deserialize(b)?;
+            Ok((address, block_number))
+        }
+        _ => Err(JsonRpcError {
+            code: -32602,
+            data: request,
+            message: "Too many params".into(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app, test_case::test_case};
+
+    #[test_case("0x4200000000000000000000000000000000000016", false; "contract")]
+    #[test_case("0x0000000000000000000000000000000000000001", false; "move packages")]
+    #[test_case("0x00000000000000000000000000000000000000ab", true; "missing account")]
+    #[tokio::test]
+    async fn test_execute(address: &str, is_empty: bool) {
+        let (reader, _app) = create_app();
+
+        let request: serde_json::Value = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_getCode",
+            "params": [address, "latest"],
+            "id": 1
+        });
+
+        let response = execute(request, &reader).await.unwrap();
+        let code: alloy::primitives::Bytes = serde_json::from_value(response).unwrap();
+
+        assert_eq!(code.is_empty(), is_empty);
+    }
+}
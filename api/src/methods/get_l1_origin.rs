@@ -0,0 +1,35 @@
+use {
+    crate::{json_utils::parse_params_1, jsonrpc::JsonRpcError, schema::L1OriginResponse},
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let number = parse_params_1(request)?;
+
+    let response = app.l1_origin(number).map(L1OriginResponse::from);
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app, alloy::eips::BlockNumberOrTag::Latest};
+
+    #[tokio::test]
+    async fn test_block_without_l1_attributes_deposit_has_no_l1_origin() {
+        let (reader, _app) = create_app();
+
+        let request = serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "umi_getL1Origin",
+            "params": [Latest]
+        });
+        let response = execute(request, &reader).await.unwrap();
+
+        assert_eq!(response, serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,122 @@
+use {
+    crate::{json_utils::parse_params_1, jsonrpc::JsonRpcError},
+    alloy::{
+        eips::BlockNumberOrTag,
+        rpc::types::{Filter, FilterBlockOption, Log},
+    },
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+/// Most blocks that a single query scans for logs, so that one request cannot read the whole
+/// chain. Same as the limit of common public RPC providers.
+const MAX_BLOCK_RANGE: u64 = 10_000;
+
+/// Returns the logs matching a filter of addresses and topics, either in a range of blocks or in
+/// the block with a given hash.
+///
+/// A range reaching past the latest block ends at the latest block.
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<Vec<Log>, JsonRpcError> {
+    let filter: Filter = parse_params_1(request)?;
+
+    let logs = match filter.block_option {
+        FilterBlockOption::AtBlockHash(hash) => app
+            .logs_by_block_hash(&filter, hash)
+            .ok_or(JsonRpcError::block_not_found(hash))?,
+        FilterBlockOption::Range {
+            from_block,
+            to_block,
+        } => {
+            let from_block = from_block.unwrap_or(BlockNumberOrTag::Latest);
+            let to_block = to_block.unwrap_or(BlockNumberOrTag::Latest);
+            let from_height = app
+                .height_from_block_id(from_block)
+                .ok_or(JsonRpcError::block_not_found(from_block))?;
+            let to_height = app
+                .height_from_block_id(to_block)
+                .ok_or(JsonRpcError::block_not_found(to_block))?
+                .min(app.block_number());
+
+            if from_height > to_height {
+                return Err(JsonRpcError::without_data(
+                    -32602,
+                    format!("Invalid block range: {from_height} is after {to_height}"),
+                ));
+            }
+            if to_height - from_height >= MAX_BLOCK_RANGE {
+                return Err(JsonRpcError::without_data(
+                    -32005,
+                    format!("Query exceeds max block range of {MAX_BLOCK_RANGE}"),
+                ));
+            }
+
+            app.logs(&filter, from_height, to_height)
+        }
+    };
+
+    Ok(logs)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::methods::tests::{create_app, deposit_eth},
+        moved_app::CommandActor,
+        tokio::sync::mpsc,
+    };
+
+    #[tokio::test]
+    async fn test_execute_finds_logs_of_deposit() {
+        let (reader, mut app) = create_app();
+        let (state_channel, rx) = mpsc::channel(10);
+        let state_actor = CommandActor::new(rx, &mut app);
+
+        moved_app::run(state_actor, async move {
+            deposit_eth("0x8fd379246834eac74b8419ffda202cf8051f7a03", &state_channel).await;
+            state_channel.reserve_many(10).await.unwrap();
+
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getLogs",
+                "params": [{ "fromBlock": "earliest", "toBlock": "latest" }],
+                "id": 1
+            });
+            let logs = execute(request, &reader).await.unwrap();
+            let block_hash = logs[0].block_hash.unwrap();
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getLogs",
+                "params": [{ "blockHash": block_hash, "address": logs[0].address() }],
+                "id": 1
+            });
+            let logs_in_block = execute(request, &reader).await.unwrap();
+
+            assert!(!logs.is_empty());
+            assert!(!logs_in_block.is_empty());
+            assert!(
+                logs_in_block
+                    .iter()
+                    .all(|log| log.address() == logs[0].address())
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_reversed_block_range() {
+        let (reader, _app) = create_app();
+
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_getLogs",
+            "params": [{ "fromBlock": "latest", "toBlock": "earliest" }],
+            "id": 1
+        });
+        let error = execute(request, &reader).await.unwrap_err();
+
+        assert_eq!(error.code, -32602);
+    }
+}
@@ -0,0 +1,136 @@
+use {
+    crate::{json_utils, jsonrpc::JsonRpcError, schema::MoveModuleResponse},
+    alloy::eips::BlockNumberOrTag,
+    move_binary_format::CompiledModule,
+    move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    },
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (id, height) = parse_params(request)?;
+
+    let response = app.module_by_height(&id, height).map(|bytecode| {
+        let module =
+            CompiledModule::deserialize(&bytecode).expect("Deployed modules should deserialize");
+
+        MoveModuleResponse {
+            abi: (&module).into(),
+            bytecode: bytecode.into(),
+        }
+    });
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+fn parse_params(request: serde_json::Value) -> Result<(ModuleId, BlockNumberOrTag), JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    let (address, name, height) = match params {
+        [] | [_] => return Err(JsonRpcError::parse_error(request, "Not enough params")),
+        [a, b] => (
+            json_utils::deserialize::<String>(a)?,
+            json_utils::deserialize::<String>(b)?,
+            BlockNumberOrTag::Latest,
+        ),
+        [a, b, c] => (
+            json_utils::deserialize(a)?,
+            json_utils::deserialize(b)?,
+            json_utils::deserialize(c)?,
+        ),
+        _ => return Err(JsonRpcError::parse_error(request, "Too many params")),
+    };
+    let address = AccountAddress::from_hex_literal(&address)
+        .map_err(|_| JsonRpcError::without_data(-32602, format!("Invalid address: {address}")))?;
+    let name = Identifier::new(name.as_str())
+        .map_err(|_| JsonRpcError::without_data(-32602, format!("Invalid module name: {name}")))?;
+
+    Ok((ModuleId::new(address, name), height))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            methods::tests::create_app,
+            schema::{MoveFieldAbi, MoveVisibility},
+        },
+    };
+
+    fn example_request(address: &str, name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "move_getModule",
+            "params": [address, name]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_returns_bytecode_and_abi_of_framework_module() {
+        let (reader, _app) = create_app();
+
+        let response = execute(example_request("0x1", "string"), &reader)
+            .await
+            .unwrap();
+        let response: MoveModuleResponse = serde_json::from_value(response).unwrap();
+
+        assert!(!response.bytecode.is_empty());
+        assert_eq!(response.abi.name, "string");
+        assert_eq!(
+            response.abi.address,
+            AccountAddress::ONE.into_bytes().into()
+        );
+
+        let utf8 = response
+            .abi
+            .exposed_functions
+            .iter()
+            .find(|function| function.name == "utf8")
+            .unwrap();
+        assert_eq!(utf8.visibility, MoveVisibility::Public);
+        assert_eq!(utf8.params, vec!["vector<u8>".to_string()]);
+        assert_eq!(utf8.returns, vec!["0x1::string::String".to_string()]);
+
+        let string = response
+            .abi
+            .structs
+            .iter()
+            .find(|s| s.name == "String")
+            .unwrap();
+        assert_eq!(string.abilities, vec!["copy", "drop", "store"]);
+        assert_eq!(
+            string.fields,
+            vec![MoveFieldAbi {
+                name: "bytes".into(),
+                type_name: "vector<u8>".into(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_returns_null_for_unknown_module() {
+        let (reader, _app) = create_app();
+
+        let response = execute(example_request("0x1", "no_such_module"), &reader)
+            .await
+            .unwrap();
+
+        assert_eq!(response, serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_invalid_module_name() {
+        let (reader, _app) = create_app();
+
+        let error = execute(example_request("0x1", "not-an-identifier"), &reader)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, -32602);
+    }
+}
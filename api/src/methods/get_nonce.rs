@@ -1,6 +1,9 @@
 use {
     crate::{json_utils, jsonrpc::JsonRpcError},
-    alloy::{eips::BlockNumberOrTag, primitives::Address},
+    alloy::{
+        eips::{BlockId, BlockNumberOrTag},
+        primitives::Address,
+    },
     moved_app::{ApplicationReader, Dependencies},
 };
 
@@ -10,6 +13,9 @@ pub async fn execute(
 ) -> Result<serde_json::Value, JsonRpcError> {
     let (address, block_number) = parse_params(request)?;
 
+    app.ensure_state_retained(block_number)
+        .map_err(JsonRpcError::state_unavailable)?;
+
     let response = app
         .nonce_by_height(address, block_number)
         .ok_or(JsonRpcError::block_not_found(block_number))?;
@@ -19,7 +25,7 @@ pub async fn execute(
         .expect("Must be able to JSON-serialize response"))
 }
 
-fn parse_params(request: serde_json::Value) -> Result<(Address, BlockNumberOrTag), JsonRpcError> {
+fn parse_params(request: serde_json::Value) -> Result<(Address, BlockId), JsonRpcError> {
     let params = json_utils::get_params_list(&request);
     match params {
         [] => Err(JsonRpcError {
@@ -29,11 +35,11 @@ fn parse_params(request: serde_json::Value) -> Result<(Address, BlockNumberOrTag
         }),
         [a] => {
             let address: Address = json_utils::deserialize(a)?;
-            Ok((address, BlockNumberOrTag::Latest))
+            Ok((address, BlockNumberOrTag::Latest.into()))
         }
         [a, b] => {
             let address: Address = json_utils::deserialize(a)?;
-            let block_number: BlockNumberOrTag = json_utils::deserialize(b)?;
+            let block_number: BlockId = json_utils::deserialize(b)?;
             Ok((address, block_number))
         }
         _ => Err(JsonRpcError {
@@ -72,11 +78,11 @@ mod tests {
             Address::from_str("0x0000000000000000000000000000000000000001").unwrap()
         );
         match block {
-            "latest" => assert_eq!(block_number, BlockNumberOrTag::Latest),
-            "pending" => assert_eq!(block_number, BlockNumberOrTag::Pending),
+            "latest" => assert_eq!(block_number, BlockNumberOrTag::Latest.into()),
+            "pending" => assert_eq!(block_number, BlockNumberOrTag::Pending.into()),
             _ => assert_eq!(
                 block_number,
-                BlockNumberOrTag::Number(U64::from_str(block).unwrap().into_limbs()[0])
+                BlockNumberOrTag::Number(U64::from_str(block).unwrap().into_limbs()[0]).into()
             ),
         }
     }
@@ -2,11 +2,34 @@ use {
     crate::{
         json_utils::parse_params_1,
         jsonrpc::JsonRpcError,
-        schema::{GetPayloadResponseV3, PayloadId},
+        schema::{GetPayloadResponseV2, GetPayloadResponseV3, GetPayloadResponseV4, PayloadId},
     },
     moved_app::{ApplicationReader, Dependencies},
+    moved_blockchain::payload::PayloadResponse,
 };
 
+/// Same as [`execute_v3`], but for blocks from before the Ecotone upgrade, which have no blobs
+/// bundle nor parent beacon block root.
+pub async fn execute_v2(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let payload_id: PayloadId = parse_params_1(request)?;
+
+    // Spec: https://github.com/ethereum/execution-apis/blob/main/src/engine/shanghai.md#specification-2
+    let payload = known_payload(app, payload_id)?;
+
+    if payload.parent_beacon_block_root.is_some() {
+        return Err(JsonRpcError::unsupported_fork(
+            "Unsupported fork, the payload was built after Ecotone",
+        ));
+    }
+
+    let response = GetPayloadResponseV2::from(payload);
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
 pub async fn execute_v3(
     request: serde_json::Value,
     app: &ApplicationReader<impl Dependencies>,
@@ -14,39 +37,83 @@ pub async fn execute_v3(
     let payload_id: PayloadId = parse_params_1(request)?;
 
     // Spec: https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#specification-2
-    let response = app
-        .payload(payload_id.into())
-        .map(GetPayloadResponseV3::from)
-        .ok_or_else(|| JsonRpcError {
-            code: -38001,
-            data: serde_json::to_value(payload_id).expect("Must serialize payload id"),
-            message: "Unknown payload".into(),
-        })?;
+    let response = GetPayloadResponseV3::try_from(known_payload(app, payload_id)?)
+        .map_err(JsonRpcError::unsupported_fork)?;
 
     Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
 }
 
+/// Same as [`execute_v3`], but for blocks since the Isthmus upgrade, which also return their
+/// execution requests.
+pub async fn execute_v4(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let payload_id: PayloadId = parse_params_1(request)?;
+
+    // Spec: https://specs.optimism.io/protocol/isthmus/exec-engine.html#engine_getpayloadv4
+    let response = GetPayloadResponseV4::try_from(known_payload(app, payload_id)?)
+        .map_err(JsonRpcError::unsupported_fork)?;
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+/// Finds the payload built under `payload_id`, unless it has expired.
+///
+/// An expired payload counts as unknown, same as one whose build process was stopped. Expired
+/// payloads are removed from storage as the chain advances, this only catches the ones fetched
+/// before they are.
+fn known_payload(
+    app: &ApplicationReader<impl Dependencies>,
+    payload_id: PayloadId,
+) -> Result<PayloadResponse, JsonRpcError> {
+    let unknown_payload = || JsonRpcError {
+        code: -38001,
+        data: serde_json::to_value(payload_id).expect("Must serialize payload id"),
+        message: "Unknown payload".into(),
+    };
+    let payload = app.payload(payload_id.into()).ok_or_else(unknown_payload)?;
+
+    if app.is_payload_expired(&payload) {
+        app.payload_expiry.record_rejected_fetch(payload_id.into());
+        return Err(unknown_payload());
+    }
+
+    app.payload_expiry.record_fetch(payload_id.into());
+
+    Ok(payload)
+}
+
 #[cfg(test)]
 mod tests {
     use {
         super::*,
-        crate::methods::forkchoice_updated,
+        crate::methods::{
+            forkchoice_updated,
+            tests::{create_app, create_app_before_ecotone},
+        },
         alloy::primitives::hex,
-        moved_app::{Application, CommandActor, TestDependencies},
+        moved_app::{
+            Application, CommandActor, GasEstimateCache, GasPriceOracle, ResponseCache,
+            TestDependencies,
+        },
         moved_blockchain::{
             block::{
                 Block, BlockRepository, Eip1559GasFee, InMemoryBlockQueries,
                 InMemoryBlockRepository, MovedBlockHash,
             },
             in_memory::shared_memory,
-            payload::InMemoryPayloadQueries,
+            payload::{InMemoryPayloadJobRepository, InMemoryPayloadQueries},
             receipt::{InMemoryReceiptQueries, InMemoryReceiptRepository, receipt_memory},
             state::InMemoryStateQueries,
-            transaction::{InMemoryTransactionQueries, InMemoryTransactionRepository},
+            transaction::{
+                InMemoryTransactionJournal, InMemoryTransactionQueries,
+                InMemoryTransactionRepository,
+            },
         },
         moved_evm_ext::state::InMemoryStorageTrieRepository,
         moved_genesis::config::GenesisConfig,
-        moved_shared::primitives::{B256, U256},
+        moved_shared::primitives::{B256, U64, U256},
         moved_state::InMemoryState,
     };
 
@@ -104,6 +171,7 @@ mod tests {
 
         let mut app = Application::<TestDependencies<_, _, _, _>> {
             mem_pool: Default::default(),
+            payload_expiry: Default::default(),
             genesis_config: genesis_config.clone(),
             state,
             block_hash: head_hash,
@@ -122,14 +190,15 @@ mod tests {
                 genesis_state_root,
             ),
             transaction_repository: InMemoryTransactionRepository::new(),
+            transaction_journal: InMemoryTransactionJournal::new(),
             transaction_queries: InMemoryTransactionQueries::new(),
             receipt_memory,
             receipt_repository: InMemoryReceiptRepository::new(),
             receipt_queries: InMemoryReceiptQueries::new(),
             payload_queries: InMemoryPayloadQueries::new(),
             evm_storage: evm_storage.clone(),
-            on_tx: CommandActor::on_tx_noop(),
-            on_tx_batch: CommandActor::on_tx_batch_noop(),
+            observers: Vec::new(),
+            payload_job_repository: InMemoryPayloadJobRepository::new(),
             on_payload: CommandActor::on_payload_in_memory(),
         };
         let reader = ApplicationReader::<
@@ -165,6 +234,13 @@ mod tests {
             receipt_queries: InMemoryReceiptQueries::new(),
             payload_queries: InMemoryPayloadQueries::new(),
             evm_storage,
+            gas_price_oracle: GasPriceOracle::new(Default::default()),
+            gas_estimates: GasEstimateCache::default(),
+            responses: ResponseCache::default(),
+            l1_fee: U256::ZERO,
+            mem_pool: Default::default(),
+            payload_expiry: Default::default(),
+            peer_count: Default::default(),
         };
         let (queue, state) = moved_app::create(&mut app, 10);
 
@@ -174,6 +250,7 @@ mod tests {
                 forkchoice_updated::tests::example_request(),
                 queue.clone(),
                 &0x03421ee50df45cacu64,
+                &reader,
             )
                 .await
                 .unwrap();
@@ -234,4 +311,194 @@ mod tests {
             assert_eq!(actual_response, expected_response);
         }).await;
     }
+
+    fn forkchoice_updated_request_v2() -> serde_json::Value {
+        let mut request = forkchoice_updated::tests::example_request();
+        request["method"] = "engine_forkchoiceUpdatedV2".into();
+        request["params"][1]
+            .as_object_mut()
+            .unwrap()
+            .remove("parentBeaconBlockRoot");
+        request
+    }
+
+    fn get_payload_request(method: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": 30054,
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": ["0x03421ee50df45cac"]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_v2_has_no_cancun_fields() {
+        let (reader, mut app) = create_app_before_ecotone();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            forkchoice_updated::execute_v2(
+                forkchoice_updated_request_v2(),
+                queue.clone(),
+                &0x03421ee50df45cacu64,
+                &reader,
+            )
+            .await
+            .unwrap();
+            queue.wait_for_pending_commands().await;
+
+            let response = execute_v2(get_payload_request("engine_getPayloadV2"), &reader)
+                .await
+                .unwrap();
+
+            assert!(response.get("parentBeaconBlockRoot").is_none());
+            assert!(response.get("blobsBundle").is_none());
+            assert!(response["executionPayload"].get("blobGasUsed").is_none());
+            assert!(response["executionPayload"].get("excessBlobGas").is_none());
+            let response: GetPayloadResponseV2 = serde_json::from_value(response).unwrap();
+            assert_eq!(response.execution_payload.block_number, U64::from(1));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v2_rejects_payload_built_after_ecotone() {
+        let (reader, mut app) = create_app();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            forkchoice_updated::execute_v3(
+                forkchoice_updated::tests::example_request(),
+                queue.clone(),
+                &0x03421ee50df45cacu64,
+                &reader,
+            )
+            .await
+            .unwrap();
+            queue.wait_for_pending_commands().await;
+
+            let error = execute_v2(get_payload_request("engine_getPayloadV2"), &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(error.code, -38005);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v3_rejects_payload_built_before_ecotone() {
+        let (reader, mut app) = create_app_before_ecotone();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            forkchoice_updated::execute_v2(
+                forkchoice_updated_request_v2(),
+                queue.clone(),
+                &0x03421ee50df45cacu64,
+                &reader,
+            )
+            .await
+            .unwrap();
+            queue.wait_for_pending_commands().await;
+
+            let error = execute_v3(get_payload_request("engine_getPayloadV3"), &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(error.code, -38005);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v4_rejects_payload_built_before_isthmus() {
+        let (reader, mut app) = create_app();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            forkchoice_updated::execute_v3(
+                forkchoice_updated::tests::example_request(),
+                queue.clone(),
+                &0x03421ee50df45cacu64,
+                &reader,
+            )
+            .await
+            .unwrap();
+            queue.wait_for_pending_commands().await;
+
+            let error = execute_v4(get_payload_request("engine_getPayloadV4"), &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(error.code, -38005);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v4_returns_withdrawals_root_and_execution_requests() {
+        let (reader, mut app) = create_app();
+        app.genesis_config.hardforks.isthmus_time = Some(0);
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            forkchoice_updated::execute_v3(
+                forkchoice_updated::tests::example_request(),
+                queue.clone(),
+                &0x03421ee50df45cacu64,
+                &reader,
+            )
+            .await
+            .unwrap();
+            queue.wait_for_pending_commands().await;
+
+            let response = execute_v4(get_payload_request("engine_getPayloadV4"), &reader)
+                .await
+                .unwrap();
+
+            assert!(
+                response["executionPayload"]
+                    .get("withdrawalsRoot")
+                    .is_some()
+            );
+            let response: GetPayloadResponseV4 = serde_json::from_value(response).unwrap();
+            assert!(response.execution_requests.is_empty());
+            assert_eq!(response.execution_payload.block_number, U64::from(1));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v3_rejects_expired_payload() {
+        let (reader, mut app) = create_app();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            forkchoice_updated::execute_v3(
+                forkchoice_updated::tests::example_request(),
+                queue.clone(),
+                &0x03421ee50df45cacu64,
+                &reader,
+            )
+            .await
+            .unwrap();
+            // The next block comes long after the payload was due to be fetched
+            let mut request = forkchoice_updated::tests::example_request();
+            request["params"][1]["timestamp"] = "0x66607500".into();
+            forkchoice_updated::execute_v3(request, queue.clone(), &0x03421ee50df45cadu64, &reader)
+                .await
+                .unwrap();
+            queue.wait_for_pending_commands().await;
+
+            let error = execute_v3(get_payload_request("engine_getPayloadV3"), &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(error.code, -38001);
+            assert!(reader.payload(U64::from(0x03421ee50df45cacu64)).is_none());
+            assert_eq!(reader.payload_expiry.counts().unfetched_payloads, 1);
+        })
+        .await;
+    }
 }
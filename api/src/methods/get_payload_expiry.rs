@@ -0,0 +1,37 @@
+use {
+    crate::{jsonrpc::JsonRpcError, schema::PayloadExpiryResponse},
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+/// Returns how many payloads have expired since the node has started, which only grow while the
+/// op-node stalls.
+pub async fn execute(
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let response = PayloadExpiryResponse::from(app.payload_expiry.counts());
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app, moved_shared::primitives::U64};
+
+    #[tokio::test]
+    async fn test_execute_reports_expiries_seen_by_app() {
+        let (reader, app) = create_app();
+        app.payload_expiry.record_dropped_job(U64::from(1));
+        reader.payload_expiry.record_rejected_fetch(U64::from(2));
+
+        let response = execute(&reader).await.unwrap();
+
+        assert_eq!(
+            response,
+            serde_json::json!({
+                "droppedJobs": "0x1",
+                "rejectedFetches": "0x1",
+                "unfetchedPayloads": "0x0",
+            })
+        );
+    }
+}
@@ -16,6 +16,9 @@ pub async fn execute(
 ) -> Result<serde_json::Value, JsonRpcError> {
     let (address, storage_slots, block_number) = parse_params(request)?;
 
+    app.ensure_state_retained(block_number)
+        .map_err(JsonRpcError::state_unavailable)?;
+
     let response = app
         .proof(address, storage_slots, block_number)
         .ok_or(JsonRpcError::block_not_found(block_number))?;
@@ -134,4 +137,54 @@ mod tests {
             assert!(list.len() == 2 || list.len() == 17);
         }
     }
+
+    #[tokio::test]
+    async fn test_execute_proves_requested_storage_slots() {
+        let (reader, _app) = create_app();
+        // Proxy admin slot, which is set, and a slot that is not
+        let slots = [
+            U256::from_be_bytes(hex!(
+                "b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103"
+            )),
+            U256::from(12345),
+        ];
+
+        let request: serde_json::Value = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_getProof",
+            "params": [
+                "0x4200000000000000000000000000000000000016",
+                slots,
+                "latest",
+            ],
+            "id": 1
+        });
+
+        let response: ProofResponse =
+            serde_json::from_value(execute(request, &reader).await.unwrap()).unwrap();
+
+        assert_eq!(response.storage_proof.len(), slots.len());
+        assert_eq!(response.storage_proof[0].key.as_b256(), slots[0].into());
+        assert!(!response.storage_proof[0].value.is_zero());
+        assert!(!response.storage_proof[0].proof.is_empty());
+        assert!(response.storage_proof[1].value.is_zero());
+    }
+
+    #[tokio::test]
+    async fn test_execute_proves_address_outside_of_predeploys() {
+        let (reader, _app) = create_app();
+
+        let request: serde_json::Value = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_getProof",
+            "params": ["0xabababababababababababababababababababab", [], "latest"],
+            "id": 1
+        });
+
+        let response: ProofResponse =
+            serde_json::from_value(execute(request, &reader).await.unwrap()).unwrap();
+
+        assert_eq!(response.balance, U256::ZERO);
+        assert!(!response.account_proof.is_empty());
+    }
 }
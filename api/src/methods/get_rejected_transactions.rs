@@ -0,0 +1,86 @@
+use {
+    crate::{
+        json_utils::parse_params_1, jsonrpc::JsonRpcError, schema::RejectedTransactionResponse,
+    },
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let number = parse_params_1(request)?;
+
+    let response = app.rejected_transactions(number).map(|rejected| {
+        rejected
+            .into_iter()
+            .map(RejectedTransactionResponse::from)
+            .collect::<Vec<_>>()
+    });
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::methods::tests::create_app,
+        alloy::{eips::BlockNumberOrTag, primitives::keccak256},
+        moved_app::{Command, CommandActor, Payload, TestDependencies},
+        moved_shared::primitives::{Bytes, U64},
+        std::time::Instant,
+        tokio::sync::mpsc,
+    };
+
+    fn example_request(tag: BlockNumberOrTag) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "umi_getRejectedTransactions",
+            "params": [tag]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_undecodable_transaction_is_reported_as_rejected() {
+        let (state_channel, rx) = mpsc::channel(10);
+        let (reader, mut app) = create_app();
+        let state: CommandActor<TestDependencies> = CommandActor::new(rx, &mut app);
+        let tx = Bytes::from_static(&[0xff, 0xff]);
+        let tx_hash = keccak256(&tx);
+
+        moved_app::run(state, async move {
+            let msg = Command::StartBlockBuild {
+                payload_attributes: Payload {
+                    transactions: vec![tx],
+                    ..Default::default()
+                },
+                payload_id: U64::from(0x03421ee50df45cacu64),
+                requested_at: Instant::now(),
+            };
+            state_channel.send(msg).await.unwrap();
+
+            state_channel.reserve_many(10).await.unwrap();
+
+            let request = example_request(BlockNumberOrTag::Latest);
+            let response = execute(request, &reader).await.unwrap();
+            let rejected: Vec<RejectedTransactionResponse> =
+                serde_json::from_value(response).unwrap();
+
+            assert_eq!(rejected.len(), 1);
+            assert_eq!(rejected[0].transaction_hash, tx_hash);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_unknown_block_has_no_rejected_transactions() {
+        let (reader, _app) = create_app();
+
+        let request = example_request(BlockNumberOrTag::Number(1));
+        let response = execute(request, &reader).await.unwrap();
+
+        assert_eq!(response, serde_json::Value::Null);
+    }
+}
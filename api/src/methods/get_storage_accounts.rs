@@ -0,0 +1,66 @@
+use {
+    crate::{json_utils, jsonrpc::JsonRpcError},
+    alloy::primitives::B256,
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+/// Most accounts a single request may look up.
+pub const MAX_STORAGE_ACCOUNTS: usize = 1_024;
+
+/// Returns the addresses of the EVM accounts whose keys in the state trie have the given hashes,
+/// so that a peer syncing the state can fetch the storage tries it finds there.
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let key_hashes = parse_params(request)?;
+
+    if key_hashes.len() > MAX_STORAGE_ACCOUNTS {
+        return Err(JsonRpcError::without_data(
+            -32602,
+            format!("At most {MAX_STORAGE_ACCOUNTS} accounts can be requested at once"),
+        ));
+    }
+
+    let response = app
+        .storage_accounts(&key_hashes)
+        .map_err(|e| JsonRpcError::without_data(-32000, e.to_string()))?;
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+fn parse_params(request: serde_json::Value) -> Result<Vec<B256>, JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    match params {
+        [] => Err(JsonRpcError::parse_error(request, "Not enough params")),
+        [a] => json_utils::deserialize(a),
+        _ => Err(JsonRpcError::parse_error(request, "Too many params")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::methods::tests::create_app,
+        alloy::primitives::{Address, keccak256},
+        moved_evm_ext::state::StorageTrieRepository,
+    };
+
+    #[tokio::test]
+    async fn test_execute_finds_accounts_with_storage_by_key_hash() {
+        let (reader, _app) = create_app();
+        let account = reader.evm_storage.accounts().unwrap()[0];
+
+        let request = serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "umi_getStorageAccounts",
+            "params": [[keccak256(account), B256::ZERO]]
+        });
+        let response = execute(request, &reader).await.unwrap();
+        let accounts: Vec<Option<Address>> = serde_json::from_value(response).unwrap();
+
+        assert_eq!(accounts, vec![Some(account), None]);
+    }
+}
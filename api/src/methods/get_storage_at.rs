@@ -0,0 +1,84 @@
+use {
+    crate::{json_utils, jsonrpc::JsonRpcError},
+    alloy::{
+        eips::{BlockId, BlockNumberOrTag},
+        primitives::{Address, B256, U256},
+    },
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+/// Returns the value of a storage slot of an EVM contract, which is zero for a slot without a value
+/// and for every slot of an account that is not an EVM contract.
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (address, index, block_number) = parse_params(request)?;
+
+    app.ensure_state_retained(block_number)
+        .map_err(JsonRpcError::state_unavailable)?;
+
+    let response = app
+        .storage_by_height(address, index, block_number)
+        .ok_or(JsonRpcError::block_not_found(block_number))?;
+
+    Ok(
+        serde_json::to_value(B256::from(response))
+            .expect("Must be able to JSON-serialize response"),
+    )
+}
+
+fn parse_params(request: serde_json::Value) -> Result<(Address, U256, BlockId), JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    match params {
+        [] | [_] => Err(JsonRpcError {
+            code: -32602,
+            data: request,
+            message: "Not enough params".into(),
+        }),
+        [a, b] => {
+            let address: Address = json_utils::deserialize(a)?;
+            let index: U256 = json_utils::deserialize(b)?;
+            Ok((address, index, BlockNumberOrTag::Latest.into()))
+        }
+        [a, b, c] => {
+            let address: Address = json_utils::deserialize(a)?;
+            let index: U256 = json_utils::deserialize(b)?;
+            let block_number: BlockId = json_utils::deserialize(c)?;
+            Ok((address, index, block_number))
+        }
+        _ => Err(JsonRpcError {
+            code: -32602,
+            data: request,
+            message: "Too many params".into(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app, test_case::test_case};
+
+    // Proxy admin slot of a predeployed L2 contract, which is set at genesis
+    const ADMIN_SLOT: &str = "0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103";
+
+    #[test_case("0x4200000000000000000000000000000000000016", ADMIN_SLOT, false; "set slot")]
+    #[test_case("0x4200000000000000000000000000000000000016", "0x3039", true; "unset slot")]
+    #[test_case("0x00000000000000000000000000000000000000ab", ADMIN_SLOT, true; "missing account")]
+    #[tokio::test]
+    async fn test_execute(address: &str, index: &str, is_zero: bool) {
+        let (reader, _app) = create_app();
+
+        let request: serde_json::Value = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_getStorageAt",
+            "params": [address, index, "latest"],
+            "id": 1
+        });
+
+        let response = execute(request, &reader).await.unwrap();
+        let value: B256 = serde_json::from_value(response).unwrap();
+
+        assert_eq!(value.is_zero(), is_zero);
+    }
+}
@@ -0,0 +1,216 @@
+use {
+    crate::{json_utils, jsonrpc::JsonRpcError, schema::ViewValueResponse},
+    alloy::eips::BlockNumberOrTag,
+    move_core_types::{account_address::AccountAddress, language_storage::TypeTag},
+    moved_app::{ApplicationReader, Dependencies},
+    moved_shared::primitives::Bytes,
+    std::str::FromStr,
+};
+
+/// Reads the item under a bcs encoded key of a Move table, given the types of its keys and values.
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (handle, key_type, value_type, key, height) = parse_params(request)?;
+
+    let response = app
+        .table_item_by_height(handle, &key_type, &key, value_type, height)
+        .map_err(|_| {
+            JsonRpcError::without_data(-32602, format!("Key does not decode as {key_type}"))
+        })?
+        .map(ViewValueResponse::from);
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+fn parse_params(
+    request: serde_json::Value,
+) -> Result<(AccountAddress, TypeTag, TypeTag, Bytes, BlockNumberOrTag), JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    let (handle, key_type, value_type, key, height) = match params {
+        [] | [_] | [_, _] | [_, _, _] => {
+            return Err(JsonRpcError::parse_error(request, "Not enough params"));
+        }
+        [a, b, c, d] => (
+            json_utils::deserialize::<String>(a)?,
+            json_utils::deserialize::<String>(b)?,
+            json_utils::deserialize::<String>(c)?,
+            json_utils::deserialize(d)?,
+            BlockNumberOrTag::Latest,
+        ),
+        [a, b, c, d, e] => (
+            json_utils::deserialize(a)?,
+            json_utils::deserialize(b)?,
+            json_utils::deserialize(c)?,
+            json_utils::deserialize(d)?,
+            json_utils::deserialize(e)?,
+        ),
+        _ => return Err(JsonRpcError::parse_error(request, "Too many params")),
+    };
+    let handle = AccountAddress::from_hex_literal(&handle)
+        .map_err(|_| JsonRpcError::without_data(-32602, format!("Invalid handle: {handle}")))?;
+
+    Ok((
+        handle,
+        parse_type(&key_type)?,
+        parse_type(&value_type)?,
+        key,
+        height,
+    ))
+}
+
+fn parse_type(tag: &str) -> Result<TypeTag, JsonRpcError> {
+    TypeTag::from_str(tag)
+        .map_err(|_| JsonRpcError::without_data(-32602, format!("Invalid type: {tag}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::methods::tests::{PRIVATE_KEY, create_app, fund_account},
+        alloy::{
+            consensus::{SignableTransaction, TxEip1559},
+            network::TxSignerSync,
+            primitives::{TxKind, U256},
+            rlp::Encodable,
+            signers::local::PrivateKeySigner,
+        },
+        aptos_types::transaction::EntryFunction,
+        move_core_types::{
+            ident_str,
+            language_storage::{ModuleId, StructTag},
+        },
+        moved_app::Payload,
+        moved_execution::transaction::TransactionData,
+        moved_genesis::{TOKEN_ADDRESS, config::CHAIN_ID},
+        moved_shared::primitives::{ToMoveAddress, U64},
+        op_alloy::consensus::OpTxEnvelope,
+    };
+
+    fn example_request(key_type: &str, key: Bytes) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "move_getTableItem",
+            "params": ["0x1234", key_type, "u64", key, "latest"]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_returns_null_for_unknown_item() {
+        let (reader, _app) = create_app();
+
+        let response = execute(
+            example_request("address", AccountAddress::ONE.to_vec().into()),
+            &reader,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response, serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_invalid_key_type() {
+        let (reader, _app) = create_app();
+
+        let error = execute(example_request("0x1::string", Bytes::new()), &reader)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_key_not_decoding_as_key_type() {
+        let (reader, _app) = create_app();
+
+        let error = execute(example_request("address", vec![1, 2, 3].into()), &reader)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_execute_reads_item_written_by_transaction() {
+        let (mut reader, mut app) = create_app();
+        reader.genesis_config.move_vm.table_state_time = Some(0);
+        app.genesis_config.move_vm.table_state_time = Some(0);
+        let signer = PrivateKeySigner::from_bytes(&PRIVATE_KEY.into()).unwrap();
+        fund_account(&mut app, signer.address());
+
+        // Creating a token collection adds it to the table of collections of its creator
+        let name = bcs::to_bytes("Tables").unwrap();
+        let entry_fn = EntryFunction::new(
+            ModuleId::new(TOKEN_ADDRESS, ident_str!("token").into()),
+            ident_str!("create_collection_script").into(),
+            Vec::new(),
+            vec![
+                name.clone(),
+                bcs::to_bytes("").unwrap(),
+                bcs::to_bytes("").unwrap(),
+                bcs::to_bytes(&0u64).unwrap(),
+                bcs::to_bytes(&vec![false; 3]).unwrap(),
+            ],
+        );
+        let mut tx = TxEip1559 {
+            chain_id: CHAIN_ID,
+            nonce: 0,
+            gas_limit: u64::MAX,
+            to: TxKind::Call(signer.address()),
+            value: U256::ZERO,
+            input: TransactionData::EntryFunction(entry_fn)
+                .to_bytes()
+                .unwrap()
+                .into(),
+            ..Default::default()
+        };
+        let signature = signer.sign_transaction_sync(&mut tx).unwrap();
+        let mut encoded = Vec::new();
+        OpTxEnvelope::Eip1559(tx.into_signed(signature)).encode(&mut encoded);
+        app.start_block_build(
+            Payload {
+                gas_limit: U64::MAX,
+                transactions: vec![encoded.into()],
+                ..Default::default()
+            },
+            U64::from(0x03421ee50df45bbbu64),
+        );
+
+        // The handle of the table is the first field of the collections of the creator
+        let collections = StructTag {
+            address: TOKEN_ADDRESS,
+            module: ident_str!("token").into(),
+            name: ident_str!("Collections").into(),
+            type_args: Vec::new(),
+        };
+        let collections = reader
+            .resource_by_height(
+                signer.address().to_move_address(),
+                &collections,
+                BlockNumberOrTag::Latest,
+            )
+            .unwrap();
+        let handle = AccountAddress::from_bytes(&collections[..AccountAddress::LENGTH]).unwrap();
+        let request = serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "move_getTableItem",
+            "params": [
+                handle.to_hex_literal(),
+                "0x1::string::String",
+                "0x13::token::CollectionData",
+                Bytes::from(name),
+                "latest"
+            ]
+        });
+
+        let response = execute(request, &reader).await.unwrap();
+
+        assert_eq!(response["type"], "0x13::token::CollectionData");
+        assert_ne!(response["json"], serde_json::Value::Null);
+    }
+}
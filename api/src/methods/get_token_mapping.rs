@@ -0,0 +1,58 @@
+use {
+    crate::{
+        jsonrpc::JsonRpcError,
+        schema::{TokenKind, TokenMappingResponse},
+    },
+    alloy::primitives::address,
+    moved_app::{ApplicationReader, Dependencies},
+    moved_genesis::eth_token_metadata_address,
+    moved_shared::primitives::{Address, B256},
+};
+
+/// Address that EVM tooling uses for the native asset, as in EIP-7528.
+const NATIVE_ASSET_ADDRESS: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+
+pub async fn execute(
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let base_token = TokenMappingResponse {
+        kind: TokenKind::Native,
+        evm_address: Some(NATIVE_ASSET_ADDRESS),
+        move_address: Some(B256::new(eth_token_metadata_address().into_bytes())),
+        l1_address: None,
+        name: "Ether".into(),
+        symbol: "ETH".into(),
+        decimals: 18,
+    };
+    let response: Vec<_> = std::iter::once(base_token)
+        .chain(app.bridged_tokens().iter().map(TokenMappingResponse::from))
+        .collect();
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app, moved_shared::primitives::ToMoveAddress};
+
+    #[tokio::test]
+    async fn test_execute_maps_base_token_and_bridged_tokens() {
+        let (reader, _app) = create_app();
+
+        let response = execute(&reader).await.unwrap();
+        let tokens: Vec<TokenMappingResponse> = serde_json::from_value(response).unwrap();
+        let symbols: Vec<_> = tokens.iter().map(|token| token.symbol.as_str()).collect();
+
+        assert_eq!(symbols, ["ETH", "USDC.e", "AAVE"]);
+        assert_eq!(tokens[0].kind, TokenKind::Native);
+        for token in &tokens[1..] {
+            assert_eq!(token.kind, TokenKind::Bridged);
+            assert_eq!(
+                token.move_address,
+                token
+                    .evm_address
+                    .map(|address| B256::new(address.to_move_address().into_bytes()))
+            );
+        }
+    }
+}
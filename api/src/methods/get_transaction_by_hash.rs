@@ -21,7 +21,10 @@ mod tests {
     use {
         super::*,
         crate::{
-            methods::{forkchoice_updated, get_payload, send_raw_transaction, tests::create_app},
+            methods::{
+                forkchoice_updated, get_payload, send_raw_transaction,
+                tests::{create_app, fund_account},
+            },
             schema::{ForkchoiceUpdatedResponseV1, GetPayloadResponseV3},
         },
         serde_json::json,
@@ -31,6 +34,7 @@ mod tests {
     #[tokio::test]
     async fn test_execute() {
         let (reader, mut app) = create_app();
+        fund_account(&mut app, send_raw_transaction::tests::EXAMPLE_SENDER);
         let (queue, state) = moved_app::create(&mut app, 10);
 
         moved_app::run(state, async move {
@@ -38,6 +42,7 @@ mod tests {
             let tx_hash = send_raw_transaction::execute(
                 send_raw_transaction::tests::example_request(),
                 queue.clone(),
+                &reader,
             )
             .await
             .unwrap();
@@ -48,6 +53,7 @@ mod tests {
                     forkchoice_updated::tests::example_request(),
                     queue.clone(),
                     &0x03421ee50df45cacu64,
+                    &reader,
                 )
                 .await
                 .unwrap(),
@@ -96,7 +102,7 @@ mod tests {
                 "v": "0x0",
                 "hash": "0x3545efb3ce7a22353c346c98771640131b81baa64eb03113b20ad2bef5c0ec53",
                 "blockHash": block_hash,
-                "blockNumber": "0x1",
+                "blockNumber": "0x2",
                 "transactionIndex": "0x2",
                 "from": "0x88f9b82462f6c4bf4a0fb15e5c3971559a316e7f",
                 "gasPrice": "0x0"
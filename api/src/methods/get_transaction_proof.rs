@@ -0,0 +1,105 @@
+use {
+    crate::{json_utils::parse_params_1, jsonrpc::JsonRpcError, schema::InclusionProofResponse},
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let tx_hash = parse_params_1(request)?;
+
+    let response = app
+        .transaction_proof(tx_hash)
+        .map(InclusionProofResponse::from);
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use {
+        super::*,
+        crate::methods::{
+            forkchoice_updated, send_raw_transaction,
+            tests::{create_app, fund_account},
+        },
+        alloy::eips::BlockNumberOrTag::Latest,
+        alloy_trie::{Nibbles, proof::verify_proof},
+        moved_app::{CommandQueue, TestDependencies},
+        moved_shared::primitives::B256,
+    };
+
+    pub fn example_request(tx_hash: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "umi_getTransactionProof",
+            "params": [tx_hash]
+        })
+    }
+
+    /// Sends a transaction and builds a block including it, returning the transaction hash.
+    ///
+    /// The sender of the transaction needs to be funded with [`fund_account`] beforehand.
+    pub async fn include_example_transaction(
+        queue: CommandQueue,
+        reader: &ApplicationReader<TestDependencies>,
+    ) -> serde_json::Value {
+        let tx_hash = send_raw_transaction::execute(
+            send_raw_transaction::tests::example_request(),
+            queue.clone(),
+            reader,
+        )
+        .await
+        .unwrap();
+        forkchoice_updated::execute_v3(
+            forkchoice_updated::tests::example_request(),
+            queue.clone(),
+            &0x03421ee50df45cacu64,
+            reader,
+        )
+        .await
+        .unwrap();
+        queue.wait_for_pending_commands().await;
+
+        tx_hash
+    }
+
+    #[tokio::test]
+    async fn test_execute_proves_transaction_against_transactions_root() {
+        let (reader, mut app) = create_app();
+        fund_account(&mut app, send_raw_transaction::tests::EXAMPLE_SENDER);
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            let tx_hash = include_example_transaction(queue, &reader).await;
+
+            let response = execute(example_request(tx_hash), &reader).await.unwrap();
+            let proof: InclusionProofResponse = serde_json::from_value(response).unwrap();
+
+            let header = reader.block_by_height(Latest, false).unwrap().block.header;
+            assert_eq!(proof.block_hash, header.hash);
+            assert_eq!(proof.root, header.transactions_root);
+            verify_proof(
+                proof.root,
+                Nibbles::unpack(&proof.key),
+                Some(proof.value.to_vec()),
+                &proof.proof,
+            )
+            .unwrap();
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_of_unknown_transaction_is_null() {
+        let (reader, _app) = create_app();
+
+        let response = execute(example_request(serde_json::json!(B256::ZERO)), &reader)
+            .await
+            .unwrap();
+
+        assert!(response.is_null());
+    }
+}
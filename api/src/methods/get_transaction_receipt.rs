@@ -19,7 +19,10 @@ mod tests {
     use {
         super::*,
         crate::{
-            methods::{forkchoice_updated, get_payload, send_raw_transaction, tests::create_app},
+            methods::{
+                forkchoice_updated, get_payload, send_raw_transaction,
+                tests::{create_app, fund_account},
+            },
             schema::{ForkchoiceUpdatedResponseV1, GetPayloadResponseV3},
         },
         moved_blockchain::receipt::TransactionReceipt,
@@ -29,6 +32,7 @@ mod tests {
     #[tokio::test]
     async fn test_execute() {
         let (reader, mut app) = create_app();
+        fund_account(&mut app, send_raw_transaction::tests::EXAMPLE_SENDER);
         let (queue, state) = moved_app::create(&mut app, 10);
 
         moved_app::run(state, async move {
@@ -36,6 +40,7 @@ mod tests {
             let tx_hash = send_raw_transaction::execute(
                 send_raw_transaction::tests::example_request(),
                 queue.clone(),
+                &reader,
             )
             .await
             .unwrap();
@@ -46,6 +51,7 @@ mod tests {
                     forkchoice_updated::tests::example_request(),
                     queue.clone(),
                     &0x03421ee50df45cacu64,
+                    &reader,
                 )
                 .await
                 .unwrap(),
@@ -0,0 +1,136 @@
+use {
+    crate::{json_utils, jsonrpc::JsonRpcError},
+    alloy::primitives::{Address, B256, Bytes},
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+/// Most trie nodes a single request may ask for.
+pub const MAX_TRIE_NODES: usize = 1_024;
+
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (hashes, account) = parse_params(request)?;
+
+    if hashes.len() > MAX_TRIE_NODES {
+        return Err(JsonRpcError::without_data(
+            -32602,
+            format!("At most {MAX_TRIE_NODES} trie nodes can be requested at once"),
+        ));
+    }
+
+    let response: Vec<_> = app
+        .trie_nodes(account, &hashes)
+        .into_iter()
+        .map(|node| node.map(Bytes::from))
+        .collect();
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+fn parse_params(request: serde_json::Value) -> Result<(Vec<B256>, Option<Address>), JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    match params {
+        [] => Err(JsonRpcError::parse_error(request, "Not enough params")),
+        [a] => Ok((json_utils::deserialize(a)?, None)),
+        [a, b] => Ok((json_utils::deserialize(a)?, json_utils::deserialize(b)?)),
+        _ => Err(JsonRpcError::parse_error(request, "Too many params")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::methods::tests::create_app,
+        alloy::{eips::BlockNumberOrTag, primitives::keccak256},
+        eth_trie::MemoryDB,
+        moved_blockchain::state::TrieSync,
+        std::sync::Arc,
+    };
+
+    fn latest_state_root(reader: &ApplicationReader<impl Dependencies>) -> B256 {
+        reader
+            .block_by_height(BlockNumberOrTag::Latest, false)
+            .unwrap()
+            .block
+            .header
+            .state_root
+    }
+
+    fn example_request(hashes: &[B256]) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "umi_getTrieNodes",
+            "params": [hashes]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_serves_state_root_node() {
+        let (reader, _app) = create_app();
+        let root = latest_state_root(&reader);
+
+        let request = example_request(&[root, B256::ZERO]);
+        let response = execute(request, &reader).await.unwrap();
+        let nodes: Vec<Option<Bytes>> = serde_json::from_value(response).unwrap();
+
+        assert_eq!(keccak256(nodes[0].as_ref().unwrap()), root);
+        assert_eq!(nodes[1], None);
+    }
+
+    async fn sync(
+        reader: &ApplicationReader<impl Dependencies>,
+        root: B256,
+        account: Option<Address>,
+    ) -> Vec<(B256, B256)> {
+        let mut sync = TrieSync::new(root, Arc::new(MemoryDB::new(true)));
+        let mut storage_roots = Vec::new();
+
+        while !sync.is_complete() {
+            let hashes = sync.pending(MAX_TRIE_NODES);
+            let mut request = example_request(&hashes);
+            if let Some(account) = account {
+                let params = request["params"].as_array_mut().unwrap();
+                params.push(serde_json::to_value(account).unwrap());
+            }
+            let response = execute(request, reader).await.unwrap();
+            let nodes: Vec<Option<Bytes>> = serde_json::from_value(response).unwrap();
+            let nodes = nodes.into_iter().map(|node| node.unwrap().to_vec());
+
+            sync.process(nodes.collect()).unwrap();
+            storage_roots.extend(sync.take_storage_roots());
+        }
+
+        storage_roots
+    }
+
+    #[tokio::test]
+    async fn test_state_syncs_from_served_nodes() {
+        let (reader, _app) = create_app();
+        let root = latest_state_root(&reader);
+
+        let storage_roots = sync(&reader, root, None).await;
+        assert!(!storage_roots.is_empty());
+
+        let key_hashes: Vec<_> = storage_roots
+            .iter()
+            .map(|(key_hash, _)| *key_hash)
+            .collect();
+        let accounts = reader.storage_accounts(&key_hashes).unwrap();
+        for ((_, storage_root), account) in storage_roots.into_iter().zip(accounts) {
+            sync(&reader, storage_root, Some(account.unwrap())).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_too_many_hashes() {
+        let (reader, _app) = create_app();
+
+        let request = example_request(&vec![B256::ZERO; MAX_TRIE_NODES + 1]);
+
+        assert!(execute(request, &reader).await.is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use {
+    crate::{
+        json_utils::parse_params_1, jsonrpc::JsonRpcError, schema::TransactionReceiptResponse,
+    },
+    moved_app::{ApplicationReader, Dependencies},
+};
+
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let tx_hash = parse_params_1(request)?;
+
+    let response = app
+        .transaction_receipt(tx_hash)
+        .map(|receipt| TransactionReceiptResponse {
+            receipt,
+            vm_status: app.transaction_vm_status(tx_hash),
+        });
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::methods::tests::create_app,
+        moved_blockchain::receipt::{ExtendedReceipt, VmStatus},
+        moved_shared::primitives::B256,
+        op_alloy::consensus::OpReceiptEnvelope,
+    };
+
+    fn example_request(tx_hash: B256) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "umi_getTransactionReceipt",
+            "params": [tx_hash]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_includes_vm_status_of_failed_transaction() {
+        let (reader, mut app) = create_app();
+        let tx_hash = B256::repeat_byte(1);
+        let vm_status = VmStatus {
+            location: Some("0x1::coin".into()),
+            abort_code: Some(0x1_0006),
+            message: "EINSUFFICIENT_BALANCE in 0x1::coin".into(),
+            revert_data: None,
+        };
+        app.receipt_memory.extend([ExtendedReceipt {
+            transaction_hash: tx_hash,
+            transaction_index: 0,
+            to: None,
+            from: Default::default(),
+            receipt: OpReceiptEnvelope::Legacy(Default::default()),
+            l1_block_info: None,
+            gas_used: 21_000,
+            l2_gas_price: Default::default(),
+            contract_address: None,
+            logs_offset: 0,
+            block_hash: B256::repeat_byte(2),
+            block_number: 1,
+            block_timestamp: 0,
+            vm_status: Some(vm_status),
+        }]);
+
+        let response = execute(example_request(tx_hash), &reader).await.unwrap();
+
+        assert_eq!(response["transactionHash"], serde_json::json!(tx_hash));
+        assert_eq!(
+            response["vmStatus"],
+            serde_json::json!({
+                "location": "0x1::coin",
+                "abortCode": 0x1_0006,
+                "message": "EINSUFFICIENT_BALANCE in 0x1::coin",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_of_unknown_transaction_is_null() {
+        let (reader, _app) = create_app();
+
+        let response = execute(example_request(B256::ZERO), &reader).await.unwrap();
+
+        assert!(response.is_null());
+    }
+}
@@ -0,0 +1,173 @@
+use {
+    crate::{
+        json_utils,
+        jsonrpc::JsonRpcError,
+        schema::{ListResourcesOptions, ListResourcesResponse, MoveResourceResponse},
+    },
+    alloy::eips::BlockNumberOrTag,
+    move_core_types::{account_address::AccountAddress, language_storage::StructTag},
+    moved_app::{ApplicationReader, Dependencies},
+    std::str::FromStr,
+};
+
+/// The number of resources in a page that sets no limit.
+const DEFAULT_LIMIT: usize = 100;
+/// Larger limits are lowered to this one.
+const MAX_LIMIT: usize = 1000;
+
+/// Lists the Move resources stored under an account, a page at a time.
+///
+/// Each page ends with a cursor that the next page starts after, which is `null` for the last page.
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (address, options, height) = parse_params(request)?;
+    // The resources written before the index was added to the database are missing from it, so
+    // listing them would leave some out without telling
+    if !app.is_resource_index_complete() {
+        return Err(JsonRpcError::without_data(
+            -32000,
+            "Resources cannot be listed, as the database predates the resource index",
+        ));
+    }
+    let after = options
+        .cursor
+        .as_deref()
+        .map(|cursor| {
+            StructTag::from_str(cursor).map_err(|_| {
+                JsonRpcError::without_data(-32602, format!("Invalid cursor: {cursor}"))
+            })
+        })
+        .transpose()?;
+    let limit = match options.limit {
+        Some(0) => return Err(JsonRpcError::without_data(-32602, "Limit must be positive")),
+        Some(limit) => limit.min(MAX_LIMIT),
+        None => DEFAULT_LIMIT,
+    };
+
+    let response = app
+        .resources_by_height(address, after.as_ref(), limit, height)
+        .map(|resources| {
+            let resources = resources
+                .into_iter()
+                .map(MoveResourceResponse::from)
+                .collect::<Vec<_>>();
+            let next_cursor = (resources.len() == limit)
+                .then(|| resources.last().map(|resource| resource.type_tag.clone()))
+                .flatten();
+
+            ListResourcesResponse {
+                resources,
+                next_cursor,
+            }
+        });
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+fn parse_params(
+    request: serde_json::Value,
+) -> Result<(AccountAddress, ListResourcesOptions, BlockNumberOrTag), JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    let (address, options, height) = match params {
+        [] => return Err(JsonRpcError::parse_error(request, "Not enough params")),
+        [a] => (
+            json_utils::deserialize::<String>(a)?,
+            ListResourcesOptions::default(),
+            BlockNumberOrTag::Latest,
+        ),
+        [a, b] => (
+            json_utils::deserialize(a)?,
+            json_utils::deserialize::<Option<_>>(b)?.unwrap_or_default(),
+            BlockNumberOrTag::Latest,
+        ),
+        [a, b, c] => (
+            json_utils::deserialize(a)?,
+            json_utils::deserialize::<Option<_>>(b)?.unwrap_or_default(),
+            json_utils::deserialize(c)?,
+        ),
+        _ => return Err(JsonRpcError::parse_error(request, "Too many params")),
+    };
+    let address = AccountAddress::from_hex_literal(&address)
+        .map_err(|_| JsonRpcError::without_data(-32602, format!("Invalid address: {address}")))?;
+
+    Ok((address, options, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app};
+
+    fn example_request(address: &str, options: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "move_listResources",
+            "params": [address, options, "latest"]
+        })
+    }
+
+    async fn list(
+        reader: &ApplicationReader<impl Dependencies>,
+        options: serde_json::Value,
+    ) -> ListResourcesResponse {
+        let response = execute(example_request("0x1", options), reader)
+            .await
+            .unwrap();
+
+        serde_json::from_value(response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_lists_resources_of_framework_account_in_pages() {
+        let (reader, _app) = create_app();
+
+        let all = list(&reader, serde_json::json!({ "limit": MAX_LIMIT })).await;
+        let first = list(&reader, serde_json::json!({ "limit": 2 })).await;
+        let second = list(
+            &reader,
+            serde_json::json!({ "cursor": first.next_cursor, "limit": 2 }),
+        )
+        .await;
+
+        assert!(all.resources.len() > 4);
+        assert!(all.next_cursor.is_none());
+        assert!(all.resources.iter().all(|resource| resource.json.is_some()));
+        assert_eq!(
+            first.next_cursor.as_ref(),
+            Some(&first.resources[1].type_tag)
+        );
+        assert_eq!(
+            [first.resources, second.resources].concat(),
+            all.resources[..4]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_lists_no_resources_of_unused_account() {
+        let (reader, _app) = create_app();
+
+        let response = execute(example_request("0x1234", serde_json::Value::Null), &reader)
+            .await
+            .unwrap();
+        let response: ListResourcesResponse = serde_json::from_value(response).unwrap();
+
+        assert!(response.resources.is_empty());
+        assert!(response.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_invalid_cursor() {
+        let (reader, _app) = create_app();
+
+        let error = execute(
+            example_request("0x1", serde_json::json!({ "cursor": "0x1::account" })),
+            &reader,
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.code, -32602);
+    }
+}
@@ -0,0 +1,31 @@
+use {
+    crate::jsonrpc::JsonRpcError,
+    moved_app::{ApplicationReader, Dependencies},
+    moved_shared::primitives::U256,
+};
+
+pub async fn execute(
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let response = U256::from(app.max_priority_fee_per_gas());
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app};
+
+    #[tokio::test]
+    async fn test_execute_without_tips_suggests_minimum_tip() {
+        let (reader, _app) = create_app();
+
+        let response = execute(&reader).await.unwrap();
+        let tip: U256 = serde_json::from_value(response).unwrap();
+
+        assert_eq!(
+            tip,
+            U256::from(reader.genesis_config.gas_price_oracle.min_priority_fee)
+        );
+    }
+}
@@ -1,20 +1,46 @@
 pub mod block_number;
 pub mod call;
 pub mod chain_id;
+pub mod client_version;
 pub mod estimate_gas;
+pub mod estimate_l1_fee;
 pub mod fee_history;
 pub mod forkchoice_updated;
 pub mod gas_price;
 pub mod get_balance;
 pub mod get_block_by_hash;
 pub mod get_block_by_number;
+pub mod get_block_transaction_count;
+pub mod get_bridged_tokens;
+pub mod get_code;
+pub mod get_l1_origin;
+pub mod get_logs;
+pub mod get_module;
 pub mod get_nonce;
 pub mod get_payload;
+pub mod get_payload_expiry;
 pub mod get_proof;
+pub mod get_receipt_proof;
+pub mod get_rejected_transactions;
+pub mod get_storage_accounts;
+pub mod get_storage_at;
+pub mod get_table_item;
+pub mod get_token_mapping;
 pub mod get_transaction_by_hash;
+pub mod get_transaction_proof;
 pub mod get_transaction_receipt;
+pub mod get_trie_nodes;
+pub mod get_umi_transaction_receipt;
+pub mod list_resources;
+pub mod max_priority_fee_per_gas;
+pub mod net;
 pub mod new_payload;
+pub mod resolve_address;
 pub mod send_raw_transaction;
+pub mod simulate_transaction;
+pub mod syncing;
+pub mod trace_transaction;
+pub mod view;
 
 #[cfg(test)]
 pub mod tests {
@@ -23,39 +49,46 @@ pub mod tests {
             consensus::{Sealed, SignableTransaction, TxEip1559},
             hex::FromHex,
             network::TxSignerSync,
-            primitives::{Bytes, FixedBytes, TxKind, hex, utils::parse_ether},
+            primitives::{Bytes, FixedBytes, TxKind, address, hex, utils::parse_ether},
             rlp::Encodable,
             signers::local::PrivateKeySigner,
         },
         move_core_types::account_address::AccountAddress,
         moved_app::{
-            Application, ApplicationReader, Command, CommandActor, DependenciesThreadSafe, Payload,
-            TestDependencies,
+            Application, ApplicationReader, Command, CommandActor, DependenciesThreadSafe,
+            GasEstimateCache, GasPriceOracle, MemPool, Payload, PayloadExpiry, ResponseCache,
+            TestDependencies, Workers,
         },
         moved_blockchain::{
             block::{
                 Block, BlockQueries, BlockRepository, BlockResponse, Eip1559GasFee, Header,
-                InMemoryBlockQueries, InMemoryBlockRepository, MovedBlockHash,
+                InMemoryBlockQueries, InMemoryBlockRepository, MovedBlockHash, RejectedTransaction,
             },
             in_memory::shared_memory,
-            payload::InMemoryPayloadQueries,
+            payload::{InMemoryPayloadJobRepository, InMemoryPayloadQueries},
             receipt::{InMemoryReceiptQueries, InMemoryReceiptRepository, receipt_memory},
             state::{InMemoryStateQueries, MockStateQueries},
-            transaction::{InMemoryTransactionQueries, InMemoryTransactionRepository},
+            transaction::{
+                InMemoryTransactionJournal, InMemoryTransactionQueries,
+                InMemoryTransactionRepository,
+            },
         },
         moved_evm_ext::state::InMemoryStorageTrieRepository,
         moved_execution::MovedBaseTokenAccounts,
-        moved_genesis::config::{CHAIN_ID, GenesisConfig},
+        moved_genesis::config::{CHAIN_ID, GenesisConfig, HardforkSchedule},
         moved_shared::primitives::{Address, B256, U64, U256},
-        moved_state::InMemoryState,
+        moved_state::{InMemoryState, resource_index::InMemoryResourceIndex},
         op_alloy::consensus::{OpTxEnvelope, TxDeposit},
-        std::convert::Infallible,
+        std::{convert::Infallible, time::Instant},
         tokio::sync::mpsc::Sender,
     };
 
     /// The address corresponding to this private key is 0x8fd379246834eac74B8419FfdA202CF8051F7A03
     pub const PRIVATE_KEY: [u8; 32] = [0xaa; 32];
 
+    /// Sends the deposits of [`deposit_eth`], which increment the nonce of their sender.
+    pub const DEPOSITOR_ADDRESS: Address = address!("4a04a3191b7a44a99bfd3184f0d2c2c82b98b939");
+
     pub fn create_app() -> (
         ApplicationReader<TestDependencies>,
         Application<TestDependencies>,
@@ -78,12 +111,15 @@ pub mod tests {
         repository.add(&mut memory, genesis_block).unwrap();
 
         let trie_db = InMemoryState::create_db();
-        let mut state = InMemoryState::new(trie_db.clone());
+        let resource_index = InMemoryResourceIndex::default();
+        let mut state =
+            InMemoryState::new(trie_db.clone()).with_resource_index(resource_index.clone());
         let state_queries = InMemoryStateQueries::new(
             memory_reader.clone(),
             trie_db,
             genesis_config.initial_state_root,
-        );
+        )
+        .with_resource_index(resource_index);
         let mut evm_storage = InMemoryStorageTrieRepository::new();
         let (changes, table_changes, evm_storage_changes) = moved_genesis_image::load();
         moved_genesis::apply(
@@ -96,6 +132,9 @@ pub mod tests {
         );
         let (receipt_memory_reader, receipt_memory) = receipt_memory::new();
 
+        let mem_pool = MemPool::default();
+        let payload_expiry = PayloadExpiry::default();
+
         (
             ApplicationReader {
                 genesis_config: genesis_config.clone(),
@@ -108,9 +147,18 @@ pub mod tests {
                 state_queries: state_queries.clone(),
                 evm_storage: evm_storage.clone(),
                 transaction_queries: InMemoryTransactionQueries::new(),
+                gas_price_oracle: GasPriceOracle::new(genesis_config.gas_price_oracle.clone()),
+                gas_estimates: GasEstimateCache::default(),
+                responses: ResponseCache::default(),
+                l1_fee: U256::ZERO,
+                mem_pool: mem_pool.clone(),
+                payload_expiry: payload_expiry.clone(),
+                peer_count: Default::default(),
             },
             Application {
-                mem_pool: Default::default(),
+                mem_pool,
+                payload_expiry,
+                workers: Workers::new(&genesis_config.concurrency),
                 genesis_config,
                 gas_fee: Eip1559GasFee::default(),
                 base_token: MovedBaseTokenAccounts::new(AccountAddress::ONE),
@@ -120,8 +168,8 @@ pub mod tests {
                 block_queries: InMemoryBlockQueries,
                 block_repository: repository,
                 on_payload: CommandActor::on_payload_in_memory(),
-                on_tx: CommandActor::on_tx_noop(),
-                on_tx_batch: CommandActor::on_tx_batch_noop(),
+                observers: Vec::new(),
+                payload_job_repository: InMemoryPayloadJobRepository::new(),
                 payload_queries: InMemoryPayloadQueries::new(),
                 receipt_queries: InMemoryReceiptQueries::new(),
                 receipt_repository: InMemoryReceiptRepository::new(),
@@ -134,18 +182,53 @@ pub mod tests {
                 evm_storage,
                 transaction_queries: InMemoryTransactionQueries::new(),
                 transaction_repository: InMemoryTransactionRepository::new(),
+                transaction_journal: InMemoryTransactionJournal::new(),
             },
         )
     }
 
+    /// Same as [`create_app`], but for a chain that has not activated Ecotone nor any later
+    /// hardfork yet.
+    pub fn create_app_before_ecotone() -> (
+        ApplicationReader<TestDependencies>,
+        Application<TestDependencies>,
+    ) {
+        let (mut reader, mut app) = create_app();
+        let hardforks = HardforkSchedule {
+            ecotone_time: None,
+            fjord_time: None,
+            granite_time: None,
+            holocene_time: None,
+            ..Default::default()
+        };
+        reader.genesis_config.hardforks = hardforks.clone();
+        app.genesis_config.hardforks = hardforks;
+
+        (reader, app)
+    }
+
     pub async fn deposit_eth(to: &str, channel: &Sender<Command>) {
-        let to = Address::from_hex(to).unwrap();
+        let msg = Command::StartBlockBuild {
+            payload_attributes: deposit_eth_payload(Address::from_hex(to).unwrap()),
+            payload_id: U64::from(0x03421ee50df45cacu64),
+            requested_at: Instant::now(),
+        };
+        channel.send(msg).await.unwrap();
+    }
+
+    /// Mints 1 ETH to `to` in a block that `app` builds right away, so that transactions sent by
+    /// `to` pass the balance check of `eth_sendRawTransaction`.
+    pub fn fund_account(app: &mut Application<TestDependencies>, to: Address) {
+        app.start_block_build(deposit_eth_payload(to), U64::from(0x03421ee50df45abbu64));
+    }
+
+    fn deposit_eth_payload(to: Address) -> Payload {
         let amount = parse_ether("1").unwrap();
         let tx = OpTxEnvelope::Deposit(Sealed::new(TxDeposit {
             to: TxKind::Call(to),
             value: amount,
             source_hash: FixedBytes::default(),
-            from: to,
+            from: DEPOSITOR_ADDRESS,
             mint: Some(amount.try_into().unwrap()),
             gas_limit: u64::MAX,
             is_system_transaction: false,
@@ -154,17 +237,11 @@ pub mod tests {
 
         let mut encoded = Vec::new();
         tx.encode(&mut encoded);
-        let payload_attributes = Payload {
+        Payload {
             gas_limit: U64::MAX,
             transactions: vec![encoded.into()],
             ..Default::default()
-        };
-
-        let msg = Command::StartBlockBuild {
-            payload_attributes,
-            payload_id: U64::from(0x03421ee50df45cacu64),
-        };
-        channel.send(msg).await.unwrap();
+        }
     }
 
     pub async fn deploy_contract(contract_bytes: Bytes, channel: &Sender<Command>) {
@@ -196,6 +273,7 @@ pub mod tests {
         let msg = Command::StartBlockBuild {
             payload_attributes,
             payload_id: U64::from(0x03421ee50df45aaau64),
+            requested_at: Instant::now(),
         };
         channel.send(msg).await.unwrap();
     }
@@ -236,6 +314,38 @@ pub mod tests {
             fn latest(&self, _: &Self::Storage) -> Result<Option<u64>, Self::Err> {
                 Ok(Some(self.0))
             }
+
+            fn safe(&self, _: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+                unimplemented!("Unexpected call to `safe`")
+            }
+
+            fn finalized(&self, _: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+                unimplemented!("Unexpected call to `finalized`")
+            }
+
+            fn rejected_transactions(
+                &self,
+                _: &Self::Storage,
+                _: u64,
+            ) -> Result<Option<Vec<RejectedTransaction>>, Self::Err> {
+                unimplemented!("Unexpected call to `rejected_transactions`")
+            }
+
+            fn transaction_count_by_hash(
+                &self,
+                _: &Self::Storage,
+                _: B256,
+            ) -> Result<Option<u64>, Self::Err> {
+                unimplemented!("Unexpected call to `transaction_count_by_hash`")
+            }
+
+            fn transaction_count_by_height(
+                &self,
+                _: &Self::Storage,
+                _: u64,
+            ) -> Result<Option<u64>, Self::Err> {
+                unimplemented!("Unexpected call to `transaction_count_by_height`")
+            }
         }
 
         Box::new((
@@ -272,10 +382,18 @@ pub mod tests {
                 state_queries: MockStateQueries(address, height),
                 evm_storage: (),
                 transaction_queries: (),
+                gas_price_oracle: GasPriceOracle::new(Default::default()),
+                gas_estimates: GasEstimateCache::default(),
+                responses: ResponseCache::default(),
+                l1_fee: U256::ZERO,
+                mem_pool: Default::default(),
+                payload_expiry: Default::default(),
+                peer_count: Default::default(),
             },
             Application::<TestDependencies<_, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _>> {
                 genesis_config: GenesisConfig::default(),
                 mem_pool: Default::default(),
+                payload_expiry: Default::default(),
                 gas_fee: Eip1559GasFee::default(),
                 base_token: MovedBaseTokenAccounts::new(AccountAddress::ONE),
                 l1_fee: U256::ZERO,
@@ -284,8 +402,8 @@ pub mod tests {
                 block_queries: StubLatest(height),
                 block_repository: (),
                 on_payload: CommandActor::on_payload_noop(),
-                on_tx: CommandActor::on_tx_noop(),
-                on_tx_batch: CommandActor::on_tx_batch_noop(),
+                observers: Vec::new(),
+                payload_job_repository: InMemoryPayloadJobRepository::new(),
                 payload_queries: (),
                 receipt_queries: (),
                 receipt_repository: (),
@@ -298,6 +416,7 @@ pub mod tests {
                 evm_storage: (),
                 transaction_queries: (),
                 transaction_repository: (),
+                transaction_journal: InMemoryTransactionJournal::new(),
             },
         ))
     }
@@ -0,0 +1,66 @@
+//! The `net` namespace, which describes the network of the node as wallets and health checkers
+//! expect it.
+//!
+//! The node takes part in the network through its rollup node, and its only peers of its own are
+//! those of the optional P2P gossip network. It is always listening.
+
+use {
+    crate::jsonrpc::JsonRpcError,
+    moved_app::{ApplicationReader, Dependencies},
+    moved_shared::primitives::U64,
+};
+
+/// Returns the ID of the network, which is the chain ID in decimal.
+pub async fn execute_version(
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let response = app.chain_id().to_string();
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+pub async fn execute_listening() -> Result<serde_json::Value, JsonRpcError> {
+    Ok(true.into())
+}
+
+/// Returns the number of peers connected over the P2P gossip network, which is zero if disabled.
+pub async fn execute_peer_count(
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let response = U64::from(app.peer_count.get());
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app};
+
+    #[tokio::test]
+    async fn test_execute_version_is_decimal_chain_id() {
+        let (reader, _app) = create_app();
+
+        let response = execute_version(&reader).await.unwrap();
+
+        assert_eq!(response, serde_json::json!("404"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_peer_count_is_zero_without_network() {
+        let (reader, _app) = create_app();
+
+        let response = execute_peer_count(&reader).await.unwrap();
+
+        assert_eq!(response, serde_json::json!("0x0"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_peer_count_reports_connected_peers() {
+        let (reader, _app) = create_app();
+        reader.peer_count.set(3);
+
+        let response = execute_peer_count(&reader).await.unwrap();
+
+        assert_eq!(response, serde_json::json!("0x3"));
+    }
+}
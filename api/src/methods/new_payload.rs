@@ -1,37 +1,79 @@
 use {
     crate::{
-        json_utils::parse_params_3,
+        json_utils::{parse_params_1, parse_params_3, parse_params_4},
         jsonrpc::JsonRpcError,
-        schema::{ExecutionPayloadV3, GetPayloadResponseV3, PayloadStatusV1, Status},
+        schema::{
+            ExecutionPayloadV2, ExecutionPayloadV3, ExecutionPayloadV4, PayloadStatusV1, Status,
+        },
     },
+    alloy_trie::root::ordered_trie_root,
     moved_app::{ApplicationReader, Dependencies},
-    moved_shared::primitives::B256,
+    moved_blockchain::payload::{ExecutionPayload, PayloadResponse},
+    moved_shared::primitives::{B256, Bytes},
 };
 
+/// Same as [`execute_v3`], but for blocks from before the Ecotone upgrade, which have neither blob
+/// versioned hashes nor a parent beacon block root.
+pub async fn execute_v2(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let execution_payload: ExecutionPayloadV2 = parse_params_1(request)?;
+    // Spec: https://github.com/ethereum/execution-apis/blob/main/src/engine/shanghai.md#specification
+    let response = inner_execute(execution_payload.into(), Vec::new(), None, app).await?;
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
 pub async fn execute_v3(
     request: serde_json::Value,
     app: &ApplicationReader<impl Dependencies>,
 ) -> Result<serde_json::Value, JsonRpcError> {
     let (execution_payload, expected_blob_versioned_hashes, parent_beacon_block_root) =
-        parse_params_3(request)?;
-    let response = inner_execute_v3(
-        execution_payload,
+        parse_params_3::<ExecutionPayloadV3, _, _>(request)?;
+    // Spec: https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#specification
+    let response = inner_execute(
+        execution_payload.into(),
         expected_blob_versioned_hashes,
-        parent_beacon_block_root,
+        Some(parent_beacon_block_root),
         app,
     )
     .await?;
     Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
 }
 
-async fn inner_execute_v3(
-    execution_payload: ExecutionPayloadV3,
+/// Same as [`execute_v3`], but for blocks since the Isthmus upgrade, which also commit to the
+/// withdrawals initiated on L2 and to the execution layer requests.
+pub async fn execute_v4(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (execution_payload, expected_blob_versioned_hashes, parent_beacon_block_root, requests) =
+        parse_params_4::<ExecutionPayloadV4, _, _, Vec<Bytes>>(request)?;
+
+    // Spec: https://specs.optimism.io/protocol/isthmus/exec-engine.html#engine_newpayloadv4
+    if !requests.is_empty() {
+        return Err(JsonRpcError::without_data(
+            -32602,
+            "Execution requests must be empty on the OP stack",
+        ));
+    }
+
+    let response = inner_execute(
+        execution_payload.into(),
+        expected_blob_versioned_hashes,
+        Some(parent_beacon_block_root),
+        app,
+    )
+    .await?;
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+async fn inner_execute(
+    execution_payload: ExecutionPayload,
     expected_blob_versioned_hashes: Vec<B256>,
-    parent_beacon_block_root: B256,
+    parent_beacon_block_root: Option<B256>,
     app: &ApplicationReader<impl Dependencies>,
 ) -> Result<PayloadStatusV1, JsonRpcError> {
-    // Spec: https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#specification
-
     // TODO: in theory we should start syncing to learn about this block hash.
     let response = app
         .payload_by_block_hash(execution_payload.block_hash)
@@ -40,8 +82,7 @@ async fn inner_execute_v3(
             data: serde_json::to_value(execution_payload.block_hash)
                 .expect("Must serialize block hash"),
             message: "Unknown block hash".into(),
-        })?
-        .into();
+        })?;
 
     validate_payload(
         execution_payload,
@@ -52,10 +93,10 @@ async fn inner_execute_v3(
 }
 
 fn validate_payload(
-    execution_payload: ExecutionPayloadV3,
+    execution_payload: ExecutionPayload,
     expected_blob_versioned_hashes: Vec<B256>,
-    parent_beacon_block_root: B256,
-    known_payload: GetPayloadResponseV3,
+    parent_beacon_block_root: Option<B256>,
+    known_payload: PayloadResponse,
 ) -> Result<PayloadStatusV1, JsonRpcError> {
     if execution_payload.block_number != known_payload.execution_payload.block_number {
         return Ok(PayloadStatusV1 {
@@ -113,7 +154,17 @@ fn validate_payload(
         });
     }
 
-    if execution_payload.withdrawals != known_payload.execution_payload.withdrawals {
+    // Until Isthmus the header commits to the withdrawals of the block, which are not kept apart
+    // from it, so the payload is checked against that root instead
+    let withdrawals_match = match execution_payload.withdrawals_root {
+        Some(_) => execution_payload.withdrawals == known_payload.execution_payload.withdrawals,
+        None => known_payload
+            .execution_payload
+            .withdrawals_root
+            .is_none_or(|root| root == ordered_trie_root(&execution_payload.withdrawals)),
+    };
+
+    if !withdrawals_match {
         return Ok(PayloadStatusV1 {
             status: Status::Invalid,
             latest_valid_hash: None,
@@ -121,6 +172,18 @@ fn validate_payload(
         });
     }
 
+    // Only payloads since Isthmus commit to a withdrawals root
+    if execution_payload
+        .withdrawals_root
+        .is_some_and(|root| Some(root) != known_payload.execution_payload.withdrawals_root)
+    {
+        return Ok(PayloadStatusV1 {
+            status: Status::Invalid,
+            latest_valid_hash: None,
+            validation_error: Some("Incorrect withdrawals root".into()),
+        });
+    }
+
     // TODO: validate execution relates fields once op-geth no longer used
     // base_fee_per_gas, gas_used, logs_bool, receipts_root, state_root, transactions
 
@@ -152,19 +215,28 @@ fn validate_payload(
 mod tests {
     use {
         super::*,
-        crate::methods::{forkchoice_updated, get_payload},
+        crate::methods::{
+            forkchoice_updated, get_payload,
+            tests::{create_app, create_app_before_ecotone},
+        },
         alloy::primitives::hex,
-        moved_app::{Application, CommandActor, TestDependencies},
+        moved_app::{
+            Application, CommandActor, GasEstimateCache, GasPriceOracle, ResponseCache,
+            TestDependencies,
+        },
         moved_blockchain::{
             block::{
                 Block, BlockRepository, Eip1559GasFee, InMemoryBlockQueries,
                 InMemoryBlockRepository, MovedBlockHash,
             },
             in_memory::shared_memory,
-            payload::InMemoryPayloadQueries,
+            payload::{InMemoryPayloadJobRepository, InMemoryPayloadQueries},
             receipt::{InMemoryReceiptQueries, InMemoryReceiptRepository, receipt_memory},
             state::InMemoryStateQueries,
-            transaction::{InMemoryTransactionQueries, InMemoryTransactionRepository},
+            transaction::{
+                InMemoryTransactionJournal, InMemoryTransactionQueries,
+                InMemoryTransactionRepository,
+            },
         },
         moved_evm_ext::state::InMemoryStorageTrieRepository,
         moved_genesis::config::GenesisConfig,
@@ -283,6 +355,7 @@ mod tests {
 
         let mut app = Application::<TestDependencies<_, _, _, _>> {
             mem_pool: Default::default(),
+            payload_expiry: Default::default(),
             genesis_config: genesis_config.clone(),
             gas_fee: Eip1559GasFee::default(),
             base_token: (),
@@ -294,8 +367,8 @@ mod tests {
             block_queries: InMemoryBlockQueries,
             block_repository: repository,
             on_payload: CommandActor::on_payload_in_memory(),
-            on_tx: CommandActor::on_tx_noop(),
-            on_tx_batch: CommandActor::on_tx_batch_noop(),
+            observers: Vec::new(),
+            payload_job_repository: InMemoryPayloadJobRepository::new(),
             payload_queries: InMemoryPayloadQueries::new(),
             receipt_queries: InMemoryReceiptQueries::new(),
             receipt_repository: InMemoryReceiptRepository::new(),
@@ -312,6 +385,7 @@ mod tests {
                 genesis_state_root,
             ),
             transaction_repository: InMemoryTransactionRepository::new(),
+            transaction_journal: InMemoryTransactionJournal::new(),
         };
         let reader = ApplicationReader::<
             TestDependencies<
@@ -346,6 +420,13 @@ mod tests {
             receipt_queries: InMemoryReceiptQueries::new(),
             payload_queries: InMemoryPayloadQueries::new(),
             evm_storage,
+            gas_price_oracle: GasPriceOracle::new(Default::default()),
+            gas_estimates: GasEstimateCache::default(),
+            responses: ResponseCache::default(),
+            l1_fee: U256::ZERO,
+            mem_pool: Default::default(),
+            payload_expiry: Default::default(),
+            peer_count: Default::default(),
         };
         let (queue, state) = moved_app::create(&mut app, 10);
 
@@ -431,6 +512,7 @@ mod tests {
                 fc_updated_request,
                 queue.clone(),
                 &0x0306d51fc5aa1533u64,
+                &reader,
             )
                 .await
                 .unwrap();
@@ -457,4 +539,192 @@ mod tests {
             assert_eq!(response, expected_response);
         }).await;
     }
+
+    #[tokio::test]
+    async fn test_execute_v2_accepts_payload_built_before_ecotone() {
+        let (reader, mut app) = create_app_before_ecotone();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            let mut fc_updated_request = forkchoice_updated::tests::example_request();
+            fc_updated_request["params"][1]
+                .as_object_mut()
+                .unwrap()
+                .remove("parentBeaconBlockRoot");
+            forkchoice_updated::execute_v2(
+                fc_updated_request,
+                queue.clone(),
+                &0x03421ee50df45cacu64,
+                &reader,
+            )
+            .await
+            .unwrap();
+            queue.wait_for_pending_commands().await;
+
+            let get_payload_request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 8,
+                "method": "engine_getPayloadV2",
+                "params": ["0x03421ee50df45cac"]
+            });
+            let payload = get_payload::execute_v2(get_payload_request, &reader)
+                .await
+                .unwrap();
+            let new_payload_request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 9,
+                "method": "engine_newPayloadV2",
+                "params": [payload["executionPayload"]]
+            });
+
+            let response: PayloadStatusV1 =
+                serde_json::from_value(execute_v2(new_payload_request, &reader).await.unwrap())
+                    .unwrap();
+
+            assert_eq!(response.status, Status::Valid);
+            assert_eq!(
+                response.latest_valid_hash,
+                Some(
+                    serde_json::from_value(payload["executionPayload"]["blockHash"].clone())
+                        .unwrap()
+                )
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v3_validates_withdrawals_against_block() {
+        let (reader, mut app) = create_app();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            let fc_updated_request = forkchoice_updated::tests::example_request();
+            let parent_beacon_block_root =
+                fc_updated_request["params"][1]["parentBeaconBlockRoot"].clone();
+            forkchoice_updated::execute_v3(
+                fc_updated_request,
+                queue.clone(),
+                &0x03421ee50df45cacu64,
+                &reader,
+            )
+            .await
+            .unwrap();
+            queue.wait_for_pending_commands().await;
+
+            let get_payload_request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 8,
+                "method": "engine_getPayloadV3",
+                "params": ["0x03421ee50df45cac"]
+            });
+            let payload = get_payload::execute_v3(get_payload_request, &reader)
+                .await
+                .unwrap();
+            let mut execution_payload = payload["executionPayload"].clone();
+            execution_payload["withdrawals"] = serde_json::json!([{
+                "index": "0x0",
+                "validatorIndex": "0x0",
+                "address": Address::repeat_byte(0x11),
+                "amount": "0x1"
+            }]);
+            let new_payload_request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 9,
+                "method": "engine_newPayloadV3",
+                "params": [execution_payload, [], parent_beacon_block_root]
+            });
+
+            let response: PayloadStatusV1 =
+                serde_json::from_value(execute_v3(new_payload_request, &reader).await.unwrap())
+                    .unwrap();
+
+            assert_eq!(response.status, Status::Invalid);
+            assert_eq!(
+                response.validation_error.as_deref(),
+                Some("Incorrect withdraws")
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v4_validates_withdrawals_root() {
+        let (reader, mut app) = create_app();
+        app.genesis_config.hardforks.isthmus_time = Some(0);
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            let fc_updated_request = forkchoice_updated::tests::example_request();
+            let parent_beacon_block_root =
+                fc_updated_request["params"][1]["parentBeaconBlockRoot"].clone();
+            forkchoice_updated::execute_v3(
+                fc_updated_request,
+                queue.clone(),
+                &0x03421ee50df45cacu64,
+                &reader,
+            )
+            .await
+            .unwrap();
+            queue.wait_for_pending_commands().await;
+
+            let get_payload_request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 8,
+                "method": "engine_getPayloadV4",
+                "params": ["0x03421ee50df45cac"]
+            });
+            let payload = get_payload::execute_v4(get_payload_request, &reader)
+                .await
+                .unwrap();
+            let mut execution_payload = payload["executionPayload"].clone();
+            let new_payload_request = |execution_payload: &serde_json::Value| {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 9,
+                    "method": "engine_newPayloadV4",
+                    "params": [execution_payload, [], parent_beacon_block_root, []]
+                })
+            };
+
+            let response: PayloadStatusV1 = serde_json::from_value(
+                execute_v4(new_payload_request(&execution_payload), &reader)
+                    .await
+                    .unwrap(),
+            )
+            .unwrap();
+
+            assert_eq!(response.status, Status::Valid);
+
+            execution_payload["withdrawalsRoot"] = serde_json::to_value(B256::ZERO).unwrap();
+            let response: PayloadStatusV1 = serde_json::from_value(
+                execute_v4(new_payload_request(&execution_payload), &reader)
+                    .await
+                    .unwrap(),
+            )
+            .unwrap();
+
+            assert_eq!(response.status, Status::Invalid);
+            assert_eq!(
+                response.validation_error.as_deref(),
+                Some("Incorrect withdrawals root")
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_v4_rejects_execution_requests() {
+        let (reader, _app) = create_app();
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 9,
+            "method": "engine_newPayloadV4",
+            "params": [ExecutionPayloadV4::default(), [], B256::ZERO, ["0x00"]]
+        });
+
+        let error = execute_v4(request, &reader).await.unwrap_err();
+
+        assert_eq!(error.code, -32602);
+    }
 }
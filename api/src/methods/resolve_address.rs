@@ -0,0 +1,114 @@
+use {
+    crate::{json_utils::parse_params_1, jsonrpc::JsonRpcError, schema::ResolveAddressResponse},
+    alloy::eips::BlockNumberOrTag,
+    move_core_types::account_address::AccountAddress,
+    moved_app::{ApplicationReader, Dependencies},
+    moved_shared::primitives::{Address, B256, ToEthAddress, ToMoveAddress},
+};
+
+/// Length of the hex digits of an EVM address, which tells it apart from a Move address.
+const EVM_ADDRESS_HEX_LEN: usize = 40;
+
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let address: String = parse_params_1(request)?;
+    let (evm_address, move_address) = resolve(&address)?;
+
+    let response = ResolveAddressResponse {
+        evm_address,
+        move_address: B256::new(move_address.into_bytes()),
+        evm_account_exists: evm_address
+            .is_some_and(|address| app.evm_account_exists(address, BlockNumberOrTag::Latest)),
+        move_account_exists: app.move_account_exists(move_address, BlockNumberOrTag::Latest),
+    };
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+/// Maps a hex encoded EVM or Move address to its forms in both.
+///
+/// An address with 40 hex digits is an EVM address, any other is a Move address that may omit its
+/// leading zeros, like `0x1`. Only Move addresses whose first 12 bytes are zero have an EVM form.
+fn resolve(address: &str) -> Result<(Option<Address>, AccountAddress), JsonRpcError> {
+    let invalid = || JsonRpcError::without_data(-32602, format!("Invalid address: {address}"));
+    let digits = address.strip_prefix("0x").ok_or_else(invalid)?;
+
+    if digits.len() == EVM_ADDRESS_HEX_LEN {
+        let evm_address: Address = address.parse().map_err(|_| invalid())?;
+        return Ok((Some(evm_address), evm_address.to_move_address()));
+    }
+
+    let move_address = AccountAddress::from_hex_literal(address).map_err(|_| invalid())?;
+    let evm_address = move_address.to_eth_address();
+    let is_evm_form = evm_address.to_move_address() == move_address;
+
+    Ok((is_evm_form.then_some(evm_address), move_address))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app, alloy::primitives::address};
+
+    fn example_request(address: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "umi_resolveAddress",
+            "params": [address]
+        })
+    }
+
+    #[test]
+    fn test_resolve_pads_evm_address_to_move_address() {
+        let evm_address = address!("8fd379246834eac74b8419ffda202cf8051f7a03");
+
+        let (resolved, move_address) =
+            resolve("0x8fd379246834eac74b8419ffda202cf8051f7a03").unwrap();
+
+        assert_eq!(resolved, Some(evm_address));
+        assert_eq!(move_address, evm_address.to_move_address());
+    }
+
+    #[test]
+    fn test_resolve_maps_short_move_address_to_evm_address() {
+        let (evm_address, move_address) = resolve("0x1").unwrap();
+
+        assert_eq!(
+            evm_address,
+            Some(address!("0000000000000000000000000000000000000001"))
+        );
+        assert_eq!(move_address, AccountAddress::ONE);
+    }
+
+    #[test]
+    fn test_resolve_finds_no_evm_form_of_full_move_address() {
+        let (evm_address, _) =
+            resolve("0x1a2b000000000000000000000000000000000000000000000000000000000001").unwrap();
+
+        assert_eq!(evm_address, None);
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_address() {
+        assert!(resolve("8fd379246834eac74b8419ffda202cf8051f7a03").is_err());
+        assert!(resolve("0xnothex").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_reports_no_accounts_at_unused_address() {
+        let (reader, _app) = create_app();
+
+        let response = execute(
+            example_request("0x00000000000000000000000000000000000a11ce"),
+            &reader,
+        )
+        .await
+        .unwrap();
+        let response: ResolveAddressResponse = serde_json::from_value(response).unwrap();
+
+        assert!(!response.evm_account_exists);
+        assert!(!response.move_account_exists);
+    }
+}
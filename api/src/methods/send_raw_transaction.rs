@@ -1,15 +1,23 @@
 use {
     crate::{json_utils, jsonrpc::JsonRpcError},
-    alloy::{consensus::transaction::TxEnvelope, rlp::Decodable},
-    moved_app::{Command, CommandQueue},
-    moved_shared::primitives::{B256, Bytes},
+    alloy::{
+        consensus::{Transaction, transaction::TxEnvelope},
+        rlp::Decodable,
+    },
+    moved_app::{ApplicationReader, Command, CommandQueue, Dependencies},
+    moved_shared::primitives::{Address, B256, Bytes},
 };
 
 pub async fn execute(
     request: serde_json::Value,
     queue: CommandQueue,
+    app: &ApplicationReader<impl Dependencies>,
 ) -> Result<serde_json::Value, JsonRpcError> {
     let tx = parse_params(request)?;
+    let sender = app
+        .validate_transaction(&tx)
+        .map_err(|e| json_utils::transaction_error(e, -32000))?;
+    check_replacement(&tx, sender, app)?;
     let response = inner_execute(tx, queue).await?;
     Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
 }
@@ -40,18 +48,51 @@ fn parse_params(request: serde_json::Value) -> Result<TxEnvelope, JsonRpcError>
     }
 }
 
+/// Rejects a transaction with the sender and nonce of one waiting in the mem-pool, unless it raises
+/// the fees enough to replace it.
+fn check_replacement(
+    tx: &TxEnvelope,
+    sender: Address,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<(), JsonRpcError> {
+    let price_bump_percent = app.genesis_config.tx_admission.price_bump_percent;
+
+    match app.mem_pool.by_sender_nonce(sender, tx.nonce()) {
+        Some(pooled) if !pooled.is_replaceable_by(tx, price_bump_percent) => Err(
+            JsonRpcError::without_data(-32000, "replacement transaction underpriced"),
+        ),
+        _ => Ok(()),
+    }
+}
+
 async fn inner_execute(tx: TxEnvelope, queue: CommandQueue) -> Result<B256, JsonRpcError> {
     let tx_hash = tx.tx_hash().0.into();
 
     let msg = Command::AddTransaction { tx };
-    queue.send(msg).await;
+    queue.send_or_shed(msg).await.map_err(|_| {
+        JsonRpcError::without_data(-32005, "Node is busy, try sending the transaction later")
+    })?;
 
     Ok(tx_hash)
 }
 
 #[cfg(test)]
 pub mod tests {
-    use {super::*, crate::methods::tests::create_app};
+    use {
+        super::*,
+        crate::methods::tests::{PRIVATE_KEY, create_app, fund_account},
+        alloy::{
+            consensus::{SignableTransaction, TxEip1559, TxEip4844, TxEip7702},
+            network::TxSignerSync,
+            primitives::{TxKind, U256, address},
+            rlp::Encodable,
+            signers::local::PrivateKeySigner,
+        },
+        moved_genesis::config::CHAIN_ID,
+    };
+
+    /// The sender of the transaction of [`example_request`].
+    pub const EXAMPLE_SENDER: Address = address!("88f9b82462f6c4bf4a0fb15e5c3971559a316e7f");
 
     pub fn example_request() -> serde_json::Value {
         serde_json::from_str(
@@ -70,7 +111,8 @@ pub mod tests {
 
     #[tokio::test]
     async fn test_execute() {
-        let (_reader, mut app) = create_app();
+        let (reader, mut app) = create_app();
+        fund_account(&mut app, EXAMPLE_SENDER);
         let (queue, state) = moved_app::create(&mut app, 10);
 
         moved_app::run(state, async move {
@@ -81,10 +123,165 @@ pub mod tests {
             )
             .unwrap();
 
-            let response = execute(request, queue).await.unwrap();
+            let response = execute(request, queue, &reader).await.unwrap();
 
             assert_eq!(response, expected_response);
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn test_execute_rejects_gas_limit_above_maximum() {
+        let (mut reader, mut app) = create_app();
+        reader.genesis_config.max_transaction_gas_limit = Some(30_000_000);
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            let err = execute(example_request(), queue, &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(err.code, -32000);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_fees_below_minimum() {
+        let (mut reader, mut app) = create_app();
+        reader.genesis_config.tx_admission.min_priority_fee_per_gas = 1;
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            // The example transaction pays no fees at all
+            let err = execute(example_request(), queue, &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(err.code, -32000);
+            assert!(err.message.starts_with("transaction underpriced"));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_sender_without_funds() {
+        let (reader, mut app) = create_app();
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            // The example transaction sends value from an account that was never funded
+            let err = execute(example_request(), queue, &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(err.code, -32000);
+            assert!(err.message.starts_with("insufficient funds"));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_other_chain_id() {
+        let (mut reader, mut app) = create_app();
+        reader.genesis_config.chain_id += 1;
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            let err = execute(example_request(), queue, &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(err.code, -32000);
+            assert_eq!(err.message, "Incorrect chain id");
+        })
+        .await;
+    }
+
+    fn signed_transaction(max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> TxEnvelope {
+        let signer = PrivateKeySigner::from_bytes(&PRIVATE_KEY.into()).unwrap();
+        let mut tx = TxEip1559 {
+            chain_id: CHAIN_ID,
+            nonce: 0,
+            gas_limit: 100_000,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            to: TxKind::Call(EXAMPLE_SENDER),
+            value: U256::ZERO,
+            access_list: Default::default(),
+            input: Default::default(),
+        };
+        let signature = signer.sign_transaction_sync(&mut tx).unwrap();
+
+        TxEnvelope::Eip1559(tx.into_signed(signature))
+    }
+
+    fn request_for(tx: &TxEnvelope) -> serde_json::Value {
+        let mut encoded = Vec::new();
+        tx.encode(&mut encoded);
+
+        serde_json::json!({
+            "method": "eth_sendRawTransaction",
+            "params": [Bytes::from(encoded)],
+            "id": 4,
+            "jsonrpc": "2.0"
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_underpriced_replacement() {
+        let (reader, mut app) = create_app();
+        let sender = PrivateKeySigner::from_bytes(&PRIVATE_KEY.into())
+            .unwrap()
+            .address();
+        fund_account(&mut app, sender);
+        app.add_transaction(signed_transaction(10_000_000_000, 1_000_000_000));
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            // Both fees are raised by 5%, less than the price bump of 10% by default
+            let replacement = signed_transaction(10_500_000_000, 1_050_000_000);
+
+            let err = execute(request_for(&replacement), queue, &reader)
+                .await
+                .unwrap_err();
+
+            assert_eq!(err.code, -32000);
+            assert_eq!(err.message, "replacement transaction underpriced");
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_blob_and_set_code_transactions() {
+        let (reader, mut app) = create_app();
+        let signer = PrivateKeySigner::from_bytes(&PRIVATE_KEY.into()).unwrap();
+        let mut blob_tx = TxEip4844 {
+            chain_id: CHAIN_ID,
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        let signature = signer.sign_transaction_sync(&mut blob_tx).unwrap();
+        let blob_tx = TxEnvelope::from(blob_tx.into_signed(signature));
+        let mut set_code_tx = TxEip7702 {
+            chain_id: CHAIN_ID,
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        let signature = signer.sign_transaction_sync(&mut set_code_tx).unwrap();
+        let set_code_tx = TxEnvelope::from(set_code_tx.into_signed(signature));
+        let (queue, state) = moved_app::create(&mut app, 10);
+
+        moved_app::run(state, async move {
+            for tx in [blob_tx, set_code_tx] {
+                let err = execute(request_for(&tx), queue.clone(), &reader)
+                    .await
+                    .unwrap_err();
+
+                assert_eq!(err.code, -32000);
+                assert_eq!(err.message, "transaction type not supported");
+            }
+        })
+        .await;
+    }
 }
@@ -0,0 +1,185 @@
+use {
+    crate::{
+        json_utils::{parse_params_2, transaction_error},
+        jsonrpc::JsonRpcError,
+        schema::SimulationResponse,
+        simulate,
+    },
+    alloy::{
+        consensus::transaction::TxEnvelope, eips::BlockId, rlp::Decodable,
+        rpc::types::TransactionRequest,
+    },
+    moved_app::{ApplicationReader, DependenciesThreadSafe},
+    moved_execution::transaction::NormalizedEthTransaction,
+    moved_shared::primitives::Bytes,
+    serde::Deserialize,
+};
+
+/// A transaction of `move_simulateTransaction`, either signed and RLP encoded like the transactions
+/// of `eth_sendRawTransaction`, or unsigned like the ones of `eth_call`.
+///
+/// An unsigned transaction is executed as if it was signed by its `from` address.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SimulatedTransaction {
+    Signed(Bytes),
+    Unsigned(TransactionRequest),
+}
+
+impl SimulatedTransaction {
+    fn into_request(self) -> Result<TransactionRequest, JsonRpcError> {
+        let bytes = match self {
+            Self::Signed(bytes) => bytes,
+            Self::Unsigned(request) => return Ok(request),
+        };
+        let tx = TxEnvelope::decode(&mut bytes.as_ref())
+            .map_err(|e| JsonRpcError::without_data(-32602, format!("RLP decode failed: {e:?}")))?;
+        let tx = NormalizedEthTransaction::try_from(tx).map_err(|e| transaction_error(e, 3))?;
+
+        Ok(tx.into())
+    }
+}
+
+/// Executes a transaction on the state of a block without committing it, returning the effects of
+/// the execution: the gas used, the emitted Move events with their types, the logs of EVM contracts
+/// and Move events, the resources and modules written and the status of the VM if it failed.
+///
+/// Served as `umi_simulateTransaction` too, its name before the method moved to the `move`
+/// namespace.
+///
+/// Unlike `eth_call` and `eth_estimateGas`, a transaction that fails is not an error, as the
+/// effects of a failure are part of the result.
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (transaction, block_id): (SimulatedTransaction, BlockId) = parse_params_2(request)?;
+    let transaction = transaction.into_request()?;
+    let block_number = simulate::block_number(app, block_id)?;
+
+    app.ensure_state_retained(block_number)
+        .map_err(JsonRpcError::state_unavailable)?;
+
+    let response = simulate::with_timeout(app, move |app| app.simulate(transaction, block_number))
+        .await?
+        .map(SimulationResponse::from)
+        .map_err(|e| transaction_error(e, 3))?;
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            methods::tests::{PRIVATE_KEY, create_app, deposit_eth},
+            schema::{WriteSetChangeKind, WriteSetChangeOp},
+        },
+        alloy::{
+            consensus::{SignableTransaction, TxEip1559},
+            network::TxSignerSync,
+            primitives::{TxKind, address},
+            rlp::Encodable,
+            signers::local::PrivateKeySigner,
+        },
+        moved_app::CommandActor,
+        moved_genesis::config::CHAIN_ID,
+        moved_shared::primitives::{Address, B256, ToMoveAddress, U64, U256},
+        tokio::sync::mpsc,
+    };
+
+    const SENDER: Address = address!("8fd379246834eac74b8419ffda202cf8051f7a03");
+
+    fn signed_transfer(to: Address, value: U256) -> Bytes {
+        let mut tx = TxEip1559 {
+            chain_id: CHAIN_ID,
+            nonce: 0,
+            max_fee_per_gas: 0,
+            max_priority_fee_per_gas: 0,
+            gas_limit: 1_000_000,
+            to: TxKind::Call(to),
+            value,
+            input: Default::default(),
+            access_list: Default::default(),
+        };
+        let signer = PrivateKeySigner::from_bytes(&PRIVATE_KEY.into()).unwrap();
+        let signature = signer.sign_transaction_sync(&mut tx).unwrap();
+        let tx = TxEnvelope::Eip1559(tx.into_signed(signature));
+
+        let mut encoded = Vec::new();
+        tx.encode(&mut encoded);
+        encoded.into()
+    }
+
+    #[test]
+    fn test_parse_params_of_signed_and_unsigned_transactions() {
+        let to = Address::repeat_byte(0x11);
+        let signed: SimulatedTransaction =
+            serde_json::from_value(serde_json::json!(signed_transfer(to, U256::ONE))).unwrap();
+        let unsigned: SimulatedTransaction = serde_json::from_value(serde_json::json!({
+            "from": SENDER,
+            "to": to,
+            "value": "0x1",
+        }))
+        .unwrap();
+
+        let signed = signed.into_request().unwrap();
+        let unsigned = unsigned.into_request().unwrap();
+
+        assert_eq!(signed.from, Some(SENDER));
+        assert_eq!(signed.nonce, Some(0));
+        assert_eq!(signed.to, unsigned.to);
+        assert_eq!(signed.value, unsigned.value);
+    }
+
+    #[tokio::test]
+    async fn test_execute_returns_move_effects_of_signed_transfer() {
+        let (reader, mut app) = create_app();
+        let (state_channel, rx) = mpsc::channel(10);
+        let state_actor = CommandActor::new(rx, &mut app);
+
+        moved_app::run(state_actor, async move {
+            deposit_eth(&SENDER.to_string(), &state_channel).await;
+            state_channel.reserve_many(10).await.unwrap();
+
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "move_simulateTransaction",
+                "params": [signed_transfer(Address::repeat_byte(0x11), U256::ONE), "latest"],
+                "id": 1
+            });
+
+            let response: SimulationResponse =
+                serde_json::from_value(execute(request, &reader).await.unwrap()).unwrap();
+
+            assert!(response.success);
+            assert!(response.vm_status.is_none());
+            assert!(response.gas_used > U64::ZERO);
+            // Moving the base token emits fungible asset events, which the receipt logs too
+            assert!(!response.logs.is_empty());
+            let sender = B256::new(SENDER.to_move_address().into_bytes());
+            assert!(response.changes.iter().any(|change| {
+                change.address == sender
+                    && change.kind == WriteSetChangeKind::Resource
+                    && change.op == WriteSetChangeOp::Modify
+            }));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_safe_block_before_it_is_marked() {
+        let (reader, _app) = create_app();
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "move_simulateTransaction",
+            "params": [signed_transfer(Address::repeat_byte(0x11), U256::ONE), "safe"],
+            "id": 1
+        });
+
+        let error = execute(request, &reader).await.unwrap_err();
+
+        assert_eq!(error.code, -32001);
+    }
+}
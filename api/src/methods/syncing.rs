@@ -0,0 +1,81 @@
+use {
+    crate::{json_utils::parse_params_0, jsonrpc::JsonRpcError},
+    moved_app::{ApplicationReader, CommandQueue, Dependencies},
+};
+
+/// Reports the node as not syncing, unless its command queue is saturated.
+///
+/// A saturated node is behind on the commands it was sent, much like a syncing node is behind the
+/// chain, so clients and load balancers that skip syncing nodes steer away from it until the
+/// backlog clears. The status only has the standard fields, all of which are the latest block.
+pub async fn execute(
+    request: serde_json::Value,
+    queue: &CommandQueue,
+    app: &ApplicationReader<impl Dependencies>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    parse_params_0(request)?;
+    let load = queue.load();
+
+    if !load.is_saturated() {
+        return Ok(serde_json::Value::Bool(false));
+    }
+
+    let head = format!("{:#x}", app.block_number());
+    Ok(serde_json::json!({
+        "startingBlock": head,
+        "currentBlock": head,
+        "highestBlock": head,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::methods::tests::create_app, moved_app::Command,
+        moved_shared::primitives::B256,
+    };
+
+    fn request() -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_syncing",
+            "params": [],
+            "id": 1
+        })
+    }
+
+    #[tokio::test]
+    async fn test_idle_node_is_not_syncing() {
+        let (reader, mut app) = create_app();
+        let (queue, _actor) = moved_app::create(&mut app, 10);
+
+        let actual_response = execute(request(), &queue, &reader).await.unwrap();
+
+        assert_eq!(actual_response, serde_json::Value::Bool(false));
+    }
+
+    #[tokio::test]
+    async fn test_saturated_node_is_syncing_at_its_head() {
+        let (reader, mut app) = create_app();
+        let (queue, _actor) = moved_app::create(&mut app, 10);
+        for _ in 0..9 {
+            queue
+                .send(Command::ForkchoiceUpdate {
+                    safe_block_hash: B256::ZERO,
+                    finalized_block_hash: B256::ZERO,
+                })
+                .await;
+        }
+
+        let actual_response = execute(request(), &queue, &reader).await.unwrap();
+
+        assert_eq!(
+            actual_response,
+            serde_json::json!({
+                "startingBlock": "0x0",
+                "currentBlock": "0x0",
+                "highestBlock": "0x0",
+            })
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use {
+    crate::{
+        json_utils::{self, transaction_error},
+        jsonrpc::JsonRpcError,
+        schema::TransactionTraceResponse,
+        simulate,
+    },
+    alloy::eips::BlockNumberOrTag,
+    moved_app::{ApplicationReader, DependenciesThreadSafe},
+    moved_shared::primitives::B256,
+    serde::Deserialize,
+};
+
+/// The only tracer there is, which reports the Move calls of the transaction along with the EVM
+/// calls made from them.
+const CALL_TRACER: &str = "callTracer";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceOptions {
+    tracer: Option<String>,
+}
+
+/// Executes a transaction of a block again on the state it was executed on, returning the Move
+/// functions it called and the EVM calls made from each of them.
+///
+/// Returns `null` for a transaction that is not in a block.
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let tx_hash = parse_params(request)?;
+
+    let Some(block_number) = app
+        .transaction_by_hash(tx_hash)
+        .and_then(|tx| tx.inner.block_number)
+    else {
+        return Ok(serde_json::Value::Null);
+    };
+    // The transaction executed on top of the state of the parent block
+    let parent = BlockNumberOrTag::Number(block_number.saturating_sub(1));
+    app.ensure_state_retained(parent)
+        .map_err(JsonRpcError::state_unavailable)?;
+
+    let response = simulate::with_timeout(app, move |app| {
+        app.trace_transaction(tx_hash)
+            .map(|trace| trace.map(TransactionTraceResponse::from))
+    })
+    .await?
+    .transpose()
+    .map_err(|e| transaction_error(e, 3))?;
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+fn parse_params(request: serde_json::Value) -> Result<B256, JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    let (tx_hash, options) = match params {
+        [] => {
+            return Err(JsonRpcError {
+                code: -32602,
+                data: request,
+                message: "Not enough params".into(),
+            });
+        }
+        [a] => (json_utils::deserialize(a)?, TraceOptions::default()),
+        [a, b] => (json_utils::deserialize(a)?, json_utils::deserialize(b)?),
+        _ => {
+            return Err(JsonRpcError {
+                code: -32602,
+                data: request,
+                message: "Too many params".into(),
+            });
+        }
+    };
+
+    match options.tracer.as_deref() {
+        None | Some(CALL_TRACER) => Ok(tx_hash),
+        Some(other) => Err(JsonRpcError::without_data(
+            -32602,
+            format!("Unsupported tracer: {other}, only {CALL_TRACER} is"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::methods::tests::{create_app, deposit_eth},
+        alloy::{
+            eips::BlockNumberOrTag::Latest,
+            primitives::{address, utils::parse_ether},
+        },
+        moved_app::CommandActor,
+        moved_shared::primitives::{Address, U256},
+        test_case::test_case,
+        tokio::sync::mpsc,
+    };
+
+    const RECIPIENT: Address = address!("8fd379246834eac74b8419ffda202cf8051f7a03");
+
+    fn example_request(params: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "debug_traceTransaction",
+            "params": params,
+            "id": 1
+        })
+    }
+
+    #[test_case(serde_json::json!([B256::ZERO]), true; "hash only")]
+    #[test_case(serde_json::json!([B256::ZERO, {"tracer": "callTracer"}]), true; "call tracer")]
+    #[test_case(serde_json::json!([B256::ZERO, {"tracer": "4byteTracer"}]), false; "other tracer")]
+    #[test_case(serde_json::json!([]), false; "no params")]
+    fn test_parse_params(params: serde_json::Value, is_valid: bool) {
+        let result = parse_params(example_request(params));
+
+        assert_eq!(result.is_ok(), is_valid, "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_execute_traces_evm_call_of_deposit() {
+        let (reader, mut app) = create_app();
+        let (state_channel, rx) = mpsc::channel(10);
+        let state_actor = CommandActor::new(rx, &mut app);
+
+        moved_app::run(state_actor, async move {
+            deposit_eth(&RECIPIENT.to_string(), &state_channel).await;
+            state_channel.reserve_many(10).await.unwrap();
+
+            let block = reader.block_by_height(Latest, false).unwrap().block;
+            let tx_hash = block.transactions.hashes().next().unwrap();
+            let request = example_request(serde_json::json!([tx_hash]));
+
+            let response: TransactionTraceResponse =
+                serde_json::from_value(execute(request, &reader).await.unwrap()).unwrap();
+
+            assert!(!response.failed);
+            assert_eq!(response.calls.len(), 1);
+            let deposit = &response.calls[0];
+            assert_eq!(deposit.function, "0x1::evm::system_deposit_evm_call");
+            assert!(deposit.error.is_none());
+            assert_eq!(deposit.calls.len(), 1);
+            let evm_call = &deposit.calls[0];
+            assert_eq!(evm_call.kind, "CALL");
+            assert_eq!(evm_call.to, Some(RECIPIENT));
+            assert_eq!(evm_call.value, parse_ether("1").unwrap());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_state_changes_of_deposit_credit_recipient() {
+        let (reader, mut app) = create_app();
+        let (state_channel, rx) = mpsc::channel(10);
+        let state_actor = CommandActor::new(rx, &mut app);
+
+        moved_app::run(state_actor, async move {
+            deposit_eth(&RECIPIENT.to_string(), &state_channel).await;
+            state_channel.reserve_many(10).await.unwrap();
+
+            let block = reader.block_by_height(Latest, false).unwrap().block;
+            let tx_hash = block.transactions.hashes().next().unwrap();
+
+            let (_, state_changes) = reader
+                .trace_transaction_with_state_changes(tx_hash)
+                .unwrap()
+                .unwrap();
+
+            let recipient = state_changes
+                .balances
+                .iter()
+                .find(|change| change.address == RECIPIENT)
+                .unwrap();
+            assert_eq!(recipient.old_value, U256::ZERO);
+            assert_eq!(recipient.new_value, parse_ether("1").unwrap());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_returns_null_for_unknown_transaction() {
+        let (reader, _app) = create_app();
+        let request = example_request(serde_json::json!([B256::ZERO]));
+
+        let response = execute(request, &reader).await.unwrap();
+
+        assert!(response.is_null());
+    }
+}
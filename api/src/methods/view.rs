@@ -0,0 +1,174 @@
+use {
+    crate::{
+        json_utils::{self, transaction_error},
+        jsonrpc::JsonRpcError,
+        schema::{ViewRequest, ViewValueResponse},
+        simulate,
+    },
+    alloy::eips::{BlockId, BlockNumberOrTag},
+    move_core_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        language_storage::{ModuleId, TypeTag},
+    },
+    moved_app::{ApplicationReader, DependenciesThreadSafe},
+    std::str::FromStr,
+};
+
+/// Calls a `#[view]` function on the state of a block without committing its changes, returning
+/// the values it returns both bcs encoded and rendered as JSON.
+pub async fn execute(
+    request: serde_json::Value,
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (view, block_id) = parse_params(request)?;
+    let (module, function) = parse_function(&view.function)?;
+    let ty_args = view
+        .type_arguments
+        .iter()
+        .map(|tag| {
+            TypeTag::from_str(tag).map_err(|_| {
+                JsonRpcError::without_data(-32602, format!("Invalid type argument: {tag}"))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let args = view.arguments.into_iter().map(Vec::from).collect();
+    let block_number = simulate::block_number(app, block_id)?;
+
+    app.ensure_state_retained(block_number)
+        .map_err(JsonRpcError::state_unavailable)?;
+
+    let response = simulate::with_timeout(app, move |app| {
+        app.view(module, function, ty_args, args, block_number)
+    })
+    .await?
+    .map_err(|e| transaction_error(e, 3))?
+    .into_iter()
+    .map(ViewValueResponse::from)
+    .collect::<Vec<_>>();
+
+    Ok(serde_json::to_value(response).expect("Must be able to JSON-serialize response"))
+}
+
+fn parse_params(request: serde_json::Value) -> Result<(ViewRequest, BlockId), JsonRpcError> {
+    let params = json_utils::get_params_list(&request);
+    match params {
+        [] => Err(JsonRpcError::parse_error(request, "Not enough params")),
+        [a] => Ok((
+            json_utils::deserialize(a)?,
+            BlockId::Number(BlockNumberOrTag::Latest),
+        )),
+        [a, b] => Ok((json_utils::deserialize(a)?, json_utils::deserialize(b)?)),
+        _ => Err(JsonRpcError::parse_error(request, "Too many params")),
+    }
+}
+
+/// Splits a fully qualified function name like `0x1::account::exists_at` into its module and name.
+fn parse_function(function: &str) -> Result<(ModuleId, Identifier), JsonRpcError> {
+    let invalid = || JsonRpcError::without_data(-32602, format!("Invalid function: {function}"));
+    let mut parts = function.split("::");
+    let (Some(address), Some(module), Some(name), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let address = AccountAddress::from_hex_literal(address).map_err(|_| invalid())?;
+    let module = Identifier::new(module).map_err(|_| invalid())?;
+    let name = Identifier::new(name).map_err(|_| invalid())?;
+
+    Ok((ModuleId::new(address, module), name))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app, moved_shared::primitives::Bytes};
+
+    fn example_request(function: &str, arguments: Vec<serde_json::Value>) -> serde_json::Value {
+        example_request_at(function, arguments, "latest")
+    }
+
+    fn example_request_at(
+        function: &str,
+        arguments: Vec<serde_json::Value>,
+        block: &str,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "move_view",
+            "params": [{
+                "function": function,
+                "typeArguments": [],
+                "arguments": arguments,
+            }, block]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_returns_values_of_view_function() {
+        let (reader, _app) = create_app();
+        let address = AccountAddress::new([0x11; 32]);
+        let argument = serde_json::to_value(Bytes::copy_from_slice(address.as_ref())).unwrap();
+
+        let response = execute(
+            example_request("0x1::account::exists_at", vec![argument]),
+            &reader,
+        )
+        .await
+        .unwrap();
+        let response: Vec<ViewValueResponse> = serde_json::from_value(response).unwrap();
+
+        assert_eq!(
+            response,
+            vec![ViewValueResponse {
+                type_tag: "bool".into(),
+                bcs: vec![0].into(),
+                json: Some(false.into()),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_function_that_is_not_a_view() {
+        let (reader, _app) = create_app();
+        // A vector of bytes is its length followed by the bytes
+        let argument = serde_json::to_value(Bytes::from_static(b"\x03umi")).unwrap();
+
+        let error = execute(
+            example_request("0x1::string::utf8", vec![argument]),
+            &reader,
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.code, -32000);
+        assert_eq!(error.message, "Function is not a view function");
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_invalid_function_name() {
+        let (reader, _app) = create_app();
+
+        let error = execute(example_request("0x1::account", Vec::new()), &reader)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_finalized_block_before_it_is_marked() {
+        let (reader, _app) = create_app();
+        let address = AccountAddress::new([0x11; 32]);
+        let argument = serde_json::to_value(Bytes::copy_from_slice(address.as_ref())).unwrap();
+
+        let error = execute(
+            example_request_at("0x1::account::exists_at", vec![argument], "finalized"),
+            &reader,
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.code, -32001);
+    }
+}
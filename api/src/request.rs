@@ -1,10 +1,10 @@
 use {
     crate::{
         json_utils,
-        jsonrpc::{JsonRpcError, JsonRpcResponse},
+        jsonrpc::{JsonRpcError, JsonRpcResponse, JsonRpcResult},
         method_name::MethodName,
     },
-    moved_app::{ApplicationReader, CommandQueue, Dependencies},
+    moved_app::{ApplicationReader, CommandQueue, DependenciesThreadSafe},
     moved_blockchain::payload::NewPayloadId,
 };
 
@@ -13,8 +13,8 @@ pub async fn handle(
     queue: CommandQueue,
     is_allowed: impl Fn(&MethodName) -> bool,
     payload_id: &impl NewPayloadId,
-    app: ApplicationReader<impl Dependencies>,
-) -> JsonRpcResponse {
+    app: ApplicationReader<impl DependenciesThreadSafe>,
+) -> JsonRpcResponse<JsonRpcResult> {
     let id = json_utils::get_field(&request, "id");
     let jsonrpc = json_utils::get_field(&request, "jsonrpc");
 
@@ -39,8 +39,8 @@ async fn inner_handle_request(
     queue: CommandQueue,
     is_allowed: impl Fn(&MethodName) -> bool,
     payload_id: &impl NewPayloadId,
-    app: &ApplicationReader<impl Dependencies>,
-) -> Result<serde_json::Value, JsonRpcError> {
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
+) -> Result<JsonRpcResult, JsonRpcError> {
     use {crate::methods::*, MethodName::*};
 
     let method: MethodName = json_utils::get_field(&request, "method")
@@ -52,23 +52,79 @@ async fn inner_handle_request(
         return Err(JsonRpcError::without_data(-32601, "Invalid/missing method"));
     }
 
-    match method {
-        ForkChoiceUpdatedV3 => forkchoice_updated::execute_v3(request, queue, payload_id).await,
+    let result = match method {
+        GetLogs => {
+            return get_logs::execute(request, app)
+                .await
+                .map(JsonRpcResult::Logs);
+        }
+        GetBlockByHash => {
+            return get_block_by_hash::execute(request, app)
+                .await
+                .map(JsonRpcResult::Block);
+        }
+        GetBlockByNumber => {
+            return get_block_by_number::execute(request, app)
+                .await
+                .map(JsonRpcResult::Block);
+        }
+        ForkChoiceUpdatedV2 => {
+            forkchoice_updated::execute_v2(request, queue, payload_id, app).await
+        }
+        ForkChoiceUpdatedV3 => {
+            forkchoice_updated::execute_v3(request, queue, payload_id, app).await
+        }
+        GetPayloadV2 => get_payload::execute_v2(request, app).await,
         GetPayloadV3 => get_payload::execute_v3(request, app).await,
+        GetPayloadV4 => get_payload::execute_v4(request, app).await,
+        NewPayloadV2 => new_payload::execute_v2(request, app).await,
         NewPayloadV3 => new_payload::execute_v3(request, app).await,
-        SendRawTransaction => send_raw_transaction::execute(request, queue).await,
+        NewPayloadV4 => new_payload::execute_v4(request, app).await,
+        SendRawTransaction => send_raw_transaction::execute(request, queue, app).await,
         ChainId => chain_id::execute(app).await,
         GetBalance => get_balance::execute(request, app).await,
         GetNonce => get_nonce::execute(request, app).await,
+        GetCode => get_code::execute(request, app).await,
+        GetStorageAt => get_storage_at::execute(request, app).await,
         GetTransactionByHash => get_transaction_by_hash::execute(request, app).await,
-        GetBlockByHash => get_block_by_hash::execute(request, app).await,
-        GetBlockByNumber => get_block_by_number::execute(request, app).await,
+        GetBlockTransactionCountByHash => {
+            get_block_transaction_count::execute_by_hash(request, app).await
+        }
+        GetBlockTransactionCountByNumber => {
+            get_block_transaction_count::execute_by_number(request, app).await
+        }
         BlockNumber => block_number::execute(request, app).await,
         FeeHistory => fee_history::execute(request, app).await,
         EstimateGas => estimate_gas::execute(request, app).await,
         Call => call::execute(request, app).await,
         TransactionReceipt => get_transaction_receipt::execute(request, app).await,
         GetProof => get_proof::execute(request, app).await,
-        GasPrice => gas_price::execute().await,
-    }
+        GasPrice => gas_price::execute(app).await,
+        MaxPriorityFeePerGas => max_priority_fee_per_gas::execute(app).await,
+        GetRejectedTransactions => get_rejected_transactions::execute(request, app).await,
+        GetL1Origin => get_l1_origin::execute(request, app).await,
+        GetBridgedTokens => get_bridged_tokens::execute(app).await,
+        GetTokenMapping => get_token_mapping::execute(app).await,
+        GetTrieNodes => get_trie_nodes::execute(request, app).await,
+        GetStorageAccounts => get_storage_accounts::execute(request, app).await,
+        EstimateL1Fee => estimate_l1_fee::execute(request, app).await,
+        UmiTransactionReceipt => get_umi_transaction_receipt::execute(request, app).await,
+        ResolveAddress => resolve_address::execute(request, app).await,
+        GetTransactionProof => get_transaction_proof::execute(request, app).await,
+        GetReceiptProof => get_receipt_proof::execute(request, app).await,
+        GetPayloadExpiry => get_payload_expiry::execute(app).await,
+        SimulateTransaction => simulate_transaction::execute(request, app).await,
+        Syncing => syncing::execute(request, &queue, app).await,
+        NetVersion => net::execute_version(app).await,
+        NetListening => net::execute_listening().await,
+        NetPeerCount => net::execute_peer_count(app).await,
+        ClientVersion => client_version::execute().await,
+        TraceTransaction => trace_transaction::execute(request, app).await,
+        GetModule => get_module::execute(request, app).await,
+        View => view::execute(request, app).await,
+        ListResources => list_resources::execute(request, app).await,
+        GetTableItem => get_table_item::execute(request, app).await,
+    };
+
+    result.map(JsonRpcResult::Value)
 }
@@ -0,0 +1,90 @@
+//! Types of the methods in the `debug` namespace.
+
+use {
+    moved_blockchain::receipt::VmStatus,
+    moved_evm_ext::EvmCallFrame,
+    moved_execution::{MoveCallFrame, TransactionTrace, transaction::TransactionExecutionOutcome},
+    moved_shared::primitives::{Address, Bytes, U64, U256},
+    serde::{Deserialize, Serialize},
+};
+
+/// The trace of `debug_traceTransaction`, which nests the EVM calls in the `callTracer` format of
+/// geth under the Move calls they were made from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTraceResponse {
+    pub gas_used: U64,
+    pub failed: bool,
+    /// The status the VM ended the transaction with, if it failed.
+    pub vm_status: Option<VmStatus>,
+    pub calls: Vec<MoveCallFrameResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveCallFrameResponse {
+    /// The called function as `address::module::function`, or `script` for a script.
+    pub function: String,
+    pub gas_used: U64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub calls: Vec<EvmCallFrameResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmCallFrameResponse {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub from: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    pub value: U256,
+    pub gas: U64,
+    pub gas_used: U64,
+    pub input: Bytes,
+    pub output: Bytes,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<EvmCallFrameResponse>,
+}
+
+impl From<(TransactionExecutionOutcome, TransactionTrace)> for TransactionTraceResponse {
+    fn from((outcome, trace): (TransactionExecutionOutcome, TransactionTrace)) -> Self {
+        Self {
+            gas_used: U64::from(outcome.gas_used),
+            failed: outcome.vm_outcome.is_err(),
+            vm_status: outcome.vm_outcome.as_ref().err().map(VmStatus::from),
+            calls: trace.calls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<MoveCallFrame> for MoveCallFrameResponse {
+    fn from(value: MoveCallFrame) -> Self {
+        Self {
+            function: value.call.to_string(),
+            gas_used: U64::from(value.gas_used),
+            error: value.error,
+            calls: value.evm_calls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<EvmCallFrame> for EvmCallFrameResponse {
+    fn from(value: EvmCallFrame) -> Self {
+        Self {
+            kind: value.kind.as_str().to_owned(),
+            from: value.from,
+            to: value.to,
+            value: value.value,
+            gas: U64::from(value.gas),
+            gas_used: U64::from(value.gas_used),
+            input: value.input,
+            output: value.output,
+            error: value.error,
+            calls: value.calls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
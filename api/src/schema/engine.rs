@@ -4,7 +4,7 @@
 use {
     moved_app::Payload,
     moved_blockchain::payload::{BlobsBundle, ExecutionPayload, PayloadResponse, Withdrawal},
-    moved_shared::primitives::{Address, B256, B2048, Bytes, U64, U256},
+    moved_shared::primitives::{Address, B64, B256, B2048, Bytes, U64, U256},
     serde::{Deserialize, Serialize},
     std::{
         fmt::{Debug, Display, Formatter},
@@ -21,6 +21,29 @@ pub struct WithdrawalV1 {
     pub amount: U64,
 }
 
+/// The payload of blocks from before the Ecotone upgrade, which has no blob gas fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayloadV2 {
+    pub parent_hash: B256,
+    pub fee_recipient: Address,
+    pub state_root: B256,
+    pub receipts_root: B256,
+    pub logs_bloom: B2048,
+    pub prev_randao: B256,
+    pub block_number: U64,
+    pub gas_limit: U64,
+    pub gas_used: U64,
+    pub timestamp: U64,
+    pub extra_data: Bytes,
+    pub base_fee_per_gas: U256,
+    pub block_hash: B256,
+    pub transactions: Vec<Bytes>,
+    /// Missing from the `ExecutionPayloadV1` accepted in place of this one before Shanghai.
+    #[serde(default)]
+    pub withdrawals: Vec<WithdrawalV1>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ExecutionPayloadV3 {
@@ -43,6 +66,33 @@ pub struct ExecutionPayloadV3 {
     pub excess_blob_gas: U64,
 }
 
+/// The payload of blocks since the Isthmus upgrade, which commits to the withdrawals initiated on
+/// L2 through the storage root of the L2 to L1 message passer.
+///
+/// See <https://specs.optimism.io/protocol/isthmus/exec-engine.html#engine-api-updates>.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayloadV4 {
+    pub parent_hash: B256,
+    pub fee_recipient: Address,
+    pub state_root: B256,
+    pub receipts_root: B256,
+    pub logs_bloom: B2048,
+    pub prev_randao: B256,
+    pub block_number: U64,
+    pub gas_limit: U64,
+    pub gas_used: U64,
+    pub timestamp: U64,
+    pub extra_data: Bytes,
+    pub base_fee_per_gas: U256,
+    pub block_hash: B256,
+    pub transactions: Vec<Bytes>,
+    pub withdrawals: Vec<WithdrawalV1>,
+    pub blob_gas_used: U64,
+    pub excess_blob_gas: U64,
+    pub withdrawals_root: B256,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ForkchoiceStateV1 {
@@ -51,6 +101,21 @@ pub struct ForkchoiceStateV1 {
     pub finalized_block_hash: B256,
 }
 
+/// The payload attributes of blocks from before the Ecotone upgrade, which have no parent beacon
+/// block root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadAttributesV2 {
+    pub timestamp: U64,
+    pub prev_randao: B256,
+    pub suggested_fee_recipient: Address,
+    /// Missing from the `PayloadAttributesV1` accepted in place of this one before Shanghai.
+    #[serde(default)]
+    pub withdrawals: Vec<WithdrawalV1>,
+    pub transactions: Vec<Bytes>,
+    pub gas_limit: U64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct PayloadAttributesV3 {
@@ -61,6 +126,9 @@ pub struct PayloadAttributesV3 {
     pub parent_beacon_block_root: B256,
     pub transactions: Vec<Bytes>,
     pub gas_limit: U64,
+    /// Introduced in the Holocene upgrade, absent before it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip1559_params: Option<B64>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -145,6 +213,13 @@ pub struct ForkchoiceUpdatedResponseV1 {
     pub payload_id: Option<PayloadId>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPayloadResponseV2 {
+    pub execution_payload: ExecutionPayloadV2,
+    pub block_value: U256,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetPayloadResponseV3 {
@@ -155,6 +230,17 @@ pub struct GetPayloadResponseV3 {
     pub parent_beacon_block_root: B256,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPayloadResponseV4 {
+    pub execution_payload: ExecutionPayloadV4,
+    pub block_value: U256,
+    pub blobs_bundle: BlobsBundleV1,
+    pub should_override_builder: bool,
+    pub parent_beacon_block_root: B256,
+    pub execution_requests: Vec<Bytes>,
+}
+
 impl From<GetPayloadResponseV3> for PayloadResponse {
     fn from(value: GetPayloadResponseV3) -> Self {
         Self {
@@ -162,19 +248,58 @@ impl From<GetPayloadResponseV3> for PayloadResponse {
             block_value: value.block_value,
             blobs_bundle: value.blobs_bundle.into(),
             should_override_builder: value.should_override_builder,
-            parent_beacon_block_root: value.parent_beacon_block_root,
+            parent_beacon_block_root: Some(value.parent_beacon_block_root),
+            execution_requests: None,
         }
     }
 }
 
-impl From<PayloadResponse> for GetPayloadResponseV3 {
-    fn from(value: PayloadResponse) -> Self {
-        Self {
+/// Fails with the reason if the payload was built before Ecotone, as it has no parent beacon block
+/// root then.
+impl TryFrom<PayloadResponse> for GetPayloadResponseV3 {
+    type Error = &'static str;
+
+    fn try_from(value: PayloadResponse) -> Result<Self, Self::Error> {
+        let parent_beacon_block_root = value
+            .parent_beacon_block_root
+            .ok_or("Unsupported fork, the payload was built before Ecotone")?;
+
+        Ok(Self {
             execution_payload: value.execution_payload.into(),
             block_value: value.block_value,
             blobs_bundle: value.blobs_bundle.into(),
             should_override_builder: value.should_override_builder,
-            parent_beacon_block_root: value.parent_beacon_block_root,
+            parent_beacon_block_root,
+        })
+    }
+}
+
+/// Fails with the reason if the payload was built before Isthmus, as it has no execution requests
+/// then.
+impl TryFrom<PayloadResponse> for GetPayloadResponseV4 {
+    type Error = &'static str;
+
+    fn try_from(value: PayloadResponse) -> Result<Self, Self::Error> {
+        let unsupported_fork = "Unsupported fork, the payload was built before Isthmus";
+        let execution_requests = value.execution_requests.ok_or(unsupported_fork)?;
+        let parent_beacon_block_root = value.parent_beacon_block_root.ok_or(unsupported_fork)?;
+
+        Ok(Self {
+            execution_payload: value.execution_payload.into(),
+            block_value: value.block_value,
+            blobs_bundle: value.blobs_bundle.into(),
+            should_override_builder: value.should_override_builder,
+            parent_beacon_block_root,
+            execution_requests,
+        })
+    }
+}
+
+impl From<PayloadResponse> for GetPayloadResponseV2 {
+    fn from(value: PayloadResponse) -> Self {
+        Self {
+            execution_payload: value.execution_payload.into(),
+            block_value: value.block_value,
         }
     }
 }
@@ -199,6 +324,53 @@ impl From<BlobsBundle> for BlobsBundleV1 {
     }
 }
 
+impl From<ExecutionPayloadV2> for ExecutionPayload {
+    fn from(value: ExecutionPayloadV2) -> Self {
+        Self {
+            parent_hash: value.parent_hash,
+            fee_recipient: value.fee_recipient,
+            state_root: value.state_root,
+            receipts_root: value.receipts_root,
+            logs_bloom: value.logs_bloom,
+            prev_randao: value.prev_randao,
+            block_number: value.block_number,
+            gas_limit: value.gas_limit,
+            gas_used: value.gas_used,
+            timestamp: value.timestamp,
+            extra_data: value.extra_data,
+            base_fee_per_gas: value.base_fee_per_gas,
+            block_hash: value.block_hash,
+            transactions: value.transactions,
+            withdrawals: value.withdrawals.into_iter().map(Into::into).collect(),
+            blob_gas_used: U64::ZERO,
+            excess_blob_gas: U64::ZERO,
+            withdrawals_root: None,
+        }
+    }
+}
+
+impl From<ExecutionPayload> for ExecutionPayloadV2 {
+    fn from(value: ExecutionPayload) -> Self {
+        Self {
+            parent_hash: value.parent_hash,
+            fee_recipient: value.fee_recipient,
+            state_root: value.state_root,
+            receipts_root: value.receipts_root,
+            logs_bloom: value.logs_bloom,
+            prev_randao: value.prev_randao,
+            block_number: value.block_number,
+            gas_limit: value.gas_limit,
+            gas_used: value.gas_used,
+            timestamp: value.timestamp,
+            extra_data: value.extra_data,
+            base_fee_per_gas: value.base_fee_per_gas,
+            block_hash: value.block_hash,
+            transactions: value.transactions,
+            withdrawals: value.withdrawals.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
 impl From<ExecutionPayloadV3> for ExecutionPayload {
     fn from(value: ExecutionPayloadV3) -> Self {
         Self {
@@ -219,6 +391,57 @@ impl From<ExecutionPayloadV3> for ExecutionPayload {
             withdrawals: value.withdrawals.into_iter().map(Into::into).collect(),
             blob_gas_used: value.blob_gas_used,
             excess_blob_gas: value.excess_blob_gas,
+            withdrawals_root: None,
+        }
+    }
+}
+
+impl From<ExecutionPayloadV4> for ExecutionPayload {
+    fn from(value: ExecutionPayloadV4) -> Self {
+        Self {
+            parent_hash: value.parent_hash,
+            fee_recipient: value.fee_recipient,
+            state_root: value.state_root,
+            receipts_root: value.receipts_root,
+            logs_bloom: value.logs_bloom,
+            prev_randao: value.prev_randao,
+            block_number: value.block_number,
+            gas_limit: value.gas_limit,
+            gas_used: value.gas_used,
+            timestamp: value.timestamp,
+            extra_data: value.extra_data,
+            base_fee_per_gas: value.base_fee_per_gas,
+            block_hash: value.block_hash,
+            transactions: value.transactions,
+            withdrawals: value.withdrawals.into_iter().map(Into::into).collect(),
+            blob_gas_used: value.blob_gas_used,
+            excess_blob_gas: value.excess_blob_gas,
+            withdrawals_root: Some(value.withdrawals_root),
+        }
+    }
+}
+
+impl From<ExecutionPayload> for ExecutionPayloadV4 {
+    fn from(value: ExecutionPayload) -> Self {
+        Self {
+            parent_hash: value.parent_hash,
+            fee_recipient: value.fee_recipient,
+            state_root: value.state_root,
+            receipts_root: value.receipts_root,
+            logs_bloom: value.logs_bloom,
+            prev_randao: value.prev_randao,
+            block_number: value.block_number,
+            gas_limit: value.gas_limit,
+            gas_used: value.gas_used,
+            timestamp: value.timestamp,
+            extra_data: value.extra_data,
+            base_fee_per_gas: value.base_fee_per_gas,
+            block_hash: value.block_hash,
+            transactions: value.transactions,
+            withdrawals: value.withdrawals.into_iter().map(Into::into).collect(),
+            blob_gas_used: value.blob_gas_used,
+            excess_blob_gas: value.excess_blob_gas,
+            withdrawals_root: value.withdrawals_root.unwrap_or_default(),
         }
     }
 }
@@ -276,9 +499,25 @@ impl From<PayloadAttributesV3> for Payload {
             prev_randao: value.prev_randao,
             suggested_fee_recipient: value.suggested_fee_recipient,
             withdrawals: value.withdrawals.into_iter().map(Into::into).collect(),
-            parent_beacon_block_root: value.parent_beacon_block_root,
+            parent_beacon_block_root: Some(value.parent_beacon_block_root),
+            transactions: value.transactions,
+            gas_limit: value.gas_limit,
+            eip1559_params: value.eip1559_params,
+        }
+    }
+}
+
+impl From<PayloadAttributesV2> for Payload {
+    fn from(value: PayloadAttributesV2) -> Self {
+        Self {
+            timestamp: value.timestamp,
+            prev_randao: value.prev_randao,
+            suggested_fee_recipient: value.suggested_fee_recipient,
+            withdrawals: value.withdrawals.into_iter().map(Into::into).collect(),
+            parent_beacon_block_root: None,
             transactions: value.transactions,
             gas_limit: value.gas_limit,
+            eip1559_params: None,
         }
     }
 }
@@ -1,17 +1,27 @@
 pub use alloy::eips::BlockNumberOrTag;
 
 use {
+    crate::schema::L1OriginResponse,
     moved_app::{RpcBlock, RpcTransaction},
     moved_blockchain::{block::BlockResponse, transaction::TransactionResponse},
     serde::{Deserialize, Serialize},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct GetBlockResponse(pub RpcBlock);
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockResponse {
+    #[serde(flatten)]
+    pub block: RpcBlock,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_origin: Option<L1OriginResponse>,
+}
 
 impl From<BlockResponse> for GetBlockResponse {
     fn from(value: BlockResponse) -> Self {
-        Self(value.0)
+        Self {
+            block: value.block,
+            l1_origin: value.l1_origin.map(L1OriginResponse::from),
+        }
     }
 }
 
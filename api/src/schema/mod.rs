@@ -1,4 +1,6 @@
-pub use {engine::*, eth::*};
+pub use {debug::*, engine::*, eth::*, umi::*};
 
+mod debug;
 mod engine;
 mod eth;
+mod umi;
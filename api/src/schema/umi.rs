@@ -0,0 +1,631 @@
+//! Types of the methods in the `umi` namespace, which are specific to this node.
+
+use {
+    crate::json_utils::move_value_to_json,
+    alloy::primitives::Log,
+    move_binary_format::{
+        CompiledModule,
+        file_format::{
+            Ability, AbilitySet, FieldDefinition, SignatureToken, StructFieldInformation,
+            StructHandleIndex, Visibility,
+        },
+    },
+    move_core_types::{account_address::AccountAddress, effects::Op},
+    moved_app::{
+        InclusionProof, PayloadExpiryCounts, ResourceValue, SimulatedEvent, Simulation, ViewValue,
+    },
+    moved_blockchain::{
+        block::{L1Origin, RejectedTransaction},
+        receipt::{TransactionReceipt, VmStatus},
+    },
+    moved_genesis::BridgedToken,
+    moved_shared::primitives::{Address, B256, Bytes, ToMoveAddress, U64},
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedTransactionResponse {
+    pub transaction_hash: B256,
+    pub reason: String,
+}
+
+impl From<RejectedTransaction> for RejectedTransactionResponse {
+    fn from(value: RejectedTransaction) -> Self {
+        Self {
+            transaction_hash: value.transaction_hash,
+            reason: value.reason,
+        }
+    }
+}
+
+/// How many payloads have expired since the node has started, for stalls of the op-node to show.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadExpiryResponse {
+    pub dropped_jobs: U64,
+    pub rejected_fetches: U64,
+    pub unfetched_payloads: U64,
+}
+
+impl From<PayloadExpiryCounts> for PayloadExpiryResponse {
+    fn from(value: PayloadExpiryCounts) -> Self {
+        Self {
+            dropped_jobs: U64::from(value.dropped_jobs),
+            rejected_fetches: U64::from(value.rejected_fetches),
+            unfetched_payloads: U64::from(value.unfetched_payloads),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1OriginResponse {
+    pub number: U64,
+    pub hash: B256,
+}
+
+impl From<L1Origin> for L1OriginResponse {
+    fn from(value: L1Origin) -> Self {
+        Self {
+            number: U64::from(value.number),
+            hash: value.hash,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgedTokenResponse {
+    pub l1_address: Address,
+    pub l2_address: Option<Address>,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Object holding the Move fungible asset metadata of the token, if genesis created one.
+    pub metadata_address: Option<B256>,
+}
+
+impl BridgedTokenResponse {
+    pub fn new(value: &BridgedToken, metadata_address: Option<AccountAddress>) -> Self {
+        Self {
+            l1_address: value.ethereum_address,
+            l2_address: value.l2_address,
+            name: value.name.clone(),
+            symbol: value.symbol.clone(),
+            decimals: value.decimals,
+            metadata_address: metadata_address.map(|address| B256::new(address.into_bytes())),
+        }
+    }
+}
+
+/// A token of `umi_getTokenMapping`, with the addresses EVM and Move code know it by.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenMappingResponse {
+    pub kind: TokenKind,
+    /// Address of the token to EVM code, or `None` if it is not known.
+    pub evm_address: Option<Address>,
+    /// Address of the token to Move code, or `None` if it is not known.
+    ///
+    /// For the base token, this is its fungible asset metadata object. For ERC-20 tokens, this is
+    /// the token address as taken by the `Erc20::erc20` facade module.
+    pub move_address: Option<B256>,
+    /// Address of the original token on L1, for bridged tokens.
+    pub l1_address: Option<Address>,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenKind {
+    /// The base token, a Move fungible asset that EVM code holds as the native balance.
+    Native,
+    /// An ERC-20 token bridged from L1 through the standard bridge.
+    Bridged,
+}
+
+impl From<&BridgedToken> for TokenMappingResponse {
+    fn from(value: &BridgedToken) -> Self {
+        Self {
+            kind: TokenKind::Bridged,
+            evm_address: value.l2_address,
+            move_address: value
+                .l2_address
+                .map(|address| B256::new(address.to_move_address().into_bytes())),
+            l1_address: Some(value.ethereum_address),
+            name: value.name.clone(),
+            symbol: value.symbol.clone(),
+            decimals: value.decimals,
+        }
+    }
+}
+
+/// A receipt of `eth_getTransactionReceipt`, along with the status the VM ended the transaction
+/// with if it failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReceiptResponse {
+    #[serde(flatten)]
+    pub receipt: TransactionReceipt,
+    pub vm_status: Option<VmStatus>,
+}
+
+/// The EVM and Move forms of an address of `umi_resolveAddress`, with the accounts found at it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveAddressResponse {
+    /// The EVM address, or `None` if the Move address is not one that an EVM address maps to.
+    pub evm_address: Option<Address>,
+    /// The Move address, which is the EVM address left-padded with zeros.
+    pub move_address: B256,
+    pub evm_account_exists: bool,
+    pub move_account_exists: bool,
+}
+
+/// A merkle proof of `umi_getTransactionProof` or `umi_getReceiptProof`, showing that a
+/// transaction or receipt is a leaf of the trie whose `root` is in the header of its block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionProofResponse {
+    pub block_hash: B256,
+    pub block_number: U64,
+    pub root: B256,
+    pub index: U64,
+    /// The RLP encoded `index`, which is the key of the leaf.
+    pub key: Bytes,
+    /// The encoded transaction or receipt, which is the value of the leaf.
+    pub value: Bytes,
+    /// Trie nodes on the path from the root to the leaf.
+    pub proof: Vec<Bytes>,
+}
+
+impl From<InclusionProof> for InclusionProofResponse {
+    fn from(value: InclusionProof) -> Self {
+        Self {
+            block_hash: value.block_hash,
+            block_number: U64::from(value.block_number),
+            root: value.root,
+            index: U64::from(value.index),
+            key: value.key,
+            value: value.value,
+            proof: value.proof,
+        }
+    }
+}
+
+/// The effects of a transaction simulated by `move_simulateTransaction`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationResponse {
+    pub success: bool,
+    pub gas_used: U64,
+    /// The status the VM ended the transaction with, if it failed.
+    pub vm_status: Option<VmStatus>,
+    pub events: Vec<SimulatedEventResponse>,
+    /// The logs of EVM contracts and of Move events, as the receipt of the transaction would have
+    /// them.
+    pub logs: Vec<Log>,
+    pub changes: Vec<WriteSetChangeResponse>,
+}
+
+/// A Move event of `move_simulateTransaction`, in the same form as the events of
+/// `umi_subscribeEvents`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedEventResponse {
+    #[serde(rename = "type")]
+    pub type_tag: String,
+    pub bcs: Bytes,
+    /// The event with named fields, or `None` if its type cannot be resolved.
+    pub json: Option<serde_json::Value>,
+}
+
+/// A resource or module written by a simulated transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteSetChangeResponse {
+    pub address: B256,
+    pub kind: WriteSetChangeKind,
+    /// The struct tag of the resource, or the name of the module.
+    pub name: String,
+    pub op: WriteSetChangeOp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WriteSetChangeKind {
+    Resource,
+    Module,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WriteSetChangeOp {
+    Create,
+    Modify,
+    Delete,
+}
+
+impl<T> From<&Op<T>> for WriteSetChangeOp {
+    fn from(value: &Op<T>) -> Self {
+        match value {
+            Op::New(_) => Self::Create,
+            Op::Modify(_) => Self::Modify,
+            Op::Delete => Self::Delete,
+        }
+    }
+}
+
+impl From<Simulation> for SimulationResponse {
+    fn from(value: Simulation) -> Self {
+        let events = value
+            .events
+            .into_iter()
+            .map(|SimulatedEvent { event, value }| SimulatedEventResponse {
+                type_tag: event.type_tag.to_canonical_string(),
+                bcs: event.data.into(),
+                json: value.map(|(value, layout)| move_value_to_json(value, Some(&layout))),
+            })
+            .collect();
+        let changes = value
+            .changes
+            .accounts()
+            .iter()
+            .flat_map(|(address, account)| {
+                let address = B256::new(address.into_bytes());
+                let resources =
+                    account
+                        .resources()
+                        .iter()
+                        .map(move |(tag, op)| WriteSetChangeResponse {
+                            address,
+                            kind: WriteSetChangeKind::Resource,
+                            name: tag.to_canonical_string(),
+                            op: op.into(),
+                        });
+                let modules =
+                    account
+                        .modules()
+                        .iter()
+                        .map(move |(name, op)| WriteSetChangeResponse {
+                            address,
+                            kind: WriteSetChangeKind::Module,
+                            name: name.to_string(),
+                            op: op.into(),
+                        });
+                resources.chain(modules)
+            })
+            .collect();
+
+        Self {
+            success: value.vm_outcome.is_ok(),
+            gas_used: U64::from(value.gas_used),
+            vm_status: value.vm_outcome.as_ref().err().map(VmStatus::from),
+            events,
+            logs: value.logs,
+            changes,
+        }
+    }
+}
+
+/// A call of a `#[view]` function in `move_view`, like the view requests of Aptos but with bcs
+/// encoded arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewRequest {
+    /// The fully qualified name of the function, like `0x1::account::exists_at`.
+    pub function: String,
+    #[serde(default)]
+    pub type_arguments: Vec<String>,
+    #[serde(default)]
+    pub arguments: Vec<Bytes>,
+}
+
+/// A value returned by the function of `move_view`, or the item of `move_getTableItem`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewValueResponse {
+    #[serde(rename = "type")]
+    pub type_tag: String,
+    pub bcs: Bytes,
+    /// The value with named fields, or `None` if it does not decode as its type.
+    pub json: Option<serde_json::Value>,
+}
+
+impl From<ViewValue> for ViewValueResponse {
+    fn from(value: ViewValue) -> Self {
+        Self {
+            type_tag: value.type_tag.to_canonical_string(),
+            bcs: value.bytes.into(),
+            json: value
+                .value
+                .map(|(value, layout)| move_value_to_json(value, Some(&layout))),
+        }
+    }
+}
+
+/// Which page of resources `move_listResources` returns.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResourcesOptions {
+    /// The type of the last resource of the previous page, as returned in its `nextCursor`.
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// A page of the resources stored under an account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResourcesResponse {
+    pub resources: Vec<MoveResourceResponse>,
+    /// The cursor of the next page, or `None` if this page is the last one.
+    pub next_cursor: Option<String>,
+}
+
+/// A Move resource stored under an account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveResourceResponse {
+    #[serde(rename = "type")]
+    pub type_tag: String,
+    pub bcs: Bytes,
+    /// The value with named fields, or `None` if it does not decode as its type.
+    pub json: Option<serde_json::Value>,
+}
+
+impl From<ResourceValue> for MoveResourceResponse {
+    fn from(value: ResourceValue) -> Self {
+        Self {
+            type_tag: value.tag.to_canonical_string(),
+            bcs: value.bytes.into(),
+            json: value
+                .value
+                .map(|(value, layout)| move_value_to_json(value, Some(&layout))),
+        }
+    }
+}
+
+/// A Move module of `move_getModule`, along with the interface derived from its bytecode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveModuleResponse {
+    pub bytecode: Bytes,
+    pub abi: MoveModuleAbi,
+}
+
+/// What it takes to build transactions against a Move module without its source.
+///
+/// Types are written as in Move source, with struct types qualified by their module, like
+/// `0x1::string::String`, and type parameters named by their position, like `T0`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveModuleAbi {
+    pub address: B256,
+    pub name: String,
+    pub friends: Vec<String>,
+    /// The public, friend and entry functions, which are the ones callable from outside of the
+    /// module.
+    pub exposed_functions: Vec<MoveFunctionAbi>,
+    pub structs: Vec<MoveStructAbi>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveFunctionAbi {
+    pub name: String,
+    pub visibility: MoveVisibility,
+    pub is_entry: bool,
+    /// The abilities required of each type argument.
+    pub generic_type_params: Vec<Vec<String>>,
+    pub params: Vec<String>,
+    #[serde(rename = "return")]
+    pub returns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MoveVisibility {
+    Private,
+    Public,
+    Friend,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveStructAbi {
+    pub name: String,
+    pub is_native: bool,
+    pub abilities: Vec<String>,
+    /// The abilities required of each type argument.
+    pub generic_type_params: Vec<Vec<String>>,
+    /// Empty for native structs and enums.
+    pub fields: Vec<MoveFieldAbi>,
+    /// Only present on enums.
+    pub variants: Vec<MoveVariantAbi>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveFieldAbi {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveVariantAbi {
+    pub name: String,
+    pub fields: Vec<MoveFieldAbi>,
+}
+
+impl From<Visibility> for MoveVisibility {
+    fn from(value: Visibility) -> Self {
+        match value {
+            Visibility::Private => Self::Private,
+            Visibility::Public => Self::Public,
+            Visibility::Friend => Self::Friend,
+        }
+    }
+}
+
+impl From<&CompiledModule> for MoveModuleAbi {
+    fn from(module: &CompiledModule) -> Self {
+        let id = module.self_id();
+        let friends = module
+            .friend_decls()
+            .iter()
+            .map(|handle| {
+                format!(
+                    "{}::{}",
+                    module
+                        .address_identifier_at(handle.address)
+                        .to_hex_literal(),
+                    module.identifier_at(handle.name)
+                )
+            })
+            .collect();
+        let exposed_functions = module
+            .function_defs()
+            .iter()
+            .filter(|def| def.is_entry || def.visibility != Visibility::Private)
+            .map(|def| {
+                let handle = module.function_handle_at(def.function);
+
+                MoveFunctionAbi {
+                    name: module.identifier_at(handle.name).to_string(),
+                    visibility: def.visibility.into(),
+                    is_entry: def.is_entry,
+                    generic_type_params: handle
+                        .type_parameters
+                        .iter()
+                        .map(|constraints| ability_names(*constraints))
+                        .collect(),
+                    params: type_names(module, &module.signature_at(handle.parameters).0),
+                    returns: type_names(module, &module.signature_at(handle.return_).0),
+                }
+            })
+            .collect();
+        let structs = module
+            .struct_defs()
+            .iter()
+            .map(|def| {
+                let handle = module.struct_handle_at(def.struct_handle);
+                let (fields, variants) = match &def.field_information {
+                    StructFieldInformation::Native => (Vec::new(), Vec::new()),
+                    StructFieldInformation::Declared(fields) => {
+                        (field_abis(module, fields), Vec::new())
+                    }
+                    StructFieldInformation::DeclaredVariants(variants) => {
+                        let variants = variants
+                            .iter()
+                            .map(|variant| MoveVariantAbi {
+                                name: module.identifier_at(variant.name).to_string(),
+                                fields: field_abis(module, &variant.fields),
+                            })
+                            .collect();
+                        (Vec::new(), variants)
+                    }
+                };
+
+                MoveStructAbi {
+                    name: module.identifier_at(handle.name).to_string(),
+                    is_native: matches!(def.field_information, StructFieldInformation::Native),
+                    abilities: ability_names(handle.abilities),
+                    generic_type_params: handle
+                        .type_parameters
+                        .iter()
+                        .map(|param| ability_names(param.constraints))
+                        .collect(),
+                    fields,
+                    variants,
+                }
+            })
+            .collect();
+
+        Self {
+            address: B256::new(id.address().into_bytes()),
+            name: id.name().to_string(),
+            friends,
+            exposed_functions,
+            structs,
+        }
+    }
+}
+
+fn ability_names(abilities: AbilitySet) -> Vec<String> {
+    abilities
+        .into_iter()
+        .map(|ability| {
+            match ability {
+                Ability::Copy => "copy",
+                Ability::Drop => "drop",
+                Ability::Store => "store",
+                Ability::Key => "key",
+            }
+            .to_string()
+        })
+        .collect()
+}
+
+fn field_abis(module: &CompiledModule, fields: &[FieldDefinition]) -> Vec<MoveFieldAbi> {
+    fields
+        .iter()
+        .map(|field| MoveFieldAbi {
+            name: module.identifier_at(field.name).to_string(),
+            type_name: type_name(module, &field.signature.0),
+        })
+        .collect()
+}
+
+fn type_names(module: &CompiledModule, tokens: &[SignatureToken]) -> Vec<String> {
+    tokens
+        .iter()
+        .map(|token| type_name(module, token))
+        .collect()
+}
+
+fn type_name(module: &CompiledModule, token: &SignatureToken) -> String {
+    match token {
+        SignatureToken::Bool => "bool".into(),
+        SignatureToken::U8 => "u8".into(),
+        SignatureToken::U16 => "u16".into(),
+        SignatureToken::U32 => "u32".into(),
+        SignatureToken::U64 => "u64".into(),
+        SignatureToken::U128 => "u128".into(),
+        SignatureToken::U256 => "u256".into(),
+        SignatureToken::Address => "address".into(),
+        SignatureToken::Signer => "signer".into(),
+        SignatureToken::Vector(inner) => format!("vector<{}>", type_name(module, inner)),
+        SignatureToken::Reference(inner) => format!("&{}", type_name(module, inner)),
+        SignatureToken::MutableReference(inner) => format!("&mut {}", type_name(module, inner)),
+        SignatureToken::TypeParameter(index) => format!("T{index}"),
+        SignatureToken::Struct(index) => struct_name(module, *index),
+        SignatureToken::StructInstantiation(index, type_args) => format!(
+            "{}<{}>",
+            struct_name(module, *index),
+            type_names(module, type_args).join(", ")
+        ),
+        // Function values of newer bytecode versions have no such name, and cannot be passed to
+        // entry functions anyway
+        #[allow(unreachable_patterns)]
+        other => format!("{other:?}"),
+    }
+}
+
+fn struct_name(module: &CompiledModule, index: StructHandleIndex) -> String {
+    let handle = module.struct_handle_at(index);
+    let module_handle = module.module_handle_at(handle.module);
+
+    format!(
+        "{}::{}::{}",
+        module
+            .address_identifier_at(module_handle.address)
+            .to_hex_literal(),
+        module.identifier_at(module_handle.name),
+        module.identifier_at(handle.name)
+    )
+}
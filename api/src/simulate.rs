@@ -0,0 +1,130 @@
+use {
+    crate::jsonrpc::JsonRpcError,
+    alloy::eips::{BlockId, BlockNumberOrTag},
+    moved_app::{ApplicationReader, Dependencies, DependenciesThreadSafe},
+    std::{
+        panic,
+        sync::{
+            Arc, LazyLock,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::Duration,
+    },
+    tokio::sync::Semaphore,
+};
+
+/// Most simulations that execute at once, counting those that timed out but have not completed.
+const MAX_RUNNING_SIMULATIONS: usize = 64;
+
+static RUNNING_SIMULATIONS: LazyLock<Arc<Semaphore>> =
+    LazyLock::new(|| Arc::new(Semaphore::new(MAX_RUNNING_SIMULATIONS)));
+
+/// Resolves the block `id` that a simulation executes on, looking up the number of a block hash.
+///
+/// Fails if the hash is of a block that is not canonical, as its state is not kept.
+pub fn block_number(
+    app: &ApplicationReader<impl Dependencies>,
+    id: BlockId,
+) -> Result<BlockNumberOrTag, JsonRpcError> {
+    match id {
+        BlockId::Number(number) => Ok(number),
+        BlockId::Hash(_) => app
+            .ensure_state_retained(id)
+            .map_err(JsonRpcError::state_unavailable)
+            .and_then(|()| {
+                app.height_from_block_id(id)
+                    .map(BlockNumberOrTag::Number)
+                    .ok_or_else(|| JsonRpcError::block_not_found(id))
+            }),
+    }
+}
+
+/// Runs the read-only execution `simulate` on a blocking thread, so that it does not hold up the
+/// thread serving requests.
+///
+/// Fails once the execution takes longer than the configured timeout, including the time spent
+/// waiting for one of the [`MAX_RUNNING_SIMULATIONS`] slots. An execution that has not started by
+/// then never runs. The VM cannot be interrupted, so one that has started keeps its slot until it
+/// completes, bounded by the gas cap of the simulations, and timed out executions cannot pile up.
+pub async fn with_timeout<D: DependenciesThreadSafe, T: Send + 'static>(
+    app: &ApplicationReader<D>,
+    simulate: impl FnOnce(&ApplicationReader<D>) -> T + Send + 'static,
+) -> Result<T, JsonRpcError> {
+    run_with_timeout(&RUNNING_SIMULATIONS, app, simulate).await
+}
+
+async fn run_with_timeout<D: DependenciesThreadSafe, T: Send + 'static>(
+    slots: &Arc<Semaphore>,
+    app: &ApplicationReader<D>,
+    simulate: impl FnOnce(&ApplicationReader<D>) -> T + Send + 'static,
+) -> Result<T, JsonRpcError> {
+    let timeout = Duration::from_millis(app.genesis_config.simulation.timeout_ms);
+    let app = app.clone();
+    let slots = slots.clone();
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let task_timed_out = timed_out.clone();
+    let task = async move {
+        let slot = slots
+            .acquire_owned()
+            .await
+            .expect("Simulation slots are never closed");
+
+        tokio::task::spawn_blocking(move || {
+            let outcome = (!task_timed_out.load(Ordering::Acquire)).then(|| simulate(&app));
+            drop(slot);
+            outcome
+        })
+        .await
+    };
+
+    match tokio::time::timeout(timeout, task).await {
+        Ok(Ok(Some(outcome))) => Ok(outcome),
+        Ok(Err(e)) => panic::resume_unwind(e.into_panic()),
+        Ok(Ok(None)) | Err(_) => {
+            timed_out.store(true, Ordering::Release);
+            Err(JsonRpcError::without_data(
+                -32000,
+                format!("Execution aborted (timeout = {timeout:?})"),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::methods::tests::create_app, std::sync::atomic::AtomicU32};
+
+    #[tokio::test]
+    async fn test_simulation_that_times_out_before_starting_never_runs() {
+        static RUNS: AtomicU32 = AtomicU32::new(0);
+        let (mut reader, _app) = create_app();
+        reader.genesis_config.simulation.timeout_ms = 50;
+
+        // Occupy the only slot so that the next simulation cannot start within its timeout
+        let slots = Arc::new(Semaphore::new(1));
+        let slot = slots.clone().acquire_owned().await.unwrap();
+        let result =
+            run_with_timeout(&slots, &reader, |_| RUNS.fetch_add(1, Ordering::Relaxed)).await;
+        drop(slot);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(result.unwrap_err().code, -32000);
+        assert_eq!(RUNS.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_slow_simulation_times_out() {
+        let (mut reader, _app) = create_app();
+        reader.genesis_config.simulation.timeout_ms = 10;
+        let slots = Arc::new(Semaphore::new(1));
+
+        let result = run_with_timeout(&slots, &reader, |_| {
+            std::thread::sleep(Duration::from_millis(200));
+        })
+        .await;
+
+        assert_eq!(result.unwrap_err().code, -32000);
+        // The execution keeps its slot until it completes
+        assert_eq!(slots.available_permits(), 0);
+    }
+}
@@ -1,20 +1,67 @@
 use {
     crate::{Application, Dependencies, DependenciesThreadSafe, input::Command},
     move_core_types::effects::ChangeSet,
+    move_table_extension::TableChangeSet,
     moved_blockchain::{
+        block::ExtendedBlock,
         payload::{InMemoryPayloadQueries, PayloadId},
-        state::InMemoryStateQueries,
     },
+    moved_evm_ext::state::StorageTriesChanges,
+    moved_execution::transaction::MoveEvent,
     moved_shared::primitives::B256,
-    std::{fmt::Debug, ops::DerefMut},
+    moved_state::witness::StateReads,
+    std::{fmt::Debug, ops::DerefMut, sync::Arc},
     tokio::sync::mpsc::Receiver,
 };
 
-/// A function invoked on a completion of new transaction execution batch.
-pub type OnTxBatch<S> = dyn Fn(&mut S) + Send + Sync;
+/// Receives notifications about the progress of block execution.
+///
+/// Any number of observers can be registered in [`Application::observers`]. They are notified in
+/// the order of registration. Every hook has a no-op default implementation, so an observer only
+/// needs to implement the ones it is interested in.
+pub trait BlockExecutionObserver<D: Dependencies>: Send + Sync {
+    /// Invoked on an execution of a new transaction, before its `changes` are applied to the state.
+    ///
+    /// The `changes` are shared rather than copied for each observer. An observer that keeps them
+    /// past this call makes the application copy them once to apply them.
+    fn on_tx(&self, _app: &mut Application<D>, _changes: &Arc<ChangeSet>) {}
+
+    /// Invoked on an execution of a new transaction, before its `changes` to Move tables are
+    /// applied to the state.
+    fn on_tx_tables(&self, _app: &mut Application<D>, _changes: &TableChangeSet) {}
+
+    /// Invoked on an execution of a new transaction, before its EVM storage `changes` are applied.
+    fn on_tx_evm_storage(&self, _app: &mut Application<D>, _changes: &StorageTriesChanges) {}
+
+    /// Invoked on an execution of a new transaction with the Move `events` it emitted.
+    fn on_tx_events(&self, _app: &mut Application<D>, _tx_hash: B256, _events: &[MoveEvent]) {}
+
+    /// Whether the state read by transactions is recorded for [`Self::on_state_reads`].
+    ///
+    /// Recording costs some work on every read, so it is only done if an observer asks for it.
+    fn records_state_reads(&self) -> bool {
+        false
+    }
+
+    /// Invoked once all transactions of a block are executed, with the state they have read.
+    ///
+    /// Only invoked if any registered observer [records state reads].
+    ///
+    /// [records state reads]: Self::records_state_reads
+    fn on_state_reads(&self, _app: &mut Application<D>, _reads: &StateReads) {}
+
+    /// Invoked on a completion of new transaction execution batch.
+    fn on_tx_batch(&self, _app: &mut Application<D>) {}
+
+    /// Invoked once a new `block` is committed along with its transactions and receipts.
+    fn on_block(&self, _app: &mut Application<D>, _block: &ExtendedBlock) {}
+
+    /// Invoked once a transaction sent to the node is accepted into the mem-pool.
+    fn on_tx_accepted(&self, _app: &mut Application<D>, _tx_hash: B256) {}
+}
 
-/// A function invoked on an execution of a new transaction.
-pub type OnTx<S> = dyn Fn(&mut S, ChangeSet) + Send + Sync;
+/// A collection of [`BlockExecutionObserver`]s registered on an [`Application`].
+pub type BlockExecutionObservers<D> = Vec<Box<dyn BlockExecutionObserver<D>>>;
 
 /// A function invoked on an execution of a new payload.
 pub type OnPayload<S> = dyn Fn(&mut S, PayloadId, B256) + Send + Sync;
@@ -42,35 +89,21 @@ impl<'a, D: Dependencies> CommandActor<'a, D> {
             Command::StartBlockBuild {
                 payload_attributes,
                 payload_id,
-            } => app.start_block_build(payload_attributes, payload_id),
+                requested_at,
+            } => app.start_requested_block_build(payload_attributes, payload_id, requested_at),
             Command::AddTransaction { tx } => app.add_transaction(tx),
-            Command::GenesisUpdate { block } => app.genesis_update(block),
+            Command::ForkchoiceUpdate {
+                safe_block_hash,
+                finalized_block_hash,
+            } => app.forkchoice_update(safe_block_hash, finalized_block_hash),
         }
     }
 
-    pub fn on_tx_batch_noop() -> &'static OnTxBatch<Application<D>> {
-        &|_| {}
-    }
-
-    pub fn on_tx_noop() -> &'static OnTx<Application<D>> {
-        &|_, _| {}
-    }
-
     pub fn on_payload_noop() -> &'static OnPayload<Application<D>> {
         &|_, _, _| {}
     }
 }
 
-impl<D: Dependencies<StateQueries = InMemoryStateQueries>> CommandActor<'_, D> {
-    pub fn on_tx_in_memory() -> &'static OnTx<Application<D>> {
-        &|_state, _changes| ()
-    }
-
-    pub fn on_tx_batch_in_memory() -> &'static OnTxBatch<Application<D>> {
-        &|_state| ()
-    }
-}
-
 impl<D: Dependencies<PayloadQueries = InMemoryPayloadQueries>> CommandActor<'_, D> {
     pub fn on_payload_in_memory() -> &'static OnPayload<Application<D>> {
         &|_state, _payload_id, _block_hash| ()
@@ -0,0 +1,112 @@
+use {
+    crate::{ApplicationReader, Dependencies},
+    alloy::{
+        consensus::{Transaction, TxEnvelope},
+        eips::BlockNumberOrTag::Latest,
+    },
+    moved_execution::{calldata_floor_gas, covers_intrinsic_gas},
+    moved_shared::{
+        error::{InvalidTransactionCause, Result},
+        primitives::{Address, U256},
+    },
+};
+
+impl<D: Dependencies> ApplicationReader<D> {
+    /// Checks that `tx` could be executed on top of the latest block, returning its sender.
+    ///
+    /// A transaction failing these checks would be rejected by block building anyway, so it is
+    /// refused before it enters the mem-pool, which lets its sender know why.
+    pub fn validate_transaction(&self, tx: &TxEnvelope) -> Result<Address> {
+        // Blob and set code transactions cannot be executed, so they would stay in the mem-pool
+        if matches!(tx, TxEnvelope::Eip4844(_) | TxEnvelope::Eip7702(_)) {
+            return Err(InvalidTransactionCause::UnsupportedType.into());
+        }
+
+        if tx
+            .chain_id()
+            .is_some_and(|chain_id| chain_id != self.chain_id())
+        {
+            return Err(InvalidTransactionCause::IncorrectChainId.into());
+        }
+
+        // Block building would skip a transaction over the maximum gas limit of the network
+        if let Some(max) = self
+            .genesis_config
+            .max_transaction_gas_limit
+            .filter(|max| tx.gas_limit() > *max)
+        {
+            return Err(InvalidTransactionCause::GasLimitTooHigh {
+                max,
+                given: tx.gas_limit(),
+            }
+            .into());
+        }
+
+        // Transactions that cost nothing to send would flood the block builder
+        let admission = &self.genesis_config.tx_admission;
+        if tx.max_fee_per_gas() < admission.min_fee_per_gas {
+            return Err(InvalidTransactionCause::FeeBelowMinimum {
+                min: admission.min_fee_per_gas,
+                given: tx.max_fee_per_gas(),
+            }
+            .into());
+        }
+        if tx.priority_fee_or_price() < admission.min_priority_fee_per_gas {
+            return Err(InvalidTransactionCause::PriorityFeeBelowMinimum {
+                min: admission.min_priority_fee_per_gas,
+                given: tx.priority_fee_or_price(),
+            }
+            .into());
+        }
+
+        let sender = tx
+            .recover_signer()
+            .map_err(|_| InvalidTransactionCause::InvalidSigner)?;
+        let header = self
+            .block_by_height(Latest, false)
+            .map(|block| block.block.header);
+
+        let is_isthmus = header.as_ref().is_some_and(|header| {
+            self.genesis_config
+                .hardforks
+                .is_isthmus_active(header.timestamp)
+        });
+        if !covers_intrinsic_gas(&self.genesis_config, tx.gas_limit(), tx.input())
+            || (is_isthmus && tx.gas_limit() < calldata_floor_gas(tx.input()))
+        {
+            return Err(InvalidTransactionCause::InsufficientIntrinsicGas.into());
+        }
+
+        if let Some(base_fee) = header.and_then(|header| header.base_fee_per_gas) {
+            let base_fee = u128::from(base_fee);
+            if tx.max_fee_per_gas() < base_fee {
+                return Err(InvalidTransactionCause::FeeBelowBaseFee {
+                    base_fee,
+                    given: tx.max_fee_per_gas(),
+                }
+                .into());
+            }
+        }
+
+        let nonce = self.nonce_by_height(sender, Latest).unwrap_or_default();
+        if tx.nonce() < nonce {
+            return Err(InvalidTransactionCause::IncorrectNonce {
+                expected: nonce,
+                given: tx.nonce(),
+            }
+            .into());
+        }
+
+        // The L1 data fee is left out, as it depends on the L1 attributes of the block that ends up
+        // including the transaction
+        let cost = U256::from(tx.gas_limit())
+            .saturating_mul(U256::from(tx.max_fee_per_gas()))
+            .saturating_add(tx.value());
+        let balance = self.balance_by_height(sender, Latest).unwrap_or_default();
+        if balance < cost {
+            return Err(InvalidTransactionCause::InsufficientFunds { balance, cost }.into());
+        }
+
+        Ok(sender)
+    }
+}
@@ -32,6 +32,6 @@ where
             .block_query
             .by_height(self.storage, number, false)
             .ok()??;
-        Some(block.0.header.hash)
+        Some(block.block.header.hash)
     }
 }
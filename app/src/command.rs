@@ -2,34 +2,65 @@ use {
     crate::{
         Application, Dependencies, ExecutionOutcome, Payload,
         block_hash::StorageBasedProvider,
-        input::{WithExecutionOutcome, WithPayloadAttributes},
+        input::{ToWithdrawal, WithExecutionOutcome, WithPayloadAttributes, holocene_extra_data},
+        mem_pool::PooledTransaction,
+        trie_root::OrderedTrieRoot,
     },
     alloy::{
-        consensus::{Receipt, Transaction, TxEnvelope},
-        eips::eip2718::Encodable2718,
+        consensus::{EMPTY_ROOT_HASH, Receipt, Transaction, TxEnvelope},
+        eips::{
+            eip2718::Encodable2718,
+            eip2930::AccessList,
+            eip4844::{DATA_GAS_PER_BLOB, calc_excess_blob_gas},
+            eip4895::Withdrawal,
+            eip7685::EMPTY_REQUESTS_HASH,
+        },
         primitives::{Bloom, keccak256},
-        rlp::{Decodable, Encodable},
+        rlp::{Decodable, Encodable, length_of_length},
     },
     moved_blockchain::{
-        block::{BaseGasFee, Block, BlockHash, BlockRepository, ExtendedBlock, Header},
-        payload::{PayloadId, PayloadQueries},
-        receipt::{ExtendedReceipt, ReceiptRepository},
-        transaction::{ExtendedTransaction, TransactionRepository},
+        block::{
+            BaseGasFee, Block, BlockHash, BlockRepository, ExtendedBlock, Header, L1Origin,
+            RejectedTransaction,
+        },
+        payload::{PayloadId, PayloadJobRepository, PayloadQueries},
+        receipt::{ExtendedReceipt, ReceiptQueries, ReceiptRepository, VmStatus},
+        transaction::{ExtendedTransaction, TransactionJournal, TransactionRepository},
     },
-    moved_evm_ext::{HeaderForExecution, state::StorageTrieRepository},
+    moved_evm_ext::{HeaderForExecution, ResolverBackedDB, state::StorageTrieRepository},
     moved_execution::{
-        CanonicalExecutionInput, CreateL1GasFee, CreateL2GasFee, DepositExecutionInput, L1GasFee,
-        L1GasFeeInput, L2GasFeeInput, LogsBloom, execute_transaction,
+        CanonicalExecutionInput, CreateL1GasFee, CreateL2GasFee, DepositExecutionInput,
+        EventLayouts, L1GasFee, L1GasFeeInput, L2GasFeeInput, LogsBloom, WithdrawalExecutionInput,
+        execute_transaction, execute_withdrawals, interop,
         transaction::{NormalizedExtendedTxEnvelope, WrapReceipt},
     },
+    moved_genesis::L2_TO_L1_MESSAGE_PASSER,
     moved_shared::{
-        error::Error::{InvalidTransaction, InvariantViolation, User},
+        error::{
+            Error::{InvalidTransaction, InvariantViolation, User},
+            InvalidTransactionCause,
+        },
         primitives::{B256, ToEthAddress, U64, U256},
     },
-    moved_state::State,
+    moved_state::{
+        State,
+        witness::{ReadRecorder, RecordingResolver, RecordingStorageTrieRepository},
+    },
     op_alloy::consensus::OpTxEnvelope,
+    std::{
+        collections::HashSet,
+        sync::Arc,
+        time::{Duration, Instant},
+    },
 };
 
+/// Percentage of the time between two blocks that pool transactions are executed for, leaving
+/// the rest for sealing the block before the op-node asks for it.
+const BUILD_TIME_PERCENT: u32 = 50;
+/// Longest time between two blocks taken into account, as the parent of the first block after a
+/// restart can be arbitrarily old.
+pub(crate) const MAX_BLOCK_TIME_SECS: u64 = 12;
+
 impl<D: Dependencies> Application<D> {
     pub fn start_block_build(&mut self, attributes: Payload, id: PayloadId) {
         if self
@@ -41,25 +72,52 @@ impl<D: Dependencies> Application<D> {
             return;
         }
 
+        self.payload_job_repository.add(id, &attributes).unwrap();
+
+        let mut rejected_transactions = Vec::new();
+        // Do not include transactions we have already processed before
+        let is_new = |tx_hash: &B256| {
+            !self
+                .receipt_repository
+                .contains(&self.receipt_memory, *tx_hash)
+                .unwrap()
+        };
+
         // Include transactions from both `payload_attributes` and internal mem-pool
-        let transactions_with_metadata = attributes
+        let decoded_transactions = attributes
             .transactions
             .iter()
             .filter_map(|tx_bytes| {
                 let mut slice: &[u8] = tx_bytes.as_ref();
                 let tx_hash = B256::new(keccak256(slice).0);
                 let tx = OpTxEnvelope::decode(&mut slice)
-                    .inspect_err(|_| {
-                        println!("WARN: Failed to RLP decode transaction in payload_attributes")
+                    .inspect_err(|e| {
+                        println!("WARN: Failed to RLP decode transaction in payload_attributes");
+                        rejected_transactions.push(RejectedTransaction::new(tx_hash, e));
                     })
                     .ok()?;
 
-                Some((tx_hash, (tx, L1GasFeeInput::from(slice))))
+                Some((tx_hash, tx, L1GasFeeInput::from(slice)))
+            })
+            .filter(|(tx_hash, ..)| is_new(tx_hash))
+            .collect::<Vec<_>>();
+        // Transactions from the mem-pool had their senders recovered when they were added to it,
+        // the ones from the payload attributes are recovered here for the whole block at once
+        let recovered_transactions = self
+            .workers
+            .recover_senders(&decoded_transactions, |(_, tx, l1_fee_input)| {
+                PooledTransaction::new(tx.clone(), l1_fee_input.clone())
+            });
+        let mut transactions_with_metadata = decoded_transactions
+            .into_iter()
+            .zip(recovered_transactions)
+            .filter_map(|((tx_hash, ..), tx)| match tx {
+                Ok(tx) => Some((tx_hash, tx)),
+                Err(e) => {
+                    rejected_transactions.push(RejectedTransaction::new(tx_hash, e));
+                    None
+                }
             })
-            .chain(self.mem_pool.drain())
-            .filter(|(tx_hash, _)|
-                // Do not include transactions we have already processed before
-                !self.receipt_repository.contains(&self.receipt_memory, *tx_hash).unwrap())
             .collect::<Vec<_>>();
         let parent = self
             .block_repository
@@ -71,48 +129,122 @@ impl<D: Dependencies> Application<D> {
             parent.block.header.gas_used,
             U256::from(parent.block.header.base_fee_per_gas.unwrap_or_default()),
         );
+        // The transactions of the payload attributes are always executed, while the ones from the
+        // mem-pool only until the deadline, so the ones paying the most are tried first
+        let forced_transactions = transactions_with_metadata.len();
+        let pooled_transactions = self.mem_pool.drain_by_priority(base_fee.saturating_to());
+        let pooled_hashes = pooled_transactions
+            .iter()
+            .map(|(tx_hash, _)| *tx_hash)
+            .collect::<Vec<_>>();
+        transactions_with_metadata.extend(
+            pooled_transactions
+                .into_iter()
+                .filter(|(tx_hash, _)| is_new(tx_hash)),
+        );
 
         let header_for_execution = HeaderForExecution {
             number: parent.block.header.number + 1,
             timestamp: attributes.timestamp.as_limbs()[0],
             prev_randao: attributes.prev_randao,
         };
+        let deadline = Instant::now()
+            + build_time(
+                header_for_execution.timestamp,
+                parent.block.header.timestamp,
+            );
         let transactions: Vec<_> = transactions_with_metadata
             .iter()
-            .map(|(_, (tx, _))| tx.clone())
+            .map(|(tx_hash, pooled)| (*tx_hash, pooled.tx.clone()))
             .collect();
-        let (execution_outcome, receipts) = self.execute_transactions(
-            transactions_with_metadata
-                .into_iter()
-                .map(|(tx_hash, (tx, bytes))| (tx_hash, tx, bytes)),
+        // https://github.com/ethereum-optimism/specs/blob/9dbc6b0/specs/protocol/deposits.md#kinds-of-deposited-transactions
+        let l1_origin = transactions
+            .first()
+            .and_then(|(_, tx)| tx.as_deposit())
+            .and_then(|tx| L1Origin::from_l1_attributes(tx.input.as_ref()));
+        let withdrawals: Vec<_> = attributes
+            .withdrawals
+            .iter()
+            .map(ToWithdrawal::to_withdrawal)
+            .collect();
+        // Since Canyon the op-node always sends an empty list of withdrawals and expects the root
+        // of an empty trie. Either way, the root is built from the EIP-4895 encoding of each item.
+        let withdrawals_root = alloy_trie::root::ordered_trie_root(&withdrawals);
+        let is_isthmus = self
+            .genesis_config
+            .hardforks
+            .is_isthmus_active(header_for_execution.timestamp);
+        let (execution_outcome, receipts, rejected_during_execution) = self.execute_transactions(
+            transactions_with_metadata.into_iter(),
+            &withdrawals,
+            &withdrawals_root,
             base_fee,
             &header_for_execution,
+            forced_transactions,
+            deadline,
         );
+        // Transactions rejected during execution or left in the mem-pool past the deadline are
+        // left out of the block, so that they are neither found by their hash nor have a receipt
+        let executed: HashSet<_> = receipts.iter().map(|rx| rx.transaction_hash).collect();
+        let transactions: Vec<_> = transactions
+            .into_iter()
+            .filter(|(tx_hash, _)| executed.contains(tx_hash))
+            .map(|(_, tx)| tx)
+            .collect();
+        rejected_transactions.extend(rejected_during_execution);
+        // Since Isthmus the header commits to the withdrawals initiated on L2 instead
+        let header_withdrawals_root = if is_isthmus {
+            self.message_passer_storage_root()
+        } else {
+            withdrawals_root
+        };
 
-        let transactions_root = alloy_trie::root::ordered_trie_root(&transactions);
-        // TODO: is this the correct withdrawals root calculation?
-        let withdrawals_root = alloy_trie::root::ordered_trie_root(&attributes.withdrawals);
         let total_tip = execution_outcome.total_tip;
+        // The OP stack has no blob transactions, so this always ends up as zero. It is still
+        // derived the same way as in op-geth to keep the block hashes in agreement.
+        let blob_gas_used = transactions
+            .iter()
+            .filter_map(Transaction::blob_versioned_hashes)
+            .map(|hashes| hashes.len() as u64 * DATA_GAS_PER_BLOB)
+            .sum();
+        // A parent from before Dencun has no blob fields, which counts as zero for both
+        let excess_blob_gas = calc_excess_blob_gas(
+            parent.block.header.excess_blob_gas.unwrap_or_default(),
+            parent.block.header.blob_gas_used.unwrap_or_default(),
+        );
+
+        // Blocks from before Ecotone, which brought Dencun to the OP stack, have no blob fields
+        let is_ecotone = attributes.parent_beacon_block_root.is_some();
+        let extra_data = attributes
+            .eip1559_params
+            .map(|params| holocene_extra_data(params, self.gas_fee.eip1559_params()))
+            .unwrap_or_default();
 
         let header = Header {
             parent_hash: parent.hash,
             number: header_for_execution.number,
-            transactions_root,
-            withdrawals_root: Some(withdrawals_root),
+            extra_data,
+            withdrawals_root: Some(header_withdrawals_root),
             base_fee_per_gas: Some(base_fee.saturating_to()),
-            blob_gas_used: Some(0),
-            excess_blob_gas: Some(0),
+            blob_gas_used: is_ecotone.then_some(blob_gas_used),
+            excess_blob_gas: is_ecotone.then_some(excess_blob_gas),
+            // The OP stack has no execution layer requests, so Isthmus always commits to none
+            requests_hash: is_isthmus.then_some(EMPTY_REQUESTS_HASH),
             ..Default::default()
         }
         .with_payload_attributes(attributes)
         .with_execution_outcome(execution_outcome);
 
         let block_hash = self.block_hash.block_hash(&header);
+        let size = block_size(&header, &transactions, &withdrawals);
 
         let block = Block::new(header, transactions.iter().map(|v| v.trie_hash()).collect())
             .with_hash(block_hash)
             .with_value(total_tip)
-            .with_payload_id(id);
+            .with_payload_id(id)
+            .with_size(size)
+            .with_rejected_transactions(rejected_transactions)
+            .with_l1_origin(l1_origin);
 
         let block_number = block.block.header.number;
         let base_fee = block.block.header.base_fee_per_gas;
@@ -144,71 +276,306 @@ impl<D: Dependencies> Application<D> {
             )
             .unwrap();
 
-        self.block_repository.add(&mut self.storage, block).unwrap();
+        self.block_repository
+            .add(&mut self.storage, block.clone())
+            .unwrap();
+        self.mem_pool.settle();
+        for tx_hash in self
+            .mem_pool
+            .evict_queued(&self.genesis_config.tx_admission, Instant::now())
+        {
+            println!("WARN: transaction {tx_hash} queued behind a nonce gap is evicted");
+        }
+        self.on_block(&block);
+
+        // Only the transactions deferred past the deadline or queued behind a nonce gap within the
+        // limits are back in the mem-pool, the rest are either in the block, rejected or evicted and
+        // do not need to survive a restart anymore
+        let settled = pooled_hashes
+            .into_iter()
+            .filter(|tx_hash| self.mem_pool.get(tx_hash).is_none());
+        self.transaction_journal.remove(settled).unwrap();
 
         (self.on_payload)(self, id, block_hash);
+        self.track_payload(id, &block, parent.block.header.timestamp);
+        self.remove_expired_payloads();
+
+        self.payload_job_repository.remove(id).unwrap();
+    }
+
+    /// Builds the payload requested at `requested_at`, unless the job has waited behind other
+    /// commands for longer than the payload would live.
+    pub fn start_requested_block_build(
+        &mut self,
+        attributes: Payload,
+        id: PayloadId,
+        requested_at: Instant,
+    ) {
+        if self.has_job_waited_too_long(attributes.timestamp.as_limbs()[0], requested_at) {
+            self.payload_expiry.record_dropped_job(id);
+        } else {
+            self.start_block_build(attributes, id);
+        }
+    }
+
+    /// Builds the payloads that were requested, but not finished before the node has stopped.
+    ///
+    /// Each payload keeps its original ID, so it can be fetched with the ID handed out before. Jobs
+    /// that have expired while the node was down are dropped instead, as the op-node has moved on.
+    ///
+    /// The payloads built before the node has stopped are tracked again, so that they are removed
+    /// from storage once they expire.
+    pub fn resume_payload_jobs(&mut self) {
+        self.track_recent_payloads();
+        self.remove_expired_payloads();
+        for (id, attributes) in self.payload_job_repository.pending().unwrap() {
+            if self.is_job_expired(attributes.timestamp.as_limbs()[0]) {
+                self.payload_expiry.record_dropped_job(id);
+            } else {
+                self.start_block_build(attributes, id);
+            }
+            self.payload_job_repository.remove(id).unwrap();
+        }
     }
 
     pub fn add_transaction(&mut self, tx: TxEnvelope) {
         let tx_hash = tx.tx_hash().0.into();
-        let mut encoded = Vec::new();
-        tx.encode(&mut encoded);
-        let encoded = encoded.as_slice().into();
-        self.mem_pool.insert(
-            tx_hash,
-            (
-                OpTxEnvelope::try_from_eth_envelope(tx)
-                    .unwrap_or_else(|_| unreachable!("EIP-4844 not supported")),
-                encoded,
-            ),
-        );
+        match PooledTransaction::from_eth_envelope(tx.clone()) {
+            Ok(pooled) => match self
+                .mem_pool
+                .insert(tx_hash, pooled, self.price_bump_percent())
+            {
+                Ok(replaced) => {
+                    if let Some(replaced) = replaced {
+                        self.transaction_journal.remove([replaced]).unwrap();
+                    }
+                    self.transaction_journal.add(tx_hash, &tx).unwrap();
+                    self.on_tx_accepted(tx_hash);
+                }
+                Err(e) => {
+                    println!("WARN: transaction {tx_hash} is left out of the mem-pool: {e:?}")
+                }
+            },
+            Err(e) => println!("WARN: transaction {tx_hash} is left out of the mem-pool: {e:?}"),
+        }
+    }
+
+    fn price_bump_percent(&self) -> u64 {
+        self.genesis_config.tx_admission.price_bump_percent
+    }
+
+    /// Puts a transaction taken out of the mem-pool back into it, to be included in a later block.
+    fn requeue(&mut self, tx_hash: B256, pooled: PooledTransaction) {
+        if let Err(e) = self
+            .mem_pool
+            .insert(tx_hash, pooled, self.price_bump_percent())
+        {
+            println!("WARN: transaction {tx_hash} is dropped from the mem-pool: {e:?}");
+            self.transaction_journal.remove([tx_hash]).unwrap();
+        }
+    }
+
+    /// Puts the transactions that were accepted, but not included before the node has stopped
+    /// back into the mem-pool.
+    pub fn restore_mem_pool(&mut self) {
+        for (tx_hash, tx) in self.transaction_journal.pending().unwrap() {
+            let dropped = match PooledTransaction::from_eth_envelope(tx) {
+                Ok(pooled) => self
+                    .mem_pool
+                    .insert(tx_hash, pooled, self.price_bump_percent())
+                    .err()
+                    .map(|e| format!("{e:?}")),
+                Err(e) => Some(format!("{e:?}")),
+            };
+            if let Some(e) = dropped {
+                println!("WARN: transaction {tx_hash} is dropped from the journal: {e}");
+                self.transaction_journal.remove([tx_hash]).unwrap();
+            }
+        }
     }
 
     pub fn genesis_update(&mut self, block: ExtendedBlock) {
         self.block_repository.add(&mut self.storage, block).unwrap();
     }
 
-    fn execute_transactions(
+    /// Moves the safe and finalized markers to the blocks identified by the given hashes.
+    ///
+    /// Hashes of unknown blocks are ignored. This is the case for the zero hash, which the
+    /// consensus layer sends until it has any safe or finalized block.
+    pub fn forkchoice_update(&mut self, safe_block_hash: B256, finalized_block_hash: B256) {
+        if let Some(block) = self
+            .block_repository
+            .by_hash(&self.storage, safe_block_hash)
+            .unwrap()
+        {
+            self.block_repository
+                .mark_safe(&mut self.storage, block.block.header.number)
+                .unwrap();
+        }
+
+        if let Some(block) = self
+            .block_repository
+            .by_hash(&self.storage, finalized_block_hash)
+            .unwrap()
+        {
+            self.block_repository
+                .mark_finalized(&mut self.storage, block.block.header.number)
+                .unwrap();
+        }
+    }
+
+    /// Checks the executing messages declared in the `access_list` of a transaction from the
+    /// mem-pool, once interop is active, against the logs of the blocks of this chain.
+    fn validate_executing_messages(
+        &self,
+        access_list: &AccessList,
+        block_header: &HeaderForExecution,
+    ) -> Result<(), InvalidTransactionCause> {
+        let Some(interop_time) = self
+            .genesis_config
+            .hardforks
+            .interop_time
+            .filter(|time| *time <= block_header.timestamp)
+        else {
+            return Ok(());
+        };
+        if !interop::has_executing_messages(access_list) {
+            return Ok(());
+        }
+
+        interop::validate_executing_messages(
+            access_list,
+            self.genesis_config.chain_id,
+            block_header.timestamp,
+            interop_time,
+            |height| {
+                self.receipt_queries
+                    .by_block_height(&self.receipt_memory_reader, height)
+                    .unwrap()
+                    .into_iter()
+                    .flat_map(|receipt| receipt.inner.inner.logs().to_vec())
+                    .collect()
+            },
+        )
+    }
+
+    /// Reads the storage root of the L2 to L1 message passer, which holds the withdrawals
+    /// initiated on L2, from the current state.
+    fn message_passer_storage_root(&self) -> B256 {
+        ResolverBackedDB::new(&self.evm_storage, self.state.resolver(), &(), 0)
+            .get_account(&L2_TO_L1_MESSAGE_PASSER)
+            .unwrap_or_else(|e| panic!("ERROR: failed to read the L2 to L1 message passer {e:?}"))
+            .map_or(EMPTY_ROOT_HASH, |account| account.inner.storage_root)
+    }
+
+    /// Executes `transactions` in order, the first `forced_transactions` of them unconditionally.
+    ///
+    /// The rest are only executed until the `deadline`, any left after it are put back into the
+    /// mem-pool for a later block.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn execute_transactions(
         &mut self,
-        transactions: impl Iterator<Item = (B256, OpTxEnvelope, L1GasFeeInput)>,
+        transactions: impl Iterator<Item = (B256, PooledTransaction)>,
+        withdrawals: &[Withdrawal],
+        withdrawals_root: &B256,
         base_fee: U256,
         block_header: &HeaderForExecution,
-    ) -> (ExecutionOutcome, Vec<ExtendedReceipt>) {
+        forced_transactions: usize,
+        deadline: Instant,
+    ) -> (
+        ExecutionOutcome,
+        Vec<ExtendedReceipt>,
+        Vec<RejectedTransaction>,
+    ) {
         let mut total_tip = U256::ZERO;
         let mut receipts = Vec::new();
-        let mut transactions = transactions.peekable();
+        let mut rejected_transactions = Vec::new();
+        let transactions: Vec<_> = transactions.collect();
         let mut cumulative_gas_used = 0u128;
         let mut logs_bloom = Bloom::ZERO;
+        // Both roots are built as each transaction finishes, rather than in passes over the whole
+        // block once it is executed
+        let mut transactions_root = OrderedTrieRoot::default();
+        let mut receipts_root = OrderedTrieRoot::default();
         let mut tx_index = 0;
         let mut log_offset = 0;
+        let recorder = self.records_state_reads().then(ReadRecorder::default);
 
         // https://github.com/ethereum-optimism/specs/blob/9dbc6b0/specs/protocol/deposits.md#kinds-of-deposited-transactions
         let l1_fee = transactions
-            .peek()
-            .and_then(|(_, v, _)| v.as_deposit())
+            .first()
+            .and_then(|(_, v)| v.tx.as_deposit())
             .map(|tx| self.l1_fee.for_deposit(tx.input.as_ref()));
         let l2_fee = self.l2_fee.with_default_gas_fee_multiplier();
+        let event_layouts = EventLayouts::new(
+            self.genesis_config
+                .hardforks
+                .is_isthmus_active(block_header.timestamp),
+        );
+
+        let mut deferred = 0;
 
         // TODO: parallel transaction processing?
-        for (tx_hash, tx, l1_cost_input) in transactions {
-            let Ok(normalized_tx): Result<NormalizedExtendedTxEnvelope, _> = tx.clone().try_into()
-            else {
+        for (index, (tx_hash, pooled)) in transactions.into_iter().enumerate() {
+            if index >= forced_transactions && Instant::now() >= deadline {
+                self.requeue(tx_hash, pooled);
+                deferred += 1;
                 continue;
-            };
-            // TODO: implement gas limits etc. for `ExtendedTxEnvelope` so that
-            // l2 gas inputs can be constructed at an earlier stage and stored in mempool
+            }
+
+            let PooledTransaction {
+                tx,
+                normalized: normalized_tx,
+                l1_fee_input: l1_cost_input,
+            } = pooled;
+            // Executing messages are not checked by execution, which replays the blocks derived by
+            // the op-node as is, so only the ones included by this node are checked here
+            if let NormalizedExtendedTxEnvelope::Canonical(canonical) = &normalized_tx {
+                // A transaction that cannot cover the base fee waits in the mem-pool for it to
+                // drop, while the op-node is told about a forced one it cannot include
+                if canonical.max_fee_per_gas < base_fee {
+                    if index >= forced_transactions {
+                        let pooled = PooledTransaction {
+                            tx,
+                            normalized: normalized_tx,
+                            l1_fee_input: l1_cost_input,
+                        };
+                        self.requeue(tx_hash, pooled);
+                    } else {
+                        let cause = InvalidTransactionCause::FeeBelowBaseFee {
+                            base_fee: base_fee.saturating_to(),
+                            given: canonical.max_fee_per_gas.saturating_to(),
+                        };
+                        rejected_transactions.push(RejectedTransaction::new(tx_hash, cause));
+                    }
+                    continue;
+                }
+                if index >= forced_transactions {
+                    let result =
+                        self.validate_executing_messages(&canonical.access_list, block_header);
+                    if let Err(cause) = result {
+                        rejected_transactions.push(RejectedTransaction::new(tx_hash, cause));
+                        continue;
+                    }
+                }
+            }
+            // The effective gas price depends on the base fee of the block, so unlike the L1 fee
+            // input this cannot be derived when the transaction enters the mem-pool
             let l2_gas_input = L2GasFeeInput::new(
                 normalized_tx.gas_limit(),
                 normalized_tx.effective_gas_price(base_fee),
             );
             let block_hash_lookup =
                 StorageBasedProvider::new(&self.storage_reader, &self.block_queries);
+            let state = RecordingResolver::new(self.state.resolver(), recorder.as_ref());
+            let evm_storage =
+                RecordingStorageTrieRepository::new(&self.evm_storage, recorder.as_ref());
             let input = match &normalized_tx {
                 NormalizedExtendedTxEnvelope::Canonical(tx) => CanonicalExecutionInput {
                     tx,
                     tx_hash: &tx_hash,
-                    state: self.state.resolver(),
-                    storage_trie: &self.evm_storage,
+                    state: &state,
+                    storage_trie: &evm_storage,
                     genesis_config: &self.genesis_config,
                     l1_cost: l1_fee
                         .as_ref()
@@ -219,32 +586,62 @@ impl<D: Dependencies> Application<D> {
                     base_token: &self.base_token,
                     block_header: block_header.clone(),
                     block_hash_lookup: &block_hash_lookup,
+                    event_layouts: &event_layouts,
                 }
                 .into(),
                 NormalizedExtendedTxEnvelope::DepositedTx(tx) => DepositExecutionInput {
                     tx,
                     tx_hash: &tx_hash,
-                    state: self.state.resolver(),
-                    storage_trie: &self.evm_storage,
+                    state: &state,
+                    storage_trie: &evm_storage,
                     genesis_config: &self.genesis_config,
                     block_header: block_header.clone(),
                     block_hash_lookup: &block_hash_lookup,
+                    event_layouts: &event_layouts,
                 }
                 .into(),
             };
             let outcome = match execute_transaction(input) {
                 Ok(outcome) => outcome,
                 Err(User(e)) => unreachable!("User errors are handled in execution {e:?}"),
-                Err(InvalidTransaction(_)) => continue,
+                // A transaction from the mem-pool that is ahead of the nonce of its sender stays
+                // queued there, to be included once the transactions filling the gap are
+                Err(InvalidTransaction(InvalidTransactionCause::IncorrectNonce {
+                    expected,
+                    given,
+                })) if given > expected && index >= forced_transactions => {
+                    let pooled = PooledTransaction {
+                        tx,
+                        normalized: normalized_tx,
+                        l1_fee_input: l1_cost_input,
+                    };
+                    self.requeue(tx_hash, pooled);
+                    self.mem_pool.mark_queued(tx_hash, Instant::now());
+                    continue;
+                }
+                Err(InvalidTransaction(cause)) => {
+                    rejected_transactions.push(RejectedTransaction::new(tx_hash, cause));
+                    continue;
+                }
                 Err(InvariantViolation(e)) => panic!("ERROR: execution error {e:?}"),
             };
 
-            let l1_block_info = l1_fee.as_ref().and_then(|x| x.l1_block_info(l1_cost_input));
+            // Deposits do not pay for their L1 data, so their receipts have no L1 fee fields
+            let l1_block_info = match &normalized_tx {
+                NormalizedExtendedTxEnvelope::Canonical(_) => {
+                    l1_fee.as_ref().and_then(|x| x.l1_block_info(l1_cost_input))
+                }
+                NormalizedExtendedTxEnvelope::DepositedTx(_) => None,
+            };
 
-            self.on_tx(outcome.changes.move_vm.clone());
+            let changes = Arc::new(outcome.changes.move_vm);
+            self.on_tx(&changes);
+            self.on_tx_tables(&outcome.changes.tables);
+            self.on_tx_evm_storage(&outcome.changes.evm);
+            self.on_tx_events(tx_hash, &outcome.events);
 
             self.state
-                .apply(outcome.changes.move_vm)
+                .apply_with_tables(Arc::unwrap_or_clone(changes), outcome.changes.tables)
                 .unwrap_or_else(|e| {
                     panic!("ERROR: state update failed for transaction {tx:?}\n{e:?}")
                 });
@@ -261,6 +658,7 @@ impl<D: Dependencies> Application<D> {
 
             let tx_log_offset = log_offset;
             log_offset += outcome.logs.len() as u64;
+            let vm_status = outcome.vm_outcome.as_ref().err().map(VmStatus::from);
             let receipt = Receipt {
                 status: outcome.vm_outcome.is_ok().into(),
                 cumulative_gas_used: if cumulative_gas_used < u64::MAX as u128 {
@@ -272,6 +670,8 @@ impl<D: Dependencies> Application<D> {
             };
 
             let receipt = tx.wrap_receipt(receipt, bloom);
+            receipts_root.push(&receipt);
+            transactions_root.push(&tx);
 
             total_tip = total_tip.saturating_add(
                 U256::from(outcome.gas_used).saturating_mul(normalized_tx.tip_per_gas(base_fee)),
@@ -298,28 +698,106 @@ impl<D: Dependencies> Application<D> {
                 block_hash: Default::default(),
                 block_number: block_header.number,
                 block_timestamp: block_header.timestamp,
+                vm_status,
             });
 
             tx_index += 1;
         }
 
-        (self.on_tx_batch)(self);
+        if deferred > 0 {
+            println!(
+                "INFO: block {} reached its building deadline, {deferred} transactions are left in the mem-pool",
+                block_header.number
+            );
+        }
+
+        // Withdrawals are processed after all transactions, as specified in EIP-4895
+        if !withdrawals.is_empty() {
+            let block_hash_lookup =
+                StorageBasedProvider::new(&self.storage_reader, &self.block_queries);
+            let state = RecordingResolver::new(self.state.resolver(), recorder.as_ref());
+            let evm_storage =
+                RecordingStorageTrieRepository::new(&self.evm_storage, recorder.as_ref());
+            let changes = execute_withdrawals(WithdrawalExecutionInput {
+                withdrawals,
+                withdrawals_root,
+                state: &state,
+                storage_trie: &evm_storage,
+                genesis_config: &self.genesis_config,
+                block_header: block_header.clone(),
+                block_hash_lookup: &block_hash_lookup,
+            })
+            .unwrap_or_else(|e| panic!("ERROR: withdrawals execution failed {e:?}"));
+
+            let move_vm = Arc::new(changes.move_vm);
+            self.on_tx(&move_vm);
+            self.on_tx_evm_storage(&changes.evm);
 
-        // Compute the receipts root by RLP-encoding each receipt to be a leaf of
-        // a merkle trie.
-        let receipts_root =
-            alloy_trie::root::ordered_trie_root_with_encoder(&receipts, |rx, buf| {
-                rx.receipt.encode(buf)
+            self.state
+                .apply(Arc::unwrap_or_clone(move_vm))
+                .unwrap_or_else(|e| panic!("ERROR: state update failed for withdrawals\n{e:?}"));
+            self.evm_storage.apply(changes.evm).unwrap_or_else(|e| {
+                panic!("ERROR: EVM storage update failed for withdrawals\n{e:?}")
             });
+        }
+
+        if let Some(recorder) = recorder {
+            self.on_state_reads(&recorder.take());
+        }
+        self.on_tx_batch();
+
         let logs_bloom = logs_bloom.into();
 
+        let (transactions_root, receipts_root) = self
+            .workers
+            .hash_tries(|| transactions_root.root(), || receipts_root.root());
+
         let outcome = ExecutionOutcome {
             state_root: self.state.state_root(),
             gas_used: U64::from(cumulative_gas_used),
+            transactions_root,
             receipts_root,
             logs_bloom,
             total_tip,
         };
-        (outcome, receipts)
+        (outcome, receipts, rejected_transactions)
+    }
+}
+
+/// Computes how long pool transactions are executed for in a block with `timestamp` built on top
+/// of a parent with `parent_timestamp`, a share of the time between the two.
+fn build_time(timestamp: u64, parent_timestamp: u64) -> Duration {
+    let block_time = timestamp
+        .saturating_sub(parent_timestamp)
+        .clamp(1, MAX_BLOCK_TIME_SECS);
+
+    Duration::from_secs(block_time) * BUILD_TIME_PERCENT / 100
+}
+
+/// Computes the length of the RLP encoding of a block with full `transactions`.
+///
+/// Blocks of the OP stack have no ommers, so the list of them is always empty.
+fn block_size(header: &Header, transactions: &[OpTxEnvelope], withdrawals: &[Withdrawal]) -> u64 {
+    let payload_length = header.length()
+        + transactions.length()
+        + Vec::<Header>::new().length()
+        + withdrawals.length();
+
+    (payload_length + length_of_length(payload_length)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_time_is_share_of_block_time() {
+        assert_eq!(build_time(1_002, 1_000), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_build_time_is_bounded_for_old_parent() {
+        assert_eq!(build_time(1_000_000, 0), Duration::from_secs(6));
+        assert_eq!(build_time(1_000, 1_000), Duration::from_millis(500));
     }
 }
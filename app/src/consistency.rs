@@ -0,0 +1,41 @@
+use {
+    crate::{Application, BlockExecutionObserver, Dependencies},
+    move_core_types::effects::ChangeSet,
+    moved_state::consistency::{self, ResolverKey},
+    std::{
+        collections::BTreeSet,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Verifies after each block that the state trie holds the same values as the state resolver for
+/// every key the block wrote to.
+///
+/// A divergence between the two only shows as a state root mismatch blocks later, when it is hard
+/// to trace back. The check reads every written key twice, so it is meant for development and
+/// staging nodes. Block production stops on the first divergence found, keeping the state intact
+/// for inspection.
+#[derive(Debug, Default)]
+pub struct TrieConsistencyChecker {
+    touched: Mutex<BTreeSet<ResolverKey>>,
+}
+
+impl<D: Dependencies> BlockExecutionObserver<D> for TrieConsistencyChecker {
+    fn on_tx(&self, _app: &mut Application<D>, changes: &Arc<ChangeSet>) {
+        self.touched
+            .lock()
+            .unwrap()
+            .extend(consistency::touched_keys(changes));
+    }
+
+    fn on_tx_batch(&self, app: &mut Application<D>) {
+        let touched = std::mem::take(&mut *self.touched.lock().unwrap());
+        let inconsistent = consistency::inconsistent_keys(&app.state, &touched)
+            .unwrap_or_else(|e| panic!("ERROR: state trie is unreadable\n{e:?}"));
+
+        assert!(
+            inconsistent.is_empty(),
+            "ERROR: state trie diverged from the resolver at {inconsistent:#?}"
+        );
+    }
+}
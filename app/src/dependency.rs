@@ -2,9 +2,19 @@
 pub use test_doubles::TestDependencies;
 
 use {
-    move_core_types::effects::ChangeSet, moved_blockchain::payload::PayloadId,
-    moved_execution::L1GasFeeInput, moved_genesis::config::GenesisConfig,
-    moved_shared::primitives::B256, op_alloy::consensus::OpTxEnvelope, std::collections::HashMap,
+    crate::{
+        BlockExecutionObserver, BlockExecutionObservers, GasEstimateCache, GasPriceOracle, MemPool,
+        PayloadExpiry, PeerCount, ResponseCache, Workers,
+    },
+    move_core_types::effects::ChangeSet,
+    move_table_extension::TableChangeSet,
+    moved_blockchain::{block::ExtendedBlock, payload::PayloadId},
+    moved_evm_ext::state::StorageTriesChanges,
+    moved_execution::transaction::MoveEvent,
+    moved_genesis::config::GenesisConfig,
+    moved_shared::primitives::B256,
+    moved_state::witness::StateReads,
+    std::sync::Arc,
 };
 
 pub struct ApplicationReader<D: Dependencies> {
@@ -18,6 +28,16 @@ pub struct ApplicationReader<D: Dependencies> {
     pub state_queries: D::StateQueries,
     pub evm_storage: D::StorageTrieRepository,
     pub transaction_queries: D::TransactionQueries,
+    pub gas_price_oracle: GasPriceOracle,
+    pub gas_estimates: GasEstimateCache,
+    pub responses: ResponseCache,
+    pub l1_fee: D::CreateL1GasFee,
+    /// Shared with the [`Application`] to answer queries about the `pending` block.
+    pub mem_pool: MemPool,
+    /// Shared with the [`Application`] to count the payloads expired on either side.
+    pub payload_expiry: PayloadExpiry,
+    /// Shared with the P2P network, if enabled, which updates it as peers come and go.
+    pub peer_count: PeerCount,
 }
 
 unsafe impl<D: Dependencies> Sync for ApplicationReader<D> {}
@@ -35,6 +55,13 @@ impl<D: Dependencies> Clone for ApplicationReader<D> {
             state_queries: self.state_queries.clone(),
             evm_storage: self.evm_storage.clone(),
             transaction_queries: self.transaction_queries.clone(),
+            gas_price_oracle: self.gas_price_oracle.clone(),
+            gas_estimates: self.gas_estimates.clone(),
+            responses: self.responses.clone(),
+            l1_fee: self.l1_fee.clone(),
+            mem_pool: self.mem_pool.clone(),
+            payload_expiry: self.payload_expiry.clone(),
+            peer_count: self.peer_count.clone(),
         }
     }
 }
@@ -52,13 +79,35 @@ impl<D: Dependencies> ApplicationReader<D> {
             state_queries: deps.state_queries(genesis_config),
             evm_storage: D::storage_trie_repository(),
             transaction_queries: D::transaction_queries(),
+            gas_price_oracle: GasPriceOracle::new(genesis_config.gas_price_oracle.clone()),
+            gas_estimates: GasEstimateCache::default(),
+            responses: ResponseCache::default(),
+            l1_fee: D::create_l1_gas_fee(),
+            mem_pool: MemPool::default(),
+            payload_expiry: PayloadExpiry::default(),
+            peer_count: PeerCount::default(),
         }
     }
+
+    /// Makes the transactions waiting in the `mem_pool` of the [`Application`] count towards the
+    /// `pending` block.
+    pub fn with_mem_pool(mut self, mem_pool: MemPool) -> Self {
+        self.mem_pool = mem_pool;
+        self
+    }
+
+    /// Makes the payloads expired by the [`Application`] count towards the same `payload_expiry`.
+    pub fn with_payload_expiry(mut self, payload_expiry: PayloadExpiry) -> Self {
+        self.payload_expiry = payload_expiry;
+        self
+    }
 }
 
 pub struct Application<D: Dependencies> {
     pub genesis_config: GenesisConfig,
-    pub mem_pool: HashMap<B256, (OpTxEnvelope, L1GasFeeInput)>,
+    pub mem_pool: MemPool,
+    pub payload_expiry: PayloadExpiry,
+    pub workers: Workers,
     pub gas_fee: D::BaseGasFee,
     pub base_token: D::BaseTokenAccounts,
     pub l1_fee: D::CreateL1GasFee,
@@ -67,8 +116,8 @@ pub struct Application<D: Dependencies> {
     pub block_queries: D::BlockQueries,
     pub block_repository: D::BlockRepository,
     pub on_payload: &'static D::OnPayload,
-    pub on_tx: &'static D::OnTx,
-    pub on_tx_batch: &'static D::OnTxBatch,
+    pub observers: BlockExecutionObservers<D>,
+    pub payload_job_repository: D::PayloadJobRepository,
     pub payload_queries: D::PayloadQueries,
     pub receipt_queries: D::ReceiptQueries,
     pub receipt_repository: D::ReceiptRepository,
@@ -81,6 +130,7 @@ pub struct Application<D: Dependencies> {
     pub evm_storage: D::StorageTrieRepository,
     pub transaction_queries: D::TransactionQueries,
     pub transaction_repository: D::TransactionRepository,
+    pub transaction_journal: D::TransactionJournal,
 }
 
 impl<D: Dependencies> Application<D> {
@@ -88,7 +138,9 @@ impl<D: Dependencies> Application<D> {
         Self {
             genesis_config: genesis_config.clone(),
             mem_pool: Default::default(),
-            gas_fee: D::base_gas_fee(),
+            payload_expiry: Default::default(),
+            workers: Workers::new(&genesis_config.concurrency),
+            gas_fee: D::base_gas_fee(genesis_config),
             base_token: D::base_token_accounts(genesis_config),
             l1_fee: D::create_l1_gas_fee(),
             l2_fee: D::create_l2_gas_fee(),
@@ -96,8 +148,8 @@ impl<D: Dependencies> Application<D> {
             block_queries: D::block_queries(),
             block_repository: D::block_repository(),
             on_payload: D::on_payload(),
-            on_tx: D::on_tx(),
-            on_tx_batch: D::on_tx_batch(),
+            observers: D::block_execution_observers(),
+            payload_job_repository: D::payload_job_repository(),
             payload_queries: D::payload_queries(),
             receipt_queries: D::receipt_queries(),
             receipt_repository: D::receipt_repository(),
@@ -110,11 +162,58 @@ impl<D: Dependencies> Application<D> {
             evm_storage: D::storage_trie_repository(),
             transaction_queries: D::transaction_queries(),
             transaction_repository: D::transaction_repository(),
+            transaction_journal: D::transaction_journal(),
         }
     }
 
-    pub fn on_tx(&mut self, changes: ChangeSet) {
-        (self.on_tx)(self, changes)
+    pub fn on_tx(&mut self, changes: &Arc<ChangeSet>) {
+        self.notify_observers(|observer, app| observer.on_tx(app, changes))
+    }
+
+    pub fn on_tx_tables(&mut self, changes: &TableChangeSet) {
+        self.notify_observers(|observer, app| observer.on_tx_tables(app, changes))
+    }
+
+    pub fn on_tx_evm_storage(&mut self, changes: &StorageTriesChanges) {
+        self.notify_observers(|observer, app| observer.on_tx_evm_storage(app, changes))
+    }
+
+    pub fn on_tx_events(&mut self, tx_hash: B256, events: &[MoveEvent]) {
+        self.notify_observers(|observer, app| observer.on_tx_events(app, tx_hash, events))
+    }
+
+    pub fn records_state_reads(&self) -> bool {
+        self.observers
+            .iter()
+            .any(|observer| observer.records_state_reads())
+    }
+
+    pub fn on_state_reads(&mut self, reads: &StateReads) {
+        self.notify_observers(|observer, app| observer.on_state_reads(app, reads))
+    }
+
+    pub fn on_tx_batch(&mut self) {
+        self.notify_observers(|observer, app| observer.on_tx_batch(app))
+    }
+
+    pub fn on_block(&mut self, block: &ExtendedBlock) {
+        self.notify_observers(|observer, app| observer.on_block(app, block))
+    }
+
+    pub fn on_tx_accepted(&mut self, tx_hash: B256) {
+        self.notify_observers(|observer, app| observer.on_tx_accepted(app, tx_hash))
+    }
+
+    fn notify_observers(&mut self, notify: impl Fn(&dyn BlockExecutionObserver<D>, &mut Self)) {
+        // Observers receive the whole application, so they are detached for the duration of the
+        // notification to avoid aliasing them.
+        let observers = std::mem::take(&mut self.observers);
+
+        for observer in &observers {
+            notify(observer.as_ref(), self);
+        }
+
+        self.observers = observers;
     }
 }
 
@@ -125,8 +224,7 @@ pub trait DependenciesThreadSafe:
         BlockQueries: Send + 'static,
         BlockRepository: Send + 'static,
         OnPayload: Send + Sync + 'static,
-        OnTx: Send + Sync + 'static,
-        OnTxBatch: Send + Sync + 'static,
+        PayloadJobRepository: Send + 'static,
         PayloadQueries: Send + 'static,
         ReceiptQueries: Send + 'static,
         ReceiptRepository: Send + 'static,
@@ -139,6 +237,7 @@ pub trait DependenciesThreadSafe:
         StorageTrieRepository: Send + 'static,
         TransactionQueries: Send + 'static,
         TransactionRepository: Send + 'static,
+        TransactionJournal: Send + 'static,
         BaseGasFee: Send + 'static,
         CreateL1GasFee: Send + 'static,
         CreateL2GasFee: Send + 'static,
@@ -154,8 +253,7 @@ impl<
             BlockQueries: Send + 'static,
             BlockRepository: Send + 'static,
             OnPayload: Send + Sync + 'static,
-            OnTx: Send + Sync + 'static,
-            OnTxBatch: Send + Sync + 'static,
+            PayloadJobRepository: Send + 'static,
             PayloadQueries: Send + 'static,
             ReceiptQueries: Send + 'static,
             ReceiptRepository: Send + 'static,
@@ -168,6 +266,7 @@ impl<
             StorageTrieRepository: Send + 'static,
             TransactionQueries: Send + 'static,
             TransactionRepository: Send + 'static,
+            TransactionJournal: Send + 'static,
             BaseGasFee: Send + 'static,
             CreateL1GasFee: Send + 'static,
             CreateL2GasFee: Send + 'static,
@@ -187,12 +286,7 @@ pub trait Dependencies {
     /// A function invoked on an execution of a new payload.
     type OnPayload: Fn(&mut Application<Self>, PayloadId, B256) + 'static + ?Sized;
 
-    /// A function invoked on an execution of a new transaction.
-    type OnTx: Fn(&mut Application<Self>, ChangeSet) + 'static + ?Sized;
-
-    /// A function invoked on a completion of new transaction execution batch.
-    type OnTxBatch: Fn(&mut Application<Self>) + 'static + ?Sized;
-
+    type PayloadJobRepository: moved_blockchain::payload::PayloadJobRepository<crate::Payload>;
     type PayloadQueries: moved_blockchain::payload::PayloadQueries<Storage = Self::SharedStorageReader>
         + Clone;
     type ReceiptQueries: moved_blockchain::receipt::ReceiptQueries<Storage = Self::ReceiptStorageReader>
@@ -208,8 +302,9 @@ pub trait Dependencies {
     type TransactionQueries: moved_blockchain::transaction::TransactionQueries<Storage = Self::SharedStorageReader>
         + Clone;
     type TransactionRepository: moved_blockchain::transaction::TransactionRepository<Storage = Self::SharedStorage>;
+    type TransactionJournal: moved_blockchain::transaction::TransactionJournal;
     type BaseGasFee: moved_blockchain::block::BaseGasFee;
-    type CreateL1GasFee: moved_execution::CreateL1GasFee;
+    type CreateL1GasFee: moved_execution::CreateL1GasFee + Clone;
     type CreateL2GasFee: moved_execution::CreateL2GasFee;
 
     fn base_token_accounts(genesis_config: &GenesisConfig) -> Self::BaseTokenAccounts;
@@ -222,9 +317,10 @@ pub trait Dependencies {
 
     fn on_payload() -> &'static Self::OnPayload;
 
-    fn on_tx() -> &'static Self::OnTx;
+    /// Observers registered on the [`Application`] to be notified about block execution.
+    fn block_execution_observers() -> BlockExecutionObservers<Self>;
 
-    fn on_tx_batch() -> &'static Self::OnTxBatch;
+    fn payload_job_repository() -> Self::PayloadJobRepository;
 
     fn payload_queries() -> Self::PayloadQueries;
 
@@ -250,7 +346,9 @@ pub trait Dependencies {
 
     fn transaction_repository() -> Self::TransactionRepository;
 
-    fn base_gas_fee() -> Self::BaseGasFee;
+    fn transaction_journal() -> Self::TransactionJournal;
+
+    fn base_gas_fee(genesis_config: &GenesisConfig) -> Self::BaseGasFee;
 
     fn create_l1_gas_fee() -> Self::CreateL1GasFee;
 
@@ -287,6 +385,8 @@ mod test_doubles {
         BF = moved_blockchain::block::Eip1559GasFee,
         F1 = U256,
         F2 = U256,
+        PJ = moved_blockchain::payload::InMemoryPayloadJobRepository<crate::Payload>,
+        TJ = moved_blockchain::transaction::InMemoryTransactionJournal,
     >(
         SQ,
         S,
@@ -307,6 +407,8 @@ mod test_doubles {
         BF,
         F1,
         F2,
+        PJ,
+        TJ,
     );
 
     impl<
@@ -327,8 +429,10 @@ mod test_doubles {
         TQ: moved_blockchain::transaction::TransactionQueries<Storage = BMR> + Clone + Send + 'static,
         TR: moved_blockchain::transaction::TransactionRepository<Storage = B> + Send + 'static,
         BF: moved_blockchain::block::BaseGasFee + Send + 'static,
-        F1: moved_execution::CreateL1GasFee + Send + 'static,
+        F1: moved_execution::CreateL1GasFee + Clone + Send + 'static,
         F2: moved_execution::CreateL2GasFee + Send + 'static,
+        PJ: moved_blockchain::payload::PayloadJobRepository<crate::Payload> + Send + 'static,
+        TJ: moved_blockchain::transaction::TransactionJournal + Send + 'static,
     > Dependencies
         for TestDependencies<
             SQ,
@@ -350,6 +454,8 @@ mod test_doubles {
             BF,
             F1,
             F2,
+            PJ,
+            TJ,
         >
     {
         type BaseTokenAccounts = BT;
@@ -357,8 +463,7 @@ mod test_doubles {
         type BlockQueries = BQ;
         type BlockRepository = BR;
         type OnPayload = crate::OnPayload<Application<Self>>;
-        type OnTx = crate::OnTx<Application<Self>>;
-        type OnTxBatch = crate::OnTxBatch<Application<Self>>;
+        type PayloadJobRepository = PJ;
         type PayloadQueries = PQ;
         type ReceiptQueries = RQ;
         type ReceiptRepository = RR;
@@ -371,6 +476,7 @@ mod test_doubles {
         type StorageTrieRepository = ST;
         type TransactionQueries = TQ;
         type TransactionRepository = TR;
+        type TransactionJournal = TJ;
         type BaseGasFee = BF;
         type CreateL1GasFee = F1;
         type CreateL2GasFee = F2;
@@ -395,11 +501,11 @@ mod test_doubles {
             unimplemented!("Dependencies are created manually in tests")
         }
 
-        fn on_tx() -> &'static Self::OnTx {
+        fn block_execution_observers() -> crate::BlockExecutionObservers<Self> {
             unimplemented!("Dependencies are created manually in tests")
         }
 
-        fn on_tx_batch() -> &'static Self::OnTxBatch {
+        fn payload_job_repository() -> Self::PayloadJobRepository {
             unimplemented!("Dependencies are created manually in tests")
         }
 
@@ -451,7 +557,11 @@ mod test_doubles {
             unimplemented!("Dependencies are created manually in tests")
         }
 
-        fn base_gas_fee() -> Self::BaseGasFee {
+        fn transaction_journal() -> Self::TransactionJournal {
+            unimplemented!("Dependencies are created manually in tests")
+        }
+
+        fn base_gas_fee(_: &GenesisConfig) -> Self::BaseGasFee {
             unimplemented!("Dependencies are created manually in tests")
         }
 
@@ -0,0 +1,147 @@
+use {
+    crate::{Application, BlockExecutionObserver, Dependencies},
+    move_core_types::effects::ChangeSet,
+    moved_blockchain::{
+        block::ExtendedBlock,
+        receipt::{ReceiptQueries, TransactionReceipt},
+    },
+    moved_execution::transaction::MoveEvent,
+    moved_shared::primitives::B256,
+    std::{
+        panic::{self, AssertUnwindSafe},
+        sync::{
+            Arc, Mutex,
+            mpsc::{self, Receiver, SyncSender},
+        },
+        thread,
+    },
+};
+
+/// Number of committed blocks queued for an extension before block production waits for it.
+pub const DEFAULT_EXTENSION_CAPACITY: usize = 64;
+
+/// A block that has been committed, as delivered to [`ExecutionExtension`]s.
+#[derive(Debug)]
+pub struct CommittedBlock {
+    pub block: ExtendedBlock,
+    /// Receipts of the block transactions, in the block order.
+    pub receipts: Vec<TransactionReceipt>,
+    /// Move VM changes made by each transaction of the block in the execution order, followed by
+    /// the changes made by its withdrawals if there are any.
+    pub state_diff: Vec<Arc<ChangeSet>>,
+    /// Move events emitted by each transaction of the block, in the execution order.
+    pub events: Vec<(B256, Vec<MoveEvent>)>,
+}
+
+/// Custom logic run inside the node on every committed block, such as an indexer or a bridge relay.
+///
+/// Each extension runs on a thread of its own, fed through a queue of bounded size. When the queue
+/// is full, block production waits for the extension to catch up. An extension that panics is
+/// stopped without affecting the node or the other extensions.
+pub trait ExecutionExtension: Send + 'static {
+    /// Identifies the extension in logs.
+    fn name(&self) -> &str;
+
+    /// Invoked for every committed block, in the order of the chain.
+    fn on_block_committed(&mut self, block: &CommittedBlock);
+}
+
+/// Delivers committed blocks to a set of [`ExecutionExtension`]s.
+///
+/// Register it among the [`Application::observers`] to enable the extensions.
+pub struct ExtensionObserver {
+    senders: Mutex<Vec<SyncSender<Arc<CommittedBlock>>>>,
+    state_diff: Mutex<Vec<Arc<ChangeSet>>>,
+    events: Mutex<Vec<(B256, Vec<MoveEvent>)>>,
+}
+
+impl ExtensionObserver {
+    pub fn new(extensions: Vec<Box<dyn ExecutionExtension>>) -> Self {
+        Self::with_capacity(extensions, DEFAULT_EXTENSION_CAPACITY)
+    }
+
+    /// Creates the observer with at most `capacity` blocks queued for each extension.
+    pub fn with_capacity(extensions: Vec<Box<dyn ExecutionExtension>>, capacity: usize) -> Self {
+        let senders = extensions
+            .into_iter()
+            .map(|extension| {
+                let (sender, receiver) = mpsc::sync_channel(capacity);
+                thread::Builder::new()
+                    .name(format!("exex-{}", extension.name()))
+                    .spawn(move || run_extension(extension, receiver))
+                    .expect("Extension thread should spawn");
+
+                sender
+            })
+            .collect();
+
+        Self {
+            senders: Mutex::new(senders),
+            state_diff: Mutex::new(Vec::new()),
+            events: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+fn run_extension(
+    mut extension: Box<dyn ExecutionExtension>,
+    blocks: Receiver<Arc<CommittedBlock>>,
+) {
+    for block in blocks {
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            extension.on_block_committed(&block);
+        }));
+
+        if outcome.is_err() {
+            println!(
+                "WARN: extension {} panicked on block {} and is stopped",
+                extension.name(),
+                block.block.block.header.number
+            );
+            return;
+        }
+    }
+}
+
+impl<D: Dependencies> BlockExecutionObserver<D> for ExtensionObserver {
+    fn on_tx(&self, _app: &mut Application<D>, changes: &Arc<ChangeSet>) {
+        if !self.senders.lock().unwrap().is_empty() {
+            self.state_diff.lock().unwrap().push(Arc::clone(changes));
+        }
+    }
+
+    fn on_tx_events(&self, _app: &mut Application<D>, tx_hash: B256, events: &[MoveEvent]) {
+        if !self.senders.lock().unwrap().is_empty() {
+            self.events.lock().unwrap().push((tx_hash, events.to_vec()));
+        }
+    }
+
+    fn on_block(&self, app: &mut Application<D>, block: &ExtendedBlock) {
+        let state_diff = std::mem::take(&mut *self.state_diff.lock().unwrap());
+        let events = std::mem::take(&mut *self.events.lock().unwrap());
+        let mut senders = self.senders.lock().unwrap();
+        if senders.is_empty() {
+            return;
+        }
+
+        let receipts = block
+            .block
+            .transactions
+            .iter()
+            .filter_map(|hash| {
+                app.receipt_queries
+                    .by_transaction_hash(&app.receipt_memory_reader, *hash)
+                    .unwrap()
+            })
+            .collect();
+        let committed = Arc::new(CommittedBlock {
+            block: block.clone(),
+            receipts,
+            state_diff,
+            events,
+        });
+
+        // Sending fails only once the extension has stopped, which is reported by its thread
+        senders.retain(|sender| sender.send(committed.clone()).is_ok());
+    }
+}
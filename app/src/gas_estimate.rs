@@ -0,0 +1,112 @@
+use {
+    alloy::rpc::types::TransactionRequest,
+    moved_shared::primitives::B256,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Most estimates remembered at once, so that a flood of distinct requests cannot grow the cache
+/// without bound.
+const MAX_CACHED_ESTIMATES: usize = 1_024;
+
+/// Remembers the gas estimated for transactions on top of the most recently estimated block.
+///
+/// Frontends tend to send the same `eth_estimateGas` request many times over, and the estimate
+/// cannot change until the block it is made on does. The cache is emptied whenever an estimate is
+/// made on a different block. The clones of a cache share its estimates.
+#[derive(Debug, Clone, Default)]
+pub struct GasEstimateCache {
+    estimates: Arc<Mutex<BlockEstimates>>,
+}
+
+#[derive(Debug, Default)]
+struct BlockEstimates {
+    block_hash: B256,
+    gas: HashMap<TransactionRequest, u64>,
+}
+
+impl GasEstimateCache {
+    /// Returns the gas estimated for `transaction` on top of the block `block_hash`, calling
+    /// `estimate` if it is not cached yet.
+    ///
+    /// Failed estimates are not cached.
+    pub fn get_or_estimate<E>(
+        &self,
+        block_hash: B256,
+        transaction: &TransactionRequest,
+        estimate: impl FnOnce() -> Result<u64, E>,
+    ) -> Result<u64, E> {
+        {
+            let estimates = self.estimates.lock().unwrap();
+            if estimates.block_hash == block_hash {
+                if let Some(gas) = estimates.gas.get(transaction) {
+                    return Ok(*gas);
+                }
+            }
+        }
+
+        // The lock is not held while estimating, so that other estimates can be served meanwhile
+        let gas = estimate()?;
+
+        let mut estimates = self.estimates.lock().unwrap();
+        if estimates.block_hash != block_hash || estimates.gas.len() >= MAX_CACHED_ESTIMATES {
+            estimates.block_hash = block_hash;
+            estimates.gas.clear();
+        }
+        estimates.gas.insert(transaction.clone(), gas);
+
+        Ok(gas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::cell::Cell};
+
+    #[test]
+    fn test_estimate_is_reused_on_same_block() {
+        let cache = GasEstimateCache::default();
+        let estimates = Cell::new(0);
+        let estimate = || {
+            estimates.set(estimates.get() + 1);
+            Ok::<_, ()>(21_000)
+        };
+        let transaction = TransactionRequest::default();
+
+        cache
+            .get_or_estimate(B256::ZERO, &transaction, estimate)
+            .unwrap();
+        let gas = cache.get_or_estimate(B256::ZERO, &transaction, estimate);
+
+        assert_eq!(gas, Ok(21_000));
+        assert_eq!(estimates.get(), 1);
+    }
+
+    #[test]
+    fn test_estimate_is_made_again_on_new_block() {
+        let cache = GasEstimateCache::default();
+        let transaction = TransactionRequest::default();
+
+        cache
+            .get_or_estimate(B256::ZERO, &transaction, || Ok::<_, ()>(21_000))
+            .unwrap();
+        let gas = cache.get_or_estimate(B256::repeat_byte(1), &transaction, || Ok::<_, ()>(30_000));
+
+        assert_eq!(gas, Ok(30_000));
+    }
+
+    #[test]
+    fn test_failed_estimate_is_not_cached() {
+        let cache = GasEstimateCache::default();
+        let transaction = TransactionRequest::default();
+
+        cache
+            .get_or_estimate(B256::ZERO, &transaction, || Err(()))
+            .unwrap_err();
+        let gas = cache.get_or_estimate(B256::ZERO, &transaction, || Ok::<_, ()>(21_000));
+
+        assert_eq!(gas, Ok(21_000));
+    }
+}
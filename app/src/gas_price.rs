@@ -0,0 +1,200 @@
+use {
+    moved_genesis::config::GasPriceOracleParameters,
+    moved_shared::primitives::B256,
+    std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Suggests priority fees from the tips paid by the transactions of the most recent blocks.
+///
+/// The tips of a block are read when the block first enters the sampled range and kept until it
+/// leaves it, so every query only reads the blocks committed since the previous one. The clones of
+/// an oracle share their samples.
+#[derive(Debug, Clone)]
+pub struct GasPriceOracle {
+    parameters: GasPriceOracleParameters,
+    samples: Arc<Mutex<BTreeMap<u64, BlockTips>>>,
+}
+
+#[derive(Debug)]
+struct BlockTips {
+    hash: B256,
+    tips: Vec<u128>,
+}
+
+impl GasPriceOracle {
+    pub fn new(parameters: GasPriceOracleParameters) -> Self {
+        Self {
+            parameters,
+            samples: Default::default(),
+        }
+    }
+
+    /// Number of the most recent blocks to pass to [`Self::suggest_priority_fee`].
+    pub fn blocks(&self) -> u64 {
+        self.parameters.blocks
+    }
+
+    /// Suggests a priority fee from the tips paid in `blocks`, given as their heights and hashes.
+    ///
+    /// `read_tips` is only called for the blocks that are not sampled yet, or that have been
+    /// replaced by a different block at the same height.
+    pub fn suggest_priority_fee(
+        &self,
+        blocks: impl IntoIterator<Item = (u64, B256)>,
+        read_tips: impl Fn(B256) -> Vec<u128>,
+    ) -> u128 {
+        let mut samples = self.samples.lock().unwrap();
+        let mut sampled = BTreeMap::new();
+
+        for (height, hash) in blocks {
+            let block = match samples.remove(&height) {
+                Some(block) if block.hash == hash => block,
+                _ => BlockTips {
+                    hash,
+                    tips: read_tips(hash),
+                },
+            };
+            sampled.insert(height, block);
+        }
+        *samples = sampled;
+
+        let mut tips: Vec<_> = samples
+            .values()
+            .flat_map(|block| block.tips.iter().copied())
+            .collect();
+        tips.sort_unstable();
+
+        let suggested = match tips.len() {
+            0 => 0,
+            len => tips[(len - 1) * usize::from(self.parameters.percentile) / 100],
+        };
+
+        suggested.clamp(
+            self.parameters.min_priority_fee,
+            self.parameters.max_priority_fee,
+        )
+    }
+}
+
+/// Picks the tips at the given `percentiles` of the gas used by the transactions paying them.
+///
+/// `tips` pairs the tip paid per gas by each transaction with the gas it used. This is the reward
+/// of a block in `eth_feeHistory`, computed the same way as geth does.
+pub(crate) fn block_rewards(percentiles: &[f64], mut tips: Vec<(u128, u64)>) -> Vec<u128> {
+    if tips.is_empty() {
+        return vec![0; percentiles.len()];
+    }
+    tips.sort_unstable_by_key(|(tip, _)| *tip);
+
+    let total_gas_used: u64 = tips.iter().map(|(_, gas_used)| gas_used).sum();
+    let mut index = 0;
+    let mut cumulative_gas_used = tips[0].1;
+
+    percentiles
+        .iter()
+        .map(|percentile| {
+            let threshold = (total_gas_used as f64 * percentile / 100.0) as u64;
+            while cumulative_gas_used < threshold && index < tips.len() - 1 {
+                index += 1;
+                cumulative_gas_used += tips[index].1;
+            }
+            tips[index].0
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::cell::Cell};
+
+    fn oracle(percentile: u8, min_priority_fee: u128) -> GasPriceOracle {
+        GasPriceOracle::new(GasPriceOracleParameters {
+            blocks: 3,
+            percentile,
+            min_priority_fee,
+            max_priority_fee: u128::MAX,
+        })
+    }
+
+    fn block(height: u64) -> (u64, B256) {
+        (height, B256::repeat_byte(height as u8))
+    }
+
+    #[test]
+    fn test_suggestion_is_percentile_of_sampled_tips() {
+        let oracle = oracle(50, 0);
+        let tips = |hash: B256| vec![u128::from(hash[0]) * 10, u128::from(hash[0]) * 10 + 5];
+
+        let suggested = oracle.suggest_priority_fee([block(1), block(2), block(3)], tips);
+
+        // Tips are 10, 15, 20, 25, 30 and 35
+        assert_eq!(suggested, 20);
+    }
+
+    #[test]
+    fn test_suggestion_is_never_below_minimum() {
+        let oracle = oracle(60, 1_000);
+
+        assert_eq!(oracle.suggest_priority_fee([block(1)], |_| vec![5]), 1_000);
+        assert_eq!(oracle.suggest_priority_fee([block(2)], |_| vec![]), 1_000);
+    }
+
+    #[test]
+    fn test_suggestion_is_never_above_maximum() {
+        let oracle = GasPriceOracle::new(GasPriceOracleParameters {
+            blocks: 3,
+            percentile: 100,
+            min_priority_fee: 0,
+            max_priority_fee: 1_000,
+        });
+
+        assert_eq!(
+            oracle.suggest_priority_fee([block(1)], |_| vec![5_000]),
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_only_new_blocks_are_read() {
+        let oracle = oracle(100, 0);
+        let reads = Cell::new(0);
+        let tips = |hash: B256| {
+            reads.set(reads.get() + 1);
+            vec![u128::from(hash[0])]
+        };
+
+        oracle.suggest_priority_fee([block(1), block(2), block(3)], tips);
+        let suggested = oracle.suggest_priority_fee([block(2), block(3), block(4)], tips);
+
+        assert_eq!(reads.get(), 4);
+        assert_eq!(suggested, 4);
+    }
+
+    #[test]
+    fn test_replaced_block_is_read_again() {
+        let oracle = oracle(100, 0);
+        oracle.suggest_priority_fee([block(1)], |_| vec![1]);
+
+        let suggested = oracle.suggest_priority_fee([(1, B256::ZERO)], |_| vec![7]);
+
+        assert_eq!(suggested, 7);
+    }
+
+    #[test]
+    fn test_reward_percentiles_are_weighted_by_gas_used() {
+        let tips = vec![(30, 10_000), (10, 21_000), (20, 69_000)];
+
+        let rewards = block_rewards(&[0.0, 20.0, 50.0, 100.0], tips);
+
+        // Cumulative gas used by the tips in ascending order is 21_000, 90_000 and 100_000
+        assert_eq!(rewards, [10, 10, 20, 30]);
+    }
+
+    #[test]
+    fn test_rewards_of_block_without_transactions_are_zero() {
+        assert_eq!(block_rewards(&[25.0, 75.0], Vec::new()), [0, 0]);
+    }
+}
@@ -0,0 +1,104 @@
+//! Merkle proofs of transactions and receipts included in a block.
+//!
+//! The transactions and the receipts of a block are leaves of two ordered tries, keyed by the RLP
+//! encoding of their index, whose roots are in the block header. A proof lists the trie nodes on
+//! the path from the root to one leaf, so that it is checked against nothing but the header.
+
+use {
+    alloy::rlp::Encodable,
+    alloy_trie::{HashBuilder, Nibbles, proof::ProofRetainer, root::adjust_index_for_rlp},
+    moved_shared::primitives::{B256, Bytes},
+};
+
+/// Proof that the item at `index` of a block is a leaf of the trie with `root`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub block_hash: B256,
+    pub block_number: u64,
+    pub root: B256,
+    pub index: u64,
+    /// The RLP encoded `index`, which the leaf is keyed by.
+    pub key: Bytes,
+    /// The encoded item, which is the value of the leaf.
+    pub value: Bytes,
+    /// Trie nodes on the path from the root to the leaf, starting with the root node.
+    pub proof: Vec<Bytes>,
+}
+
+impl InclusionProof {
+    /// Builds the ordered trie of `items` the same way as
+    /// [`alloy_trie::root::ordered_trie_root`], keeping the nodes on the path to the item at
+    /// `index`.
+    ///
+    /// Returns `None` if there is no item at `index`.
+    pub(crate) fn new<T: Encodable>(
+        items: &[T],
+        index: usize,
+        block_hash: B256,
+        block_number: u64,
+    ) -> Option<Self> {
+        let value = alloy::rlp::encode(items.get(index)?);
+        let key = alloy::rlp::encode_fixed_size(&index);
+        let retainer = ProofRetainer::new(vec![Nibbles::unpack(&key)]);
+        let mut builder = HashBuilder::default().with_proof_retainer(retainer);
+
+        let mut buf = Vec::new();
+        for i in 0..items.len() {
+            let leaf_index = adjust_index_for_rlp(i, items.len());
+            buf.clear();
+            items[leaf_index].encode(&mut buf);
+            builder.add_leaf(
+                Nibbles::unpack(alloy::rlp::encode_fixed_size(&leaf_index)),
+                &buf,
+            );
+        }
+        let root = builder.root();
+        let proof = builder
+            .take_proof_nodes()
+            .into_nodes_sorted()
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect();
+
+        Some(Self {
+            block_hash,
+            block_number,
+            root,
+            index: index as u64,
+            key: Bytes::copy_from_slice(&key),
+            value: value.into(),
+            proof,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy_trie::proof::verify_proof, test_case::test_case};
+
+    #[test_case(1, 0; "single leaf")]
+    #[test_case(3, 1; "few leaves")]
+    #[test_case(200, 0; "first of many leaves")]
+    #[test_case(200, 127; "leaf keyed by one byte index")]
+    #[test_case(200, 128; "leaf keyed by two byte index")]
+    fn test_proof_verifies_against_ordered_trie_root(len: u64, index: usize) {
+        let items: Vec<u64> = (0..len).map(|i| i * 1_000_003).collect();
+
+        let proof = InclusionProof::new(&items, index, B256::ZERO, 0).unwrap();
+
+        assert_eq!(proof.root, alloy_trie::root::ordered_trie_root(&items));
+        assert_eq!(proof.value, Bytes::from(alloy::rlp::encode(items[index])));
+        verify_proof(
+            proof.root,
+            Nibbles::unpack(&proof.key),
+            Some(proof.value.to_vec()),
+            &proof.proof,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_proof_of_missing_item_is_none() {
+        assert_eq!(InclusionProof::new(&[1u64, 2], 2, B256::ZERO, 0), None);
+    }
+}
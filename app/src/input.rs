@@ -1,23 +1,33 @@
 use {
     alloy::{consensus::transaction::TxEnvelope, primitives::Bloom},
     moved_blockchain::{
-        block::{ExtendedBlock, Header},
+        block::Header,
         payload::{NewPayloadIdInput, PayloadId},
     },
-    moved_shared::primitives::{Address, B256, B2048, Bytes, ToU64, U64, U256},
+    moved_shared::primitives::{Address, B64, B256, B2048, Bytes, ToU64, U64, U256},
+    std::time::Instant,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub struct Payload {
     pub timestamp: U64,
     pub prev_randao: B256,
     pub suggested_fee_recipient: Address,
     pub withdrawals: Vec<Withdrawal>,
-    pub parent_beacon_block_root: B256,
+    /// Given by the consensus layer since the Ecotone upgrade, whose blocks also carry the blob gas
+    /// fields in their header.
+    #[serde(default)]
+    pub parent_beacon_block_root: Option<B256>,
     pub transactions: Vec<Bytes>,
     pub gas_limit: U64,
+    /// Base fee parameters requested by the consensus layer since the Holocene upgrade.
+    #[serde(default)]
+    pub eip1559_params: Option<B64>,
 }
 
+/// Version of the Holocene `extraData` format, which is the only one defined so far.
+const HOLOCENE_EXTRA_DATA_VERSION: u8 = 0;
+
 pub type Withdrawal = alloy::rpc::types::Withdrawal;
 
 #[derive(Debug)]
@@ -25,12 +35,15 @@ pub enum Command {
     StartBlockBuild {
         payload_attributes: Payload,
         payload_id: PayloadId,
+        /// When the payload was requested, for the job to expire if it waits for too long.
+        requested_at: Instant,
     },
     AddTransaction {
         tx: TxEnvelope,
     },
-    GenesisUpdate {
-        block: ExtendedBlock,
+    ForkchoiceUpdate {
+        safe_block_hash: B256,
+        finalized_block_hash: B256,
     },
 }
 
@@ -39,6 +52,7 @@ pub type RpcTransaction = op_alloy::rpc_types::Transaction;
 
 #[derive(Debug)]
 pub struct ExecutionOutcome {
+    pub transactions_root: B256,
     pub receipts_root: B256,
     pub state_root: B256,
     pub logs_bloom: B2048,
@@ -54,6 +68,7 @@ impl WithExecutionOutcome for Header {
     fn with_execution_outcome(self, outcome: ExecutionOutcome) -> Self {
         Self {
             state_root: outcome.state_root,
+            transactions_root: outcome.transactions_root,
             receipts_root: outcome.receipts_root,
             logs_bloom: Bloom::new(outcome.logs_bloom.0),
             gas_used: outcome.gas_used.to_u64(),
@@ -68,14 +83,24 @@ pub trait ToPayloadIdInput<'a> {
 
 impl<'a> ToPayloadIdInput<'a> for Payload {
     fn to_payload_id_input(&'a self, head: &'a B256) -> NewPayloadIdInput<'a> {
-        NewPayloadIdInput::new_v3(
-            head,
-            self.timestamp.into_limbs()[0],
-            &self.prev_randao,
-            &self.suggested_fee_recipient,
-        )
-        .with_beacon_root(&self.parent_beacon_block_root)
-        .with_withdrawals(
+        let timestamp = self.timestamp.into_limbs()[0];
+        let input = match &self.parent_beacon_block_root {
+            Some(beacon_root) => NewPayloadIdInput::new_v3(
+                head,
+                timestamp,
+                &self.prev_randao,
+                &self.suggested_fee_recipient,
+            )
+            .with_beacon_root(beacon_root),
+            None => NewPayloadIdInput::new_v2(
+                head,
+                timestamp,
+                &self.prev_randao,
+                &self.suggested_fee_recipient,
+            ),
+        };
+
+        input.with_withdrawals(
             self.withdrawals
                 .iter()
                 .map(ToWithdrawal::to_withdrawal)
@@ -84,7 +109,7 @@ impl<'a> ToPayloadIdInput<'a> for Payload {
     }
 }
 
-trait ToWithdrawal {
+pub(crate) trait ToWithdrawal {
     fn to_withdrawal(&self) -> alloy::eips::eip4895::Withdrawal;
 }
 
@@ -99,6 +124,22 @@ impl ToWithdrawal for Withdrawal {
     }
 }
 
+/// Encodes the block header `extraData` field as defined by the Holocene upgrade.
+///
+/// The consensus layer sends zero parameters to request the chain defaults, given by
+/// `default_params`.
+pub(crate) fn holocene_extra_data(eip1559_params: B64, default_params: B64) -> Bytes {
+    let params = if eip1559_params.is_zero() {
+        default_params
+    } else {
+        eip1559_params
+    };
+
+    [&[HOLOCENE_EXTRA_DATA_VERSION], params.as_slice()]
+        .concat()
+        .into()
+}
+
 pub(crate) trait WithPayloadAttributes {
     fn with_payload_attributes(self, payload: Payload) -> Self;
 }
@@ -109,7 +150,7 @@ impl WithPayloadAttributes for Header {
             beneficiary: payload.suggested_fee_recipient,
             gas_limit: payload.gas_limit.to_u64(),
             timestamp: payload.timestamp.to_u64(),
-            parent_beacon_block_root: Some(payload.parent_beacon_block_root),
+            parent_beacon_block_root: payload.parent_beacon_block_root,
             mix_hash: payload.prev_randao,
             ..self
         }
@@ -3,13 +3,54 @@ pub mod factory;
 pub(crate) mod input;
 
 mod actor;
+mod admission;
 mod block_hash;
 mod command;
+mod consistency;
 mod dependency;
+mod extension;
+mod gas_estimate;
+mod gas_price;
+mod inclusion;
+mod mem_pool;
+mod memory_usage;
+mod parallel;
+mod payload_expiry;
+mod peer_count;
+mod preimage;
+mod pruning;
 mod query;
 mod queue;
+mod response_cache;
+mod state_changes;
+mod trie_root;
+mod witness;
 
 #[cfg(test)]
 mod tests;
 
-pub use {actor::*, dependency::*, factory::create, input::*, queue::CommandQueue};
+pub use {
+    actor::*,
+    consistency::TrieConsistencyChecker,
+    dependency::*,
+    extension::*,
+    factory::create,
+    gas_estimate::GasEstimateCache,
+    gas_price::GasPriceOracle,
+    inclusion::InclusionProof,
+    input::*,
+    mem_pool::{MemPool, PooledTransaction, ReplacementUnderpriced},
+    memory_usage::{MEMORY_USAGE_INTERVAL, MemoryUsageReporter},
+    parallel::Workers,
+    payload_expiry::{PayloadExpiry, PayloadExpiryCounts},
+    peer_count::PeerCount,
+    preimage::TriePreimageRecorder,
+    pruning::{STATE_PRUNING_INTERVAL, StatePruner},
+    query::{
+        ResourceValue, SimulatedEvent, Simulation, StateUnavailable, UndecodableTableKey, ViewValue,
+    },
+    queue::{CommandQueue, QueueLoad, QueueSaturated},
+    response_cache::{DEFAULT_RESPONSE_CACHE_CAPACITY, ResponseCache},
+    state_changes::{BalanceChange, NonceChange, StateChanges, StorageChange},
+    witness::*,
+};
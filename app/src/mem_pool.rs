@@ -0,0 +1,330 @@
+use {
+    alloy::{
+        consensus::{Transaction, TxEnvelope},
+        rlp::Encodable,
+    },
+    moved_execution::{L1GasFeeInput, transaction::NormalizedExtendedTxEnvelope},
+    moved_genesis::config::TxAdmissionParameters,
+    moved_shared::{
+        error::{InvalidTransactionCause, Result},
+        primitives::{Address, B256},
+    },
+    op_alloy::consensus::OpTxEnvelope,
+    std::{
+        cmp::Reverse,
+        collections::{BinaryHeap, HashMap, HashSet, hash_map},
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+};
+
+/// Transactions waiting to be included in a block, by their hash.
+///
+/// This includes the transactions that are queued behind a nonce gap, which are included once the
+/// transactions filling the gap are.
+///
+/// The clones of a mem-pool share its transactions, so that queries answered outside of the
+/// command actor see the transactions it has accepted, but not included yet.
+///
+/// Transactions taken out for a block are still in flight until the block is committed, so the
+/// sender and nonce of each of them is kept until then for [`MemPool::pending_nonce`].
+///
+/// The transactions waiting behind a nonce gap are bounded by [`MemPool::evict_queued`], as they
+/// cost nothing to their senders until they are included.
+#[derive(Debug, Clone, Default)]
+pub struct MemPool {
+    transactions: Arc<Mutex<HashMap<B256, PooledTransaction>>>,
+    in_flight: Arc<Mutex<HashSet<(Address, u64)>>>,
+    queued_since: Arc<Mutex<HashMap<B256, Instant>>>,
+}
+
+impl MemPool {
+    /// Adds `tx`, replacing the transaction with the same sender and nonce if `tx` raises both of
+    /// its fees by at least `price_bump_percent`.
+    ///
+    /// Returns the hash of the replaced transaction, or leaves the mem-pool as is if `tx` does not
+    /// offer enough to replace it.
+    pub fn insert(
+        &self,
+        tx_hash: B256,
+        tx: PooledTransaction,
+        price_bump_percent: u64,
+    ) -> std::result::Result<Option<B256>, ReplacementUnderpriced> {
+        let mut transactions = self.transactions.lock().unwrap();
+        let replaced = tx.sender_nonce().and_then(|sender_nonce| {
+            transactions
+                .iter()
+                .find(|(_, pooled)| pooled.sender_nonce() == Some(sender_nonce))
+        });
+        let replaced = match replaced {
+            // The same transaction sent again is kept as is
+            Some((replaced, _)) if *replaced == tx_hash => None,
+            Some((_, pooled)) if !pooled.is_replaceable_by(&tx.tx, price_bump_percent) => {
+                return Err(ReplacementUnderpriced);
+            }
+            Some((replaced, _)) => Some(*replaced),
+            None => None,
+        };
+        if let Some(replaced) = replaced {
+            transactions.remove(&replaced);
+        }
+        transactions.insert(tx_hash, tx);
+        Ok(replaced)
+    }
+
+    pub fn get(&self, tx_hash: &B256) -> Option<PooledTransaction> {
+        self.transactions.lock().unwrap().get(tx_hash).cloned()
+    }
+
+    /// Finds the transaction of `address` with `nonce`, which a new transaction would replace.
+    pub fn by_sender_nonce(&self, address: Address, nonce: u64) -> Option<PooledTransaction> {
+        self.transactions
+            .lock()
+            .unwrap()
+            .values()
+            .find(|pooled| pooled.sender_nonce() == Some((address, nonce)))
+            .cloned()
+    }
+
+    /// Counts the transaction with `tx_hash` as waiting behind a nonce gap of its sender from
+    /// `now`, unless it already is from an earlier time.
+    pub fn mark_queued(&self, tx_hash: B256, now: Instant) {
+        self.queued_since
+            .lock()
+            .unwrap()
+            .entry(tx_hash)
+            .or_insert(now);
+    }
+
+    /// Evicts the transactions waiting behind a nonce gap that exceed the `limits` at `now`,
+    /// returning their hashes.
+    ///
+    /// Same as in geth, the transactions waiting for longer than their lifetime go first. Then each
+    /// sender keeps the ones with its lowest nonces up to its own limit. Beyond the global limit,
+    /// the senders whose transactions were queued the longest ago lose their highest nonces first.
+    pub fn evict_queued(&self, limits: &TxAdmissionParameters, now: Instant) -> Vec<B256> {
+        let mut transactions = self.transactions.lock().unwrap();
+        let mut queued_since = self.queued_since.lock().unwrap();
+        // Transactions included or replaced since they were queued are not waiting anymore
+        queued_since.retain(|tx_hash, _| transactions.contains_key(tx_hash));
+
+        let lifetime = Duration::from_secs(limits.queued_lifetime_secs);
+        let (expired, queued): (Vec<_>, Vec<_>) = queued_since
+            .iter()
+            .partition(|(_, since)| now.saturating_duration_since(**since) > lifetime);
+        let mut evicted: Vec<B256> = expired.into_iter().map(|(tx_hash, _)| *tx_hash).collect();
+
+        let mut by_sender = HashMap::<Address, Vec<(u64, B256)>>::new();
+        for (tx_hash, _) in queued {
+            if let Some((sender, nonce)) = transactions[tx_hash].sender_nonce() {
+                by_sender.entry(sender).or_default().push((nonce, *tx_hash));
+            }
+        }
+        let mut senders: Vec<_> = by_sender.into_values().collect();
+        for queue in &mut senders {
+            queue.sort_unstable();
+            let kept = limits.max_queued_per_account.min(queue.len());
+            evicted.extend(queue.drain(kept..).map(|(_, tx_hash)| tx_hash));
+        }
+
+        let mut excess = senders
+            .iter()
+            .map(Vec::len)
+            .sum::<usize>()
+            .saturating_sub(limits.max_queued);
+        senders.sort_by_key(|queue| queue.iter().map(|(_, tx_hash)| queued_since[tx_hash]).max());
+        for mut queue in senders {
+            while excess > 0 {
+                let Some((_, tx_hash)) = queue.pop() else {
+                    break;
+                };
+                evicted.push(tx_hash);
+                excess -= 1;
+            }
+        }
+
+        for tx_hash in &evicted {
+            transactions.remove(tx_hash);
+            queued_since.remove(tx_hash);
+        }
+
+        evicted
+    }
+
+    /// Takes all transactions out of the mem-pool.
+    ///
+    /// They count towards the pending nonce of their senders until [`MemPool::settle`] is called.
+    pub fn drain(&self) -> hash_map::IntoIter<B256, PooledTransaction> {
+        let mut transactions = self.transactions.lock().unwrap();
+        self.in_flight.lock().unwrap().extend(
+            transactions
+                .values()
+                .filter_map(PooledTransaction::sender_nonce),
+        );
+
+        std::mem::take(&mut *transactions).into_iter()
+    }
+
+    /// Forgets the transactions taken out of the mem-pool, once the block they were taken for is
+    /// committed and its state reflects them.
+    pub fn settle(&self) {
+        self.in_flight.lock().unwrap().clear();
+    }
+
+    /// Takes all transactions out of the mem-pool in the order they are included in a block with
+    /// `base_fee`.
+    ///
+    /// The transactions of each sender follow their nonces, while the next transaction of the
+    /// sender paying the highest effective tip per gas goes first, same as in geth. Ties are broken
+    /// by the transaction hash to keep the order deterministic.
+    ///
+    /// A transaction whose max fee per gas is below `base_fee` cannot pay for its gas, so it stays
+    /// in the mem-pool along with the later nonces of its sender until the base fee drops.
+    pub fn drain_by_priority(&self, base_fee: u64) -> Vec<(B256, PooledTransaction)> {
+        let tip = |pooled: &PooledTransaction| pooled.tx.effective_tip_per_gas(base_fee);
+        let mut ordered = Vec::new();
+        let mut by_sender = HashMap::<Address, Vec<_>>::new();
+
+        for (tx_hash, pooled) in self.drain() {
+            match pooled.sender_nonce() {
+                Some((sender, _)) => by_sender.entry(sender).or_default().push((tx_hash, pooled)),
+                None => ordered.push((tx_hash, pooled)),
+            }
+        }
+
+        // Each queue is sorted by descending nonce, so that the next transaction is popped off
+        // its end
+        let mut queues = by_sender
+            .into_values()
+            .map(|mut queue| {
+                queue.sort_by_key(|(_, pooled)| Reverse(pooled.sender_nonce()));
+                queue
+            })
+            .collect::<Vec<_>>();
+        let mut heads = queues
+            .iter()
+            .enumerate()
+            .filter_map(|(index, queue)| {
+                let (tx_hash, pooled) = queue.last()?;
+                Some((tip(pooled)?, Reverse(*tx_hash), index))
+            })
+            .collect::<BinaryHeap<_>>();
+
+        while let Some((_, _, index)) = heads.pop() {
+            let next = queues[index]
+                .pop()
+                .expect("Queue of a head should not be empty");
+            ordered.push(next);
+
+            if let Some((tx_hash, pooled)) = queues[index].last() {
+                if let Some(tip) = tip(pooled) {
+                    heads.push((tip, Reverse(*tx_hash), index));
+                }
+            }
+        }
+
+        // Whatever is left of each queue is behind a transaction that does not cover the base fee
+        self.transactions
+            .lock()
+            .unwrap()
+            .extend(queues.into_iter().flatten());
+
+        ordered
+    }
+
+    /// Returns the nonce of `address` once its transactions in the mem-pool are included, given
+    /// its `nonce` in the latest block.
+    ///
+    /// Only transactions that follow `nonce` without a gap are counted, as the ones after a gap
+    /// cannot be included until it is filled. The transactions in flight to a block that is not
+    /// committed yet are counted too, as `nonce` does not reflect them yet.
+    pub fn pending_nonce(&self, address: Address, nonce: u64) -> u64 {
+        let pooled_nonces = self
+            .transactions
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|pooled| match &pooled.normalized {
+                NormalizedExtendedTxEnvelope::Canonical(tx) if tx.signer == address => {
+                    Some(tx.nonce)
+                }
+                _ => None,
+            })
+            .chain(
+                self.in_flight
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|(sender, _)| *sender == address)
+                    .map(|(_, nonce)| *nonce),
+            )
+            .collect::<HashSet<_>>();
+
+        let mut pending_nonce = nonce;
+        while pooled_nonces.contains(&pending_nonce) {
+            pending_nonce += 1;
+        }
+        pending_nonce
+    }
+}
+
+/// A transaction is left out of the [`MemPool`], as it does not raise the fees of the transaction
+/// with the same sender and nonce enough to replace it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplacementUnderpriced;
+
+/// A transaction along with everything derived from it that does not depend on the block it ends
+/// up in.
+///
+/// Transactions sent to the node are prepared once when they enter the [`MemPool`], so that block
+/// building neither encodes them to size their L1 data nor recovers their senders.
+#[derive(Debug, Clone)]
+pub struct PooledTransaction {
+    pub tx: OpTxEnvelope,
+    /// The transaction with its sender recovered.
+    pub normalized: NormalizedExtendedTxEnvelope,
+    /// Sizes of the encoded transaction, which its L1 data fee is charged for.
+    pub l1_fee_input: L1GasFeeInput,
+}
+
+impl PooledTransaction {
+    /// Prepares `tx` with the given `l1_fee_input`, failing if its sender cannot be recovered.
+    pub fn new(tx: OpTxEnvelope, l1_fee_input: L1GasFeeInput) -> Result<Self> {
+        let normalized = tx.clone().try_into()?;
+
+        Ok(Self {
+            tx,
+            normalized,
+            l1_fee_input,
+        })
+    }
+
+    /// The sender and nonce of the transaction, which deposits have none of.
+    pub fn sender_nonce(&self) -> Option<(Address, u64)> {
+        match &self.normalized {
+            NormalizedExtendedTxEnvelope::Canonical(tx) => Some((tx.signer, tx.nonce)),
+            NormalizedExtendedTxEnvelope::DepositedTx(_) => None,
+        }
+    }
+
+    /// Whether `tx` offers enough to replace this transaction, which is both its max fee and max
+    /// priority fee per gas raised by at least `price_bump_percent`.
+    pub fn is_replaceable_by(&self, tx: &impl Transaction, price_bump_percent: u64) -> bool {
+        let bumps = |old: u128, new: u128| {
+            new.saturating_mul(100) >= old.saturating_mul(100 + u128::from(price_bump_percent))
+        };
+
+        bumps(self.tx.max_fee_per_gas(), tx.max_fee_per_gas())
+            && bumps(self.tx.priority_fee_or_price(), tx.priority_fee_or_price())
+    }
+
+    /// Prepares a transaction sent to the node, sizing its L1 data from its encoding.
+    pub fn from_eth_envelope(tx: TxEnvelope) -> Result<Self> {
+        let mut encoded = Vec::new();
+        tx.encode(&mut encoded);
+        let tx = OpTxEnvelope::try_from_eth_envelope(tx)
+            .map_err(|_| InvalidTransactionCause::UnsupportedType)?;
+
+        Self::new(tx, encoded.as_slice().into())
+    }
+}
@@ -0,0 +1,38 @@
+use {
+    crate::{Application, BlockExecutionObserver, Dependencies},
+    moved_blockchain::{
+        block::ExtendedBlock,
+        in_memory::SharedMemory,
+        receipt::{ReadReceiptMemory, ReceiptMemory},
+        transaction::ReadTransactionMemory,
+    },
+};
+
+/// Number of blocks between two reports of the [`MemoryUsageReporter`].
+pub const MEMORY_USAGE_INTERVAL: u64 = 64;
+
+/// Reports how many transactions and receipts the in-memory stores hold, and of how many blocks
+/// they have evicted them, once every [`MEMORY_USAGE_INTERVAL`] blocks.
+#[derive(Debug, Default)]
+pub struct MemoryUsageReporter;
+
+impl<D> BlockExecutionObserver<D> for MemoryUsageReporter
+where
+    D: Dependencies<SharedStorage = SharedMemory, ReceiptStorage = ReceiptMemory>,
+{
+    fn on_block(&self, app: &mut Application<D>, block: &ExtendedBlock) {
+        if block.block.header.number % MEMORY_USAGE_INTERVAL != 0 {
+            return;
+        }
+        let transactions = &app.storage.transaction_memory;
+        let receipts = &app.receipt_memory;
+
+        println!(
+            "INFO: memory holds {} transactions and {} receipts, evicted those of {} and {} blocks",
+            transactions.count(),
+            receipts.count(),
+            transactions.evicted_blocks(),
+            receipts.evicted_blocks(),
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use {
+    moved_genesis::config::ConcurrencyParameters,
+    rayon::{ThreadPool, ThreadPoolBuilder, prelude::*},
+    std::sync::Arc,
+};
+
+/// The threads that block building hands work to.
+///
+/// They are started once along with the node and kept for its whole life, instead of being spawned
+/// anew for every block. A single thread means no pool at all, the work is done on the calling
+/// thread instead.
+#[derive(Debug, Clone)]
+pub struct Workers {
+    sender_recovery: Option<Arc<ThreadPool>>,
+    trie_hashing: Option<Arc<ThreadPool>>,
+}
+
+impl Workers {
+    pub fn new(parameters: &ConcurrencyParameters) -> Self {
+        Self {
+            sender_recovery: pool(parameters.sender_recovery_threads, "sender-recovery"),
+            trie_hashing: pool(parameters.trie_hashing_threads, "trie-hashing"),
+        }
+    }
+
+    /// Maps each of the `items` with `f` on the sender recovery threads, keeping their order.
+    pub(crate) fn recover_senders<T: Sync, U: Send>(
+        &self,
+        items: &[T],
+        f: impl Fn(&T) -> U + Sync + Send,
+    ) -> Vec<U> {
+        match &self.sender_recovery {
+            Some(pool) => pool.install(|| items.par_iter().map(f).collect()),
+            None => items.iter().map(f).collect(),
+        }
+    }
+
+    /// Runs `a` and `b` at the same time on the trie hashing threads, returning both results.
+    pub(crate) fn hash_tries<A: Send, B: Send>(
+        &self,
+        a: impl FnOnce() -> A + Send,
+        b: impl FnOnce() -> B + Send,
+    ) -> (A, B) {
+        match &self.trie_hashing {
+            Some(pool) => pool.join(a, b),
+            None => (a(), b()),
+        }
+    }
+}
+
+fn pool(threads: usize, name: &'static str) -> Option<Arc<ThreadPool>> {
+    if threads <= 1 {
+        return None;
+    }
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(move |index| format!("{name}-{index}"))
+        .build()
+        .unwrap_or_else(|e| panic!("ERROR: failed to start the {name} threads {e:?}"));
+
+    Some(Arc::new(pool))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, test_case::test_case};
+
+    #[test_case(1; "single thread")]
+    #[test_case(3; "several threads")]
+    #[test_case(64; "more threads than items")]
+    fn test_recovery_keeps_order_of_items(threads: usize) {
+        let workers = Workers::new(&ConcurrencyParameters {
+            sender_recovery_threads: threads,
+            trie_hashing_threads: 1,
+        });
+        let items: Vec<u64> = (0..10).collect();
+
+        let mapped = workers.recover_senders(&items, |item| item * 2);
+
+        assert_eq!(mapped, (0..10).map(|item| item * 2).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,252 @@
+use {
+    crate::{Application, ApplicationReader, Dependencies, command::MAX_BLOCK_TIME_SECS},
+    alloy::eips::BlockNumberOrTag::Latest,
+    moved_blockchain::{
+        block::{BlockRepository, ExtendedBlock},
+        payload::{PayloadId, PayloadResponse},
+    },
+    moved_shared::primitives::B256,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicU64, Ordering},
+        },
+        time::{Duration, Instant, SystemTime},
+    },
+};
+
+/// Number of block times after its timestamp that a payload can still be fetched for.
+///
+/// The op-node fetches a payload right before its timestamp, so a payload this old was left behind
+/// by a stalled sequencer and would only be rejected as outdated by the time it is proposed.
+const PAYLOAD_TTL_BLOCKS: u64 = 10;
+
+/// Tracks the payloads built since the node has started until they expire, and counts the ones
+/// that have expired.
+///
+/// The clones of a tracker share their state, so that the expiries seen by the [`Application`] and
+/// by its readers add up.
+#[derive(Debug, Clone, Default)]
+pub struct PayloadExpiry {
+    built: Arc<Mutex<HashMap<PayloadId, BuiltPayload>>>,
+    dropped_jobs: Arc<AtomicU64>,
+    rejected_fetches: Arc<AtomicU64>,
+    unfetched_payloads: Arc<AtomicU64>,
+}
+
+/// How many payloads have expired before being built or fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PayloadExpiryCounts {
+    /// Build jobs that were dropped instead of built, either pending on a restart or waiting too
+    /// long behind other commands.
+    pub dropped_jobs: u64,
+    /// Requests for a payload that was built, but not fetched in time.
+    pub rejected_fetches: u64,
+    /// Payloads removed from storage without ever being fetched.
+    pub unfetched_payloads: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BuiltPayload {
+    block_hash: B256,
+    timestamp: u64,
+    parent_timestamp: u64,
+    fetched: bool,
+}
+
+impl BuiltPayload {
+    fn new(block: &ExtendedBlock, parent_timestamp: u64) -> Self {
+        Self {
+            block_hash: block.hash,
+            timestamp: block.block.header.timestamp,
+            parent_timestamp,
+            fetched: false,
+        }
+    }
+}
+
+impl PayloadExpiry {
+    pub fn counts(&self) -> PayloadExpiryCounts {
+        PayloadExpiryCounts {
+            dropped_jobs: self.dropped_jobs.load(Ordering::Relaxed),
+            rejected_fetches: self.rejected_fetches.load(Ordering::Relaxed),
+            unfetched_payloads: self.unfetched_payloads.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn record_dropped_job(&self, id: PayloadId) {
+        let dropped = self.dropped_jobs.fetch_add(1, Ordering::Relaxed) + 1;
+        println!("WARN: payload job {id:#x} expired before it was built, {dropped} dropped so far");
+    }
+
+    pub fn record_rejected_fetch(&self, id: PayloadId) {
+        let rejected = self.rejected_fetches.fetch_add(1, Ordering::Relaxed) + 1;
+        println!("WARN: payload {id:#x} expired before it was fetched, {rejected} rejected so far");
+    }
+
+    pub fn record_fetch(&self, id: PayloadId) {
+        if let Some(payload) = self.built.lock().unwrap().get_mut(&id) {
+            payload.fetched = true;
+        }
+    }
+
+    fn track(&self, id: PayloadId, payload: BuiltPayload) {
+        self.built.lock().unwrap().insert(id, payload);
+    }
+
+    /// Stops tracking the payloads that have expired by `now`, returning their IDs along with the
+    /// hashes of their blocks.
+    fn take_expired(&self, now: u64) -> Vec<(PayloadId, B256)> {
+        let mut expired = Vec::new();
+        self.built.lock().unwrap().retain(|id, payload| {
+            if !is_expired(payload.timestamp, payload.parent_timestamp, now) {
+                return true;
+            }
+            if !payload.fetched {
+                let unfetched = self.unfetched_payloads.fetch_add(1, Ordering::Relaxed) + 1;
+                println!("WARN: payload {id:#x} expired unfetched, {unfetched} unfetched so far");
+            }
+            expired.push((*id, payload.block_hash));
+            false
+        });
+        expired
+    }
+}
+
+/// Whether a payload with `timestamp` on top of a parent with `parent_timestamp` has expired once
+/// the chain has reached `now`.
+///
+/// The time to live is measured in the time between the payload and its parent, so that it scales
+/// with the block time of the chain.
+pub(crate) fn is_expired(timestamp: u64, parent_timestamp: u64, now: u64) -> bool {
+    now > timestamp.saturating_add(ttl_secs(timestamp, parent_timestamp))
+}
+
+fn ttl_secs(timestamp: u64, parent_timestamp: u64) -> u64 {
+    let block_time = timestamp
+        .saturating_sub(parent_timestamp)
+        .clamp(1, MAX_BLOCK_TIME_SECS);
+
+    PAYLOAD_TTL_BLOCKS * block_time
+}
+
+impl<D: Dependencies> ApplicationReader<D> {
+    /// Whether `payload` has expired by the timestamp of the latest block.
+    pub fn is_payload_expired(&self, payload: &PayloadResponse) -> bool {
+        let Some(latest) = self.block_by_height(Latest, false) else {
+            return false;
+        };
+        let timestamp = payload.execution_payload.timestamp.to::<u64>();
+        let parent_timestamp = self
+            .block_by_hash(payload.execution_payload.parent_hash, false)
+            .map_or(timestamp, |parent| parent.block.header.timestamp);
+
+        is_expired(timestamp, parent_timestamp, latest.block.header.timestamp)
+    }
+}
+
+impl<D: Dependencies> Application<D> {
+    /// Whether a job to build a payload with `timestamp` on top of the latest block has expired.
+    ///
+    /// Jobs are only left pending while the node is down, during which the chain does not advance,
+    /// so they are measured against the current time instead.
+    pub(crate) fn is_job_expired(&self, timestamp: u64) -> bool {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Current system time should be available")
+            .as_secs();
+
+        is_expired(timestamp, self.latest_block().block.header.timestamp, now)
+    }
+
+    /// Whether a job to build a payload with `timestamp`, which was requested at `requested_at`,
+    /// has waited for longer than the payload would live.
+    ///
+    /// The op-node has moved on by then, so building the payload would only hold up the jobs
+    /// queued behind it.
+    pub(crate) fn has_job_waited_too_long(&self, timestamp: u64, requested_at: Instant) -> bool {
+        let ttl = ttl_secs(timestamp, self.latest_block().block.header.timestamp);
+
+        requested_at.elapsed() > Duration::from_secs(ttl)
+    }
+
+    /// Tracks the payload built under `id` as `block`, until it expires.
+    pub(crate) fn track_payload(
+        &self,
+        id: PayloadId,
+        block: &ExtendedBlock,
+        parent_timestamp: u64,
+    ) {
+        self.payload_expiry
+            .track(id, BuiltPayload::new(block, parent_timestamp));
+    }
+
+    /// Tracks the payloads built before the node has started that have not expired by the latest
+    /// block yet.
+    ///
+    /// The payloads that expired earlier were removed while the node was running. Whether the
+    /// tracked ones were fetched is not known anymore, so they are not counted once they expire.
+    pub(crate) fn track_recent_payloads(&mut self) {
+        let latest = self.latest_block();
+        let now = latest.block.header.timestamp;
+        let mut block = latest;
+        // A payload lives for at most `PAYLOAD_TTL_BLOCKS` of the longest block time, so the ones
+        // built before that have all expired
+        while block.block.header.number > 0
+            && now <= block.block.header.timestamp + PAYLOAD_TTL_BLOCKS * MAX_BLOCK_TIME_SECS
+        {
+            let Some(parent) = self
+                .block_repository
+                .by_hash(&self.storage, block.block.header.parent_hash)
+                .unwrap()
+            else {
+                break;
+            };
+            self.payload_expiry.track(
+                block.payload_id,
+                BuiltPayload {
+                    fetched: true,
+                    ..BuiltPayload::new(&block, parent.block.header.timestamp)
+                },
+            );
+            block = parent;
+        }
+    }
+
+    /// Removes the payloads that have expired by the latest block from storage, so that they can no
+    /// longer be fetched by their IDs.
+    pub(crate) fn remove_expired_payloads(&mut self) {
+        let now = self.latest_block().block.header.timestamp;
+        for (id, block_hash) in self.payload_expiry.take_expired(now) {
+            self.block_repository
+                .remove_payload_id(&mut self.storage, id, block_hash)
+                .unwrap();
+        }
+    }
+
+    fn latest_block(&self) -> ExtendedBlock {
+        self.block_repository
+            .latest(&self.storage)
+            .unwrap()
+            .expect("Parent block should exist")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_expires_after_ttl_in_block_times() {
+        assert!(!is_expired(1_002, 1_000, 1_022));
+        assert!(is_expired(1_002, 1_000, 1_023));
+    }
+
+    #[test]
+    fn test_payload_ttl_is_bounded_for_old_parent() {
+        assert!(!is_expired(1_000_000, 0, 1_000_120));
+        assert!(is_expired(1_000_000, 0, 1_000_121));
+        assert!(is_expired(1_000, 1_000, 1_011));
+    }
+}
@@ -0,0 +1,22 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// Number of peers that the node is connected to over its P2P network, which is zero unless the
+/// network is enabled.
+///
+/// The clones of a counter share their count, so that the network updates the count that the
+/// readers report.
+#[derive(Debug, Clone, Default)]
+pub struct PeerCount(Arc<AtomicU64>);
+
+impl PeerCount {
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, peers: u64) {
+        self.0.store(peers, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,61 @@
+use {
+    crate::{Application, BlockExecutionObserver, Dependencies},
+    move_core_types::effects::ChangeSet,
+    move_table_extension::TableChangeSet,
+    moved_evm_ext::state::StorageTriesChanges,
+    moved_state::preimage::{self, Preimage, TriePreimages},
+    std::sync::{Arc, Mutex},
+};
+
+/// Records the preimages of the trie keys written by each block, so that debug tooling can tell
+/// which resource, module, account or storage slot a trie key stands for.
+///
+/// Every written key costs an extra write, so recording is meant for nodes whose state gets
+/// inspected. A failure to store the preimages is reported without stopping block production.
+#[derive(Debug)]
+pub struct TriePreimageRecorder<P> {
+    store: P,
+    preimages: Mutex<Vec<Preimage>>,
+}
+
+impl<P> TriePreimageRecorder<P> {
+    pub fn new(store: P) -> Self {
+        Self {
+            store,
+            preimages: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<D: Dependencies, P: TriePreimages + Send + Sync> BlockExecutionObserver<D>
+    for TriePreimageRecorder<P>
+{
+    fn on_tx(&self, _app: &mut Application<D>, changes: &Arc<ChangeSet>) {
+        self.preimages
+            .lock()
+            .unwrap()
+            .extend(preimage::state_preimages(changes));
+    }
+
+    fn on_tx_tables(&self, _app: &mut Application<D>, changes: &TableChangeSet) {
+        self.preimages
+            .lock()
+            .unwrap()
+            .extend(preimage::table_preimages(changes));
+    }
+
+    fn on_tx_evm_storage(&self, _app: &mut Application<D>, changes: &StorageTriesChanges) {
+        self.preimages
+            .lock()
+            .unwrap()
+            .extend(preimage::storage_preimages(changes));
+    }
+
+    fn on_tx_batch(&self, _app: &mut Application<D>) {
+        let preimages = std::mem::take(&mut *self.preimages.lock().unwrap());
+
+        if let Err(e) = self.store.extend(preimages) {
+            println!("WARN: failed to store trie key preimages: {e:?}");
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use {
+    crate::{Application, BlockExecutionObserver, Dependencies},
+    moved_blockchain::{block::ExtendedBlock, state::InMemoryStateQueries},
+};
+
+/// Number of blocks between two prunings of the state trie.
+pub const STATE_PRUNING_INTERVAL: u64 = 64;
+
+/// Removes the state trie nodes of the blocks below the horizon of the
+/// [`StateHistoryParameters`], so the memory held by the state stays bounded.
+///
+/// Pruning walks the state of every retained block, so it only runs once every
+/// [`STATE_PRUNING_INTERVAL`] blocks. Nothing is pruned if the whole history is retained.
+///
+/// [`StateHistoryParameters`]: moved_genesis::config::StateHistoryParameters
+#[derive(Debug, Default)]
+pub struct StatePruner;
+
+impl<D: Dependencies<StateQueries = InMemoryStateQueries>> BlockExecutionObserver<D>
+    for StatePruner
+{
+    fn on_block(&self, app: &mut Application<D>, block: &ExtendedBlock) {
+        let state_history = &app.genesis_config.state_history;
+        let height = block.block.header.number;
+        if state_history.retained_blocks.is_none() || height % STATE_PRUNING_INTERVAL != 0 {
+            return;
+        }
+        let horizon = state_history.horizon(height);
+
+        let removed = app.state_queries.prune(horizon);
+        println!("INFO: pruned {removed} state trie nodes below block {horizon}");
+    }
+}
@@ -1,51 +1,211 @@
 use {
-    crate::{ApplicationReader, Dependencies, block_hash::StorageBasedProvider},
+    crate::{
+        ApplicationReader, Dependencies, PooledTransaction, StateChanges,
+        block_hash::StorageBasedProvider, gas_price::block_rewards, inclusion::InclusionProof,
+    },
     alloy::{
         eips::{
             BlockId,
             BlockNumberOrTag::{self, Earliest, Finalized, Latest, Number, Pending, Safe},
         },
-        rpc::types::{FeeHistory, TransactionRequest},
+        primitives::{KECCAK_EMPTY, keccak256},
+        rlp::Encodable,
+        rpc::types::{BlockTransactions, FeeHistory, Filter, Log, TransactionRequest},
+    },
+    aptos_types::transaction::EntryFunction,
+    move_core_types::{
+        account_address::AccountAddress,
+        effects::ChangeSet,
+        ident_str,
+        identifier::Identifier,
+        language_storage::{ModuleId, StructTag, TypeTag},
+        value::{MoveTypeLayout, MoveValue},
+    },
+    move_table_extension::{TableChangeSet, TableHandle, TableResolver},
+    move_vm_types::{
+        resolver::{ModuleResolver, ResourceResolver},
+        value_serde::ValueSerDeContext,
+        values::VMValueCast,
     },
     moved_blockchain::{
-        block::{BlockQueries, BlockResponse},
+        block::{
+            BaseGasFee, BlockQueries, BlockResponse, Eip1559GasFee, L1Origin, RejectedTransaction,
+        },
         payload::{PayloadId, PayloadQueries, PayloadResponse},
-        receipt::{ReceiptQueries, TransactionReceipt},
-        state::{ProofResponse, StateQueries},
+        receipt::{ReceiptQueries, TransactionReceipt, VmStatus},
+        state::{AccountView, ProofResponse, StateQueries},
         transaction::{TransactionQueries, TransactionResponse},
     },
-    moved_execution::simulate::{call_transaction, simulate_transaction},
+    moved_evm_ext::{
+        CODE_LAYOUT, EVM_NATIVE_ADDRESS, HeaderForExecution,
+        state::{self, Account, StorageTrieRepository, StorageTriesChanges, StorageTriesOverlay},
+        type_utils::{account_info_struct_tag, code_hash_struct_tag},
+    },
+    moved_execution::{
+        CanonicalExecutionInput, ChangesOverlay, CreateL1GasFee, CreateL2GasFee,
+        DepositExecutionInput, EventLayouts, L1GasFee, L1GasFeeInput, L2GasFeeInput,
+        TransactionTrace, execute_transaction,
+        simulate::{annotate_value, call_transaction, simulate_transaction, view_function},
+        trace_transaction,
+        transaction::{MoveEvent, NormalizedExtendedTxEnvelope, TransactionExecutionOutcome},
+    },
+    moved_genesis::BridgedToken,
     moved_shared::{
-        error::Result,
-        primitives::{Address, B256, ToMoveAddress, U256},
+        error::{Error, Result, UserError},
+        primitives::{Address, B256, ToMoveAddress, U256, with_primitive_logs},
+    },
+    op_alloy::consensus::{OpReceiptEnvelope, OpTxEnvelope},
+    std::{
+        collections::{HashMap, btree_map::Entry},
+        sync::LazyLock,
     },
 };
 
+/// Most blocks covered by a single fee history, same as in geth.
+const MAX_FEE_HISTORY_BLOCKS: u64 = 1_024;
+
+/// The effects of a transaction executed on the state of a block, without committing them.
+#[derive(Debug)]
+pub struct Simulation {
+    pub gas_used: u64,
+    /// Why the transaction failed, or `Ok` if it succeeded.
+    pub vm_outcome: std::result::Result<(), UserError>,
+    /// The emitted Move events, along with their values decoded as their types.
+    pub events: Vec<SimulatedEvent>,
+    /// The logs of the transaction as its receipt would have them, which include the logs of EVM
+    /// contracts along with the Move events converted to logs.
+    pub logs: Vec<alloy::primitives::Log>,
+    /// The resources and modules written by the transaction.
+    pub changes: ChangeSet,
+}
+
+/// A Move event emitted by a simulated transaction.
+#[derive(Debug)]
+pub struct SimulatedEvent {
+    pub event: MoveEvent,
+    /// The event with the layout of its type, or `None` if its type is not defined before the
+    /// transaction.
+    pub value: Option<(MoveValue, MoveTypeLayout)>,
+}
+
+/// A Move value along with its type, such as one returned by a view function.
+#[derive(Debug)]
+pub struct ViewValue {
+    pub type_tag: TypeTag,
+    pub bytes: Vec<u8>,
+    /// The value with the layout of its type, or `None` if the bytes do not decode as the type.
+    pub value: Option<(MoveValue, MoveTypeLayout)>,
+}
+
+/// A Move resource stored under an account.
+#[derive(Debug)]
+pub struct ResourceValue {
+    pub tag: StructTag,
+    pub bytes: Vec<u8>,
+    /// The value with the layout of its type, or `None` if the bytes do not decode as the type.
+    pub value: Option<(MoveValue, MoveTypeLayout)>,
+}
+
+/// The key of a table item does not decode as the key type of the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndecodableTableKey;
+
+/// The reason the state of a block cannot be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateUnavailable {
+    /// The state of the block at `height` is no longer kept, as it is below the `horizon`.
+    Pruned { height: u64, horizon: u64 },
+    /// The block with `hash` is not canonical, and the state is only kept for canonical blocks.
+    ///
+    /// `require_canonical` tells whether the request only asked for a canonical block.
+    NotCanonical { hash: B256, require_canonical: bool },
+}
+
+static ACCOUNT_STRUCT_TAG: LazyLock<StructTag> = LazyLock::new(|| StructTag {
+    address: AccountAddress::ONE,
+    module: ident_str!("account").into(),
+    name: ident_str!("Account").into(),
+    type_args: Vec::new(),
+});
+
 impl<D: Dependencies> ApplicationReader<D> {
     pub fn chain_id(&self) -> u64 {
         self.genesis_config.chain_id
     }
 
-    pub fn balance_by_height(&self, address: Address, height: BlockNumberOrTag) -> Option<U256> {
+    pub fn bridged_tokens(&self) -> &[BridgedToken] {
+        &self.genesis_config.token_list
+    }
+
+    /// Address of the Move fungible asset metadata of `token`, if genesis created it.
+    pub fn bridged_token_metadata(&self, token: &BridgedToken) -> Option<AccountAddress> {
+        self.genesis_config
+            .token_metadata
+            .then(|| moved_genesis::token_metadata_address(token))
+    }
+
+    pub fn balance_by_height(&self, address: Address, height: impl Into<BlockId>) -> Option<U256> {
         self.state_queries.balance_at(
             &self.evm_storage,
             address.to_move_address(),
-            self.resolve_height(height)?,
+            self.retained_height(self.height_from_block_id(height)?)?,
+        )
+    }
+
+    /// Reads the nonce of `address` at `height`.
+    ///
+    /// The `pending` nonce also counts the transactions of `address` waiting in the mem-pool, so
+    /// that wallets do not reuse their nonces.
+    pub fn nonce_by_height(&self, address: Address, height: impl Into<BlockId>) -> Option<u64> {
+        let height = height.into();
+        let nonce = self.state_queries.nonce_at(
+            &self.evm_storage,
+            address.to_move_address(),
+            self.retained_height(self.height_from_block_id(height)?)?,
+        )?;
+
+        Some(match height {
+            BlockId::Number(Pending) => self.mem_pool.pending_nonce(address, nonce),
+            _ => nonce,
+        })
+    }
+
+    /// Reads the balance, nonce and code of `address` at `height`, which every address has even
+    /// if nothing was ever stored for it.
+    pub fn account_by_height(
+        &self,
+        address: Address,
+        height: impl Into<BlockId>,
+    ) -> Option<AccountView> {
+        self.state_queries.account_at(
+            &self.evm_storage,
+            address.to_move_address(),
+            self.retained_height(self.height_from_block_id(height)?)?,
         )
     }
 
-    pub fn nonce_by_height(&self, address: Address, height: BlockNumberOrTag) -> Option<u64> {
-        self.state_queries.nonce_at(
+    /// Reads the value of the EVM storage slot at `index` of `address` at `height`.
+    pub fn storage_by_height(
+        &self,
+        address: Address,
+        index: U256,
+        height: impl Into<BlockId>,
+    ) -> Option<U256> {
+        self.state_queries.storage_at(
             &self.evm_storage,
             address.to_move_address(),
-            self.resolve_height(height)?,
+            index,
+            self.retained_height(self.height_from_block_id(height)?)?,
         )
     }
 
     pub fn block_by_hash(&self, hash: B256, include_transactions: bool) -> Option<BlockResponse> {
-        self.block_queries
-            .by_hash(&self.storage, hash, include_transactions)
-            .unwrap()
+        self.responses
+            .block_by_hash(hash, include_transactions, || {
+                self.block_queries
+                    .by_hash(&self.storage, hash, include_transactions)
+                    .unwrap()
+            })
     }
 
     pub fn block_by_height(
@@ -53,27 +213,226 @@ impl<D: Dependencies> ApplicationReader<D> {
         height: BlockNumberOrTag,
         include_transactions: bool,
     ) -> Option<BlockResponse> {
+        let height = self.resolve_height(height)?;
+
+        self.responses.block_by_height(
+            height,
+            include_transactions,
+            self.is_finalized(height),
+            || {
+                self.block_queries
+                    .by_height(&self.storage, height, include_transactions)
+                    .unwrap()
+            },
+        )
+    }
+
+    pub fn rejected_transactions(
+        &self,
+        height: BlockNumberOrTag,
+    ) -> Option<Vec<RejectedTransaction>> {
         self.block_queries
-            .by_height(
-                &self.storage,
-                self.resolve_height(height)?,
-                include_transactions,
-            )
+            .rejected_transactions(&self.storage, self.resolve_height(height)?)
             .unwrap()
     }
 
+    pub fn transaction_count_by_hash(&self, hash: B256) -> Option<u64> {
+        self.block_queries
+            .transaction_count_by_hash(&self.storage, hash)
+            .unwrap()
+    }
+
+    pub fn transaction_count_by_height(&self, height: BlockNumberOrTag) -> Option<u64> {
+        self.block_queries
+            .transaction_count_by_height(&self.storage, self.resolve_height(height)?)
+            .unwrap()
+    }
+
+    pub fn l1_origin(&self, height: BlockNumberOrTag) -> Option<L1Origin> {
+        self.block_by_height(height, false)?.l1_origin
+    }
+
     pub fn block_number(&self) -> u64 {
         self.block_queries.latest(&self.storage).unwrap().unwrap()
     }
 
+    /// Returns the lowest height whose state can still be queried.
+    pub fn state_horizon(&self) -> u64 {
+        self.genesis_config
+            .state_history
+            .horizon(self.block_number())
+    }
+
+    /// Fails if the state of the block `id` is below the [`Self::state_horizon`], or if `id` is
+    /// the hash of a block that is not canonical.
+    ///
+    /// An unknown block is not reported here, but by the query of its state.
+    pub fn ensure_state_retained(
+        &self,
+        id: impl Into<BlockId>,
+    ) -> std::result::Result<(), StateUnavailable> {
+        let id = id.into();
+        if let BlockId::Hash(h) = id {
+            let is_forked = self
+                .block_queries
+                .by_hash(&self.storage, h.block_hash, false)
+                .ok()
+                .flatten()
+                .is_some_and(|block| !self.is_canonical(h.block_hash, block.block.header.number));
+            if is_forked {
+                return Err(StateUnavailable::NotCanonical {
+                    hash: h.block_hash,
+                    require_canonical: h.require_canonical.unwrap_or(false),
+                });
+            }
+        }
+        let Some(height) = self.height_from_block_id(id) else {
+            return Ok(());
+        };
+        let horizon = self.state_horizon();
+
+        if height < horizon {
+            return Err(StateUnavailable::Pruned { height, horizon });
+        }
+
+        Ok(())
+    }
+
+    /// Reads the base fees, gas usage and optionally the tips at `reward_percentiles` of at most
+    /// `block_count` blocks up to the block at `block_number`.
+    ///
+    /// Returns `None` if the block at `block_number` is not known.
     pub fn fee_history(
         &self,
-        _block_count: u64,
-        _block_number: BlockNumberOrTag,
-        _reward_percentiles: Option<Vec<f64>>,
-    ) -> FeeHistory {
-        // TODO: Respond with a real fee history
-        FeeHistory::default()
+        block_count: u64,
+        block_number: BlockNumberOrTag,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> Option<FeeHistory> {
+        let newest = self.resolve_known_height(block_number)?;
+        let block_count = block_count.min(MAX_FEE_HISTORY_BLOCKS).min(newest + 1);
+        let mut history = FeeHistory {
+            oldest_block: newest + 1 - block_count,
+            reward: reward_percentiles.as_ref().map(|_| Vec::new()),
+            ..Default::default()
+        };
+        if block_count == 0 {
+            return Some(history);
+        }
+
+        let mut last_header = None;
+        for height in history.oldest_block..=newest {
+            let block = self
+                .block_queries
+                .by_height(&self.storage, height, false)
+                .ok()??;
+            let header = block.block.header;
+            history
+                .base_fee_per_gas
+                .push(u128::from(header.base_fee_per_gas.unwrap_or_default()));
+            history.gas_used_ratio.push(if header.gas_limit == 0 {
+                0.0
+            } else {
+                header.gas_used as f64 / header.gas_limit as f64
+            });
+            if let (Some(percentiles), Some(rewards)) = (&reward_percentiles, &mut history.reward) {
+                let tips = self.block_tips(header.hash);
+                rewards.push(block_rewards(percentiles, tips));
+            }
+            last_header = Some(header);
+        }
+
+        // The history also holds the base fee of the block after the newest one
+        let last_header = last_header?;
+        let gas_fee = Eip1559GasFee::new(
+            self.genesis_config.fees.eip1559_elasticity_multiplier,
+            U256::from(
+                self.genesis_config
+                    .fees
+                    .eip1559_base_fee_max_change_denominator,
+            ),
+        );
+        let next_base_fee = gas_fee.base_fee_per_gas(
+            last_header.gas_limit,
+            last_header.gas_used,
+            U256::from(last_header.base_fee_per_gas.unwrap_or_default()),
+        );
+        history.base_fee_per_gas.push(next_base_fee.saturating_to());
+
+        Some(history)
+    }
+
+    /// Suggests a priority fee from the tips paid in the most recent blocks.
+    pub fn max_priority_fee_per_gas(&self) -> u128 {
+        let latest = self.block_number();
+        let first = latest.saturating_sub(self.gas_price_oracle.blocks() - 1);
+        let blocks = (first..=latest).filter_map(|height| {
+            let block = self
+                .block_queries
+                .by_height(&self.storage, height, false)
+                .ok()??;
+            Some((height, block.block.header.hash))
+        });
+
+        self.gas_price_oracle.suggest_priority_fee(blocks, |hash| {
+            self.block_tips(hash)
+                .into_iter()
+                .map(|(tip, _)| tip)
+                .collect()
+        })
+    }
+
+    /// Suggests a gas price for legacy transactions, the latest base fee with a suggested tip.
+    pub fn gas_price(&self) -> u128 {
+        let base_fee = self
+            .block_by_height(Latest, false)
+            .and_then(|block| block.block.header.base_fee_per_gas)
+            .unwrap_or_default();
+
+        u128::from(base_fee) + self.max_priority_fee_per_gas()
+    }
+
+    /// Tips paid per gas by the transactions of the block `hash`, paired with the gas they used.
+    ///
+    /// Deposits are left out, as they pay no tip.
+    fn block_tips(&self, hash: B256) -> Vec<(u128, u64)> {
+        let Some(block) = self.block_by_hash(hash, false) else {
+            return Vec::new();
+        };
+        let base_fee = u128::from(block.block.header.base_fee_per_gas.unwrap_or_default());
+
+        self.receipt_queries
+            .by_block_hash(&self.receipt_memory, hash)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|receipt| !matches!(receipt.inner.inner, OpReceiptEnvelope::Deposit(_)))
+            .map(|receipt| {
+                let tip = receipt.inner.effective_gas_price.saturating_sub(base_fee);
+                (tip, receipt.inner.gas_used)
+            })
+            .collect()
+    }
+
+    /// Estimates the fee for publishing the encoded transaction `tx_data` to L1.
+    ///
+    /// The fee is priced with the L1 attributes of the latest block, and is zero until a block
+    /// carrying them is produced.
+    pub fn l1_fee(&self, tx_data: &[u8]) -> U256 {
+        let Some(block) = self.block_by_height(BlockNumberOrTag::Latest, true) else {
+            return U256::ZERO;
+        };
+
+        // https://github.com/ethereum-optimism/specs/blob/9dbc6b0/specs/protocol/deposits.md#kinds-of-deposited-transactions
+        block
+            .block
+            .transactions
+            .as_transactions()
+            .and_then(|transactions| transactions.first())
+            .and_then(|tx| tx.inner.inner.as_deposit())
+            .map_or(U256::ZERO, |tx| {
+                self.l1_fee
+                    .for_deposit(tx.input.as_ref())
+                    .l1_fee(L1GasFeeInput::from(tx_data))
+            })
     }
 
     pub fn estimate_gas(
@@ -81,30 +440,84 @@ impl<D: Dependencies> ApplicationReader<D> {
         transaction: TransactionRequest,
         block_number: BlockNumberOrTag,
     ) -> Result<u64> {
-        let height = self.resolve_height(block_number).unwrap();
-        let block_height = match block_number {
-            Number(height) => height,
-            Finalized | Pending | Latest | Safe => self
-                .block_queries
-                .latest(&self.storage)
-                .unwrap()
-                .expect("Blocks should be non-empty"),
-            Earliest => 0,
+        let transaction = self.cap_gas(transaction);
+        let height = self
+            .resolve_height(block_number)
+            .ok_or(UserError::BlockNotFound(block_number))?;
+        let estimate = || {
+            let block_hash_lookup = StorageBasedProvider::new(&self.storage, &self.block_queries);
+            let outcome = simulate_transaction(
+                transaction.clone(),
+                &self.state_queries.resolver_at(height),
+                &self.evm_storage,
+                &self.genesis_config,
+                &self.base_token,
+                height,
+                &block_hash_lookup,
+            );
+
+            outcome.and_then(|outcome| {
+                // A failing transaction has no estimate, as no gas limit would make it succeed
+                outcome.vm_outcome.map_err(Error::User)?;
+                // Add 33% extra gas as a buffer.
+                Ok(outcome.gas_used + (outcome.gas_used / 3))
+            })
         };
+
+        match self.block_queries.by_height(&self.storage, height, false) {
+            Ok(Some(block)) => {
+                self.gas_estimates
+                    .get_or_estimate(block.block.header.hash, &transaction, estimate)
+            }
+            _ => estimate(),
+        }
+    }
+
+    /// Executes `transaction` on the state of `block_number` like [`Self::call`] does, keeping the
+    /// Move level effects of the execution rather than its output.
+    pub fn simulate(
+        &self,
+        transaction: TransactionRequest,
+        block_number: BlockNumberOrTag,
+    ) -> Result<Simulation> {
+        let transaction = self.cap_gas(transaction);
+        let height = self
+            .resolve_height(block_number)
+            .ok_or(UserError::BlockNotFound(block_number))?;
+        let resolver = self.state_queries.resolver_at(height);
         let block_hash_lookup = StorageBasedProvider::new(&self.storage, &self.block_queries);
         let outcome = simulate_transaction(
             transaction,
-            &self.state_queries.resolver_at(height),
+            &resolver,
             &self.evm_storage,
             &self.genesis_config,
             &self.base_token,
-            block_height,
+            height,
             &block_hash_lookup,
-        );
+        )?;
+
+        let events = outcome
+            .events
+            .into_iter()
+            .map(|event| {
+                let value = annotate_value(
+                    &TypeTag::Struct(Box::new(event.type_tag.clone())),
+                    &event.data,
+                    &resolver,
+                    &self.evm_storage,
+                    &self.genesis_config,
+                    &block_hash_lookup,
+                );
+                SimulatedEvent { event, value }
+            })
+            .collect();
 
-        outcome.map(|outcome| {
-            // Add 33% extra gas as a buffer.
-            outcome.gas_used + (outcome.gas_used / 3)
+        Ok(Simulation {
+            gas_used: outcome.gas_used,
+            vm_outcome: outcome.vm_outcome,
+            events,
+            logs: outcome.logs,
+            changes: outcome.changes.move_vm,
         })
     }
 
@@ -113,7 +526,10 @@ impl<D: Dependencies> ApplicationReader<D> {
         transaction: TransactionRequest,
         block_number: BlockNumberOrTag,
     ) -> Result<Vec<u8>> {
-        let height = self.resolve_height(block_number).unwrap();
+        let transaction = self.cap_gas(transaction);
+        let height = self
+            .resolve_height(block_number)
+            .ok_or(UserError::BlockNotFound(block_number))?;
         let block_hash_lookup = StorageBasedProvider::new(&self.storage, &self.block_queries);
         call_transaction(
             transaction,
@@ -125,9 +541,123 @@ impl<D: Dependencies> ApplicationReader<D> {
         )
     }
 
+    /// Calls the `#[view]` function `function` of `module` on the state of `block_number`, without
+    /// committing any changes, and decodes the values it returns.
+    pub fn view(
+        &self,
+        module: ModuleId,
+        function: Identifier,
+        ty_args: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
+        block_number: BlockNumberOrTag,
+    ) -> Result<Vec<ViewValue>> {
+        let height = self
+            .resolve_height(block_number)
+            .ok_or(UserError::BlockNotFound(block_number))?;
+        let resolver = self.state_queries.resolver_at(height);
+        let block_hash_lookup = StorageBasedProvider::new(&self.storage, &self.block_queries);
+        let return_values = view_function(
+            EntryFunction::new(module, function, ty_args, args),
+            self.gas_cap(),
+            &resolver,
+            &self.evm_storage,
+            &self.genesis_config,
+            &block_hash_lookup,
+        )?;
+
+        Ok(return_values
+            .into_iter()
+            .map(|(type_tag, bytes)| {
+                let value = annotate_value(
+                    &type_tag,
+                    &bytes,
+                    &resolver,
+                    &self.evm_storage,
+                    &self.genesis_config,
+                    &block_hash_lookup,
+                );
+                ViewValue {
+                    type_tag,
+                    bytes,
+                    value,
+                }
+            })
+            .collect())
+    }
+
+    /// Decodes the bcs encoded `bytes` of a Move value of type `type_tag` as defined in the latest
+    /// block, naming the fields of its structs.
+    pub fn annotate_value(
+        &self,
+        type_tag: &TypeTag,
+        bytes: &[u8],
+    ) -> Option<(MoveValue, MoveTypeLayout)> {
+        let height = self.resolve_height(Latest)?;
+        let block_hash_lookup = StorageBasedProvider::new(&self.storage, &self.block_queries);
+        annotate_value(
+            type_tag,
+            bytes,
+            &self.state_queries.resolver_at(height),
+            &self.evm_storage,
+            &self.genesis_config,
+            &block_hash_lookup,
+        )
+    }
+
+    /// Limits the gas of a simulated `transaction` to the configured cap, which also applies when
+    /// the transaction sets no limit.
+    ///
+    /// The cap never exceeds the gas limit allowed to transactions, so that a simulation without a
+    /// limit is not rejected for it.
+    fn cap_gas(&self, mut transaction: TransactionRequest) -> TransactionRequest {
+        let gas_cap = self.gas_cap();
+        transaction.gas = Some(transaction.gas.map_or(gas_cap, |gas| gas.min(gas_cap)));
+        transaction
+    }
+
+    fn gas_cap(&self) -> u64 {
+        self.genesis_config
+            .max_transaction_gas_limit
+            .map_or(self.genesis_config.simulation.gas_cap, |max| {
+                max.min(self.genesis_config.simulation.gas_cap)
+            })
+    }
+
     pub fn transaction_receipt(&self, tx_hash: B256) -> Option<TransactionReceipt> {
+        self.responses.receipt(
+            tx_hash,
+            |height| self.is_finalized(height),
+            || {
+                self.receipt_queries
+                    .by_transaction_hash(&self.receipt_memory, tx_hash)
+                    .unwrap()
+            },
+        )
+    }
+
+    /// Returns the logs of the blocks from `from_height` to `to_height`, both inclusive, that match
+    /// the addresses and topics of the `filter`.
+    pub fn logs(&self, filter: &Filter, from_height: u64, to_height: u64) -> Vec<Log> {
         self.receipt_queries
-            .by_transaction_hash(&self.receipt_memory, tx_hash)
+            .logs(&self.receipt_memory, filter, from_height, to_height)
+            .unwrap()
+    }
+
+    /// Returns the logs of the block with `block_hash` that match the addresses and topics of the
+    /// `filter`, or `None` if there is no such block.
+    pub fn logs_by_block_hash(&self, filter: &Filter, block_hash: B256) -> Option<Vec<Log>> {
+        self.block_by_hash(block_hash, false)?;
+
+        Some(
+            self.receipt_queries
+                .logs_by_block_hash(&self.receipt_memory, filter, block_hash)
+                .unwrap(),
+        )
+    }
+
+    pub fn transaction_vm_status(&self, tx_hash: B256) -> Option<VmStatus> {
+        self.receipt_queries
+            .vm_status_by_transaction_hash(&self.receipt_memory, tx_hash)
             .unwrap()
     }
 
@@ -138,20 +668,400 @@ impl<D: Dependencies> ApplicationReader<D> {
             .flatten()
     }
 
+    /// Proves the transaction with `tx_hash` is a leaf of the transactions trie of its block.
+    ///
+    /// Returns `None` if the transaction is not in a block.
+    pub fn transaction_proof(&self, tx_hash: B256) -> Option<InclusionProof> {
+        let tx = self.transaction_by_hash(tx_hash)?;
+        let block = self.block_by_hash(tx.inner.block_hash?, true)?.block;
+        let BlockTransactions::Full(transactions) = block.transactions else {
+            return None;
+        };
+        let transactions: Vec<_> = transactions
+            .iter()
+            .map(|tx| OpTxEnvelope::clone(&tx.inner.inner))
+            .collect();
+
+        InclusionProof::new(
+            &transactions,
+            tx.inner.transaction_index? as usize,
+            block.header.hash,
+            block.header.inner.number,
+        )
+    }
+
+    /// Executes the transaction with `tx_hash` again on the state its block was built on, after
+    /// the transactions before it in the block, recording the Move and EVM calls it makes.
+    ///
+    /// Returns `None` if the transaction is not in a block.
+    pub fn trace_transaction(
+        &self,
+        tx_hash: B256,
+    ) -> Option<Result<(TransactionExecutionOutcome, TransactionTrace)>> {
+        self.replay_transaction(tx_hash, false)
+            .map(|traced| traced.map(|(outcome, trace, _)| (outcome, trace)))
+    }
+
+    /// Traces the transaction with `tx_hash` like [`Self::trace_transaction`], along with the
+    /// balances, nonces and EVM storage slots it changed.
+    pub fn trace_transaction_with_state_changes(
+        &self,
+        tx_hash: B256,
+    ) -> Option<Result<(TransactionTrace, StateChanges)>> {
+        self.replay_transaction(tx_hash, true)
+            .map(|traced| traced.map(|(_, trace, state_changes)| (trace, state_changes)))
+    }
+
+    fn replay_transaction(
+        &self,
+        tx_hash: B256,
+        with_state_changes: bool,
+    ) -> Option<Result<(TransactionExecutionOutcome, TransactionTrace, StateChanges)>> {
+        let tx = self.transaction_by_hash(tx_hash)?;
+        let block = self.block_by_hash(tx.inner.block_hash?, true)?.block;
+        let index = tx.inner.transaction_index? as usize;
+        let BlockTransactions::Full(transactions) = block.transactions else {
+            return None;
+        };
+        let transactions: Vec<_> = transactions
+            .iter()
+            .take(index + 1)
+            .map(|tx| OpTxEnvelope::clone(&tx.inner.inner))
+            .collect();
+        let header = block.header.inner;
+        let parent_height = header.number.checked_sub(1)?;
+
+        let block_header = HeaderForExecution {
+            number: header.number,
+            timestamp: header.timestamp,
+            prev_randao: header.mix_hash,
+        };
+        let base_fee = U256::from(header.base_fee_per_gas.unwrap_or_default());
+        // https://github.com/ethereum-optimism/specs/blob/9dbc6b0/specs/protocol/deposits.md#kinds-of-deposited-transactions
+        let l1_fee = transactions
+            .first()
+            .and_then(|tx| tx.as_deposit())
+            .map(|tx| self.l1_fee.for_deposit(tx.input.as_ref()));
+        let l2_fee = D::create_l2_gas_fee().with_default_gas_fee_multiplier();
+        let resolver = self.state_queries.resolver_at(parent_height);
+        let block_hash_lookup = StorageBasedProvider::new(&self.storage, &self.block_queries);
+        let event_layouts = EventLayouts::new(
+            self.genesis_config
+                .hardforks
+                .is_isthmus_active(block_header.timestamp),
+        );
+        // Writes of the transactions before the traced one, which it executes on top of
+        let mut changes = ChangeSet::new();
+        let mut evm_changes = StorageTriesChanges::empty();
+        let mut table_changes = TableChangeSet::default();
+
+        for (position, tx) in transactions.into_iter().enumerate() {
+            let tx_hash = tx.tx_hash();
+            let mut encoded = Vec::new();
+            tx.encode(&mut encoded);
+            let pooled = match PooledTransaction::new(tx, encoded.as_slice().into()) {
+                Ok(pooled) => pooled,
+                Err(e) => return Some(Err(e)),
+            };
+            let state = ChangesOverlay::new(&resolver, &changes).with_table_changes(&table_changes);
+            // The storage tries are kept at their latest roots, so they are read at the roots of
+            // the parent state instead
+            let storage_trie = StorageTriesOverlay::new(&self.evm_storage, &state, &evm_changes);
+            let input = match &pooled.normalized {
+                NormalizedExtendedTxEnvelope::Canonical(tx) => CanonicalExecutionInput {
+                    tx,
+                    tx_hash: &tx_hash,
+                    state: &state,
+                    storage_trie: &storage_trie,
+                    genesis_config: &self.genesis_config,
+                    l1_cost: l1_fee
+                        .as_ref()
+                        .map(|v| v.l1_fee(pooled.l1_fee_input.clone()))
+                        .unwrap_or(U256::ZERO),
+                    l2_fee: l2_fee.clone(),
+                    l2_input: L2GasFeeInput::new(tx.gas_limit(), tx.effective_gas_price(base_fee)),
+                    base_token: &self.base_token,
+                    block_header: block_header.clone(),
+                    block_hash_lookup: &block_hash_lookup,
+                    event_layouts: &event_layouts,
+                }
+                .into(),
+                NormalizedExtendedTxEnvelope::DepositedTx(tx) => DepositExecutionInput {
+                    tx,
+                    tx_hash: &tx_hash,
+                    state: &state,
+                    storage_trie: &storage_trie,
+                    genesis_config: &self.genesis_config,
+                    block_header: block_header.clone(),
+                    block_hash_lookup: &block_hash_lookup,
+                    event_layouts: &event_layouts,
+                }
+                .into(),
+            };
+
+            if position == index {
+                return Some(trace_transaction(input).map(|(outcome, trace)| {
+                    let state_changes = if with_state_changes {
+                        StateChanges::between(&state, &storage_trie, &outcome.changes)
+                    } else {
+                        StateChanges::default()
+                    };
+                    (outcome, trace, state_changes)
+                }));
+            }
+            let outcome = match execute_transaction(input) {
+                Ok(outcome) => outcome,
+                Err(e) => return Some(Err(e)),
+            };
+            if let Err(e) = changes.squash(outcome.changes.move_vm) {
+                return Some(Err(e.into()));
+            }
+            for (account, trie_changes) in outcome.changes.evm {
+                evm_changes = evm_changes.with_trie_changes(account, trie_changes);
+            }
+            merge_table_changes(&mut table_changes, outcome.changes.tables);
+        }
+
+        None
+    }
+
+    /// Proves the receipt of the transaction with `tx_hash` is a leaf of the receipts trie of its
+    /// block.
+    ///
+    /// Returns `None` if the transaction is not in a block.
+    pub fn receipt_proof(&self, tx_hash: B256) -> Option<InclusionProof> {
+        let receipt = self.transaction_receipt(tx_hash)?.inner;
+        let block_hash = receipt.block_hash?;
+        let receipts: Vec<_> = self
+            .receipt_queries
+            .by_block_hash(&self.receipt_memory, block_hash)
+            .ok()?
+            .iter()
+            .map(|receipt| with_primitive_logs(&receipt.inner.inner))
+            .collect();
+
+        InclusionProof::new(
+            &receipts,
+            receipt.transaction_index? as usize,
+            block_hash,
+            receipt.block_number?,
+        )
+    }
+
     pub fn proof(
         &self,
         address: Address,
         storage_slots: Vec<U256>,
         height: BlockId,
     ) -> Option<ProofResponse> {
-        self.height_from_block_id(height).and_then(|height| {
-            self.state_queries.proof_at(
+        self.height_from_block_id(height)
+            .and_then(|height| self.retained_height(height))
+            .and_then(|height| {
+                self.state_queries.proof_at(
+                    &self.evm_storage,
+                    address.to_move_address(),
+                    &storage_slots,
+                    height,
+                )
+            })
+    }
+
+    /// Reads the bcs encoded Move resource of type `tag` stored under `address`.
+    ///
+    /// Returns `None` if the resource does not exist or the block at `height` is not known.
+    pub fn resource_by_height(
+        &self,
+        address: AccountAddress,
+        tag: &StructTag,
+        height: BlockNumberOrTag,
+    ) -> Option<Vec<u8>> {
+        let height = self.resolve_known_height(height)?;
+        let (bytes, _) = self
+            .state_queries
+            .resolver_at(height)
+            .get_resource_bytes_with_metadata_and_layout(&address, tag, &[], None)
+            .ok()?;
+
+        bytes.map(|bytes| bytes.to_vec())
+    }
+
+    /// Lists up to `limit` Move resources stored under `address`, starting after the one of type
+    /// `after` if it is given, with their values decoded as defined in the block at `height`.
+    ///
+    /// Returns `None` if the block at `height` is not known.
+    pub fn resources_by_height(
+        &self,
+        address: AccountAddress,
+        after: Option<&StructTag>,
+        limit: usize,
+        height: BlockNumberOrTag,
+    ) -> Option<Vec<ResourceValue>> {
+        let height = self.resolve_known_height(height)?;
+        let resources = self
+            .state_queries
+            .resources_at(address, after, limit, height)?;
+        let resolver = self.state_queries.resolver_at(height);
+        let block_hash_lookup = StorageBasedProvider::new(&self.storage, &self.block_queries);
+
+        Some(
+            resources
+                .into_iter()
+                .map(|(tag, bytes)| {
+                    let value = annotate_value(
+                        &TypeTag::Struct(Box::new(tag.clone())),
+                        &bytes,
+                        &resolver,
+                        &self.evm_storage,
+                        &self.genesis_config,
+                        &block_hash_lookup,
+                    );
+                    ResourceValue { tag, bytes, value }
+                })
+                .collect(),
+        )
+    }
+
+    /// Whether [`Self::resources_by_height`] lists every resource, which it does not for a
+    /// database that predates the resource index.
+    pub fn is_resource_index_complete(&self) -> bool {
+        self.state_queries.is_resource_index_complete()
+    }
+
+    /// Reads the item under the bcs encoded `key` of the table `handle`, decoding it as
+    /// `value_type` as defined in the block at `height`.
+    ///
+    /// Returns `None` if the key is not in the table or the block at `height` is not known, and
+    /// fails if the key does not decode as `key_type`.
+    pub fn table_item_by_height(
+        &self,
+        handle: AccountAddress,
+        key_type: &TypeTag,
+        key: &[u8],
+        value_type: TypeTag,
+        height: BlockNumberOrTag,
+    ) -> std::result::Result<Option<ViewValue>, UndecodableTableKey> {
+        let Some(height) = self.resolve_known_height(height) else {
+            return Ok(None);
+        };
+        let resolver = self.state_queries.resolver_at(height);
+        let block_hash_lookup = StorageBasedProvider::new(&self.storage, &self.block_queries);
+        let annotate = |type_tag: &TypeTag, bytes: &[u8]| {
+            annotate_value(
+                type_tag,
+                bytes,
+                &resolver,
                 &self.evm_storage,
-                address.to_move_address(),
-                &storage_slots,
-                height,
+                &self.genesis_config,
+                &block_hash_lookup,
             )
-        })
+        };
+        annotate(key_type, key).ok_or(UndecodableTableKey)?;
+        let Some(bytes) = resolver
+            .resolve_table_entry_bytes_with_layout(&TableHandle(handle), key, None)
+            .ok()
+            .flatten()
+        else {
+            return Ok(None);
+        };
+        let bytes = bytes.to_vec();
+        let value = annotate(&value_type, &bytes);
+
+        Ok(Some(ViewValue {
+            type_tag: value_type,
+            bytes,
+            value,
+        }))
+    }
+
+    /// Reads the bytecode of the Move module `id`.
+    ///
+    /// Returns `None` if the module does not exist or the block at `height` is not known.
+    pub fn module_by_height(&self, id: &ModuleId, height: BlockNumberOrTag) -> Option<Vec<u8>> {
+        let height = self.resolve_known_height(height)?;
+        let bytes = self
+            .state_queries
+            .resolver_at(height)
+            .get_module(id)
+            .ok()??;
+
+        Some(bytes.to_vec())
+    }
+
+    /// Whether a Move account is created at `address`, which holds the `0x1::account::Account`
+    /// resource.
+    pub fn move_account_exists(&self, address: AccountAddress, height: BlockNumberOrTag) -> bool {
+        self.resource_by_height(address, &ACCOUNT_STRUCT_TAG, height)
+            .is_some()
+    }
+
+    /// Whether the EVM knows an account at `address`, such as a contract or an account that has
+    /// sent an EVM transaction.
+    pub fn evm_account_exists(&self, address: Address, height: BlockNumberOrTag) -> bool {
+        self.resource_by_height(
+            EVM_NATIVE_ADDRESS,
+            &account_info_struct_tag(&address),
+            height,
+        )
+        .is_some()
+    }
+
+    /// Reads the deployed bytecode of the EVM contract at `address`.
+    ///
+    /// Returns `None` if there is no contract at `address` or the block at `height` is not known.
+    pub fn evm_code_by_height(
+        &self,
+        address: Address,
+        height: BlockNumberOrTag,
+    ) -> Option<Vec<u8>> {
+        let account = self.resource_by_height(
+            EVM_NATIVE_ADDRESS,
+            &account_info_struct_tag(&address),
+            height,
+        )?;
+        let code_hash = Account::try_deserialize(&account)?.inner.code_hash;
+        if code_hash == KECCAK_EMPTY {
+            return None;
+        }
+        let code = self.resource_by_height(
+            EVM_NATIVE_ADDRESS,
+            &code_hash_struct_tag(&code_hash),
+            height,
+        )?;
+        let value = ValueSerDeContext::new().deserialize(&code, &CODE_LAYOUT)?;
+
+        value.cast().ok()
+    }
+
+    /// Reads encoded trie nodes by their hashes, for peers syncing the state.
+    ///
+    /// The nodes come from the storage trie of `account` if it is given, otherwise from the state
+    /// trie. A node that is not found is returned as `None` in its position.
+    pub fn trie_nodes(&self, account: Option<Address>, hashes: &[B256]) -> Vec<Option<Vec<u8>>> {
+        hashes
+            .iter()
+            .map(|hash| match account {
+                Some(account) => self.evm_storage.trie_node(&account, *hash).ok().flatten(),
+                None => self.state_queries.trie_node(*hash),
+            })
+            .collect()
+    }
+
+    /// Looks up the EVM accounts that have storage by the hashes of their addresses, which are the
+    /// keys of their leaves in the state trie, for peers syncing the storage tries they find there.
+    ///
+    /// An account that is not found is returned as `None` in its position.
+    pub fn storage_accounts(&self, key_hashes: &[B256]) -> state::Result<Vec<Option<Address>>> {
+        let accounts: HashMap<_, _> = self
+            .evm_storage
+            .accounts()?
+            .into_iter()
+            .map(|account| (keccak256(account), account))
+            .collect();
+
+        Ok(key_hashes
+            .iter()
+            .map(|hash| accounts.get(hash).copied())
+            .collect())
     }
 
     pub fn payload(&self, id: PayloadId) -> Option<PayloadResponse> {
@@ -168,24 +1078,74 @@ impl<D: Dependencies> ApplicationReader<D> {
     fn resolve_height(&self, height: BlockNumberOrTag) -> Option<u64> {
         Some(match height {
             Number(height) => height,
-            Finalized | Pending | Latest | Safe => {
-                self.block_queries.latest(&self.storage).ok()??
-            }
+            Pending | Latest => self.block_queries.latest(&self.storage).ok()??,
+            Safe => self.block_queries.safe(&self.storage).ok()??,
+            Finalized => self.block_queries.finalized(&self.storage).ok()??,
             Earliest => 0,
         })
     }
 
-    fn height_from_block_id(&self, id: BlockId) -> Option<u64> {
-        Some(match id {
+    /// Returns `height` unless its state is below the [`Self::state_horizon`].
+    fn retained_height(&self, height: u64) -> Option<u64> {
+        (height >= self.state_horizon()).then_some(height)
+    }
+
+    /// Whether the block at `height` is finalized, so that a reorg cannot replace it anymore.
+    fn is_finalized(&self, height: u64) -> bool {
+        self.resolve_height(Finalized)
+            .is_some_and(|finalized| height <= finalized)
+    }
+
+    /// Same as [`Self::resolve_height`], but also rejects heights of blocks not produced yet.
+    fn resolve_known_height(&self, height: BlockNumberOrTag) -> Option<u64> {
+        let height = self.resolve_height(height)?;
+
+        (height <= self.block_number()).then_some(height)
+    }
+
+    /// Resolves the block `id` to the height of the block, which a hash is looked up for as in
+    /// [EIP-1898](https://eips.ethereum.org/EIPS/eip-1898).
+    ///
+    /// Returns `None` if no block has the hash, or a tag has no block yet.
+    pub fn height_from_block_id(&self, id: impl Into<BlockId>) -> Option<u64> {
+        Some(match id.into() {
             BlockId::Number(height) => self.resolve_height(height)?,
             BlockId::Hash(h) => {
-                self.block_queries
+                let height = self
+                    .block_queries
                     .by_hash(&self.storage, h.block_hash, false)
                     .ok()??
-                    .0
+                    .block
                     .header
-                    .number
+                    .number;
+
+                // The state is indexed by height, so that of a block replaced by a reorg is gone
+                self.is_canonical(h.block_hash, height).then_some(height)?
             }
         })
     }
+
+    /// Whether the block with `hash` at `height` is the one on the canonical chain.
+    fn is_canonical(&self, hash: B256, height: u64) -> bool {
+        self.block_queries
+            .by_height(&self.storage, height, false)
+            .ok()
+            .flatten()
+            .is_some_and(|block| block.block.header.hash == hash)
+    }
+}
+
+/// Adds the table writes of a transaction to those of the transactions before it, the later write
+/// of a key taking precedence.
+fn merge_table_changes(changes: &mut TableChangeSet, later: TableChangeSet) {
+    changes.new_tables.extend(later.new_tables);
+    changes.removed_tables.extend(later.removed_tables);
+    for (handle, table) in later.changes {
+        match changes.changes.entry(handle) {
+            Entry::Vacant(entry) => {
+                entry.insert(table);
+            }
+            Entry::Occupied(mut entry) => entry.get_mut().entries.extend(table.entries),
+        }
+    }
 }
@@ -1,13 +1,43 @@
 use {
     crate::Command,
-    std::pin::Pin,
+    std::{
+        pin::Pin,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    },
     tokio::sync::{broadcast, mpsc},
 };
 
+/// Percentage of the queue capacity in use above which the queue counts as saturated.
+const SATURATION_PERCENT: usize = 90;
+
+/// How many commands wait in a [`CommandQueue`] out of how many it can buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueLoad {
+    pub pending: usize,
+    pub capacity: usize,
+}
+
+impl QueueLoad {
+    /// Whether the queue is close enough to its capacity that senders are about to wait.
+    pub fn is_saturated(&self) -> bool {
+        self.pending * 100 >= self.capacity * SATURATION_PERCENT
+    }
+}
+
+/// A command dropped by [`CommandQueue::send_or_shed`] because the queue is saturated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueSaturated;
+
 #[derive(Debug, Clone)]
 pub struct CommandQueue {
     sender: mpsc::Sender<Command>,
     killshot: broadcast::Sender<()>,
+    /// Whether the last [`Self::send_or_shed`] found the queue saturated, to report only the
+    /// changes between the two.
+    shedding: Arc<AtomicBool>,
 }
 
 impl CommandQueue {
@@ -16,7 +46,11 @@ impl CommandQueue {
     /// In case of a panic of the `sender` channel, a shutdown signal is sent through the
     /// `killshot`.
     pub fn new(sender: mpsc::Sender<Command>, killshot: broadcast::Sender<()>) -> Self {
-        Self { sender, killshot }
+        Self {
+            sender,
+            killshot,
+            shedding: Arc::new(AtomicBool::new(false)),
+        }
     }
 
     /// Sends a [`Command`] to the background queue for asynchronous processing.
@@ -26,6 +60,42 @@ impl CommandQueue {
         }
     }
 
+    /// Sends a [`Command`] that can be dropped under load, such as a new transaction.
+    ///
+    /// While the queue is saturated the command is shed instead of waiting for room, so that the
+    /// engine API commands driving block production do not queue up behind a backlog of
+    /// transactions.
+    pub async fn send_or_shed(&self, msg: Command) -> Result<(), QueueSaturated> {
+        let load = self.load();
+        let saturated = load.is_saturated();
+        if self.shedding.swap(saturated, Ordering::Relaxed) != saturated {
+            if saturated {
+                println!(
+                    "WARN: command queue is saturated with {} of {} commands, shedding transactions",
+                    load.pending, load.capacity
+                );
+            } else {
+                println!("INFO: command queue is no longer saturated");
+            }
+        }
+
+        if saturated {
+            return Err(QueueSaturated);
+        }
+        self.send(msg).await;
+        Ok(())
+    }
+
+    /// Returns the number of commands waiting to be processed.
+    pub fn load(&self) -> QueueLoad {
+        let capacity = self.sender.max_capacity();
+
+        QueueLoad {
+            pending: capacity - self.sender.capacity(),
+            capacity,
+        }
+    }
+
     /// Waits for all the commands in the queue to be processed.
     pub async fn wait_for_pending_commands(&self) {
         if self
@@ -52,3 +122,35 @@ impl CommandQueue {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, moved_shared::primitives::B256};
+
+    fn command() -> Command {
+        Command::ForkchoiceUpdate {
+            safe_block_hash: B256::ZERO,
+            finalized_block_hash: B256::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_commands_are_shed_once_queue_is_saturated() {
+        let (tx, _rx) = mpsc::channel(10);
+        let queue = CommandQueue::new(tx, broadcast::channel(1).0);
+
+        for _ in 0..9 {
+            queue.send_or_shed(command()).await.unwrap();
+        }
+
+        assert_eq!(
+            queue.load(),
+            QueueLoad {
+                pending: 9,
+                capacity: 10
+            }
+        );
+        assert_eq!(queue.send_or_shed(command()).await, Err(QueueSaturated));
+        assert_eq!(queue.load().pending, 9);
+    }
+}
@@ -0,0 +1,234 @@
+use {
+    alloy::eips::BlockHashOrNumber,
+    moved_blockchain::{block::BlockResponse, receipt::TransactionReceipt},
+    moved_shared::primitives::B256,
+    std::{
+        collections::{HashMap, VecDeque},
+        hash::Hash,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Most blocks and most receipts remembered at once by default.
+pub const DEFAULT_RESPONSE_CACHE_CAPACITY: usize = 256;
+
+/// Remembers the most recently queried blocks and receipts that can no longer change.
+///
+/// Explorers and indexers query the same recent blocks over and over. A block read by its hash
+/// never changes, while a block read by its height and a receipt are only remembered once their
+/// block is finalized, as a reorg could replace them before that. The clones of a cache share its
+/// responses.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    blocks: Arc<Mutex<Lru<(BlockHashOrNumber, bool), BlockResponse>>>,
+    receipts: Arc<Mutex<Lru<B256, TransactionReceipt>>>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESPONSE_CACHE_CAPACITY)
+    }
+}
+
+impl ResponseCache {
+    /// Creates the cache remembering at most `capacity` blocks and `capacity` receipts.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            blocks: Arc::new(Mutex::new(Lru::new(capacity))),
+            receipts: Arc::new(Mutex::new(Lru::new(capacity))),
+        }
+    }
+
+    /// Returns the block with `hash`, calling `query` if it is not cached yet.
+    pub fn block_by_hash(
+        &self,
+        hash: B256,
+        include_transactions: bool,
+        query: impl FnOnce() -> Option<BlockResponse>,
+    ) -> Option<BlockResponse> {
+        self.block(hash.into(), include_transactions, true, query)
+    }
+
+    /// Returns the block at `height`, calling `query` if it is not cached yet.
+    ///
+    /// The block is cached only if it is `finalized`.
+    pub fn block_by_height(
+        &self,
+        height: u64,
+        include_transactions: bool,
+        finalized: bool,
+        query: impl FnOnce() -> Option<BlockResponse>,
+    ) -> Option<BlockResponse> {
+        self.block(height.into(), include_transactions, finalized, query)
+    }
+
+    /// Returns the receipt of the transaction with `tx_hash`, calling `query` if it is not cached
+    /// yet.
+    ///
+    /// The receipt is cached only if `is_finalized` holds for the height of its block.
+    pub fn receipt(
+        &self,
+        tx_hash: B256,
+        is_finalized: impl FnOnce(u64) -> bool,
+        query: impl FnOnce() -> Option<TransactionReceipt>,
+    ) -> Option<TransactionReceipt> {
+        if let Some(receipt) = self.receipts.lock().unwrap().get(&tx_hash) {
+            return Some(receipt);
+        }
+
+        let receipt = query()?;
+        if receipt.inner.block_number.is_some_and(is_finalized) {
+            self.receipts
+                .lock()
+                .unwrap()
+                .insert(tx_hash, receipt.clone());
+        }
+
+        Some(receipt)
+    }
+
+    fn block(
+        &self,
+        id: BlockHashOrNumber,
+        include_transactions: bool,
+        cacheable: bool,
+        query: impl FnOnce() -> Option<BlockResponse>,
+    ) -> Option<BlockResponse> {
+        let key = (id, include_transactions);
+        if let Some(block) = self.blocks.lock().unwrap().get(&key) {
+            return Some(block);
+        }
+
+        // The lock is not held while querying, so that cached blocks can be served meanwhile
+        let block = query()?;
+        if cacheable {
+            self.blocks.lock().unwrap().insert(key, block.clone());
+        }
+
+        Some(block)
+    }
+}
+
+/// Keeps at most `capacity` values, evicting the least recently used one to make room.
+///
+/// Meant for small capacities, as the recency order is a queue searched on every access.
+#[derive(Debug)]
+struct Lru<K, V> {
+    values: HashMap<K, V>,
+    order: VecDeque<K>,
+    capacity: usize,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Lru<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            values: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.values.get(key)?.clone();
+        self.touch(key);
+        Some(value)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.values.insert(key.clone(), value).is_some() {
+            self.touch(&key);
+            return;
+        }
+
+        if self.order.len() == self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.values.remove(&evicted);
+            }
+        }
+        self.order.push_back(key);
+    }
+
+    /// Marks `key` as the most recently used.
+    fn touch(&mut self, key: &K) {
+        if let Some(position) = self.order.iter().position(|k| k == key) {
+            let key = self
+                .order
+                .remove(position)
+                .expect("Position should be in bounds");
+            self.order.push_back(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, moved_blockchain::block::Block, std::cell::Cell};
+
+    fn block() -> BlockResponse {
+        BlockResponse::from_block_with_transaction_hashes(Block::default().with_hash(B256::ZERO))
+    }
+
+    #[test]
+    fn test_lru_evicts_least_recently_used_value() {
+        let mut lru = Lru::new(2);
+        lru.insert(1, "one");
+        lru.insert(2, "two");
+
+        assert_eq!(lru.get(&1), Some("one"));
+        lru.insert(3, "three");
+
+        assert_eq!(lru.get(&2), None);
+        assert_eq!(lru.get(&1), Some("one"));
+        assert_eq!(lru.get(&3), Some("three"));
+    }
+
+    #[test]
+    fn test_block_by_hash_is_queried_once() {
+        let cache = ResponseCache::default();
+        let queries = Cell::new(0);
+        let query = || {
+            queries.set(queries.get() + 1);
+            Some(block())
+        };
+
+        cache.block_by_hash(B256::ZERO, false, query);
+        cache.block_by_hash(B256::ZERO, false, query);
+
+        assert_eq!(queries.get(), 1);
+    }
+
+    #[test]
+    fn test_block_by_height_is_not_cached_until_finalized() {
+        let cache = ResponseCache::default();
+        let queries = Cell::new(0);
+        let query = || {
+            queries.set(queries.get() + 1);
+            Some(block())
+        };
+
+        cache.block_by_height(1, true, false, query);
+        cache.block_by_height(1, true, true, query);
+        cache.block_by_height(1, true, true, query);
+
+        assert_eq!(queries.get(), 2);
+    }
+
+    #[test]
+    fn test_missing_block_is_not_cached() {
+        let cache = ResponseCache::default();
+        let queries = Cell::new(0);
+        let query = || {
+            queries.set(queries.get() + 1);
+            None
+        };
+
+        cache.block_by_hash(B256::ZERO, false, query);
+        cache.block_by_hash(B256::ZERO, false, query);
+
+        assert_eq!(queries.get(), 2);
+    }
+}
@@ -0,0 +1,162 @@
+//! Balances, nonces and EVM storage slots that a transaction changes, with their values before and
+//! after it.
+//!
+//! The changes are found by comparing the state before the transaction with the state after its
+//! writes, so a value that a transaction changes and then restores is not reported.
+
+use {
+    aptos_types::{
+        account_address::create_derived_object_address, account_config::AccountResource,
+    },
+    move_core_types::{
+        account_address::AccountAddress, ident_str, language_storage::StructTag,
+        move_resource::MoveStructType,
+    },
+    move_vm_types::resolver::{MoveResolver, ResourceResolver},
+    moved_blockchain::state::storage_from_resolver,
+    moved_evm_ext::state::{StorageTrieRepository, StorageTriesOverlay},
+    moved_execution::{ChangesOverlay, read_eth_balance, read_nonce, transaction::Changes},
+    moved_genesis::{FRAMEWORK_ADDRESS, eth_token_metadata_address},
+    moved_shared::primitives::{Address, ToEthAddress, U256},
+    std::collections::BTreeSet,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateChanges {
+    /// Base token balances, ordered by address.
+    pub balances: Vec<BalanceChange>,
+    /// Nonces, ordered by address.
+    pub nonces: Vec<NonceChange>,
+    /// EVM storage slots, ordered by address and then by slot.
+    pub storage: Vec<StorageChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub address: Address,
+    pub old_value: U256,
+    pub new_value: U256,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceChange {
+    pub address: Address,
+    pub old_value: u64,
+    pub new_value: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageChange {
+    pub address: Address,
+    pub key: U256,
+    pub old_value: U256,
+    pub new_value: U256,
+}
+
+impl StateChanges {
+    /// Compares the `state` and `storage_trie` that a transaction executed on with the state after
+    /// its `changes`.
+    ///
+    /// A balance is found through the primary base token store of its account, which is an object
+    /// owned by the account.
+    pub fn between(
+        state: &impl MoveResolver,
+        storage_trie: &impl StorageTrieRepository,
+        changes: &Changes,
+    ) -> Self {
+        let state_after = ChangesOverlay::new(state, &changes.move_vm);
+        let storage_trie_after = StorageTriesOverlay::new(storage_trie, &state_after, &changes.evm);
+        let account_tag = AccountResource::struct_tag();
+        let store_tag = fungible_store_struct_tag();
+        let mut balances = Vec::new();
+        let mut nonces = Vec::new();
+
+        for (address, account) in changes.move_vm.accounts() {
+            if account.resources().contains_key(&account_tag) {
+                let old_value = read_nonce(address, state).unwrap_or_default();
+                let new_value = read_nonce(address, &state_after).unwrap_or_default();
+                if old_value != new_value {
+                    nonces.push(NonceChange {
+                        address: address.to_eth_address(),
+                        old_value,
+                        new_value,
+                    });
+                }
+            }
+            if account.resources().contains_key(&store_tag) {
+                let Some(owner) = primary_store_owner(address, &state_after) else {
+                    continue;
+                };
+                let old_value = read_eth_balance(&owner, state).unwrap_or_default();
+                let new_value = read_eth_balance(&owner, &state_after).unwrap_or_default();
+                if old_value != new_value {
+                    balances.push(BalanceChange {
+                        address: owner.to_eth_address(),
+                        old_value,
+                        new_value,
+                    });
+                }
+            }
+        }
+        balances.sort_by_key(|change| change.address);
+        nonces.sort_by_key(|change| change.address);
+
+        let slots: BTreeSet<_> = changes
+            .evm
+            .tries
+            .iter()
+            .flat_map(|(address, trie)| trie.slots.iter().map(|slot| (*address, *slot)))
+            .collect();
+        let storage = slots
+            .into_iter()
+            .filter_map(|(address, key)| {
+                let old_value = storage_from_resolver(address, key, state, storage_trie)?;
+                let new_value =
+                    storage_from_resolver(address, key, &state_after, &storage_trie_after)?;
+
+                (old_value != new_value).then_some(StorageChange {
+                    address,
+                    key,
+                    old_value,
+                    new_value,
+                })
+            })
+            .collect();
+
+        Self {
+            balances,
+            nonces,
+            storage,
+        }
+    }
+}
+
+fn fungible_store_struct_tag() -> StructTag {
+    StructTag {
+        address: FRAMEWORK_ADDRESS,
+        module: ident_str!("fungible_asset_u256").into(),
+        name: ident_str!("FungibleStore").into(),
+        type_args: Vec::new(),
+    }
+}
+
+/// Reads the owner of the object at `store`, if it is the primary base token store of its owner.
+fn primary_store_owner(
+    store: &AccountAddress,
+    state: &impl ResourceResolver,
+) -> Option<AccountAddress> {
+    let object_core_tag = StructTag {
+        address: FRAMEWORK_ADDRESS,
+        module: ident_str!("object").into(),
+        name: ident_str!("ObjectCore").into(),
+        type_args: Vec::new(),
+    };
+    let (bytes, _) = state
+        .get_resource_bytes_with_metadata_and_layout(store, &object_core_tag, &[], None)
+        .ok()?;
+    // The BCS encoding of `ObjectCore` starts with a `u64` GUID creation number followed by the
+    // owner address
+    let owner = AccountAddress::from_bytes(bytes?.get(8..8 + AccountAddress::LENGTH)?).ok()?;
+
+    (create_derived_object_address(owner, eth_token_metadata_address()) == *store).then_some(owner)
+}
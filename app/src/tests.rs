@@ -2,14 +2,26 @@ use {
     super::*,
     crate::TestDependencies,
     alloy::{
-        consensus::{SignableTransaction, TxEip1559, TxEnvelope},
-        eips::BlockNumberOrTag::{self, *},
+        consensus::{
+            EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH, Sealed, SignableTransaction, TxEip1559,
+            TxEnvelope,
+        },
+        eips::{
+            BlockId,
+            BlockNumberOrTag::{self, *},
+            eip2930::{AccessList, AccessListItem},
+            eip7685::EMPTY_REQUESTS_HASH,
+        },
         hex,
         network::TxSignerSync,
-        primitives::{TxKind, address},
+        primitives::{TxKind, address, keccak256},
+        rlp::Encodable,
         signers::local::PrivateKeySigner,
     },
-    move_core_types::{account_address::AccountAddress, effects::ChangeSet},
+    aptos_types::state_store::state_key::StateKey,
+    move_core_types::{
+        account_address::AccountAddress, effects::ChangeSet, ident_str, language_storage::StructTag,
+    },
     move_vm_runtime::{
         AsUnsyncCodeStorage,
         module_traversal::{TraversalContext, TraversalStorage},
@@ -18,22 +30,46 @@ use {
     moved_blockchain::{
         block::{
             Block, BlockHash, BlockRepository, Eip1559GasFee, Header, InMemoryBlockQueries,
-            InMemoryBlockRepository, MovedBlockHash,
+            InMemoryBlockRepository, L1Origin, MovedBlockHash, RejectedTransaction,
         },
         in_memory::shared_memory,
-        payload::InMemoryPayloadQueries,
+        payload::{InMemoryPayloadJobRepository, InMemoryPayloadQueries, PayloadJobRepository},
         receipt::{InMemoryReceiptQueries, InMemoryReceiptRepository, receipt_memory},
         state::{BlockHeight, InMemoryStateQueries, MockStateQueries, StateQueries},
-        transaction::{InMemoryTransactionQueries, InMemoryTransactionRepository},
+        transaction::{
+            InMemoryTransactionJournal, InMemoryTransactionQueries, InMemoryTransactionRepository,
+            TransactionJournal,
+        },
+    },
+    moved_evm_ext::{
+        HeaderForExecution,
+        state::{InMemoryStorageTrieRepository, StorageTrieRepository},
+    },
+    moved_execution::{
+        MovedBaseTokenAccounts, create_vm_session, interop::CROSS_L2_INBOX, session_id::SessionId,
+        transaction::NormalizedExtendedTxEnvelope,
     },
-    moved_evm_ext::state::{InMemoryStorageTrieRepository, StorageTrieRepository},
-    moved_execution::{MovedBaseTokenAccounts, create_vm_session, session_id::SessionId},
     moved_genesis::{
-        CreateMoveVm, MovedVm,
-        config::{CHAIN_ID, GenesisConfig},
+        CreateMoveVm, L2_TO_L1_MESSAGE_PASSER, MovedVm,
+        config::{CHAIN_ID, GenesisConfig, TxAdmissionParameters},
+    },
+    moved_shared::{
+        error::InvalidTransactionCause,
+        primitives::{Address, B64, B256, ToMoveAddress, U64, U256},
+    },
+    moved_state::{
+        InMemoryState, ResolverBasedModuleBytesStorage, State,
+        preimage::{InMemoryTriePreimages, TriePreimages},
+    },
+    op_alloy::consensus::{OpTxEnvelope, TxDeposit},
+    std::{
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicUsize, Ordering},
+            mpsc,
+        },
+        time::{Duration, Instant, SystemTime},
     },
-    moved_shared::primitives::{Address, B256, ToMoveAddress, U64, U256},
-    moved_state::{InMemoryState, ResolverBasedModuleBytesStorage, State},
     test_case::test_case,
 };
 
@@ -81,6 +117,13 @@ fn create_app_with_given_queries<SQ: StateQueries + Clone + Send + Sync + 'stati
         repository.add(&mut memory, block).unwrap();
     }
 
+    repository
+        .mark_safe(&mut memory, height.saturating_sub(1))
+        .unwrap();
+    repository
+        .mark_finalized(&mut memory, height.saturating_sub(2))
+        .unwrap();
+
     let mut state = InMemoryState::new(InMemoryState::create_db());
     let mut evm_storage = InMemoryStorageTrieRepository::new();
     let (changes, tables, evm_storage_changes) = moved_genesis_image::load();
@@ -95,6 +138,8 @@ fn create_app_with_given_queries<SQ: StateQueries + Clone + Send + Sync + 'stati
 
     let (receipt_memory_reader, receipt_memory) = receipt_memory::new();
 
+    let mem_pool = MemPool::default();
+
     (
         ApplicationReader {
             genesis_config: genesis_config.clone(),
@@ -107,17 +152,26 @@ fn create_app_with_given_queries<SQ: StateQueries + Clone + Send + Sync + 'stati
             state_queries: state_queries.clone(),
             evm_storage: evm_storage.clone(),
             transaction_queries: InMemoryTransactionQueries::new(),
+            gas_price_oracle: GasPriceOracle::new(genesis_config.gas_price_oracle.clone()),
+            gas_estimates: GasEstimateCache::default(),
+            responses: ResponseCache::default(),
+            l1_fee: U256::ZERO,
+            mem_pool: mem_pool.clone(),
+            payload_expiry: Default::default(),
+            peer_count: Default::default(),
         },
         Application {
-            mem_pool: Default::default(),
+            mem_pool,
+            payload_expiry: Default::default(),
+            workers: Workers::new(&genesis_config.concurrency),
             genesis_config,
             base_token: MovedBaseTokenAccounts::new(AccountAddress::ONE),
             block_hash: MovedBlockHash,
             block_queries: InMemoryBlockQueries,
             block_repository: repository,
             on_payload: CommandActor::on_payload_noop(),
-            on_tx: CommandActor::on_tx_noop(),
-            on_tx_batch: CommandActor::on_tx_batch_noop(),
+            observers: Vec::new(),
+            payload_job_repository: InMemoryPayloadJobRepository::new(),
             payload_queries: InMemoryPayloadQueries::new(),
             receipt_queries: InMemoryReceiptQueries::new(),
             receipt_repository: InMemoryReceiptRepository::new(),
@@ -130,6 +184,7 @@ fn create_app_with_given_queries<SQ: StateQueries + Clone + Send + Sync + 'stati
             evm_storage,
             transaction_queries: InMemoryTransactionQueries::new(),
             transaction_repository: InMemoryTransactionRepository::new(),
+            transaction_journal: InMemoryTransactionJournal::new(),
             gas_fee: Eip1559GasFee::default(),
             l1_fee: U256::ZERO,
             l2_fee: U256::ZERO,
@@ -209,6 +264,8 @@ fn create_app_with_fake_queries(
         genesis_config.initial_state_root,
     );
 
+    let mem_pool = MemPool::default();
+
     (
         ApplicationReader {
             genesis_config: genesis_config.clone(),
@@ -221,17 +278,25 @@ fn create_app_with_fake_queries(
             state_queries: state_queries.clone(),
             evm_storage: evm_storage.clone(),
             transaction_queries: InMemoryTransactionQueries::new(),
+            gas_price_oracle: GasPriceOracle::new(genesis_config.gas_price_oracle.clone()),
+            gas_estimates: GasEstimateCache::default(),
+            responses: ResponseCache::default(),
+            l1_fee: U256::ZERO,
+            mem_pool: mem_pool.clone(),
+            payload_expiry: Default::default(),
+            peer_count: Default::default(),
         },
         Application::<TestDependencies> {
-            mem_pool: Default::default(),
+            mem_pool,
+            payload_expiry: Default::default(),
             genesis_config,
             base_token: MovedBaseTokenAccounts::new(AccountAddress::ONE),
             block_hash: MovedBlockHash,
             block_queries: InMemoryBlockQueries,
             block_repository: repository,
             on_payload: CommandActor::on_payload_in_memory(),
-            on_tx: CommandActor::on_tx_in_memory(),
-            on_tx_batch: CommandActor::on_tx_batch_in_memory(),
+            observers: Vec::new(),
+            payload_job_repository: InMemoryPayloadJobRepository::new(),
             payload_queries: InMemoryPayloadQueries::new(),
             receipt_queries: InMemoryReceiptQueries::new(),
             receipt_repository: InMemoryReceiptRepository::new(),
@@ -244,6 +309,7 @@ fn create_app_with_fake_queries(
             evm_storage,
             transaction_queries: InMemoryTransactionQueries::new(),
             transaction_repository: InMemoryTransactionRepository::new(),
+            transaction_journal: InMemoryTransactionJournal::new(),
             gas_fee: Eip1559GasFee::default(),
             l1_fee: U256::ZERO,
             l2_fee: U256::ZERO,
@@ -262,12 +328,16 @@ fn test_build_block_hash() {
         )),
         suggested_fee_recipient: address!("4200000000000000000000000000000000000011"),
         withdrawals: Vec::new(),
-        parent_beacon_block_root: Default::default(),
+        parent_beacon_block_root: Some(B256::ZERO),
         transactions: Vec::new(),
         gas_limit: U64::from(0x1c9c380),
+        eip1559_params: None,
     };
 
     let execution_outcome = ExecutionOutcome {
+        transactions_root: B256::new(hex!(
+            "c355179c91ebb544d6662d6ad580c45eb3f155e1626b693b3afa4fdca677c450"
+        )),
         receipts_root: B256::new(hex!(
             "3c55e3bccc48ee3ee637d8fc6936e4825d1489cbebf6057ce8025d63755ebf54"
         )),
@@ -284,9 +354,6 @@ fn test_build_block_hash() {
             "966c80cc0cbf7dbf7a2b2579002b95c8756f388c3fbf4a77c4d94d3719880c6e"
         )),
         number: 1,
-        transactions_root: B256::new(hex!(
-            "c355179c91ebb544d6662d6ad580c45eb3f155e1626b693b3afa4fdca677c450"
-        )),
         base_fee_per_gas: Some(0x3b5dc100),
         blob_gas_used: Some(0),
         excess_blob_gas: Some(0),
@@ -308,8 +375,8 @@ fn test_build_block_hash() {
 }
 
 #[test_case(Latest, 4, 4; "Latest")]
-#[test_case(Finalized, 4, 4; "Finalized")]
-#[test_case(Safe, 4, 4; "Safe")]
+#[test_case(Finalized, 4, 2; "Finalized")]
+#[test_case(Safe, 4, 3; "Safe")]
 #[test_case(Earliest, 4, 0; "Earliest")]
 #[test_case(Pending, 4, 4; "Pending")]
 #[test_case(Number(2), 4, 2; "Number")]
@@ -331,8 +398,8 @@ fn test_nonce_is_fetched_by_height_successfully(
 }
 
 #[test_case(Latest, 2, 2; "Latest")]
-#[test_case(Finalized, 2, 2; "Finalized")]
-#[test_case(Safe, 2, 2; "Safe")]
+#[test_case(Finalized, 2, 0; "Finalized")]
+#[test_case(Safe, 2, 1; "Safe")]
 #[test_case(Earliest, 2, 0; "Earliest")]
 #[test_case(Pending, 2, 2; "Pending")]
 #[test_case(Number(1), 2, 1; "Number")]
@@ -353,16 +420,57 @@ fn test_balance_is_fetched_by_height_successfully(
     assert_eq!(actual_balance, expected_balance);
 }
 
+#[test]
+fn test_state_below_horizon_is_pruned() {
+    let address = Address::new(hex!("44223344556677889900ffeeaabbccddee111111"));
+    let (mut reader, _app) =
+        create_app_with_given_queries(10, MockStateQueries(address.to_move_address(), 8));
+    reader.genesis_config.state_history.retained_blocks = Some(3);
+
+    assert_eq!(
+        reader.ensure_state_retained(Number(7)),
+        Err(StateUnavailable::Pruned {
+            height: 7,
+            horizon: 8
+        })
+    );
+    assert_eq!(reader.ensure_state_retained(Number(8)), Ok(()));
+    assert!(reader.balance_by_height(address, Number(7)).is_none());
+    assert!(reader.balance_by_height(address, Number(8)).is_some());
+}
+
 fn create_transaction(nonce: u64) -> TxEnvelope {
+    create_transaction_with_fees(nonce, 0, 0)
+}
+
+fn create_transaction_with_fees(
+    nonce: u64,
+    max_fee_per_gas: u128,
+    max_priority_fee_per_gas: u128,
+) -> TxEnvelope {
+    create_transaction_signed_by(
+        &PRIVATE_KEY,
+        nonce,
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+    )
+}
+
+fn create_transaction_signed_by(
+    key_bytes: &[u8; 32],
+    nonce: u64,
+    max_fee_per_gas: u128,
+    max_priority_fee_per_gas: u128,
+) -> TxEnvelope {
     let to = Address::new(hex!("44223344556677889900ffeeaabbccddee111111"));
     let amount = U256::from(4);
-    let signer = Signer::new(&PRIVATE_KEY);
+    let signer = Signer::new(key_bytes);
     let mut tx = TxEip1559 {
         chain_id: CHAIN_ID,
         nonce: signer.nonce + nonce,
         gas_limit: u64::MAX,
-        max_fee_per_gas: 0,
-        max_priority_fee_per_gas: 0,
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
         to: TxKind::Call(to),
         value: amount,
         access_list: Default::default(),
@@ -397,6 +505,658 @@ fn test_fetched_balances_are_updated_after_transfer_of_funds() {
     assert_eq!(actual_sender_balance, expected_sender_balance);
 }
 
+#[test]
+fn test_withdrawal_amount_is_credited_to_recipient() {
+    let to = Address::new(hex!("44223344556677889900ffeeaabbccddee111111"));
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+
+    let withdrawal = Withdrawal {
+        index: 0,
+        validator_index: 0,
+        address: to,
+        amount: 3,
+    };
+
+    app.start_block_build(
+        Payload {
+            withdrawals: vec![withdrawal],
+            gas_limit: U64::MAX,
+            ..Default::default()
+        },
+        U64::from(0x03421ee50df45cacu64),
+    );
+
+    let actual_balance = reader.balance_by_height(to, Latest).unwrap();
+    // Withdrawal amounts are given in Gwei
+    let expected_balance = U256::from(3_000_000_000u64);
+
+    assert_eq!(actual_balance, expected_balance);
+}
+
+#[test]
+fn test_built_block_carries_dencun_header_fields() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    let parent_beacon_block_root = B256::repeat_byte(0xbe);
+
+    app.start_block_build(
+        Payload {
+            parent_beacon_block_root: Some(parent_beacon_block_root),
+            gas_limit: U64::MAX,
+            ..Default::default()
+        },
+        U64::from(0x03421ee50df45cacu64),
+    );
+
+    let header = reader.block_by_height(Latest, false).unwrap().block.header;
+
+    assert_eq!(
+        header.parent_beacon_block_root,
+        Some(parent_beacon_block_root)
+    );
+    assert_eq!(header.blob_gas_used, Some(0));
+    assert_eq!(header.excess_blob_gas, Some(0));
+}
+
+#[test]
+fn test_built_block_before_ecotone_has_no_dencun_header_fields() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+
+    app.start_block_build(
+        Payload {
+            parent_beacon_block_root: None,
+            gas_limit: U64::MAX,
+            ..Default::default()
+        },
+        U64::from(0x03421ee50df45cacu64),
+    );
+
+    let header = reader.block_by_height(Latest, false).unwrap().block.header;
+
+    assert_eq!(header.parent_beacon_block_root, None);
+    assert_eq!(header.blob_gas_used, None);
+    assert_eq!(header.excess_blob_gas, None);
+    assert_eq!(header.requests_hash, None);
+}
+
+#[test]
+fn test_built_block_after_isthmus_commits_to_message_passer_storage() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    app.genesis_config.hardforks.isthmus_time = Some(0);
+
+    app.start_block_build(
+        Payload {
+            parent_beacon_block_root: Some(B256::ZERO),
+            gas_limit: U64::MAX,
+            ..Default::default()
+        },
+        U64::from(0x03421ee50df45cacu64),
+    );
+
+    let header = reader.block_by_height(Latest, false).unwrap().block.header;
+    let message_passer = reader
+        .proof(L2_TO_L1_MESSAGE_PASSER, Vec::new(), BlockId::latest())
+        .unwrap();
+
+    assert_eq!(header.withdrawals_root, Some(message_passer.storage_hash));
+    assert_eq!(header.requests_hash, Some(EMPTY_REQUESTS_HASH));
+}
+
+#[test_case(B64::new([0, 0, 0, 50, 0, 0, 0, 4]), [0, 0, 0, 0, 50, 0, 0, 0, 4]; "given params")]
+#[test_case(B64::ZERO, [0, 0, 0, 0, 8, 0, 0, 0, 2]; "default params")]
+fn test_built_block_extra_data_holds_holocene_eip1559_params(
+    eip1559_params: B64,
+    expected_extra_data: [u8; 9],
+) {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+
+    app.start_block_build(
+        Payload {
+            eip1559_params: Some(eip1559_params),
+            gas_limit: U64::MAX,
+            ..Default::default()
+        },
+        U64::from(0x03421ee50df45cacu64),
+    );
+
+    let block = reader.block_by_height(Latest, false).unwrap().block;
+
+    assert_eq!(block.header.extra_data.as_ref(), expected_extra_data);
+    assert_eq!(block.header.ommers_hash, EMPTY_OMMER_ROOT_HASH);
+    assert_eq!(block.header.total_difficulty, Some(U256::ZERO));
+    assert!(block.header.size.unwrap() > U256::ZERO);
+}
+
+#[test]
+fn test_built_block_records_l1_origin_of_l1_attributes_deposit() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    let l1_number = 0x1234u64;
+    let l1_hash = B256::repeat_byte(0x11);
+
+    // Packed `setL1BlockValuesEcotone` calldata
+    let mut input = vec![0u8; 164];
+    input[0..4].copy_from_slice(&hex!("440a5e20"));
+    input[28..36].copy_from_slice(&l1_number.to_be_bytes());
+    input[100..132].copy_from_slice(l1_hash.as_slice());
+
+    let tx = OpTxEnvelope::Deposit(Sealed::new(TxDeposit {
+        source_hash: B256::repeat_byte(0x22),
+        from: address!("deaddeaddeaddeaddeaddeaddeaddeaddead0001"),
+        to: TxKind::Call(address!("4200000000000000000000000000000000000015")),
+        mint: None,
+        value: U256::ZERO,
+        gas_limit: 1_000_000,
+        is_system_transaction: false,
+        input: input.into(),
+    }));
+    let mut encoded = Vec::new();
+    tx.encode(&mut encoded);
+
+    app.start_block_build(
+        Payload {
+            transactions: vec![encoded.into()],
+            gas_limit: U64::MAX,
+            ..Default::default()
+        },
+        U64::from(0x03421ee50df45cacu64),
+    );
+
+    let actual_l1_origin = reader.l1_origin(Latest);
+    let expected_l1_origin = Some(L1Origin {
+        number: l1_number,
+        hash: l1_hash,
+    });
+
+    assert_eq!(actual_l1_origin, expected_l1_origin);
+}
+
+#[test]
+fn test_receipt_of_pending_transaction_is_none() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    let tx = create_transaction(0);
+    let tx_hash = *tx.tx_hash();
+
+    app.add_transaction(tx);
+
+    assert!(reader.transaction_receipt(tx_hash).is_none());
+    assert!(reader.transaction_receipt(B256::repeat_byte(1)).is_none());
+}
+
+#[test]
+fn test_failed_transaction_is_included_with_failed_receipt() {
+    // Not enough to transfer the amount of the transaction
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(3));
+    let tx = create_transaction(0);
+    let tx_hash = *tx.tx_hash();
+
+    app.add_transaction(tx);
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    let receipt = reader.transaction_receipt(tx_hash).unwrap();
+    assert!(!receipt.inner.inner.status());
+    assert!(reader.transaction_by_hash(tx_hash).is_some());
+}
+
+#[test]
+fn test_rejected_transaction_is_left_out_of_block_without_receipt() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    // The nonce of the sender is still zero
+    let tx = create_transaction(1);
+    let tx_hash = *tx.tx_hash();
+
+    app.add_transaction(tx);
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    assert!(reader.transaction_receipt(tx_hash).is_none());
+    assert!(reader.transaction_by_hash(tx_hash).is_none());
+    let block = reader.block_by_height(Latest, false).unwrap().block;
+    assert!(block.transactions.is_empty());
+}
+
+#[test]
+fn test_transaction_with_unknown_executing_message_is_rejected_after_interop() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    app.genesis_config.hardforks.interop_time = Some(0);
+    // Declares the first log of the genesis block, which has none
+    let mut lookup = B256::ZERO;
+    lookup[0] = 1;
+    lookup[4..12].copy_from_slice(&CHAIN_ID.to_be_bytes());
+    let mut checksum = B256::ZERO;
+    checksum[0] = 3;
+    let signer = Signer::new(&PRIVATE_KEY);
+    let mut tx = TxEip1559 {
+        chain_id: CHAIN_ID,
+        nonce: signer.nonce,
+        gas_limit: u64::MAX,
+        max_fee_per_gas: 0,
+        max_priority_fee_per_gas: 0,
+        to: TxKind::Call(Address::repeat_byte(0x11)),
+        value: U256::from(1),
+        access_list: AccessList(vec![AccessListItem {
+            address: CROSS_L2_INBOX,
+            storage_keys: vec![lookup, checksum],
+        }]),
+        input: Default::default(),
+    };
+    let signature = signer.inner.sign_transaction_sync(&mut tx).unwrap();
+    let tx = TxEnvelope::Eip1559(tx.into_signed(signature));
+    let tx_hash = *tx.tx_hash();
+
+    app.add_transaction(tx);
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    assert!(reader.transaction_receipt(tx_hash).is_none());
+    let block = reader.block_by_height(Latest, false).unwrap().block;
+    assert!(block.transactions.is_empty());
+}
+
+#[test]
+fn test_transaction_enters_mem_pool_with_its_sender_recovered() {
+    let (_reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    let tx = create_transaction(0);
+    let tx_hash = *tx.tx_hash();
+
+    app.add_transaction(tx);
+
+    let pooled = app.mem_pool.get(&tx_hash).unwrap();
+    match &pooled.normalized {
+        NormalizedExtendedTxEnvelope::Canonical(tx) => assert_eq!(tx.signer, EVM_ADDRESS),
+        NormalizedExtendedTxEnvelope::DepositedTx(_) => panic!("Transaction should be canonical"),
+    }
+}
+
+#[test_case(&[0], 1; "next nonce")]
+#[test_case(&[0, 1, 2], 3; "consecutive nonces")]
+#[test_case(&[0, 2], 1; "gap")]
+#[test_case(&[1], 0; "future nonce")]
+fn test_pending_nonce_counts_mem_pool_transactions(nonces: &[u64], expected_nonce: u64) {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+
+    for nonce in nonces {
+        app.add_transaction(create_transaction(*nonce));
+    }
+
+    assert_eq!(reader.nonce_by_height(EVM_ADDRESS, Latest), Some(0));
+    assert_eq!(
+        reader.nonce_by_height(EVM_ADDRESS, Pending),
+        Some(expected_nonce)
+    );
+}
+
+#[test]
+fn test_pending_nonce_counts_transactions_in_flight_until_block_is_committed() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+
+    let tx = create_transaction(0);
+    let tx_hash = *tx.tx_hash();
+
+    app.add_transaction(tx);
+    app.mem_pool.drain_by_priority(0);
+
+    assert!(app.mem_pool.get(&tx_hash).is_none());
+    assert_eq!(reader.nonce_by_height(EVM_ADDRESS, Pending), Some(1));
+
+    app.mem_pool.settle();
+
+    assert_eq!(reader.nonce_by_height(EVM_ADDRESS, Pending), Some(0));
+}
+
+#[test]
+fn test_pending_nonce_ignores_mem_pool_transactions_of_other_senders() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    let other_address = Address::repeat_byte(0x42);
+
+    app.add_transaction(create_transaction(0));
+
+    assert_eq!(reader.nonce_by_height(other_address, Pending), Some(0));
+}
+
+#[test]
+fn test_transaction_replaces_mem_pool_transaction_with_same_sender_and_nonce() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    let tx = create_transaction_with_fees(0, 100, 100);
+    let replacement = create_transaction_with_fees(0, 110, 110);
+    let (tx_hash, replacement_hash) = (*tx.tx_hash(), *replacement.tx_hash());
+
+    app.add_transaction(tx);
+    app.add_transaction(replacement);
+
+    assert!(app.mem_pool.get(&tx_hash).is_none());
+    assert!(app.mem_pool.get(&replacement_hash).is_some());
+    assert_eq!(reader.nonce_by_height(EVM_ADDRESS, Pending), Some(1));
+}
+
+#[test_case(110, 11, true; "both fees bumped")]
+#[test_case(200, 10, false; "priority fee not bumped")]
+#[test_case(109, 100, false; "max fee bumped too little")]
+fn test_replacement_must_bump_both_fees(
+    max_fee_per_gas: u128,
+    max_priority_fee_per_gas: u128,
+    expected: bool,
+) {
+    let pooled =
+        PooledTransaction::from_eth_envelope(create_transaction_with_fees(0, 100, 10)).unwrap();
+    let replacement = create_transaction_with_fees(0, max_fee_per_gas, max_priority_fee_per_gas);
+
+    assert_eq!(pooled.is_replaceable_by(&replacement, 10), expected);
+}
+
+#[test]
+fn test_mem_pool_keeps_transaction_when_replacement_is_underpriced() {
+    let pooled = |tx: TxEnvelope| {
+        let tx_hash = *tx.tx_hash();
+        (tx_hash, PooledTransaction::from_eth_envelope(tx).unwrap())
+    };
+    let (tx_hash, tx) = pooled(create_transaction_with_fees(0, 100, 10));
+    let (underpriced_hash, underpriced) = pooled(create_transaction_with_fees(0, 105, 11));
+    let (replacement_hash, replacement) = pooled(create_transaction_with_fees(0, 110, 11));
+    let mem_pool = MemPool::default();
+    mem_pool.insert(tx_hash, tx, 10).unwrap();
+
+    let result = mem_pool.insert(underpriced_hash, underpriced, 10);
+
+    assert_eq!(result, Err(ReplacementUnderpriced));
+    assert!(mem_pool.get(&tx_hash).is_some());
+    assert!(mem_pool.get(&underpriced_hash).is_none());
+
+    let result = mem_pool.insert(replacement_hash, replacement, 10);
+
+    assert_eq!(result, Ok(Some(tx_hash)));
+    assert!(mem_pool.get(&tx_hash).is_none());
+}
+
+fn queue_in(
+    mem_pool: &MemPool,
+    key_bytes: &[u8; 32],
+    nonces: impl IntoIterator<Item = u64>,
+    now: Instant,
+) -> Vec<B256> {
+    nonces
+        .into_iter()
+        .map(|nonce| {
+            let tx = create_transaction_signed_by(key_bytes, nonce, 0, 0);
+            let tx_hash = *tx.tx_hash();
+            let tx = PooledTransaction::from_eth_envelope(tx).unwrap();
+            mem_pool.insert(tx_hash, tx, 10).unwrap();
+            mem_pool.mark_queued(tx_hash, now);
+            tx_hash
+        })
+        .collect()
+}
+
+#[test]
+fn test_mem_pool_evicts_queued_transactions_past_their_lifetime() {
+    let limits = TxAdmissionParameters::default();
+    let lifetime = Duration::from_secs(limits.queued_lifetime_secs);
+    let now = Instant::now();
+    let mem_pool = MemPool::default();
+    let old = queue_in(&mem_pool, &PRIVATE_KEY, [1], now);
+    let recent = queue_in(&mem_pool, &PRIVATE_KEY, [2], now + lifetime);
+
+    assert!(mem_pool.evict_queued(&limits, now + lifetime).is_empty());
+
+    let evicted = mem_pool.evict_queued(&limits, now + lifetime + Duration::from_secs(1));
+
+    assert_eq!(evicted, old);
+    assert!(mem_pool.get(&old[0]).is_none());
+    assert!(mem_pool.get(&recent[0]).is_some());
+}
+
+#[test]
+fn test_mem_pool_evicts_highest_queued_nonces_over_the_account_limit() {
+    let limits = TxAdmissionParameters {
+        max_queued_per_account: 2,
+        ..Default::default()
+    };
+    let now = Instant::now();
+    let mem_pool = MemPool::default();
+    let queued = queue_in(&mem_pool, &PRIVATE_KEY, [4, 1, 3, 2], now);
+    let other = queue_in(&mem_pool, &[0xbb; 32], [1, 2], now);
+
+    let mut evicted = mem_pool.evict_queued(&limits, now);
+    evicted.sort();
+    let mut expected = vec![queued[0], queued[2]];
+    expected.sort();
+
+    assert_eq!(evicted, expected);
+    assert!(mem_pool.get(&queued[1]).is_some());
+    assert!(mem_pool.get(&queued[3]).is_some());
+    assert!(other.iter().all(|tx_hash| mem_pool.get(tx_hash).is_some()));
+}
+
+#[test]
+fn test_mem_pool_evicts_longest_queued_sender_over_the_global_limit() {
+    let limits = TxAdmissionParameters {
+        max_queued: 3,
+        ..Default::default()
+    };
+    let now = Instant::now();
+    let mem_pool = MemPool::default();
+    let oldest = queue_in(&mem_pool, &PRIVATE_KEY, [1, 2], now);
+    let newest = queue_in(&mem_pool, &[0xbb; 32], [1, 2], now + Duration::from_secs(1));
+
+    let evicted = mem_pool.evict_queued(&limits, now + Duration::from_secs(1));
+
+    assert_eq!(evicted, vec![oldest[1]]);
+    assert!(mem_pool.get(&oldest[0]).is_some());
+    assert!(newest.iter().all(|tx_hash| mem_pool.get(tx_hash).is_some()));
+}
+
+#[test]
+fn test_mem_pool_is_drained_by_tip_and_then_nonce() {
+    let pooled = |tx: TxEnvelope| {
+        let tx_hash = *tx.tx_hash();
+        (tx_hash, PooledTransaction::from_eth_envelope(tx).unwrap())
+    };
+    // The second transaction of the first sender pays the highest tip, but cannot go before its
+    // first one, which pays the lowest
+    let first = pooled(create_transaction_signed_by(&PRIVATE_KEY, 0, 100, 1));
+    let second = pooled(create_transaction_signed_by(&PRIVATE_KEY, 1, 100, 50));
+    let other = pooled(create_transaction_signed_by(&[0xbb; 32], 0, 100, 20));
+    let mem_pool = MemPool::default();
+    for (tx_hash, tx) in [second.clone(), other.clone(), first.clone()] {
+        mem_pool.insert(tx_hash, tx, 10).unwrap();
+    }
+
+    let order: Vec<_> = mem_pool
+        .drain_by_priority(10)
+        .into_iter()
+        .map(|(tx_hash, _)| tx_hash)
+        .collect();
+
+    assert_eq!(order, [other.0, first.0, second.0]);
+}
+
+#[test]
+fn test_mem_pool_keeps_sender_below_base_fee_when_drained() {
+    let pooled = |tx: TxEnvelope| {
+        let tx_hash = *tx.tx_hash();
+        (tx_hash, PooledTransaction::from_eth_envelope(tx).unwrap())
+    };
+    let first = pooled(create_transaction_signed_by(&PRIVATE_KEY, 0, 5, 1));
+    let second = pooled(create_transaction_signed_by(&PRIVATE_KEY, 1, 100, 50));
+    let other = pooled(create_transaction_signed_by(&[0xbb; 32], 0, 100, 20));
+    let mem_pool = MemPool::default();
+    for (tx_hash, tx) in [first.clone(), second.clone(), other.clone()] {
+        mem_pool.insert(tx_hash, tx, 10).unwrap();
+    }
+
+    let order: Vec<_> = mem_pool
+        .drain_by_priority(10)
+        .into_iter()
+        .map(|(tx_hash, _)| tx_hash)
+        .collect();
+
+    assert_eq!(order, [other.0]);
+    assert!(mem_pool.get(&first.0).is_some());
+    assert!(mem_pool.get(&second.0).is_some());
+}
+
+#[test]
+fn test_transaction_behind_nonce_gap_is_queued_until_gap_is_filled() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(10));
+    let (first, second) = (create_transaction(0), create_transaction(1));
+    let (first_hash, second_hash) = (*first.tx_hash(), *second.tx_hash());
+
+    app.add_transaction(second);
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    assert!(reader.transaction_by_hash(second_hash).is_none());
+    assert!(app.mem_pool.get(&second_hash).is_some());
+
+    app.add_transaction(first);
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cadu64));
+
+    assert!(reader.transaction_by_hash(first_hash).is_some());
+    assert!(reader.transaction_by_hash(second_hash).is_some());
+}
+
+#[test]
+fn test_pooled_transactions_past_deadline_are_deferred_to_mem_pool() {
+    let (_reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(10));
+    let (forced, pooled) = (create_transaction(0), create_transaction(1));
+    let (forced_hash, pooled_hash) = (*forced.tx_hash(), *pooled.tx_hash());
+    let transactions = [forced, pooled].map(|tx| {
+        let tx_hash = *tx.tx_hash();
+        (tx_hash, PooledTransaction::from_eth_envelope(tx).unwrap())
+    });
+    let header = HeaderForExecution {
+        number: 1,
+        ..Default::default()
+    };
+
+    // The deadline has passed before the first transaction is executed
+    let (_, receipts, rejected) = app.execute_transactions(
+        transactions.into_iter(),
+        &[],
+        &EMPTY_ROOT_HASH,
+        U256::ZERO,
+        &header,
+        1,
+        Instant::now(),
+    );
+
+    let executed: Vec<_> = receipts.iter().map(|rx| rx.transaction_hash).collect();
+    assert_eq!(executed, [forced_hash]);
+    assert!(rejected.is_empty());
+    assert!(app.mem_pool.get(&forced_hash).is_none());
+    assert!(app.mem_pool.get(&pooled_hash).is_some());
+}
+
+#[test]
+fn test_transactions_below_base_fee_are_not_included() {
+    let (_reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(10));
+    let (forced, pooled) = (create_transaction(0), create_transaction(1));
+    let (forced_hash, pooled_hash) = (*forced.tx_hash(), *pooled.tx_hash());
+    let transactions = [forced, pooled].map(|tx| {
+        let tx_hash = *tx.tx_hash();
+        (tx_hash, PooledTransaction::from_eth_envelope(tx).unwrap())
+    });
+    let header = HeaderForExecution {
+        number: 1,
+        ..Default::default()
+    };
+
+    // Both transactions have a max fee per gas of 0
+    let (_, receipts, rejected) = app.execute_transactions(
+        transactions.into_iter(),
+        &[],
+        &EMPTY_ROOT_HASH,
+        U256::from(1),
+        &header,
+        1,
+        Instant::now() + Duration::from_secs(60),
+    );
+
+    assert!(receipts.is_empty());
+    assert_eq!(
+        rejected,
+        [RejectedTransaction::new(
+            forced_hash,
+            InvalidTransactionCause::FeeBelowBaseFee {
+                base_fee: 1,
+                given: 0
+            }
+        )]
+    );
+    assert!(app.mem_pool.get(&forced_hash).is_none());
+    assert!(app.mem_pool.get(&pooled_hash).is_some());
+}
+
+#[test]
+fn test_journaled_transaction_is_restored_until_included() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    let tx = create_transaction(0);
+    let tx_hash = *tx.tx_hash();
+
+    app.add_transaction(tx);
+    app.mem_pool.drain();
+    app.restore_mem_pool();
+
+    assert!(app.mem_pool.get(&tx_hash).is_some());
+
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    assert!(reader.transaction_by_hash(tx_hash).is_some());
+    assert!(app.transaction_journal.pending().unwrap().is_empty());
+}
+
+#[test]
+fn test_inclusion_proofs_verify_against_block_header_roots() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(10));
+    let tx_hashes: Vec<_> = (0..2)
+        .map(|nonce| {
+            let tx = create_transaction(nonce);
+            let tx_hash = *tx.tx_hash();
+            app.add_transaction(tx);
+            tx_hash
+        })
+        .collect();
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    let header = reader.block_by_height(Latest, false).unwrap().block.header;
+    for tx_hash in tx_hashes {
+        let transaction_proof = reader.transaction_proof(tx_hash).unwrap();
+        let receipt_proof = reader.receipt_proof(tx_hash).unwrap();
+
+        for (proof, root) in [
+            (transaction_proof, header.transactions_root),
+            (receipt_proof, header.receipts_root),
+        ] {
+            assert_eq!(proof.block_hash, header.hash);
+            assert_eq!(proof.root, root);
+            alloy_trie::proof::verify_proof(
+                root,
+                alloy_trie::Nibbles::unpack(&proof.key),
+                Some(proof.value.to_vec()),
+                &proof.proof,
+            )
+            .unwrap();
+        }
+    }
+    assert!(reader.transaction_proof(B256::repeat_byte(1)).is_none());
+    assert!(reader.receipt_proof(B256::repeat_byte(1)).is_none());
+}
+
 #[test]
 fn test_fetched_nonces_are_updated_after_executing_transaction() {
     let to = Address::new(hex!("44223344556677889900ffeeaabbccddee111111"));
@@ -483,3 +1243,310 @@ fn test_older_payload_can_be_fetched_again_successfully() {
 
     assert_eq!(expected_payload, actual_payload);
 }
+
+#[test]
+fn test_pending_payload_job_is_built_on_resume() {
+    let initial_balance = U256::from(5);
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), initial_balance);
+
+    let payload_id = U64::from(0x03421ee50df45cacu64);
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    app.payload_job_repository
+        .add(
+            payload_id,
+            &Payload {
+                timestamp: U64::from(now),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    assert!(reader.payload(payload_id).is_none());
+
+    app.resume_payload_jobs();
+
+    assert!(reader.payload(payload_id).is_some());
+    assert!(app.payload_job_repository.pending().unwrap().is_empty());
+}
+
+#[test]
+fn test_expired_payload_job_is_dropped_on_resume() {
+    let initial_balance = U256::from(5);
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), initial_balance);
+
+    let payload_id = U64::from(0x03421ee50df45cacu64);
+
+    app.payload_job_repository
+        .add(payload_id, &Payload::default())
+        .unwrap();
+
+    app.resume_payload_jobs();
+
+    assert!(reader.payload(payload_id).is_none());
+    assert!(app.payload_job_repository.pending().unwrap().is_empty());
+    assert_eq!(app.payload_expiry.counts().dropped_jobs, 1);
+}
+
+#[test]
+fn test_expired_payload_is_removed_from_storage() {
+    let initial_balance = U256::from(5);
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), initial_balance);
+    let payload_id = U64::from(0x03421ee50df45cacu64);
+    let fetched_payload_id = U64::from(0x03421ee50df45cadu64);
+    let next_payload_id = U64::from(0x03421ee50df45caeu64);
+
+    app.start_block_build(Default::default(), payload_id);
+    app.start_block_build(
+        Payload {
+            timestamp: U64::from(1u64),
+            ..Default::default()
+        },
+        fetched_payload_id,
+    );
+    app.payload_expiry.record_fetch(fetched_payload_id);
+
+    assert!(reader.payload(payload_id).is_some());
+
+    // Both payloads live for ten block times of a second
+    app.start_block_build(
+        Payload {
+            timestamp: U64::from(12u64),
+            ..Default::default()
+        },
+        next_payload_id,
+    );
+
+    assert!(reader.payload(payload_id).is_none());
+    assert!(reader.payload(fetched_payload_id).is_none());
+    assert!(reader.payload(next_payload_id).is_some());
+    assert_eq!(app.payload_expiry.counts().unfetched_payloads, 1);
+}
+
+#[test]
+fn test_payloads_built_before_restart_are_removed_once_expired() {
+    let initial_balance = U256::from(5);
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), initial_balance);
+    let payload_id = U64::from(0x03421ee50df45cacu64);
+
+    app.start_block_build(Default::default(), payload_id);
+    // The payloads tracked before are lost on restart
+    app.payload_expiry = Default::default();
+    app.resume_payload_jobs();
+    app.start_block_build(
+        Payload {
+            timestamp: U64::from(11u64),
+            ..Default::default()
+        },
+        U64::from(0x03421ee50df45cadu64),
+    );
+
+    assert!(reader.payload(payload_id).is_none());
+    assert_eq!(app.payload_expiry.counts().unfetched_payloads, 0);
+}
+
+#[test]
+fn test_payload_job_waiting_too_long_is_dropped() {
+    let initial_balance = U256::from(5);
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), initial_balance);
+    let payload_id = U64::from(0x03421ee50df45cacu64);
+    let fresh_payload_id = U64::from(0x03421ee50df45cadu64);
+
+    // The payload would live for ten block times of a second
+    let requested_at = Instant::now() - Duration::from_secs(11);
+    app.start_requested_block_build(Default::default(), payload_id, requested_at);
+
+    assert!(reader.payload(payload_id).is_none());
+    assert_eq!(app.payload_expiry.counts().dropped_jobs, 1);
+
+    app.start_requested_block_build(Default::default(), fresh_payload_id, Instant::now());
+
+    assert!(reader.payload(fresh_payload_id).is_some());
+}
+
+#[derive(Default)]
+struct CountingObserver {
+    txs: Arc<AtomicUsize>,
+    batches: Arc<AtomicUsize>,
+}
+
+impl<D: Dependencies> BlockExecutionObserver<D> for CountingObserver {
+    fn on_tx(&self, _app: &mut Application<D>, _changes: &Arc<ChangeSet>) {
+        self.txs.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_tx_batch(&self, _app: &mut Application<D>) {
+        self.batches.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_all_registered_observers_are_notified_about_block_execution() {
+    let initial_balance = U256::from(5);
+    let (_reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), initial_balance);
+
+    let observers = [CountingObserver::default(), CountingObserver::default()];
+    let counters: Vec<_> = observers
+        .iter()
+        .map(|observer| (observer.txs.clone(), observer.batches.clone()))
+        .collect();
+
+    for observer in observers {
+        app.observers.push(Box::new(observer));
+    }
+
+    app.add_transaction(create_transaction(0));
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    for (txs, batches) in counters {
+        assert_eq!(txs.load(Ordering::SeqCst), 1);
+        assert_eq!(batches.load(Ordering::SeqCst), 1);
+    }
+    assert_eq!(app.observers.len(), 2);
+}
+
+#[test]
+fn test_trie_consistency_checker_accepts_built_block() {
+    let (reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    app.observers
+        .push(Box::new(crate::TrieConsistencyChecker::default()));
+
+    app.add_transaction(create_transaction(0));
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    assert_eq!(reader.nonce_by_height(EVM_ADDRESS, Latest).unwrap(), 1);
+}
+
+#[test]
+fn test_trie_preimage_recorder_records_keys_of_built_block() {
+    let (_reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    let preimages = InMemoryTriePreimages::default();
+    app.observers
+        .push(Box::new(crate::TriePreimageRecorder::new(
+            preimages.clone(),
+        )));
+
+    app.add_transaction(create_transaction(0));
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    // The nonce of the sender is kept in its Move account
+    let account_tag = StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("account").into(),
+        name: ident_str!("Account").into(),
+        type_args: Vec::new(),
+    };
+    let account_key = StateKey::resource(&EVM_ADDRESS.to_move_address(), &account_tag).unwrap();
+    assert_eq!(
+        preimages
+            .preimage(keccak256(account_key.encoded()))
+            .unwrap()
+            .as_deref(),
+        Some(account_key.encoded())
+    );
+}
+
+#[derive(Clone, Default)]
+struct CollectedWitnesses(Arc<Mutex<Vec<ExecutionWitness>>>);
+
+impl ExecutionWitnessExport for CollectedWitnesses {
+    type Err = std::convert::Infallible;
+
+    fn export(&self, witness: ExecutionWitness) -> Result<(), Self::Err> {
+        self.0.lock().unwrap().push(witness);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_execution_witness_recorder_exports_witness_of_built_block() {
+    let (_reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), U256::from(5));
+    let witnesses = CollectedWitnesses::default();
+    app.observers
+        .push(Box::new(ExecutionWitnessRecorder::new(witnesses.clone())));
+    let parent_state_root = app.state.state_root();
+
+    app.add_transaction(create_transaction(0));
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+
+    let witnesses = witnesses.0.lock().unwrap();
+    let [witness] = witnesses.as_slice() else {
+        panic!("Expected one witness, got {witnesses:?}");
+    };
+    assert_eq!(witness.parent_state_root, parent_state_root);
+    // The paths to every key start at the root node of the parent state
+    assert!(
+        witness
+            .state
+            .iter()
+            .any(|node| keccak256(node) == parent_state_root)
+    );
+    // Executing a transaction runs framework modules
+    assert!(!witness.codes.is_empty());
+}
+
+struct ForwardingExtension(mpsc::Sender<(u64, usize, usize)>);
+
+impl ExecutionExtension for ForwardingExtension {
+    fn name(&self) -> &str {
+        "forwarding"
+    }
+
+    fn on_block_committed(&mut self, block: &CommittedBlock) {
+        let number = block.block.block.header.number;
+        self.0
+            .send((number, block.receipts.len(), block.state_diff.len()))
+            .unwrap();
+    }
+}
+
+struct PanickingExtension;
+
+impl ExecutionExtension for PanickingExtension {
+    fn name(&self) -> &str {
+        "panicking"
+    }
+
+    fn on_block_committed(&mut self, _block: &CommittedBlock) {
+        panic!("Extension failure");
+    }
+}
+
+#[test]
+fn test_extensions_receive_committed_blocks_despite_a_failing_one() {
+    let initial_balance = U256::from(5);
+    let (_reader, mut app) =
+        create_app_with_fake_queries(EVM_ADDRESS.to_move_address(), initial_balance);
+    let (tx, rx) = mpsc::channel();
+
+    app.observers.push(Box::new(ExtensionObserver::new(vec![
+        Box::new(PanickingExtension),
+        Box::new(ForwardingExtension(tx)),
+    ])));
+
+    app.add_transaction(create_transaction(0));
+    app.start_block_build(Default::default(), U64::from(0x03421ee50df45cacu64));
+    app.start_block_build(
+        Payload {
+            timestamp: U64::from(1u64),
+            ..Default::default()
+        },
+        U64::from(0x03421ee50df45dadu64),
+    );
+
+    let (number, receipts, state_diff) = rx.recv().unwrap();
+    assert_eq!((receipts, state_diff), (1, 1));
+    assert_eq!(rx.recv().unwrap(), (number + 1, 0, 0));
+}
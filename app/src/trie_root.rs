@@ -0,0 +1,55 @@
+use {
+    alloy::rlp::Encodable,
+    alloy_trie::{HashBuilder, Nibbles, root::adjust_index_for_rlp},
+    moved_shared::primitives::B256,
+};
+
+/// Builds the root of an ordered trie out of items pushed one at a time, the same way as
+/// [`alloy_trie::root::ordered_trie_root`].
+///
+/// The leaves are keyed by the RLP encoding of their index, which does not sort like the index
+/// itself, so no leaf can be hashed before the number of items is known. Each item is encoded as
+/// soon as it is pushed instead, which leaves a single pass over the encoded leaves for the root.
+#[derive(Debug, Default)]
+pub(crate) struct OrderedTrieRoot {
+    leaves: Vec<Vec<u8>>,
+}
+
+impl OrderedTrieRoot {
+    pub fn push(&mut self, item: &impl Encodable) {
+        let mut leaf = Vec::with_capacity(item.length());
+        item.encode(&mut leaf);
+        self.leaves.push(leaf);
+    }
+
+    pub fn root(&self) -> B256 {
+        let len = self.leaves.len();
+        let mut builder = HashBuilder::default();
+        for i in 0..len {
+            let index = adjust_index_for_rlp(i, len);
+            builder.add_leaf(
+                Nibbles::unpack(alloy::rlp::encode_fixed_size(&index)),
+                &self.leaves[index],
+            );
+        }
+        builder.root()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, test_case::test_case};
+
+    #[test_case(0; "empty")]
+    #[test_case(1; "single leaf")]
+    #[test_case(3; "few leaves")]
+    #[test_case(200; "leaves keyed by one and two byte indices")]
+    fn test_root_matches_ordered_trie_root(len: u64) {
+        let items: Vec<u64> = (0..len).map(|i| i * 1_000_003).collect();
+        let mut root = OrderedTrieRoot::default();
+
+        items.iter().for_each(|item| root.push(item));
+
+        assert_eq!(root.root(), alloy_trie::root::ordered_trie_root(&items));
+    }
+}
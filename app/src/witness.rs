@@ -0,0 +1,148 @@
+use {
+    crate::{Application, BlockExecutionObserver, Dependencies},
+    move_core_types::effects::ChangeSet,
+    moved_blockchain::block::ExtendedBlock,
+    moved_shared::primitives::{B256, Bytes},
+    moved_state::{
+        State,
+        consistency::{self, ResolverKey},
+        witness::{self, StateReads},
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeSet,
+        fmt::Debug,
+        fs, io,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// The part of the state that a block is executed on, enough to execute it again without the rest
+/// of the state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionWitness {
+    pub block_hash: B256,
+    pub block_number: u64,
+    /// Root of the state trie the block is executed on, which is that of its parent.
+    pub parent_state_root: B256,
+    /// Nodes of the state trie on the paths to every key the block reads or writes.
+    pub state: Vec<Bytes>,
+    /// Nodes of the EVM storage tries the block reads.
+    pub storage: Vec<Bytes>,
+    /// Bytecode of the Move modules and the EVM contracts the block reads.
+    pub codes: Vec<Bytes>,
+}
+
+/// Destination of the witnesses built by an [`ExecutionWitnessRecorder`].
+pub trait ExecutionWitnessExport {
+    type Err: Debug;
+
+    fn export(&self, witness: ExecutionWitness) -> Result<(), Self::Err>;
+}
+
+/// Writes each witness as JSON into a directory, to a file named after its block number.
+#[derive(Debug, Clone)]
+pub struct ExecutionWitnessDir {
+    dir: PathBuf,
+}
+
+impl ExecutionWitnessDir {
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+}
+
+impl ExecutionWitnessExport for ExecutionWitnessDir {
+    type Err = io::Error;
+
+    fn export(&self, witness: ExecutionWitness) -> Result<(), Self::Err> {
+        let path = self.dir.join(format!("{}.json", witness.block_number));
+        fs::write(path, serde_json::to_vec(&witness)?)
+    }
+}
+
+/// Records the state that each block reads and writes, and exports it as an [`ExecutionWitness`]
+/// once the block is committed.
+///
+/// Every state read is recorded and every witness costs a trie walk per key, so recording is meant
+/// for nodes that serve fault proofs or replay blocks in isolation. A failure to build or export a
+/// witness is reported without stopping block production.
+#[derive(Debug)]
+pub struct ExecutionWitnessRecorder<E> {
+    export: E,
+    pending: Mutex<PendingWitness>,
+}
+
+#[derive(Debug, Default)]
+struct PendingWitness {
+    parent_state_root: Option<B256>,
+    written: BTreeSet<ResolverKey>,
+    reads: StateReads,
+}
+
+impl<E> ExecutionWitnessRecorder<E> {
+    pub fn new(export: E) -> Self {
+        Self {
+            export,
+            pending: Mutex::new(PendingWitness::default()),
+        }
+    }
+}
+
+impl<D: Dependencies, E: ExecutionWitnessExport + Send + Sync> BlockExecutionObserver<D>
+    for ExecutionWitnessRecorder<E>
+{
+    fn on_tx(&self, app: &mut Application<D>, changes: &Arc<ChangeSet>) {
+        let mut pending = self.pending.lock().unwrap();
+        // The first transaction of a block is executed on the state of the parent block
+        pending
+            .parent_state_root
+            .get_or_insert_with(|| app.state.state_root());
+        pending.written.extend(consistency::touched_keys(changes));
+    }
+
+    fn records_state_reads(&self) -> bool {
+        true
+    }
+
+    fn on_state_reads(&self, _app: &mut Application<D>, reads: &StateReads) {
+        self.pending.lock().unwrap().reads.extend(reads.clone());
+    }
+
+    fn on_block(&self, app: &mut Application<D>, block: &ExtendedBlock) {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        // A block with no transactions leaves the state of its parent as is
+        let parent_state_root = pending
+            .parent_state_root
+            .unwrap_or_else(|| app.state.state_root());
+        let keys = pending.reads.keys.iter().chain(&pending.written);
+        let state = match witness::state_trie_nodes(app.state.db(), parent_state_root, keys) {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                println!("WARN: failed to collect the state trie nodes of a witness: {e:?}");
+                return;
+            }
+        };
+        let witness = ExecutionWitness {
+            block_hash: block.hash,
+            block_number: block.block.header.number,
+            parent_state_root,
+            state: state.into_iter().map(Bytes::from).collect(),
+            storage: pending
+                .reads
+                .storage_nodes
+                .into_iter()
+                .map(Bytes::from)
+                .collect(),
+            codes: pending.reads.codes.into_iter().map(Bytes::from).collect(),
+        };
+
+        if let Err(e) = self.export.export(witness) {
+            println!("WARN: failed to export an execution witness: {e:?}");
+        }
+    }
+}
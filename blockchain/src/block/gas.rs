@@ -1,6 +1,9 @@
 //! This module is concerned about calculating fees charged for gas usage.
 
-use {moved_shared::primitives::U256, std::cmp::Ordering};
+use {
+    moved_shared::primitives::{B64, U256},
+    std::cmp::Ordering,
+};
 
 /// Determines amount of fees charged per gas used in transaction execution.
 ///
@@ -16,6 +19,13 @@ pub trait BaseGasFee {
         parent_gas_used: u64,
         parent_base_fee_per_gas: U256,
     ) -> U256;
+
+    /// Encodes the parameters of the formula the way the Holocene upgrade of the OP stack expects
+    /// them in the block header `extraData`.
+    ///
+    /// The first 4 bytes are the big-endian max change denominator, the other 4 bytes are the
+    /// big-endian elasticity multiplier.
+    fn eip1559_params(&self) -> B64;
 }
 
 /// Calculates base fee per gas according to the Ethereum model based on EIP-1559.
@@ -101,6 +111,16 @@ impl BaseGasFee for Eip1559GasFee {
             Ordering::Equal => parent_base_fee_per_gas,
         }
     }
+
+    fn eip1559_params(&self) -> B64 {
+        let denominator: u32 = self.base_fee_max_change_denominator.saturating_to();
+        let elasticity = u32::try_from(self.elasticity_multiplier).unwrap_or(u32::MAX);
+        let mut params = [0u8; 8];
+        params[..4].copy_from_slice(&denominator.to_be_bytes());
+        params[4..].copy_from_slice(&elasticity.to_be_bytes());
+
+        B64::new(params)
+    }
 }
 
 #[cfg(any(feature = "test-doubles", test))]
@@ -162,4 +182,12 @@ mod tests {
 
         assert!(actual_fee < parent_fee, "{actual_fee} < {parent_fee}");
     }
+
+    #[test]
+    fn test_eip1559_params_encode_denominator_then_elasticity() {
+        let actual_params = Eip1559GasFee::new(6, U256::from(250)).eip1559_params();
+        let expected_params = B64::new([0, 0, 0, 250, 0, 0, 0, 6]);
+
+        assert_eq!(actual_params, expected_params);
+    }
 }
@@ -1,5 +1,9 @@
 use {
-    crate::{block::ExtendedBlock, payload::PayloadId},
+    crate::{
+        block::ExtendedBlock,
+        in_memory::{COMPACTION_INTERVAL, EvictionWindow},
+        payload::PayloadId,
+    },
     moved_shared::primitives::B256,
     std::sync::Arc,
 };
@@ -10,16 +14,37 @@ pub type WriteHeights = evmap::WriteHandle<u64, Arc<ExtendedBlock>>;
 pub type ReadHeights = evmap::ReadHandle<u64, Arc<ExtendedBlock>>;
 pub type WritePayloadIds = evmap::WriteHandle<PayloadId, Arc<ExtendedBlock>>;
 pub type ReadPayloadIds = evmap::ReadHandle<PayloadId, Arc<ExtendedBlock>>;
+pub type WriteMarkers = evmap::WriteHandle<ForkchoiceMarker, u64>;
+pub type ReadMarkers = evmap::ReadHandle<ForkchoiceMarker, u64>;
+
+/// Fewest blocks kept by a [`BlockMemory`] with a limited capacity, as the `BLOCKHASH` opcode reads
+/// the hashes of the last 256 blocks.
+pub const MIN_RETAINED_BLOCKS: u64 = 256;
+
+/// A block height marked by the consensus layer in a forkchoice update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ForkchoiceMarker {
+    /// The highest block added, which the next one builds on.
+    Head,
+    Safe,
+    Finalized,
+}
 
 /// A storage for blocks that keeps data in memory.
 ///
 /// The repository keeps data stored locally and its memory is not shared outside the struct. It
 /// maintains a set of indices for efficient lookup.
+///
+/// Every block is kept unless the storage is bounded by [`Self::set_retained_blocks`].
 #[derive(Debug)]
 pub struct BlockMemory {
     hashes: WriteHashes,
     heights: WriteHeights,
     payload_ids: WritePayloadIds,
+    markers: WriteMarkers,
+    retained_blocks: Option<u64>,
+    /// Lowest height above genesis that has not been trimmed yet
+    window: EvictionWindow,
 }
 
 impl BlockMemory {
@@ -27,23 +52,117 @@ impl BlockMemory {
         hashes: WriteHashes,
         heights: WriteHeights,
         payload_ids: WritePayloadIds,
+        markers: WriteMarkers,
     ) -> Self {
         Self {
             hashes,
             heights,
             payload_ids,
+            markers,
+            retained_blocks: None,
+            window: EvictionWindow::default(),
         }
     }
 
+    /// Keeps only the `retained_blocks` highest blocks, but at least [`MIN_RETAINED_BLOCKS`], and
+    /// trims older ones as new blocks are added.
+    ///
+    /// The genesis block, and the safe and finalized blocks along with every block above them, are
+    /// never trimmed, so that every block tag resolves.
+    pub fn set_retained_blocks(&mut self, retained_blocks: u64) {
+        self.retained_blocks = Some(retained_blocks.max(MIN_RETAINED_BLOCKS));
+    }
+
+    /// Lowest height above genesis whose block is still kept.
+    pub fn lowest_height(&self) -> u64 {
+        self.window.lowest_height()
+    }
+
+    /// The window of the kept blocks, which moves up as blocks are trimmed.
+    pub fn eviction_window(&self) -> EvictionWindow {
+        self.window.clone()
+    }
+
     pub fn add(&mut self, block: ExtendedBlock) {
+        let height = block.block.header.number;
+        let head = self
+            .marker(ForkchoiceMarker::Head)
+            .map_or(height, |head| head.max(height));
         let block = Arc::new(block);
         self.hashes.insert(block.hash, block.clone());
-        self.heights
-            .insert(block.block.header.number, block.clone());
+        self.heights.insert(height, block.clone());
         self.payload_ids.insert(block.payload_id, block);
+        self.markers.update(ForkchoiceMarker::Head, head);
+        self.trim(head);
         self.hashes.refresh();
         self.heights.refresh();
         self.payload_ids.refresh();
+        self.markers.refresh();
+    }
+
+    /// Removes `payload_id` from the index, unless another block than the one with `block_hash` has
+    /// been built under it since.
+    pub fn remove_payload_id(&mut self, payload_id: PayloadId, block_hash: B256) {
+        self.forget_payload_id(payload_id, block_hash);
+        self.payload_ids.refresh();
+    }
+
+    fn forget_payload_id(&mut self, payload_id: PayloadId, block_hash: B256) {
+        let same_payload = self
+            .payload_ids
+            .get_one(&payload_id)
+            .is_some_and(|other| other.hash == block_hash);
+        if same_payload {
+            self.payload_ids.empty(payload_id);
+        }
+    }
+
+    /// Removes the blocks below the retained ones while `head` is the highest block.
+    ///
+    /// Only a block or two fall out of the retained ones with each block added, so trimming as they
+    /// are added keeps the indices compact without ever pausing for a large sweep.
+    fn trim(&mut self, head: u64) {
+        let Some(retained_blocks) = self.retained_blocks else {
+            return;
+        };
+        let horizon = [ForkchoiceMarker::Safe, ForkchoiceMarker::Finalized]
+            .into_iter()
+            .filter_map(|marker| self.marker(marker))
+            .fold((head + 1).saturating_sub(retained_blocks), u64::min);
+
+        let trimmed_from = self.lowest_height();
+        let mut lowest_height = trimmed_from;
+        while lowest_height < horizon {
+            let blocks: Vec<_> = self
+                .heights
+                .get(&lowest_height)
+                .map(|blocks| blocks.iter().cloned().collect())
+                .unwrap_or_default();
+            for block in blocks {
+                self.hashes.empty(block.hash);
+                self.forget_payload_id(block.payload_id, block.hash);
+            }
+            self.heights.empty(lowest_height);
+            lowest_height += 1;
+        }
+        self.window.advance(lowest_height);
+
+        if trimmed_from / COMPACTION_INTERVAL != lowest_height / COMPACTION_INTERVAL {
+            self.compact();
+        }
+    }
+
+    /// Shrinks the indices to the blocks they still hold, as the memory of the trimmed ones is
+    /// otherwise only reused by new blocks.
+    fn compact(&mut self) {
+        self.hashes.fit_all();
+        self.heights.fit_all();
+        self.payload_ids.fit_all();
+    }
+
+    pub fn mark(&mut self, marker: ForkchoiceMarker, height: u64) {
+        self.markers.update(marker, height);
+        self.markers.refresh();
     }
 }
 
@@ -65,11 +184,18 @@ impl AsRef<ReadPayloadIds> for BlockMemory {
     }
 }
 
+impl AsRef<ReadMarkers> for BlockMemory {
+    fn as_ref(&self) -> &ReadMarkers {
+        &self.markers
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BlockMemoryReader {
     hashes: ReadHashes,
     heights: ReadHeights,
     payload_ids: ReadPayloadIds,
+    markers: ReadMarkers,
 }
 
 impl BlockMemoryReader {
@@ -77,11 +203,13 @@ impl BlockMemoryReader {
         hashes: ReadHashes,
         heights: ReadHeights,
         payload_ids: ReadPayloadIds,
+        markers: ReadMarkers,
     ) -> Self {
         Self {
             hashes,
             heights,
             payload_ids,
+            markers,
         }
     }
 }
@@ -104,9 +232,16 @@ impl AsRef<ReadPayloadIds> for BlockMemoryReader {
     }
 }
 
+impl AsRef<ReadMarkers> for BlockMemoryReader {
+    fn as_ref(&self) -> &ReadMarkers {
+        &self.markers
+    }
+}
+
 pub trait ReadBlockMemory {
     fn by_hash(&self, hash: B256) -> Option<ExtendedBlock>;
     fn by_payload_id(&self, payload_id: PayloadId) -> Option<ExtendedBlock>;
+    fn map_by_hash<U>(&self, hash: B256, f: impl FnOnce(&'_ ExtendedBlock) -> U) -> Option<U>;
     fn by_height(&self, height: u64) -> Option<ExtendedBlock> {
         self.map_by_height(height, Clone::clone)
     }
@@ -115,9 +250,12 @@ pub trait ReadBlockMemory {
     fn last(&self) -> Option<ExtendedBlock> {
         self.by_height(self.height()?)
     }
+    fn marker(&self, marker: ForkchoiceMarker) -> Option<u64>;
 }
 
-impl<T: AsRef<ReadHashes> + AsRef<ReadHeights> + AsRef<ReadPayloadIds>> ReadBlockMemory for T {
+impl<T: AsRef<ReadHashes> + AsRef<ReadHeights> + AsRef<ReadPayloadIds> + AsRef<ReadMarkers>>
+    ReadBlockMemory for T
+{
     fn by_hash(&self, hash: B256) -> Option<ExtendedBlock> {
         <T as AsRef<ReadHashes>>::as_ref(self)
             .get_one(&hash)
@@ -130,6 +268,12 @@ impl<T: AsRef<ReadHashes> + AsRef<ReadHeights> + AsRef<ReadPayloadIds>> ReadBloc
             .map(|v| ExtendedBlock::clone(&v))
     }
 
+    fn map_by_hash<U>(&self, hash: B256, f: impl FnOnce(&'_ ExtendedBlock) -> U) -> Option<U> {
+        <T as AsRef<ReadHashes>>::as_ref(self)
+            .get_one(&hash)
+            .map(|v| f(&v))
+    }
+
     fn map_by_height<U>(&self, height: u64, f: impl FnOnce(&'_ ExtendedBlock) -> U) -> Option<U> {
         <T as AsRef<ReadHeights>>::as_ref(self)
             .get_one(&height)
@@ -137,6 +281,12 @@ impl<T: AsRef<ReadHashes> + AsRef<ReadHeights> + AsRef<ReadPayloadIds>> ReadBloc
     }
 
     fn height(&self) -> Option<u64> {
-        (<T as AsRef<ReadHeights>>::as_ref(self).len() as u64).checked_sub(1)
+        self.marker(ForkchoiceMarker::Head)
+    }
+
+    fn marker(&self, marker: ForkchoiceMarker) -> Option<u64> {
+        <T as AsRef<ReadMarkers>>::as_ref(self)
+            .get_one(&marker)
+            .map(|v| *v)
     }
 }
@@ -14,9 +14,13 @@ pub use {
     gas::{BaseGasFee, Eip1559GasFee},
     hash::{BlockHash, MovedBlockHash},
     in_memory::{
-        BlockMemory, BlockMemoryReader, ReadBlockMemory, ReadHashes, ReadHeights, ReadPayloadIds,
-        WriteHashes, WriteHeights, WritePayloadIds,
+        BlockMemory, BlockMemoryReader, ForkchoiceMarker, MIN_RETAINED_BLOCKS, ReadBlockMemory,
+        ReadHashes, ReadHeights, ReadMarkers, ReadPayloadIds, WriteHashes, WriteHeights,
+        WriteMarkers, WritePayloadIds,
     },
     read::{BlockQueries, BlockResponse, in_memory::InMemoryBlockQueries},
-    write::{Block, BlockRepository, ExtendedBlock, Header, in_memory::InMemoryBlockRepository},
+    write::{
+        Block, BlockRepository, ExtendedBlock, Header, L1Origin, RejectedTransaction,
+        in_memory::InMemoryBlockRepository,
+    },
 };
@@ -1,7 +1,10 @@
 use {
-    crate::{block::write::ExtendedBlock, transaction::ExtendedTransaction},
+    crate::{
+        block::write::{ExtendedBlock, L1Origin, RejectedTransaction},
+        transaction::ExtendedTransaction,
+    },
     alloy::{eips::eip4895::Withdrawals, network::primitives::BlockTransactions},
-    moved_shared::primitives::B256,
+    moved_shared::primitives::{B256, U256},
     std::fmt::Debug,
 };
 
@@ -26,28 +29,71 @@ pub trait BlockQueries: Debug {
     ) -> Result<Option<BlockResponse>, Self::Err>;
 
     fn latest(&self, storage: &Self::Storage) -> Result<Option<u64>, Self::Err>;
+
+    /// Height of the most recent block marked as safe by the consensus layer.
+    fn safe(&self, storage: &Self::Storage) -> Result<Option<u64>, Self::Err>;
+
+    /// Height of the most recent block marked as finalized by the consensus layer.
+    fn finalized(&self, storage: &Self::Storage) -> Result<Option<u64>, Self::Err>;
+
+    /// Transactions skipped while building the block at `height`, or `None` if there is no such
+    /// block.
+    fn rejected_transactions(
+        &self,
+        storage: &Self::Storage,
+        height: u64,
+    ) -> Result<Option<Vec<RejectedTransaction>>, Self::Err>;
+
+    /// Number of transactions in the block with `hash`, or `None` if there is no such block.
+    ///
+    /// Unlike [`Self::by_hash`], this reads neither the transactions nor their hashes.
+    fn transaction_count_by_hash(
+        &self,
+        storage: &Self::Storage,
+        hash: B256,
+    ) -> Result<Option<u64>, Self::Err>;
+
+    /// Number of transactions in the block at `height`, or `None` if there is no such block.
+    fn transaction_count_by_height(
+        &self,
+        storage: &Self::Storage,
+        height: u64,
+    ) -> Result<Option<u64>, Self::Err>;
 }
 
 type RpcBlock = alloy::rpc::types::Block<RpcTransaction>;
 type RpcTransaction = op_alloy::rpc_types::Transaction;
 
-#[derive(Debug)]
-pub struct BlockResponse(pub RpcBlock);
+#[derive(Debug, Clone)]
+pub struct BlockResponse {
+    pub block: RpcBlock,
+    pub l1_origin: Option<L1Origin>,
+}
 
 impl BlockResponse {
     fn new(transactions: BlockTransactions<RpcTransaction>, value: ExtendedBlock) -> Self {
-        Self(RpcBlock {
+        let l1_origin = value.l1_origin;
+        // Withdrawals are not stored with the block, so they are listed as empty from Canyon, which
+        // adds the withdrawals root to the header, and left out before like the root is
+        let withdrawals = value
+            .block
+            .header
+            .withdrawals_root
+            .map(|_| Withdrawals(Vec::new()));
+        let block = RpcBlock {
             transactions,
             header: alloy::rpc::types::Header {
                 hash: value.hash,
                 inner: value.block.header,
-                // TODO: review fields below
-                total_difficulty: None,
-                size: None,
+                // Difficulty is always zero since the merge
+                total_difficulty: Some(U256::ZERO),
+                size: Some(U256::from(value.size)),
             },
             uncles: Vec::new(),
-            withdrawals: Some(Withdrawals(Vec::new())),
-        })
+            withdrawals,
+        };
+
+        Self { block, l1_origin }
     }
 
     pub fn from_block_with_transaction_hashes(block: ExtendedBlock) -> Self {
@@ -71,7 +117,10 @@ impl BlockResponse {
 pub mod in_memory {
     use {
         crate::{
-            block::{BlockResponse, ReadBlockMemory, read::BlockQueries},
+            block::{
+                BlockResponse, ExtendedBlock, ForkchoiceMarker, ReadBlockMemory,
+                RejectedTransaction, read::BlockQueries,
+            },
             in_memory::SharedMemoryReader,
             transaction::ReadTransactionMemory,
         },
@@ -130,6 +179,44 @@ pub mod in_memory {
         fn latest(&self, mem: &Self::Storage) -> Result<Option<u64>, Self::Err> {
             Ok(mem.block_memory.height())
         }
+
+        fn safe(&self, mem: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+            Ok(mem.block_memory.marker(ForkchoiceMarker::Safe))
+        }
+
+        fn finalized(&self, mem: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+            Ok(mem.block_memory.marker(ForkchoiceMarker::Finalized))
+        }
+
+        fn rejected_transactions(
+            &self,
+            mem: &Self::Storage,
+            height: u64,
+        ) -> Result<Option<Vec<RejectedTransaction>>, Self::Err> {
+            Ok(mem
+                .block_memory
+                .map_by_height(height, |block| block.rejected_transactions.clone()))
+        }
+
+        fn transaction_count_by_hash(
+            &self,
+            mem: &Self::Storage,
+            hash: B256,
+        ) -> Result<Option<u64>, Self::Err> {
+            Ok(mem
+                .block_memory
+                .map_by_hash(hash, ExtendedBlock::transaction_count))
+        }
+
+        fn transaction_count_by_height(
+            &self,
+            mem: &Self::Storage,
+            height: u64,
+        ) -> Result<Option<u64>, Self::Err> {
+            Ok(mem
+                .block_memory
+                .map_by_height(height, ExtendedBlock::transaction_count))
+        }
     }
 }
 
@@ -162,5 +249,55 @@ mod test_doubles {
         fn latest(&self, _: &Self::Storage) -> Result<Option<u64>, Self::Err> {
             Ok(None)
         }
+
+        fn safe(&self, _: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+            Ok(None)
+        }
+
+        fn finalized(&self, _: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+            Ok(None)
+        }
+
+        fn rejected_transactions(
+            &self,
+            _: &Self::Storage,
+            _: u64,
+        ) -> Result<Option<Vec<RejectedTransaction>>, Self::Err> {
+            Ok(None)
+        }
+
+        fn transaction_count_by_hash(
+            &self,
+            _: &Self::Storage,
+            _: B256,
+        ) -> Result<Option<u64>, Self::Err> {
+            Ok(None)
+        }
+
+        fn transaction_count_by_height(
+            &self,
+            _: &Self::Storage,
+            _: u64,
+        ) -> Result<Option<u64>, Self::Err> {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::block::write::Block};
+
+    #[test]
+    fn test_block_lists_withdrawals_only_with_withdrawals_root() {
+        let mut block = Block::default();
+        let without_root =
+            BlockResponse::from_block_with_transaction_hashes(block.clone().with_hash(B256::ZERO));
+        block.header.withdrawals_root = Some(B256::ZERO);
+        let with_root =
+            BlockResponse::from_block_with_transaction_hashes(block.with_hash(B256::ZERO));
+
+        assert_eq!(without_root.block.withdrawals, None);
+        assert_eq!(with_root.block.withdrawals, Some(Withdrawals(Vec::new())));
     }
 }
@@ -22,6 +22,62 @@ pub struct ExtendedBlock {
     pub value: U256,
     pub payload_id: PayloadId,
     pub block: Block,
+    /// Length in bytes of the RLP encoded block, including full transactions rather than their
+    /// hashes.
+    #[serde(default)]
+    pub size: u64,
+    /// Transactions that were considered for this block, but could not be included.
+    #[serde(default)]
+    pub rejected_transactions: Vec<RejectedTransaction>,
+    /// The L1 block this block was derived from, taken from its L1 attributes deposit.
+    #[serde(default)]
+    pub l1_origin: Option<L1Origin>,
+}
+
+/// A transaction that was skipped during block building, with a human-readable explanation.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RejectedTransaction {
+    pub transaction_hash: B256,
+    pub reason: String,
+}
+
+impl RejectedTransaction {
+    pub fn new(transaction_hash: B256, reason: impl ToString) -> Self {
+        Self {
+            transaction_hash,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Identifies the L1 block, also known as the epoch, that an L2 block was derived from.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct L1Origin {
+    pub number: u64,
+    pub hash: B256,
+}
+
+impl L1Origin {
+    /// Reads the L1 origin from the calldata of the L1 attributes deposited transaction, which is
+    /// always the first transaction of an L2 block.
+    ///
+    /// The calldata is expected in the packed layout introduced with Ecotone, see
+    /// <https://github.com/ethereum-optimism/specs/blob/main/specs/protocol/ecotone/l1-attributes.md>.
+    /// Isthmus only appends fields to that layout. Returns `None` if `data` calls any other
+    /// function or is too short to hold the L1 block number and hash.
+    pub fn from_l1_attributes(data: &[u8]) -> Option<Self> {
+        const SET_L1_BLOCK_VALUES_ECOTONE: [u8; 4] = [0x44, 0x0a, 0x5e, 0x20];
+        const SET_L1_BLOCK_VALUES_ISTHMUS: [u8; 4] = [0x09, 0x89, 0x99, 0xbe];
+
+        let selector: [u8; 4] = data.get(..4)?.try_into().ok()?;
+        if selector != SET_L1_BLOCK_VALUES_ECOTONE && selector != SET_L1_BLOCK_VALUES_ISTHMUS {
+            return None;
+        }
+        let number = u64::from_be_bytes(data.get(28..36)?.try_into().ok()?);
+        let hash = B256::from_slice(data.get(100..132)?);
+
+        Some(Self { number, hash })
+    }
 }
 
 impl ExtendedBlock {
@@ -31,9 +87,30 @@ impl ExtendedBlock {
             value,
             payload_id,
             block,
+            size: 0,
+            rejected_transactions: Vec::new(),
+            l1_origin: None,
         }
     }
 
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_rejected_transactions(
+        mut self,
+        rejected_transactions: Vec<RejectedTransaction>,
+    ) -> Self {
+        self.rejected_transactions = rejected_transactions;
+        self
+    }
+
+    pub fn with_l1_origin(mut self, l1_origin: Option<L1Origin>) -> Self {
+        self.l1_origin = l1_origin;
+        self
+    }
+
     pub fn with_value(mut self, value: U256) -> Self {
         self.value = value;
         self
@@ -47,6 +124,10 @@ impl ExtendedBlock {
     pub fn transaction_hashes(&self) -> impl Iterator<Item = B256> + use<'_> {
         self.block.transactions.iter().copied()
     }
+
+    pub fn transaction_count(&self) -> u64 {
+        self.block.transactions.len() as u64
+    }
 }
 
 /// TODO: Add withdrawals
@@ -84,16 +165,35 @@ pub trait BlockRepository: Debug {
     ) -> Result<Option<ExtendedBlock>, Self::Err>;
 
     fn latest(&self, storage: &Self::Storage) -> Result<Option<ExtendedBlock>, Self::Err>;
+
+    /// Forgets that the block with `block_hash` was built under `payload_id`, so that its payload
+    /// can no longer be fetched by its ID. The block itself is kept.
+    ///
+    /// A payload ID taken over by another block since is left as is.
+    fn remove_payload_id(
+        &mut self,
+        storage: &mut Self::Storage,
+        payload_id: PayloadId,
+        block_hash: B256,
+    ) -> Result<(), Self::Err>;
+
+    /// Marks the block at `height` as safe, as instructed by the consensus layer.
+    fn mark_safe(&mut self, storage: &mut Self::Storage, height: u64) -> Result<(), Self::Err>;
+
+    /// Marks the block at `height` as finalized, as instructed by the consensus layer.
+    fn mark_finalized(&mut self, storage: &mut Self::Storage, height: u64)
+    -> Result<(), Self::Err>;
 }
 
 pub mod in_memory {
     use {
         crate::{
             block::{
-                ReadBlockMemory,
+                ForkchoiceMarker, ReadBlockMemory,
                 write::{BlockRepository, ExtendedBlock},
             },
             in_memory::SharedMemory,
+            payload::PayloadId,
         },
         moved_shared::primitives::B256,
         std::convert::Infallible,
@@ -137,6 +237,30 @@ pub mod in_memory {
         fn latest(&self, mem: &Self::Storage) -> Result<Option<ExtendedBlock>, Self::Err> {
             Ok(mem.block_memory.last())
         }
+
+        fn remove_payload_id(
+            &mut self,
+            mem: &mut Self::Storage,
+            payload_id: PayloadId,
+            block_hash: B256,
+        ) -> Result<(), Self::Err> {
+            mem.block_memory.remove_payload_id(payload_id, block_hash);
+            Ok(())
+        }
+
+        fn mark_safe(&mut self, mem: &mut Self::Storage, height: u64) -> Result<(), Self::Err> {
+            mem.block_memory.mark(ForkchoiceMarker::Safe, height);
+            Ok(())
+        }
+
+        fn mark_finalized(
+            &mut self,
+            mem: &mut Self::Storage,
+            height: u64,
+        ) -> Result<(), Self::Err> {
+            mem.block_memory.mark(ForkchoiceMarker::Finalized, height);
+            Ok(())
+        }
     }
 }
 
@@ -159,5 +283,62 @@ mod test_doubles {
         fn latest(&self, _: &Self::Storage) -> Result<Option<ExtendedBlock>, Self::Err> {
             Ok(None)
         }
+
+        fn remove_payload_id(
+            &mut self,
+            _: &mut Self::Storage,
+            _: PayloadId,
+            _: B256,
+        ) -> Result<(), Self::Err> {
+            Ok(())
+        }
+
+        fn mark_safe(&mut self, _: &mut Self::Storage, _: u64) -> Result<(), Self::Err> {
+            Ok(())
+        }
+
+        fn mark_finalized(&mut self, _: &mut Self::Storage, _: u64) -> Result<(), Self::Err> {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn l1_attributes(selector: [u8; 4]) -> Vec<u8> {
+        let mut data = vec![0u8; 164];
+        data[0..4].copy_from_slice(&selector);
+        data[28..36].copy_from_slice(&7u64.to_be_bytes());
+        data[100..132].copy_from_slice(B256::repeat_byte(1).as_slice());
+        data
+    }
+
+    #[test]
+    fn test_l1_origin_is_read_from_ecotone_and_isthmus_l1_attributes() {
+        let expected = Some(L1Origin {
+            number: 7,
+            hash: B256::repeat_byte(1),
+        });
+
+        assert_eq!(
+            L1Origin::from_l1_attributes(&l1_attributes([0x44, 0x0a, 0x5e, 0x20])),
+            expected
+        );
+        assert_eq!(
+            L1Origin::from_l1_attributes(&l1_attributes([0x09, 0x89, 0x99, 0xbe])),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_l1_origin_is_none_for_other_calldata() {
+        // The Bedrock `setL1BlockValues` selector, whose calldata is ABI-encoded instead
+        assert_eq!(
+            L1Origin::from_l1_attributes(&l1_attributes([0x01, 0x5d, 0x8e, 0xb9])),
+            None
+        );
+        assert_eq!(L1Origin::from_l1_attributes(&[0x44, 0x0a, 0x5e]), None);
     }
 }
@@ -1,8 +1,20 @@
-use crate::{
-    block::{BlockMemory, BlockMemoryReader},
-    transaction::{TransactionMemory, TransactionMemoryReader},
+use {
+    crate::{
+        block::{BlockMemory, BlockMemoryReader},
+        transaction::{TransactionMemory, TransactionMemoryReader},
+    },
+    std::{
+        collections::BTreeMap,
+        sync::{
+            Arc,
+            atomic::{AtomicU64, Ordering},
+        },
+    },
 };
 
+/// Number of blocks evicted from an in-memory store between two compactions of its indices.
+pub(crate) const COMPACTION_INTERVAL: u64 = 1024;
+
 #[derive(Debug, Clone)]
 pub struct SharedMemoryReader {
     pub block_memory: BlockMemoryReader,
@@ -34,6 +46,105 @@ impl SharedMemory {
             transaction_memory,
         }
     }
+
+    /// Keeps only the blocks retained by [`BlockMemory::set_retained_blocks`] along with their
+    /// transactions.
+    ///
+    /// Returns the window of the retained blocks, for the other in-memory stores to evict the
+    /// records of the same blocks.
+    pub fn set_retained_blocks(&mut self, retained_blocks: u64) -> EvictionWindow {
+        self.block_memory.set_retained_blocks(retained_blocks);
+        let window = self.block_memory.eviction_window();
+        self.transaction_memory.set_eviction_window(window.clone());
+
+        window
+    }
+}
+
+/// The lowest height above genesis whose block an in-memory store still keeps.
+///
+/// The [`BlockMemory`] moves it up as it trims its blocks. The stores that share it evict the
+/// records of the blocks below it, so that every store keeps the records of the same blocks.
+#[derive(Debug, Clone)]
+pub struct EvictionWindow(Arc<AtomicU64>);
+
+impl Default for EvictionWindow {
+    fn default() -> Self {
+        Self(Arc::new(AtomicU64::new(1)))
+    }
+}
+
+impl EvictionWindow {
+    pub fn lowest_height(&self) -> u64 {
+        self.0.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn advance(&self, lowest_height: u64) {
+        self.0.store(lowest_height, Ordering::Release);
+    }
+}
+
+/// Remembers the keys written for each block height, so that an in-memory store can evict the
+/// records of blocks that fall below an [`EvictionWindow`].
+///
+/// Nothing is remembered until the window is set, so an unbounded store pays nothing for it.
+#[derive(Debug)]
+pub(crate) struct HeightWindow<K> {
+    window: Option<EvictionWindow>,
+    keys: BTreeMap<u64, Vec<K>>,
+    evicted_blocks: u64,
+}
+
+impl<K> Default for HeightWindow<K> {
+    fn default() -> Self {
+        Self {
+            window: None,
+            keys: BTreeMap::new(),
+            evicted_blocks: 0,
+        }
+    }
+}
+
+impl<K> HeightWindow<K> {
+    /// Evicts the records of the blocks below `window`.
+    ///
+    /// Only the records written from now on are evicted.
+    pub fn set_eviction_window(&mut self, window: EvictionWindow) {
+        self.window = Some(window);
+    }
+
+    /// Remembers that `key` was written for the block at `height`.
+    pub fn push(&mut self, height: u64, key: K) {
+        if self.window.is_some() {
+            self.keys.entry(height).or_default().push(key);
+        }
+    }
+
+    /// Forgets the blocks below the window and returns the keys written for each of them.
+    pub fn evict(&mut self) -> BTreeMap<u64, Vec<K>> {
+        let Some(window) = &self.window else {
+            return BTreeMap::new();
+        };
+
+        let retained = self.keys.split_off(&window.lowest_height());
+        let evicted = std::mem::replace(&mut self.keys, retained);
+        self.evicted_blocks += evicted.len() as u64;
+
+        evicted
+    }
+
+    /// Number of blocks whose records were evicted so far.
+    pub fn evicted_blocks(&self) -> u64 {
+        self.evicted_blocks
+    }
+
+    /// Whether the records evicted last brought the store to another compaction, which is due once
+    /// every [`COMPACTION_INTERVAL`] evicted blocks.
+    pub fn is_compaction_due(&self, evicted: &BTreeMap<u64, Vec<K>>) -> bool {
+        let before = self.evicted_blocks - evicted.len() as u64;
+
+        before / COMPACTION_INTERVAL != self.evicted_blocks / COMPACTION_INTERVAL
+    }
 }
 
 pub mod shared_memory {
@@ -47,8 +158,9 @@ pub mod shared_memory {
         let (r1, w1) = evmap::new();
         let (r2, w2) = evmap::new();
         let (r3, w3) = evmap::new();
-        let bw = BlockMemory::new(w1, w2, w3);
-        let br = BlockMemoryReader::new(r1, r2, r3);
+        let (r4, w4) = evmap::new();
+        let bw = BlockMemory::new(w1, w2, w3, w4);
+        let br = BlockMemoryReader::new(r1, r2, r3, r4);
         let (r1, w1) = evmap::new();
         let tw = TransactionMemory::new(w1);
         let tr = TransactionMemoryReader::new(r1);
@@ -63,9 +175,16 @@ pub mod shared_memory {
 mod tests {
     use {
         super::*,
-        crate::block::{Block, ExtendedBlock, Header, ReadBlockMemory},
-        alloy::hex,
-        moved_shared::primitives::B256,
+        crate::{
+            block::{
+                Block, ExtendedBlock, ForkchoiceMarker, Header, MIN_RETAINED_BLOCKS,
+                ReadBlockMemory,
+            },
+            transaction::{ExtendedTransaction, ReadTransactionMemory},
+        },
+        alloy::{consensus::Sealable, hex},
+        moved_shared::primitives::{B256, U256},
+        op_alloy::consensus::{OpTxEnvelope, TxDeposit},
     };
 
     #[test]
@@ -109,4 +228,137 @@ mod tests {
 
         assert_eq!(actual_height, expected_height);
     }
+
+    fn block(height: u64) -> ExtendedBlock {
+        ExtendedBlock {
+            hash: B256::from(U256::from(height)),
+            block: Block {
+                header: Header {
+                    number: height,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn transaction(height: u64) -> ExtendedTransaction {
+        let tx = TxDeposit {
+            source_hash: B256::from(U256::from(height)),
+            ..Default::default()
+        };
+
+        ExtendedTransaction::new(
+            0,
+            OpTxEnvelope::Deposit(tx.seal_slow()),
+            height,
+            block(height).hash,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_block_memory_trims_blocks_below_retained_ones() {
+        let (r, mut w) = shared_memory::new();
+        w.block_memory.set_retained_blocks(MIN_RETAINED_BLOCKS);
+
+        (0..=300).for_each(|height| w.block_memory.add(block(height)));
+
+        assert_eq!(r.block_memory.height(), Some(300));
+        assert_eq!(w.block_memory.lowest_height(), 45);
+        assert!(r.block_memory.by_height(44).is_none());
+        assert!(r.block_memory.by_hash(block(44).hash).is_none());
+        assert_eq!(r.block_memory.by_height(45), Some(block(45)));
+        assert_eq!(r.block_memory.by_height(0), Some(block(0)));
+    }
+
+    #[test]
+    fn test_block_memory_keeps_blocks_from_finalized_one() {
+        let (r, mut w) = shared_memory::new();
+        w.block_memory.set_retained_blocks(MIN_RETAINED_BLOCKS);
+
+        (0..=20).for_each(|height| w.block_memory.add(block(height)));
+        w.block_memory.mark(ForkchoiceMarker::Finalized, 20);
+        (21..=300).for_each(|height| w.block_memory.add(block(height)));
+
+        assert_eq!(w.block_memory.lowest_height(), 20);
+        assert_eq!(r.block_memory.by_height(20), Some(block(20)));
+    }
+
+    #[test]
+    fn test_height_window_evicts_blocks_below_it() {
+        let mut window = HeightWindow::default();
+        let eviction_window = EvictionWindow::default();
+        window.set_eviction_window(eviction_window.clone());
+
+        window.push(1, "a");
+        window.push(2, "b");
+        window.push(2, "c");
+        assert!(window.evict().is_empty());
+
+        window.push(3, "d");
+        eviction_window.advance(2);
+        assert_eq!(window.evict(), BTreeMap::from([(1, vec!["a"])]));
+        window.push(4, "e");
+        eviction_window.advance(3);
+        assert_eq!(window.evict(), BTreeMap::from([(2, vec!["b", "c"])]));
+        assert_eq!(window.evicted_blocks(), 2);
+    }
+
+    #[test]
+    fn test_height_window_without_retention_keeps_nothing_to_evict() {
+        let mut window = HeightWindow::default();
+
+        window.push(1, "a");
+        window.push(100, "b");
+
+        assert!(window.evict().is_empty());
+        assert_eq!(window.evicted_blocks(), 0);
+    }
+
+    #[test]
+    fn test_transactions_are_evicted_with_their_blocks() {
+        let (r, mut w) = shared_memory::new();
+        w.set_retained_blocks(1);
+
+        for height in 0..=300 {
+            w.transaction_memory.extend([transaction(height)]);
+            w.block_memory.add(block(height));
+        }
+
+        // The transactions of a block are evicted on the addition of the next ones
+        assert_eq!(w.block_memory.lowest_height(), 45);
+        assert!(
+            r.transaction_memory
+                .by_hash(transaction(43).hash())
+                .is_none()
+        );
+        assert!(
+            r.transaction_memory
+                .by_hash(transaction(45).hash())
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_safe_block_keeps_its_transactions() {
+        let (r, mut w) = shared_memory::new();
+        w.set_retained_blocks(1);
+
+        for height in 0..=300 {
+            w.transaction_memory.extend([transaction(height)]);
+            w.block_memory.add(block(height));
+            if height == 10 {
+                w.block_memory.mark(ForkchoiceMarker::Safe, 10);
+            }
+        }
+
+        assert_eq!(w.block_memory.lowest_height(), 10);
+        assert!(
+            r.transaction_memory
+                .by_hash(transaction(10).hash())
+                .is_some()
+        );
+    }
 }
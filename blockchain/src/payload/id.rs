@@ -42,6 +42,23 @@ impl<'a> NewPayloadIdInput<'a> {
         }
     }
 
+    /// Creates payload ID input parameters with `parent`, `timestamp`, `random` and `fee_recipient`
+    /// and omits `withdrawals`, for payloads from before the Ecotone upgrade that have no
+    /// `beacon_root`.
+    ///
+    /// Marks `version` as `2`.
+    pub fn new_v2(
+        parent: &'a B256,
+        timestamp: u64,
+        random: &'a B256,
+        fee_recipient: &'a Address,
+    ) -> Self {
+        Self {
+            version: 2,
+            ..Self::new_v3(parent, timestamp, random, fee_recipient)
+        }
+    }
+
     /// Creates this input with `withdrawals`.
     pub fn with_withdrawals(
         mut self,
@@ -2,11 +2,11 @@ use {
     crate::{
         block::{ExtendedBlock, ReadBlockMemory},
         in_memory::SharedMemoryReader,
-        payload::{PayloadId, PayloadQueries, PayloadResponse},
+        payload::{PayloadId, PayloadJobRepository, PayloadQueries, PayloadResponse},
         transaction::ReadTransactionMemory,
     },
     moved_shared::primitives::B256,
-    std::convert::Infallible,
+    std::{collections::BTreeMap, convert::Infallible},
 };
 
 #[derive(Debug, Clone)]
@@ -60,3 +60,45 @@ impl PayloadQueries for InMemoryPayloadQueries {
             .map(|block| Self::block_into_payload(storage, block)))
     }
 }
+
+/// Keeps the payload jobs in memory, so they are lost on restart.
+#[derive(Debug)]
+pub struct InMemoryPayloadJobRepository<A> {
+    jobs: BTreeMap<PayloadId, A>,
+}
+
+impl<A> Default for InMemoryPayloadJobRepository<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> InMemoryPayloadJobRepository<A> {
+    pub fn new() -> Self {
+        Self {
+            jobs: BTreeMap::new(),
+        }
+    }
+}
+
+impl<A: Clone> PayloadJobRepository<A> for InMemoryPayloadJobRepository<A> {
+    type Err = Infallible;
+
+    fn add(&mut self, id: PayloadId, attributes: &A) -> Result<(), Self::Err> {
+        self.jobs.insert(id, attributes.clone());
+        Ok(())
+    }
+
+    fn remove(&mut self, id: PayloadId) -> Result<(), Self::Err> {
+        self.jobs.remove(&id);
+        Ok(())
+    }
+
+    fn pending(&self) -> Result<Vec<(PayloadId, A)>, Self::Err> {
+        Ok(self
+            .jobs
+            .iter()
+            .map(|(id, attributes)| (*id, attributes.clone()))
+            .collect())
+    }
+}
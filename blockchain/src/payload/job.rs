@@ -0,0 +1,39 @@
+use {crate::payload::PayloadId, std::fmt::Debug};
+
+/// Keeps track of payload builds that have been requested but not finished yet.
+///
+/// A payload job is identified by its [`PayloadId`] and described by the payload `attributes` of
+/// type `A`. Jobs persisted by a durable implementation survive a restart of the node, which
+/// allows the payload to be rebuilt under the same ID that was handed out before.
+pub trait PayloadJobRepository<A> {
+    /// The associated error type for the backing storage access operation.
+    type Err: Debug;
+
+    fn add(&mut self, id: PayloadId, attributes: &A) -> Result<(), Self::Err>;
+
+    fn remove(&mut self, id: PayloadId) -> Result<(), Self::Err>;
+
+    /// Returns all jobs that have been added and not removed yet, ordered by their ID.
+    fn pending(&self) -> Result<Vec<(PayloadId, A)>, Self::Err>;
+}
+
+#[cfg(any(feature = "test-doubles", test))]
+mod test_doubles {
+    use {super::*, std::convert::Infallible};
+
+    impl<A> PayloadJobRepository<A> for () {
+        type Err = Infallible;
+
+        fn add(&mut self, _: PayloadId, _: &A) -> Result<(), Self::Err> {
+            Ok(())
+        }
+
+        fn remove(&mut self, _: PayloadId) -> Result<(), Self::Err> {
+            Ok(())
+        }
+
+        fn pending(&self) -> Result<Vec<(PayloadId, A)>, Self::Err> {
+            Ok(Vec::new())
+        }
+    }
+}
@@ -1,9 +1,11 @@
 mod id;
 mod in_memory;
+mod job;
 mod read;
 
 pub use {
     id::{NewPayloadId, NewPayloadIdInput, PayloadId, StatePayloadId},
-    in_memory::InMemoryPayloadQueries,
+    in_memory::{InMemoryPayloadJobRepository, InMemoryPayloadQueries},
+    job::PayloadJobRepository,
     read::{BlobsBundle, ExecutionPayload, PayloadQueries, PayloadResponse, Withdrawal},
 };
@@ -14,7 +14,11 @@ pub struct PayloadResponse {
     pub block_value: U256,
     pub blobs_bundle: BlobsBundle,
     pub should_override_builder: bool,
-    pub parent_beacon_block_root: B256,
+    /// Absent from payloads built before the Ecotone upgrade.
+    pub parent_beacon_block_root: Option<B256>,
+    /// Absent from payloads built before the Isthmus upgrade. The OP stack has no execution layer
+    /// requests, so it is always empty otherwise.
+    pub execution_requests: Option<Vec<Bytes>>,
 }
 
 impl PayloadResponse {
@@ -23,11 +27,8 @@ impl PayloadResponse {
         transactions: impl IntoIterator<Item = OpTxEnvelope>,
     ) -> Self {
         Self {
-            parent_beacon_block_root: block
-                .block
-                .header
-                .parent_beacon_block_root
-                .unwrap_or_default(),
+            parent_beacon_block_root: block.block.header.parent_beacon_block_root,
+            execution_requests: block.block.header.requests_hash.map(|_| Vec::new()),
             block_value: block.value,
             execution_payload: ExecutionPayload::from_block_with_transactions(block, transactions),
             blobs_bundle: Default::default(),
@@ -55,6 +56,9 @@ pub struct ExecutionPayload {
     pub withdrawals: Vec<Withdrawal>,
     pub blob_gas_used: U64,
     pub excess_blob_gas: U64,
+    /// Taken from the header, where since Isthmus it holds the storage root of the L2 to L1
+    /// message passer. Only exchanged with the consensus layer since then.
+    pub withdrawals_root: Option<B256>,
 }
 
 impl ExecutionPayload {
@@ -90,6 +94,7 @@ impl ExecutionPayload {
             withdrawals: Vec::new(), // TODO: withdrawals
             blob_gas_used: U64::from(block.block.header.blob_gas_used.unwrap_or_default()),
             excess_blob_gas: U64::from(block.block.header.excess_blob_gas.unwrap_or_default()),
+            withdrawals_root: block.block.header.withdrawals_root,
         }
     }
 }
@@ -1,6 +1,9 @@
 use {
-    crate::receipt::{
-        ExtendedReceipt, ReceiptQueries, TransactionReceipt, write::ReceiptRepository,
+    crate::{
+        in_memory::{EvictionWindow, HeightWindow},
+        receipt::{
+            ExtendedReceipt, ReceiptQueries, TransactionReceipt, VmStatus, write::ReceiptRepository,
+        },
     },
     moved_shared::primitives::B256,
     std::{
@@ -12,6 +15,10 @@ use {
 
 pub type ReadHandle = evmap::ReadHandle<B256, Arc<ExtendedReceipt>>;
 pub type WriteHandle = evmap::WriteHandle<B256, Arc<ExtendedReceipt>>;
+pub type ReadBlockHashes = evmap::ReadHandle<B256, Arc<ExtendedReceipt>>;
+pub type WriteBlockHashes = evmap::WriteHandle<B256, Arc<ExtendedReceipt>>;
+pub type ReadBlockHeights = evmap::ReadHandle<u64, Arc<ExtendedReceipt>>;
+pub type WriteBlockHeights = evmap::WriteHandle<u64, Arc<ExtendedReceipt>>;
 
 impl Hash for ExtendedReceipt {
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -26,26 +33,99 @@ impl Hash for ExtendedReceipt {
         self.block_hash.hash(state);
         self.block_number.hash(state);
         self.block_timestamp.hash(state);
+        self.vm_status.hash(state);
     }
 }
 
+/// A storage for receipts that keeps data in memory.
+///
+/// Besides the lookup by transaction hash, receipts are indexed by the hash and the height of the
+/// block that includes them. A height only holds the receipts of the last block added at it, which
+/// replaces any block a reorg took out of the chain. Every receipt is kept unless the storage is
+/// bounded by [`Self::set_eviction_window`].
 #[derive(Debug)]
 pub struct ReceiptMemory {
     receipts: WriteHandle,
+    block_hashes: WriteBlockHashes,
+    block_heights: WriteBlockHeights,
+    /// Hashes of the transaction and of the block of each receipt
+    window: HeightWindow<(B256, B256)>,
 }
 
 impl ReceiptMemory {
-    pub fn new(receipts: WriteHandle) -> Self {
-        Self { receipts }
+    pub fn new(
+        receipts: WriteHandle,
+        block_hashes: WriteBlockHashes,
+        block_heights: WriteBlockHeights,
+    ) -> Self {
+        Self {
+            receipts,
+            block_hashes,
+            block_heights,
+            window: HeightWindow::default(),
+        }
+    }
+
+    /// Evicts the receipts of the blocks that fall below the `window`, as new receipts are added.
+    ///
+    /// A transaction whose receipt is evicted no longer counts as processed, but it cannot be
+    /// included again anyway as its nonce is used.
+    pub fn set_eviction_window(&mut self, window: EvictionWindow) {
+        self.window.set_eviction_window(window);
+    }
+
+    /// Number of blocks whose receipts were evicted so far.
+    pub fn evicted_blocks(&self) -> u64 {
+        self.window.evicted_blocks()
     }
 
     pub fn extend(&mut self, receipts: impl IntoIterator<Item = ExtendedReceipt>) {
-        self.receipts.extend(
-            receipts
-                .into_iter()
-                .map(|receipt| (receipt.transaction_hash, Arc::new(receipt))),
-        );
+        let mut added_block = None;
+        for receipt in receipts {
+            let block = (receipt.block_number, receipt.block_hash);
+            if added_block != Some(block) {
+                self.replace_block_at_height(block);
+                added_block = Some(block);
+            }
+            self.window.push(
+                receipt.block_number,
+                (receipt.transaction_hash, receipt.block_hash),
+            );
+            let receipt = Arc::new(receipt);
+            self.receipts
+                .insert(receipt.transaction_hash, receipt.clone());
+            self.block_hashes
+                .insert(receipt.block_hash, receipt.clone());
+            self.block_heights.insert(receipt.block_number, receipt);
+        }
+        let evicted = self.window.evict();
+        if self.window.is_compaction_due(&evicted) {
+            self.receipts.fit_all();
+            self.block_hashes.fit_all();
+            self.block_heights.fit_all();
+        }
+        for (height, keys) in evicted {
+            self.block_heights.empty(height);
+            for (transaction_hash, block_hash) in keys {
+                self.receipts.empty(transaction_hash);
+                self.block_hashes.empty(block_hash);
+            }
+        }
         self.receipts.refresh();
+        self.block_hashes.refresh();
+        self.block_heights.refresh();
+    }
+
+    /// Drops the receipts of another block than the one with `block_hash` from the `height` index,
+    /// so that they no longer mix with the receipts of the block that replaces it.
+    fn replace_block_at_height(&mut self, (height, block_hash): (u64, B256)) {
+        let replaced = self
+            .block_heights
+            .get_one(&height)
+            .is_some_and(|other| other.block_hash != block_hash);
+        if replaced {
+            self.block_heights.empty(height);
+        }
     }
 }
 
@@ -58,11 +138,21 @@ impl AsRef<ReadHandle> for ReceiptMemory {
 #[derive(Debug, Clone)]
 pub struct ReceiptMemoryReader {
     receipts: ReadHandle,
+    block_hashes: ReadBlockHashes,
+    block_heights: ReadBlockHeights,
 }
 
 impl ReceiptMemoryReader {
-    pub fn new(receipts: ReadHandle) -> Self {
-        Self { receipts }
+    pub fn new(
+        receipts: ReadHandle,
+        block_hashes: ReadBlockHashes,
+        block_heights: ReadBlockHeights,
+    ) -> Self {
+        Self {
+            receipts,
+            block_hashes,
+            block_heights,
+        }
     }
 }
 
@@ -75,27 +165,93 @@ impl AsRef<ReadHandle> for ReceiptMemoryReader {
 pub trait ReadReceiptMemory {
     fn contains(&self, transaction_hash: B256) -> bool;
     fn by_transaction_hash(&self, transaction_hash: B256) -> Option<ExtendedReceipt>;
+    fn by_block_hash(&self, block_hash: B256) -> Vec<ExtendedReceipt>;
+    fn by_block_height(&self, block_height: u64) -> Vec<ExtendedReceipt>;
+    /// Number of receipts held in memory.
+    fn count(&self) -> usize;
+}
+
+impl ReadReceiptMemory for ReceiptMemory {
+    fn contains(&self, transaction_hash: B256) -> bool {
+        self.receipts.contains_key(&transaction_hash)
+    }
+
+    fn by_transaction_hash(&self, transaction_hash: B256) -> Option<ExtendedReceipt> {
+        get_one(&self.receipts, &transaction_hash)
+    }
+
+    fn by_block_hash(&self, block_hash: B256) -> Vec<ExtendedReceipt> {
+        get_sorted(&self.block_hashes, &block_hash)
+    }
+
+    fn by_block_height(&self, block_height: u64) -> Vec<ExtendedReceipt> {
+        get_sorted(&self.block_heights, &block_height)
+    }
+
+    fn count(&self) -> usize {
+        self.receipts.len()
+    }
 }
 
-impl<T: AsRef<ReadHandle>> ReadReceiptMemory for T {
+impl ReadReceiptMemory for ReceiptMemoryReader {
     fn contains(&self, transaction_hash: B256) -> bool {
-        self.as_ref().contains_key(&transaction_hash)
+        self.receipts.contains_key(&transaction_hash)
     }
 
     fn by_transaction_hash(&self, transaction_hash: B256) -> Option<ExtendedReceipt> {
-        self.as_ref()
-            .get_one(&transaction_hash)
-            .map(|v| ExtendedReceipt::clone(&v))
+        get_one(&self.receipts, &transaction_hash)
+    }
+
+    fn by_block_hash(&self, block_hash: B256) -> Vec<ExtendedReceipt> {
+        get_sorted(&self.block_hashes, &block_hash)
+    }
+
+    fn by_block_height(&self, block_height: u64) -> Vec<ExtendedReceipt> {
+        get_sorted(&self.block_heights, &block_height)
+    }
+
+    fn count(&self) -> usize {
+        self.receipts.len()
     }
 }
 
+fn get_one<K: Eq + Hash>(
+    handle: &evmap::ReadHandle<K, Arc<ExtendedReceipt>>,
+    key: &K,
+) -> Option<ExtendedReceipt> {
+    handle.get_one(key).map(|v| ExtendedReceipt::clone(&v))
+}
+
+/// Collects all receipts stored under `key` in the order of transactions in the block.
+fn get_sorted<K: Eq + Hash>(
+    handle: &evmap::ReadHandle<K, Arc<ExtendedReceipt>>,
+    key: &K,
+) -> Vec<ExtendedReceipt> {
+    let mut receipts = handle
+        .get(key)
+        .map(|values| {
+            values
+                .iter()
+                .map(|v| ExtendedReceipt::clone(v))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    receipts.sort_by_key(|receipt| receipt.transaction_index);
+    receipts
+}
+
 pub mod receipt_memory {
     use crate::receipt::{ReceiptMemory, ReceiptMemoryReader};
 
     pub fn new() -> (ReceiptMemoryReader, ReceiptMemory) {
-        let (r, w) = evmap::new();
+        let (r1, w1) = evmap::new();
+        let (r2, w2) = evmap::new();
+        let (r3, w3) = evmap::new();
 
-        (ReceiptMemoryReader::new(r), ReceiptMemory::new(w))
+        (
+            ReceiptMemoryReader::new(r1, r2, r3),
+            ReceiptMemory::new(w1, w2, w3),
+        )
     }
 }
 
@@ -127,6 +283,40 @@ impl ReceiptQueries for InMemoryReceiptQueries {
             .by_transaction_hash(transaction_hash)
             .map(TransactionReceipt::from))
     }
+
+    fn vm_status_by_transaction_hash(
+        &self,
+        storage: &Self::Storage,
+        transaction_hash: B256,
+    ) -> Result<Option<VmStatus>, Self::Err> {
+        Ok(storage
+            .by_transaction_hash(transaction_hash)
+            .and_then(|receipt| receipt.vm_status))
+    }
+
+    fn by_block_hash(
+        &self,
+        storage: &Self::Storage,
+        block_hash: B256,
+    ) -> Result<Vec<TransactionReceipt>, Self::Err> {
+        Ok(storage
+            .by_block_hash(block_hash)
+            .into_iter()
+            .map(TransactionReceipt::from)
+            .collect())
+    }
+
+    fn by_block_height(
+        &self,
+        storage: &Self::Storage,
+        block_height: u64,
+    ) -> Result<Vec<TransactionReceipt>, Self::Err> {
+        Ok(storage
+            .by_block_height(block_height)
+            .into_iter()
+            .map(TransactionReceipt::from)
+            .collect())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -161,3 +351,153 @@ impl ReceiptRepository for InMemoryReceiptRepository {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        alloy::{
+            consensus::{Receipt, ReceiptWithBloom},
+            primitives::{Address, Log},
+            rpc::types::Filter,
+        },
+        op_alloy::consensus::OpReceiptEnvelope,
+    };
+
+    fn receipt(transaction_index: u64, block_number: u64) -> ExtendedReceipt {
+        ExtendedReceipt {
+            transaction_hash: B256::repeat_byte(block_number as u8 * 16 + transaction_index as u8),
+            transaction_index,
+            to: None,
+            from: Default::default(),
+            receipt: OpReceiptEnvelope::Legacy(Default::default()),
+            l1_block_info: None,
+            gas_used: 0,
+            l2_gas_price: Default::default(),
+            contract_address: None,
+            logs_offset: 0,
+            block_hash: B256::repeat_byte(block_number as u8),
+            block_number,
+            block_timestamp: 0,
+            vm_status: None,
+        }
+    }
+
+    fn receipt_with_log(
+        transaction_index: u64,
+        block_number: u64,
+        address: Address,
+        topic: B256,
+    ) -> ExtendedReceipt {
+        let log = Log::new_unchecked(address, vec![topic], Default::default());
+
+        ExtendedReceipt {
+            receipt: OpReceiptEnvelope::Legacy(ReceiptWithBloom {
+                receipt: Receipt {
+                    logs: vec![log],
+                    ..Default::default()
+                },
+                logs_bloom: Default::default(),
+            }),
+            logs_offset: transaction_index,
+            ..receipt(transaction_index, block_number)
+        }
+    }
+
+    #[test]
+    fn test_receipts_by_block_are_returned_in_transaction_order() {
+        let (reader, mut memory) = receipt_memory::new();
+
+        memory.extend([receipt(2, 1), receipt(0, 1), receipt(0, 2), receipt(1, 1)]);
+
+        let expected_receipts = vec![receipt(0, 1), receipt(1, 1), receipt(2, 1)];
+
+        assert_eq!(
+            reader.by_block_hash(B256::repeat_byte(1)),
+            expected_receipts
+        );
+        assert_eq!(reader.by_block_height(1), expected_receipts);
+        assert_eq!(reader.by_block_height(2), vec![receipt(0, 2)]);
+        assert!(reader.by_block_height(3).is_empty());
+    }
+
+    #[test]
+    fn test_receipts_by_block_height_are_of_block_replacing_reorged_one() {
+        let (reader, mut memory) = receipt_memory::new();
+        let reorged_block_hash = B256::repeat_byte(1);
+        let replacing = ExtendedReceipt {
+            transaction_hash: B256::repeat_byte(0xff),
+            block_hash: B256::repeat_byte(0xee),
+            ..receipt(0, 1)
+        };
+
+        memory.extend([receipt(0, 1), receipt(1, 1)]);
+        memory.extend([replacing.clone()]);
+
+        assert_eq!(reader.by_block_height(1), vec![replacing]);
+        assert_eq!(
+            reader.by_block_hash(reorged_block_hash),
+            vec![receipt(0, 1), receipt(1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_receipts_of_blocks_below_retention_window_are_evicted() {
+        let (reader, mut memory) = receipt_memory::new();
+        let window = EvictionWindow::default();
+        memory.set_eviction_window(window.clone());
+
+        memory.extend([receipt(0, 1), receipt(1, 1)]);
+        memory.extend([receipt(0, 2)]);
+        window.advance(2);
+        memory.extend([receipt(0, 3)]);
+
+        assert!(reader.by_block_height(1).is_empty());
+        assert!(reader.by_block_hash(B256::repeat_byte(1)).is_empty());
+        assert!(!reader.contains(receipt(0, 1).transaction_hash));
+        assert_eq!(reader.by_block_height(2), vec![receipt(0, 2)]);
+        assert_eq!(reader.count(), 2);
+        assert_eq!(memory.evicted_blocks(), 1);
+    }
+
+    #[test]
+    fn test_logs_are_filtered_by_address_and_topics_in_emission_order() {
+        let (reader, mut memory) = receipt_memory::new();
+        let (a, b) = (Address::repeat_byte(0xa), Address::repeat_byte(0xb));
+        let (transfer, approval) = (B256::repeat_byte(0x1), B256::repeat_byte(0x2));
+        memory.extend([
+            receipt_with_log(0, 1, a, transfer),
+            receipt_with_log(1, 1, b, transfer),
+            receipt_with_log(0, 2, a, approval),
+        ]);
+        let queries = InMemoryReceiptQueries::new();
+
+        let by_address = queries
+            .logs(&reader, &Filter::new().address(a), 1, 2)
+            .unwrap();
+        let by_topic = queries
+            .logs(&reader, &Filter::new().event_signature(transfer), 1, 2)
+            .unwrap();
+        let in_block = queries
+            .logs_by_block_hash(&reader, &Filter::new().address(a), B256::repeat_byte(2))
+            .unwrap();
+        let out_of_range = queries
+            .logs(&reader, &Filter::new().event_signature(approval), 1, 1)
+            .unwrap();
+
+        assert_eq!(
+            by_address
+                .iter()
+                .map(|log| (log.block_number, log.topics()[0]))
+                .collect::<Vec<_>>(),
+            vec![(Some(1), transfer), (Some(2), approval)]
+        );
+        assert_eq!(
+            by_topic.iter().map(|log| log.address()).collect::<Vec<_>>(),
+            vec![a, b]
+        );
+        assert_eq!(in_block.len(), 1);
+        assert_eq!(in_block[0].log_index, Some(0));
+        assert!(out_of_range.is_empty());
+    }
+}
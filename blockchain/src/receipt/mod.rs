@@ -1,10 +1,11 @@
 pub use {
     in_memory::{
-        InMemoryReceiptQueries, InMemoryReceiptRepository, ReadHandle, ReceiptMemory,
-        ReceiptMemoryReader, WriteHandle, receipt_memory,
+        InMemoryReceiptQueries, InMemoryReceiptRepository, ReadBlockHashes, ReadBlockHeights,
+        ReadHandle, ReadReceiptMemory, ReceiptMemory, ReceiptMemoryReader, WriteBlockHashes,
+        WriteBlockHeights, WriteHandle, receipt_memory,
     },
-    read::{ReceiptQueries, TransactionReceipt},
-    write::{ExtendedReceipt, ReceiptRepository},
+    read::{ReceiptQueries, TransactionReceipt, log_matches},
+    write::{ExtendedReceipt, ReceiptRepository, VmStatus},
 };
 
 mod in_memory;
@@ -1,6 +1,6 @@
 use {
-    crate::receipt::ExtendedReceipt,
-    alloy::rpc::types::TransactionReceipt as AlloyTxReceipt,
+    crate::receipt::{ExtendedReceipt, VmStatus},
+    alloy::rpc::types::{Filter, Log, TransactionReceipt as AlloyTxReceipt},
     moved_shared::{primitives, primitives::B256},
     std::fmt::Debug,
 };
@@ -14,6 +14,91 @@ pub trait ReceiptQueries {
         storage: &Self::Storage,
         transaction_hash: B256,
     ) -> Result<Option<TransactionReceipt>, Self::Err>;
+
+    /// Returns the [`VmStatus`] of the transaction with `transaction_hash`.
+    ///
+    /// Yields `None` if the transaction is unknown or did not fail.
+    fn vm_status_by_transaction_hash(
+        &self,
+        storage: &Self::Storage,
+        transaction_hash: B256,
+    ) -> Result<Option<VmStatus>, Self::Err>;
+
+    /// Returns receipts of all transactions in the block with `block_hash`, in the order the
+    /// transactions appear in the block.
+    ///
+    /// An unknown block yields an empty list.
+    fn by_block_hash(
+        &self,
+        storage: &Self::Storage,
+        block_hash: B256,
+    ) -> Result<Vec<TransactionReceipt>, Self::Err>;
+
+    /// Same as [`Self::by_block_hash`], but looks the block up by its `block_height`.
+    fn by_block_height(
+        &self,
+        storage: &Self::Storage,
+        block_height: u64,
+    ) -> Result<Vec<TransactionReceipt>, Self::Err>;
+
+    /// Returns the logs of the blocks from `from_height` to `to_height`, both inclusive, that
+    /// match the addresses and topics of the `filter`, in the order they were emitted.
+    ///
+    /// The block range of the `filter` is not used, as it may be given by tags or a block hash.
+    fn logs(
+        &self,
+        storage: &Self::Storage,
+        filter: &Filter,
+        from_height: u64,
+        to_height: u64,
+    ) -> Result<Vec<Log>, Self::Err> {
+        let mut logs = Vec::new();
+
+        for height in from_height..=to_height {
+            let receipts = self.by_block_height(storage, height)?;
+            logs.extend(matching_logs(filter, &receipts));
+        }
+
+        Ok(logs)
+    }
+
+    /// Same as [`Self::logs`], but of the single block with `block_hash`.
+    fn logs_by_block_hash(
+        &self,
+        storage: &Self::Storage,
+        filter: &Filter,
+        block_hash: B256,
+    ) -> Result<Vec<Log>, Self::Err> {
+        let receipts = self.by_block_hash(storage, block_hash)?;
+
+        Ok(matching_logs(filter, &receipts).collect())
+    }
+}
+
+fn matching_logs<'a>(
+    filter: &'a Filter,
+    receipts: &'a [TransactionReceipt],
+) -> impl Iterator<Item = Log> + 'a {
+    receipts
+        .iter()
+        .flat_map(|receipt| receipt.inner.inner.logs())
+        .filter(|log| log_matches(filter, log))
+        .cloned()
+}
+
+/// A log matches when its address is one of the filter addresses and each of its topics is one of
+/// the filter topics in the same position. An empty set of addresses or topics matches anything.
+///
+/// The block range of the `filter` is not checked.
+pub fn log_matches(filter: &Filter, log: &Log) -> bool {
+    filter.address.matches(&log.address())
+        && filter.topics.iter().enumerate().all(|(position, topic)| {
+            topic.is_empty()
+                || log
+                    .topics()
+                    .get(position)
+                    .is_some_and(|value| topic.matches(value))
+        })
 }
 
 pub type TransactionReceipt = op_alloy::rpc_types::OpTransactionReceipt;
@@ -47,7 +132,6 @@ impl From<ExtendedReceipt> for TransactionReceipt {
                 block_hash: Some(rx.block_hash),
                 block_number: Some(rx.block_number),
                 gas_used: rx.gas_used,
-                // TODO: make all gas prices bounded by u128?
                 effective_gas_price: rx.l2_gas_price.saturating_to(),
                 // Always None because we do not support eip-4844 transactions
                 blob_gas_used: None,
@@ -76,5 +160,29 @@ mod test_doubles {
         ) -> Result<Option<TransactionReceipt>, Self::Err> {
             Ok(None)
         }
+
+        fn vm_status_by_transaction_hash(
+            &self,
+            _: &Self::Storage,
+            _: B256,
+        ) -> Result<Option<VmStatus>, Self::Err> {
+            Ok(None)
+        }
+
+        fn by_block_hash(
+            &self,
+            _: &Self::Storage,
+            _: B256,
+        ) -> Result<Vec<TransactionReceipt>, Self::Err> {
+            Ok(Vec::new())
+        }
+
+        fn by_block_height(
+            &self,
+            _: &Self::Storage,
+            _: u64,
+        ) -> Result<Vec<TransactionReceipt>, Self::Err> {
+            Ok(Vec::new())
+        }
     }
 }
@@ -1,5 +1,9 @@
 use {
-    moved_shared::primitives::{Address, B256, U256},
+    move_binary_format::errors::Location,
+    moved_shared::{
+        error::UserError,
+        primitives::{Address, B256, Bytes, U256},
+    },
     op_alloy::{consensus::OpReceiptEnvelope, rpc_types::L1BlockInfo},
     std::fmt::Debug,
 };
@@ -44,6 +48,62 @@ pub struct ExtendedReceipt {
     pub block_hash: B256,
     pub block_number: u64,
     pub block_timestamp: u64,
+    /// Why the transaction failed, if it did.
+    ///
+    /// Receipts stored before this field was introduced read it as `None`.
+    #[serde(default)]
+    pub vm_status: Option<VmStatus>,
+}
+
+/// The status the VM ended a failed transaction with.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VmStatus {
+    /// The module or script that the VM failed in, if the VM knows it.
+    pub location: Option<String>,
+    /// The abort code of the failure, if the VM aborted.
+    pub abort_code: Option<u64>,
+    pub message: String,
+    /// Output of the EVM execution that failed the transaction, from which the revert reason of a
+    /// contract is decoded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_data: Option<Bytes>,
+}
+
+impl From<&UserError> for VmStatus {
+    fn from(value: &UserError) -> Self {
+        let message = value.to_string();
+        match value {
+            UserError::MoveAbort(abort) => Self {
+                location: Some(abort.module.short_str_lossless()),
+                abort_code: Some(abort.code),
+                message,
+                revert_data: abort.evm_output.clone().map(Bytes::from),
+            },
+            UserError::Vm(e) => Self {
+                location: match e.location() {
+                    Location::Module(module) => Some(module.short_str_lossless()),
+                    Location::Script => Some("script".into()),
+                    Location::Undefined => None,
+                },
+                abort_code: e.sub_status(),
+                message,
+                revert_data: None,
+            },
+            UserError::EvmReverted(output) => Self {
+                location: None,
+                abort_code: None,
+                message,
+                revert_data: Some(Bytes::from(output.clone())),
+            },
+            _ => Self {
+                location: None,
+                abort_code: None,
+                message,
+                revert_data: None,
+            },
+        }
+    }
 }
 
 impl ExtendedReceipt {
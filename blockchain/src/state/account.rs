@@ -0,0 +1,151 @@
+use {
+    crate::state::{Balance, Nonce},
+    alloy::primitives::KECCAK_EMPTY,
+    move_binary_format::errors::{PartialVMError, PartialVMResult},
+    move_core_types::{
+        account_address::AccountAddress, ident_str, language_storage::StructTag,
+        vm_status::StatusCode,
+    },
+    move_vm_types::{
+        resolver::ResourceResolver, value_serde::ValueSerDeContext, values::VMValueCast,
+    },
+    moved_evm_ext::{
+        CODE_LAYOUT, EVM_NATIVE_ADDRESS,
+        state::Account,
+        type_utils::{account_info_struct_tag, code_hash_struct_tag},
+    },
+    moved_execution::{read_eth_balance, read_nonce},
+    moved_shared::primitives::{Bytes, ToEthAddress, ToMoveAddress},
+};
+
+/// The code reported for a Move account, which is the `INVALID` opcode so that EVM tools never
+/// mistake it for bytecode.
+///
+/// The marker only tells such accounts apart from the ones without code in the queries. The EVM
+/// account of a Move account has no code, so its `EXTCODESIZE` is zero and its proof reports the
+/// empty code hash, as for any account without code.
+pub const MOVE_CODE_MARKER: u8 = 0xfe;
+
+/// An account as the EVM-facing queries report it, whichever way the account came to exist.
+///
+/// An account is kept in up to two places: the Move `Account` resource, which accounts get once
+/// they send a transaction or receive the base token, and the EVM account info of the accounts the
+/// EVM has touched. Accounts created by Move code, such as resource accounts and objects, may have
+/// neither. The view combines both so that every address has a balance, nonce and code:
+/// * The balance is the one of the primary base token store, which EVM transfers are replicated to.
+/// * The nonce of a contract is its EVM nonce, as a contract never sends transactions but counts
+///   the contracts it creates. Any other account uses the sequence number of its Move account.
+/// * The code is the bytecode of an EVM contract. An account that Move packages are published at
+///   has the single [`MOVE_CODE_MARKER`] byte instead, so that it is told apart from the accounts
+///   without code. Any other account has no code.
+///
+/// An address without any of these is an empty account.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountView {
+    pub balance: Balance,
+    pub nonce: Nonce,
+    pub code: Bytes,
+}
+
+impl AccountView {
+    /// Reads the view of the account at `address` from the `state`.
+    pub fn read(address: &AccountAddress, state: &impl ResourceResolver) -> PartialVMResult<Self> {
+        let balance = read_eth_balance(address, state)?;
+        let (nonce, code) = match read_evm_contract(address, state)? {
+            Some(contract) => (contract.inner.nonce, read_evm_code(&contract, state)?),
+            None => (read_nonce(address, state)?, read_move_code(address, state)?),
+        };
+
+        Ok(Self {
+            balance,
+            nonce,
+            code,
+        })
+    }
+}
+
+/// Reads the nonce of the account at `address` as [`AccountView::read`] does, without reading its
+/// code.
+pub fn read_account_nonce(
+    address: &AccountAddress,
+    state: &impl ResourceResolver,
+) -> PartialVMResult<Nonce> {
+    match read_evm_contract(address, state)? {
+        Some(contract) => Ok(contract.inner.nonce),
+        None => read_nonce(address, state),
+    }
+}
+
+/// Reads the EVM account info of `address` if it is a contract.
+fn read_evm_contract(
+    address: &AccountAddress,
+    state: &impl ResourceResolver,
+) -> PartialVMResult<Option<Account>> {
+    let account = read_evm_account(address, state)?;
+
+    Ok(account.filter(|account| account.inner.code_hash != KECCAK_EMPTY))
+}
+
+/// Reads the EVM account info of `address`, if it has an EVM form.
+fn read_evm_account(
+    address: &AccountAddress,
+    state: &impl ResourceResolver,
+) -> PartialVMResult<Option<Account>> {
+    let evm_address = address.to_eth_address();
+    // Move addresses that are not padded EVM addresses are out of reach of the EVM
+    if evm_address.to_move_address() != *address {
+        return Ok(None);
+    }
+
+    let (bytes, _) = state.get_resource_bytes_with_metadata_and_layout(
+        &EVM_NATIVE_ADDRESS,
+        &account_info_struct_tag(&evm_address),
+        &[],
+        None,
+    )?;
+
+    bytes
+        .map(|bytes| {
+            Account::try_deserialize(&bytes)
+                .ok_or_else(|| PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_RESOURCE))
+        })
+        .transpose()
+}
+
+fn read_evm_code(account: &Account, state: &impl ResourceResolver) -> PartialVMResult<Bytes> {
+    let (bytes, _) = state.get_resource_bytes_with_metadata_and_layout(
+        &EVM_NATIVE_ADDRESS,
+        &code_hash_struct_tag(&account.inner.code_hash),
+        &[],
+        None,
+    )?;
+    let Some(bytes) = bytes else {
+        return Err(PartialVMError::new(StatusCode::MISSING_DATA));
+    };
+    let value = ValueSerDeContext::new()
+        .deserialize(&bytes, &CODE_LAYOUT)
+        .ok_or_else(|| PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_RESOURCE))?;
+    let code: Vec<u8> = value.cast()?;
+
+    Ok(code.into())
+}
+
+/// Reads the code of an account that Move packages are published at, which is the
+/// [`MOVE_CODE_MARKER`], or empty code without any packages.
+fn read_move_code(
+    address: &AccountAddress,
+    state: &impl ResourceResolver,
+) -> PartialVMResult<Bytes> {
+    let struct_tag = StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("code").into(),
+        name: ident_str!("PackageRegistry").into(),
+        type_args: Vec::new(),
+    };
+    let (bytes, _) =
+        state.get_resource_bytes_with_metadata_and_layout(address, &struct_tag, &[], None)?;
+
+    Ok(bytes
+        .map(|_| Bytes::from_static(&[MOVE_CODE_MARKER]))
+        .unwrap_or_default())
+}
@@ -1,8 +1,15 @@
+mod account;
 mod read;
+mod sync;
 
 #[cfg(any(feature = "test-doubles", test))]
 pub use read::test_doubles::MockStateQueries;
-pub use read::{
-    Balance, BlockHeight, EthTrieResolver, InMemoryStateQueries, Nonce, ProofResponse,
-    StateQueries, StorageProof, Version, proof_from_trie_and_resolver,
+pub use {
+    account::{AccountView, MOVE_CODE_MARKER, read_account_nonce},
+    read::{
+        Balance, BlockHeight, EthTrieResolver, InMemoryStateQueries, Nonce, ProofResponse,
+        StateQueries, StorageProof, Version, proof_from_trie_and_resolver, resources_from_index,
+        storage_from_resolver,
+    },
+    sync::{TrieSync, TrieSyncError},
 };
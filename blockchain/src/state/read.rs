@@ -1,10 +1,19 @@
 use {
-    crate::{block::ReadBlockMemory, in_memory::SharedMemoryReader},
+    crate::{
+        block::ReadBlockMemory,
+        in_memory::SharedMemoryReader,
+        state::{AccountView, read_account_nonce, sync::child_hashes},
+    },
     alloy::{
-        primitives::keccak256,
+        consensus::EMPTY_ROOT_HASH,
+        primitives::KECCAK_EMPTY,
+        rlp::Decodable,
         rpc::types::{EIP1186AccountProofResponse, EIP1186StorageProof},
     },
-    aptos_types::state_store::{state_key::StateKey, state_value::StateValue},
+    alloy_trie::{Nibbles, nodes::TrieNode},
+    aptos_types::state_store::{
+        state_key::StateKey, state_value::StateValue, table::TableHandle as StateTableHandle,
+    },
     bytes::Bytes,
     eth_trie::{DB, EthTrie, Trie, TrieError},
     move_binary_format::errors::PartialVMError,
@@ -21,15 +30,14 @@ use {
         ResolverBackedDB,
         state::{self, StorageTrieRepository},
     },
-    moved_execution::{
-        quick_get_eth_balance, quick_get_nonce,
-        transaction::{L2_HIGHEST_ADDRESS, L2_LOWEST_ADDRESS},
-    },
-    moved_shared::primitives::{Address, B256, KeyHashable, ToEthAddress, U256},
+    moved_execution::read_eth_balance,
+    moved_shared::primitives::{Address, B256, KeyHashable, ToEthAddress, ToMoveAddress, U256},
     moved_state::{
-        IN_MEMORY_EXPECT_MSG, evm_key_address, is_evm_storage_or_account_key, nodes::TreeKey,
+        IN_MEMORY_EXPECT_MSG, InMemoryTrieDb, evm_key_address, is_evm_storage_or_account_key,
+        nodes::{KeyHashCache, TreeKey},
+        resource_index::{InMemoryResourceIndex, ResourceIndex},
     },
-    std::{fmt::Debug, sync::Arc},
+    std::{collections::HashSet, fmt::Debug, sync::Arc},
 };
 
 pub type ProofResponse = EIP1186AccountProofResponse;
@@ -57,6 +65,11 @@ pub type Version = u64;
 /// * [`Self::balance_at`] - To fetch an amount of base token in an account read in its smallest
 ///   denomination at given block height.
 /// * [`Self::nonce_at`] - To fetch the nonce value set for an account at given block height.
+/// * [`Self::account_at`] - To fetch the balance, nonce and code of an account at given block
+///   height, all of them read the way [`AccountView`] describes.
+/// * [`Self::storage_at`] - To fetch the value of a storage slot of an EVM contract at given block
+///   height.
+/// * [`Self::resources_at`] - To list the Move resources held by an account at given block height.
 pub trait StateQueries {
     /// Queries the blockchain state version corresponding with block `height` for the amount of
     /// base token associated with `account`.
@@ -69,6 +82,8 @@ pub trait StateQueries {
 
     /// Queries the blockchain state version corresponding with block `height` for the nonce value
     /// associated with `account`.
+    ///
+    /// The nonce of an EVM contract is the one of its EVM account rather than its Move account.
     fn nonce_at(
         &self,
         evm_storage: &impl StorageTrieRepository,
@@ -76,6 +91,33 @@ pub trait StateQueries {
         height: BlockHeight,
     ) -> Option<Nonce>;
 
+    /// Queries the blockchain state version corresponding with block `height` for the view of
+    /// `account` that the EVM-facing queries report, which every address has.
+    fn account_at(
+        &self,
+        evm_storage: &impl StorageTrieRepository,
+        account: AccountAddress,
+        height: BlockHeight,
+    ) -> Option<AccountView>;
+
+    /// Queries the blockchain state version corresponding with block `height` for the value of the
+    /// EVM storage slot at `index` of `account`.
+    ///
+    /// A slot without a value, as well as any slot of an account that is not an EVM contract, is
+    /// zero.
+    fn storage_at(
+        &self,
+        evm_storage: &impl StorageTrieRepository,
+        account: AccountAddress,
+        index: U256,
+        height: BlockHeight,
+    ) -> Option<U256>;
+
+    /// Queries the blockchain state version corresponding with block `height` for the proof of the
+    /// EVM account of `account` and of each of its `storage_slots`, see
+    /// [`proof_from_trie_and_resolver`].
+    ///
+    /// Any address can be proven, including those of contracts deployed after genesis.
     fn proof_at(
         &self,
         evm_storage: &impl StorageTrieRepository,
@@ -84,7 +126,26 @@ pub trait StateQueries {
         height: BlockHeight,
     ) -> Option<ProofResponse>;
 
+    /// Queries the blockchain state version corresponding with block `height` for up to `limit`
+    /// resources of `account` along with their bcs encoded values, see [`resources_from_index`].
+    fn resources_at(
+        &self,
+        account: AccountAddress,
+        after: Option<&StructTag>,
+        limit: usize,
+        height: BlockHeight,
+    ) -> Option<Vec<(StructTag, Vec<u8>)>>;
+
+    /// Whether [`Self::resources_at`] lists every resource, which it does not for a database that
+    /// predates the resource index.
+    fn is_resource_index_complete(&self) -> bool;
+
     fn resolver_at(&self, height: BlockHeight) -> impl MoveResolver + TableResolver + '_;
+
+    /// Reads the encoded state trie node identified by its `hash`.
+    ///
+    /// Nodes are content addressed, so they are shared by every state version that contains them.
+    fn trie_node(&self, hash: B256) -> Option<Vec<u8>>;
 }
 
 pub trait ReadStateRoot {
@@ -113,15 +174,19 @@ pub struct InMemoryStateQueries<
     memory: R,
     db: Arc<D>,
     genesis_state_root: B256,
+    key_hashes: KeyHashCache,
+    resource_index: InMemoryResourceIndex,
 }
 
 impl<R: ReadStateRoot + Clone, D: DB> Clone for InMemoryStateQueries<R, D> {
     fn clone(&self) -> Self {
-        Self::new(
-            self.memory.clone(),
-            self.db.clone(),
-            self.genesis_state_root,
-        )
+        Self {
+            memory: self.memory.clone(),
+            db: self.db.clone(),
+            genesis_state_root: self.genesis_state_root,
+            key_hashes: self.key_hashes.clone(),
+            resource_index: self.resource_index.clone(),
+        }
     }
 }
 
@@ -131,9 +196,20 @@ impl<R: ReadStateRoot, D: DB> InMemoryStateQueries<R, D> {
             memory,
             db,
             genesis_state_root,
+            key_hashes: KeyHashCache::default(),
+            resource_index: InMemoryResourceIndex::default(),
         }
     }
 
+    /// Lists resources from the `resource_index` that the [`InMemoryState`] writing the state
+    /// shares.
+    ///
+    /// [`InMemoryState`]: moved_state::InMemoryState
+    pub fn with_resource_index(mut self, resource_index: InMemoryResourceIndex) -> Self {
+        self.resource_index = resource_index;
+        self
+    }
+
     fn root_by_height(&self, height: BlockHeight) -> Option<B256> {
         if height == 0 {
             return Some(self.genesis_state_root);
@@ -143,13 +219,62 @@ impl<R: ReadStateRoot, D: DB> InMemoryStateQueries<R, D> {
     }
 
     fn resolver(&self, height: BlockHeight) -> Option<impl MoveResolver + TableResolver + '_> {
-        Some(EthTrieResolver::new(
+        Some(EthTrieResolver::with_key_hashes(
             EthTrie::from(self.db.clone(), self.root_by_height(height)?)
                 .expect("State root should be valid"),
+            self.key_hashes.clone(),
         ))
     }
 }
 
+impl<R: ReadStateRoot> InMemoryStateQueries<R, InMemoryTrieDb> {
+    /// Removes the trie nodes that only the state of blocks below `horizon` refers to, returning
+    /// how many were removed.
+    ///
+    /// Every node of the state of a block from the `horizon` up to the latest one is kept. The
+    /// state must not be written while it is pruned, as the nodes of a state root that no block
+    /// refers to yet would be removed too.
+    pub fn prune(&self, horizon: BlockHeight) -> usize {
+        let roots =
+            (horizon..=self.memory.height()).filter_map(|height| self.root_by_height(height));
+        let reachable = reachable_nodes(self.db.as_ref(), roots);
+
+        self.db.retain(|hash| reachable.contains(&hash))
+    }
+}
+
+/// Hashes of the nodes of the tries under `roots` that are found in `db`.
+fn reachable_nodes(db: &impl DB, roots: impl IntoIterator<Item = B256>) -> HashSet<B256> {
+    let mut reachable = HashSet::new();
+    let mut pending: Vec<_> = roots.into_iter().collect();
+
+    while let Some(hash) = pending.pop() {
+        // Tries share most of their nodes, so a subtrie is only walked the first time it is reached
+        if !reachable.insert(hash) {
+            continue;
+        }
+        let Some(node) = db.get(hash.as_slice()).ok().flatten() else {
+            continue;
+        };
+        let Ok(node) = TrieNode::decode(&mut node.as_slice()) else {
+            continue;
+        };
+        pending.extend(
+            child_hashes(&Nibbles::default(), &node)
+                .into_iter()
+                .map(|(child, _)| child),
+        );
+    }
+
+    reachable
+}
+
+/// Builds an [EIP-1186](https://eips.ethereum.org/EIPS/eip-1186) proof of the EVM account at
+/// `address` and of each of its `storage_slots`.
+///
+/// Accounts and slots without a value are reported as empty, along with a proof of their absence.
+/// The balance and nonce of an account without an EVM account, such as one that only ever sent
+/// Move transactions, are still those of its Move account, while its code is reported as empty.
 pub fn proof_from_trie_and_resolver(
     address: Address,
     storage_slots: &[U256],
@@ -159,8 +284,15 @@ pub fn proof_from_trie_and_resolver(
 ) -> Option<ProofResponse> {
     let evm_db = ResolverBackedDB::new(storage_trie, resolver, &(), 0);
 
-    // All L2 contract account data is part of the EVM state
-    let account_info = evm_db.get_account(&address).ok()??;
+    // All L2 contract account data is part of the EVM state. An account that the EVM has never
+    // touched has no leaf in the trie, so its balance and nonce are read as any other query does.
+    let account = match evm_db.get_account(&address).ok()? {
+        Some(account) => account.inner,
+        None => {
+            let view = AccountView::read(&address.to_move_address(), resolver).ok()?;
+            state::Account::new(view.nonce, view.balance, KECCAK_EMPTY, EMPTY_ROOT_HASH).inner
+        }
+    };
 
     let account_key = TreeKey::Evm(address);
     let account_proof = tree
@@ -170,63 +302,153 @@ pub fn proof_from_trie_and_resolver(
         .map(Into::into)
         .collect();
 
-    let storage_proof = if storage_slots.is_empty() {
-        Vec::new()
+    let storage_proof = if account.storage_root == EMPTY_ROOT_HASH {
+        // An empty trie has no nodes, so the proof of any slot is empty too
+        storage_slots
+            .iter()
+            .map(|index| StorageProof {
+                key: (*index).into(),
+                value: U256::ZERO,
+                proof: Vec::new(),
+            })
+            .collect()
     } else {
         let mut storage = storage_trie
-            .for_account_with_root(&address, &account_info.inner.storage_root)
+            .for_account_with_root(&address, &account.storage_root)
             .ok()?;
 
-        storage_slots
-            .iter()
-            .filter_map(|index| {
-                let key = keccak256::<[u8; 32]>(index.to_be_bytes());
-                storage.proof(key.as_slice()).ok().map(|proof| {
-                    let value = storage.get(index)?.unwrap_or_default();
-
-                    Ok::<StorageProof, state::Error>(StorageProof {
-                        key: (*index).into(),
-                        value,
-                        proof: proof.into_iter().map(Into::into).collect(),
-                    })
-                })
+        storage
+            .proofs(storage_slots)
+            .ok()?
+            .into_iter()
+            .zip(storage_slots)
+            .map(|(slot, index)| StorageProof {
+                key: (*index).into(),
+                value: slot.value,
+                proof: slot.proof.into_iter().map(Into::into).collect(),
             })
-            .collect::<Result<_, _>>()
-            .unwrap()
+            .collect()
     };
 
     Some(ProofResponse {
         address,
-        balance: account_info.inner.balance,
-        code_hash: account_info.inner.code_hash,
-        nonce: account_info.inner.nonce,
-        storage_hash: account_info.inner.storage_root,
+        balance: account.balance,
+        code_hash: account.code_hash,
+        nonce: account.nonce,
+        storage_hash: account.storage_root,
         account_proof,
         storage_proof,
     })
 }
 
+/// Lists up to `limit` resources of `account` in the order of the `index`, starting after the one of
+/// `after` if it is given, along with their bcs encoded values read through `resolver`.
+///
+/// The index keeps every resource ever written, so the ones missing from the state version that
+/// `resolver` reads are skipped.
+pub fn resources_from_index(
+    index: &impl ResourceIndex,
+    resolver: &impl MoveResolver,
+    account: &AccountAddress,
+    after: Option<&StructTag>,
+    limit: usize,
+) -> Option<Vec<(StructTag, Vec<u8>)>> {
+    let mut resources = Vec::new();
+    let mut cursor = after.cloned();
+
+    while resources.len() < limit {
+        let tags = index.resources(account, cursor.as_ref(), limit).ok()?;
+        let Some(last) = tags.last().cloned() else {
+            break;
+        };
+
+        for tag in tags {
+            if resources.len() == limit {
+                break;
+            }
+            let (bytes, _) = resolver
+                .get_resource_bytes_with_metadata_and_layout(account, &tag, &[], None)
+                .ok()?;
+            if let Some(bytes) = bytes {
+                resources.push((tag, bytes.to_vec()));
+            }
+        }
+
+        cursor = Some(last);
+    }
+
+    Some(resources)
+}
+
+/// Reads the value of the storage slot at `index` of the EVM account at `address`, which is zero
+/// for a slot without a value.
+pub fn storage_from_resolver(
+    address: Address,
+    index: U256,
+    resolver: &impl MoveResolver,
+    storage_trie: &impl StorageTrieRepository,
+) -> Option<U256> {
+    let evm_db = ResolverBackedDB::new(storage_trie, resolver, &(), 0);
+    let Some(account) = evm_db.get_account(&address).ok()? else {
+        return Some(U256::ZERO);
+    };
+
+    // An empty trie has no root node to open it at
+    if account.inner.storage_root == EMPTY_ROOT_HASH {
+        return Some(U256::ZERO);
+    }
+
+    let storage = storage_trie
+        .for_account_with_root(&address, &account.inner.storage_root)
+        .ok()?;
+
+    Some(storage.get(&index).ok()?.unwrap_or_default())
+}
+
 impl<R: ReadStateRoot, D: DB> StateQueries for InMemoryStateQueries<R, D> {
     fn balance_at(
         &self,
-        evm_storage: &impl StorageTrieRepository,
+        _evm_storage: &impl StorageTrieRepository,
         account: AccountAddress,
         height: BlockHeight,
     ) -> Option<Balance> {
         let resolver = self.resolver(height)?;
 
-        Some(quick_get_eth_balance(&account, &resolver, evm_storage))
+        read_eth_balance(&account, &resolver).ok()
     }
 
     fn nonce_at(
         &self,
-        evm_storage: &impl StorageTrieRepository,
+        _evm_storage: &impl StorageTrieRepository,
         account: AccountAddress,
         height: BlockHeight,
     ) -> Option<Nonce> {
         let resolver = self.resolver(height)?;
 
-        Some(quick_get_nonce(&account, &resolver, evm_storage))
+        read_account_nonce(&account, &resolver).ok()
+    }
+
+    fn account_at(
+        &self,
+        _evm_storage: &impl StorageTrieRepository,
+        account: AccountAddress,
+        height: BlockHeight,
+    ) -> Option<AccountView> {
+        let resolver = self.resolver(height)?;
+
+        AccountView::read(&account, &resolver).ok()
+    }
+
+    fn storage_at(
+        &self,
+        evm_storage: &impl StorageTrieRepository,
+        account: AccountAddress,
+        index: U256,
+        height: BlockHeight,
+    ) -> Option<U256> {
+        let resolver = self.resolver(height)?;
+
+        storage_from_resolver(account.to_eth_address(), index, &resolver, evm_storage)
     }
 
     fn proof_at(
@@ -237,12 +459,6 @@ impl<R: ReadStateRoot, D: DB> StateQueries for InMemoryStateQueries<R, D> {
         height: BlockHeight,
     ) -> Option<ProofResponse> {
         let address = account.to_eth_address();
-
-        // Only L2 contract addresses supported at this time
-        if address < L2_LOWEST_ADDRESS || L2_HIGHEST_ADDRESS < address {
-            return None;
-        }
-
         let root = self.root_by_height(height)?;
         let resolver = self.resolver(height)?;
         let mut tree = EthTrie::from(self.db.clone(), root).expect(IN_MEMORY_EXPECT_MSG);
@@ -250,9 +466,29 @@ impl<R: ReadStateRoot, D: DB> StateQueries for InMemoryStateQueries<R, D> {
         proof_from_trie_and_resolver(address, storage_slots, &mut tree, &resolver, evm_storage)
     }
 
+    fn resources_at(
+        &self,
+        account: AccountAddress,
+        after: Option<&StructTag>,
+        limit: usize,
+        height: BlockHeight,
+    ) -> Option<Vec<(StructTag, Vec<u8>)>> {
+        let resolver = self.resolver(height)?;
+
+        resources_from_index(&self.resource_index, &resolver, &account, after, limit)
+    }
+
+    fn is_resource_index_complete(&self) -> bool {
+        matches!(self.resource_index.is_complete(), Ok(true))
+    }
+
     fn resolver_at(&self, height: BlockHeight) -> impl MoveResolver + TableResolver + '_ {
         self.resolver(height).unwrap()
     }
+
+    fn trie_node(&self, hash: B256) -> Option<Vec<u8>> {
+        self.db.get(hash.as_slice()).ok().flatten()
+    }
 }
 
 /// This is a [`MoveResolver`] that accesses blockchain state via [`EthTrie`].
@@ -261,11 +497,18 @@ impl<R: ReadStateRoot, D: DB> StateQueries for InMemoryStateQueries<R, D> {
 /// height, it will read from the blockchain state version at that block.
 pub struct EthTrieResolver<D: DB> {
     tree: EthTrie<D>,
+    key_hashes: KeyHashCache,
 }
 
 impl<D: DB> EthTrieResolver<D> {
     pub fn new(tree: EthTrie<D>) -> Self {
-        Self { tree }
+        Self::with_key_hashes(tree, KeyHashCache::default())
+    }
+
+    /// Creates the resolver looking the hashes of the keys it reads up in `key_hashes`, which can
+    /// be shared with other resolvers.
+    pub fn with_key_hashes(tree: EthTrie<D>, key_hashes: KeyHashCache) -> Self {
+        Self { tree, key_hashes }
     }
 }
 
@@ -276,7 +519,7 @@ impl<D: DB> ModuleResolver for EthTrieResolver<D> {
 
     fn get_module(&self, id: &ModuleId) -> Result<Option<Bytes>, PartialVMError> {
         let state_key = StateKey::module(id.address(), id.name());
-        let key_hash = TreeKey::StateKey(state_key).key_hash();
+        let key_hash = self.key_hashes.key_hash(&TreeKey::StateKey(state_key));
         let value = self.tree.get(key_hash.0.as_slice()).map_err(trie_err)?;
 
         Ok(deserialize_state_value(value))
@@ -299,7 +542,7 @@ impl<D: DB> ResourceResolver for EthTrieResolver<D> {
                 .map_err(|_| PartialVMError::new(StatusCode::DATA_FORMAT_ERROR))?;
             TreeKey::StateKey(state_key)
         };
-        let key_hash = tree_key.key_hash();
+        let key_hash = self.key_hashes.key_hash(&tree_key);
         let value = self.tree.get(key_hash.0.as_slice()).map_err(trie_err)?;
         let value = if is_evm_storage_or_account_key(struct_tag) {
             // In the case of EVM there is no additional serialization
@@ -316,11 +559,15 @@ impl<D: DB> ResourceResolver for EthTrieResolver<D> {
 impl<D: DB> TableResolver for EthTrieResolver<D> {
     fn resolve_table_entry_bytes_with_layout(
         &self,
-        _handle: &TableHandle,
-        _key: &[u8],
+        handle: &TableHandle,
+        key: &[u8],
         _maybe_layout: Option<&MoveTypeLayout>,
     ) -> Result<Option<Bytes>, PartialVMError> {
-        unimplemented!()
+        let state_key = StateKey::table_item(&StateTableHandle(handle.0), key);
+        let key_hash = self.key_hashes.key_hash(&TreeKey::StateKey(state_key));
+        let value = self.tree.get(key_hash.0.as_slice()).map_err(trie_err)?;
+
+        Ok(deserialize_state_value(value))
     }
 }
 
@@ -371,6 +618,32 @@ pub mod test_doubles {
             Some(3)
         }
 
+        fn account_at(
+            &self,
+            _evm_storage: &impl StorageTrieRepository,
+            account: AccountAddress,
+            height: BlockHeight,
+        ) -> Option<AccountView> {
+            assert_eq!(account, self.0);
+            assert_eq!(height, self.1);
+
+            Some(AccountView {
+                balance: U256::from(5),
+                nonce: 3,
+                code: Default::default(),
+            })
+        }
+
+        fn storage_at(
+            &self,
+            _evm_storage: &impl StorageTrieRepository,
+            _account: AccountAddress,
+            _index: U256,
+            _height: BlockHeight,
+        ) -> Option<U256> {
+            None
+        }
+
         fn proof_at(
             &self,
             _evm_storage: &impl StorageTrieRepository,
@@ -381,9 +654,27 @@ pub mod test_doubles {
             None
         }
 
+        fn resources_at(
+            &self,
+            _account: AccountAddress,
+            _after: Option<&StructTag>,
+            _limit: usize,
+            _height: BlockHeight,
+        ) -> Option<Vec<(StructTag, Vec<u8>)>> {
+            None
+        }
+
+        fn is_resource_index_complete(&self) -> bool {
+            true
+        }
+
         fn resolver_at(&self, _: BlockHeight) -> impl MoveResolver + TableResolver + '_ {
             EthTrieResolver::new(EthTrie::new(Arc::new(eth_trie::MemoryDB::new(true))))
         }
+
+        fn trie_node(&self, _: B256) -> Option<Vec<u8>> {
+            None
+        }
     }
 }
 
@@ -391,9 +682,10 @@ pub mod test_doubles {
 mod tests {
     use {
         super::*,
-        alloy::hex,
-        move_core_types::effects::ChangeSet,
-        move_table_extension::TableChangeSet,
+        alloy::{hex, primitives::keccak256},
+        alloy_trie::{Nibbles, proof::verify_proof},
+        move_core_types::effects::{ChangeSet, Op},
+        move_table_extension::{TableChange, TableChangeSet},
         move_vm_runtime::{
             AsUnsyncCodeStorage,
             module_traversal::{TraversalContext, TraversalStorage},
@@ -402,7 +694,7 @@ mod tests {
         moved_evm_ext::state::InMemoryStorageTrieRepository,
         moved_execution::{check_nonce, create_vm_session, mint_eth, session_id::SessionId},
         moved_genesis::{CreateMoveVm, MovedVm, config::GenesisConfig},
-        moved_shared::primitives::B256,
+        moved_shared::primitives::{B256, ToMoveAddress},
         moved_state::{InMemoryState, ResolverBasedModuleBytesStorage, State},
     };
 
@@ -835,4 +1127,317 @@ mod tests {
 
         assert_eq!(actual_nonce, expected_nonce);
     }
+
+    #[test]
+    fn test_query_fetches_allocated_nonce_of_account_without_code() {
+        let evm_storage = InMemoryStorageTrieRepository::new();
+        let address = Address::new(hex!("8fd379246834eac74b8419ffda202cf8051f7a03"));
+        let mut genesis_config = GenesisConfig::default();
+        genesis_config.alloc.insert(
+            address,
+            alloy::genesis::GenesisAccount {
+                nonce: Some(3),
+                ..Default::default()
+            },
+        );
+        let vm = MovedVm::new(&genesis_config);
+        let (changes, tables, _) = moved_genesis::build(&vm, &genesis_config, &evm_storage);
+        let mut state = InMemoryState::default();
+        state.apply_with_tables(changes, tables).unwrap();
+
+        let query =
+            InMemoryStateQueries::new(vec![state.state_root()], state.db(), state.state_root());
+
+        let actual_nonce = query
+            .nonce_at(&evm_storage, address.to_move_address(), 0)
+            .expect("Block height should exist");
+
+        assert_eq!(actual_nonce, 3);
+    }
+
+    fn genesis_state() -> (InMemoryState, InMemoryStorageTrieRepository, GenesisConfig) {
+        let mut state = InMemoryState::default();
+        let mut evm_storage = InMemoryStorageTrieRepository::new();
+        let genesis_config = GenesisConfig::default();
+        let (changes, tables, evm_storage_changes) = moved_genesis_image::load();
+        moved_genesis::apply(
+            changes,
+            tables,
+            evm_storage_changes,
+            &genesis_config,
+            &mut state,
+            &mut evm_storage,
+        );
+
+        (state, evm_storage, genesis_config)
+    }
+
+    #[test]
+    fn test_prune_removes_only_the_nodes_of_states_below_horizon() {
+        let db = InMemoryState::create_db();
+        let mut state = InMemoryState::new(db.clone());
+        let mut evm_storage = InMemoryStorageTrieRepository::new();
+        let genesis_config = GenesisConfig::default();
+        let (changes, tables, evm_storage_changes) = moved_genesis_image::load();
+        moved_genesis::apply(
+            changes,
+            tables,
+            evm_storage_changes,
+            &genesis_config,
+            &mut state,
+            &mut evm_storage,
+        );
+        let addr = AccountAddress::TWO;
+        let mut storage = vec![genesis_config.initial_state_root];
+        for _ in 0..2 {
+            mint_one_eth(&mut state, addr);
+            storage.push(state.state_root());
+        }
+        let query =
+            InMemoryStateQueries::new(storage, db.clone(), genesis_config.initial_state_root);
+
+        let removed = query.prune(2);
+
+        assert!(removed > 0);
+        assert_eq!(query.prune(2), 0);
+        assert!(
+            db.get(genesis_config.initial_state_root.as_slice())
+                .unwrap()
+                .is_none()
+        );
+        let balance = query.balance_at(&evm_storage, addr, 2).unwrap();
+        assert_eq!(balance, U256::from(2u64));
+    }
+
+    /// Checks the proofs using `alloy-trie`, rather than the trie implementation that built them.
+    fn verify(response: &ProofResponse, state_root: B256) {
+        let account = state::Account::new(
+            response.nonce,
+            response.balance,
+            response.code_hash,
+            response.storage_hash,
+        );
+        let is_empty = response.nonce == 0
+            && response.balance.is_zero()
+            && response.code_hash == KECCAK_EMPTY
+            && response.storage_hash == EMPTY_ROOT_HASH;
+        verify_proof(
+            state_root,
+            Nibbles::unpack(keccak256(response.address)),
+            (!is_empty).then(|| account.serialize()),
+            &response.account_proof,
+        )
+        .unwrap();
+
+        for slot in &response.storage_proof {
+            let key = keccak256(slot.key.as_b256());
+            let value = (!slot.value.is_zero())
+                .then(|| alloy::rlp::encode_fixed_size(&slot.value).to_vec());
+            verify_proof(
+                response.storage_hash,
+                Nibbles::unpack(key),
+                value,
+                &slot.proof,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_proof_of_contract_with_storage_verifies_against_state_root() {
+        let (state, evm_storage, genesis_config) = genesis_state();
+        let query = InMemoryStateQueries::new(
+            vec![genesis_config.initial_state_root],
+            state.db(),
+            genesis_config.initial_state_root,
+        );
+        let address = hex!("4200000000000000000000000000000000000016");
+        // Proxy admin slot, which is set, and a slot that is not
+        let slots = [
+            U256::from_be_bytes(hex!(
+                "b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103"
+            )),
+            U256::from(12345),
+        ];
+
+        let response = query
+            .proof_at(
+                &evm_storage,
+                Address::new(address).to_move_address(),
+                &slots,
+                0,
+            )
+            .unwrap();
+
+        assert_eq!(response.storage_proof.len(), slots.len());
+        assert!(!response.storage_proof[0].value.is_zero());
+        assert!(response.storage_proof[1].value.is_zero());
+        verify(&response, genesis_config.initial_state_root);
+    }
+
+    #[test]
+    fn test_proof_of_missing_account_verifies_as_empty() {
+        let (state, evm_storage, genesis_config) = genesis_state();
+        let mut tree = EthTrie::from(state.db(), genesis_config.initial_state_root).unwrap();
+        let address = Address::repeat_byte(0xab);
+
+        let response = proof_from_trie_and_resolver(
+            address,
+            &[U256::ZERO],
+            &mut tree,
+            state.resolver(),
+            &evm_storage,
+        )
+        .unwrap();
+
+        assert_eq!(response.balance, U256::ZERO);
+        assert_eq!(response.code_hash, KECCAK_EMPTY);
+        assert_eq!(response.storage_hash, EMPTY_ROOT_HASH);
+        assert!(response.storage_proof[0].proof.is_empty());
+        verify(&response, genesis_config.initial_state_root);
+    }
+
+    #[test]
+    fn test_proof_of_move_only_account_reports_its_balance() {
+        let (mut state, evm_storage, _) = genesis_state();
+        let address = Address::repeat_byte(0xab);
+        mint_one_eth(&mut state, address.to_move_address());
+        let mut tree = EthTrie::from(state.db(), state.state_root()).unwrap();
+
+        let response =
+            proof_from_trie_and_resolver(address, &[], &mut tree, state.resolver(), &evm_storage)
+                .unwrap();
+
+        assert_eq!(response.balance, U256::from(1u64));
+        assert_eq!(response.code_hash, KECCAK_EMPTY);
+        assert_eq!(response.storage_hash, EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn test_proof_of_address_outside_of_predeploys_verifies_against_state_root() {
+        let (state, evm_storage, genesis_config) = genesis_state();
+        let query = InMemoryStateQueries::new(
+            vec![genesis_config.initial_state_root],
+            state.db(),
+            genesis_config.initial_state_root,
+        );
+        let address = Address::repeat_byte(0xab);
+
+        let response = query
+            .proof_at(&evm_storage, address.to_move_address(), &[U256::from(1)], 0)
+            .unwrap();
+
+        assert_eq!(response.address, address);
+        assert_eq!(response.storage_proof.len(), 1);
+        verify(&response, genesis_config.initial_state_root);
+    }
+
+    #[test]
+    fn test_account_of_contract_has_its_evm_nonce_and_code() {
+        let (state, evm_storage, genesis_config) = genesis_state();
+        let query = InMemoryStateQueries::new(
+            vec![genesis_config.initial_state_root],
+            state.db(),
+            genesis_config.initial_state_root,
+        );
+        let address = Address::new(hex!("4200000000000000000000000000000000000016"));
+
+        let account = query
+            .account_at(&evm_storage, address.to_move_address(), 0)
+            .unwrap();
+        let proof = query
+            .proof_at(&evm_storage, address.to_move_address(), &[], 0)
+            .unwrap();
+
+        assert!(!account.code.is_empty());
+        assert_eq!(keccak256(&account.code), proof.code_hash);
+        assert_eq!(account.nonce, proof.nonce);
+        assert_eq!(
+            query.nonce_at(&evm_storage, address.to_move_address(), 0),
+            Some(proof.nonce)
+        );
+    }
+
+    #[test]
+    fn test_account_without_any_resource_is_empty() {
+        let (state, evm_storage, genesis_config) = genesis_state();
+        let query = InMemoryStateQueries::new(
+            vec![genesis_config.initial_state_root],
+            state.db(),
+            genesis_config.initial_state_root,
+        );
+        let address = Address::repeat_byte(0xab);
+
+        let account = query
+            .account_at(&evm_storage, address.to_move_address(), 0)
+            .unwrap();
+
+        assert_eq!(account, AccountView::default());
+    }
+
+    #[test]
+    fn test_storage_matches_values_of_proof() {
+        let (state, evm_storage, genesis_config) = genesis_state();
+        let query = InMemoryStateQueries::new(
+            vec![genesis_config.initial_state_root],
+            state.db(),
+            genesis_config.initial_state_root,
+        );
+        let address = Address::new(hex!("4200000000000000000000000000000000000016"));
+        let slots = [
+            U256::from_be_bytes(hex!(
+                "b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103"
+            )),
+            U256::from(12345),
+        ];
+
+        let proof = query
+            .proof_at(&evm_storage, address.to_move_address(), &slots, 0)
+            .unwrap();
+
+        for (index, slot) in slots.into_iter().zip(proof.storage_proof) {
+            let value = query.storage_at(&evm_storage, address.to_move_address(), index, 0);
+            assert_eq!(value, Some(slot.value));
+        }
+        assert_eq!(
+            query.storage_at(
+                &evm_storage,
+                Address::repeat_byte(0xab).to_move_address(),
+                U256::ZERO,
+                0
+            ),
+            Some(U256::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_resolver_reads_table_items_from_trie() {
+        let handle = TableHandle(AccountAddress::new([0x11; 32]));
+        let mut table_changes = TableChangeSet::default();
+        table_changes.changes.insert(
+            handle,
+            TableChange {
+                entries: [(vec![1], Op::New(Bytes::from_static(b"value")))].into(),
+            },
+        );
+        let mut state = InMemoryState::default();
+        state
+            .apply_with_tables(ChangeSet::new(), table_changes)
+            .unwrap();
+
+        let resolver = EthTrieResolver::new(EthTrie::from(state.db(), state.state_root()).unwrap());
+
+        assert_eq!(
+            resolver
+                .resolve_table_entry_bytes_with_layout(&handle, &[1], None)
+                .unwrap(),
+            Some(Bytes::from_static(b"value"))
+        );
+        assert_eq!(
+            resolver
+                .resolve_table_entry_bytes_with_layout(&handle, &[2], None)
+                .unwrap(),
+            None
+        );
+    }
 }
@@ -0,0 +1,324 @@
+//! Client side of the trie node sync.
+//!
+//! A node catching up with the chain can copy a trie from a peer instead of re-executing every
+//! block. Trie nodes are content addressed, so knowing the root hash is enough to walk the whole
+//! trie: every fetched node is checked against the hash it was requested by, and reveals the hashes
+//! of its children. The state trie and each EVM storage trie are synced separately, by their roots.
+//! The storage roots are found in the EVM account leaves of the state trie as it is synced, see
+//! [`TrieSync::take_storage_roots`].
+
+use {
+    alloy::{
+        consensus::Account,
+        primitives::keccak256,
+        rlp::{self, Decodable},
+    },
+    alloy_trie::{EMPTY_ROOT_HASH, Nibbles, nodes::TrieNode},
+    eth_trie::DB,
+    moved_shared::primitives::B256,
+    std::{collections::HashMap, sync::Arc},
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum TrieSyncError {
+    #[error("Node {0} was not requested")]
+    UnexpectedNode(B256),
+    #[error("Node {0} is not a valid trie node: {1}")]
+    InvalidNode(B256, alloy::rlp::Error),
+    #[error("Failed to access the trie database: {0}")]
+    Db(String),
+}
+
+/// A node that has been requested but not stored in the database yet.
+#[derive(Debug, Default)]
+struct Request {
+    /// The encoded node, once it is fetched.
+    data: Option<Vec<u8>>,
+    /// Number of children to store before the node itself can be stored.
+    missing_children: usize,
+    parents: Vec<B256>,
+    /// Nibbles of the keys under the node, which lead to it from the root.
+    path: Nibbles,
+}
+
+/// Schedules the fetching of the trie nodes under `root` that are missing from the database.
+///
+/// A node is only stored after its whole subtrie is, so a node present in the database always has
+/// its subtrie complete. A sync that is interrupted can then resume from a new [`TrieSync`] for the
+/// same root, skipping the subtries already stored.
+pub struct TrieSync<D> {
+    db: Arc<D>,
+    requests: HashMap<B256, Request>,
+    /// Hashes of the requested nodes that are not fetched yet.
+    queue: Vec<B256>,
+    /// Key hashes and storage roots of the EVM accounts found since the last
+    /// [`Self::take_storage_roots`].
+    storage_roots: Vec<(B256, B256)>,
+}
+
+impl<D: DB> TrieSync<D> {
+    pub fn new(root: B256, db: Arc<D>) -> Self {
+        let mut sync = Self {
+            db,
+            requests: HashMap::new(),
+            queue: Vec::new(),
+            storage_roots: Vec::new(),
+        };
+        if !sync.is_stored(root) {
+            sync.requests.insert(root, Request::default());
+            sync.queue.push(root);
+        }
+        sync
+    }
+
+    /// Returns `true` once the whole trie is stored in the database.
+    pub fn is_complete(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Returns the hashes of at most `max` nodes to fetch next.
+    ///
+    /// The same hashes are returned again until the nodes are passed to [`Self::process`].
+    pub fn pending(&self, max: usize) -> Vec<B256> {
+        self.queue.iter().rev().take(max).copied().collect()
+    }
+
+    /// Returns the key hashes and storage roots of the EVM accounts with storage found in the leaves
+    /// processed since the last call.
+    ///
+    /// Each of these storage tries is to be synced separately. The leaves of subtries that were
+    /// already stored when the sync started are not visited again, so their accounts are not found.
+    pub fn take_storage_roots(&mut self) -> Vec<(B256, B256)> {
+        std::mem::take(&mut self.storage_roots)
+    }
+
+    /// Verifies fetched `nodes` and stores every node whose subtrie is complete.
+    ///
+    /// The nodes may come in any order. Requested nodes that are missing from `nodes` stay pending.
+    pub fn process(&mut self, nodes: Vec<Vec<u8>>) -> Result<(), TrieSyncError> {
+        for node in nodes {
+            let hash = keccak256(&node);
+            if !self
+                .requests
+                .get(&hash)
+                .is_some_and(|request| request.data.is_none())
+            {
+                return Err(TrieSyncError::UnexpectedNode(hash));
+            }
+
+            let path = self.requests[&hash].path.clone();
+            let decoded = TrieNode::decode(&mut &node[..])
+                .map_err(|e| TrieSyncError::InvalidNode(hash, e))?;
+            if let Some(storage_root) = storage_root(&path, &decoded) {
+                self.storage_roots.push(storage_root);
+            }
+            let mut missing_children = 0;
+            for (child, child_path) in child_hashes(&path, &decoded) {
+                if self.is_stored(child) {
+                    continue;
+                }
+                missing_children += 1;
+                self.requests
+                    .entry(child)
+                    .or_insert_with(|| {
+                        self.queue.push(child);
+                        Request {
+                            path: child_path,
+                            ..Default::default()
+                        }
+                    })
+                    .parents
+                    .push(hash);
+            }
+
+            let request = self.requests.get_mut(&hash).expect("Node is requested");
+            request.data = Some(node);
+            request.missing_children = missing_children;
+            self.queue.retain(|queued| *queued != hash);
+
+            if missing_children == 0 {
+                self.store(hash)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stores the node `hash` and every ancestor that it completes.
+    fn store(&mut self, hash: B256) -> Result<(), TrieSyncError> {
+        let mut complete = vec![hash];
+
+        while let Some(hash) = complete.pop() {
+            let request = self.requests.remove(&hash).expect("Node is requested");
+            let data = request.data.expect("Complete node is fetched");
+            self.db
+                .insert(hash.as_slice(), data)
+                .map_err(|e| TrieSyncError::Db(format!("{e:?}")))?;
+
+            for parent in request.parents {
+                let parent_request = self.requests.get_mut(&parent).expect("Parent is requested");
+                parent_request.missing_children -= 1;
+                if parent_request.missing_children == 0 {
+                    complete.push(parent);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_stored(&self, hash: B256) -> bool {
+        self.db.get(hash.as_slice()).ok().flatten().is_some()
+    }
+}
+
+/// Hashes and paths of the children of `node`, which is found at `path`.
+///
+/// Children shorter than a hash are embedded in their parent and need no fetching.
+pub(super) fn child_hashes(path: &Nibbles, node: &TrieNode) -> Vec<(B256, Nibbles)> {
+    match node {
+        TrieNode::Branch(branch) => (0..16u8)
+            .filter(|nibble| branch.state_mask.is_bit_set(*nibble))
+            .zip(&branch.stack)
+            .filter_map(|(nibble, child)| {
+                let mut child_path = path.clone();
+                child_path.push(nibble);
+                Some((child.as_hash()?, child_path))
+            })
+            .collect(),
+        TrieNode::Extension(extension) => extension
+            .child
+            .as_hash()
+            .map(|child| (child, path.join(&extension.key)))
+            .into_iter()
+            .collect(),
+        TrieNode::Leaf(_) | TrieNode::EmptyRoot => Vec::new(),
+    }
+}
+
+/// The key hash and storage root of the EVM account stored in `node`, which is found at `path`, if
+/// it is the leaf of an account with storage.
+///
+/// Only the EVM accounts are RLP lists among the values of the state trie.
+fn storage_root(path: &Nibbles, node: &TrieNode) -> Option<(B256, B256)> {
+    let TrieNode::Leaf(leaf) = node else {
+        return None;
+    };
+    let key = path.join(&leaf.key);
+    if key.len() != 2 * B256::len_bytes() {
+        return None;
+    }
+    let account: Account = rlp::decode_exact(&leaf.value).ok()?;
+
+    (account.storage_root != EMPTY_ROOT_HASH)
+        .then(|| (B256::from_slice(&key.pack()), account.storage_root))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        eth_trie::{EthTrie, MemoryDB, Trie},
+    };
+
+    fn source_trie() -> (Arc<MemoryDB>, B256) {
+        let db = Arc::new(MemoryDB::new(true));
+        let mut trie = EthTrie::new(db.clone());
+        for i in 0..500u32 {
+            trie.insert(keccak256(i.to_be_bytes()).as_slice(), &i.to_be_bytes())
+                .unwrap();
+        }
+        let root = trie.root_hash().unwrap();
+        (db, root)
+    }
+
+    fn serve(db: &MemoryDB, hashes: &[B256]) -> Vec<Vec<u8>> {
+        hashes
+            .iter()
+            .filter_map(|hash| db.get(hash.as_slice()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_sync_copies_trie_from_peer() {
+        let (source, root) = source_trie();
+        let target = Arc::new(MemoryDB::new(true));
+        let mut sync = TrieSync::new(root, target.clone());
+
+        while !sync.is_complete() {
+            let hashes = sync.pending(16);
+            sync.process(serve(&source, &hashes)).unwrap();
+        }
+
+        let source_trie = EthTrie::from(source, root).unwrap();
+        let synced = EthTrie::from(target, root).unwrap();
+        for i in 0..500u32 {
+            let key = keccak256(i.to_be_bytes());
+            assert_eq!(
+                synced.get(key.as_slice()).unwrap(),
+                source_trie.get(key.as_slice()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_interrupted_sync_resumes_with_complete_subtries() {
+        let (source, root) = source_trie();
+        let target = Arc::new(MemoryDB::new(true));
+        let mut sync = TrieSync::new(root, target.clone());
+        for _ in 0..3 {
+            let hashes = sync.pending(16);
+            sync.process(serve(&source, &hashes)).unwrap();
+        }
+        assert!(target.get(root.as_slice()).unwrap().is_none());
+
+        let mut sync = TrieSync::new(root, target.clone());
+        while !sync.is_complete() {
+            let hashes = sync.pending(16);
+            sync.process(serve(&source, &hashes)).unwrap();
+        }
+
+        assert!(target.get(root.as_slice()).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_sync_finds_storage_roots_of_evm_accounts() {
+        let (source, _) = source_trie();
+        let mut trie = EthTrie::new(source.clone());
+        let with_storage = keccak256([1u8; 20]);
+        let storage_root = B256::repeat_byte(0x22);
+        let account = |storage_root| {
+            alloy::rlp::encode(Account {
+                nonce: 1,
+                storage_root,
+                ..Default::default()
+            })
+        };
+        trie.insert(with_storage.as_slice(), &account(storage_root))
+            .unwrap();
+        trie.insert(keccak256([2u8; 20]).as_slice(), &account(EMPTY_ROOT_HASH))
+            .unwrap();
+        let root = trie.root_hash().unwrap();
+        let mut sync = TrieSync::new(root, Arc::new(MemoryDB::new(true)));
+
+        let mut storage_roots = Vec::new();
+        while !sync.is_complete() {
+            let hashes = sync.pending(16);
+            sync.process(serve(&source, &hashes)).unwrap();
+            storage_roots.extend(sync.take_storage_roots());
+        }
+
+        assert_eq!(storage_roots, vec![(with_storage, storage_root)]);
+    }
+
+    #[test]
+    fn test_unrequested_node_is_rejected() {
+        let (_, root) = source_trie();
+        let mut sync = TrieSync::new(root, Arc::new(MemoryDB::new(true)));
+
+        let result = sync.process(vec![vec![0xc0]]);
+
+        assert!(matches!(result, Err(TrieSyncError::UnexpectedNode(_))));
+    }
+}
@@ -1,5 +1,8 @@
 use {
-    crate::transaction::ExtendedTransaction,
+    crate::{
+        in_memory::{EvictionWindow, HeightWindow},
+        transaction::ExtendedTransaction,
+    },
     moved_shared::primitives::B256,
     std::{ops::Deref, sync::Arc},
 };
@@ -24,19 +27,47 @@ impl AsRef<ReadHandle> for TransactionMemoryReader {
     }
 }
 
+/// A storage for transactions that keeps data in memory.
+///
+/// Every transaction is kept unless the storage is bounded by [`Self::set_eviction_window`].
 #[derive(Debug)]
 pub struct TransactionMemory {
     transactions: WriteHandle,
+    window: HeightWindow<B256>,
 }
 
 impl TransactionMemory {
-    pub const fn new(transactions: WriteHandle) -> Self {
-        Self { transactions }
+    pub fn new(transactions: WriteHandle) -> Self {
+        Self {
+            transactions,
+            window: HeightWindow::default(),
+        }
+    }
+
+    /// Evicts the transactions of the blocks that fall below the `window`, as new transactions are
+    /// added.
+    pub fn set_eviction_window(&mut self, window: EvictionWindow) {
+        self.window.set_eviction_window(window);
+    }
+
+    /// Number of blocks whose transactions were evicted so far.
+    pub fn evicted_blocks(&self) -> u64 {
+        self.window.evicted_blocks()
     }
 
     pub fn extend(&mut self, tx: impl IntoIterator<Item = ExtendedTransaction>) {
-        self.transactions
-            .extend(tx.into_iter().map(|tx| (tx.hash(), Arc::new(tx))));
+        for tx in tx {
+            let hash = tx.hash();
+            self.window.push(tx.block_number, hash);
+            self.transactions.insert(hash, Arc::new(tx));
+        }
+        let evicted = self.window.evict();
+        if self.window.is_compaction_due(&evicted) {
+            self.transactions.fit_all();
+        }
+        for hash in evicted.into_values().flatten() {
+            self.transactions.empty(hash);
+        }
         self.transactions.refresh();
     }
 }
@@ -50,6 +81,8 @@ impl AsRef<ReadHandle> for TransactionMemory {
 pub trait ReadTransactionMemory {
     fn by_hash(&self, hash: B256) -> Option<ExtendedTransaction>;
     fn by_hashes(&self, hashes: impl IntoIterator<Item = B256>) -> Vec<ExtendedTransaction>;
+    /// Number of transactions held in memory.
+    fn count(&self) -> usize;
 }
 
 impl<T: AsRef<ReadHandle>> ReadTransactionMemory for T {
@@ -65,4 +98,50 @@ impl<T: AsRef<ReadHandle>> ReadTransactionMemory for T {
             .filter_map(|hash| self.by_hash(hash))
             .collect()
     }
+
+    fn count(&self) -> usize {
+        self.as_ref().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        alloy::consensus::Sealable,
+        op_alloy::consensus::{OpTxEnvelope, TxDeposit},
+    };
+
+    fn transaction(block_number: u64) -> ExtendedTransaction {
+        let tx = TxDeposit {
+            source_hash: B256::repeat_byte(block_number as u8),
+            ..Default::default()
+        };
+
+        ExtendedTransaction::new(
+            0,
+            OpTxEnvelope::Deposit(tx.seal_slow()),
+            block_number,
+            B256::repeat_byte(block_number as u8),
+            0,
+        )
+    }
+
+    #[test]
+    fn test_transactions_of_blocks_below_retention_window_are_evicted() {
+        let (reader, writer) = evmap::new();
+        let reader = TransactionMemoryReader::new(reader);
+        let mut memory = TransactionMemory::new(writer);
+        let window = EvictionWindow::default();
+        memory.set_eviction_window(window.clone());
+
+        memory.extend([transaction(1)]);
+        window.advance(2);
+        memory.extend([transaction(2)]);
+
+        assert!(reader.by_hash(transaction(1).hash()).is_none());
+        assert_eq!(reader.by_hash(transaction(2).hash()), Some(transaction(2)));
+        assert_eq!(reader.count(), 1);
+        assert_eq!(memory.evicted_blocks(), 1);
+    }
 }
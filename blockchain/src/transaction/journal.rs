@@ -0,0 +1,82 @@
+use {alloy::consensus::TxEnvelope, moved_shared::primitives::B256, std::fmt::Debug};
+
+/// Keeps the transactions that were accepted into the mem-pool, but not included in a block yet.
+///
+/// The mem-pool itself lives in memory, so a durable implementation lets a restarted node pick up
+/// the transactions it had accepted before it stopped rather than dropping them.
+pub trait TransactionJournal {
+    /// The associated error type for the backing storage access operation.
+    type Err: Debug;
+
+    fn add(&mut self, tx_hash: B256, tx: &TxEnvelope) -> Result<(), Self::Err>;
+
+    fn remove(&mut self, tx_hashes: impl IntoIterator<Item = B256>) -> Result<(), Self::Err>;
+
+    /// Returns all transactions that have been added and not removed yet.
+    fn pending(&self) -> Result<Vec<(B256, TxEnvelope)>, Self::Err>;
+}
+
+pub mod in_memory {
+    use {
+        crate::transaction::TransactionJournal,
+        alloy::consensus::TxEnvelope,
+        moved_shared::primitives::B256,
+        std::{collections::HashMap, convert::Infallible},
+    };
+
+    #[derive(Debug, Clone, Default)]
+    pub struct InMemoryTransactionJournal {
+        transactions: HashMap<B256, TxEnvelope>,
+    }
+
+    impl InMemoryTransactionJournal {
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+
+    impl TransactionJournal for InMemoryTransactionJournal {
+        type Err = Infallible;
+
+        fn add(&mut self, tx_hash: B256, tx: &TxEnvelope) -> Result<(), Self::Err> {
+            self.transactions.insert(tx_hash, tx.clone());
+            Ok(())
+        }
+
+        fn remove(&mut self, tx_hashes: impl IntoIterator<Item = B256>) -> Result<(), Self::Err> {
+            for tx_hash in tx_hashes {
+                self.transactions.remove(&tx_hash);
+            }
+            Ok(())
+        }
+
+        fn pending(&self) -> Result<Vec<(B256, TxEnvelope)>, Self::Err> {
+            Ok(self
+                .transactions
+                .iter()
+                .map(|(tx_hash, tx)| (*tx_hash, tx.clone()))
+                .collect())
+        }
+    }
+}
+
+#[cfg(any(feature = "test-doubles", test))]
+mod test_doubles {
+    use super::*;
+
+    impl TransactionJournal for () {
+        type Err = std::convert::Infallible;
+
+        fn add(&mut self, _: B256, _: &TxEnvelope) -> Result<(), Self::Err> {
+            Ok(())
+        }
+
+        fn remove(&mut self, _: impl IntoIterator<Item = B256>) -> Result<(), Self::Err> {
+            Ok(())
+        }
+
+        fn pending(&self) -> Result<Vec<(B256, TxEnvelope)>, Self::Err> {
+            Ok(Vec::new())
+        }
+    }
+}
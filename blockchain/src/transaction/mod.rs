@@ -2,10 +2,12 @@ pub use {
     in_memory::{
         ReadHandle, ReadTransactionMemory, TransactionMemory, TransactionMemoryReader, WriteHandle,
     },
+    journal::{TransactionJournal, in_memory::InMemoryTransactionJournal},
     read::{TransactionQueries, TransactionResponse, in_memory::InMemoryTransactionQueries},
     write::{ExtendedTransaction, TransactionRepository, in_memory::InMemoryTransactionRepository},
 };
 
 mod in_memory;
+mod journal;
 mod read;
 mod write;
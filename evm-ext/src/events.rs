@@ -23,6 +23,8 @@ pub trait EthTransferLog {
     fn take_transfers(&self) -> Vec<EthTransfer>;
     fn add_tx_origin(&self, address: AccountAddress, amount: U256);
     fn take_origins(&self) -> Vec<(AccountAddress, U256)>;
+    fn push_burn(&self, address: AccountAddress, amount: U256);
+    fn take_burns(&self) -> Vec<(AccountAddress, U256)>;
 }
 
 /// Struct external to the EVM to capture transfer events.
@@ -36,6 +38,7 @@ pub trait EthTransferLog {
 pub struct EthTransfersLogger {
     transfers: RefCell<Vec<EthTransfer>>,
     origins: RefCell<Vec<(AccountAddress, U256)>>,
+    burns: RefCell<Vec<(AccountAddress, U256)>>,
 }
 
 impl EthTransferLog for EthTransfersLogger {
@@ -54,6 +57,39 @@ impl EthTransferLog for EthTransfersLogger {
     fn take_origins(&self) -> Vec<(AccountAddress, U256)> {
         self.origins.take()
     }
+
+    fn push_burn(&self, address: AccountAddress, amount: U256) {
+        self.burns.borrow_mut().push((address, amount));
+    }
+
+    fn take_burns(&self) -> Vec<(AccountAddress, U256)> {
+        self.burns.take()
+    }
+}
+
+impl EthTransfersLogger {
+    /// Returns the accounts that are credited a non-zero amount by the logged transfers, without
+    /// taking them.
+    pub fn recipients(&self) -> Vec<AccountAddress> {
+        let origins = self.origins.borrow();
+        let transfers = self.transfers.borrow();
+        let mut recipients: Vec<_> = origins
+            .iter()
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(address, _)| *address)
+            .chain(
+                transfers
+                    .iter()
+                    .filter(|transfer| !transfer.amount.is_zero())
+                    .map(|transfer| transfer.to),
+            )
+            .filter(|address| address != &EVM_NATIVE_ADDRESS)
+            .collect();
+        recipients.sort_unstable();
+        recipients.dedup();
+
+        recipients
+    }
 }
 
 impl EthTransferLog for () {
@@ -68,6 +104,12 @@ impl EthTransferLog for () {
     fn take_origins(&self) -> Vec<(AccountAddress, U256)> {
         Vec::new()
     }
+
+    fn push_burn(&self, _address: AccountAddress, _amount: U256) {}
+
+    fn take_burns(&self) -> Vec<(AccountAddress, U256)> {
+        Vec::new()
+    }
 }
 
 /// Marker struct defined in our framework for marking data as FixedBytes in the Solidity ABI.
@@ -7,8 +7,10 @@ pub use self::{
         evm_transact_with_native,
     },
     state_changes::{
-        Changes, extract_evm_changes, extract_evm_changes_from_native, genesis_state_changes,
+        Changes, StorageSlotWrites, extract_evm_changes, extract_evm_changes_from_native,
+        genesis_state_changes, storage_slot_writes,
     },
+    trace::{EvmCallFrame, EvmCallKind, EvmCallTracer},
     type_utils::extract_evm_result,
 };
 
@@ -30,6 +32,7 @@ mod native_impl;
 mod solidity_abi;
 pub mod state;
 mod state_changes;
+mod trace;
 pub mod type_utils;
 
 /// Address where the EVM native is stored
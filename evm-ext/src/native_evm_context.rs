@@ -6,6 +6,7 @@ use {
     crate::{
         events::EthTransferLog,
         state::{self, BlockHashLookup, StorageTrieRepository},
+        trace::EvmCallTracer,
     },
     alloy::primitives::map::HashMap,
     aptos_types::vm_status::StatusCode,
@@ -46,6 +47,11 @@ pub struct NativeEVMContext<'a> {
     pub db: CacheDB<ResolverBackedDB<'a>>,
     pub state_changes: Vec<HashMap<Address, Account>>,
     pub block_header: HeaderForExecution,
+    /// Output of the latest EVM execution if it failed, such as the data of a revert. The
+    /// framework aborts on a failed execution, which loses the output otherwise.
+    pub failed_output: Option<Vec<u8>>,
+    /// Records the call frames of the EVM executions when the transaction is traced.
+    pub tracer: Option<EvmCallTracer>,
 }
 
 impl<'a> NativeEVMContext<'a> {
@@ -68,6 +74,8 @@ impl<'a> NativeEVMContext<'a> {
             )),
             state_changes: Vec::new(),
             block_header,
+            failed_output: None,
+            tracer: None,
         }
     }
 
@@ -6,7 +6,9 @@ use {
         solidity_abi::{abi_decode_params, abi_encode_params},
         type_utils::evm_result_to_move_value,
     },
-    crate::{ResolverBackedDB, events::EthTransferLog, native_evm_context::DbError},
+    crate::{
+        ResolverBackedDB, events::EthTransferLog, native_evm_context::DbError, trace::EvmCallTracer,
+    },
     alloy::eips::eip2930::AccessList,
     aptos_gas_algebra::{GasExpression, GasQuantity, InternalGasUnit},
     aptos_native_interface::{
@@ -27,6 +29,7 @@ use {
             EthFrame, EthPrecompiles, FrameResult, Handler, MainnetContext, MainnetHandler,
             instructions::EthInstructions,
         },
+        inspector::InspectorHandler,
         interpreter::{InitialAndFloorGas, interpreter::EthInterpreter},
         primitives::{Address, TxKind, U256},
     },
@@ -207,18 +210,29 @@ pub fn evm_transact_with_native(
         .transfer_logs
         .add_tx_origin(caller.to_move_address(), value);
     let block_env = evm_native_ctx.block_env();
+    let tracer = evm_native_ctx.tracer.clone();
+    let transfer_logs = evm_native_ctx.transfer_logs;
     let db = &mut evm_native_ctx.db;
-    let mut evm = build_evm(db, block_env, caller, transact_to, value, data, gas_limit);
+    let context = build_context(db, block_env, caller, transact_to, value, data, gas_limit);
 
-    let mut handler = WrappedMainnetHandler {
-        inner: InnerMainnetHandler::default(),
-        transfer_logs: evm_native_ctx.transfer_logs,
-    };
-    let outcome = handler.run(&mut evm).map_err(evm_error)?;
+    // The inspector hooks are only run when the execution is traced
+    let outcome = match tracer {
+        None => WrappedMainnetHandler::new(transfer_logs).run(&mut context.build_mainnet()),
+        Some(tracer) => WrappedMainnetHandler::new(transfer_logs)
+            .inspect_run(&mut context.build_mainnet_with_inspector(tracer)),
+    }
+    .map_err(evm_error)?;
 
     // Capture changes in native context so that they can be
     // converted into Move changes when the session is finalized
     evm_native_ctx.state_changes.push(outcome.state.clone());
+    evm_native_ctx.failed_output = (!outcome.result.is_success()).then(|| {
+        outcome
+            .result
+            .output()
+            .map(|o| o.to_vec())
+            .unwrap_or_default()
+    });
 
     Ok(outcome)
 }
@@ -271,6 +285,18 @@ fn build_evm<DB: Database>(
     data: Vec<u8>,
     gas_limit: u64,
 ) -> revm::MainnetEvm<MainnetContext<DB>> {
+    build_context(db, block_env, caller, transact_to, value, data, gas_limit).build_mainnet()
+}
+
+fn build_context<DB: Database>(
+    db: DB,
+    block_env: BlockEnv,
+    caller: Address,
+    transact_to: TxKind,
+    value: U256,
+    data: Vec<u8>,
+    gas_limit: u64,
+) -> MainnetContext<DB> {
     Context::mainnet()
         .with_db(db)
         .with_tx(TxEnv {
@@ -304,7 +330,6 @@ fn build_evm<DB: Database>(
             // Nonce can be ignored because replay attacks are prevented by MoveVM.
             env.disable_nonce_check = true;
         })
-        .build_mainnet()
 }
 
 struct EvmCallArgs {
@@ -318,27 +343,37 @@ struct EvmCallArgs {
 type EvmDB<'a, 'b> = &'a mut CacheDB<ResolverBackedDB<'b>>;
 type EvmCtx<'a, 'b> =
     Context<BlockEnv, TxEnv, CfgEnv, EvmDB<'a, 'b>, Journal<EvmDB<'a, 'b>, JournalEntry>>;
-type InnerMainnetHandler<'a, 'b> = MainnetHandler<
-    Evm<EvmCtx<'a, 'b>, (), EthInstructions<EthInterpreter, EvmCtx<'a, 'b>>, EthPrecompiles>,
+type MainnetEvmWith<'a, 'b, INSP> =
+    Evm<EvmCtx<'a, 'b>, INSP, EthInstructions<EthInterpreter, EvmCtx<'a, 'b>>, EthPrecompiles>;
+type InnerMainnetHandler<'a, 'b, INSP> = MainnetHandler<
+    MainnetEvmWith<'a, 'b, INSP>,
     EVMError<DbError>,
-    EthFrame<
-        Evm<EvmCtx<'a, 'b>, (), EthInstructions<EthInterpreter, EvmCtx<'a, 'b>>, EthPrecompiles>,
-        EVMError<DbError>,
-        EthInterpreter,
-    >,
+    EthFrame<MainnetEvmWith<'a, 'b, INSP>, EVMError<DbError>, EthInterpreter>,
 >;
 
 /// Custom handler to allow extracting transfer events.
-struct WrappedMainnetHandler<'a, 'b> {
-    inner: InnerMainnetHandler<'a, 'b>,
+///
+/// The `INSP` inspector is only invoked when the handler runs through [`InspectorHandler`], which
+/// is `()` outside of tracing.
+struct WrappedMainnetHandler<'a, 'b, INSP = ()> {
+    inner: InnerMainnetHandler<'a, 'b, INSP>,
     transfer_logs: &'a dyn EthTransferLog,
 }
 
-impl<'a, 'b> Handler for WrappedMainnetHandler<'a, 'b> {
-    type Evm = <InnerMainnetHandler<'a, 'b> as Handler>::Evm;
-    type Error = <InnerMainnetHandler<'a, 'b> as Handler>::Error;
-    type Frame = <InnerMainnetHandler<'a, 'b> as Handler>::Frame;
-    type HaltReason = <InnerMainnetHandler<'a, 'b> as Handler>::HaltReason;
+impl<'a, INSP> WrappedMainnetHandler<'a, '_, INSP> {
+    fn new(transfer_logs: &'a dyn EthTransferLog) -> Self {
+        Self {
+            inner: InnerMainnetHandler::default(),
+            transfer_logs,
+        }
+    }
+}
+
+impl<'a, 'b, INSP> Handler for WrappedMainnetHandler<'a, 'b, INSP> {
+    type Evm = <InnerMainnetHandler<'a, 'b, INSP> as Handler>::Evm;
+    type Error = <InnerMainnetHandler<'a, 'b, INSP> as Handler>::Error;
+    type Frame = <InnerMainnetHandler<'a, 'b, INSP> as Handler>::Frame;
+    type HaltReason = <InnerMainnetHandler<'a, 'b, INSP> as Handler>::HaltReason;
 
     // Modify the post-execution handler to extract transfer events.
     fn post_execution(
@@ -348,27 +383,47 @@ impl<'a, 'b> Handler for WrappedMainnetHandler<'a, 'b> {
         init_and_floor_gas: InitialAndFloorGas,
         eip7702_gas_refund: i64,
     ) -> Result<ResultAndState<Self::HaltReason>, Self::Error> {
-        let transfers = evm.journaled_state.journal.iter().flat_map(|entries| {
-            entries.iter().filter_map(|entry| {
-                if let revm::JournalEntry::BalanceTransfer { from, to, balance } = entry {
-                    Some(EthTransfer {
+        for entry in evm.journaled_state.journal.iter().flatten() {
+            match entry {
+                revm::JournalEntry::BalanceTransfer { from, to, balance } => {
+                    self.transfer_logs.push_transfer(EthTransfer {
                         from: from.to_move_address(),
                         to: to.to_move_address(),
                         amount: *balance,
-                    })
-                } else {
-                    None
+                    });
+                }
+                // A self-destruct moves the balance to its target. If the target is the
+                // destroyed account itself, such as in the `Burn.eth` of the
+                // L2ToL1MessagePasser, the balance is burned.
+                revm::JournalEntry::AccountDestroyed {
+                    address,
+                    target,
+                    had_balance,
+                    ..
+                } if !had_balance.is_zero() => {
+                    if address == target {
+                        self.transfer_logs
+                            .push_burn(address.to_move_address(), *had_balance);
+                    } else {
+                        self.transfer_logs.push_transfer(EthTransfer {
+                            from: address.to_move_address(),
+                            to: target.to_move_address(),
+                            amount: *had_balance,
+                        });
+                    }
                 }
-            })
-        });
-        for t in transfers {
-            self.transfer_logs.push_transfer(t);
+                _ => (),
+            }
         }
         self.inner
             .post_execution(evm, exec_result, init_and_floor_gas, eip7702_gas_refund)
     }
 }
 
+impl InspectorHandler for WrappedMainnetHandler<'_, '_, EvmCallTracer> {
+    type IT = EthInterpreter;
+}
+
 struct EvmGasUsed {
     amount: u64,
 }
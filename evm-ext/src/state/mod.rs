@@ -1,5 +1,6 @@
 mod account;
 mod block_hash;
+mod overlay;
 mod storage;
 
-pub use {account::*, block_hash::BlockHashLookup, storage::*};
+pub use {account::*, block_hash::BlockHashLookup, overlay::StorageTriesOverlay, storage::*};
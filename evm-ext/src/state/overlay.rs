@@ -0,0 +1,133 @@
+use {
+    super::{
+        BoxedTrieDb, DbWithRoot, Error, Result, StorageTrie, StorageTrieRepository,
+        StorageTriesChanges,
+    },
+    crate::native_evm_context::ResolverBackedDB,
+    alloy::consensus::EMPTY_ROOT_HASH,
+    eth_trie::{DB, TrieError},
+    move_vm_types::resolver::MoveResolver,
+    moved_shared::primitives::{Address, B256},
+    moved_trie::StagingEthTrieDb,
+    std::{result, sync::Arc},
+};
+
+/// Reads the storage tries as they are in the `state`, which may be that of a past block, after
+/// applying the `changes` made on top of it.
+///
+/// The `inner` repository opens the trie of an account at the latest root it has written, so the
+/// root comes from the `changes` of the account instead, or from its EVM account info in the
+/// `state` if it has none. The nodes of the `changes` are only kept by the tries opened here.
+pub struct StorageTriesOverlay<'a, S, R> {
+    inner: &'a S,
+    state: &'a R,
+    changes: &'a StorageTriesChanges,
+}
+
+impl<'a, S, R> StorageTriesOverlay<'a, S, R> {
+    pub fn new(inner: &'a S, state: &'a R, changes: &'a StorageTriesChanges) -> Self {
+        Self {
+            inner,
+            state,
+            changes,
+        }
+    }
+}
+
+impl<S: StorageTrieRepository, R: MoveResolver> StorageTrieRepository
+    for StorageTriesOverlay<'_, S, R>
+{
+    fn for_account(&self, account: &Address) -> Result<StorageTrie> {
+        let root = match self.changes.tries.get(account) {
+            Some(changes) => changes.root,
+            None => ResolverBackedDB::new(self.inner, self.state, &(), 0)
+                .get_account(account)
+                .map_err(|e| Error::EthTrie(TrieError::DB(format!("{e:?}"))))?
+                .map_or(EMPTY_ROOT_HASH, |account| account.inner.storage_root),
+        };
+
+        self.for_account_with_root(account, &root)
+    }
+
+    fn for_account_with_root(&self, account: &Address, storage_root: &B256) -> Result<StorageTrie> {
+        let staging = StagingEthTrieDb::new(BoxedTrieDb::new(SharedTrieDb(
+            self.inner.for_account(account)?.0.db,
+        )));
+        if let Some(changes) = self.changes.tries.get(account) {
+            let (keys, values) = changes
+                .trie_diff
+                .iter()
+                .map(|(hash, node)| (hash.to_vec(), node.clone()))
+                .unzip();
+            staging.insert_batch(keys, values)?;
+        }
+        let db = Arc::new(staging);
+
+        Ok(if *storage_root == EMPTY_ROOT_HASH {
+            StorageTrie::new(db)
+        } else {
+            StorageTrie::from(db, *storage_root)?
+        })
+    }
+
+    fn trie_node(&self, account: &Address, hash: B256) -> Result<Option<Vec<u8>>> {
+        match self
+            .changes
+            .tries
+            .get(account)
+            .and_then(|changes| changes.trie_diff.get(&hash))
+        {
+            Some(node) => Ok(Some(node.clone())),
+            None => self.inner.trie_node(account, hash),
+        }
+    }
+
+    fn accounts(&self) -> Result<Vec<Address>> {
+        let mut accounts = self.inner.accounts()?;
+        for account in self.changes.tries.keys() {
+            if !accounts.contains(account) {
+                accounts.push(*account);
+            }
+        }
+
+        Ok(accounts)
+    }
+
+    /// The overlay is only ever read, so that the `inner` repository is left as is.
+    fn apply(&self, _: StorageTriesChanges) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Shares the DB of a trie opened by another repository.
+struct SharedTrieDb(Arc<StagingEthTrieDb<BoxedTrieDb>>);
+
+impl DB for SharedTrieDb {
+    type Error = Error;
+
+    fn get(&self, key: &[u8]) -> result::Result<Option<Vec<u8>>, Self::Error> {
+        self.0.get(key)
+    }
+
+    fn insert(&self, key: &[u8], value: Vec<u8>) -> result::Result<(), Self::Error> {
+        self.0.insert(key, value)
+    }
+
+    fn remove(&self, key: &[u8]) -> result::Result<(), Self::Error> {
+        self.0.remove(key)
+    }
+
+    fn flush(&self) -> result::Result<(), Self::Error> {
+        self.0.flush()
+    }
+}
+
+impl DbWithRoot for SharedTrieDb {
+    fn root(&self) -> result::Result<Option<B256>, Self::Error> {
+        self.0.root()
+    }
+
+    fn put_root(&self, root: B256) -> result::Result<(), Self::Error> {
+        self.0.put_root(root)
+    }
+}
@@ -3,7 +3,7 @@ use {
     auto_impl::auto_impl,
     eth_trie::{DB, EthTrie, MemDBError, MemoryDB, RootWithTrieDiff, Trie, TrieError},
     moved_shared::primitives::{Address, B256, U256},
-    moved_trie::StagingEthTrieDb,
+    moved_trie::{CachedEthTrieDb, StagingEthTrieDb},
     std::{
         collections::HashMap,
         fmt::Debug,
@@ -36,6 +36,9 @@ pub struct StorageTrie(pub EthTrie<StagingEthTrieDb<BoxedTrieDb>>);
 #[auto_impl(Box)]
 pub trait StorageTrieDb {
     fn db(&self, account: Address) -> Arc<StagingEthTrieDb<BoxedTrieDb>>;
+
+    /// Lists every account that has a storage trie.
+    fn accounts(&self) -> Result<Vec<Address>>;
 }
 
 pub trait StorageTrieRepository {
@@ -43,6 +46,12 @@ pub trait StorageTrieRepository {
 
     fn for_account_with_root(&self, account: &Address, storage_root: &B256) -> Result<StorageTrie>;
 
+    /// Reads the encoded node identified by its `hash` from the storage trie of `account`.
+    fn trie_node(&self, account: &Address, hash: B256) -> Result<Option<Vec<u8>>>;
+
+    /// Lists every account that has a storage trie.
+    fn accounts(&self) -> Result<Vec<Address>>;
+
     // TODO: move this out of repository
     fn apply(&self, changes: StorageTriesChanges) -> Result<()>;
 }
@@ -64,6 +73,14 @@ impl<T: StorageTrieDb> StorageTrieRepository for T {
         Ok(StorageTrie::from(db, *storage_root)?)
     }
 
+    fn trie_node(&self, account: &Address, hash: B256) -> Result<Option<Vec<u8>>> {
+        self.db(*account).get(hash.as_slice())
+    }
+
+    fn accounts(&self) -> Result<Vec<Address>> {
+        StorageTrieDb::accounts(self)
+    }
+
     fn apply(&self, changes: StorageTriesChanges) -> Result<()> {
         for (account, changes) in changes {
             self.for_account(&account)?.apply(changes)?;
@@ -159,6 +176,8 @@ impl StorageTriesChanges {
 pub struct StorageTrieChanges {
     pub root: B256,
     pub trie_diff: HashMap<B256, Vec<u8>>,
+    /// Indices of the slots written, whose hashes are the keys of the trie.
+    pub slots: Vec<U256>,
 }
 
 impl Add for StorageTrieChanges {
@@ -167,6 +186,7 @@ impl Add for StorageTrieChanges {
     fn add(mut self, rhs: Self) -> Self::Output {
         self.root = rhs.root;
         self.trie_diff.extend(rhs.trie_diff);
+        self.slots.extend(rhs.slots);
         self
     }
 }
@@ -176,6 +196,7 @@ impl From<RootWithTrieDiff> for StorageTrieChanges {
         Self {
             root: value.root,
             trie_diff: value.trie_diff.into_iter().collect(),
+            slots: Vec::new(),
         }
     }
 }
@@ -200,6 +221,30 @@ impl StorageTrie {
         Ok(self.0.get_proof(key)?)
     }
 
+    /// Returns the value of every slot in `indices` along with its proof.
+    ///
+    /// The trie nodes are read through a cache shared by all slots, so the common upper levels
+    /// of the trie are only fetched once for the whole batch.
+    pub fn proofs(&mut self, indices: &[U256]) -> Result<Vec<SlotProof>> {
+        let root = self.0.root_hash()?;
+        let db = Arc::new(CachedEthTrieDb::new(self.0.db.clone()));
+        let mut trie = EthTrie::from(db, root)?;
+
+        indices
+            .iter()
+            .map(|index| {
+                let trie_key = keccak256::<[u8; 32]>(index.to_be_bytes());
+                let proof = trie.get_proof(trie_key.as_slice())?;
+                let value = match trie.get(trie_key.as_slice())? {
+                    Some(bytes) => rlp::decode_exact(&bytes)?,
+                    None => U256::ZERO,
+                };
+
+                Ok(SlotProof { value, proof })
+            })
+            .collect()
+    }
+
     pub fn get(&self, index: &U256) -> Result<Option<U256>> {
         let trie_key = keccak256::<[u8; 32]>(index.to_be_bytes());
         let Some(bytes) = self.0.get(trie_key.as_slice())? else {
@@ -246,6 +291,13 @@ impl StorageTrie {
     }
 }
 
+/// Value of a storage slot with the trie nodes proving it, ordered from the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotProof {
+    pub value: U256,
+    pub proof: Vec<Vec<u8>>,
+}
+
 pub struct InMemoryDb {
     root: RwLock<Option<B256>>,
     db: MemoryDB,
@@ -317,6 +369,10 @@ impl StorageTrieDb for InMemoryStorageTrieRepository {
             .or_insert_with(Self::create)
             .clone()
     }
+
+    fn accounts(&self) -> Result<Vec<Address>> {
+        Ok(self.accounts.read().unwrap().keys().copied().collect())
+    }
 }
 
 pub struct EthTrieDbWithLocalError<T>(pub T);
@@ -424,5 +480,40 @@ mod test_doubles {
         fn db(&self, _: Address) -> Arc<StagingEthTrieDb<BoxedTrieDb>> {
             Arc::new(StagingEthTrieDb::new(BoxedTrieDb::new(NoopEthTrieDb)))
         }
+
+        fn accounts(&self) -> Result<Vec<Address>> {
+            Ok(Vec::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batched_proofs_match_individual_proofs() {
+        let repository = InMemoryStorageTrieRepository::new();
+        let account = Address::repeat_byte(0x11);
+        let mut trie = repository.for_account(&account).unwrap();
+        for index in 0..64u64 {
+            trie.insert(&U256::from(index), &U256::from(index + 1))
+                .unwrap();
+        }
+        let changes = trie.commit().unwrap();
+        let root = changes.root;
+        repository
+            .apply(StorageTriesChanges::empty().with_trie_changes(account, changes))
+            .unwrap();
+
+        let mut trie = repository.for_account_with_root(&account, &root).unwrap();
+        let indices = (0..80u64).map(U256::from).collect::<Vec<_>>();
+        let batched = trie.proofs(&indices).unwrap();
+
+        for (index, slot) in indices.iter().zip(batched) {
+            let trie_key = keccak256::<[u8; 32]>(index.to_be_bytes());
+            assert_eq!(slot.proof, trie.proof(trie_key.as_slice()).unwrap());
+            assert_eq!(slot.value, trie.get(index).unwrap().unwrap_or_default());
+        }
     }
 }
@@ -16,6 +16,7 @@ use {
         primitives::{Address, KECCAK_EMPTY, U256},
         state::{Account, AccountInfo, AccountStatus, EvmStorageSlot},
     },
+    std::collections::HashMap,
 };
 
 #[derive(Debug, Clone)]
@@ -30,6 +31,51 @@ impl Changes {
     }
 }
 
+/// EVM storage slots that a transaction writes, for pricing the state it grows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageSlotWrites {
+    /// Slots that are empty before the transaction and hold a value after it.
+    pub created: u64,
+    /// Slots that hold a value before the transaction and a different one after it.
+    pub modified: u64,
+}
+
+/// Counts the EVM storage slots written within the session of `extensions`.
+///
+/// Each slot is compared between its value before the first EVM call of the session and its value
+/// after the last one. Clearing a slot is not counted, same as deleting a resource.
+pub fn storage_slot_writes(extensions: &NativeContextExtensions) -> StorageSlotWrites {
+    let evm_native_ctx = extensions.get::<NativeEVMContext>();
+    let mut slots = HashMap::<(Address, U256), (U256, U256)>::new();
+    for state in &evm_native_ctx.state_changes {
+        for (address, account) in state {
+            // Same as in `extract_evm_changes_from_native`, as these are not persisted
+            if !account.is_touched() || account.is_selfdestructed() {
+                continue;
+            }
+            for (index, slot) in account.changed_storage_slots() {
+                slots
+                    .entry((*address, *index))
+                    .and_modify(|(_, present)| *present = slot.present_value)
+                    .or_insert((slot.original_value, slot.present_value));
+            }
+        }
+    }
+
+    let mut writes = StorageSlotWrites::default();
+    for (original, present) in slots.into_values() {
+        if present.is_zero() || present == original {
+            continue;
+        }
+        if original.is_zero() {
+            writes.created += 1;
+        } else {
+            writes.modified += 1;
+        }
+    }
+    writes
+}
+
 pub fn genesis_state_changes(
     genesis: alloy::genesis::Genesis,
     resolver: &impl MoveResolver,
@@ -173,10 +219,15 @@ fn add_account_changes(
     };
 
     let mut storage = storage_trie.for_account(address).unwrap();
+    let mut slots = Vec::new();
     for (index, value) in account.changed_storage_slots() {
         storage.insert(index, &value.present_value).unwrap();
+        slots.push(*index);
     }
-    let storage_changes = storage.commit().unwrap();
+    let storage_changes = StorageTrieChanges {
+        slots,
+        ..storage.commit().unwrap()
+    };
 
     // Push AccountInfo resource
     let struct_tag = account_info_struct_tag(address);
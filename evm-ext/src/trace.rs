@@ -0,0 +1,221 @@
+use {
+    revm::{
+        context_interface::CreateScheme,
+        inspector::Inspector,
+        interpreter::{
+            CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, InstructionResult,
+            interpreter::EthInterpreter,
+        },
+        primitives::{Address, Bytes, U256},
+    },
+    std::{cell::RefCell, rc::Rc},
+};
+
+/// How an [`EvmCallFrame`] was entered, named after the opcode as in the `callTracer` of geth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvmCallKind {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+    Create,
+    Create2,
+}
+
+impl EvmCallKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Call => "CALL",
+            Self::CallCode => "CALLCODE",
+            Self::DelegateCall => "DELEGATECALL",
+            Self::StaticCall => "STATICCALL",
+            Self::Create => "CREATE",
+            Self::Create2 => "CREATE2",
+        }
+    }
+}
+
+/// A call or contract creation made in the EVM, along with the ones it made in turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmCallFrame {
+    pub kind: EvmCallKind,
+    pub from: Address,
+    /// The called account, or the created one if the creation succeeded.
+    pub to: Option<Address>,
+    pub value: U256,
+    pub gas: u64,
+    pub gas_used: u64,
+    pub input: Bytes,
+    pub output: Bytes,
+    /// Why the frame failed, if it did.
+    pub error: Option<String>,
+    pub calls: Vec<EvmCallFrame>,
+}
+
+/// Records the call frames of the EVM executions it inspects.
+///
+/// The clones of a tracer share their frames, so that the frames recorded by the tracer handed to
+/// an EVM execution can be read from the one kept in the [`NativeEVMContext`].
+///
+/// [`NativeEVMContext`]: crate::NativeEVMContext
+#[derive(Debug, Clone, Default)]
+pub struct EvmCallTracer {
+    frames: Rc<RefCell<TracedFrames>>,
+}
+
+#[derive(Debug, Default)]
+struct TracedFrames {
+    /// Frames entered and not exited yet, innermost last.
+    open: Vec<EvmCallFrame>,
+    /// Outermost frames of the finished executions.
+    finished: Vec<EvmCallFrame>,
+}
+
+impl EvmCallTracer {
+    /// Takes the outermost frames of the executions finished since the last time they were taken.
+    pub fn take_frames(&self) -> Vec<EvmCallFrame> {
+        std::mem::take(&mut self.frames.borrow_mut().finished)
+    }
+
+    fn enter(&self, frame: EvmCallFrame) {
+        self.frames.borrow_mut().open.push(frame);
+    }
+
+    fn exit(&self, result: InstructionResult, gas_used: u64, output: &Bytes, to: Option<Address>) {
+        let mut frames = self.frames.borrow_mut();
+        let Some(mut frame) = frames.open.pop() else {
+            return;
+        };
+
+        frame.gas_used = gas_used;
+        frame.output = output.clone();
+        frame.error = if result.is_ok() {
+            None
+        } else if result.is_revert() {
+            Some("execution reverted".to_owned())
+        } else {
+            Some(format!("{result:?}"))
+        };
+        if frame.to.is_none() {
+            frame.to = to;
+        }
+
+        match frames.open.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => frames.finished.push(frame),
+        }
+    }
+}
+
+impl<CTX> Inspector<CTX, EthInterpreter> for EvmCallTracer {
+    fn call(&mut self, _context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let kind = match inputs.scheme {
+            CallScheme::Call | CallScheme::ExtCall => EvmCallKind::Call,
+            CallScheme::CallCode => EvmCallKind::CallCode,
+            CallScheme::DelegateCall | CallScheme::ExtDelegateCall => EvmCallKind::DelegateCall,
+            CallScheme::StaticCall | CallScheme::ExtStaticCall => EvmCallKind::StaticCall,
+        };
+        self.enter(EvmCallFrame {
+            kind,
+            from: inputs.caller,
+            to: Some(inputs.target_address),
+            value: inputs.call_value(),
+            gas: inputs.gas_limit,
+            gas_used: 0,
+            input: inputs.input.clone(),
+            output: Bytes::new(),
+            error: None,
+            calls: Vec::new(),
+        });
+
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.exit(
+            *outcome.instruction_result(),
+            outcome.gas().spent(),
+            outcome.output(),
+            None,
+        );
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        let kind = match inputs.scheme {
+            CreateScheme::Create => EvmCallKind::Create,
+            CreateScheme::Create2 { .. } => EvmCallKind::Create2,
+        };
+        self.enter(EvmCallFrame {
+            kind,
+            from: inputs.caller,
+            to: None,
+            value: inputs.value,
+            gas: inputs.gas_limit,
+            gas_used: 0,
+            input: inputs.init_code.clone(),
+            output: Bytes::new(),
+            error: None,
+            calls: Vec::new(),
+        });
+
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.exit(
+            *outcome.instruction_result(),
+            outcome.gas().spent(),
+            outcome.output(),
+            outcome.address,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(kind: EvmCallKind) -> EvmCallFrame {
+        EvmCallFrame {
+            kind,
+            from: Address::ZERO,
+            to: None,
+            value: U256::ZERO,
+            gas: 100,
+            gas_used: 0,
+            input: Bytes::new(),
+            output: Bytes::new(),
+            error: None,
+            calls: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_inner_frames_are_nested_in_outer_frame() {
+        let tracer = EvmCallTracer::default();
+        let created = Address::repeat_byte(1);
+
+        tracer.enter(frame(EvmCallKind::Call));
+        tracer.enter(frame(EvmCallKind::Create));
+        tracer.exit(InstructionResult::Return, 10, &Bytes::new(), Some(created));
+        tracer.enter(frame(EvmCallKind::StaticCall));
+        tracer.exit(InstructionResult::Revert, 5, &Bytes::new(), None);
+        tracer.exit(InstructionResult::Stop, 40, &Bytes::new(), None);
+
+        let frames = tracer.take_frames();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].gas_used, 40);
+        assert_eq!(frames[0].calls.len(), 2);
+        assert_eq!(frames[0].calls[0].to, Some(created));
+        assert_eq!(
+            frames[0].calls[1].error.as_deref(),
+            Some("execution reverted")
+        );
+        assert!(tracer.take_frames().is_empty());
+    }
+}
@@ -18,6 +18,7 @@ use {
 };
 
 pub const ACCOUNT_INFO_PREFIX: &str = "Account_";
+pub const CODE_HASH_PREFIX: &str = "CodeHash_";
 
 pub fn account_info_struct_tag(address: &Address) -> StructTag {
     let name = format!("{ACCOUNT_INFO_PREFIX}{}", address.encode_hex());
@@ -31,7 +32,7 @@ pub fn account_info_struct_tag(address: &Address) -> StructTag {
 }
 
 pub fn code_hash_struct_tag(code_hash: &B256) -> StructTag {
-    let name = format!("CodeHash_{}", code_hash.encode_hex());
+    let name = format!("{CODE_HASH_PREFIX}{}", code_hash.encode_hex());
     let name = Identifier::new(name).expect("Code hash name is valid");
     StructTag {
         address: EVM_NATIVE_ADDRESS,
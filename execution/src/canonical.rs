@@ -1,15 +1,17 @@
 use {
     super::{L2GasFee, L2GasFeeInput},
     crate::{
-        CanonicalExecutionInput, Logs, create_vm_session,
+        CanonicalExecutionInput, Events, create_vm_session,
         eth_token::{self, BaseTokenAccounts, TransferArgs},
         execute::{
             deploy_evm_contract, deploy_module, execute_entry_function, execute_evm_contract,
             execute_script,
         },
-        gas::{new_gas_meter, total_gas_used},
+        gas::{calldata_floor_gas, new_gas_meter, storage_write_gas, total_gas_used},
         nonces::check_nonce,
+        overlay::ChangesOverlay,
         session_id::SessionId,
+        trace::{MoveCall, Tracer},
         transaction::{
             Changes, NormalizedEthTransaction, ScriptOrDeployment, TransactionData,
             TransactionExecutionOutcome,
@@ -17,11 +19,13 @@ use {
     },
     alloy::primitives::U256,
     aptos_gas_meter::{AptosGasMeter, StandardGasAlgebra, StandardGasMeter},
-    aptos_table_natives::TableResolver,
-    move_core_types::{effects::ChangeSet, language_storage::ModuleId},
+    aptos_table_natives::{NativeTableContext, TableResolver},
+    move_binary_format::errors::{Location, PartialVMError},
+    move_core_types::{effects::ChangeSet, language_storage::ModuleId, vm_status::StatusCode},
     move_vm_runtime::{
-        AsUnsyncCodeStorage, ModuleStorage,
+        AsFunctionValueExtension, AsUnsyncCodeStorage, ModuleStorage,
         module_traversal::{TraversalContext, TraversalStorage},
+        move_vm::MoveVM,
         session::Session,
     },
     move_vm_types::{gas::UnmeteredGasMeter, resolver::MoveResolver},
@@ -29,6 +33,7 @@ use {
         EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE,
         events::EthTransfersLogger,
         state::{BlockHashLookup, StorageTrieRepository},
+        storage_slot_writes,
     },
     moved_genesis::{CreateMoveVm, MovedVm, config::GenesisConfig},
     moved_shared::{
@@ -47,6 +52,7 @@ pub struct CanonicalVerificationInput<'input, 'r, 'l, B, MS> {
     pub traversal_context: &'input mut TraversalContext<'input>,
     pub gas_meter: &'input mut StandardGasMeter<StandardGasAlgebra>,
     pub genesis_config: &'input GenesisConfig,
+    pub block_timestamp: u64,
     pub l1_cost: U256,
     pub l2_cost: U256,
     pub base_token: &'input B,
@@ -62,6 +68,25 @@ pub(super) fn verify_transaction<B: BaseTokenAccounts, MS: ModuleStorage>(
         }
     }
 
+    if let Some(max) = input.genesis_config.max_transaction_gas_limit {
+        let given = input.tx.gas_limit();
+        if given > max {
+            return Err(InvalidTransactionCause::GasLimitTooHigh { max, given }.into());
+        }
+    }
+
+    // The gas limit must cover the calldata floor, that is charged even if execution uses less
+    if input
+        .genesis_config
+        .hardforks
+        .is_isthmus_active(input.block_timestamp)
+        && input.tx.gas_limit() < calldata_floor_gas(&input.tx.data)
+    {
+        return Err(InvalidTransaction(
+            InvalidTransactionCause::InsufficientIntrinsicGas,
+        ));
+    }
+
     let sender_move_address = input.tx.signer.to_move_address();
 
     // Charge gas for the transaction itself.
@@ -125,10 +150,18 @@ pub(super) fn execute_canonical_transaction<
     H: BlockHashLookup,
 >(
     input: CanonicalExecutionInput<S, ST, F, B, H>,
+    mut tracer: Option<&mut Tracer>,
 ) -> moved_shared::error::Result<TransactionExecutionOutcome> {
     let sender_move_address = input.tx.signer.to_move_address();
 
-    let tx_data = TransactionData::parse_from(input.tx)?;
+    let tx_data = TransactionData::parse_from(
+        input.tx,
+        input
+            .genesis_config
+            .move_vm
+            .is_versioned_tx_data_active(input.block_header.timestamp),
+    )?;
+    let traced_call = tracer.as_ref().and_then(|_| MoveCall::canonical(&tx_data));
 
     let moved_vm = MovedVm::new(input.genesis_config);
     let module_bytes_storage: ResolverBasedModuleBytesStorage<'_, S> =
@@ -140,7 +173,7 @@ pub(super) fn execute_canonical_transaction<
         tx_data.maybe_entry_fn(),
         input.tx_hash,
         input.genesis_config,
-        input.block_header,
+        input.block_header.clone(),
         tx_data.script_hash(),
     );
     let eth_transfers_logger = EthTransfersLogger::default();
@@ -152,6 +185,9 @@ pub(super) fn execute_canonical_transaction<
         &eth_transfers_logger,
         input.block_hash_lookup,
     );
+    if let Some(tracer) = &tracer {
+        tracer.attach(&mut session);
+    }
     let traversal_storage = TraversalStorage::new();
     let mut traversal_context = TraversalContext::new(&traversal_storage);
 
@@ -169,12 +205,14 @@ pub(super) fn execute_canonical_transaction<
         traversal_context: &mut traversal_context,
         gas_meter: &mut gas_meter,
         genesis_config: input.genesis_config,
+        block_timestamp: input.block_header.timestamp,
         l1_cost: input.l1_cost,
         l2_cost,
         base_token: input.base_token,
         module_storage: &code_storage,
     };
     verify_transaction(&mut verify_input)?;
+    let verified_gas = total_gas_used(verify_input.gas_meter, input.genesis_config);
 
     let vm_outcome = match tx_data {
         TransactionData::EntryFunction(entry_fn) => execute_entry_function(
@@ -267,6 +305,13 @@ pub(super) fn execute_canonical_transaction<
         .map(|_| ()),
     };
 
+    if let (Some(tracer), Some(call)) = (tracer.as_deref_mut(), traced_call) {
+        let gas_used = total_gas_used(verify_input.gas_meter, input.genesis_config)
+            .saturating_sub(verified_gas);
+        let error = vm_outcome.as_ref().err().map(ToString::to_string);
+        tracer.record(call, gas_used, error);
+    }
+
     let vm_outcome = vm_outcome.and_then(|_| {
         // Ensure any base token balance changes in EVM are reflected in Move too
         eth_token::replicate_transfers(
@@ -278,18 +323,61 @@ pub(super) fn execute_canonical_transaction<
         )
     });
 
-    let gas_used = total_gas_used(verify_input.gas_meter, input.genesis_config);
+    let metered_gas = total_gas_used(verify_input.gas_meter, input.genesis_config);
+    let (mut changes, mut extensions) = session.finish_with_extensions(&code_storage)?;
+    let (mut logs, mut events) =
+        extensions.events(input.event_layouts, &vm, input.state, &code_storage);
+
+    // Writes are only known once the session is finished, so they are charged on top of the gas
+    // metered while executing. The transaction runs out of gas if they cost more than is left.
+    let table_changes = extensions
+        .remove::<NativeTableContext>()
+        .into_change_set(&code_storage.as_function_value_extension())?;
+    let storage_gas = storage_write_gas(
+        &changes,
+        &table_changes,
+        storage_slot_writes(&extensions),
+        &input.genesis_config.gas_costs.storage_write,
+    );
+    let mut gas_used = metered_gas.saturating_add(storage_gas);
+    if gas_used > input.l2_input.gas_limit {
+        drop(extensions);
+        return execute_out_of_gas_transaction(&input, &vm, &code_storage, l2_cost);
+    }
+    if input
+        .genesis_config
+        .hardforks
+        .is_isthmus_active(input.block_header.timestamp)
+    {
+        gas_used = gas_used.max(calldata_floor_gas(&input.tx.data));
+    }
     let used_l2_input = L2GasFeeInput::new(gas_used, input.l2_input.effective_gas_price);
     let used_l2_cost = input.l2_fee.l2_fee(used_l2_input);
 
+    // The refund depends on the writes, so it runs in a session of its own on top of them
+    let overlay = ChangesOverlay::new(input.state, &changes).with_table_changes(&table_changes);
+    let mut refund_session = create_vm_session(
+        &vm,
+        &overlay,
+        SessionId::new_from_refund(
+            input.tx_hash,
+            input.genesis_config,
+            input.block_header.clone(),
+        ),
+        input.storage_trie,
+        &(),
+        input.block_hash_lookup,
+    );
+    let mut refund_traversal_context = TraversalContext::new(&traversal_storage);
+
     // Refunds should not be metered as they're supposed to always succeed
     input
         .base_token
         .refund_gas_cost(
             &sender_move_address,
             l2_cost.saturating_sub(used_l2_cost),
-            verify_input.session,
-            verify_input.traversal_context,
+            &mut refund_session,
+            &mut refund_traversal_context,
             &code_storage,
         )
         .map_err(|_| {
@@ -298,8 +386,15 @@ pub(super) fn execute_canonical_transaction<
             ))
         })?;
 
-    let (mut changes, mut extensions) = session.finish_with_extensions(&code_storage)?;
-    let logs = extensions.logs();
+    let (refund_changes, mut refund_extensions) =
+        refund_session.finish_with_extensions(&code_storage)?;
+    let (refund_logs, refund_events) =
+        refund_extensions.events(input.event_layouts, &vm, input.state, &code_storage);
+    logs.extend(refund_logs);
+    events.extend(refund_events);
+    changes
+        .squash(refund_changes)
+        .expect("Refund changes must merge with other session changes");
     let evm_changes = moved_evm_ext::extract_evm_changes(&extensions);
     changes
         .squash(evm_changes.accounts)
@@ -307,7 +402,14 @@ pub(super) fn execute_canonical_transaction<
     changes
         .squash(deploy_changes)
         .expect("Module deploy changes must merge with other session changes");
-    let changes = Changes::new(changes, evm_changes.storage);
+    let mut changes = Changes::new(changes, evm_changes.storage);
+    if input
+        .genesis_config
+        .move_vm
+        .is_table_state_active(input.block_header.timestamp)
+    {
+        changes = changes.with_tables(table_changes);
+    }
 
     match vm_outcome {
         Ok(_) => Ok(TransactionExecutionOutcome::new(
@@ -316,6 +418,7 @@ pub(super) fn execute_canonical_transaction<
             gas_used,
             input.l2_input.effective_gas_price,
             logs,
+            events,
             deployment,
         )),
         // User error still generates a receipt and consumes gas
@@ -325,8 +428,79 @@ pub(super) fn execute_canonical_transaction<
             gas_used,
             input.l2_input.effective_gas_price,
             logs,
+            events,
             None,
         )),
         Err(e) => Err(e),
     }
 }
+
+/// Discards all effects of a transaction whose storage writes cost more gas than it has left,
+/// except for the ones of its verification: the fees are charged and the nonce is incremented. The
+/// transaction uses up its entire gas limit, so none of the charged `l2_cost` is refunded.
+fn execute_out_of_gas_transaction<
+    S: MoveResolver + TableResolver,
+    ST: StorageTrieRepository,
+    F: L2GasFee,
+    B: BaseTokenAccounts,
+    H: BlockHashLookup,
+>(
+    input: &CanonicalExecutionInput<S, ST, F, B, H>,
+    vm: &MoveVM,
+    code_storage: &impl ModuleStorage,
+    l2_cost: U256,
+) -> moved_shared::error::Result<TransactionExecutionOutcome> {
+    let tx_data = TransactionData::parse_from(
+        input.tx,
+        input
+            .genesis_config
+            .move_vm
+            .is_versioned_tx_data_active(input.block_header.timestamp),
+    )?;
+    let session_id = SessionId::new_from_canonical(
+        input.tx,
+        tx_data.maybe_entry_fn(),
+        input.tx_hash,
+        input.genesis_config,
+        input.block_header.clone(),
+        tx_data.script_hash(),
+    );
+    let mut session = create_vm_session(
+        vm,
+        input.state,
+        session_id,
+        input.storage_trie,
+        &(),
+        input.block_hash_lookup,
+    );
+    let traversal_storage = TraversalStorage::new();
+    let mut traversal_context = TraversalContext::new(&traversal_storage);
+    let mut gas_meter = new_gas_meter(input.genesis_config, input.l2_input.gas_limit);
+
+    verify_transaction(&mut CanonicalVerificationInput {
+        tx: input.tx,
+        session: &mut session,
+        traversal_context: &mut traversal_context,
+        gas_meter: &mut gas_meter,
+        genesis_config: input.genesis_config,
+        block_timestamp: input.block_header.timestamp,
+        l1_cost: input.l1_cost,
+        l2_cost,
+        base_token: input.base_token,
+        module_storage: code_storage,
+    })?;
+
+    let (changes, mut extensions) = session.finish_with_extensions(code_storage)?;
+    let (logs, events) = extensions.events(input.event_layouts, vm, input.state, code_storage);
+    let error = PartialVMError::new(StatusCode::OUT_OF_GAS).finish(Location::Undefined);
+
+    Ok(TransactionExecutionOutcome::new(
+        Err(error.into()),
+        changes.into(),
+        input.l2_input.gas_limit,
+        input.l2_input.effective_gas_price,
+        logs,
+        events,
+        None,
+    ))
+}
@@ -1,18 +1,25 @@
 use {
     crate::{
-        ADDRESS_LAYOUT, DepositExecutionInput, Logs, U256_LAYOUT, create_vm_session, eth_token,
-        gas::{new_gas_meter, total_gas_used},
+        ADDRESS_LAYOUT, DepositExecutionInput, Events, U256_LAYOUT, create_vm_session, eth_token,
+        execute::deploy_evm_contract,
+        gas::{intrinsic_gas, new_gas_meter, total_gas_used},
+        nonces::{create_account, increment_nonce},
         session_id::SessionId,
+        trace::{MoveCall, Tracer},
         transaction::{Changes, TransactionExecutionOutcome},
     },
-    alloy::primitives::U256,
+    alloy::primitives::{TxKind, U256},
     aptos_table_natives::TableResolver,
     move_core_types::language_storage::ModuleId,
     move_vm_runtime::{
-        AsUnsyncCodeStorage,
+        AsUnsyncCodeStorage, ModuleStorage,
         module_traversal::{TraversalContext, TraversalStorage},
+        move_vm::MoveVM,
+    },
+    move_vm_types::{
+        gas::UnmeteredGasMeter, resolver::MoveResolver, value_serde::ValueSerDeContext,
+        values::Value,
     },
-    move_vm_types::{resolver::MoveResolver, value_serde::ValueSerDeContext, values::Value},
     moved_evm_ext::{
         self, CODE_LAYOUT, EVM_DEPOSIT_FN_NAME, EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE,
         events::EthTransfersLogger,
@@ -21,7 +28,7 @@ use {
     },
     moved_genesis::{CreateMoveVm, MovedVm},
     moved_shared::{
-        error::{Error, UserError},
+        error::{Error, InvalidDepositCause, UserError},
         primitives::{ToMoveAddress, ToMoveU256},
     },
     moved_state::ResolverBasedModuleBytesStorage,
@@ -33,16 +40,21 @@ pub(super) fn execute_deposited_transaction<
     H: BlockHashLookup,
 >(
     input: DepositExecutionInput<S, ST, H>,
+    tracer: Option<&mut Tracer>,
 ) -> moved_shared::error::Result<TransactionExecutionOutcome> {
     let moved_vm = MovedVm::new(input.genesis_config);
     let module_bytes_storage = ResolverBasedModuleBytesStorage::new(input.state);
     let code_storage = module_bytes_storage.as_unsync_code_storage(&moved_vm);
     let vm = moved_vm.create_move_vm()?;
+    if let Err(cause) = validate_deposit(&input) {
+        let error = UserError::InvalidDeposit(cause);
+        return execute_failed_deposit(&input, &vm, &code_storage, error);
+    }
     let session_id = SessionId::new_from_deposited(
         input.tx,
         input.tx_hash,
         input.genesis_config,
-        input.block_header,
+        input.block_header.clone(),
     );
     let eth_transfers_log = EthTransfersLogger::default();
     let mut session = create_vm_session(
@@ -53,93 +65,162 @@ pub(super) fn execute_deposited_transaction<
         &eth_transfers_log,
         input.block_hash_lookup,
     );
+    if let Some(tracer) = &tracer {
+        tracer.attach(&mut session);
+    }
     let traversal_storage = TraversalStorage::new();
     let mut traversal_context = TraversalContext::new(&traversal_storage);
     // The type of `tx.gas` is essentially `[u64; 1]` so taking the 0th element
     // is a 1:1 mapping to `u64`.
     let mut gas_meter = new_gas_meter(input.genesis_config, input.tx.gas_limit);
 
-    let module = ModuleId::new(EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE.into());
-    let function_name = EVM_DEPOSIT_FN_NAME;
-    // Unwraps in serialization are safe because the layouts match the types.
-    let to_address = match input.tx.to {
-        revm::primitives::TxKind::Call(addr) => addr.to_move_address(),
-        _ => unimplemented!("Contract creation through deposit tx not yet supported"),
-    };
-    let args: Vec<Vec<u8>> = [
-        (
-            Value::address(input.tx.from.to_move_address()),
-            &ADDRESS_LAYOUT,
-        ),
-        (Value::address(to_address), &ADDRESS_LAYOUT),
-        (Value::u256(input.tx.value.to_move_u256()), &U256_LAYOUT),
-        (
-            Value::vector_u8(input.tx.input.iter().copied()),
-            &CODE_LAYOUT,
-        ),
-    ]
-    .into_iter()
-    .map(|(value, layout)| {
-        ValueSerDeContext::new()
-            .serialize(&value, layout)
-            .unwrap()
-            .unwrap()
-    })
-    .collect();
-    let outcome = session
-        .execute_function_bypass_visibility(
-            &module,
-            function_name,
-            Vec::new(),
-            args,
-            &mut gas_meter,
+    // Deposits are not charged for the nonce increment, like they are not charged any fees
+    if is_deposit_accounts_active(&input) {
+        increment_nonce(
+            &input.tx.from.to_move_address(),
+            &mut session,
             &mut traversal_context,
+            &mut UnmeteredGasMeter,
             &code_storage,
-        )
-        .map_err(Error::from)
-        .and_then(|values| {
-            let evm_outcome = extract_evm_result(values);
-            if !evm_outcome.is_success {
-                return Err(UserError::DepositFailure(evm_outcome.output).into());
-            }
+        )?;
+    }
 
-            // If there is a non-zero mint amount then we start by
-            // giving those tokens to the EVM native address.
-            // The tokens will then be distributed to the correct
-            // accounts according to the transfers that happened
-            // during EVM execution.
-            let mint_amount = input.tx.mint.unwrap_or_default();
-            if mint_amount != 0 {
-                eth_token::mint_eth(
-                    &EVM_NATIVE_ADDRESS,
-                    U256::from(input.tx.mint.unwrap()),
-                    &mut session,
-                    &mut traversal_context,
+    let sender = input.tx.from.to_move_address();
+    let mint_amount = U256::from(input.tx.mint.unwrap_or_default());
+    let mut deployment = None;
+    let outcome = match input.tx.to {
+        TxKind::Call(to) => {
+            let module = ModuleId::new(EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE.into());
+            let function_name = EVM_DEPOSIT_FN_NAME;
+            // Unwraps in serialization are safe because the layouts match the types.
+            let args: Vec<Vec<u8>> = [
+                (Value::address(sender), &ADDRESS_LAYOUT),
+                (Value::address(to.to_move_address()), &ADDRESS_LAYOUT),
+                (Value::u256(input.tx.value.to_move_u256()), &U256_LAYOUT),
+                (
+                    Value::vector_u8(input.tx.input.iter().copied()),
+                    &CODE_LAYOUT,
+                ),
+            ]
+            .into_iter()
+            .map(|(value, layout)| {
+                ValueSerDeContext::new()
+                    .serialize(&value, layout)
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect();
+            session
+                .execute_function_bypass_visibility(
+                    &module,
+                    function_name,
+                    Vec::new(),
+                    args,
                     &mut gas_meter,
+                    &mut traversal_context,
                     &code_storage,
-                )?;
-            }
-            eth_token::replicate_transfers(
-                &eth_transfers_log,
+                )
+                .map_err(Error::from)
+                .and_then(|values| {
+                    let evm_outcome = extract_evm_result(values);
+                    if !evm_outcome.is_success {
+                        return Err(UserError::DepositFailure(evm_outcome.output).into());
+                    }
+
+                    // If there is a non-zero mint amount then we start by
+                    // giving those tokens to the EVM native address.
+                    // The tokens will then be distributed to the correct
+                    // accounts according to the transfers that happened
+                    // during EVM execution.
+                    if mint_amount != U256::ZERO {
+                        eth_token::mint_eth(
+                            &EVM_NATIVE_ADDRESS,
+                            mint_amount,
+                            &mut session,
+                            &mut traversal_context,
+                            &mut gas_meter,
+                            &code_storage,
+                        )?;
+                    }
+
+                    Ok(evm_outcome.logs)
+                })
+        }
+        // The contract is deployed the way a canonical transaction deploys it, which withdraws the
+        // value from the sender, so the mint is credited to the sender first
+        TxKind::Create => eth_token::mint_eth(
+            &sender,
+            mint_amount,
+            &mut session,
+            &mut traversal_context,
+            &mut gas_meter,
+            &code_storage,
+        )
+        .and_then(|_| {
+            deploy_evm_contract(
+                input.tx.input.to_vec(),
+                input.tx.value,
+                sender,
                 &mut session,
                 &mut traversal_context,
                 &mut gas_meter,
                 &code_storage,
-            )?;
+            )
+        })
+        .map(|address| {
+            deployment = Some((
+                address.to_move_address(),
+                ModuleId::new(EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE.into()),
+            ));
+            // The logs of the deployment are emitted as Move events
+            Vec::new()
+        }),
+    }
+    .and_then(|evm_logs| {
+        // A deposit is often the first transaction to reach a wallet, so the Move accounts of its
+        // recipients are created along with their base token stores
+        if is_deposit_accounts_active(&input) {
+            for recipient in eth_transfers_log.recipients() {
+                create_account(
+                    &recipient,
+                    &mut session,
+                    &mut traversal_context,
+                    &mut UnmeteredGasMeter,
+                    &code_storage,
+                )?;
+            }
+        }
+        eth_token::replicate_transfers(
+            &eth_transfers_log,
+            &mut session,
+            &mut traversal_context,
+            &mut gas_meter,
+            &code_storage,
+        )?;
 
-            Ok(evm_outcome.logs)
-        });
+        Ok(evm_logs)
+    });
 
-    let (evm_logs, vm_outcome) = match outcome {
-        Ok(logs) => (logs, Ok(())),
-        Err(Error::User(e)) => (Vec::new(), Err(e)),
+    if let Some(tracer) = tracer {
+        let gas_used = total_gas_used(&gas_meter, input.genesis_config);
+        let error = outcome.as_ref().err().map(ToString::to_string);
+        tracer.record(MoveCall::deposit(), gas_used, error);
+    }
+
+    let evm_logs = match outcome {
+        Ok(logs) => logs,
+        Err(Error::User(e)) => {
+            drop(session);
+            return execute_failed_deposit(&input, &vm, &code_storage, e);
+        }
         Err(e) => {
             return Err(e);
         }
     };
 
     let (mut changes, mut extensions) = session.finish_with_extensions(&code_storage)?;
-    let mut logs = extensions.logs();
+    let (mut logs, events) =
+        extensions.events(input.event_layouts, &vm, input.state, &code_storage);
     logs.extend(evm_logs);
     let gas_used = total_gas_used(&gas_meter, input.genesis_config);
     let evm_changes = extract_evm_changes(&extensions);
@@ -149,12 +230,114 @@ pub(super) fn execute_deposited_transaction<
     let changes = Changes::new(changes, evm_changes.storage);
 
     Ok(TransactionExecutionOutcome::new(
-        vm_outcome,
+        Ok(()),
         changes,
         gas_used,
         // No L2 gas for deposited txs
         U256::ZERO,
         logs,
+        events,
+        deployment,
+    ))
+}
+
+/// Checks the rules that a deposit has to follow before it executes.
+///
+/// The mint is a `u128` and the value a `U256`, so neither can overflow the balances they are
+/// added to. The source hash only identifies the deposit and is not checked, just like op-geth
+/// does not.
+fn validate_deposit<S, ST, H>(
+    input: &DepositExecutionInput<S, ST, H>,
+) -> Result<(), InvalidDepositCause> {
+    let tx = input.tx;
+
+    if tx.is_system_transaction
+        && input
+            .genesis_config
+            .hardforks
+            .is_regolith_active(input.block_header.timestamp)
+    {
+        return Err(InvalidDepositCause::SystemTransaction);
+    }
+
+    let required = intrinsic_gas(&tx.input, tx.to.is_create());
+    if tx.gas_limit < required {
+        return Err(InvalidDepositCause::InsufficientIntrinsicGas {
+            required,
+            given: tx.gas_limit,
+        });
+    }
+
+    Ok(())
+}
+
+/// Whether the deposit follows the account rules of `MoveVmParameters::deposit_accounts_time`.
+fn is_deposit_accounts_active<S, ST, H>(input: &DepositExecutionInput<S, ST, H>) -> bool {
+    input
+        .genesis_config
+        .move_vm
+        .is_deposit_accounts_active(input.block_header.timestamp)
+}
+
+/// Discards all effects of a failed deposit, except for the ones that the OP deposited
+/// transaction rules keep: the nonce of the sender is incremented and the mint is credited to the
+/// sender. Before `MoveVmParameters::deposit_accounts_time` nothing is kept. The deposit uses up
+/// its entire gas limit.
+fn execute_failed_deposit<
+    S: MoveResolver + TableResolver,
+    ST: StorageTrieRepository,
+    H: BlockHashLookup,
+>(
+    input: &DepositExecutionInput<S, ST, H>,
+    vm: &MoveVM,
+    code_storage: &impl ModuleStorage,
+    error: UserError,
+) -> moved_shared::error::Result<TransactionExecutionOutcome> {
+    let session_id = SessionId::new_from_deposited(
+        input.tx,
+        input.tx_hash,
+        input.genesis_config,
+        input.block_header.clone(),
+    );
+    let mut session = create_vm_session(
+        vm,
+        input.state,
+        session_id,
+        input.storage_trie,
+        &(),
+        input.block_hash_lookup,
+    );
+    let traversal_storage = TraversalStorage::new();
+    let mut traversal_context = TraversalContext::new(&traversal_storage);
+    let sender = input.tx.from.to_move_address();
+
+    if is_deposit_accounts_active(input) {
+        increment_nonce(
+            &sender,
+            &mut session,
+            &mut traversal_context,
+            &mut UnmeteredGasMeter,
+            code_storage,
+        )?;
+        eth_token::mint_eth(
+            &sender,
+            U256::from(input.tx.mint.unwrap_or_default()),
+            &mut session,
+            &mut traversal_context,
+            &mut UnmeteredGasMeter,
+            code_storage,
+        )?;
+    }
+
+    let (changes, _) = session.finish_with_extensions(code_storage)?;
+
+    Ok(TransactionExecutionOutcome::new(
+        Err(error),
+        changes.into(),
+        input.tx.gas_limit,
+        U256::ZERO,
+        Vec::new(),
+        Vec::new(),
         None,
     ))
 }
@@ -2,9 +2,15 @@ use {
     crate::session_id::SessionId,
     alloy::primitives::U256,
     aptos_table_natives::TableResolver,
+    aptos_types::account_address::create_derived_object_address,
+    move_binary_format::errors::{PartialVMError, PartialVMResult},
     move_core_types::{
-        account_address::AccountAddress, ident_str, identifier::IdentStr,
-        language_storage::ModuleId, value::MoveValue,
+        account_address::AccountAddress,
+        ident_str,
+        identifier::IdentStr,
+        language_storage::{ModuleId, StructTag},
+        value::MoveValue,
+        vm_status::StatusCode,
     },
     move_vm_runtime::{
         AsUnsyncCodeStorage, ModuleStorage,
@@ -13,23 +19,37 @@ use {
     },
     move_vm_types::{
         gas::{GasMeter, UnmeteredGasMeter},
-        resolver::MoveResolver,
+        resolver::{MoveResolver, ResourceResolver},
         value_serde::ValueSerDeContext,
     },
     moved_evm_ext::{EVM_NATIVE_ADDRESS, events::EthTransferLog, state::StorageTrieRepository},
-    moved_genesis::{CreateMoveVm, FRAMEWORK_ADDRESS, MovedVm},
+    moved_genesis::{CreateMoveVm, FRAMEWORK_ADDRESS, MovedVm, eth_token_metadata_address},
     moved_shared::{
         error::EthToken,
         primitives::{ToMoveU256, ToU256},
     },
     moved_state::ResolverBasedModuleBytesStorage,
+    serde::Deserialize,
 };
 
 const TOKEN_ADMIN: AccountAddress = FRAMEWORK_ADDRESS;
-const TOKEN_MODULE_NAME: &IdentStr = ident_str!("eth_token");
+pub(crate) const TOKEN_MODULE_NAME: &IdentStr = ident_str!("eth_token");
 const MINT_FUNCTION_NAME: &IdentStr = ident_str!("mint");
 const GET_BALANCE_FUNCTION_NAME: &IdentStr = ident_str!("get_balance");
-const TRANSFER_FUNCTION_NAME: &IdentStr = ident_str!("transfer");
+pub(crate) const TRANSFER_FUNCTION_NAME: &IdentStr = ident_str!("transfer");
+const BURN_FUNCTION_NAME: &IdentStr = ident_str!("burn");
+const FUNGIBLE_ASSET_MODULE_NAME: &IdentStr = ident_str!("fungible_asset_u256");
+const FUNGIBLE_STORE_STRUCT_NAME: &IdentStr = ident_str!("FungibleStore");
+
+/// The `fungible_asset_u256::FungibleStore` resource, in the same field order as the Move struct.
+#[derive(Debug, Deserialize)]
+struct FungibleStore {
+    /// The `Object<Metadata>` wrapper holds nothing but the address of the object.
+    _metadata: AccountAddress,
+    /// Little endian, as Move serializes `u256`.
+    balance: [u8; 32],
+    _frozen: bool,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TransferArgs<'a> {
@@ -218,6 +238,41 @@ pub fn transfer_eth<G: GasMeter>(
     Ok(())
 }
 
+pub fn burn_eth<G: GasMeter>(
+    from: &AccountAddress,
+    amount: U256,
+    session: &mut Session,
+    traversal_context: &mut TraversalContext,
+    gas_meter: &mut G,
+    module_storage: &impl ModuleStorage,
+) -> Result<(), moved_shared::error::Error> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let token_module_id = ModuleId::new(FRAMEWORK_ADDRESS, TOKEN_MODULE_NAME.into());
+    let admin_arg = bcs::to_bytes(&MoveValue::Signer(TOKEN_ADMIN)).expect("signer can serialize");
+    let from_arg = bcs::to_bytes(from).expect("from address can serialize");
+    let amount_arg =
+        bcs::to_bytes(&MoveValue::U256(amount.to_move_u256())).expect("amount can serialize");
+
+    let function =
+        session.load_function(module_storage, &token_module_id, BURN_FUNCTION_NAME, &[])?;
+
+    session.execute_entry_function(
+        function,
+        vec![
+            admin_arg.as_slice(),
+            from_arg.as_slice(),
+            amount_arg.as_slice(),
+        ],
+        gas_meter,
+        traversal_context,
+        module_storage,
+    )?;
+
+    Ok(())
+}
+
 pub fn replicate_transfers<G: GasMeter, L: EthTransferLog>(
     eth_transfer_logger: &L,
     session: &mut Session,
@@ -264,6 +319,18 @@ pub fn replicate_transfers<G: GasMeter, L: EthTransferLog>(
         )?;
     }
 
+    // Burns come last, as the burned amounts are credited by the transfers before them
+    for (address, amount) in eth_transfer_logger.take_burns() {
+        burn_eth(
+            &address,
+            amount,
+            session,
+            traversal_context,
+            gas_meter,
+            module_storage,
+        )?;
+    }
+
     Ok(())
 }
 
@@ -346,6 +413,34 @@ pub fn quick_get_eth_balance(
     .unwrap()
 }
 
+/// Reads the base token balance of `account` straight from its primary fungible store.
+///
+/// Agrees with [`quick_get_eth_balance`] without setting up a VM session to call
+/// `eth_token::get_balance`, which makes it the one to serve balance queries with. An account
+/// with no primary store has no balance.
+pub fn read_eth_balance(
+    account: &AccountAddress,
+    state: &impl ResourceResolver,
+) -> PartialVMResult<U256> {
+    let store_address = create_derived_object_address(*account, eth_token_metadata_address());
+    let store_tag = StructTag {
+        address: FRAMEWORK_ADDRESS,
+        module: FUNGIBLE_ASSET_MODULE_NAME.into(),
+        name: FUNGIBLE_STORE_STRUCT_NAME.into(),
+        type_args: Vec::new(),
+    };
+    let (bytes, _) =
+        state.get_resource_bytes_with_metadata_and_layout(&store_address, &store_tag, &[], None)?;
+
+    let Some(bytes) = bytes else {
+        return Ok(U256::ZERO);
+    };
+    let store: FungibleStore = bcs::from_bytes(&bytes)
+        .map_err(|_| PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_RESOURCE))?;
+
+    Ok(U256::from_le_bytes(store.balance))
+}
+
 #[cfg(any(feature = "test-doubles", test))]
 mod tests {
     use {super::*, moved_shared::error::Error};
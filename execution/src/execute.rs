@@ -2,13 +2,21 @@ use {
     super::tag_validation::{validate_entry_type_tag, validate_entry_value},
     crate::{ADDRESS_LAYOUT, SIGNER_LAYOUT, U256_LAYOUT, layout::has_value_invariants},
     alloy::primitives::Address,
-    aptos_types::transaction::{EntryFunction, Module, Script},
-    move_binary_format::CompiledModule,
+    aptos_types::{
+        transaction::{EntryFunction, Module, Script},
+        vm::module_metadata::get_metadata_from_compiled_code,
+    },
+    move_binary_format::{
+        CompiledModule,
+        errors::{Location, VMError},
+    },
     move_core_types::{
         account_address::AccountAddress,
         effects::{ChangeSet, Op},
+        identifier::IdentStr,
         language_storage::{ModuleId, TypeTag},
         value::MoveValue,
+        vm_status::StatusCode,
     },
     move_vm_runtime::{
         CodeStorage, ModuleStorage, StagingModuleStorage, module_traversal::TraversalContext,
@@ -20,12 +28,12 @@ use {
     },
     moved_evm_ext::{
         CODE_LAYOUT, EVM_CALL_FN_NAME, EVM_CREATE_FN_NAME, EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE,
-        EvmNativeOutcome, extract_evm_result,
+        EvmNativeOutcome, NativeEVMContext, extract_evm_result,
     },
     moved_shared::{
         error::{
             Error::{self, User},
-            InvalidTransactionCause, ScriptTransaction, UserError,
+            InvalidTransactionCause, MoveAbort, ScriptTransaction, UserError,
         },
         primitives::{ToMoveU256, U256},
     },
@@ -41,12 +49,40 @@ pub(super) fn execute_entry_function<G: GasMeter, MS: ModuleStorage>(
 ) -> moved_shared::error::Result<()> {
     let (module_id, function_name, ty_args, args) = entry_fn.into_inner();
 
-    // Validate signer params match the actual signer
+    validate_entry_args(
+        &module_id,
+        &function_name,
+        &ty_args,
+        &args,
+        signer,
+        session,
+        module_storage,
+    )?;
+
     let function = session.load_function(module_storage, &module_id, &function_name, &ty_args)?;
+    session
+        .execute_entry_function(function, args, gas_meter, traversal_context, module_storage)
+        .map_err(|e| execution_error(session, e, module_storage))?;
+    Ok(())
+}
+
+/// Checks that the serialized `args` of the function `function_name` fit its parameters, and that
+/// the types of those parameters are allowed in entry functions.
+pub(super) fn validate_entry_args(
+    module_id: &ModuleId,
+    function_name: &IdentStr,
+    ty_args: &[TypeTag],
+    args: &[Vec<u8>],
+    signer: &AccountAddress,
+    session: &mut Session,
+    module_storage: &impl ModuleStorage,
+) -> moved_shared::error::Result<()> {
+    // Validate signer params match the actual signer
+    let function = session.load_function(module_storage, module_id, function_name, ty_args)?;
     if function.param_tys().len() != args.len() {
         Err(InvalidTransactionCause::MismatchedArgumentCount)?;
     }
-    for (ty, bytes) in function.param_tys().iter().zip(&args) {
+    for (ty, bytes) in function.param_tys().iter().zip(args) {
         // References are ignored in entry function signatures because the
         // values are actualized in the serialized arguments.
         let ty = strip_reference(ty)?;
@@ -71,8 +107,6 @@ pub(super) fn execute_entry_function<G: GasMeter, MS: ModuleStorage>(
         }
     }
 
-    let function = session.load_function(module_storage, &module_id, &function_name, &ty_args)?;
-    session.execute_entry_function(function, args, gas_meter, traversal_context, module_storage)?;
     Ok(())
 }
 
@@ -119,14 +153,16 @@ pub(super) fn execute_script<G: GasMeter, CS: CodeStorage>(
 
         result
     };
-    session.execute_script(
-        script.code(),
-        script.ty_args().to_vec(),
-        args,
-        gas_meter,
-        traversal_context,
-        code_storage,
-    )?;
+    session
+        .execute_script(
+            script.code(),
+            script.ty_args().to_vec(),
+            args,
+            gas_meter,
+            traversal_context,
+            code_storage,
+        )
+        .map_err(|e| execution_error(session, e, code_storage))?;
     Ok(())
 }
 
@@ -162,7 +198,7 @@ pub(super) fn deploy_evm_contract<G: GasMeter, MS: ModuleStorage>(
             traversal_context,
             module_storage,
         )
-        .map_err(|e| User(UserError::Vm(e)))?;
+        .map_err(|e| execution_error(session, e, module_storage))?;
 
     let evm_outcome = extract_evm_result(outcome);
 
@@ -216,13 +252,74 @@ pub(super) fn execute_evm_contract<G: GasMeter, MS: ModuleStorage>(
             traversal_context,
             module_storage,
         )
-        .map_err(|e| User(UserError::Vm(e)))?;
+        .map_err(|e| execution_error(session, e, module_storage))?;
 
     let evm_outcome = extract_evm_result(outcome);
 
     Ok(evm_outcome)
 }
 
+/// Describes an abort by the module raising it, using the error map in the metadata of the module
+/// to name the abort code when it has one.
+fn move_error(e: VMError, module_storage: &impl ModuleStorage) -> Error {
+    let (Location::Module(module), Some(code)) = (e.location(), e.sub_status()) else {
+        return User(UserError::Vm(e));
+    };
+    if e.major_status() != StatusCode::ABORTED {
+        return User(UserError::Vm(e));
+    }
+
+    let info = module_storage
+        .fetch_deserialized_module(module.address(), module.name())
+        .ok()
+        .flatten()
+        .and_then(|compiled| get_metadata_from_compiled_code(compiled.as_ref()))
+        .and_then(|metadata| metadata.extract_abort_info(code));
+
+    User(UserError::MoveAbort(MoveAbort {
+        module: module.clone(),
+        code,
+        reason_name: info.as_ref().map(|info| info.reason_name.clone()),
+        description: info.map(|info| info.description),
+        evm_output: None,
+    }))
+}
+
+/// Describes a failed execution of the `session`, keeping the output of a failed EVM execution
+/// that led to it.
+///
+/// The framework aborts on a failed EVM execution, which is a revert with its output. Any other
+/// abort keeps the output too, as Move code commonly aborts after checking the result of the EVM.
+pub(super) fn execution_error(
+    session: &mut Session,
+    e: VMError,
+    module_storage: &impl ModuleStorage,
+) -> Error {
+    let failed_output = session
+        .get_native_extensions()
+        .get_mut::<NativeEVMContext>()
+        .failed_output
+        .take();
+    let Some(output) = failed_output.filter(|_| e.major_status() == StatusCode::ABORTED) else {
+        return move_error(e, module_storage);
+    };
+
+    match move_error(e, module_storage) {
+        User(UserError::MoveAbort(abort))
+            if abort.module.address() == &EVM_NATIVE_ADDRESS
+                && abort.module.name() == EVM_NATIVE_MODULE =>
+        {
+            User(UserError::EvmReverted(output))
+        }
+        User(UserError::MoveAbort(abort)) => User(UserError::MoveAbort(MoveAbort {
+            evm_output: Some(output),
+            ..abort
+        })),
+        // Scripts have no module to describe their aborts with
+        _ => User(UserError::EvmReverted(output)),
+    }
+}
+
 // If `t` is wrapped in `Type::Reference` or `Type::MutableReference`,
 // return the inner type
 fn strip_reference(t: &Type) -> moved_shared::error::Result<&Type> {
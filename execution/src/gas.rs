@@ -5,8 +5,14 @@ use {
     aptos_gas_schedule::gas_params::natives::aptos_framework::{
         CODE_REQUEST_PUBLISH_BASE, CODE_REQUEST_PUBLISH_PER_BYTE,
     },
-    move_core_types::{account_address::AccountAddress, ident_str},
-    moved_genesis::config::GenesisConfig,
+    move_core_types::{
+        account_address::AccountAddress,
+        effects::{ChangeSet, Op},
+        ident_str,
+    },
+    move_table_extension::TableChangeSet,
+    moved_evm_ext::StorageSlotWrites,
+    moved_genesis::config::{GenesisConfig, StorageWriteGasParameters},
     moved_shared::primitives::U256,
     op_alloy::rpc_types::L1BlockInfo,
 };
@@ -24,6 +30,104 @@ pub fn new_gas_meter(
     ))
 }
 
+/// Gas every Ethereum transaction pays before it executes.
+const TX_BASE_COST: u64 = 21_000;
+
+/// Gas that an Ethereum transaction needs before it executes, for its base cost and calldata.
+///
+/// A transaction that creates a contract also pays for the creation and for each word of the init
+/// code, which is its calldata.
+pub fn intrinsic_gas(data: &[u8], is_create: bool) -> u64 {
+    const TX_CREATE_COST: u64 = 32_000;
+    const TX_DATA_ZERO_GAS: u64 = 4;
+    const TX_DATA_NON_ZERO_GAS: u64 = 16;
+    const INIT_CODE_WORD_GAS: u64 = 2;
+
+    let zero_bytes = data.iter().filter(|&&byte| byte == 0).count() as u64;
+    let non_zero_bytes = data.len() as u64 - zero_bytes;
+    let creation_cost = if is_create {
+        TX_CREATE_COST + (data.len() as u64).div_ceil(32) * INIT_CODE_WORD_GAS
+    } else {
+        0
+    };
+
+    TX_BASE_COST
+        + creation_cost
+        + zero_bytes * TX_DATA_ZERO_GAS
+        + non_zero_bytes * TX_DATA_NON_ZERO_GAS
+}
+
+/// Whether `gas_limit` covers the gas that a canonical transaction with `data` is charged before it
+/// executes.
+pub fn covers_intrinsic_gas(genesis_config: &GenesisConfig, gas_limit: u64, data: &[u8]) -> bool {
+    let mut gas_meter = new_gas_meter(genesis_config, gas_limit);
+    let txn_size = (data.len() as u64).into();
+
+    gas_meter
+        .charge_intrinsic_gas_for_transaction(txn_size)
+        .and_then(|_| gas_meter.charge_io_gas_for_transaction(txn_size))
+        .is_ok()
+}
+
+/// Gas every transaction pays at least for its calldata since Isthmus, as defined by
+/// [EIP-7623](https://eips.ethereum.org/EIPS/eip-7623).
+pub fn calldata_floor_gas(data: &[u8]) -> u64 {
+    const TOTAL_COST_FLOOR_PER_TOKEN: u64 = 10;
+
+    let zero_bytes = data.iter().filter(|&&byte| byte == 0).count() as u64;
+    let non_zero_bytes = data.len() as u64 - zero_bytes;
+    let tokens = zero_bytes + non_zero_bytes * 4;
+
+    TX_BASE_COST + tokens * TOTAL_COST_FLOOR_PER_TOKEN
+}
+
+/// Gas for the resources written by `changes`, the table items written by `table_changes` and the
+/// EVM storage `slot_writes`, priced by the storage write `parameters`.
+///
+/// Deleting a resource or table item and clearing a slot are free, and module writes are not
+/// counted as publishing is charged for already. Every slot write is priced as a full word.
+pub fn storage_write_gas(
+    changes: &ChangeSet,
+    table_changes: &TableChangeSet,
+    slot_writes: StorageSlotWrites,
+    parameters: &StorageWriteGasParameters,
+) -> u64 {
+    const SLOT_SIZE: u64 = 32;
+
+    let slot_gas = parameters
+        .per_slot_created
+        .saturating_mul(slot_writes.created)
+        .saturating_add(
+            parameters
+                .per_byte_written
+                .saturating_mul(SLOT_SIZE)
+                .saturating_mul(slot_writes.created.saturating_add(slot_writes.modified)),
+        );
+    let resources = changes
+        .accounts()
+        .values()
+        .flat_map(|account| account.resources().values());
+    let table_items = table_changes
+        .changes
+        .values()
+        .flat_map(|table| table.entries.values());
+
+    resources
+        .chain(table_items)
+        .map(|op| match op {
+            Op::New(bytes) => parameters.per_slot_created.saturating_add(
+                parameters
+                    .per_byte_written
+                    .saturating_mul(bytes.len() as u64),
+            ),
+            Op::Modify(bytes) => parameters
+                .per_byte_written
+                .saturating_mul(bytes.len() as u64),
+            Op::Delete => 0,
+        })
+        .fold(slot_gas, u64::saturating_add)
+}
+
 pub fn total_gas_used<G: AptosGasMeter>(gas_meter: &G, genesis_config: &GenesisConfig) -> u64 {
     let gas_algebra = gas_meter.algebra();
     // Note: this sum is overflow safe because it uses saturating addition
@@ -78,15 +182,17 @@ impl NormalizedEthTransaction {
     /// Therefore, the returned value should be max priority fee per gas, also known as "tip" for
     /// validator.
     pub fn tip_per_gas(&self, base_fee: U256) -> U256 {
-        let extra_fee = self
-            .max_fee_per_gas
-            .checked_sub(base_fee)
-            .unwrap_or(U256::ZERO);
-        self.max_priority_fee_per_gas.min(extra_fee)
+        self.effective_gas_price(base_fee).saturating_sub(base_fee)
     }
 
+    /// Calculates the price paid per unit of gas, being `min(maxFee, baseFee + maxPriorityFee)`
+    /// as specified by EIP-1559.
+    ///
+    /// A transaction whose max fee is below the base fee is not included in a block, as it cannot
+    /// cover the base fee, which is why this is not clamped to the base fee.
     pub fn effective_gas_price(&self, base_fee: U256) -> U256 {
-        self.tip_per_gas(base_fee) + base_fee
+        self.max_fee_per_gas
+            .min(base_fee.saturating_add(self.max_priority_fee_per_gas))
     }
 }
 
@@ -112,6 +218,11 @@ impl L1GasFeeInput {
             non_zero_bytes,
         }
     }
+
+    /// Gas the data would use as calldata on L1, reported as `l1GasUsed` in receipts since Ecotone.
+    pub fn calldata_gas(&self) -> U256 {
+        self.zero_bytes * U256::from(4) + self.non_zero_bytes * U256::from(16)
+    }
 }
 
 impl<T: AsRef<[u8]>> From<T> for L1GasFeeInput {
@@ -159,7 +270,6 @@ pub struct EcotoneGasFee {
 }
 
 impl EcotoneGasFee {
-    const ZERO_BYTE_MULTIPLIER: U256 = U256::from_limbs([4, 0, 0, 0]);
     const GAS_PRICE_MULTIPLIER: U256 = U256::from_limbs([16, 0, 0, 0]);
 
     pub fn new(
@@ -179,11 +289,7 @@ impl EcotoneGasFee {
 
 impl L1GasFee for EcotoneGasFee {
     fn l1_fee(&self, input: L1GasFeeInput) -> U256 {
-        let zero_bytes = input.zero_bytes;
-        let non_zero_bytes = input.non_zero_bytes;
-        let tx_compressed_size = (zero_bytes * Self::ZERO_BYTE_MULTIPLIER
-            + non_zero_bytes * Self::GAS_PRICE_MULTIPLIER)
-            / Self::GAS_PRICE_MULTIPLIER;
+        let tx_compressed_size = input.calldata_gas() / Self::GAS_PRICE_MULTIPLIER;
         let weighted_gas_price = Self::GAS_PRICE_MULTIPLIER * self.base_fee_scalar * self.base_fee
             + self.blob_base_fee_scalar * self.blob_base_fee;
 
@@ -193,7 +299,7 @@ impl L1GasFee for EcotoneGasFee {
     fn l1_block_info(&self, input: L1GasFeeInput) -> Option<L1BlockInfo> {
         Some(L1BlockInfo {
             l1_gas_price: Some(self.base_fee.saturating_to()),
-            l1_gas_used: None,
+            l1_gas_used: Some(input.calldata_gas().saturating_to()),
             l1_fee: Some(self.l1_fee(input).saturating_to()),
             l1_fee_scalar: None,
             l1_base_fee_scalar: Some(self.base_fee_scalar.saturating_to()),
@@ -232,6 +338,7 @@ pub trait CreateL1GasFee {
     fn for_deposit(&self, data: &[u8]) -> impl L1GasFee + 'static;
 }
 
+#[derive(Debug, Clone)]
 pub struct CreateEcotoneL1GasFee;
 
 impl CreateL1GasFee for CreateEcotoneL1GasFee {
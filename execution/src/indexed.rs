@@ -0,0 +1,307 @@
+//! Move event fields as topics of the logs the events are emitted as.
+//!
+//! Solidity events mark the fields to filter on as `indexed`, which Move has no equivalent for.
+//! Instead, since Isthmus, the fields of an event struct named with the [`INDEXED_FIELD_PREFIX`]
+//! are indexed, up to [`MAX_INDEXED_FIELDS`] of them in declaration order. Their topics follow the
+//! Solidity encoding:
+//! * integers, `bool` and `address` values are padded to 32 bytes,
+//! * `vector<u8>` and `String` values are hashed with keccak-256 over their bytes, like Solidity
+//!   `bytes` and `string`,
+//! * any other value is hashed with keccak-256 over its bcs encoding.
+//!
+//! Addresses derived from EVM addresses are left-padded already, so an indexed `address` field has
+//! the same topic as an `address indexed` parameter of a Solidity event.
+
+use {
+    alloy::primitives::{B256, U256, keccak256},
+    move_core_types::{
+        account_address::AccountAddress,
+        language_storage::TypeTag,
+        value::{MoveStructLayout, MoveTypeLayout, MoveValue},
+    },
+    move_vm_runtime::{ModuleStorage, move_vm::MoveVM},
+    move_vm_types::resolver::MoveResolver,
+    moved_shared::primitives::ToU256,
+    std::{cell::RefCell, collections::HashMap},
+};
+
+/// Fields named with this prefix are indexed.
+pub const INDEXED_FIELD_PREFIX: &str = "indexed_";
+
+/// Logs have up to four topics, the first of which is the event type.
+pub const MAX_INDEXED_FIELDS: usize = 3;
+
+/// Layouts of the event types emitted in a block, which name their fields so that the indexed ones
+/// are found.
+///
+/// One is built per block and shared by its transactions, so each event type is resolved once.
+#[derive(Debug, Default)]
+pub struct EventLayouts {
+    indexed_fields: bool,
+    layouts: RefCell<HashMap<TypeTag, MoveTypeLayout>>,
+}
+
+impl EventLayouts {
+    /// Events of the block have topics for their `indexed_fields`, which they do since Isthmus.
+    pub fn new(indexed_fields: bool) -> Self {
+        Self {
+            indexed_fields,
+            layouts: RefCell::default(),
+        }
+    }
+
+    /// Returns the topics of the indexed fields of an event of `type_tag` from its bcs encoded
+    /// `data`.
+    ///
+    /// The layout of an event type seen for the first time in the block is found with `resolve`.
+    /// Event types defined in modules that are not in the state yet do not resolve, and are tried
+    /// again the next time they are emitted.
+    pub fn indexed_topics(
+        &self,
+        type_tag: &TypeTag,
+        data: &[u8],
+        resolve: impl FnOnce(&TypeTag) -> Option<MoveTypeLayout>,
+    ) -> Vec<B256> {
+        if !self.indexed_fields {
+            return Vec::new();
+        }
+        let mut layouts = self.layouts.borrow_mut();
+        if !layouts.contains_key(type_tag) {
+            let Some(layout) = resolve(type_tag) else {
+                return Vec::new();
+            };
+            layouts.insert(type_tag.clone(), layout);
+        }
+
+        indexed_topics(data, &layouts[type_tag])
+    }
+}
+
+/// Resolves the annotated layout of `type_tag` from the modules in `module_storage`.
+pub fn resolve_layout(
+    vm: &MoveVM,
+    state: &impl MoveResolver,
+    module_storage: &impl ModuleStorage,
+    type_tag: &TypeTag,
+) -> Option<MoveTypeLayout> {
+    vm.new_session(state)
+        .get_fully_annotated_type_layout(type_tag, module_storage)
+        .ok()
+}
+
+/// Returns the topics of the indexed fields of an event from its bcs encoded `data` and annotated
+/// `layout`.
+fn indexed_topics(data: &[u8], layout: &MoveTypeLayout) -> Vec<B256> {
+    let MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+        fields: field_layouts,
+        ..
+    }) = layout
+    else {
+        return Vec::new();
+    };
+    if !field_layouts
+        .iter()
+        .any(|field| field.name.as_str().starts_with(INDEXED_FIELD_PREFIX))
+    {
+        return Vec::new();
+    }
+    let Ok(MoveValue::Struct(value)) = MoveValue::simple_deserialize(data, layout) else {
+        return Vec::new();
+    };
+
+    value
+        .into_optional_variant_and_fields()
+        .1
+        .into_iter()
+        .zip(field_layouts)
+        .filter(|(_, field)| field.name.as_str().starts_with(INDEXED_FIELD_PREFIX))
+        .take(MAX_INDEXED_FIELDS)
+        .map(|(value, field)| topic(value, &field.layout))
+        .collect()
+}
+
+fn topic(value: MoveValue, layout: &MoveTypeLayout) -> B256 {
+    match value {
+        MoveValue::Bool(b) => U256::from(b as u8).into(),
+        MoveValue::U8(x) => U256::from(x).into(),
+        MoveValue::U16(x) => U256::from(x).into(),
+        MoveValue::U32(x) => U256::from(x).into(),
+        MoveValue::U64(x) => U256::from(x).into(),
+        MoveValue::U128(x) => U256::from(x).into(),
+        MoveValue::U256(x) => x.to_u256().into(),
+        MoveValue::Signer(address) | MoveValue::Address(address) => B256::new(address.into_bytes()),
+        value @ (MoveValue::Vector(_) | MoveValue::Struct(_)) => {
+            let bytes = value
+                .simple_serialize()
+                .expect("Deserialized value should serialize");
+
+            match is_byte_string(layout) {
+                // Both types encode as their bytes prefixed with the length
+                true => keccak256(
+                    bcs::from_bytes::<Vec<u8>>(&bytes).expect("Byte strings should decode"),
+                ),
+                false => keccak256(bytes),
+            }
+        }
+    }
+}
+
+/// Whether the `layout` is of a `vector<u8>` or a `0x1::string::String`.
+fn is_byte_string(layout: &MoveTypeLayout) -> bool {
+    match layout {
+        MoveTypeLayout::Vector(element) => matches!(element.as_ref(), MoveTypeLayout::U8),
+        MoveTypeLayout::Struct(MoveStructLayout::WithTypes { type_, .. }) => {
+            type_.address == AccountAddress::ONE
+                && type_.module.as_str() == "string"
+                && type_.name.as_str() == "String"
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        alloy::primitives::address,
+        move_core_types::{
+            ident_str,
+            language_storage::StructTag,
+            value::{MoveFieldLayout, MoveStruct},
+        },
+        moved_shared::primitives::ToMoveAddress,
+    };
+
+    fn transfer_layout() -> MoveTypeLayout {
+        MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: StructTag {
+                address: AccountAddress::ONE,
+                module: ident_str!("token").into(),
+                name: ident_str!("Transfer").into(),
+                type_args: Vec::new(),
+            },
+            fields: vec![
+                MoveFieldLayout::new(ident_str!("indexed_from").into(), MoveTypeLayout::Address),
+                MoveFieldLayout::new(
+                    ident_str!("memo").into(),
+                    MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+                ),
+                MoveFieldLayout::new(
+                    ident_str!("indexed_tag").into(),
+                    MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+                ),
+                MoveFieldLayout::new(ident_str!("amount").into(), MoveTypeLayout::U64),
+                MoveFieldLayout::new(ident_str!("indexed_amount").into(), MoveTypeLayout::U64),
+            ],
+        })
+    }
+
+    #[test]
+    fn test_indexed_fields_become_topics_in_order() {
+        let from = address!("6666777788889999aaaabbbbccccddddeeeeffff");
+        let tag = MoveValue::Vector(vec![MoveValue::U8(1), MoveValue::U8(2)]);
+        let value = MoveValue::Struct(MoveStruct::Runtime(vec![
+            MoveValue::Address(from.to_move_address()),
+            MoveValue::Vector(Vec::new()),
+            tag.clone(),
+            MoveValue::U64(7),
+            MoveValue::U64(5),
+        ]));
+        let data = value.simple_serialize().unwrap();
+
+        let topics = indexed_topics(&data, &transfer_layout());
+
+        assert_eq!(
+            topics,
+            [from.into_word(), keccak256([1, 2]), U256::from(5u64).into()]
+        );
+    }
+
+    #[test]
+    fn test_event_layouts_resolve_each_type_once_since_isthmus() {
+        let type_tag = TypeTag::Struct(Box::new(StructTag {
+            address: AccountAddress::ONE,
+            module: ident_str!("token").into(),
+            name: ident_str!("Transfer").into(),
+            type_args: Vec::new(),
+        }));
+        let value = MoveValue::Struct(MoveStruct::Runtime(vec![
+            MoveValue::Address(AccountAddress::TWO),
+            MoveValue::Vector(Vec::new()),
+            MoveValue::Vector(Vec::new()),
+            MoveValue::U64(7),
+            MoveValue::U64(5),
+        ]));
+        let data = value.simple_serialize().unwrap();
+        let layouts = EventLayouts::new(true);
+
+        let topics = layouts.indexed_topics(&type_tag, &data, |_| Some(transfer_layout()));
+        let cached_topics = layouts.indexed_topics(&type_tag, &data, |_| unreachable!());
+        let legacy_topics =
+            EventLayouts::new(false).indexed_topics(&type_tag, &data, |_| unreachable!());
+
+        assert_eq!(topics.len(), MAX_INDEXED_FIELDS);
+        assert_eq!(cached_topics, topics);
+        assert_eq!(legacy_topics, []);
+    }
+
+    #[test]
+    fn test_event_without_indexed_fields_has_no_topics() {
+        let layout = MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: StructTag {
+                address: AccountAddress::ONE,
+                module: ident_str!("token").into(),
+                name: ident_str!("Minted").into(),
+                type_args: Vec::new(),
+            },
+            fields: vec![MoveFieldLayout::new(
+                ident_str!("amount").into(),
+                MoveTypeLayout::U64,
+            )],
+        });
+        let data = bcs::to_bytes(&5u64).unwrap();
+
+        assert_eq!(indexed_topics(&data, &layout), []);
+    }
+
+    #[test]
+    fn test_byte_string_topics_hash_raw_bytes() {
+        let string_layout = MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: StructTag {
+                address: AccountAddress::ONE,
+                module: ident_str!("string").into(),
+                name: ident_str!("String").into(),
+                type_args: Vec::new(),
+            },
+            fields: vec![MoveFieldLayout::new(
+                ident_str!("bytes").into(),
+                MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+            )],
+        });
+        let layout = MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: StructTag {
+                address: AccountAddress::ONE,
+                module: ident_str!("token").into(),
+                name: ident_str!("Named").into(),
+                type_args: Vec::new(),
+            },
+            fields: vec![
+                MoveFieldLayout::new(
+                    ident_str!("indexed_tag").into(),
+                    MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+                ),
+                MoveFieldLayout::new(ident_str!("indexed_name").into(), string_layout),
+            ],
+        });
+        let bytes = MoveValue::vector_u8(b"moved".to_vec());
+        let value = MoveValue::Struct(MoveStruct::Runtime(vec![
+            bytes.clone(),
+            MoveValue::Struct(MoveStruct::Runtime(vec![bytes.clone()])),
+        ]));
+        let data = value.simple_serialize().unwrap();
+
+        let topics = indexed_topics(&data, &layout);
+
+        assert_eq!(topics, [keccak256(b"moved"), keccak256(b"moved")]);
+    }
+}
@@ -0,0 +1,335 @@
+//! Superchain interop support.
+//!
+//! A transaction that consumes a message sent from another chain (an "executing message") must
+//! declare it in its access list under the `CrossL2Inbox` predeploy. Once interop activates, such
+//! transactions are only valid if every declared message exists on its source chain.
+//!
+//! Each message is declared by a lookup entry identifying the log of the initiating message,
+//! optionally followed by an extension of its chain ID, and then by a checksum entry committing to
+//! the whole identifier and the log itself, see [`ExecutingMessage`]. Messages sent from this chain
+//! are checked against its own logs. Checking messages of other chains requires the op-supervisor,
+//! which is not integrated yet, so they are rejected.
+
+use {
+    alloy::{
+        eips::eip2930::AccessList,
+        primitives::{Log, address, keccak256},
+    },
+    moved_shared::{
+        error::InvalidTransactionCause,
+        primitives::{Address, B256, U256},
+    },
+};
+
+pub const CROSS_L2_INBOX: Address = address!("4200000000000000000000000000000000000022");
+pub const L2_TO_L2_CROSS_DOMAIN_MESSENGER: Address =
+    address!("4200000000000000000000000000000000000023");
+pub const SUPERCHAIN_WETH: Address = address!("4200000000000000000000000000000000000024");
+pub const ETH_LIQUIDITY: Address = address!("4200000000000000000000000000000000000025");
+pub const SUPERCHAIN_TOKEN_BRIDGE: Address = address!("4200000000000000000000000000000000000028");
+
+/// Seconds after which an initiating message can no longer be executed.
+pub const MESSAGE_EXPIRY_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+const PREFIX_LOOKUP: u8 = 1;
+const PREFIX_CHAIN_ID_EXTENSION: u8 = 2;
+const PREFIX_CHECKSUM: u8 = 3;
+
+/// Identifies the log of an initiating message, as declared by an executing message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutingMessage {
+    pub chain_id: U256,
+    pub block_number: u64,
+    pub timestamp: u64,
+    pub log_index: u32,
+    pub checksum: B256,
+}
+
+impl ExecutingMessage {
+    /// Computes the checksum this message declares if `log` is its initiating message.
+    pub fn checksum_of(&self, log: &Log) -> B256 {
+        let mut payload = Vec::new();
+        for topic in log.data.topics() {
+            payload.extend_from_slice(topic.as_slice());
+        }
+        payload.extend_from_slice(&log.data.data);
+        let log_hash = keccak256([log.address.as_slice(), keccak256(payload).as_slice()].concat());
+
+        let mut id_packed = [0u8; 32];
+        id_packed[12..20].copy_from_slice(&self.block_number.to_be_bytes());
+        id_packed[20..28].copy_from_slice(&self.timestamp.to_be_bytes());
+        id_packed[28..32].copy_from_slice(&self.log_index.to_be_bytes());
+        let id_log_hash = keccak256([log_hash.as_slice(), &id_packed].concat());
+
+        let mut checksum =
+            keccak256([id_log_hash.as_slice(), &self.chain_id.to_be_bytes::<32>()].concat());
+        checksum[0] = PREFIX_CHECKSUM;
+        checksum
+    }
+}
+
+/// Returns `true` if the access list declares any executing message.
+pub fn has_executing_messages(access_list: &AccessList) -> bool {
+    access_list
+        .iter()
+        .any(|item| item.address == CROSS_L2_INBOX && !item.storage_keys.is_empty())
+}
+
+/// Reads the executing messages declared in `access_list`.
+///
+/// Fails if the entries under the `CrossL2Inbox` do not form a sequence of complete messages.
+pub fn executing_messages(
+    access_list: &AccessList,
+) -> Result<Vec<ExecutingMessage>, InvalidTransactionCause> {
+    let mut entries = access_list
+        .iter()
+        .filter(|item| item.address == CROSS_L2_INBOX)
+        .flat_map(|item| item.storage_keys.iter())
+        .peekable();
+    let mut messages = Vec::new();
+
+    while let Some(lookup) = entries.next() {
+        if lookup[0] != PREFIX_LOOKUP || lookup[1..4] != [0; 3] {
+            return Err(InvalidTransactionCause::InvalidExecutingMessage);
+        }
+        let mut chain_id = [0u8; 32];
+        chain_id[24..].copy_from_slice(&lookup[4..12]);
+
+        if let Some(extension) = entries.next_if(|entry| entry[0] == PREFIX_CHAIN_ID_EXTENSION) {
+            if extension[1..8] != [0; 7] {
+                return Err(InvalidTransactionCause::InvalidExecutingMessage);
+            }
+            chain_id[..24].copy_from_slice(&extension[8..]);
+        }
+        let checksum = entries
+            .next_if(|entry| entry[0] == PREFIX_CHECKSUM)
+            .ok_or(InvalidTransactionCause::InvalidExecutingMessage)?;
+
+        messages.push(ExecutingMessage {
+            chain_id: U256::from_be_bytes(chain_id),
+            block_number: u64::from_be_bytes(lookup[12..20].try_into().unwrap()),
+            timestamp: u64::from_be_bytes(lookup[20..28].try_into().unwrap()),
+            log_index: u32::from_be_bytes(lookup[28..32].try_into().unwrap()),
+            checksum: *checksum,
+        });
+    }
+
+    Ok(messages)
+}
+
+/// Checks the executing messages declared in `access_list` by a transaction of a block with
+/// `timestamp` on the chain with `chain_id`, on which interop activated at `interop_time`.
+///
+/// The `logs` of a block of this chain are looked up by its number. Each of them carries the
+/// timestamp of the block and its index within it.
+pub fn validate_executing_messages(
+    access_list: &AccessList,
+    chain_id: u64,
+    timestamp: u64,
+    interop_time: u64,
+    logs: impl Fn(u64) -> Vec<alloy::rpc::types::Log>,
+) -> Result<(), InvalidTransactionCause> {
+    for message in executing_messages(access_list)? {
+        if message.timestamp < interop_time
+            || message.timestamp > timestamp
+            || message.timestamp.saturating_add(MESSAGE_EXPIRY_WINDOW) < timestamp
+        {
+            return Err(InvalidTransactionCause::InvalidExecutingMessage);
+        }
+        if message.chain_id != U256::from(chain_id) {
+            return Err(InvalidTransactionCause::UnvalidatedExecutingMessage);
+        }
+
+        let is_initiated = logs(message.block_number).iter().any(|log| {
+            log.log_index == Some(message.log_index.into())
+                && log.block_timestamp == Some(message.timestamp)
+                && message.checksum_of(&log.inner) == message.checksum
+        });
+        if !is_initiated {
+            return Err(InvalidTransactionCause::InvalidExecutingMessage);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        alloy::{eips::eip2930::AccessListItem, primitives::LogData},
+        moved_genesis::config::GenesisConfig,
+    };
+
+    const CHAIN_ID: u64 = 42069;
+
+    fn lookup(chain_id: u64, block_number: u64, timestamp: u64, log_index: u32) -> B256 {
+        let mut entry = B256::ZERO;
+        entry[0] = PREFIX_LOOKUP;
+        entry[4..12].copy_from_slice(&chain_id.to_be_bytes());
+        entry[12..20].copy_from_slice(&block_number.to_be_bytes());
+        entry[20..28].copy_from_slice(&timestamp.to_be_bytes());
+        entry[28..32].copy_from_slice(&log_index.to_be_bytes());
+        entry
+    }
+
+    fn inbox(storage_keys: Vec<B256>) -> AccessList {
+        AccessList(vec![AccessListItem {
+            address: CROSS_L2_INBOX,
+            storage_keys,
+        }])
+    }
+
+    fn initiating_log() -> alloy::rpc::types::Log {
+        alloy::rpc::types::Log {
+            inner: Log {
+                address: address!("8fd379246834eac74b8419ffda202cf8051f7a03"),
+                data: LogData::new_unchecked(vec![B256::repeat_byte(7)], vec![1, 2, 3].into()),
+            },
+            block_number: Some(5),
+            block_timestamp: Some(100),
+            log_index: Some(2),
+            ..Default::default()
+        }
+    }
+
+    fn checksum_entry(byte: u8) -> B256 {
+        let mut entry = B256::repeat_byte(byte);
+        entry[0] = PREFIX_CHECKSUM;
+        entry
+    }
+
+    /// Declares [`initiating_log`] as an executing message.
+    fn message_access_list() -> AccessList {
+        let message = ExecutingMessage {
+            chain_id: U256::from(CHAIN_ID),
+            block_number: 5,
+            timestamp: 100,
+            log_index: 2,
+            checksum: B256::ZERO,
+        };
+        let checksum = message.checksum_of(&initiating_log().inner);
+
+        inbox(vec![lookup(CHAIN_ID, 5, 100, 2), checksum])
+    }
+
+    fn logs_of_block(number: u64) -> Vec<alloy::rpc::types::Log> {
+        match number {
+            5 => vec![initiating_log()],
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_only_inbox_entries_are_executing_messages() {
+        let item = |address| AccessListItem {
+            address,
+            storage_keys: vec![B256::repeat_byte(1)],
+        };
+
+        assert!(!has_executing_messages(&AccessList(vec![item(
+            SUPERCHAIN_WETH
+        )])));
+        assert!(has_executing_messages(&AccessList(vec![
+            item(SUPERCHAIN_WETH),
+            item(CROSS_L2_INBOX),
+        ])));
+    }
+
+    #[test]
+    fn test_executing_messages_reads_lookup_extension_and_checksum() {
+        let mut extension = B256::ZERO;
+        extension[0] = PREFIX_CHAIN_ID_EXTENSION;
+        extension[31] = 1;
+        let checksum = checksum_entry(9);
+
+        let messages =
+            executing_messages(&inbox(vec![lookup(10, 5, 100, 2), extension, checksum])).unwrap();
+
+        assert_eq!(
+            messages,
+            vec![ExecutingMessage {
+                chain_id: (U256::from(1) << 64) + U256::from(10),
+                block_number: 5,
+                timestamp: 100,
+                log_index: 2,
+                checksum,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_executing_message_without_checksum_is_invalid() {
+        let result = executing_messages(&inbox(vec![lookup(CHAIN_ID, 5, 100, 2)]));
+
+        assert!(matches!(
+            result,
+            Err(InvalidTransactionCause::InvalidExecutingMessage)
+        ));
+    }
+
+    #[test]
+    fn test_message_initiated_on_this_chain_is_valid() {
+        let result =
+            validate_executing_messages(&message_access_list(), CHAIN_ID, 110, 0, logs_of_block);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_message_with_wrong_checksum_is_invalid() {
+        let access_list = inbox(vec![lookup(CHAIN_ID, 5, 100, 2), checksum_entry(9)]);
+
+        let result = validate_executing_messages(&access_list, CHAIN_ID, 110, 0, logs_of_block);
+
+        assert!(matches!(
+            result,
+            Err(InvalidTransactionCause::InvalidExecutingMessage)
+        ));
+    }
+
+    #[test]
+    fn test_expired_message_is_invalid() {
+        let timestamp = 100 + MESSAGE_EXPIRY_WINDOW + 1;
+
+        let result = validate_executing_messages(
+            &message_access_list(),
+            CHAIN_ID,
+            timestamp,
+            0,
+            logs_of_block,
+        );
+
+        assert!(matches!(
+            result,
+            Err(InvalidTransactionCause::InvalidExecutingMessage)
+        ));
+    }
+
+    #[test]
+    fn test_message_from_other_chain_cannot_be_validated() {
+        let access_list = inbox(vec![lookup(CHAIN_ID + 1, 5, 100, 2), checksum_entry(9)]);
+
+        let result = validate_executing_messages(&access_list, CHAIN_ID, 110, 0, logs_of_block);
+
+        assert!(matches!(
+            result,
+            Err(InvalidTransactionCause::UnvalidatedExecutingMessage)
+        ));
+    }
+
+    #[test]
+    fn test_interop_predeploys_are_in_l2_genesis() {
+        let alloc = GenesisConfig::default().l2_contract_genesis.alloc;
+
+        for predeploy in [
+            CROSS_L2_INBOX,
+            L2_TO_L2_CROSS_DOMAIN_MESSENGER,
+            SUPERCHAIN_WETH,
+            ETH_LIQUIDITY,
+            SUPERCHAIN_TOKEN_BRIDGE,
+        ] {
+            assert!(alloc.contains_key(&predeploy), "{predeploy} is missing");
+        }
+    }
+}
@@ -1,11 +1,19 @@
 pub use {
     alloy::primitives::U256,
-    eth_token::{BaseTokenAccounts, MovedBaseTokenAccounts, mint_eth, quick_get_eth_balance},
+    eth_token::{
+        BaseTokenAccounts, MovedBaseTokenAccounts, mint_eth, quick_get_eth_balance,
+        read_eth_balance,
+    },
     gas::{
         CreateEcotoneL1GasFee, CreateL1GasFee, CreateL2GasFee, CreateMovedL2GasFee, EcotoneGasFee,
-        L1GasFee, L1GasFeeInput, L2GasFee, L2GasFeeInput, MovedGasFee,
+        L1GasFee, L1GasFeeInput, L2GasFee, L2GasFeeInput, MovedGasFee, calldata_floor_gas,
+        covers_intrinsic_gas,
     },
-    nonces::{check_nonce, quick_get_nonce},
+    indexed::EventLayouts,
+    nonces::{check_nonce, quick_get_nonce, read_nonce},
+    overlay::ChangesOverlay,
+    trace::{MoveCall, MoveCallFrame, TransactionTrace},
+    withdrawal::{WithdrawalExecutionInput, execute_withdrawals},
 };
 
 use {
@@ -18,12 +26,14 @@ use {
     aptos_types::contract_event::ContractEvent,
     canonical::execute_canonical_transaction,
     deposited::execute_deposited_transaction,
+    indexed::resolve_layout,
     move_core_types::{
         language_storage::TypeTag,
         value::{MoveTypeLayout, MoveValue},
     },
     move_vm_runtime::{
-        move_vm::MoveVM, native_extensions::NativeContextExtensions, session::Session,
+        ModuleStorage, move_vm::MoveVM, native_extensions::NativeContextExtensions,
+        session::Session,
     },
     move_vm_types::resolver::MoveResolver,
     moved_evm_ext::{
@@ -38,9 +48,11 @@ use {
     op_alloy::consensus::TxDeposit,
     session_id::SessionId,
     std::ops::Deref,
-    transaction::{NormalizedEthTransaction, TransactionExecutionOutcome},
+    trace::Tracer,
+    transaction::{MoveEvent, NormalizedEthTransaction, TransactionExecutionOutcome},
 };
 
+pub mod interop;
 pub mod session_id;
 pub mod simulate;
 pub mod transaction;
@@ -50,11 +62,15 @@ mod deposited;
 mod eth_token;
 mod execute;
 mod gas;
+mod indexed;
 mod layout;
 mod nonces;
+mod overlay;
 mod tag_validation;
 #[cfg(test)]
 mod tests;
+mod trace;
+mod withdrawal;
 
 const ADDRESS_LAYOUT: MoveTypeLayout = MoveTypeLayout::Address;
 const SIGNER_LAYOUT: MoveTypeLayout = MoveTypeLayout::Signer;
@@ -123,6 +139,7 @@ pub struct DepositExecutionInput<'input, S, ST, H> {
     pub genesis_config: &'input GenesisConfig,
     pub block_header: HeaderForExecution,
     pub block_hash_lookup: &'input H,
+    pub event_layouts: &'input EventLayouts,
 }
 
 impl<'input, S, ST, F, B, H> From<DepositExecutionInput<'input, S, ST, H>>
@@ -146,6 +163,7 @@ pub struct CanonicalExecutionInput<'input, S, ST, F, B, H> {
     pub base_token: &'input B,
     pub block_header: HeaderForExecution,
     pub block_hash_lookup: &'input H,
+    pub event_layouts: &'input EventLayouts,
 }
 
 impl<'input, S, ST, F, B, H> From<CanonicalExecutionInput<'input, S, ST, F, B, H>>
@@ -166,11 +184,35 @@ pub fn execute_transaction<
     input: TransactionExecutionInput<S, ST, F, B, H>,
 ) -> moved_shared::error::Result<TransactionExecutionOutcome> {
     match input {
-        TransactionExecutionInput::Deposit(input) => execute_deposited_transaction(input),
-        TransactionExecutionInput::Canonical(input) => execute_canonical_transaction(input),
+        TransactionExecutionInput::Deposit(input) => execute_deposited_transaction(input, None),
+        TransactionExecutionInput::Canonical(input) => execute_canonical_transaction(input, None),
     }
 }
 
+/// Executes a transaction like [`execute_transaction`] does, recording the Move and EVM calls it
+/// makes along the way.
+pub fn trace_transaction<
+    S: MoveResolver + TableResolver,
+    ST: StorageTrieRepository,
+    F: L2GasFee,
+    B: BaseTokenAccounts,
+    H: BlockHashLookup,
+>(
+    input: TransactionExecutionInput<S, ST, F, B, H>,
+) -> moved_shared::error::Result<(TransactionExecutionOutcome, TransactionTrace)> {
+    let mut tracer = Tracer::default();
+    let outcome = match input {
+        TransactionExecutionInput::Deposit(input) => {
+            execute_deposited_transaction(input, Some(&mut tracer))
+        }
+        TransactionExecutionInput::Canonical(input) => {
+            execute_canonical_transaction(input, Some(&mut tracer))
+        }
+    }?;
+
+    Ok((outcome, tracer.finish()))
+}
+
 pub trait LogsBloom {
     fn logs_bloom(&mut self) -> Bloom;
 }
@@ -184,22 +226,47 @@ impl<'a, I: Iterator<Item = &'a Log>> LogsBloom for I {
     }
 }
 
-trait Logs {
-    fn logs(&mut self) -> Vec<Log>;
+trait Events {
+    /// Takes the events emitted in a session as Ethereum logs, along with the Move events among
+    /// them. The `layouts` of the event types in the block find their indexed fields, and the ones
+    /// not known yet are resolved with the modules in `module_storage`.
+    fn events(
+        &mut self,
+        layouts: &EventLayouts,
+        vm: &MoveVM,
+        state: &impl MoveResolver,
+        module_storage: &impl ModuleStorage,
+    ) -> (Vec<Log>, Vec<MoveEvent>);
 }
 
-impl Logs for NativeContextExtensions<'_> {
-    fn logs(&mut self) -> Vec<Log> {
-        let mut result = Vec::new();
+impl Events for NativeContextExtensions<'_> {
+    fn events(
+        &mut self,
+        layouts: &EventLayouts,
+        vm: &MoveVM,
+        state: &impl MoveResolver,
+        module_storage: &impl ModuleStorage,
+    ) -> (Vec<Log>, Vec<MoveEvent>) {
+        let mut logs = Vec::new();
+        let mut move_events = Vec::new();
         let events = self.remove::<NativeEventContext>().into_events();
         for (event, _) in events {
-            push_logs(&event, &mut result);
+            push_logs(&event, &mut logs, &mut move_events, |type_tag, data| {
+                layouts.indexed_topics(type_tag, data, |type_tag| {
+                    resolve_layout(vm, state, module_storage, type_tag)
+                })
+            });
         }
-        result
+        (logs, move_events)
     }
 }
 
-fn push_logs(event: &ContractEvent, dest: &mut Vec<Log<LogData>>) {
+fn push_logs(
+    event: &ContractEvent,
+    dest: &mut Vec<Log<LogData>>,
+    move_events: &mut Vec<MoveEvent>,
+    indexed_topics: impl FnOnce(&TypeTag, &[u8]) -> Vec<B256>,
+) {
     let (type_tag, event_data) = match event {
         ContractEvent::V1(v1) => (v1.type_tag(), v1.event_data()),
         ContractEvent::V2(v2) => (v2.type_tag(), v2.event_data()),
@@ -225,11 +292,16 @@ fn push_logs(event: &ContractEvent, dest: &mut Vec<Log<LogData>>) {
     hasher.update(type_string.as_bytes());
     let type_hash = hasher.finalize();
 
-    let topics = vec![type_hash];
+    let mut topics = vec![type_hash];
+    topics.extend(indexed_topics(type_tag, event_data));
 
     let data = event_data.to_vec();
     let data = data.into();
 
     let log = Log::new_unchecked(address, topics, data);
     dest.push(log);
+    move_events.push(MoveEvent {
+        type_tag: struct_tag.as_ref().clone(),
+        data: event_data.to_vec(),
+    });
 }
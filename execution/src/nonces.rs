@@ -1,9 +1,12 @@
 use {
     crate::session_id::SessionId,
     aptos_table_natives::TableResolver,
+    aptos_types::account_config::AccountResource,
+    move_binary_format::errors::{PartialVMError, PartialVMResult},
     move_core_types::{
         account_address::AccountAddress, ident_str, identifier::IdentStr,
-        language_storage::ModuleId, value::MoveValue, vm_status::StatusCode,
+        language_storage::ModuleId, move_resource::MoveStructType, value::MoveValue,
+        vm_status::StatusCode,
     },
     move_vm_runtime::{
         AsUnsyncCodeStorage, ModuleStorage,
@@ -12,7 +15,7 @@ use {
     },
     move_vm_types::{
         gas::{GasMeter, UnmeteredGasMeter},
-        resolver::MoveResolver,
+        resolver::{MoveResolver, ResourceResolver},
         value_serde::ValueSerDeContext,
     },
     moved_evm_ext::state::StorageTrieRepository,
@@ -26,6 +29,14 @@ const CREATE_ACCOUNT_FUNCTION_NAME: &IdentStr = ident_str!("create_account_if_do
 const GET_NONCE_FUNCTION_NAME: &IdentStr = ident_str!("get_sequence_number");
 const INCREMENT_NONCE_FUNCTION_NAME: &IdentStr = ident_str!("increment_sequence_number");
 
+/// The framework aborts instead of creating accounts at `@vm_reserved`, `@aptos_framework` and
+/// `@aptos_token`.
+const RESERVED_ADDRESSES: [AccountAddress; 3] = [
+    AccountAddress::ZERO,
+    AccountAddress::ONE,
+    AccountAddress::THREE,
+];
+
 /// Useful in tests and queries. Do not use in transaction execution
 /// since this method creates a new session and does not charge gas.
 pub fn quick_get_nonce(
@@ -57,6 +68,28 @@ pub fn quick_get_nonce(
     .unwrap_or_default()
 }
 
+/// Reads the nonce of `address` straight from its `account::Account` resource.
+///
+/// Agrees with [`quick_get_nonce`] without setting up a VM session to call
+/// `account::get_sequence_number`, which makes it the one to serve nonce queries with. An account
+/// that does not exist yet has a zero nonce.
+pub fn read_nonce(address: &AccountAddress, state: &impl ResourceResolver) -> PartialVMResult<u64> {
+    let (bytes, _) = state.get_resource_bytes_with_metadata_and_layout(
+        address,
+        &AccountResource::struct_tag(),
+        &[],
+        None,
+    )?;
+
+    let Some(bytes) = bytes else {
+        return Ok(0);
+    };
+    let account: AccountResource = bcs::from_bytes(&bytes)
+        .map_err(|_| PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_RESOURCE))?;
+
+    Ok(account.sequence_number())
+}
+
 pub fn check_nonce<G: GasMeter, MS: ModuleStorage>(
     tx_nonce: u64,
     signer: &AccountAddress,
@@ -68,24 +101,13 @@ pub fn check_nonce<G: GasMeter, MS: ModuleStorage>(
     let account_module_id = ModuleId::new(FRAMEWORK_ADDRESS, ACCOUNT_MODULE_NAME.into());
     let addr_arg = bcs::to_bytes(signer).expect("address can serialize");
 
-    session
-        .execute_function_bypass_visibility(
-            &account_module_id,
-            CREATE_ACCOUNT_FUNCTION_NAME,
-            Vec::new(),
-            vec![addr_arg.as_slice()],
-            gas_meter,
-            traversal_context,
-            module_storage,
-        )
-        .map_err(|e| {
-            if e.major_status() == StatusCode::OUT_OF_GAS {
-                Error::InvalidTransaction(InvalidTransactionCause::InsufficientIntrinsicGas)
-            } else {
-                Error::nonce_invariant_violation(NonceChecking::AnyAccountCanBeCreated)
-            }
-        })?;
-
+    create_account(
+        signer,
+        session,
+        traversal_context,
+        gas_meter,
+        module_storage,
+    )?;
     let account_nonce = get_account_nonce(
         &account_module_id,
         &addr_arg,
@@ -105,12 +127,94 @@ pub fn check_nonce<G: GasMeter, MS: ModuleStorage>(
         Err(InvalidTransactionCause::ExhaustedAccount)?;
     }
 
+    call_account_function(
+        INCREMENT_NONCE_FUNCTION_NAME,
+        &addr_arg,
+        NonceChecking::IncrementNonceAlwaysSucceeds,
+        session,
+        traversal_context,
+        gas_meter,
+        module_storage,
+    )
+}
+
+/// Creates the Move account of `account` if it does not exist yet.
+///
+/// Reserved addresses are skipped, as they can receive the base token without an account.
+pub fn create_account<G: GasMeter, MS: ModuleStorage>(
+    account: &AccountAddress,
+    session: &mut Session,
+    traversal_context: &mut TraversalContext,
+    gas_meter: &mut G,
+    module_storage: &MS,
+) -> Result<(), Error> {
+    if RESERVED_ADDRESSES.contains(account) {
+        return Ok(());
+    }
+    let addr_arg = bcs::to_bytes(account).expect("address can serialize");
+
+    call_account_function(
+        CREATE_ACCOUNT_FUNCTION_NAME,
+        &addr_arg,
+        NonceChecking::AnyAccountCanBeCreated,
+        session,
+        traversal_context,
+        gas_meter,
+        module_storage,
+    )
+}
+
+/// Increments the nonce of `account`, creating the account if it does not exist yet.
+///
+/// Deposited transactions have no nonce to check, but still increment the nonce of their sender.
+pub fn increment_nonce<G: GasMeter, MS: ModuleStorage>(
+    account: &AccountAddress,
+    session: &mut Session,
+    traversal_context: &mut TraversalContext,
+    gas_meter: &mut G,
+    module_storage: &MS,
+) -> Result<(), Error> {
+    let addr_arg = bcs::to_bytes(account).expect("address can serialize");
+
+    create_account(
+        account,
+        session,
+        traversal_context,
+        gas_meter,
+        module_storage,
+    )?;
+    call_account_function(
+        INCREMENT_NONCE_FUNCTION_NAME,
+        &addr_arg,
+        NonceChecking::IncrementNonceAlwaysSucceeds,
+        session,
+        traversal_context,
+        gas_meter,
+        module_storage,
+    )
+}
+
+/// Calls the `account` module `function` that takes the address in `addr_arg` and returns nothing.
+///
+/// Running out of gas makes the transaction invalid, while any other failure violates the
+/// `invariant` that the function always succeeds.
+fn call_account_function<G: GasMeter, MS: ModuleStorage>(
+    function: &IdentStr,
+    addr_arg: &[u8],
+    invariant: NonceChecking,
+    session: &mut Session,
+    traversal_context: &mut TraversalContext,
+    gas_meter: &mut G,
+    module_storage: &MS,
+) -> Result<(), Error> {
+    let account_module_id = ModuleId::new(FRAMEWORK_ADDRESS, ACCOUNT_MODULE_NAME.into());
+
     session
         .execute_function_bypass_visibility(
             &account_module_id,
-            INCREMENT_NONCE_FUNCTION_NAME,
+            function,
             Vec::new(),
-            vec![addr_arg.as_slice()],
+            vec![addr_arg],
             gas_meter,
             traversal_context,
             module_storage,
@@ -119,7 +223,7 @@ pub fn check_nonce<G: GasMeter, MS: ModuleStorage>(
             if e.major_status() == StatusCode::OUT_OF_GAS {
                 Error::InvalidTransaction(InvalidTransactionCause::InsufficientIntrinsicGas)
             } else {
-                Error::nonce_invariant_violation(NonceChecking::IncrementNonceAlwaysSucceeds)
+                Error::nonce_invariant_violation(invariant)
             }
         })?;
 
@@ -0,0 +1,115 @@
+use {
+    bytes::Bytes,
+    move_binary_format::errors::PartialVMError,
+    move_core_types::{
+        account_address::AccountAddress,
+        effects::{ChangeSet, Op},
+        language_storage::{ModuleId, StructTag},
+        metadata::Metadata,
+        value::MoveTypeLayout,
+    },
+    move_table_extension::{TableChangeSet, TableHandle, TableResolver},
+    move_vm_types::resolver::{ModuleResolver, ResourceResolver},
+};
+
+/// Reads the `state` as it is after applying the `changes` of a finished session, so that another
+/// session can continue from where that one left off.
+///
+/// Table changes are not part of a [`ChangeSet`], so tables are read from the `state` as is unless
+/// the changes to them are given by [`Self::with_table_changes`].
+pub struct ChangesOverlay<'a, S> {
+    state: &'a S,
+    changes: &'a ChangeSet,
+    table_changes: Option<&'a TableChangeSet>,
+}
+
+impl<'a, S> ChangesOverlay<'a, S> {
+    pub fn new(state: &'a S, changes: &'a ChangeSet) -> Self {
+        Self {
+            state,
+            changes,
+            table_changes: None,
+        }
+    }
+
+    pub fn with_table_changes(self, table_changes: &'a TableChangeSet) -> Self {
+        Self {
+            table_changes: Some(table_changes),
+            ..self
+        }
+    }
+}
+
+fn written(op: &Op<Bytes>) -> Option<Bytes> {
+    match op {
+        Op::New(bytes) | Op::Modify(bytes) => Some(bytes.clone()),
+        Op::Delete => None,
+    }
+}
+
+impl<S: ModuleResolver> ModuleResolver for ChangesOverlay<'_, S> {
+    fn get_module_metadata(&self, module_id: &ModuleId) -> Vec<Metadata> {
+        self.state.get_module_metadata(module_id)
+    }
+
+    fn get_module(&self, id: &ModuleId) -> Result<Option<Bytes>, PartialVMError> {
+        let op = self
+            .changes
+            .accounts()
+            .get(id.address())
+            .and_then(|account| account.modules().get(id.name()));
+
+        match op {
+            Some(op) => Ok(written(op)),
+            None => self.state.get_module(id),
+        }
+    }
+}
+
+impl<S: ResourceResolver> ResourceResolver for ChangesOverlay<'_, S> {
+    fn get_resource_bytes_with_metadata_and_layout(
+        &self,
+        address: &AccountAddress,
+        struct_tag: &StructTag,
+        metadata: &[Metadata],
+        layout: Option<&MoveTypeLayout>,
+    ) -> Result<(Option<Bytes>, usize), PartialVMError> {
+        let op = self
+            .changes
+            .accounts()
+            .get(address)
+            .and_then(|account| account.resources().get(struct_tag));
+
+        match op {
+            Some(op) => {
+                let value = written(op);
+                let len = value.as_ref().map(|v| v.len()).unwrap_or_default();
+                Ok((value, len))
+            }
+            None => self
+                .state
+                .get_resource_bytes_with_metadata_and_layout(address, struct_tag, metadata, layout),
+        }
+    }
+}
+
+impl<S: TableResolver> TableResolver for ChangesOverlay<'_, S> {
+    fn resolve_table_entry_bytes_with_layout(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+        maybe_layout: Option<&MoveTypeLayout>,
+    ) -> Result<Option<Bytes>, PartialVMError> {
+        let op = self
+            .table_changes
+            .and_then(|tables| tables.changes.get(handle))
+            .and_then(|table| table.entries.get(key));
+
+        match op {
+            Some(op) => Ok(written(op)),
+            None => self
+                .state
+                .resolve_table_entry_bytes_with_layout(handle, key, maybe_layout),
+        }
+    }
+}
@@ -53,6 +53,22 @@ impl SessionId {
         }
     }
 
+    /// Identifies the session refunding the unused gas of a canonical transaction, which only
+    /// starts once the session executing it has finished.
+    pub fn new_from_refund(
+        tx_hash: &B256,
+        genesis_config: &GenesisConfig,
+        block_header: HeaderForExecution,
+    ) -> Self {
+        Self {
+            txn_hash: tx_hash.0,
+            script_hash: None,
+            chain_id: u8_chain_id(genesis_config),
+            user_txn_context: None,
+            block_header,
+        }
+    }
+
     pub fn new_from_deposited(
         tx: &TxDeposit,
         tx_hash: &B256,
@@ -79,6 +95,20 @@ impl SessionId {
             block_header,
         }
     }
+
+    pub fn new_from_withdrawals(
+        withdrawals_root: &B256,
+        genesis_config: &GenesisConfig,
+        block_header: HeaderForExecution,
+    ) -> Self {
+        Self {
+            txn_hash: withdrawals_root.0,
+            script_hash: None,
+            chain_id: u8_chain_id(genesis_config),
+            user_txn_context: None,
+            block_header,
+        }
+    }
 }
 
 // TODO: Should we make it an invariant that the gas price is always less than u64::MAX?
@@ -1,10 +1,10 @@
 use {
     super::{CreateL2GasFee, CreateMovedL2GasFee, L2GasFeeInput},
     crate::{
-        BaseTokenAccounts, CanonicalExecutionInput,
+        BaseTokenAccounts, CanonicalExecutionInput, EventLayouts,
         canonical::{CanonicalVerificationInput, verify_transaction},
         create_vm_session,
-        execute::execute_evm_contract,
+        execute::{execute_evm_contract, execution_error, validate_entry_args},
         execute_transaction,
         gas::new_gas_meter,
         quick_get_nonce,
@@ -15,9 +15,23 @@ use {
         },
     },
     alloy::rpc::types::TransactionRequest,
+    aptos_types::{
+        transaction::EntryFunction,
+        vm::module_metadata::{KnownAttribute, get_metadata_from_compiled_code},
+    },
+    move_binary_format::{
+        CompiledModule,
+        file_format::{SignatureToken, StructHandleIndex},
+    },
+    move_core_types::{
+        account_address::AccountAddress,
+        identifier::IdentStr,
+        language_storage::{StructTag, TypeTag},
+        value::{MoveTypeLayout, MoveValue},
+    },
     move_table_extension::TableResolver,
     move_vm_runtime::{
-        AsUnsyncCodeStorage,
+        AsUnsyncCodeStorage, ModuleStorage,
         module_traversal::{TraversalContext, TraversalStorage},
     },
     move_vm_types::resolver::MoveResolver,
@@ -57,7 +71,7 @@ pub fn simulate_transaction(
         prev_randao: B256::random(),
     };
 
-    let l2_input = L2GasFeeInput::new(u64::MAX, U256::ZERO);
+    let l2_input = L2GasFeeInput::new(tx.gas_limit(), U256::ZERO);
     let l2_fee = CreateMovedL2GasFee.with_default_gas_fee_multiplier();
     let input = CanonicalExecutionInput {
         tx: &tx,
@@ -69,8 +83,13 @@ pub fn simulate_transaction(
         l2_fee,
         l2_input,
         base_token,
-        block_header,
         block_hash_lookup,
+        event_layouts: &EventLayouts::new(
+            genesis_config
+                .hardforks
+                .is_isthmus_active(block_header.timestamp),
+        ),
+        block_header,
     };
 
     execute_transaction(input.into())
@@ -88,13 +107,19 @@ pub fn call_transaction(
     if request.from.is_some() && request.nonce.is_none() {
         tx.nonce = quick_get_nonce(&tx.signer.to_move_address(), state, storage_trie);
     }
-    let tx_data = TransactionData::parse_from(&tx)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Should get current time")
+        .as_secs();
+    let tx_data =
+        TransactionData::parse_from(&tx, genesis_config.move_vm.is_versioned_tx_data_active(now))?;
 
     let moved_vm = MovedVm::new(genesis_config);
     let vm = moved_vm.create_move_vm()?;
     let module_storage_bytes = ResolverBasedModuleBytesStorage::new(state);
     let code_storage = module_storage_bytes.as_unsync_code_storage(&moved_vm);
     let session_id = SessionId::default();
+    let block_timestamp = session_id.block_header.timestamp;
     let mut session =
         create_vm_session(&vm, state, session_id, storage_trie, &(), block_hash_lookup);
     let traversal_storage = TraversalStorage::new();
@@ -107,6 +132,7 @@ pub fn call_transaction(
         traversal_context: &mut traversal_context,
         gas_meter: &mut gas_meter,
         genesis_config,
+        block_timestamp,
         l1_cost: U256::ZERO,
         l2_cost: U256::ZERO,
         base_token,
@@ -116,15 +142,18 @@ pub fn call_transaction(
 
     match tx_data {
         TransactionData::EntryFunction(entry_fn) => {
-            let outcome = verify_input.session.execute_function_bypass_visibility(
-                entry_fn.module(),
-                entry_fn.function(),
-                entry_fn.ty_args().to_vec(),
-                entry_fn.args().to_vec(),
-                verify_input.gas_meter,
-                verify_input.traversal_context,
-                &code_storage,
-            )?;
+            let outcome = verify_input
+                .session
+                .execute_function_bypass_visibility(
+                    entry_fn.module(),
+                    entry_fn.function(),
+                    entry_fn.ty_args().to_vec(),
+                    entry_fn.args().to_vec(),
+                    verify_input.gas_meter,
+                    verify_input.traversal_context,
+                    &code_storage,
+                )
+                .map_err(|e| execution_error(verify_input.session, e, &code_storage))?;
             // Only return the results of the transaction in bytes without the Move value layout.
             // Sending just the bytes works better when it comes to parsing on the client side.
             Ok(bcs::to_bytes(
@@ -175,3 +204,191 @@ pub fn call_transaction(
         _ => Err(InvalidTransaction(InvalidTransactionCause::UnsupportedType)),
     }
 }
+
+/// Calls the `#[view]` function of `view_fn` in a session that is discarded afterwards, returning
+/// the bcs encoded return values along with their types.
+///
+/// The function must be marked `#[view]` in the metadata of its module, so that only functions
+/// meant to be read by clients are callable this way, like the view API of Aptos.
+pub fn view_function(
+    view_fn: EntryFunction,
+    gas_limit: u64,
+    state: &(impl MoveResolver + TableResolver),
+    storage_trie: &impl StorageTrieRepository,
+    genesis_config: &GenesisConfig,
+    block_hash_lookup: &impl BlockHashLookup,
+) -> moved_shared::error::Result<Vec<(TypeTag, Vec<u8>)>> {
+    let moved_vm = MovedVm::new(genesis_config);
+    let vm = moved_vm.create_move_vm()?;
+    let module_storage_bytes = ResolverBasedModuleBytesStorage::new(state);
+    let code_storage = module_storage_bytes.as_unsync_code_storage(&moved_vm);
+    let mut session = create_vm_session(
+        &vm,
+        state,
+        SessionId::default(),
+        storage_trie,
+        &(),
+        block_hash_lookup,
+    );
+    let traversal_storage = TraversalStorage::new();
+    let mut traversal_context = TraversalContext::new(&traversal_storage);
+    let mut gas_meter = new_gas_meter(genesis_config, gas_limit);
+
+    let module = code_storage
+        .fetch_deserialized_module(view_fn.module().address(), view_fn.module().name())?
+        .filter(|module| is_view_function(module, view_fn.function()))
+        .ok_or(InvalidTransactionCause::NotViewFunction)?;
+    // View functions cannot take a signer, so no address is ever checked against this one
+    validate_entry_args(
+        view_fn.module(),
+        view_fn.function(),
+        view_fn.ty_args(),
+        view_fn.args(),
+        &AccountAddress::ZERO,
+        &mut session,
+        &code_storage,
+    )?;
+    let return_tags = return_type_tags(&module, view_fn.function(), view_fn.ty_args())
+        .ok_or(InvalidTransactionCause::NotViewFunction)?;
+
+    let (module_id, function_name, ty_args, args) = view_fn.into_inner();
+    let outcome = session
+        .execute_function_bypass_visibility(
+            &module_id,
+            &function_name,
+            ty_args,
+            args,
+            &mut gas_meter,
+            &mut traversal_context,
+            &code_storage,
+        )
+        .map_err(|e| execution_error(&mut session, e, &code_storage))?;
+
+    Ok(return_tags
+        .into_iter()
+        .zip(
+            outcome
+                .return_values
+                .into_iter()
+                .map(|(bytes, _layout)| bytes),
+        )
+        .collect())
+}
+
+fn is_view_function(module: &CompiledModule, function: &IdentStr) -> bool {
+    get_metadata_from_compiled_code(module).is_some_and(|metadata| {
+        metadata
+            .fun_attributes
+            .get(function.as_str())
+            .is_some_and(|attributes| attributes.iter().any(KnownAttribute::is_view_function))
+    })
+}
+
+/// Reads the return types of `function` from the signature in its `module`, instantiated with
+/// `ty_args`.
+fn return_type_tags(
+    module: &CompiledModule,
+    function: &IdentStr,
+    ty_args: &[TypeTag],
+) -> Option<Vec<TypeTag>> {
+    let handle = module
+        .function_defs()
+        .iter()
+        .map(|def| module.function_handle_at(def.function))
+        .find(|handle| module.identifier_at(handle.name) == function)?;
+
+    module
+        .signature_at(handle.return_)
+        .0
+        .iter()
+        .map(|token| type_tag(module, token, ty_args))
+        .collect()
+}
+
+fn type_tag(
+    module: &CompiledModule,
+    token: &SignatureToken,
+    ty_args: &[TypeTag],
+) -> Option<TypeTag> {
+    Some(match token {
+        SignatureToken::Bool => TypeTag::Bool,
+        SignatureToken::U8 => TypeTag::U8,
+        SignatureToken::U16 => TypeTag::U16,
+        SignatureToken::U32 => TypeTag::U32,
+        SignatureToken::U64 => TypeTag::U64,
+        SignatureToken::U128 => TypeTag::U128,
+        SignatureToken::U256 => TypeTag::U256,
+        SignatureToken::Address => TypeTag::Address,
+        SignatureToken::Signer => TypeTag::Signer,
+        SignatureToken::Vector(inner) => {
+            TypeTag::Vector(Box::new(type_tag(module, inner, ty_args)?))
+        }
+        // Returned references are serialized as the values they point to
+        SignatureToken::Reference(inner) | SignatureToken::MutableReference(inner) => {
+            type_tag(module, inner, ty_args)?
+        }
+        SignatureToken::TypeParameter(index) => ty_args.get(*index as usize)?.clone(),
+        SignatureToken::Struct(index) => {
+            TypeTag::Struct(Box::new(struct_tag(module, *index, Vec::new())))
+        }
+        SignatureToken::StructInstantiation(index, type_args) => {
+            let type_args = type_args
+                .iter()
+                .map(|token| type_tag(module, token, ty_args))
+                .collect::<Option<_>>()?;
+            TypeTag::Struct(Box::new(struct_tag(module, *index, type_args)))
+        }
+        // Function values of newer bytecode versions have no type tag
+        #[allow(unreachable_patterns)]
+        _ => return None,
+    })
+}
+
+fn struct_tag(
+    module: &CompiledModule,
+    index: StructHandleIndex,
+    type_args: Vec<TypeTag>,
+) -> StructTag {
+    let handle = module.struct_handle_at(index);
+    let module_handle = module.module_handle_at(handle.module);
+
+    StructTag {
+        address: *module.address_identifier_at(module_handle.address),
+        module: module.identifier_at(module_handle.name).to_owned(),
+        name: module.identifier_at(handle.name).to_owned(),
+        type_args,
+    }
+}
+
+/// Decodes the bcs encoded `bytes` of a value of type `type_tag`, along with the layout of the type
+/// that names the fields of its structs.
+///
+/// Returns `None` if the type is not defined in the `state` or the `bytes` do not decode as it.
+pub fn annotate_value(
+    type_tag: &TypeTag,
+    bytes: &[u8],
+    state: &(impl MoveResolver + TableResolver),
+    storage_trie: &impl StorageTrieRepository,
+    genesis_config: &GenesisConfig,
+    block_hash_lookup: &impl BlockHashLookup,
+) -> Option<(MoveValue, MoveTypeLayout)> {
+    let moved_vm = MovedVm::new(genesis_config);
+    let vm = moved_vm.create_move_vm().ok()?;
+    let module_storage_bytes = ResolverBasedModuleBytesStorage::new(state);
+    let code_storage = module_storage_bytes.as_unsync_code_storage(&moved_vm);
+    let session = create_vm_session(
+        &vm,
+        state,
+        SessionId::default(),
+        storage_trie,
+        &(),
+        block_hash_lookup,
+    );
+
+    let layout = session
+        .get_fully_annotated_type_layout(type_tag, &code_storage)
+        .ok()?;
+    let value = MoveValue::simple_deserialize(bytes, &layout).ok()?;
+
+    Some((value, layout))
+}
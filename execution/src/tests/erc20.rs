@@ -32,6 +32,36 @@ fn test_erc20_failed_transfer() {
     assert_eq!(new_receiver_balance, receiver_balance);
 }
 
+#[test]
+fn test_revert_data_is_kept_when_script_aborts_after_evm_call() {
+    let mut ctx = TestContext::new();
+
+    let mint_amount = U256::from(1234u64);
+    let token_address = deploy_mock_erc20(&mut ctx, mint_amount);
+
+    // ERC-20 transfer selector: 0xa9059cbb, intentionally for more than the mint amount
+    let calldata = [
+        vec![0xa9, 0x05, 0x9c, 0xbb],
+        (ALT_EVM_ADDRESS, U256::from(1250u64)).abi_encode_params(),
+    ]
+    .concat();
+    let script_bytes = ctx.compile_script(
+        "evm_script",
+        &[],
+        vec![
+            TransactionArgument::Address(token_address.to_move_address()),
+            TransactionArgument::U8Vector(calldata),
+        ],
+    );
+    let (tx_hash, tx) = create_transaction(&mut ctx.signer, TxKind::Create, script_bytes);
+    let outcome = ctx.execute_tx(&TestTransaction::new(tx, tx_hash)).unwrap();
+
+    let Err(moved_shared::error::UserError::EvmReverted(output)) = outcome.vm_outcome else {
+        panic!("Expected an EVM revert, got {:?}", outcome.vm_outcome);
+    };
+    assert!(!output.is_empty());
+}
+
 #[test]
 fn test_erc20_transfer() {
     let mut ctx = TestContext::new();
@@ -1,8 +1,10 @@
 use {
     crate::{
-        CanonicalExecutionInput, execute_transaction,
+        CanonicalExecutionInput, CreateL2GasFee, CreateMovedL2GasFee, EventLayouts, L2GasFeeInput,
+        execute_transaction,
         tests::{ALT_EVM_ADDRESS, EVM_ADDRESS, *},
-        transaction::TransactionData,
+        trace_transaction,
+        transaction::{NormalizedExtendedTxEnvelope, TransactionData},
     },
     alloy::{
         primitives::utils::parse_ether,
@@ -13,8 +15,10 @@ use {
     move_core_types::{ident_str, language_storage::ModuleId, value::MoveValue},
     move_vm_types::{value_serde::ValueSerDeContext, values::Value},
     moved_evm_ext::{
-        CODE_LAYOUT, EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE, state::InMemoryStorageTrieRepository,
+        CODE_LAYOUT, EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE, EvmCallKind,
+        state::InMemoryStorageTrieRepository,
     },
+    moved_genesis::config::StorageWriteGasParameters,
     moved_shared::primitives::{ToEthAddress, ToMoveAddress, ToMoveU256},
     moved_state::{InMemoryState, State},
     revm::primitives::{TxKind, U256},
@@ -123,6 +127,138 @@ fn test_evm() {
     assert_eq!(receiver_balance, transfer_amount + transfer_amount);
 }
 
+#[test]
+fn test_storage_slot_writes_are_charged() {
+    let transfer_gas_used = |storage_write| {
+        let mut ctx = TestContext::new();
+        ctx.genesis_config.gas_costs.storage_write = storage_write;
+        let provider = providers::builder::<AnyNetwork>()
+            .with_recommended_fillers()
+            .on_http("http://localhost:1234".parse().unwrap());
+        let deploy = ERC20::deploy_builder(
+            &provider,
+            "Gold".into(),
+            "AU".into(),
+            EVM_ADDRESS,
+            parse_ether("1").unwrap(),
+        );
+        let contract_address = ctx.evm_quick_create(deploy.calldata().to_vec()).logs[0].address;
+        let transfer_call = ERC20::new(contract_address, &provider)
+            .transfer(ALT_EVM_ADDRESS, parse_ether("0.35").unwrap());
+        let entry_fn = EntryFunction::new(
+            ModuleId::new(EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE.into()),
+            ident_str!("entry_evm_call").into(),
+            Vec::new(),
+            vec![
+                bcs::to_bytes(&MoveValue::Signer(EVM_ADDRESS.to_move_address())).unwrap(),
+                bcs::to_bytes(&MoveValue::Address(contract_address.to_move_address())).unwrap(),
+                ValueSerDeContext::new()
+                    .serialize(
+                        &Value::vector_u8(transfer_call.calldata().clone()),
+                        &CODE_LAYOUT,
+                    )
+                    .unwrap()
+                    .unwrap(),
+            ],
+        );
+        let (tx_hash, tx) = create_transaction(
+            &mut ctx.signer,
+            TxKind::Call(EVM_NATIVE_ADDRESS.to_eth_address()),
+            TransactionData::EntryFunction(entry_fn).to_bytes().unwrap(),
+        );
+
+        let outcome = ctx.execute_tx(&TestTransaction::new(tx, tx_hash)).unwrap();
+        outcome.vm_outcome.unwrap();
+        outcome.gas_used
+    };
+
+    let gas_used = transfer_gas_used(StorageWriteGasParameters::default());
+    let charged_gas_used = transfer_gas_used(StorageWriteGasParameters {
+        per_slot_created: 20_000,
+        per_byte_written: 0,
+    });
+
+    // The transfer stores the balance of the recipient in an empty slot
+    assert!(charged_gas_used >= gas_used + 20_000);
+}
+
+#[test]
+fn test_trace_records_evm_calls_of_entry_function() {
+    let mut ctx = TestContext::new();
+    let provider = providers::builder::<AnyNetwork>()
+        .with_recommended_fillers()
+        .on_http("http://localhost:1234".parse().unwrap());
+    let mint_amount = parse_ether("1").unwrap();
+    let deploy = ERC20::deploy_builder(
+        &provider,
+        "Gold".into(),
+        "AU".into(),
+        EVM_ADDRESS,
+        mint_amount,
+    );
+    let contract_address = ctx.evm_quick_create(deploy.calldata().to_vec()).logs[0].address;
+    let deployed_contract = ERC20::new(contract_address, &provider);
+
+    let transfer_call = deployed_contract.transfer(ALT_EVM_ADDRESS, mint_amount);
+    let entry_fn = EntryFunction::new(
+        ModuleId::new(EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE.into()),
+        ident_str!("entry_evm_call").into(),
+        Vec::new(),
+        vec![
+            bcs::to_bytes(&MoveValue::Signer(EVM_ADDRESS.to_move_address())).unwrap(),
+            bcs::to_bytes(&MoveValue::Address(contract_address.to_move_address())).unwrap(),
+            ValueSerDeContext::new()
+                .serialize(
+                    &Value::vector_u8(transfer_call.calldata().clone()),
+                    &CODE_LAYOUT,
+                )
+                .unwrap()
+                .unwrap(),
+        ],
+    );
+    let (tx_hash, tx) = create_transaction(
+        &mut ctx.signer,
+        TxKind::Call(EVM_NATIVE_ADDRESS.to_eth_address()),
+        TransactionData::EntryFunction(entry_fn).to_bytes().unwrap(),
+    );
+    let NormalizedExtendedTxEnvelope::Canonical(tx) = tx else {
+        unreachable!("Signed transactions are canonical")
+    };
+
+    let (outcome, trace) = trace_transaction(
+        CanonicalExecutionInput {
+            tx: &tx,
+            tx_hash: &tx_hash,
+            state: ctx.state.resolver(),
+            storage_trie: &ctx.evm_storage,
+            genesis_config: &ctx.genesis_config,
+            l1_cost: U256::ZERO,
+            l2_fee: CreateMovedL2GasFee.with_default_gas_fee_multiplier(),
+            l2_input: L2GasFeeInput::new(tx.gas_limit(), U256::ZERO),
+            base_token: &(),
+            block_header: Default::default(),
+            block_hash_lookup: &(),
+            event_layouts: &EventLayouts::default(),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    outcome.vm_outcome.unwrap();
+    assert_eq!(trace.calls.len(), 1);
+    let frame = &trace.calls[0];
+    assert_eq!(frame.call.to_string(), "0x1::evm::entry_evm_call");
+    assert!(frame.gas_used > 0);
+    assert!(frame.error.is_none());
+    assert_eq!(frame.evm_calls.len(), 1);
+    let evm_call = &frame.evm_calls[0];
+    assert_eq!(evm_call.kind, EvmCallKind::Call);
+    assert_eq!(evm_call.from, EVM_ADDRESS);
+    assert_eq!(evm_call.to, Some(contract_address));
+    assert_eq!(evm_call.input, transfer_call.calldata().clone());
+    assert!(evm_call.error.is_none());
+}
+
 #[test]
 fn test_solidity_fixed_bytes() {
     let mut ctx = TestContext::new();
@@ -158,6 +294,7 @@ fn test_solidity_fixed_bytes() {
                 base_token: &(),
                 block_header: HeaderForExecution::default(),
                 block_hash_lookup: &(),
+                event_layouts: &EventLayouts::default(),
             };
             execute_transaction(input.into()).unwrap()
         };
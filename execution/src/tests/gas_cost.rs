@@ -1,4 +1,4 @@
-use super::*;
+use {super::*, moved_genesis::config::StorageWriteGasParameters};
 
 /// How much L1 gas cost charging depletes the gas meter
 const L1_GAS_COST: u64 = 10_000;
@@ -124,6 +124,147 @@ fn test_very_low_gas_limit_makes_tx_invalid() {
     assert_eq!(receiver_balance, U256::ZERO);
 }
 
+#[test]
+fn test_gas_limit_above_maximum_makes_tx_invalid() {
+    let mut ctx = TestContext::new();
+    ctx.genesis_config.max_transaction_gas_limit = Some(30_000_000);
+
+    // Mint tokens in sender account
+    let sender = EVM_ADDRESS;
+    let mint_amount = one_eth();
+    ctx.deposit_eth(sender, mint_amount);
+
+    // The test transactions set the highest possible gas limit
+    let outcome = ctx.transfer(ALT_EVM_ADDRESS, U256::from(1), 1, 100_000, U256::from(1));
+    let err = outcome.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            moved_shared::error::Error::InvalidTransaction(
+                moved_shared::error::InvalidTransactionCause::GasLimitTooHigh {
+                    max: 30_000_000,
+                    given: u64::MAX,
+                }
+            )
+        ),
+        "Unexpected err {err:?}"
+    );
+
+    // In this case no fees are paid
+    assert_eq!(ctx.get_balance(sender), mint_amount);
+}
+
+#[test]
+fn test_calldata_floor_gas_counts_non_zero_bytes_as_four_tokens() {
+    assert_eq!(crate::gas::calldata_floor_gas(&[]), 21_000);
+    assert_eq!(crate::gas::calldata_floor_gas(&[0, 0, 1]), 21_000 + 6 * 10);
+}
+
+#[test]
+fn test_l1_block_info_reports_ecotone_fields() {
+    let l1_fee = EcotoneGasFee::new(U256::from(7), 2, U256::from(3), 5);
+
+    let info = l1_fee.l1_block_info([0u8, 0, 1].into()).unwrap();
+
+    assert_eq!(info.l1_gas_used, Some(4 * 2 + 16));
+    assert_eq!(info.l1_gas_price, Some(7));
+    assert_eq!(info.l1_blob_base_fee, Some(3));
+    assert_eq!(info.l1_base_fee_scalar, Some(2));
+    assert_eq!(info.l1_blob_base_fee_scalar, Some(5));
+    assert_eq!(info.l1_fee_scalar, None);
+}
+
+#[test]
+fn test_gas_used_is_at_least_calldata_floor_since_isthmus() {
+    // Calling an account without code with plenty of calldata uses far less gas than its floor
+    let data = TransactionData::EvmContract {
+        address: ALT_EVM_ADDRESS,
+        data: vec![1; 4_096],
+    }
+    .to_bytes()
+    .unwrap();
+    let floor = calldata_floor_gas(&data);
+    let call_gas_used = |isthmus_time| {
+        let mut ctx = TestContext::new();
+        ctx.genesis_config.hardforks.isthmus_time = isthmus_time;
+        ctx.deposit_eth(EVM_ADDRESS, one_eth());
+
+        let (tx_hash, tx) =
+            create_transaction(&mut ctx.signer, TxKind::Call(ALT_EVM_ADDRESS), data.clone());
+        let mut transaction = TestTransaction::new(tx, tx_hash);
+        let base_token = MovedBaseTokenAccounts::new(AccountAddress::ONE);
+        transaction.with_cost_and_token(1, base_token, 1_000_000, U256::from(1));
+        let outcome = ctx.execute_tx(&transaction).expect("Call should be valid");
+        outcome.vm_outcome.expect("Call should succeed");
+        outcome.gas_used
+    };
+
+    let gas_used = call_gas_used(None);
+    let floored_gas_used = call_gas_used(Some(0));
+
+    assert!(
+        gas_used < floor,
+        "{gas_used} should be below the floor {floor}"
+    );
+    assert_eq!(floored_gas_used, floor);
+}
+
+#[test]
+fn test_storage_writes_are_charged_in_l2_cost() {
+    let transfer = |storage_write| {
+        let mut ctx = TestContext::new();
+        ctx.genesis_config.gas_costs.storage_write = storage_write;
+        ctx.deposit_eth(EVM_ADDRESS, one_eth());
+
+        let gas_used = ctx
+            .transfer(ALT_EVM_ADDRESS, U256::from(1), 1, 1_000_000, U256::from(1))
+            .expect("Transfer should succeed")
+            .gas_used;
+        (gas_used, ctx.get_balance(EVM_ADDRESS))
+    };
+
+    let (gas_used, _) = transfer(StorageWriteGasParameters::default());
+    let (charged_gas_used, sender_balance) = transfer(StorageWriteGasParameters {
+        per_slot_created: 20_000,
+        per_byte_written: 0,
+    });
+
+    // Transferring to a new account creates at least its fungible store
+    assert!(charged_gas_used >= gas_used + 20_000);
+    // Only the gas used is paid, the rest of the gas limit is refunded
+    let expected_sender_balance = one_eth() - U256::from(1 + 1 + charged_gas_used);
+    assert_eq!(sender_balance, expected_sender_balance);
+}
+
+#[test]
+fn test_storage_writes_above_gas_limit_run_out_of_gas() {
+    let mut ctx = TestContext::new();
+    ctx.genesis_config.gas_costs.storage_write = StorageWriteGasParameters {
+        per_slot_created: 1_000_000,
+        per_byte_written: 0,
+    };
+    ctx.deposit_eth(EVM_ADDRESS, one_eth());
+
+    // Transferring to a new account creates its fungible store, which costs more than the limit
+    let l2_gas_limit = 100_000;
+    let outcome = ctx
+        .transfer(
+            ALT_EVM_ADDRESS,
+            U256::from(1),
+            1,
+            l2_gas_limit,
+            U256::from(1),
+        )
+        .expect("Transfer should be valid");
+
+    outcome.vm_outcome.unwrap_err();
+    assert_eq!(outcome.gas_used, l2_gas_limit);
+    // The writes are discarded, but the whole gas limit is paid for
+    assert_eq!(ctx.get_balance(ALT_EVM_ADDRESS), U256::ZERO);
+    let expected_sender_balance = one_eth() - U256::from(1 + l2_gas_limit);
+    assert_eq!(ctx.get_balance(EVM_ADDRESS), expected_sender_balance);
+}
+
 #[test]
 fn test_low_gas_limit_gets_charged_and_fails_the_tx() {
     let mut ctx = TestContext::new();
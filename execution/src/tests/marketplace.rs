@@ -42,3 +42,39 @@ fn test_marketplace() {
     assert_eq!(ctx.get_balance(buyer_address), mint_amount - item_price);
     assert_eq!(ctx.get_balance(seller_address), item_price);
 }
+
+#[test]
+fn test_abort_is_located_in_its_module() {
+    let mut ctx = TestContext::new();
+    let module_id = ctx.deploy_contract("marketplace");
+
+    let market_address = EVM_ADDRESS.to_move_address();
+    let signer = MoveValue::Signer(market_address);
+    ctx.execute(&module_id, "init", vec![&signer]);
+    let price = MoveValue::U256(U256::from(123).to_move_u256());
+    let thing = MoveValue::vector_u8(b"Something valuable".to_vec());
+    let market = MoveValue::Address(market_address);
+    ctx.execute(&module_id, "list", vec![&market, &price, &thing, &signer]);
+
+    // Paying less than the price aborts the purchase
+    ctx.deposit_eth(ALT_EVM_ADDRESS, U256::from(567));
+    ctx.signer = Signer::new(&ALT_PRIVATE_KEY);
+    let script_bytes = ctx.compile_script(
+        "marketplace_script",
+        &["marketplace"],
+        vec![
+            TransactionArgument::Address(market_address),
+            TransactionArgument::U64(0),
+            TransactionArgument::U256(U256::from(100).to_move_u256()),
+        ],
+    );
+    let (tx_hash, tx) = create_transaction(&mut ctx.signer, TxKind::Create, script_bytes);
+    let outcome = ctx.execute_tx(&TestTransaction::new(tx, tx_hash)).unwrap();
+
+    let Err(moved_shared::error::UserError::MoveAbort(abort)) = outcome.vm_outcome else {
+        panic!("Expected a Move abort, got {:?}", outcome.vm_outcome);
+    };
+    assert_eq!(abort.module, module_id);
+    // `error::invalid_argument(EINCORRECT_PAYMENT_AMOUNT)`
+    assert_eq!(abort.code, 0x1_0001);
+}
@@ -62,3 +62,6 @@ pub const ALT_EVM_ADDRESS: Address = address!("88f9b82462f6c4bf4a0fb15e5c3971559
 
 /// The address corresponding to this private key is 0x88f9b82462f6c4bf4a0fb15e5c3971559a316e7f
 pub const ALT_PRIVATE_KEY: [u8; 32] = [0xbb; 32];
+
+/// Sends the deposits of [`TestContext::deposit_eth`], which increment the nonce of their sender.
+pub const DEPOSITOR_ADDRESS: Address = address!("4a04a3191b7a44a99bfd3184f0d2c2c82b98b939");
@@ -1,7 +1,7 @@
 use {
     super::*,
     move_vm_runtime::{AsFunctionValueExtension, AsUnsyncCodeStorage},
-    moved_genesis::{CreateMoveVm, MovedVm},
+    moved_genesis::{CreateMoveVm, MovedVm, config::StorageWriteGasParameters},
     moved_state::ResolverBasedModuleBytesStorage,
 };
 
@@ -74,3 +74,32 @@ fn test_execute_tables_contract() {
     );
     assert_eq!(table_change_set.changes.len(), TABLE_CHANGE_SET_CHANGES_LEN);
 }
+
+#[test]
+fn test_table_item_writes_are_charged() {
+    let make_tables_gas_used = |storage_write| {
+        let mut ctx = TestContext::new();
+        ctx.genesis_config.gas_costs.storage_write = storage_write;
+        let module_id = ctx.deploy_contract("tables");
+        let signer_arg = MoveValue::Signer(EVM_ADDRESS.to_move_address());
+        let (tx_hash, tx) = create_test_tx(
+            &mut ctx.signer,
+            &module_id,
+            "make_test_tables",
+            vec![bcs::to_bytes(&signer_arg).unwrap()],
+        );
+
+        let outcome = ctx.execute_tx(&TestTransaction::new(tx, tx_hash)).unwrap();
+        outcome.vm_outcome.unwrap();
+        outcome.gas_used
+    };
+
+    let gas_used = make_tables_gas_used(StorageWriteGasParameters::default());
+    let charged_gas_used = make_tables_gas_used(StorageWriteGasParameters {
+        per_slot_created: 1_000,
+        per_byte_written: 0,
+    });
+
+    // tables.move adds an item to each of its 11 tables, on top of creating its resource
+    assert!(charged_gas_used >= gas_used + 12 * 1_000);
+}
@@ -0,0 +1,13 @@
+script {
+    use 0x1::eth_token::get_metadata;
+    use 0x1::evm::{evm_call, is_result_success};
+    use 0x1::fungible_asset_u256;
+
+    const ECALL_FAILED: u64 = 1;
+
+    fun call_or_abort(caller: &signer, to: address, data: vector<u8>) {
+        let value = fungible_asset_u256::zero(get_metadata());
+        let result = evm_call(caller, to, value, data);
+        assert!(is_result_success(&result), ECALL_FAILED);
+    }
+}
@@ -9,11 +9,12 @@ fn test_move_event_converts_to_eth_log_successfully() {
         name: Identifier::new("test").unwrap(),
         type_args: vec![],
     }));
-    let event = ContractEvent::V2(ContractEventV2::new(type_tag, data));
+    let event = ContractEvent::V2(ContractEventV2::new(type_tag.clone(), data.clone()));
 
+    let mut move_events = Vec::new();
     let actual_log = {
         let mut tmp = Vec::with_capacity(1);
-        push_logs(&event, &mut tmp);
+        push_logs(&event, &mut tmp, &mut move_events, |_, _| Vec::new());
         tmp.pop().unwrap()
     };
     let expected_log = Log::new_unchecked(
@@ -25,6 +26,16 @@ fn test_move_event_converts_to_eth_log_successfully() {
     );
 
     assert_eq!(actual_log, expected_log);
+    let TypeTag::Struct(struct_tag) = type_tag else {
+        unreachable!()
+    };
+    assert_eq!(
+        move_events,
+        [MoveEvent {
+            type_tag: *struct_tag,
+            data,
+        }]
+    );
 }
 
 #[test]
@@ -1,8 +1,43 @@
 use {
-    super::*, crate::transaction::NormalizedExtendedTxEnvelope, alloy::eips::Encodable2718,
+    super::*,
+    crate::transaction::NormalizedExtendedTxEnvelope,
+    alloy::{eips::Encodable2718, sol_types::SolCall},
+    move_vm_types::resolver::ResourceResolver,
+    moved_shared::{
+        error::{InvalidDepositCause, UserError},
+        primitives::ToEthAddress,
+    },
     op_alloy::consensus::OpTxEnvelope,
 };
 
+const L2_TO_L1_MESSAGE_PASSER: Address = address!("4200000000000000000000000000000000000016");
+
+/// Topic signature of MessagePassed event. The signature is generated with the command below:
+/// cast sig-event "MessagePassed(uint256 indexed nonce, address indexed sender, address indexed target, uint256 value, uint256 gasLimit, bytes data, bytes32 withdrawalHash)"
+const MESSAGE_PASSED_TOPIC: B256 = B256::new(hex!(
+    "02a52367d10742d8032712c1bb8e0144ff1ec5ffda1ed7d70bb05a2744955054"
+));
+
+/// Compiled EVM bytecode of a contract that forwards its calldata and value to the
+/// L2StandardBridge, so that the bridge is called by a contract rather than an EOA. The code it
+/// deploys is assembled from:
+/// ```text
+/// CALLDATASIZE PUSH0 PUSH0 CALLDATACOPY
+/// PUSH0 PUSH0 CALLDATASIZE PUSH0 CALLVALUE PUSH20 0x4200000000000000000000000000000000000010 GAS CALL
+/// RETURNDATASIZE PUSH0 PUSH0 RETURNDATACOPY
+/// PUSH1 0x2a JUMPI RETURNDATASIZE PUSH0 REVERT
+/// JUMPDEST RETURNDATASIZE PUSH0 RETURN
+/// ```
+const BRIDGE_FORWARDER_BYTE_CODE: &[u8] = &hex!(
+    "602e8060095f395ff3365f5f375f5f365f347342000000000000000000000000000000000000105af13d5f5f3e602a573d5ffd5b3d5ff3"
+);
+
+alloy::sol! {
+    function bridgeETHTo(address _to, uint32 _minGasLimit, bytes _extraData) external payable;
+
+    function burn() external;
+}
+
 /// Deposits can be made to the L2.
 #[test]
 fn test_deposit_tx() {
@@ -34,6 +69,266 @@ fn test_deposit_tx() {
     assert_eq!(balance, U256::from(mint_amount));
 }
 
+#[test]
+fn test_deposit_to_fresh_address_creates_its_account_since_deposit_accounts_time() {
+    let fresh_address = address!("00000000000000000000000000000000000f2e54");
+    let account_tag = StructTag {
+        address: AccountAddress::ONE,
+        module: Identifier::new("account").unwrap(),
+        name: Identifier::new("Account").unwrap(),
+        type_args: Vec::new(),
+    };
+
+    // The deposits execute at timestamp 0
+    for (deposit_accounts_time, expected) in [(Some(0), true), (Some(1), false), (None, false)] {
+        let mut ctx = TestContext::new();
+        ctx.genesis_config.move_vm.deposit_accounts_time = deposit_accounts_time;
+
+        ctx.deposit_eth(fresh_address, U256::from(1_000));
+
+        let (account, _) = ctx
+            .state
+            .resolver()
+            .get_resource_bytes_with_metadata_and_layout(
+                &fresh_address.to_move_address(),
+                &account_tag,
+                &[],
+                None,
+            )
+            .unwrap();
+        assert_eq!(account.is_some(), expected);
+    }
+}
+
+#[test]
+fn test_deposit_to_reserved_address_succeeds() {
+    let mut ctx = TestContext::new();
+    ctx.genesis_config.move_vm.deposit_accounts_time = Some(0);
+
+    ctx.deposit_eth(Address::ZERO, U256::from(1_000));
+}
+
+#[test]
+fn test_failed_deposit_only_mints_and_increments_nonce() {
+    let mut ctx = TestContext::new();
+    ctx.genesis_config.move_vm.deposit_accounts_time = Some(0);
+    let mint_amount = U256::from(1_000);
+    let sender_balance = ctx.get_balance(ALT_EVM_ADDRESS);
+    let receiver_balance = ctx.get_balance(EVM_ADDRESS);
+
+    // Too little gas to execute the deposit
+    let tx = TxDeposit {
+        source_hash: B256::ZERO,
+        from: ALT_EVM_ADDRESS,
+        to: TxKind::Call(EVM_ADDRESS),
+        mint: Some(mint_amount.saturating_to()),
+        value: mint_amount,
+        gas_limit: 1,
+        is_system_transaction: false,
+        input: Default::default(),
+    };
+    let tx_hash = OpTxEnvelope::Deposit(tx.clone().seal()).tx_hash();
+    let test_tx = TestTransaction::new(NormalizedExtendedTxEnvelope::DepositedTx(tx), tx_hash);
+
+    let outcome = ctx.execute_tx(&test_tx).unwrap();
+    assert!(outcome.vm_outcome.is_err());
+    assert_eq!(outcome.gas_used, 1);
+    assert!(outcome.logs.is_empty());
+    ctx.state.apply(outcome.changes.move_vm).unwrap();
+    ctx.evm_storage.apply(outcome.changes.evm).unwrap();
+
+    assert_eq!(
+        ctx.get_balance(ALT_EVM_ADDRESS),
+        sender_balance + mint_amount
+    );
+    assert_eq!(ctx.get_balance(EVM_ADDRESS), receiver_balance);
+    let nonce = quick_get_nonce(
+        &ALT_EVM_ADDRESS.to_move_address(),
+        ctx.state.resolver(),
+        &ctx.evm_storage,
+    );
+    assert_eq!(nonce, 1);
+}
+
+#[test]
+fn test_failed_deposit_keeps_nothing_before_deposit_accounts_time() {
+    let mut ctx = TestContext::new();
+    ctx.genesis_config.move_vm.deposit_accounts_time = Some(1);
+    let mint_amount = U256::from(1_000);
+    let sender_balance = ctx.get_balance(ALT_EVM_ADDRESS);
+
+    // Too little gas to execute the deposit, in a block before the activation
+    let tx = TxDeposit {
+        source_hash: B256::ZERO,
+        from: ALT_EVM_ADDRESS,
+        to: TxKind::Call(EVM_ADDRESS),
+        mint: Some(mint_amount.saturating_to()),
+        value: mint_amount,
+        gas_limit: 1,
+        is_system_transaction: false,
+        input: Default::default(),
+    };
+    let tx_hash = OpTxEnvelope::Deposit(tx.clone().seal()).tx_hash();
+    let test_tx = TestTransaction::new(NormalizedExtendedTxEnvelope::DepositedTx(tx), tx_hash);
+
+    let outcome = ctx.execute_tx(&test_tx).unwrap();
+    assert!(outcome.vm_outcome.is_err());
+    ctx.state.apply(outcome.changes.move_vm).unwrap();
+    ctx.evm_storage.apply(outcome.changes.evm).unwrap();
+
+    assert_eq!(ctx.get_balance(ALT_EVM_ADDRESS), sender_balance);
+    let nonce = quick_get_nonce(
+        &ALT_EVM_ADDRESS.to_move_address(),
+        ctx.state.resolver(),
+        &ctx.evm_storage,
+    );
+    assert_eq!(nonce, 0);
+}
+
+#[test]
+fn test_deposit_leaves_sender_nonce_before_deposit_accounts_time() {
+    let mut ctx = TestContext::new();
+    ctx.genesis_config.move_vm.deposit_accounts_time = Some(1);
+
+    ctx.deposit_eth(EVM_ADDRESS, U256::from(1_000));
+
+    let nonce = quick_get_nonce(
+        &DEPOSITOR_ADDRESS.to_move_address(),
+        ctx.state.resolver(),
+        &ctx.evm_storage,
+    );
+    assert_eq!(nonce, 0);
+}
+
+#[test]
+fn test_deposit_increments_sender_nonce_since_deposit_accounts_time() {
+    let mut ctx = TestContext::new();
+    ctx.genesis_config.move_vm.deposit_accounts_time = Some(0);
+
+    ctx.deposit_eth(EVM_ADDRESS, U256::from(1_000));
+
+    let nonce = quick_get_nonce(
+        &DEPOSITOR_ADDRESS.to_move_address(),
+        ctx.state.resolver(),
+        &ctx.evm_storage,
+    );
+    assert_eq!(nonce, 1);
+}
+
+#[test]
+fn test_invalid_deposit_fails_before_execution() {
+    let test_cases = [
+        (
+            TxKind::Call(EVM_ADDRESS),
+            true,
+            100_000,
+            InvalidDepositCause::SystemTransaction,
+        ),
+        (
+            TxKind::Call(EVM_ADDRESS),
+            false,
+            20_999,
+            InvalidDepositCause::InsufficientIntrinsicGas {
+                required: 21_000,
+                given: 20_999,
+            },
+        ),
+    ];
+
+    for (to, is_system_transaction, gas_limit, expected) in test_cases {
+        let mut ctx = TestContext::new();
+        ctx.genesis_config.move_vm.deposit_accounts_time = Some(0);
+        let mint_amount = U256::from(1_000);
+        let sender_balance = ctx.get_balance(ALT_EVM_ADDRESS);
+
+        let tx = TxDeposit {
+            source_hash: B256::ZERO,
+            from: ALT_EVM_ADDRESS,
+            to,
+            mint: Some(mint_amount.saturating_to()),
+            value: mint_amount,
+            gas_limit,
+            is_system_transaction,
+            input: Default::default(),
+        };
+        let tx_hash = OpTxEnvelope::Deposit(tx.clone().seal()).tx_hash();
+        let test_tx = TestTransaction::new(NormalizedExtendedTxEnvelope::DepositedTx(tx), tx_hash);
+
+        let outcome = ctx.execute_tx(&test_tx).unwrap();
+        let Err(UserError::InvalidDeposit(actual)) = outcome.vm_outcome else {
+            panic!("Deposit should be invalid, got {:?}", outcome.vm_outcome);
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(outcome.gas_used, gas_limit);
+        ctx.state.apply(outcome.changes.move_vm).unwrap();
+
+        assert_eq!(
+            ctx.get_balance(ALT_EVM_ADDRESS),
+            sender_balance + mint_amount
+        );
+    }
+}
+
+#[test]
+fn test_deposit_without_recipient_deploys_contract() {
+    let mut ctx = TestContext::new();
+    let mint_amount = U256::from(1_000);
+    let sender_balance = ctx.get_balance(ALT_EVM_ADDRESS);
+
+    let tx = TxDeposit {
+        source_hash: B256::ZERO,
+        from: ALT_EVM_ADDRESS,
+        to: TxKind::Create,
+        mint: Some(mint_amount.saturating_to()),
+        value: U256::ZERO,
+        gas_limit: 10_000_000,
+        is_system_transaction: false,
+        input: BRIDGE_FORWARDER_BYTE_CODE.to_vec().into(),
+    };
+    let tx_hash = OpTxEnvelope::Deposit(tx.clone().seal()).tx_hash();
+    let test_tx = TestTransaction::new(NormalizedExtendedTxEnvelope::DepositedTx(tx), tx_hash);
+
+    let outcome = ctx.execute_tx(&test_tx).unwrap();
+    outcome.vm_outcome.unwrap();
+    assert!(outcome.deployment.is_some());
+    ctx.state.apply(outcome.changes.move_vm).unwrap();
+    ctx.evm_storage.apply(outcome.changes.evm).unwrap();
+
+    assert_eq!(
+        ctx.get_balance(ALT_EVM_ADDRESS),
+        sender_balance + mint_amount
+    );
+}
+
+#[test]
+fn test_direct_balance_and_nonce_reads_agree_with_move_calls() {
+    let mut ctx = TestContext::new();
+    ctx.deposit_eth(EVM_ADDRESS, U256::from(1_000_000));
+    ctx.transfer(ALT_EVM_ADDRESS, U256::from(1_000), 0, u64::MAX, U256::ZERO)
+        .unwrap()
+        .vm_outcome
+        .unwrap();
+    let fresh_address = address!("00000000000000000000000000000000000f2e54");
+
+    for address in [EVM_ADDRESS, ALT_EVM_ADDRESS, fresh_address] {
+        let address = address.to_move_address();
+        let resolver = ctx.state.resolver();
+
+        assert_eq!(
+            read_eth_balance(&address, resolver).unwrap(),
+            quick_get_eth_balance(&address, resolver, &ctx.evm_storage),
+        );
+        assert_eq!(
+            read_nonce(&address, resolver).unwrap(),
+            quick_get_nonce(&address, resolver, &ctx.evm_storage),
+        );
+    }
+    assert_eq!(
+        read_nonce(&EVM_ADDRESS.to_move_address(), ctx.state.resolver()).unwrap(),
+        1
+    );
+}
+
 #[test]
 fn test_initiate_withdrawal() {
     let mut ctx = TestContext::new();
@@ -41,21 +336,21 @@ fn test_initiate_withdrawal() {
     ctx.deposit_eth(EVM_ADDRESS, mint_amount);
 
     let withdraw_amount = U256::from(1_000);
-    let l2_parser = address!("4200000000000000000000000000000000000016");
     // Transfering an amount to L2ToL1MessageParser triggers the withdrawal method via `receive() payable`
-    let outcome = ctx.transfer(l2_parser, withdraw_amount, 0, u64::MAX, U256::ZERO);
+    let outcome = ctx.transfer(
+        L2_TO_L1_MESSAGE_PASSER,
+        withdraw_amount,
+        0,
+        u64::MAX,
+        U256::ZERO,
+    );
 
-    // Topic signature of MessagePassed event. The signature is generated with the command below:
-    // cast sig-event "MessagePassed(uint256 indexed nonce, address indexed sender, address indexed target, uint256 value, uint256 gasLimit, bytes data, bytes32 withdrawalHash)"
-    let expected_topic = B256::new(hex!(
-        "02a52367d10742d8032712c1bb8e0144ff1ec5ffda1ed7d70bb05a2744955054"
-    ));
     assert!(
         outcome
             .unwrap()
             .logs
             .iter()
-            .any(|l| l.topics()[0] == expected_topic)
+            .any(|l| l.topics()[0] == MESSAGE_PASSED_TOPIC)
     );
 
     let new_balance = ctx.get_balance(EVM_ADDRESS);
@@ -66,11 +361,10 @@ fn test_initiate_withdrawal() {
 fn test_initiate_withdrawal_zero_balance() {
     let mut ctx = TestContext::new();
     let withdraw_amount = U256::from(1_000);
-    let l2_parser = address!("4200000000000000000000000000000000000016");
 
     let (tx_hash, tx) = create_transaction_with_value(
         &mut ctx.signer,
-        TxKind::Call(l2_parser),
+        TxKind::Call(L2_TO_L1_MESSAGE_PASSER),
         Vec::new(),
         U256::from(withdraw_amount),
     );
@@ -109,6 +403,74 @@ fn test_withdrawal_tx() {
     );
 }
 
+#[test]
+fn test_withdrawal_from_evm_contract() {
+    let mut ctx = TestContext::new();
+
+    // 1. Deposit ETH to user
+    let mint_amount = U256::from(123);
+    ctx.deposit_eth(EVM_ADDRESS, mint_amount);
+
+    // 2. Deploy a contract that calls the bridge
+    let deployment = ScriptOrDeployment::EvmContract(BRIDGE_FORWARDER_BYTE_CODE.to_vec());
+    let (tx_hash, tx) = create_transaction(
+        &mut ctx.signer,
+        TxKind::Create,
+        bcs::to_bytes(&deployment).unwrap(),
+    );
+    let outcome = ctx.execute_tx(&TestTransaction::new(tx, tx_hash)).unwrap();
+    outcome.vm_outcome.unwrap();
+    ctx.state.apply(outcome.changes.move_vm).unwrap();
+    ctx.evm_storage.apply(outcome.changes.evm).unwrap();
+    let forwarder = outcome.deployment.unwrap().0.to_eth_address();
+
+    // 3. Use the contract to withdraw
+    let input = TransactionData::EvmContract {
+        address: forwarder,
+        data: bridgeETHToCall {
+            _to: EVM_ADDRESS,
+            _minGasLimit: 21_000,
+            _extraData: Bytes::new(),
+        }
+        .abi_encode(),
+    };
+    let (tx_hash, tx) = create_transaction_with_value(
+        &mut ctx.signer,
+        TxKind::Call(forwarder),
+        input.to_bytes().unwrap(),
+        mint_amount,
+    );
+    let outcome = ctx.execute_tx(&TestTransaction::new(tx, tx_hash)).unwrap();
+    outcome.vm_outcome.unwrap();
+    ctx.state.apply(outcome.changes.move_vm).unwrap();
+    ctx.evm_storage.apply(outcome.changes.evm).unwrap();
+
+    assert_eq!(ctx.get_balance(EVM_ADDRESS), U256::ZERO);
+    assert_eq!(ctx.get_balance(forwarder), U256::ZERO);
+    assert_eq!(ctx.get_balance(L2_TO_L1_MESSAGE_PASSER), mint_amount);
+    assert!(
+        outcome
+            .logs
+            .iter()
+            .any(|log| log.address == L2_TO_L1_MESSAGE_PASSER
+                && log.topics()[0] == MESSAGE_PASSED_TOPIC),
+        "Outcome must have the MessagePassed log of the L2ToL1MessagePasser contract"
+    );
+
+    // 4. Burn the withdrawn ETH, which self-destructs a contract holding it
+    let (tx_hash, tx) = create_transaction(
+        &mut ctx.signer,
+        TxKind::Call(L2_TO_L1_MESSAGE_PASSER),
+        burnCall {}.abi_encode(),
+    );
+    let outcome = ctx.execute_tx(&TestTransaction::new(tx, tx_hash)).unwrap();
+    outcome.vm_outcome.unwrap();
+    ctx.state.apply(outcome.changes.move_vm).unwrap();
+    ctx.evm_storage.apply(outcome.changes.evm).unwrap();
+
+    assert_eq!(ctx.get_balance(L2_TO_L1_MESSAGE_PASSER), U256::ZERO);
+}
+
 #[test]
 fn test_eoa_base_token_transfer() {
     // Initialize state
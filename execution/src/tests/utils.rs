@@ -293,6 +293,7 @@ impl TestContext {
         let l2_gas_input = L2GasFeeInput::new(tx.l2_gas_limit, tx.l2_gas_price);
         let tx_hash = tx.tx_hash;
         let l1_cost = U256::from(tx.l1_cost);
+        let event_layouts = EventLayouts::new(self.genesis_config.hardforks.is_isthmus_active(0));
 
         match &tx.base_token {
             TestBaseToken::Empty => execute_transaction(match &tx.tx {
@@ -308,6 +309,7 @@ impl TestContext {
                     base_token: &(),
                     block_header: Default::default(),
                     block_hash_lookup: &(),
+                    event_layouts: &event_layouts,
                 }
                 .into(),
                 NormalizedExtendedTxEnvelope::DepositedTx(tx) => DepositExecutionInput {
@@ -318,6 +320,7 @@ impl TestContext {
                     genesis_config: &self.genesis_config,
                     block_header: Default::default(),
                     block_hash_lookup: &(),
+                    event_layouts: &event_layouts,
                 }
                 .into(),
             }),
@@ -334,6 +337,7 @@ impl TestContext {
                     base_token: moved_base_token,
                     block_header: Default::default(),
                     block_hash_lookup: &(),
+                    event_layouts: &event_layouts,
                 }
                 .into(),
                 NormalizedExtendedTxEnvelope::DepositedTx(tx) => DepositExecutionInput {
@@ -344,6 +348,7 @@ impl TestContext {
                     genesis_config: &self.genesis_config,
                     block_header: Default::default(),
                     block_hash_lookup: &(),
+                    event_layouts: &event_layouts,
                 }
                 .into(),
             }),
@@ -361,7 +366,7 @@ impl TestContext {
             to: TxKind::Call(to),
             value: U256::from(amount),
             source_hash: FixedBytes::default(),
-            from: to,
+            from: DEPOSITOR_ADDRESS,
             mint: Some(amount.saturating_to()),
             gas_limit: u64::MAX,
             is_system_transaction: false,
@@ -638,7 +643,7 @@ impl TestContext {
     ///
     /// # Returns
     /// The compiled script bytes
-    fn compile_script(
+    pub(crate) fn compile_script(
         &self,
         script_name: &str,
         local_deps: &[&str],
@@ -0,0 +1,130 @@
+use {
+    crate::{
+        eth_token::{TOKEN_MODULE_NAME, TRANSFER_FUNCTION_NAME},
+        transaction::{ScriptOrDeployment, TransactionData},
+    },
+    move_core_types::{
+        identifier::{IdentStr, Identifier},
+        language_storage::ModuleId,
+    },
+    move_vm_runtime::session::Session,
+    moved_evm_ext::{
+        EVM_CALL_FN_NAME, EVM_CREATE_FN_NAME, EVM_DEPOSIT_FN_NAME, EVM_NATIVE_ADDRESS,
+        EVM_NATIVE_MODULE, EvmCallFrame, EvmCallTracer, FRAMEWORK_ADDRESS, NativeEVMContext,
+    },
+    std::fmt,
+};
+
+/// The calls made by a transaction, as recorded while it executed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionTrace {
+    pub calls: Vec<MoveCallFrame>,
+}
+
+/// A Move function or script called on behalf of a transaction, along with the EVM calls made
+/// through the EVM native while it ran.
+///
+/// The MoveVM reports nothing but the gas charged while it runs, so the Move functions called in
+/// turn are not broken down into frames of their own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveCallFrame {
+    pub call: MoveCall,
+    pub gas_used: u64,
+    /// Why the call failed, if it did.
+    pub error: Option<String>,
+    pub evm_calls: Vec<EvmCallFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveCall {
+    Function {
+        module: ModuleId,
+        function: Identifier,
+    },
+    Script,
+}
+
+impl MoveCall {
+    fn function(module: ModuleId, function: &IdentStr) -> Self {
+        Self::Function {
+            module,
+            function: function.to_owned(),
+        }
+    }
+
+    fn evm_native(function: &IdentStr) -> Self {
+        Self::function(
+            ModuleId::new(EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE.into()),
+            function,
+        )
+    }
+
+    /// The call a deposited transaction makes.
+    pub(crate) fn deposit() -> Self {
+        Self::evm_native(EVM_DEPOSIT_FN_NAME)
+    }
+
+    /// The call a canonical transaction with `tx_data` makes, if it calls anything at all. A
+    /// module deployment publishes the module without calling into it.
+    pub(crate) fn canonical(tx_data: &TransactionData) -> Option<Self> {
+        Some(match tx_data {
+            TransactionData::EntryFunction(entry_fn) => {
+                Self::function(entry_fn.module().clone(), entry_fn.function())
+            }
+            TransactionData::ScriptOrDeployment(ScriptOrDeployment::Script(_)) => Self::Script,
+            TransactionData::ScriptOrDeployment(ScriptOrDeployment::Module(_)) => return None,
+            TransactionData::ScriptOrDeployment(ScriptOrDeployment::EvmContract(_)) => {
+                Self::evm_native(EVM_CREATE_FN_NAME)
+            }
+            TransactionData::EoaBaseTokenTransfer(_) => Self::function(
+                ModuleId::new(FRAMEWORK_ADDRESS, TOKEN_MODULE_NAME.into()),
+                TRANSFER_FUNCTION_NAME,
+            ),
+            TransactionData::L2Contract(_) | TransactionData::EvmContract { .. } => {
+                Self::evm_native(EVM_CALL_FN_NAME)
+            }
+        })
+    }
+}
+
+impl fmt::Display for MoveCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Function { module, function } => {
+                write!(f, "{}::{function}", module.short_str_lossless())
+            }
+            Self::Script => f.write_str("script"),
+        }
+    }
+}
+
+/// Records the [`TransactionTrace`] of a transaction while it executes.
+#[derive(Debug, Default)]
+pub(crate) struct Tracer {
+    evm: EvmCallTracer,
+    trace: TransactionTrace,
+}
+
+impl Tracer {
+    /// Makes the EVM executions of the `session` record their call frames.
+    pub fn attach(&self, session: &mut Session) {
+        session
+            .get_native_extensions()
+            .get_mut::<NativeEVMContext>()
+            .tracer = Some(self.evm.clone());
+    }
+
+    /// Records a finished `call` along with the EVM calls made since the previous one.
+    pub fn record(&mut self, call: MoveCall, gas_used: u64, error: Option<String>) {
+        self.trace.calls.push(MoveCallFrame {
+            call,
+            gas_used,
+            error,
+            evm_calls: self.evm.take_frames(),
+        });
+    }
+
+    pub fn finish(self) -> TransactionTrace {
+        self.trace
+    }
+}
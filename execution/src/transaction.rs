@@ -6,12 +6,15 @@ use {
         },
         eips::eip2930::AccessList,
         primitives::{Address, B256, Bloom, Bytes, Log, LogData, TxKind, U256, address},
-        rpc::types::TransactionRequest,
+        rpc::types::{TransactionInput, TransactionRequest},
     },
     aptos_types::transaction::{EntryFunction, Module, Script},
     move_core_types::{
-        account_address::AccountAddress, effects::ChangeSet, language_storage::ModuleId,
+        account_address::AccountAddress,
+        effects::ChangeSet,
+        language_storage::{ModuleId, StructTag},
     },
+    move_table_extension::TableChangeSet,
     moved_shared::{
         error::{Error, InvalidTransactionCause, UserError},
         primitives::ToMoveAddress,
@@ -20,7 +23,7 @@ use {
         OpDepositReceipt, OpDepositReceiptWithBloom, OpReceiptEnvelope, OpTxEnvelope, TxDeposit,
     },
     serde::{Deserialize, Serialize},
-    std::borrow::Cow,
+    std::{borrow::Cow, fmt},
 };
 
 pub const L2_LOWEST_ADDRESS: Address = address!("4200000000000000000000000000000000000000");
@@ -116,15 +119,36 @@ impl NormalizedExtendedTxEnvelope {
 type MoveChanges = ChangeSet;
 type EvmChanges = moved_evm_ext::state::StorageTriesChanges;
 
-#[derive(Debug, Clone)]
 pub struct Changes {
     pub move_vm: MoveChanges,
     pub evm: EvmChanges,
+    /// Writes to Move tables, only kept once tables are part of the state, see
+    /// `MoveVmParameters::table_state_time`.
+    pub tables: TableChangeSet,
 }
 
 impl Changes {
     pub fn new(move_vm: MoveChanges, evm: EvmChanges) -> Self {
-        Self { move_vm, evm }
+        Self {
+            move_vm,
+            evm,
+            tables: TableChangeSet::default(),
+        }
+    }
+
+    pub fn with_tables(self, tables: TableChangeSet) -> Self {
+        Self { tables, ..self }
+    }
+}
+
+// The table changes do not implement `Debug`, so only the tables they touch are shown
+impl fmt::Debug for Changes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Changes")
+            .field("move_vm", &self.move_vm)
+            .field("evm", &self.evm)
+            .field("tables", &self.tables.changes.keys().collect::<Vec<_>>())
+            .finish()
     }
 }
 
@@ -152,6 +176,9 @@ pub struct TransactionExecutionOutcome {
     pub l2_price: U256,
     /// All emitted Move events converted to Ethereum logs.
     pub logs: Vec<Log<LogData>>,
+    /// The emitted Move events with their struct tags, which the logs only keep a hash of. Events
+    /// carrying EVM logs are left out.
+    pub events: Vec<MoveEvent>,
     /// AccountAddress + ModuleId of a deployed module (if any).
     pub deployment: Option<(AccountAddress, ModuleId)>,
 }
@@ -163,6 +190,7 @@ impl TransactionExecutionOutcome {
         gas_used: u64,
         l2_price: U256,
         logs: Vec<Log<LogData>>,
+        events: Vec<MoveEvent>,
         deployment: Option<(AccountAddress, ModuleId)>,
     ) -> Self {
         Self {
@@ -171,11 +199,20 @@ impl TransactionExecutionOutcome {
             gas_used,
             l2_price,
             logs,
+            events,
             deployment,
         }
     }
 }
 
+/// An event emitted by Move code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveEvent {
+    pub type_tag: StructTag,
+    /// The bcs encoded event.
+    pub data: Vec<u8>,
+}
+
 #[derive(Debug, Clone)]
 pub struct NormalizedEthTransaction {
     pub signer: Address,
@@ -290,6 +327,23 @@ pub enum ScriptOrDeployment {
 }
 
 /// Possible parsings of transaction data from a non-deposit transaction.
+///
+/// # Encoding
+///
+/// The data of a transaction comes in one of two encodings:
+/// * Unversioned, as sent by existing clients: the BCS encoding of the payload or, for a
+///   transaction that creates a contract, of a [`ScriptOrDeployment`].
+/// * Versioned: the [`ENVELOPE_PREFIX`] byte, a [`TransactionDataVersion`] byte and the BCS
+///   encoding of the payload of that version. The prefix never starts valid unversioned data, as
+///   no payload has that many kinds. Creating a contract takes the same payload as calling one.
+///   Data in this encoding is only accepted once the network activates it, see
+///   `MoveVmParameters::versioned_tx_data_time`, so that nodes agree on which data is valid.
+///
+/// The kind of a payload is its BCS variant index, which is an explicit discriminant of the
+/// encoding: kinds such as batched calls or sponsored transactions are added by appending a
+/// variant, and existing variants are never reordered or removed. Changing how an existing kind is
+/// laid out takes a new version instead, so that nodes keep decoding every version and the
+/// unversioned encoding alongside it.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TransactionData {
     EoaBaseTokenTransfer(Address),
@@ -299,8 +353,42 @@ pub enum TransactionData {
     EvmContract { address: Address, data: Vec<u8> },
 }
 
+/// First byte of transaction data in the versioned encoding of [`TransactionData`].
+pub const ENVELOPE_PREFIX: u8 = 0xff;
+
+/// Versions of the payload that follows [`ENVELOPE_PREFIX`] in transaction data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TransactionDataVersion {
+    V1 = 1,
+}
+
+impl TransactionDataVersion {
+    pub const LATEST: Self = Self::V1;
+
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(Self::V1),
+            _ => None,
+        }
+    }
+}
+
 impl TransactionData {
-    pub fn parse_from(tx: &NormalizedEthTransaction) -> moved_shared::error::Result<Self> {
+    /// Parses the data of `tx`, which may only be in the versioned encoding if
+    /// `accepts_versioned` tells that the encoding is active.
+    pub fn parse_from(
+        tx: &NormalizedEthTransaction,
+        accepts_versioned: bool,
+    ) -> moved_shared::error::Result<Self> {
+        let decode_versioned = |data: &[u8]| {
+            if accepts_versioned {
+                decode_envelope(data)
+            } else {
+                Ok(None)
+            }
+        };
+
         match tx.to {
             TxKind::Call(to) => {
                 if to.ge(&L2_LOWEST_ADDRESS) && to.le(&L2_HIGHEST_ADDRESS) {
@@ -310,7 +398,10 @@ impl TransactionData {
                     // transaction as a base token transfer between EOAs.
                     Ok(Self::EoaBaseTokenTransfer(to))
                 } else {
-                    let tx_data: SerializableTransactionData = bcs::from_bytes(&tx.data)?;
+                    let tx_data = match decode_versioned(&tx.data)? {
+                        Some(tx_data) => tx_data,
+                        None => bcs::from_bytes(&tx.data)?,
+                    };
                     // Inner value should be an entry function type or EVM contract.
                     match tx_data {
                         SerializableTransactionData::EntryFunction(entry_fn) => {
@@ -333,7 +424,13 @@ impl TransactionData {
             }
             TxKind::Create => {
                 // Assume EVM create type transactions are either scripts or module deployments
-                let script_or_module: ScriptOrDeployment = bcs::from_bytes(&tx.data)?;
+                let script_or_module = match decode_versioned(&tx.data)? {
+                    Some(SerializableTransactionData::ScriptOrDeployment(x)) => x.into_owned(),
+                    Some(_) => Err(InvalidTransactionCause::InvalidPayload(bcs::Error::Custom(
+                        "Expected script or deployment".to_string(),
+                    )))?,
+                    None => bcs::from_bytes(&tx.data)?,
+                };
                 Ok(Self::ScriptOrDeployment(script_or_module))
             }
         }
@@ -341,11 +438,25 @@ impl TransactionData {
 
     /// Serialize this type into bytes suitable for using in the `data` field of
     /// an Ethereum transaction.
+    ///
+    /// The bytes are in the unversioned encoding, see [`Self::to_versioned_bytes`].
     pub fn to_bytes(&self) -> Result<Vec<u8>, bcs::Error> {
         let serializable: SerializableTransactionData = self.into();
         bcs::to_bytes(&serializable)
     }
 
+    /// Serialize this type into the `data` of an Ethereum transaction in the latest version of
+    /// the versioned encoding.
+    ///
+    /// Unlike [`Self::to_bytes`], the bytes of a script or deployment are also suitable for a
+    /// transaction that creates a contract.
+    pub fn to_versioned_bytes(&self) -> Result<Vec<u8>, bcs::Error> {
+        let serializable: SerializableTransactionData = self.into();
+        let mut bytes = vec![ENVELOPE_PREFIX, TransactionDataVersion::LATEST as u8];
+        bcs::serialize_into(&mut bytes, &serializable)?;
+        Ok(bytes)
+    }
+
     pub fn maybe_entry_fn(&self) -> Option<&EntryFunction> {
         if let Self::EntryFunction(entry_fn) = self {
             Some(entry_fn)
@@ -384,6 +495,24 @@ impl From<TransactionRequest> for NormalizedEthTransaction {
     }
 }
 
+impl From<NormalizedEthTransaction> for TransactionRequest {
+    fn from(value: NormalizedEthTransaction) -> Self {
+        Self {
+            from: Some(value.signer),
+            to: Some(value.to),
+            nonce: Some(value.nonce),
+            value: Some(value.value),
+            chain_id: value.chain_id,
+            gas: Some(value.gas_limit()),
+            max_priority_fee_per_gas: Some(value.max_priority_fee_per_gas.saturating_to()),
+            max_fee_per_gas: Some(value.max_fee_per_gas.saturating_to()),
+            input: TransactionInput::new(value.data),
+            access_list: Some(value.access_list),
+            ..Default::default()
+        }
+    }
+}
+
 // Intentionally left private to hide the serialization details
 // from users of `TransactionData`. This allows making changes to
 // `TransactionData` itself while remaining backwards compatible with
@@ -392,19 +521,47 @@ impl From<TransactionRequest> for NormalizedEthTransaction {
 // from a reference to `TransactionData` without cloning while also allowing
 // deserializing to `SerializableTransactionData` with owned data.
 // Data type which are `Copy` are left without `Cow` references.
-#[derive(Deserialize, Serialize)]
+// The variant indices are the kinds of payload in both encodings, so new variants are only
+// ever appended. See the encoding of `TransactionData`.
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
 enum SerializableTransactionData<'a> {
+    // 0
     EoaBaseTokenTransfer(Address),
+    // 1
     ScriptOrDeployment(Cow<'a, ScriptOrDeployment>),
-    // Entry function should be the 3rd option to match the SDK TransactionPayload
+    // 2: Entry function should be the 3rd option to match the SDK TransactionPayload
     EntryFunction(Cow<'a, EntryFunction>),
+    // 3
     L2Contract(Address),
+    // 4
     // Note: we only include the data here not the address as in the `TransactionData` type
     // because the address is taken from the Ethereum transaction `to` field. Therefore
     // encoding it here would be redundant.
     EvmContract { data: Cow<'a, [u8]> },
 }
 
+/// Decodes the payload of transaction `data` in the versioned encoding, or returns `None` if the
+/// `data` is in the unversioned encoding.
+fn decode_envelope(
+    data: &[u8],
+) -> Result<Option<SerializableTransactionData<'static>>, bcs::Error> {
+    let [ENVELOPE_PREFIX, rest @ ..] = data else {
+        return Ok(None);
+    };
+    let [version, payload @ ..] = rest else {
+        return Err(bcs::Error::Custom(
+            "Missing transaction data version".to_string(),
+        ));
+    };
+
+    match TransactionDataVersion::from_byte(*version) {
+        Some(TransactionDataVersion::V1) => bcs::from_bytes(payload).map(Some),
+        None => Err(bcs::Error::Custom(format!(
+            "Unsupported transaction data version {version}"
+        ))),
+    }
+}
+
 impl<'a> From<&'a TransactionData> for SerializableTransactionData<'a> {
     fn from(value: &'a TransactionData) -> Self {
         match value {
@@ -427,8 +584,36 @@ mod tests {
             consensus::Sealed,
             primitives::{address, hex},
         },
+        move_core_types::ident_str,
     };
 
+    fn call_to(to: Address, data: Vec<u8>) -> NormalizedEthTransaction {
+        NormalizedEthTransaction {
+            signer: Address::ZERO,
+            to: TxKind::Call(to),
+            nonce: 0,
+            value: U256::ZERO,
+            data: data.into(),
+            chain_id: None,
+            gas_limit: U256::from(21_000),
+            max_priority_fee_per_gas: U256::ZERO,
+            max_fee_per_gas: U256::ZERO,
+            access_list: AccessList::default(),
+        }
+    }
+
+    fn entry_function(module_address: Address) -> TransactionData {
+        TransactionData::EntryFunction(EntryFunction::new(
+            ModuleId::new(
+                module_address.to_move_address(),
+                ident_str!("module").into(),
+            ),
+            ident_str!("function").into(),
+            Vec::new(),
+            Vec::new(),
+        ))
+    }
+
     #[test]
     fn test_deposited_tx_hash() {
         let tx = OpTxEnvelope::Deposit(Sealed::new(TxDeposit{
@@ -448,4 +633,106 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_effective_gas_price_is_capped_by_max_fee() {
+        let tx = |max_fee_per_gas: u64, max_priority_fee_per_gas: u64| NormalizedEthTransaction {
+            signer: Address::ZERO,
+            to: TxKind::Create,
+            nonce: 0,
+            value: U256::ZERO,
+            data: Bytes::new(),
+            chain_id: None,
+            gas_limit: U256::from(21_000),
+            max_priority_fee_per_gas: U256::from(max_priority_fee_per_gas),
+            max_fee_per_gas: U256::from(max_fee_per_gas),
+            access_list: AccessList::default(),
+        };
+        let base_fee = U256::from(100);
+
+        assert_eq!(tx(150, 20).effective_gas_price(base_fee), U256::from(120));
+        assert_eq!(tx(110, 20).effective_gas_price(base_fee), U256::from(110));
+        assert_eq!(tx(110, 20).tip_per_gas(base_fee), U256::from(10));
+        assert_eq!(tx(90, 20).effective_gas_price(base_fee), U256::from(90));
+        assert_eq!(tx(90, 20).tip_per_gas(base_fee), U256::ZERO);
+        // A legacy transaction pays its gas price
+        assert_eq!(tx(130, 130).effective_gas_price(base_fee), U256::from(130));
+    }
+
+    #[test]
+    fn test_payload_kinds_keep_their_discriminants() {
+        let script = ScriptOrDeployment::Script(Script::new(Vec::new(), Vec::new(), Vec::new()));
+        let TransactionData::EntryFunction(entry_fn) = entry_function(Address::ZERO) else {
+            unreachable!()
+        };
+        let kinds = [
+            SerializableTransactionData::EoaBaseTokenTransfer(Address::ZERO),
+            SerializableTransactionData::ScriptOrDeployment(Cow::Owned(script)),
+            SerializableTransactionData::EntryFunction(Cow::Owned(entry_fn)),
+            SerializableTransactionData::L2Contract(Address::ZERO),
+            SerializableTransactionData::EvmContract {
+                data: Cow::Owned(Vec::new()),
+            },
+        ];
+
+        for (discriminant, kind) in kinds.iter().enumerate() {
+            assert_eq!(bcs::to_bytes(kind).unwrap()[0], discriminant as u8);
+        }
+    }
+
+    #[test]
+    fn test_versioned_and_unversioned_data_parse_the_same() {
+        let to = Address::repeat_byte(0x11);
+        let data = entry_function(to);
+        let unversioned = call_to(to, data.to_bytes().unwrap());
+        let versioned = call_to(to, data.to_versioned_bytes().unwrap());
+
+        assert_eq!(versioned.data[..2], [ENVELOPE_PREFIX, 1]);
+        assert_eq!(
+            TransactionData::parse_from(&unversioned, true).unwrap(),
+            data
+        );
+        assert_eq!(TransactionData::parse_from(&versioned, true).unwrap(), data);
+    }
+
+    #[test]
+    fn test_versioned_deployment_parses_for_contract_creation() {
+        let deployment =
+            TransactionData::ScriptOrDeployment(ScriptOrDeployment::Module(Module::new(vec![
+                0xa1, 0x1c,
+            ])));
+        let mut tx = call_to(Address::ZERO, deployment.to_versioned_bytes().unwrap());
+        tx.to = TxKind::Create;
+
+        assert_eq!(TransactionData::parse_from(&tx, true).unwrap(), deployment);
+    }
+
+    #[test]
+    fn test_unsupported_data_version_is_invalid() {
+        let to = Address::repeat_byte(0x11);
+        let mut data = entry_function(to).to_versioned_bytes().unwrap();
+        data[1] = 2;
+
+        let error = TransactionData::parse_from(&call_to(to, data), true).unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::InvalidTransaction(InvalidTransactionCause::InvalidPayload(bcs::Error::Custom(
+                _
+            )))
+        ));
+    }
+    #[test]
+    fn test_versioned_data_is_invalid_until_activated() {
+        let to = Address::repeat_byte(0x11);
+        let data = entry_function(to);
+        let versioned = call_to(to, data.to_versioned_bytes().unwrap());
+        let unversioned = call_to(to, data.to_bytes().unwrap());
+
+        assert!(TransactionData::parse_from(&versioned, false).is_err());
+        assert_eq!(
+            TransactionData::parse_from(&unversioned, false).unwrap(),
+            data
+        );
+    }
 }
@@ -0,0 +1,87 @@
+use {
+    crate::{create_vm_session, eth_token, session_id::SessionId, transaction::Changes},
+    alloy::{eips::eip4895::Withdrawal, primitives::U256},
+    aptos_table_natives::TableResolver,
+    move_vm_runtime::{
+        AsUnsyncCodeStorage,
+        module_traversal::{TraversalContext, TraversalStorage},
+    },
+    move_vm_types::{gas::UnmeteredGasMeter, resolver::MoveResolver},
+    moved_evm_ext::{
+        HeaderForExecution,
+        events::EthTransfersLogger,
+        extract_evm_changes,
+        state::{BlockHashLookup, StorageTrieRepository},
+    },
+    moved_genesis::{CreateMoveVm, MovedVm, config::GenesisConfig},
+    moved_shared::primitives::{B256, ToMoveAddress},
+    moved_state::ResolverBasedModuleBytesStorage,
+};
+
+/// Withdrawal amounts are denominated in Gwei, see [EIP-4895](https://eips.ethereum.org/EIPS/eip-4895).
+const WEI_PER_GWEI: u64 = 1_000_000_000;
+
+#[derive(Debug)]
+pub struct WithdrawalExecutionInput<'input, S, ST, H> {
+    pub withdrawals: &'input [Withdrawal],
+    /// Root of the `withdrawals` trie, used as a unique identifier of the VM session.
+    pub withdrawals_root: &'input B256,
+    pub state: &'input S,
+    pub storage_trie: &'input ST,
+    pub genesis_config: &'input GenesisConfig,
+    pub block_header: HeaderForExecution,
+    pub block_hash_lookup: &'input H,
+}
+
+/// Credits the amount of each withdrawal to its recipient by minting the base token.
+///
+/// Withdrawals are not transactions, so they cost no gas and cannot fail for a user-facing
+/// reason. Any error is an invariant violation.
+pub fn execute_withdrawals<
+    S: MoveResolver + TableResolver,
+    ST: StorageTrieRepository,
+    H: BlockHashLookup,
+>(
+    input: WithdrawalExecutionInput<S, ST, H>,
+) -> moved_shared::error::Result<Changes> {
+    let moved_vm = MovedVm::new(input.genesis_config);
+    let module_bytes_storage = ResolverBasedModuleBytesStorage::new(input.state);
+    let code_storage = module_bytes_storage.as_unsync_code_storage(&moved_vm);
+    let vm = moved_vm.create_move_vm()?;
+    let session_id = SessionId::new_from_withdrawals(
+        input.withdrawals_root,
+        input.genesis_config,
+        input.block_header,
+    );
+    let eth_transfers_log = EthTransfersLogger::default();
+    let mut session = create_vm_session(
+        &vm,
+        input.state,
+        session_id,
+        input.storage_trie,
+        &eth_transfers_log,
+        input.block_hash_lookup,
+    );
+    let traversal_storage = TraversalStorage::new();
+    let mut traversal_context = TraversalContext::new(&traversal_storage);
+    let mut gas_meter = UnmeteredGasMeter;
+
+    for withdrawal in input.withdrawals {
+        eth_token::mint_eth(
+            &withdrawal.address.to_move_address(),
+            U256::from(withdrawal.amount).saturating_mul(U256::from(WEI_PER_GWEI)),
+            &mut session,
+            &mut traversal_context,
+            &mut gas_meter,
+            &code_storage,
+        )?;
+    }
+
+    let (mut changes, extensions) = session.finish_with_extensions(&code_storage)?;
+    let evm_changes = extract_evm_changes(&extensions);
+    changes
+        .squash(evm_changes.accounts)
+        .expect("EVM changes must merge with other session changes");
+
+    Ok(Changes::new(changes, evm_changes.storage))
+}
@@ -6,26 +6,40 @@
 //! the Optimism bridge (i.e. did not use the standard bridge).
 
 use {
+    crate::{
+        CreateMoveVm, FRAMEWORK_ADDRESS, MovedVm, config::GenesisConfig,
+        prefunded::native_extensions,
+    },
     alloy::{
         dyn_abi::DynSolValue,
-        primitives::{Address, U256, address},
+        primitives::{Address, B256, U256, address},
     },
     anyhow::{Context, Result},
+    aptos_types::account_address::create_object_address,
     bytes::Bytes,
-    move_binary_format::errors::PartialVMResult,
+    move_binary_format::errors::{PartialVMResult, VMError},
     move_core_types::{
         account_address::AccountAddress,
         effects::ChangeSet,
+        ident_str,
         language_storage::{ModuleId, StructTag},
         metadata::Metadata,
-        value::MoveTypeLayout,
+        value::{MoveTypeLayout, MoveValue},
+    },
+    move_vm_runtime::{
+        AsUnsyncCodeStorage,
+        module_traversal::{TraversalContext, TraversalStorage},
+    },
+    move_vm_types::{
+        gas::UnmeteredGasMeter,
+        resolver::{ModuleResolver, ResourceResolver},
     },
-    move_vm_types::resolver::{ModuleResolver, ResourceResolver},
     moved_evm_ext::{
         Changes, HeaderForExecution, NativeEVMContext, evm_transact_with_native,
         extract_evm_changes_from_native,
         state::{InMemoryStorageTrieRepository, StorageTrieRepository},
     },
+    moved_state::{ResolverBasedModuleBytesStorage, State},
     std::{
         fs::{read_dir, read_to_string},
         path::Path,
@@ -35,13 +49,24 @@ use {
 const FACTORY_ADDRESS: Address = address!("4200000000000000000000000000000000000012");
 /// createOptimismMintableERC20WithDecimals selector
 const SELECTOR: [u8; 4] = [0x8c, 0xf0, 0x62, 0x9c];
+/// Key of this network among the per-chain entries of a token in the Superchain Token List.
+const L2_NETWORK_KEY: &str = "umi";
+/// Longest name in bytes that `fungible_asset_u256` accepts in the metadata of a token.
+const MAX_METADATA_NAME_LENGTH: usize = 32;
+/// Longest symbol in bytes that `fungible_asset_u256` accepts in the metadata of a token.
+const MAX_METADATA_SYMBOL_LENGTH: usize = 10;
+const MAX_METADATA_DECIMALS: u8 = 32;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BridgedToken {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
+    /// Address of the original token on L1.
     pub ethereum_address: Address,
+    /// Address of the token deployed by `OptimismMintableERC20Factory` at genesis, if the list
+    /// declares one. Deployment fails if the declared address does not match the actual one.
+    pub l2_address: Option<Address>,
 }
 
 pub fn parse_token_list(path: &Path) -> Result<Vec<BridgedToken>> {
@@ -90,6 +115,7 @@ pub fn deploy_bridged_tokens(
     // it is safe to put the no-op impl here for block hash lookup.
     let mut ctx = NativeEVMContext::new(&resolver, &trie_storage, &(), block_header, &());
     for token in tokens {
+        let declared_l2_address = token.l2_address;
         let data = encode_params(token);
         let outcome = evm_transact_with_native(
             &mut ctx,
@@ -105,6 +131,19 @@ pub fn deploy_bridged_tokens(
         if !outcome.result.is_success() {
             anyhow::bail!("Bridged token deployment failed: EVM outcome");
         }
+        // The factory returns the address of the newly deployed token
+        let deployed_l2_address = outcome
+            .result
+            .output()
+            .and_then(|output| output.get(..32))
+            .map(|word| Address::from_word(B256::from_slice(word)))
+            .context("Bridged token deployment failed: missing token address")?;
+        if let Some(declared_l2_address) = declared_l2_address {
+            anyhow::ensure!(
+                declared_l2_address == deployed_l2_address,
+                "Bridged token deployed at {deployed_l2_address}, but declared at {declared_l2_address}"
+            );
+        }
     }
     let new_changes = extract_evm_changes_from_native(&ctx);
     l2_changes.accounts.squash(new_changes.accounts)?;
@@ -114,6 +153,109 @@ pub fn deploy_bridged_tokens(
     Ok(l2_changes)
 }
 
+/// Checks that `token` fits the limits of the Move fungible asset metadata, which are tighter than
+/// those of the ERC-20, so that [`deploy_token_metadata`] does not abort at genesis.
+pub fn validate_token_metadata(token: &BridgedToken) -> Result<()> {
+    anyhow::ensure!(
+        token.name.len() <= MAX_METADATA_NAME_LENGTH,
+        "Name of bridged token {} is longer than {MAX_METADATA_NAME_LENGTH} bytes: {}",
+        token.ethereum_address,
+        token.name,
+    );
+    anyhow::ensure!(
+        token.symbol.len() <= MAX_METADATA_SYMBOL_LENGTH,
+        "Symbol of bridged token {} is longer than {MAX_METADATA_SYMBOL_LENGTH} bytes: {}",
+        token.ethereum_address,
+        token.symbol,
+    );
+    anyhow::ensure!(
+        token.decimals <= MAX_METADATA_DECIMALS,
+        "Bridged token {} has more than {MAX_METADATA_DECIMALS} decimals",
+        token.ethereum_address,
+    );
+
+    Ok(())
+}
+
+/// Address of the object holding the Move fungible asset metadata of `token`, named after its L1
+/// address.
+pub fn token_metadata_address(token: &BridgedToken) -> AccountAddress {
+    create_object_address(FRAMEWORK_ADDRESS, token.ethereum_address.as_slice())
+}
+
+/// Creates the Move fungible asset metadata of each bridged token, so that Move code sees the same
+/// name, symbol and decimals as the ERC-20 deployed by [`deploy_bridged_tokens`].
+///
+/// The `state` must already contain the framework and the tokens must pass
+/// [`validate_token_metadata`].
+pub fn deploy_token_metadata(
+    moved_vm: &MovedVm,
+    config: &GenesisConfig,
+    state: &impl State,
+) -> Result<ChangeSet, VMError> {
+    let vm = moved_vm.create_move_vm()?;
+    let mut session =
+        vm.new_session_with_extensions(state.resolver(), native_extensions(config, state));
+    let traversal_storage = TraversalStorage::new();
+    let mut traversal_context = TraversalContext::new(&traversal_storage);
+    let module_bytes_storage = ResolverBasedModuleBytesStorage::new(state.resolver());
+    let code_storage = module_bytes_storage.as_unsync_code_storage(moved_vm);
+    let object_module = ModuleId::new(FRAMEWORK_ADDRESS, ident_str!("object").into());
+    let store_module = ModuleId::new(
+        FRAMEWORK_ADDRESS,
+        ident_str!("primary_fungible_store_u256").into(),
+    );
+    let admin_arg = bcs::to_bytes(&MoveValue::Signer(FRAMEWORK_ADDRESS))
+        .expect("Serialization of constant must succeed");
+    // An `Option` is a vector of at most one element, and the supply of bridged tokens is bounded
+    // by the L1 token instead
+    let max_supply_arg = bcs::to_bytes(&MoveValue::Vector(Vec::new()))
+        .expect("Serialization of constant must succeed");
+    let uri_arg = bcs::to_bytes("").expect("Serialization of constant must succeed");
+
+    for token in &config.token_list {
+        let seed_arg =
+            bcs::to_bytes(token.ethereum_address.as_slice()).expect("seed can serialize");
+        let constructor_ref = session
+            .execute_function_bypass_visibility(
+                &object_module,
+                ident_str!("create_named_object"),
+                Vec::new(),
+                vec![admin_arg.as_slice(), seed_arg.as_slice()],
+                &mut UnmeteredGasMeter,
+                &mut traversal_context,
+                &code_storage,
+            )?
+            .return_values
+            .pop()
+            .map(|(bytes, _)| bytes)
+            .expect("Creating an object returns its constructor");
+        let name_arg = bcs::to_bytes(&token.name).expect("name can serialize");
+        let symbol_arg = bcs::to_bytes(&token.symbol).expect("symbol can serialize");
+        let decimals_arg = bcs::to_bytes(&token.decimals).expect("decimals can serialize");
+
+        session.execute_function_bypass_visibility(
+            &store_module,
+            ident_str!("create_primary_store_enabled_fungible_asset"),
+            Vec::new(),
+            vec![
+                constructor_ref.as_slice(),
+                max_supply_arg.as_slice(),
+                name_arg.as_slice(),
+                symbol_arg.as_slice(),
+                decimals_arg.as_slice(),
+                uri_arg.as_slice(),
+                uri_arg.as_slice(),
+            ],
+            &mut UnmeteredGasMeter,
+            &mut traversal_context,
+            &code_storage,
+        )?;
+    }
+
+    session.finish(&code_storage)
+}
+
 fn encode_params(token: BridgedToken) -> Vec<u8> {
     [
         SELECTOR.as_slice(),
@@ -209,11 +351,23 @@ fn parse_json(json: &serde_json::Value) -> Result<Option<BridgedToken>> {
         anyhow::bail!("Invalid value for token decimals");
     }
 
+    let l2_address = tokens
+        .get(L2_NETWORK_KEY)
+        .and_then(|v| v.get("address"))
+        .map(|v| {
+            v.as_str()
+                .ok_or_else(|| anyhow::anyhow!("L2 address must be a string"))?
+                .parse::<Address>()
+                .map_err(anyhow::Error::from)
+        })
+        .transpose()?;
+
     Ok(Some(BridgedToken {
         name: name.into(),
         symbol: symbol.into(),
         decimals: decimals as u8,
         ethereum_address,
+        l2_address,
     }))
 }
 
@@ -298,3 +452,117 @@ fn test_deploy_bridged_tokens() {
         .filter(|address| !l2_changes.storage.tries.contains_key(*address));
     assert_eq!(added_addresses.count(), n_bridged_tokens);
 }
+
+#[test]
+fn test_parse_json_reads_declared_l2_address() {
+    let json = serde_json::json!({
+        "name": "Aave Token",
+        "symbol": "AAVE",
+        "decimals": 18,
+        "tokens": {
+            "ethereum": {
+                "address": "0x7Fc66500c84A76Ad7e9c93437bFc5Ac33E2DDaE9"
+            },
+            "optimism": {
+                "address": "0x76FB31fb4af56892A25e32cFC43De717950c9278"
+            },
+            "umi": {
+                "address": "0x1111111111111111111111111111111111111111"
+            }
+        }
+    });
+
+    let token = parse_json(&json).unwrap().unwrap();
+
+    assert_eq!(
+        token.l2_address,
+        Some(address!("1111111111111111111111111111111111111111"))
+    );
+}
+
+#[test]
+fn test_deploy_bridged_tokens_rejects_mismatched_l2_address() {
+    let config = crate::config::GenesisConfig::default();
+    let state = moved_state::InMemoryState::default();
+    let storage = InMemoryStorageTrieRepository::new();
+    let l2_changes = crate::l2_contracts::init_state(config.l2_contract_genesis, &state, &storage);
+    let mut tokens = config.token_list;
+    tokens[0].l2_address = Some(address!("1111111111111111111111111111111111111111"));
+
+    assert!(deploy_bridged_tokens(l2_changes, tokens).is_err());
+}
+
+#[test]
+fn test_deploy_token_metadata_creates_metadata_objects() {
+    let config = crate::config::GenesisConfig::default();
+    let vm = MovedVm::new(&config);
+    let framework_changes =
+        crate::framework::init_state(&vm, &mut moved_state::InMemoryState::default());
+    let mut state = moved_state::InMemoryState::default();
+    state.apply(framework_changes).unwrap();
+
+    let changes = deploy_token_metadata(&vm, &config, &state).unwrap();
+
+    for token in &config.token_list {
+        assert!(
+            changes
+                .accounts()
+                .contains_key(&token_metadata_address(token))
+        );
+    }
+}
+
+#[test]
+fn test_deploy_token_metadata_stores_name_symbol_and_decimals() {
+    let mut config = crate::config::GenesisConfig::default();
+    config.token_list.truncate(1);
+    config.token_list[0].decimals = 6;
+    let token = config.token_list[0].clone();
+    let vm = MovedVm::new(&config);
+    let framework_changes =
+        crate::framework::init_state(&vm, &mut moved_state::InMemoryState::default());
+    let mut state = moved_state::InMemoryState::default();
+    state.apply(framework_changes).unwrap();
+    let metadata_tag = StructTag {
+        address: FRAMEWORK_ADDRESS,
+        module: ident_str!("fungible_asset_u256").into(),
+        name: ident_str!("Metadata").into(),
+        type_args: Vec::new(),
+    };
+
+    let changes = deploy_token_metadata(&vm, &config, &state).unwrap();
+
+    let bytes = changes.accounts()[&token_metadata_address(&token)].resources()[&metadata_tag]
+        .clone()
+        .ok()
+        .unwrap();
+    // The fields of `Metadata` are the name, symbol, decimals, icon URI and project URI
+    let (name, symbol, decimals, _, _): (String, String, u8, String, String) =
+        bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(name, token.name);
+    assert_eq!(symbol, token.symbol);
+    assert_eq!(decimals, 6);
+}
+
+#[test]
+fn test_validate_token_metadata_rejects_values_over_move_limits() {
+    let token = crate::config::GenesisConfig::default().token_list[0].clone();
+    assert!(validate_token_metadata(&token).is_ok());
+
+    let long_name = BridgedToken {
+        name: "x".repeat(MAX_METADATA_NAME_LENGTH + 1),
+        ..token.clone()
+    };
+    let long_symbol = BridgedToken {
+        symbol: "x".repeat(MAX_METADATA_SYMBOL_LENGTH + 1),
+        ..token.clone()
+    };
+    let many_decimals = BridgedToken {
+        decimals: MAX_METADATA_DECIMALS + 1,
+        ..token
+    };
+
+    for token in [long_name, long_symbol, many_decimals] {
+        assert!(validate_token_metadata(&token).is_err());
+    }
+}
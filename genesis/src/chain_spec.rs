@@ -0,0 +1,102 @@
+//! Chain specifications bundled into the binary, so that a node joins a known network by its name
+//! instead of having a genesis config file assembled for it.
+//!
+//! The public testnet and mainnet are known by name, but their genesis parameters are not published
+//! yet, so they cannot be bundled. Selecting them fails with an error saying so, rather than with
+//! the error for a missing genesis config file. They still run from a genesis config file given by
+//! its path.
+
+use {
+    crate::config::GenesisConfig,
+    anyhow::{Context, bail},
+    std::path::Path,
+};
+
+/// Names of the bundled chain specifications.
+pub const CHAIN_NAMES: [&str; 2] = ["dev", "devnet"];
+
+/// Names of the public networks, whose specifications are bundled once their genesis is final.
+pub const UNPUBLISHED_CHAIN_NAMES: [&str; 2] = ["testnet", "mainnet"];
+
+/// Chain ID of the devnet, which the docker setup runs as well.
+pub const DEVNET_CHAIN_ID: u64 = 42069;
+
+/// Returns the bundled configuration of the chain called `name`, if there is one.
+pub fn by_name(name: &str) -> Option<GenesisConfig> {
+    match name {
+        // A single node chain with the built-in defaults and the L2 contracts used by the tests
+        "dev" => Some(GenesisConfig::default()),
+        // A multi-node chain for testing deployments, which only differs from dev by its chain ID
+        // and so starts from the same genesis state
+        "devnet" => Some(GenesisConfig {
+            chain_id: DEVNET_CHAIN_ID,
+            ..GenesisConfig::default()
+        }),
+        _ => None,
+    }
+}
+
+/// Resolves `chain` to the bundled chain specification with that name or, failing that, to the
+/// genesis config file at that path.
+///
+/// A file with the same name as a bundled chain needs a path that does not look like that name,
+/// such as `./dev`.
+pub fn resolve(chain: &str) -> anyhow::Result<GenesisConfig> {
+    if let Some(config) = by_name(chain) {
+        return Ok(config);
+    }
+    if UNPUBLISHED_CHAIN_NAMES.contains(&chain) && !Path::new(chain).exists() {
+        bail!("The {chain} genesis is not published yet, give the path to its genesis config file");
+    }
+
+    GenesisConfig::from_file(Path::new(chain)).context(format!(
+        "Chain should be one of {CHAIN_NAMES:?} or a path to a genesis config file"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_chain_name_is_bundled() {
+        for name in CHAIN_NAMES {
+            assert!(by_name(name).is_some(), "{name} should be bundled");
+        }
+    }
+
+    #[test]
+    fn test_chain_resolves_to_bundled_spec_before_file() {
+        let config = resolve("dev").unwrap();
+
+        assert_eq!(config.chain_id, GenesisConfig::default().chain_id);
+    }
+
+    #[test]
+    fn test_devnet_only_differs_from_dev_by_chain_id() {
+        let devnet = resolve("devnet").unwrap();
+
+        assert_eq!(devnet.chain_id, DEVNET_CHAIN_ID);
+        assert_eq!(
+            devnet.initial_state_root,
+            GenesisConfig::default().initial_state_root
+        );
+        assert_eq!(devnet.hardforks, GenesisConfig::default().hardforks);
+    }
+
+    #[test]
+    fn test_unpublished_chain_is_not_bundled() {
+        for name in UNPUBLISHED_CHAIN_NAMES {
+            let error = resolve(name).unwrap_err();
+
+            assert!(format!("{error:#}").contains("not published"), "{error:#}");
+        }
+    }
+
+    #[test]
+    fn test_unknown_chain_without_file_is_invalid() {
+        let error = resolve("unknown-chain").unwrap_err();
+
+        assert!(format!("{error:#}").contains("\"dev\""));
+    }
+}
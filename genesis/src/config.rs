@@ -1,16 +1,55 @@
 use {
-    crate::bridged_tokens::{self, BridgedToken},
-    alloy::{genesis::Genesis, primitives::hex},
+    crate::{
+        bridged_tokens::{self, BridgedToken},
+        framework::is_framework_address,
+        preinstalls::missing_preinstalls,
+    },
+    alloy::{
+        genesis::{Genesis, GenesisAccount},
+        primitives::hex,
+    },
+    anyhow::{Context, ensure},
     aptos_gas_schedule::{InitialGasSchedule, NativeGasParameters, VMGasParameters},
     aptos_vm_types::storage::StorageGasParameters,
+    bytes::Bytes,
     move_core_types::{account_address::AccountAddress, gas_algebra::GasQuantity},
-    moved_shared::primitives::B256,
-    std::path::Path,
+    moved_shared::primitives::{Address, B256},
+    std::{
+        collections::BTreeMap,
+        fs,
+        num::NonZeroUsize,
+        path::{Path, PathBuf},
+    },
 };
 
 pub const CHAIN_ID: u64 = 404;
 const DEFAULT_L2_CONTRACT_GENESIS: &str =
     include_str!("../../execution/src/tests/res/l2_genesis_tests.json");
+const DEFAULT_INITIAL_STATE_ROOT: B256 = B256::new(hex!(
+    "4805267476cb522274ec2fe790b4dc6e889ed0d57377f90770d4a658f6b8e4ae"
+));
+const DEFAULT_TREASURY: AccountAddress = AccountAddress::ONE; // todo: fill in the real address
+const DEFAULT_EIP1559_ELASTICITY_MULTIPLIER: u64 = 6;
+const DEFAULT_EIP1559_BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 250;
+const DEFAULT_GAS_PRICE_ORACLE_BLOCKS: u64 = 20;
+const DEFAULT_GAS_PRICE_ORACLE_PERCENTILE: u8 = 60;
+// Same as the default minimum suggested priority fee of op-geth
+const DEFAULT_MIN_PRIORITY_FEE: u128 = 1_000_000;
+// Same as the default of the `--gpo.maxprice` flag of geth, 500 gwei
+const DEFAULT_MAX_PRIORITY_FEE: u128 = 500_000_000_000;
+// Same as the default of the `--txpool.pricebump` flag of geth
+const DEFAULT_PRICE_BUMP_PERCENT: u64 = 10;
+// Same as the defaults of the `--txpool.accountqueue`, `--txpool.globalqueue` and `--txpool.lifetime`
+// flags of geth
+const DEFAULT_MAX_QUEUED_PER_ACCOUNT: usize = 64;
+const DEFAULT_MAX_QUEUED: usize = 1_024;
+const DEFAULT_QUEUED_LIFETIME_SECS: u64 = 3 * 60 * 60;
+// Same as the defaults of the `--rpc.gascap` and `--rpc.evmtimeout` flags of geth
+const DEFAULT_SIMULATION_GAS_CAP: u64 = 50_000_000;
+const DEFAULT_SIMULATION_TIMEOUT_MS: u64 = 5_000;
+/// Most threads that hash tries by default, as block building has only two roots to build at once.
+const DEFAULT_TRIE_HASHING_THREADS: usize = 2;
+const MOVE_BYTECODE_EXTENSION: &str = "mv";
 
 // We're setting the scale factor lower than Aptos because we want
 // our gas costs to align with expected values for EVM chains.
@@ -37,19 +76,673 @@ pub struct GasCosts {
     pub vm: VMGasParameters,
     pub storage: StorageGasParameters,
     pub natives: NativeGasParameters,
+    pub storage_write: StorageWriteGasParameters,
 
     pub version: u64,
 }
 
+/// Gas charged for the resources a transaction writes, on top of the gas of executing it, so that
+/// state growth is priced. Writes are free unless configured.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct StorageWriteGasParameters {
+    /// Gas for every resource the transaction creates.
+    pub per_slot_created: u64,
+    /// Gas for every byte of the resources the transaction creates or modifies.
+    pub per_byte_written: u64,
+}
+
+/// Activation timestamps of the OP Stack hardforks, `None` meaning the fork is not scheduled.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct HardforkSchedule {
+    pub regolith_time: Option<u64>,
+    pub canyon_time: Option<u64>,
+    pub delta_time: Option<u64>,
+    pub ecotone_time: Option<u64>,
+    pub fjord_time: Option<u64>,
+    pub granite_time: Option<u64>,
+    pub holocene_time: Option<u64>,
+    pub isthmus_time: Option<u64>,
+    pub interop_time: Option<u64>,
+}
+
+impl HardforkSchedule {
+    /// Hardforks in the order they must activate in, paired with their activation time.
+    pub fn in_activation_order(&self) -> [(&'static str, Option<u64>); 9] {
+        [
+            ("regolith", self.regolith_time),
+            ("canyon", self.canyon_time),
+            ("delta", self.delta_time),
+            ("ecotone", self.ecotone_time),
+            ("fjord", self.fjord_time),
+            ("granite", self.granite_time),
+            ("holocene", self.holocene_time),
+            ("isthmus", self.isthmus_time),
+            ("interop", self.interop_time),
+        ]
+    }
+
+    pub fn is_regolith_active(&self, timestamp: u64) -> bool {
+        self.regolith_time.is_some_and(|time| time <= timestamp)
+    }
+
+    pub fn is_ecotone_active(&self, timestamp: u64) -> bool {
+        self.ecotone_time.is_some_and(|time| time <= timestamp)
+    }
+
+    pub fn is_isthmus_active(&self, timestamp: u64) -> bool {
+        self.isthmus_time.is_some_and(|time| time <= timestamp)
+    }
+
+    pub fn is_interop_active(&self, timestamp: u64) -> bool {
+        self.interop_time.is_some_and(|time| time <= timestamp)
+    }
+
+    /// A hardfork cannot be scheduled before, or without, any of the hardforks preceding it.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let forks = self.in_activation_order();
+
+        for ((previous, previous_time), (next, next_time)) in forks.iter().zip(&forks[1..]) {
+            let Some(next_time) = next_time else {
+                continue;
+            };
+            let previous_time = previous_time
+                .with_context(|| format!("{next} is scheduled, but {previous} is not"))?;
+            ensure!(
+                previous_time <= *next_time,
+                "{next} is scheduled at {next_time}, before {previous} at {previous_time}"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for HardforkSchedule {
+    /// Every hardfork up to and including Holocene is active from genesis.
+    fn default() -> Self {
+        Self {
+            regolith_time: Some(0),
+            canyon_time: Some(0),
+            delta_time: Some(0),
+            ecotone_time: Some(0),
+            fjord_time: Some(0),
+            granite_time: Some(0),
+            holocene_time: Some(0),
+            isthmus_time: None,
+            interop_time: None,
+        }
+    }
+}
+
+/// Parameters of the EIP-1559 base fee adjustment.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FeeParameters {
+    pub eip1559_elasticity_multiplier: u64,
+    pub eip1559_base_fee_max_change_denominator: u64,
+}
+
+impl FeeParameters {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.eip1559_elasticity_multiplier > 0,
+            "EIP-1559 elasticity multiplier must be positive"
+        );
+        ensure!(
+            self.eip1559_base_fee_max_change_denominator > 0,
+            "EIP-1559 base fee max change denominator must be positive"
+        );
+
+        Ok(())
+    }
+}
+
+impl Default for FeeParameters {
+    fn default() -> Self {
+        Self {
+            eip1559_elasticity_multiplier: DEFAULT_EIP1559_ELASTICITY_MULTIPLIER,
+            eip1559_base_fee_max_change_denominator:
+                DEFAULT_EIP1559_BASE_FEE_MAX_CHANGE_DENOMINATOR,
+        }
+    }
+}
+
+/// Parameters of the priority fee suggested to users by `eth_maxPriorityFeePerGas` and
+/// `eth_gasPrice`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GasPriceOracleParameters {
+    /// Number of the most recent blocks whose priority fees are sampled.
+    pub blocks: u64,
+    /// Percentile of the sampled priority fees that is suggested.
+    pub percentile: u8,
+    /// Lowest priority fee ever suggested, in wei.
+    pub min_priority_fee: u128,
+    /// Highest priority fee ever suggested, in wei, so that a few transactions paying outsized tips
+    /// do not drive up the suggestion for everyone else.
+    #[serde(default = "default_max_priority_fee")]
+    pub max_priority_fee: u128,
+}
+
+fn default_max_priority_fee() -> u128 {
+    DEFAULT_MAX_PRIORITY_FEE
+}
+
+impl GasPriceOracleParameters {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(self.blocks > 0, "Gas price oracle must sample some blocks");
+        ensure!(
+            self.percentile <= 100,
+            "Gas price oracle percentile must be at most 100"
+        );
+        ensure!(
+            self.min_priority_fee <= self.max_priority_fee,
+            "Gas price oracle minimum priority fee must not exceed its maximum"
+        );
+
+        Ok(())
+    }
+}
+
+impl Default for GasPriceOracleParameters {
+    fn default() -> Self {
+        Self {
+            blocks: DEFAULT_GAS_PRICE_ORACLE_BLOCKS,
+            percentile: DEFAULT_GAS_PRICE_ORACLE_PERCENTILE,
+            min_priority_fee: DEFAULT_MIN_PRIORITY_FEE,
+            max_priority_fee: DEFAULT_MAX_PRIORITY_FEE,
+        }
+    }
+}
+
+/// Limits of the read-only executions run by `eth_call` and `eth_estimateGas`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SimulationParameters {
+    /// Most gas a simulated transaction may use, replacing any higher or missing gas limit.
+    pub gas_cap: u64,
+    /// Longest time a simulation may run for, in milliseconds.
+    pub timeout_ms: u64,
+}
+
+impl SimulationParameters {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(self.gas_cap > 0, "Simulation gas cap must not be zero");
+        ensure!(self.timeout_ms > 0, "Simulation timeout must not be zero");
+
+        Ok(())
+    }
+}
+
+impl Default for SimulationParameters {
+    fn default() -> Self {
+        Self {
+            gas_cap: DEFAULT_SIMULATION_GAS_CAP,
+            timeout_ms: DEFAULT_SIMULATION_TIMEOUT_MS,
+        }
+    }
+}
+
+/// Sizes of the thread pools used in block building, to match the node to the machine it runs on.
+///
+/// They only change how fast blocks are built, never their contents.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ConcurrencyParameters {
+    /// Threads recovering the senders of the transactions that the payload attributes of a block
+    /// include, one per CPU by default. Transactions in the mem-pool are recovered as they enter
+    /// it.
+    pub sender_recovery_threads: usize,
+    /// Threads building the transactions and receipts roots of a block, at most one per CPU by
+    /// default.
+    pub trie_hashing_threads: usize,
+}
+
+impl ConcurrencyParameters {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.sender_recovery_threads > 0,
+            "Sender recovery needs at least one thread"
+        );
+        ensure!(
+            self.trie_hashing_threads > 0,
+            "Trie hashing needs at least one thread"
+        );
+
+        Ok(())
+    }
+}
+
+impl Default for ConcurrencyParameters {
+    fn default() -> Self {
+        let cpus = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+        Self {
+            sender_recovery_threads: cpus,
+            trie_hashing_threads: cpus.min(DEFAULT_TRIE_HASHING_THREADS),
+        }
+    }
+}
+
+/// How far back the state of past blocks stays queryable.
+///
+/// Blocks below the horizon are left to pruning, so queries of their state fail even on a backend
+/// that still happens to have it. That way every node of a network answers the same queries.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct StateHistoryParameters {
+    /// Number of most recent blocks whose state is kept, all of them if `None`.
+    pub retained_blocks: Option<u64>,
+}
+
+impl StateHistoryParameters {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.retained_blocks != Some(0),
+            "The state of at least the latest block must be retained"
+        );
+
+        Ok(())
+    }
+
+    /// Returns the lowest height whose state is kept while `head` is the latest block.
+    pub fn horizon(&self, head: u64) -> u64 {
+        self.retained_blocks
+            .map_or(0, |retained| (head + 1).saturating_sub(retained))
+    }
+}
+
+/// Fees a transaction must offer to be accepted by `eth_sendRawTransaction`. The lowest ones apply
+/// regardless of the base fee of the next block, and are in wei per gas.
+///
+/// Also bounds the transactions that wait in the mem-pool behind a nonce gap of their sender, which
+/// would otherwise pile up for free.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TxAdmissionParameters {
+    /// Lowest max fee per gas, or gas price of a legacy transaction.
+    pub min_fee_per_gas: u128,
+    /// Lowest max priority fee per gas, or gas price of a legacy transaction.
+    pub min_priority_fee_per_gas: u128,
+    /// How many percent a transaction must raise both fees of the mem-pool transaction with the
+    /// same sender and nonce by to replace it.
+    #[serde(default = "default_price_bump_percent")]
+    pub price_bump_percent: u64,
+    /// Most transactions of a single sender waiting behind a nonce gap.
+    #[serde(default = "default_max_queued_per_account")]
+    pub max_queued_per_account: usize,
+    /// Most transactions of all senders waiting behind a nonce gap.
+    #[serde(default = "default_max_queued")]
+    pub max_queued: usize,
+    /// Seconds a transaction may wait behind a nonce gap before it is dropped.
+    #[serde(default = "default_queued_lifetime_secs")]
+    pub queued_lifetime_secs: u64,
+}
+
+fn default_price_bump_percent() -> u64 {
+    DEFAULT_PRICE_BUMP_PERCENT
+}
+
+fn default_max_queued_per_account() -> usize {
+    DEFAULT_MAX_QUEUED_PER_ACCOUNT
+}
+
+fn default_max_queued() -> usize {
+    DEFAULT_MAX_QUEUED
+}
+
+fn default_queued_lifetime_secs() -> u64 {
+    DEFAULT_QUEUED_LIFETIME_SECS
+}
+
+impl Default for TxAdmissionParameters {
+    fn default() -> Self {
+        Self {
+            min_fee_per_gas: 0,
+            min_priority_fee_per_gas: 0,
+            price_bump_percent: DEFAULT_PRICE_BUMP_PERCENT,
+            max_queued_per_account: DEFAULT_MAX_QUEUED_PER_ACCOUNT,
+            max_queued: DEFAULT_MAX_QUEUED,
+            queued_lifetime_secs: DEFAULT_QUEUED_LIFETIME_SECS,
+        }
+    }
+}
+
+/// Safety settings of the MoveVM, to harden a public sequencer against adversarial bytecode or to
+/// relax the checks of a private deployment.
+///
+/// They apply to every transaction executed from then on, so all nodes of a network should share
+/// them.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct MoveVmParameters {
+    /// Whether the VM checks the types of values while executing, on top of the bytecode verifier.
+    pub paranoid_type_checks: bool,
+    pub verifier: VerifierLimits,
+    /// Timestamp from which transaction data may be in the versioned encoding, `None` meaning it
+    /// is never accepted. Before it, data starting with the envelope prefix stays invalid.
+    pub versioned_tx_data_time: Option<u64>,
+    /// Timestamp from which the table items written by transactions are stored in the state trie,
+    /// `None` meaning they never are. It changes the state root of blocks writing to tables.
+    pub table_state_time: Option<u64>,
+    /// Timestamp from which deposits increment the nonce of their sender and create the Move
+    /// accounts of their recipients, `None` meaning they never do. From then on a failed deposit
+    /// also keeps the increment and credits its mint to the sender, as the OP deposit rules
+    /// require.
+    pub deposit_accounts_time: Option<u64>,
+}
+
+impl MoveVmParameters {
+    pub fn is_versioned_tx_data_active(&self, timestamp: u64) -> bool {
+        self.versioned_tx_data_time
+            .is_some_and(|time| time <= timestamp)
+    }
+
+    pub fn is_table_state_active(&self, timestamp: u64) -> bool {
+        self.table_state_time.is_some_and(|time| time <= timestamp)
+    }
+
+    pub fn is_deposit_accounts_active(&self, timestamp: u64) -> bool {
+        self.deposit_accounts_time
+            .is_some_and(|time| time <= timestamp)
+    }
+}
+
+impl Default for MoveVmParameters {
+    fn default() -> Self {
+        Self {
+            paranoid_type_checks: true,
+            verifier: VerifierLimits::default(),
+            versioned_tx_data_time: None,
+            table_state_time: None,
+            deposit_accounts_time: None,
+        }
+    }
+}
+
+/// Limits that the bytecode verifier enforces on published modules and executed scripts. Each
+/// limit left as `None` keeps the default of the verifier.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct VerifierLimits {
+    /// Most basic blocks in a function, bounding its size.
+    pub max_basic_blocks: Option<usize>,
+    /// Most loops nested in one another in a function.
+    pub max_loop_depth: Option<usize>,
+    pub max_function_parameters: Option<usize>,
+    /// Most nodes in a type, such as `vector<vector<u8>>` having three.
+    pub max_type_nodes: Option<usize>,
+    pub max_function_definitions: Option<usize>,
+    pub max_struct_definitions: Option<usize>,
+    /// Most units of work the verifier spends on a function before rejecting it.
+    pub max_per_function_meter_units: Option<u128>,
+    /// Most units of work the verifier spends on a module before rejecting it.
+    pub max_per_module_meter_units: Option<u128>,
+}
+
+impl VerifierLimits {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.max_per_function_meter_units != Some(0)
+                && self.max_per_module_meter_units != Some(0),
+            "Verifier meter units must not be zero"
+        );
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GenesisConfig {
     pub chain_id: u64,
     pub initial_state_root: B256,
     pub gas_costs: GasCosts,
     pub treasury: AccountAddress,
+    pub hardforks: HardforkSchedule,
+    pub fees: FeeParameters,
+    pub gas_price_oracle: GasPriceOracleParameters,
+    pub simulation: SimulationParameters,
+    pub concurrency: ConcurrencyParameters,
+    pub state_history: StateHistoryParameters,
+    /// Highest gas limit a transaction may set, unlimited if `None`. Deposits are not limited, as
+    /// their gas is bought on L1.
+    pub max_transaction_gas_limit: Option<u64>,
+    pub tx_admission: TxAdmissionParameters,
+    pub move_vm: MoveVmParameters,
     pub l2_contract_genesis: Genesis,
     /// Superchain Token List data.
     pub token_list: Vec<BridgedToken>,
+    /// Whether the bridged tokens also get Move fungible asset metadata at genesis. Off unless
+    /// requested, because it changes the genesis state root of existing networks.
+    pub token_metadata: bool,
+    /// Accounts that hold a balance, and optionally code, from the genesis block.
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+    /// Chain specific Move packages published on top of the built-in framework.
+    pub packages: Vec<GenesisPackage>,
+    /// Genesis image applied instead of the state built from the rest of this config, such as the
+    /// one a regenesis exports.
+    pub genesis_image: Option<PathBuf>,
+}
+
+/// A pre-compiled Move package published at genesis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenesisPackage {
+    pub address: AccountAddress,
+    /// Bytecode of each module in the package.
+    pub modules: Vec<Bytes>,
+}
+
+impl GenesisPackage {
+    /// Reads every `.mv` file in `dir`, such as the `bytecode_modules` directory of a built
+    /// package.
+    pub fn from_dir(address: AccountAddress, dir: &Path) -> anyhow::Result<Self> {
+        let mut paths = fs::read_dir(dir)
+            .context(format!("Path: {dir:?}"))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.retain(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == MOVE_BYTECODE_EXTENSION)
+        });
+        paths.sort();
+        ensure!(!paths.is_empty(), "No compiled modules found in {dir:?}");
+
+        let modules = paths
+            .iter()
+            .map(|path| {
+                fs::read(path)
+                    .map(Bytes::from)
+                    .context(format!("Path: {path:?}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self { address, modules })
+    }
+}
+
+impl GenesisConfig {
+    /// Reads the configuration from a JSON file in the [`GenesisConfigFile`] format.
+    ///
+    /// Relative paths inside the file are resolved against the directory containing it.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path).context(format!("Path: {path:?}"))?;
+        let file: GenesisConfigFile = serde_json::from_str(&data)
+            .context(format!("Genesis config should be valid JSON: {path:?}"))?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+
+        file.into_config(base_dir)
+    }
+}
+
+/// The on-disk representation of [`GenesisConfig`].
+///
+/// Optional fields fall back to the same values as [`GenesisConfig::default`], except for the
+/// token list which is empty unless given.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GenesisConfigFile {
+    pub chain_id: u64,
+    #[serde(default)]
+    pub initial_state_root: Option<B256>,
+    #[serde(default)]
+    pub treasury: Option<AccountAddress>,
+    #[serde(default)]
+    pub hardforks: HardforkSchedule,
+    #[serde(default)]
+    pub fees: FeeParameters,
+    #[serde(default)]
+    pub gas_price_oracle: GasPriceOracleParameters,
+    #[serde(default)]
+    pub simulation: SimulationParameters,
+    #[serde(default)]
+    pub concurrency: ConcurrencyParameters,
+    #[serde(default)]
+    pub state_history: StateHistoryParameters,
+    #[serde(default)]
+    pub max_transaction_gas_limit: Option<u64>,
+    #[serde(default)]
+    pub storage_write_gas: StorageWriteGasParameters,
+    #[serde(default)]
+    pub tx_admission: TxAdmissionParameters,
+    #[serde(default)]
+    pub move_vm: MoveVmParameters,
+    /// Path to the JSON encoded `Genesis` of the OP Stack L2 contracts.
+    pub l2_contract_genesis: PathBuf,
+    /// Path to a Superchain Token List file or directory.
+    #[serde(default)]
+    pub token_list: Option<PathBuf>,
+    #[serde(default)]
+    pub token_metadata: bool,
+    #[serde(default)]
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+    #[serde(default)]
+    pub packages: Vec<GenesisPackageFile>,
+    /// Starts the chain even if some of the standard preinstalls have no code, see
+    /// [`missing_preinstalls`].
+    #[serde(default)]
+    pub allow_missing_preinstalls: bool,
+    /// Path to a bcs encoded genesis image to start the chain from, see [`crate::regenesis`].
+    #[serde(default)]
+    pub genesis_image: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GenesisPackageFile {
+    pub address: AccountAddress,
+    /// Path to a directory of compiled `.mv` modules.
+    pub path: PathBuf,
+}
+
+impl GenesisConfigFile {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(self.chain_id != 0, "Chain ID must not be zero");
+        self.hardforks
+            .validate()
+            .context("Invalid hardfork schedule")?;
+        self.fees.validate().context("Invalid fee parameters")?;
+        self.gas_price_oracle
+            .validate()
+            .context("Invalid gas price oracle parameters")?;
+        self.simulation
+            .validate()
+            .context("Invalid simulation parameters")?;
+        self.concurrency
+            .validate()
+            .context("Invalid concurrency parameters")?;
+        self.state_history
+            .validate()
+            .context("Invalid state history parameters")?;
+        ensure!(
+            self.max_transaction_gas_limit != Some(0),
+            "Max transaction gas limit must not be zero"
+        );
+        self.move_vm
+            .verifier
+            .validate()
+            .context("Invalid verifier limits")?;
+        for package in &self.packages {
+            ensure!(
+                !is_framework_address(&package.address),
+                "Package address {} is reserved for the framework",
+                package.address
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn into_config(self, base_dir: &Path) -> anyhow::Result<GenesisConfig> {
+        self.validate()?;
+
+        let l2_genesis_path = base_dir.join(&self.l2_contract_genesis);
+        let l2_genesis_data =
+            fs::read_to_string(&l2_genesis_path).context(format!("Path: {l2_genesis_path:?}"))?;
+        let l2_contract_genesis = serde_json::from_str(&l2_genesis_data).context(
+            "L2 contract genesis path should point to JSON encoded L2 contract `Genesis` struct",
+        )?;
+        let token_list = match &self.token_list {
+            Some(path) => bridged_tokens::parse_token_list(&base_dir.join(path))?,
+            None => Vec::new(),
+        };
+        if self.token_metadata {
+            token_list
+                .iter()
+                .try_for_each(bridged_tokens::validate_token_metadata)?;
+        }
+        let packages = self
+            .packages
+            .iter()
+            .map(|package| GenesisPackage::from_dir(package.address, &base_dir.join(&package.path)))
+            .collect::<anyhow::Result<_>>()?;
+
+        let config = GenesisConfig {
+            chain_id: self.chain_id,
+            initial_state_root: self
+                .initial_state_root
+                .unwrap_or(DEFAULT_INITIAL_STATE_ROOT),
+            gas_costs: GasCosts {
+                storage_write: self.storage_write_gas,
+                ..Default::default()
+            },
+            treasury: self.treasury.unwrap_or(DEFAULT_TREASURY),
+            hardforks: self.hardforks,
+            fees: self.fees,
+            gas_price_oracle: self.gas_price_oracle,
+            simulation: self.simulation,
+            concurrency: self.concurrency,
+            state_history: self.state_history,
+            max_transaction_gas_limit: self.max_transaction_gas_limit,
+            tx_admission: self.tx_admission,
+            move_vm: self.move_vm,
+            l2_contract_genesis,
+            token_list,
+            token_metadata: self.token_metadata,
+            alloc: self.alloc,
+            packages,
+            genesis_image: self.genesis_image.map(|path| base_dir.join(path)),
+        };
+
+        let missing = missing_preinstalls(&config)
+            .iter()
+            .map(|preinstall| preinstall.name)
+            .collect::<Vec<_>>();
+        ensure!(
+            self.allow_missing_preinstalls || missing.is_empty(),
+            "Preinstalls {missing:?} have no code, add it to the alloc section or set \
+             allowMissingPreinstalls"
+        );
+        if !missing.is_empty() {
+            println!(
+                "WARN: preinstalls {missing:?} have no code, as allowMissingPreinstalls is set"
+            );
+        }
+
+        Ok(config)
+    }
 }
 
 impl Default for GasCosts {
@@ -58,6 +751,7 @@ impl Default for GasCosts {
             vm: VMGasParameters::initial(),
             storage: StorageGasParameters::latest(),
             natives: NativeGasParameters::initial(),
+            storage_write: StorageWriteGasParameters::default(),
             version: aptos_gas_schedule::LATEST_GAS_FEATURE_VERSION,
         };
         result.vm.txn.gas_unit_scaling_factor = GasQuantity::new(EXTERNAL_GAS_SCALE_FACTOR);
@@ -75,14 +769,25 @@ impl Default for GenesisConfig {
             .join("execution/src/tests/res/bridged_tokens_test.json");
         Self {
             chain_id: CHAIN_ID,
-            initial_state_root: B256::from(hex!(
-                "0x4805267476cb522274ec2fe790b4dc6e889ed0d57377f90770d4a658f6b8e4ae"
-            )),
+            initial_state_root: DEFAULT_INITIAL_STATE_ROOT,
             gas_costs: GasCosts::default(),
-            treasury: AccountAddress::ONE, // todo: fill in the real address
+            treasury: DEFAULT_TREASURY,
+            hardforks: HardforkSchedule::default(),
+            fees: FeeParameters::default(),
+            gas_price_oracle: GasPriceOracleParameters::default(),
+            simulation: SimulationParameters::default(),
+            concurrency: ConcurrencyParameters::default(),
+            state_history: StateHistoryParameters::default(),
+            max_transaction_gas_limit: None,
+            tx_admission: TxAdmissionParameters::default(),
+            move_vm: MoveVmParameters::default(),
             l2_contract_genesis: serde_json::from_str(DEFAULT_L2_CONTRACT_GENESIS)
                 .expect("Default L2 contract genesis should be JSON encoded `Genesis` struct"),
             token_list: bridged_tokens::parse_token_list(&path).expect("Tokens list should parse"),
+            token_metadata: false,
+            alloc: BTreeMap::new(),
+            packages: Vec::new(),
+            genesis_image: None,
         }
     }
 }
@@ -101,3 +806,188 @@ fn test_default_token_list() {
         assert_eq!(token.name, expected_name);
     }
 }
+
+#[test]
+fn test_genesis_config_file_without_optional_fields_uses_defaults() {
+    let file: GenesisConfigFile = serde_json::from_value(serde_json::json!({
+        "chainId": 42069,
+        "l2ContractGenesis": "genesis.json"
+    }))
+    .unwrap();
+
+    assert!(file.validate().is_ok());
+    assert_eq!(file.hardforks, HardforkSchedule::default());
+    assert_eq!(file.fees, FeeParameters::default());
+    assert_eq!(file.gas_price_oracle, GasPriceOracleParameters::default());
+    assert_eq!(file.simulation, SimulationParameters::default());
+    assert_eq!(file.concurrency, ConcurrencyParameters::default());
+    assert_eq!(file.state_history, StateHistoryParameters::default());
+    assert_eq!(file.max_transaction_gas_limit, None);
+    assert_eq!(file.storage_write_gas, StorageWriteGasParameters::default());
+    assert_eq!(file.tx_admission, TxAdmissionParameters::default());
+    assert_eq!(file.move_vm, MoveVmParameters::default());
+    assert!(file.alloc.is_empty());
+}
+
+#[test]
+fn test_genesis_config_file_loads_relative_paths() {
+    let base_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("execution/src/tests/res");
+    let file: GenesisConfigFile = serde_json::from_value(serde_json::json!({
+        "chainId": 42069,
+        "l2ContractGenesis": "l2_genesis_tests.json",
+        "tokenList": "bridged_tokens_test.json",
+        "fees": {
+            "eip1559ElasticityMultiplier": 2,
+            "eip1559BaseFeeMaxChangeDenominator": 50
+        },
+        "storageWriteGas": {
+            "perSlotCreated": 20000,
+            "perByteWritten": 16
+        },
+        "allowMissingPreinstalls": true
+    }))
+    .unwrap();
+
+    let config = file.into_config(&base_dir).unwrap();
+
+    assert_eq!(config.chain_id, 42069);
+    assert_eq!(config.fees.eip1559_elasticity_multiplier, 2);
+    assert_eq!(config.gas_costs.storage_write.per_slot_created, 20_000);
+    assert_eq!(config.token_list, GenesisConfig::default().token_list);
+}
+
+#[test]
+fn test_genesis_config_file_without_preinstall_code_is_invalid() {
+    let base_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("execution/src/tests/res");
+    let file: GenesisConfigFile = serde_json::from_value(serde_json::json!({
+        "chainId": 42069,
+        "l2ContractGenesis": "l2_genesis_tests.json"
+    }))
+    .unwrap();
+
+    let error = file.into_config(&base_dir).unwrap_err();
+
+    assert!(format!("{error:#}").contains("CreateX"));
+}
+
+#[test]
+fn test_genesis_package_without_compiled_modules_is_invalid() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+    assert!(GenesisPackage::from_dir(AccountAddress::TWO, &dir).is_err());
+}
+
+#[test]
+fn test_genesis_package_at_framework_address_is_invalid() {
+    let file: GenesisConfigFile = serde_json::from_value(serde_json::json!({
+        "chainId": 42069,
+        "l2ContractGenesis": "genesis.json",
+        "packages": [{
+            "address": "0x1",
+            "path": "packages/framework"
+        }]
+    }))
+    .unwrap();
+
+    let error = file.validate().unwrap_err();
+
+    assert!(error.to_string().contains("reserved for the framework"));
+}
+
+#[test]
+fn test_hardfork_scheduled_before_its_predecessor_is_invalid() {
+    let schedule = HardforkSchedule {
+        holocene_time: Some(10),
+        isthmus_time: Some(5),
+        ..Default::default()
+    };
+
+    assert!(schedule.validate().is_err());
+}
+
+#[test]
+fn test_hardfork_scheduled_without_its_predecessor_is_invalid() {
+    let schedule = HardforkSchedule {
+        granite_time: None,
+        ..Default::default()
+    };
+
+    assert!(schedule.validate().is_err());
+}
+
+#[test]
+fn test_gas_price_oracle_percentile_above_100_is_invalid() {
+    let parameters = GasPriceOracleParameters {
+        percentile: 101,
+        ..Default::default()
+    };
+
+    assert!(parameters.validate().is_err());
+}
+
+#[test]
+fn test_gas_price_oracle_minimum_above_maximum_is_invalid() {
+    let parameters = GasPriceOracleParameters {
+        min_priority_fee: 2,
+        max_priority_fee: 1,
+        ..Default::default()
+    };
+
+    assert!(parameters.validate().is_err());
+}
+
+#[test]
+fn test_simulation_without_timeout_is_invalid() {
+    let parameters = SimulationParameters {
+        timeout_ms: 0,
+        ..Default::default()
+    };
+
+    assert!(parameters.validate().is_err());
+}
+
+#[test]
+fn test_concurrency_without_sender_recovery_threads_is_invalid() {
+    let parameters = ConcurrencyParameters {
+        sender_recovery_threads: 0,
+        ..Default::default()
+    };
+
+    assert!(parameters.validate().is_err());
+}
+
+#[test]
+fn test_state_history_without_retained_blocks_is_invalid() {
+    let parameters = StateHistoryParameters {
+        retained_blocks: Some(0),
+    };
+
+    assert!(parameters.validate().is_err());
+}
+
+#[test]
+fn test_state_history_horizon_keeps_retained_blocks() {
+    let parameters = StateHistoryParameters {
+        retained_blocks: Some(10),
+    };
+
+    assert_eq!(parameters.horizon(5), 0);
+    assert_eq!(parameters.horizon(100), 91);
+    assert_eq!(StateHistoryParameters::default().horizon(100), 0);
+}
+
+#[test]
+fn test_verifier_without_meter_units_is_invalid() {
+    let limits = VerifierLimits {
+        max_per_module_meter_units: Some(0),
+        ..Default::default()
+    };
+
+    assert!(limits.validate().is_err());
+}
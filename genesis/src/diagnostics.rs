@@ -0,0 +1,218 @@
+//! Diagnostics for a genesis state that does not have the expected state root.
+//!
+//! The state trie is keyed by hashes, so it cannot tell which keys a mismatch comes from. The
+//! genesis changes can, so a [`Report`] lists every key they write along with a hash of its value.
+//! Given the genesis image that produced the expected state root, the report also lists the keys
+//! whose values differ from it. Without that image, the reports of two nodes can be compared.
+
+use {
+    crate::{SerdeAllChanges, SerdeOp, regenesis},
+    alloy::primitives::keccak256,
+    anyhow::Context,
+    moved_shared::primitives::B256,
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// Where to write a [`Report`] on a genesis state root mismatch, and what to compare against.
+#[derive(Debug, Clone)]
+pub struct Diagnostics {
+    pub report_path: PathBuf,
+    /// The genesis image that the expected state root was computed from, if it is known.
+    pub expected_image: Option<SerdeAllChanges>,
+}
+
+impl Diagnostics {
+    /// Reads the diagnostics settings from the `STATE_ROOT_REPORT` and
+    /// `STATE_ROOT_EXPECTED_IMAGE` env vars. Diagnostics are off unless a report path is set.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(report_path) = std::env::var("STATE_ROOT_REPORT") else {
+            return Ok(None);
+        };
+        let expected_image = std::env::var("STATE_ROOT_EXPECTED_IMAGE")
+            .ok()
+            .map(regenesis::read_image)
+            .transpose()?;
+
+        Ok(Some(Self {
+            report_path: report_path.into(),
+            expected_image,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub expected_state_root: B256,
+    pub actual_state_root: B256,
+    /// Keys that hold a different value than in the expected image, or `None` without one.
+    pub differences: Option<Vec<Difference>>,
+    /// Every key written at genesis, with the hash of its value or `None` if it is deleted.
+    pub computed: BTreeMap<String, Option<B256>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Difference {
+    pub key: String,
+    pub expected: Option<B256>,
+    pub actual: Option<B256>,
+}
+
+impl Report {
+    pub fn new(
+        expected_state_root: B256,
+        actual_state_root: B256,
+        computed_image: &SerdeAllChanges,
+        expected_image: Option<&SerdeAllChanges>,
+    ) -> Self {
+        let computed = value_hashes(computed_image);
+        let differences = expected_image.map(|image| differences(&value_hashes(image), &computed));
+
+        Self {
+            expected_state_root,
+            actual_state_root,
+            differences,
+            computed,
+        }
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let contents = serde_json::to_vec_pretty(self)?;
+
+        fs::write(path, contents).context(format!("Path: {path:?}"))
+    }
+}
+
+/// Hashes the value of every key that `image` writes, naming each key by what it stores.
+fn value_hashes(image: &SerdeAllChanges) -> BTreeMap<String, Option<B256>> {
+    let mut hashes = BTreeMap::new();
+
+    for (address, account) in &image.changes.accounts {
+        for (name, op) in &account.modules {
+            hashes.insert(format!("module {address}::{name}"), op_hash(op));
+        }
+        for (tag, op) in &account.resources {
+            hashes.insert(format!("resource {address} {tag}"), op_hash(op));
+        }
+    }
+    for (handle, table) in &image.tables.changes {
+        for (key, op) in &table.entries {
+            let key = alloy::hex::encode_prefixed(key);
+            hashes.insert(format!("table {handle} {key}"), op_hash(op));
+        }
+    }
+    for (address, trie) in &image.evm_storage.tries {
+        hashes.insert(format!("evm storage {address}"), Some(trie.root));
+    }
+
+    hashes
+}
+
+fn op_hash<T: AsRef<[u8]>>(op: &SerdeOp<T>) -> Option<B256> {
+    match op {
+        SerdeOp::New(bytes) | SerdeOp::Modify(bytes) => Some(keccak256(bytes)),
+        SerdeOp::Delete => None,
+    }
+}
+
+fn differences(
+    expected: &BTreeMap<String, Option<B256>>,
+    actual: &BTreeMap<String, Option<B256>>,
+) -> Vec<Difference> {
+    let keys: BTreeSet<_> = expected.keys().chain(actual.keys()).collect();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let expected = expected.get(key).copied().flatten();
+            let actual = actual.get(key).copied().flatten();
+            (expected != actual).then(|| Difference {
+                key: key.clone(),
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        bytes::Bytes,
+        move_core_types::{
+            account_address::AccountAddress,
+            effects::{ChangeSet, Op},
+            ident_str,
+            language_storage::StructTag,
+        },
+        move_table_extension::TableChangeSet,
+        moved_evm_ext::state::StorageTriesChanges,
+    };
+
+    fn image_with(resources: &[(AccountAddress, u8)]) -> SerdeAllChanges {
+        let mut changes = ChangeSet::new();
+        for (address, value) in resources {
+            let tag = StructTag {
+                address: AccountAddress::ONE,
+                module: ident_str!("counter").into(),
+                name: ident_str!("Counter").into(),
+                type_args: Vec::new(),
+            };
+            changes
+                .add_resource_op(*address, tag, Op::New(Bytes::from(vec![*value])))
+                .unwrap();
+        }
+
+        SerdeAllChanges::new(
+            changes.into(),
+            TableChangeSet::default().into(),
+            StorageTriesChanges::empty().into(),
+        )
+    }
+
+    #[test]
+    fn test_report_lists_keys_that_differ_from_expected_image() {
+        let same = AccountAddress::from_hex_literal("0xa").unwrap();
+        let changed = AccountAddress::from_hex_literal("0xb").unwrap();
+        let missing = AccountAddress::from_hex_literal("0xc").unwrap();
+        let expected = image_with(&[(same, 1), (changed, 2), (missing, 3)]);
+        let computed = image_with(&[(same, 1), (changed, 4)]);
+
+        let report = Report::new(B256::ZERO, B256::repeat_byte(1), &computed, Some(&expected));
+
+        let keys: Vec<_> = report
+            .differences
+            .unwrap()
+            .into_iter()
+            .map(|difference| difference.key)
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                format!("resource {changed} 0x1::counter::Counter"),
+                format!("resource {missing} 0x1::counter::Counter"),
+            ]
+        );
+        assert_eq!(report.computed.len(), 2);
+    }
+
+    #[test]
+    fn test_report_without_expected_image_lists_computed_keys_only() {
+        let address = AccountAddress::from_hex_literal("0xa").unwrap();
+        let computed = image_with(&[(address, 1)]);
+
+        let report = Report::new(B256::ZERO, B256::repeat_byte(1), &computed, None);
+
+        assert_eq!(report.differences, None);
+        assert_eq!(
+            report.computed[&format!("resource {address} 0x1::counter::Counter")],
+            Some(keccak256([1]))
+        );
+    }
+}
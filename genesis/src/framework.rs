@@ -1,15 +1,17 @@
 use {
-    crate::MovedVm,
+    crate::{MovedVm, config::GenesisPackage},
     alloy::primitives::address,
     aptos_framework::{ReleaseBundle, ReleasePackage},
+    aptos_types::account_address::create_object_address,
     bytes::Bytes,
-    move_binary_format::errors::VMError,
+    move_binary_format::errors::{Location, PartialVMError, VMError},
     move_core_types::{
         account_address::AccountAddress,
         effects::{ChangeSet, Op},
         ident_str,
         language_storage::ModuleId,
         value::MoveValue,
+        vm_status::StatusCode,
     },
     move_vm_runtime::{
         AsUnsyncCodeStorage, ModuleStorage, StagingModuleStorage, VerifiedModuleBundle,
@@ -41,6 +43,9 @@ pub const SUI_FRAMEWORK_ADDRESS: AccountAddress = small_account_address(0x22);
 pub const SUI_STDLIB_PACKAGE_ID: ObjectID = small_object_id(0x21);
 pub const SUI_FRAMEWORK_PACKAGE_ID: ObjectID = small_object_id(0x22);
 
+/// Seed of the object holding the fungible asset metadata of the base token in `eth_token`.
+const ETH_TOKEN_SEED: &[u8] = b"ETH";
+
 // The `head.mrb` file is generated by running the following inside an aptos-core repo
 // cargo run --package aptos-framework -- release
 static APTOS_RELEASE_BUNDLE: Lazy<ReleaseBundle> = Lazy::new(|| {
@@ -77,6 +82,12 @@ const fn small_object_id(value: u8) -> ObjectID {
     ObjectID::from_single_byte(value)
 }
 
+/// Address of the fungible asset metadata object of the base token, created when the `eth_token`
+/// module is deployed.
+pub fn eth_token_metadata_address() -> AccountAddress {
+    create_object_address(FRAMEWORK_ADDRESS, ETH_TOKEN_SEED)
+}
+
 /// Returns the Aptos framework release bundle
 pub fn load_aptos_framework_snapshot() -> &'static ReleaseBundle {
     &APTOS_RELEASE_BUNDLE
@@ -92,6 +103,56 @@ pub fn init_state(vm: &MovedVm, state: &mut impl State) -> ChangeSet {
     deploy_framework(vm, state).expect("All bundle modules should be valid")
 }
 
+/// Publishes the chain specific packages from the genesis config on top of the frameworks.
+///
+/// Packages are published in the listed order, so each one can link against the previous ones.
+pub fn deploy_packages(
+    moved_vm: &MovedVm,
+    state: &mut impl State,
+    packages: &[GenesisPackage],
+) -> Result<ChangeSet, VMError> {
+    let mut total_writes = ChangeSet::new();
+
+    for package in packages {
+        if is_framework_address(&package.address) {
+            return Err(PartialVMError::new(StatusCode::CONSTRAINT_NOT_SATISFIED)
+                .with_message(format!(
+                    "Package address {} is reserved for the framework",
+                    package.address
+                ))
+                .finish(Location::Undefined));
+        }
+        let module_bytes_storage = ResolverBasedModuleBytesStorage::new(state.resolver());
+        let module_storage = module_bytes_storage.as_unsync_code_storage(moved_vm);
+        let staged_module_storage = StagingModuleStorage::create(
+            &package.address,
+            &module_storage,
+            package.modules.clone(),
+        )?;
+        let bundle = staged_module_storage.release_verified_module_bundle();
+        let package_writes = convert_bundle_into_module_ops(bundle)?;
+        state.apply(package_writes.clone()).unwrap();
+        total_writes
+            .squash(package_writes)
+            .expect("Genesis packages should not conflict with each other");
+    }
+
+    Ok(total_writes)
+}
+
+/// Whether `address` holds one of the built-in frameworks or the OP Stack L2 contracts.
+pub(crate) fn is_framework_address(address: &AccountAddress) -> bool {
+    [
+        FRAMEWORK_ADDRESS,
+        TOKEN_ADDRESS,
+        TOKEN_OBJECT_ADDRESS,
+        SUI_STDLIB_ADDRESS,
+        SUI_FRAMEWORK_ADDRESS,
+    ]
+    .contains(address)
+        || (L2_LOWEST_ADDRESS..=L2_HIGHEST_ADDRESS).contains(address)
+}
+
 pub trait CreateMoveVm {
     fn create_move_vm(&self) -> Result<MoveVM, VMError>;
 }
@@ -324,7 +385,14 @@ fn convert_bundle_into_module_ops(
 
 #[cfg(test)]
 mod tests {
-    use {super::*, crate::vm::MovedVm, moved_state::InMemoryState};
+    use {
+        super::*,
+        crate::vm::MovedVm,
+        move_binary_format::{file_format::empty_module, file_format_common::VERSION_DEFAULT},
+        move_core_types::identifier::Identifier,
+        move_vm_types::resolver::ModuleResolver,
+        moved_state::InMemoryState,
+    };
 
     // Aptos framework has 145 modules and Sui has 69. They are kept mutually exclusive.
     const APTOS_MODULES_LEN: usize = 145;
@@ -348,4 +416,50 @@ mod tests {
         let change_set = deploy_framework(&vm, &mut state).unwrap();
         assert_eq!(change_set.modules().count(), TOTAL_MODULES_LEN);
     }
+
+    #[test]
+    fn test_deploy_packages_rejects_framework_address() {
+        let mut state = InMemoryState::default();
+        let vm = MovedVm::new(&Default::default());
+        let package = GenesisPackage {
+            address: FRAMEWORK_ADDRESS,
+            modules: Vec::new(),
+        };
+
+        let err = deploy_packages(&vm, &mut state, &[package]).unwrap_err();
+
+        assert_eq!(err.major_status(), StatusCode::CONSTRAINT_NOT_SATISFIED);
+        assert!(
+            err.message()
+                .unwrap()
+                .contains("reserved for the framework")
+        );
+    }
+
+    #[test]
+    fn test_deploy_packages_publishes_modules_at_package_address() {
+        let mut state = InMemoryState::default();
+        let vm = MovedVm::new(&Default::default());
+        let address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let name = Identifier::new("greeting").unwrap();
+        let mut module = empty_module();
+        module.version = VERSION_DEFAULT;
+        module.address_identifiers[0] = address;
+        module.identifiers[0] = name.clone();
+        let mut bytes = Vec::new();
+        module.serialize(&mut bytes).unwrap();
+        let package = GenesisPackage {
+            address,
+            modules: vec![bytes.clone().into()],
+        };
+
+        let changes = deploy_packages(&vm, &mut state, &[package]).unwrap();
+
+        let module_id = ModuleId::new(address, name);
+        assert_eq!(changes.modules().count(), 1);
+        assert_eq!(
+            state.resolver().get_module(&module_id).unwrap(),
+            Some(Bytes::from(bytes))
+        );
+    }
 }
@@ -0,0 +1,168 @@
+//! Construction of the genesis block header in the format op-geth produces from the L2 genesis.
+//!
+//! The op-node learns the genesis block hash from the rollup config, which is derived from the
+//! same L2 genesis file. The header is therefore built from the EVM view of that file rather than
+//! from the Move state, which is only reflected in [`GenesisConfig::initial_state_root`].
+
+use {
+    crate::config::GenesisConfig,
+    alloy::{
+        consensus::{EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH, Header},
+        eips::{eip1559::INITIAL_BASE_FEE, eip7685::EMPTY_REQUESTS_HASH},
+        genesis::{Genesis, GenesisAccount},
+        primitives::{B64, KECCAK_EMPTY, address, keccak256},
+    },
+    alloy_rlp::RlpEncodable,
+    alloy_trie::{HashBuilder, Nibbles},
+    moved_shared::primitives::{Address, B256, U256},
+};
+
+/// Used when the L2 genesis does not specify a gas limit, same as in geth.
+const GENESIS_GAS_LIMIT: u64 = 4_712_388;
+/// Since Isthmus the withdrawals root holds the storage root of this contract.
+pub const L2_TO_L1_MESSAGE_PASSER: Address = address!("4200000000000000000000000000000000000016");
+
+#[derive(RlpEncodable)]
+struct TrieAccount {
+    nonce: u64,
+    balance: U256,
+    storage_root: B256,
+    code_hash: B256,
+}
+
+/// Builds the header of block 0 exactly like op-geth does for the L2 genesis in `config`.
+pub fn genesis_header(config: &GenesisConfig) -> Header {
+    let genesis = &config.l2_contract_genesis;
+    let number = genesis.number.unwrap_or_default();
+    let timestamp = genesis.timestamp;
+    let chain_config = &genesis.config;
+    let is_london = chain_config
+        .london_block
+        .is_some_and(|block| block <= number);
+    let is_shanghai = chain_config.shanghai_time.is_some_and(|t| t <= timestamp);
+    let is_cancun = chain_config.cancun_time.is_some_and(|t| t <= timestamp);
+    let is_isthmus = config
+        .hardforks
+        .isthmus_time
+        .is_some_and(|t| t <= timestamp);
+    let is_prague = is_isthmus || chain_config.prague_time.is_some_and(|t| t <= timestamp);
+
+    let withdrawals_root = if is_isthmus {
+        let message_passer = genesis.alloc.get(&L2_TO_L1_MESSAGE_PASSER);
+        Some(message_passer.map_or(EMPTY_ROOT_HASH, storage_root))
+    } else if is_shanghai {
+        Some(EMPTY_ROOT_HASH)
+    } else {
+        None
+    };
+
+    Header {
+        parent_hash: B256::ZERO,
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        beneficiary: genesis.coinbase,
+        state_root: state_root(genesis),
+        transactions_root: EMPTY_ROOT_HASH,
+        receipts_root: EMPTY_ROOT_HASH,
+        logs_bloom: Default::default(),
+        difficulty: genesis.difficulty,
+        number,
+        gas_limit: match genesis.gas_limit {
+            0 => GENESIS_GAS_LIMIT,
+            gas_limit => gas_limit,
+        },
+        gas_used: 0,
+        timestamp,
+        extra_data: genesis.extra_data.clone(),
+        mix_hash: genesis.mix_hash,
+        nonce: B64::from(genesis.nonce),
+        base_fee_per_gas: is_london.then(|| {
+            genesis
+                .base_fee_per_gas
+                .map_or(INITIAL_BASE_FEE, |fee| fee as u64)
+        }),
+        withdrawals_root,
+        blob_gas_used: is_cancun.then(|| genesis.blob_gas_used.unwrap_or_default()),
+        excess_blob_gas: is_cancun.then(|| genesis.excess_blob_gas.unwrap_or_default()),
+        parent_beacon_block_root: is_cancun.then_some(B256::ZERO),
+        requests_hash: is_prague.then_some(EMPTY_REQUESTS_HASH),
+    }
+}
+
+/// Root of the EVM world state trie holding the accounts allocated in `genesis`.
+fn state_root(genesis: &Genesis) -> B256 {
+    trie_root(genesis.alloc.iter().map(|(address, account)| {
+        let account = TrieAccount {
+            nonce: account.nonce.unwrap_or_default(),
+            balance: account.balance,
+            storage_root: storage_root(account),
+            code_hash: account.code.as_ref().map_or(KECCAK_EMPTY, keccak256),
+        };
+        (keccak256(address), alloy_rlp::encode(account))
+    }))
+}
+
+fn storage_root(account: &GenesisAccount) -> B256 {
+    let Some(storage) = &account.storage else {
+        return EMPTY_ROOT_HASH;
+    };
+
+    trie_root(storage.iter().filter_map(|(slot, value)| {
+        let value = U256::from_be_bytes(value.0);
+        (!value.is_zero()).then(|| (keccak256(slot), alloy_rlp::encode(value)))
+    }))
+}
+
+fn trie_root(leaves: impl Iterator<Item = (B256, Vec<u8>)>) -> B256 {
+    let mut leaves = leaves.collect::<Vec<_>>();
+    leaves.sort_unstable_by_key(|(key, _)| *key);
+
+    let mut builder = HashBuilder::default();
+    for (key, value) in leaves {
+        builder.add_leaf(Nibbles::unpack(key), &value);
+    }
+    builder.root()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::primitives::hex};
+
+    #[test]
+    fn test_genesis_header_follows_l2_genesis() {
+        let config = GenesisConfig::default();
+        let genesis = &config.l2_contract_genesis;
+
+        let header = genesis_header(&config);
+
+        assert_eq!(header.number, 0);
+        assert_eq!(header.timestamp, genesis.timestamp);
+        assert_eq!(header.gas_limit, genesis.gas_limit);
+        assert_eq!(header.beneficiary, genesis.coinbase);
+        assert_eq!(header.base_fee_per_gas, Some(1_000_000_000));
+        assert_eq!(header.withdrawals_root, Some(EMPTY_ROOT_HASH));
+        assert_eq!(header.parent_beacon_block_root, Some(B256::ZERO));
+        assert_eq!(header.requests_hash, None);
+        assert_ne!(header.state_root, EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn test_state_root_of_single_account() {
+        let mut genesis = Genesis::default();
+        genesis.alloc.insert(
+            address!("0000000000000000000000000000000000000001"),
+            GenesisAccount {
+                balance: U256::from(1),
+                ..Default::default()
+            },
+        );
+
+        let root = state_root(&genesis);
+
+        assert_eq!(
+            root,
+            B256::new(hex!(
+                "8028c28b55eab8be08883e921f20d1b6cc9f2aa02cc6cd90cfaa9b0462ff6d3e"
+            ))
+        );
+    }
+}
@@ -1,5 +1,11 @@
 pub use {
-    framework::{CreateMoveVm, FRAMEWORK_ADDRESS, load_aptos_framework_snapshot},
+    bridged_tokens::{BridgedToken, token_metadata_address},
+    framework::{
+        CreateMoveVm, FRAMEWORK_ADDRESS, TOKEN_ADDRESS, eth_token_metadata_address,
+        load_aptos_framework_snapshot,
+    },
+    header::{L2_TO_L1_MESSAGE_PASSER, genesis_header},
+    preinstalls::{PREINSTALLS, Preinstall, missing_preinstalls},
     serde::{
         SerdeAccountChanges, SerdeAllChanges, SerdeChanges, SerdeOp, SerdeTableChange,
         SerdeTableChangeSet, SerdeTableInfo,
@@ -8,19 +14,29 @@ pub use {
 };
 
 use {
-    self::config::GenesisConfig,
+    self::{
+        config::GenesisConfig,
+        diagnostics::{Diagnostics, Report},
+    },
     move_core_types::effects::ChangeSet,
     move_table_extension::TableChangeSet,
     moved_evm_ext::state::{StorageTrieRepository, StorageTriesChanges},
+    moved_shared::primitives::B256,
     moved_state::{InMemoryState, State},
 };
 
+pub mod chain_spec;
 pub mod config;
+pub mod diagnostics;
+pub mod regenesis;
 
 mod framework;
+mod header;
 
 mod bridged_tokens;
 mod l2_contracts;
+mod prefunded;
+mod preinstalls;
 mod serde;
 mod vm;
 
@@ -33,9 +49,13 @@ pub fn build(
     // Deploy Move/Aptos/Sui frameworks
     let changes_framework = framework::init_state(vm, &mut state);
 
-    // Deploy OP stack L2 contracts
-    let mut changes_l2 =
-        l2_contracts::init_state(config.l2_contract_genesis.clone(), &state, storage_trie);
+    // Publish chain specific Move packages (if any)
+    let changes_packages = framework::deploy_packages(vm, &mut state, &config.packages)
+        .expect("Genesis packages should be valid");
+
+    // Deploy OP stack L2 contracts, along with the code of allocated accounts (if any)
+    let l2_genesis = prefunded::extend_l2_genesis(config.l2_contract_genesis.clone(), config);
+    let mut changes_l2 = l2_contracts::init_state(l2_genesis, &state, storage_trie);
 
     // Deploy additional bridged tokens (if any)
     if !config.token_list.is_empty() {
@@ -49,13 +69,63 @@ pub fn build(
         .squash(changes_framework)
         .expect("Framework changes should not be in conflict");
 
+    changes
+        .squash(changes_packages)
+        .expect("Genesis packages should not conflict with the framework");
+
     changes
         .squash(changes_l2.accounts)
         .expect("L2 contract changes should not be in conflict");
 
+    // Give the bridged tokens their Move fungible asset metadata (if requested)
+    if config.token_metadata && !config.token_list.is_empty() {
+        let changes_metadata =
+            bridged_tokens::deploy_token_metadata(vm, config, &state_of(&changes))
+                .expect("Bridged token metadata must deploy");
+        changes
+            .squash(changes_metadata)
+            .expect("Bridged token metadata should not be in conflict");
+    }
+
+    // Fund allocated accounts (if any). Minting needs the base token to be initialized, so it
+    // runs against the state with everything deployed so far.
+    if config
+        .alloc
+        .values()
+        .any(|account| !account.balance.is_zero())
+    {
+        let changes_alloc = prefunded::mint_balances(vm, config, &state_of(&changes))
+            .expect("Allocated balances must mint");
+        changes
+            .squash(changes_alloc)
+            .expect("Allocated balance changes should not be in conflict");
+    }
+
+    // Give allocated accounts without code their nonces (if any), which lives in their Move
+    // account rather than their EVM one
+    if config
+        .alloc
+        .values()
+        .any(|account| account.nonce.unwrap_or_default() != 0 && !prefunded::has_code(account))
+    {
+        let changes_nonces = prefunded::set_sequence_numbers(vm, config, &state_of(&changes))
+            .expect("Allocated nonces must be set");
+        changes
+            .squash(changes_nonces)
+            .expect("Allocated nonce changes should not be in conflict");
+    }
+
     (changes, TableChangeSet::default(), changes_l2.storage)
 }
 
+fn state_of(changes: &ChangeSet) -> InMemoryState {
+    let mut state = InMemoryState::default();
+    state
+        .apply(changes.clone())
+        .expect("Genesis changes should apply to an empty state");
+    state
+}
+
 pub fn apply(
     changes: ChangeSet,
     table_changes: TableChangeSet,
@@ -64,6 +134,68 @@ pub fn apply(
     state: &mut impl State,
     storage_trie: &mut impl StorageTrieRepository,
 ) {
+    let actual_state_root = apply_changes(
+        changes,
+        table_changes,
+        evm_storage_changes,
+        state,
+        storage_trie,
+    );
+
+    assert_state_root(actual_state_root, config);
+}
+
+/// Same as [`apply`], but writes a [`Report`] of the genesis changes before failing on a state
+/// root mismatch.
+pub fn apply_with_diagnostics(
+    changes: ChangeSet,
+    table_changes: TableChangeSet,
+    evm_storage_changes: StorageTriesChanges,
+    config: &GenesisConfig,
+    state: &mut impl State,
+    storage_trie: &mut impl StorageTrieRepository,
+    diagnostics: &Diagnostics,
+) {
+    let image = SerdeAllChanges::new(
+        changes.into(),
+        table_changes.into(),
+        evm_storage_changes.into(),
+    );
+    let actual_state_root = apply_changes(
+        image.changes.clone().into(),
+        image.tables.clone().into(),
+        image.evm_storage.clone().into(),
+        state,
+        storage_trie,
+    );
+
+    if actual_state_root != config.initial_state_root {
+        let report = Report::new(
+            config.initial_state_root,
+            actual_state_root,
+            &image,
+            diagnostics.expected_image.as_ref(),
+        );
+        match report.write(&diagnostics.report_path) {
+            Ok(()) => println!(
+                "Genesis state root mismatch report written to {:?}",
+                diagnostics.report_path
+            ),
+            Err(e) => println!("WARN: failed to write genesis state root mismatch report: {e:?}"),
+        }
+    }
+
+    assert_state_root(actual_state_root, config);
+}
+
+/// Writes the genesis changes, returning the resulting state root.
+fn apply_changes(
+    changes: ChangeSet,
+    table_changes: TableChangeSet,
+    evm_storage_changes: StorageTriesChanges,
+    state: &mut impl State,
+    storage_trie: &mut impl StorageTrieRepository,
+) -> B256 {
     state
         .apply_with_tables(changes, table_changes)
         .expect("Changes should be applicable");
@@ -71,12 +203,12 @@ pub fn apply(
         .apply(evm_storage_changes)
         .expect("EVM storage changes should be applicable");
 
-    // Validate final state
-    let actual_state_root = state.state_root();
-    let expected_state_root = config.initial_state_root;
+    state.state_root()
+}
 
+fn assert_state_root(actual_state_root: B256, config: &GenesisConfig) {
     assert_eq!(
-        actual_state_root, expected_state_root,
+        actual_state_root, config.initial_state_root,
         "Fatal Error: Genesis state root mismatch"
     );
 }
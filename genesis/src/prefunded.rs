@@ -0,0 +1,238 @@
+//! Accounts listed in the `alloc` section of the genesis config, which exist from the genesis
+//! block onward.
+
+use {
+    crate::{CreateMoveVm, FRAMEWORK_ADDRESS, MovedVm, config::GenesisConfig},
+    alloy::{
+        genesis::{Genesis, GenesisAccount},
+        primitives::U256,
+    },
+    aptos_framework::natives::{
+        event::NativeEventContext, object::NativeObjectContext,
+        transaction_context::NativeTransactionContext,
+    },
+    aptos_table_natives::NativeTableContext,
+    move_binary_format::errors::VMError,
+    move_core_types::{
+        effects::ChangeSet, ident_str, language_storage::ModuleId, value::MoveValue,
+    },
+    move_vm_runtime::{
+        AsUnsyncCodeStorage,
+        module_traversal::{TraversalContext, TraversalStorage},
+        native_extensions::NativeContextExtensions,
+    },
+    move_vm_types::gas::UnmeteredGasMeter,
+    moved_shared::primitives::{B256, ToMoveAddress, ToMoveU256},
+    moved_state::{ResolverBasedModuleBytesStorage, State},
+};
+
+/// Adds the nonce, code and storage of each allocated account to the L2 contract genesis.
+///
+/// Balances are left out, because the base token lives in Move rather than in the EVM account.
+/// See [`mint_balances`].
+pub fn extend_l2_genesis(mut l2_genesis: Genesis, config: &GenesisConfig) -> Genesis {
+    l2_genesis
+        .alloc
+        .extend(config.alloc.iter().map(|(address, account)| {
+            let account = GenesisAccount {
+                balance: U256::ZERO,
+                ..account.clone()
+            };
+            (*address, account)
+        }));
+    l2_genesis
+}
+
+/// Mints the base token balance of each allocated account.
+///
+/// The `state` must already contain the initialized `eth_token` module.
+pub fn mint_balances(
+    moved_vm: &MovedVm,
+    config: &GenesisConfig,
+    state: &impl State,
+) -> Result<ChangeSet, VMError> {
+    let vm = moved_vm.create_move_vm()?;
+    let mut session =
+        vm.new_session_with_extensions(state.resolver(), native_extensions(config, state));
+    let traversal_storage = TraversalStorage::new();
+    let mut traversal_context = TraversalContext::new(&traversal_storage);
+    let module_bytes_storage = ResolverBasedModuleBytesStorage::new(state.resolver());
+    let code_storage = module_bytes_storage.as_unsync_code_storage(moved_vm);
+    let module = ModuleId::new(FRAMEWORK_ADDRESS, ident_str!("eth_token").into());
+    let function_name = ident_str!("mint");
+    let admin_arg = bcs::to_bytes(&MoveValue::Signer(FRAMEWORK_ADDRESS))
+        .expect("Serialization of constant must succeed");
+
+    for (address, account) in &config.alloc {
+        if account.balance.is_zero() {
+            continue;
+        }
+        let to_arg = bcs::to_bytes(&address.to_move_address()).expect("address can serialize");
+        let amount_arg = bcs::to_bytes(&MoveValue::U256(account.balance.to_move_u256()))
+            .expect("amount can serialize");
+
+        session.execute_function_bypass_visibility(
+            &module,
+            function_name,
+            Vec::new(),
+            vec![
+                admin_arg.as_slice(),
+                to_arg.as_slice(),
+                amount_arg.as_slice(),
+            ],
+            &mut UnmeteredGasMeter,
+            &mut traversal_context,
+            &code_storage,
+        )?;
+    }
+
+    session.finish(&code_storage)
+}
+
+/// Sets the Move account sequence number of each allocated account without code to its nonce.
+///
+/// The nonce of an account without code is its Move sequence number rather than the nonce of its
+/// EVM account, which [`extend_l2_genesis`] sets. The framework only increments sequence numbers,
+/// so the account is created and its sequence number incremented up to the nonce.
+pub fn set_sequence_numbers(
+    moved_vm: &MovedVm,
+    config: &GenesisConfig,
+    state: &impl State,
+) -> Result<ChangeSet, VMError> {
+    let vm = moved_vm.create_move_vm()?;
+    let mut session =
+        vm.new_session_with_extensions(state.resolver(), native_extensions(config, state));
+    let traversal_storage = TraversalStorage::new();
+    let mut traversal_context = TraversalContext::new(&traversal_storage);
+    let module_bytes_storage = ResolverBasedModuleBytesStorage::new(state.resolver());
+    let code_storage = module_bytes_storage.as_unsync_code_storage(moved_vm);
+    let module = ModuleId::new(FRAMEWORK_ADDRESS, ident_str!("account").into());
+
+    for (address, account) in &config.alloc {
+        let nonce = account.nonce.unwrap_or_default();
+        if nonce == 0 || has_code(account) {
+            continue;
+        }
+        let address_arg = bcs::to_bytes(&address.to_move_address()).expect("address can serialize");
+
+        session.execute_function_bypass_visibility(
+            &module,
+            ident_str!("create_account_if_does_not_exist"),
+            Vec::new(),
+            vec![address_arg.as_slice()],
+            &mut UnmeteredGasMeter,
+            &mut traversal_context,
+            &code_storage,
+        )?;
+        for _ in 0..nonce {
+            session.execute_function_bypass_visibility(
+                &module,
+                ident_str!("increment_sequence_number"),
+                Vec::new(),
+                vec![address_arg.as_slice()],
+                &mut UnmeteredGasMeter,
+                &mut traversal_context,
+                &code_storage,
+            )?;
+        }
+    }
+
+    session.finish(&code_storage)
+}
+
+/// Whether the allocated `account` is a contract, whose nonce lives in its EVM account.
+pub(crate) fn has_code(account: &GenesisAccount) -> bool {
+    account.code.as_ref().is_some_and(|code| !code.is_empty())
+}
+
+/// Extensions of a genesis session that works with fungible assets.
+///
+/// Creating a fungible asset or a primary store emits events and relies on objects.
+pub(crate) fn native_extensions<'a>(
+    config: &GenesisConfig,
+    state: &'a impl State,
+) -> NativeContextExtensions<'a> {
+    let mut native_extensions = NativeContextExtensions::default();
+    native_extensions.add(NativeEventContext::default());
+    native_extensions.add(NativeObjectContext::default());
+    native_extensions.add(NativeTransactionContext::new(
+        B256::ZERO.to_vec(),
+        Vec::new(),
+        config.chain_id.to_le_bytes()[0],
+        None,
+    ));
+    native_extensions.add(NativeTableContext::new(B256::ZERO.0, state.resolver()));
+    native_extensions
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::framework,
+        alloy::primitives::{Address, Bytes, address},
+        moved_state::InMemoryState,
+    };
+
+    const ALLOCATED_ADDRESS: Address = address!("8fd379246834eac74b8419ffda202cf8051f7a03");
+
+    fn config_with_allocated_account(account: GenesisAccount) -> GenesisConfig {
+        let mut config = GenesisConfig::default();
+        config.alloc.insert(ALLOCATED_ADDRESS, account);
+        config
+    }
+
+    #[test]
+    fn test_l2_genesis_includes_allocated_account_without_balance() {
+        let code = Bytes::from_static(&[0x60, 0x00]);
+        let config = config_with_allocated_account(GenesisAccount {
+            balance: U256::from(5),
+            nonce: Some(3),
+            code: Some(code.clone()),
+            ..Default::default()
+        });
+
+        let l2_genesis = extend_l2_genesis(Genesis::default(), &config);
+        let account = &l2_genesis.alloc[&ALLOCATED_ADDRESS];
+
+        assert_eq!(account.balance, U256::ZERO);
+        assert_eq!(account.nonce, Some(3));
+        assert_eq!(account.code, Some(code));
+    }
+
+    #[test]
+    fn test_sequence_number_is_set_for_allocated_account_without_code() {
+        let config = config_with_allocated_account(GenesisAccount {
+            nonce: Some(3),
+            ..Default::default()
+        });
+        let vm = MovedVm::new(&config);
+        let framework_changes = framework::init_state(&vm, &mut InMemoryState::default());
+        let mut state = InMemoryState::default();
+        state.apply(framework_changes).unwrap();
+
+        let changes = set_sequence_numbers(&vm, &config, &state).unwrap();
+
+        assert!(
+            changes
+                .accounts()
+                .contains_key(&ALLOCATED_ADDRESS.to_move_address())
+        );
+    }
+
+    #[test]
+    fn test_allocated_balance_is_minted() {
+        let config = config_with_allocated_account(GenesisAccount {
+            balance: U256::from(5),
+            ..Default::default()
+        });
+        let vm = MovedVm::new(&config);
+        let framework_changes = framework::init_state(&vm, &mut InMemoryState::default());
+        let mut state = InMemoryState::default();
+        state.apply(framework_changes).unwrap();
+
+        let changes = mint_balances(&vm, &config, &state).unwrap();
+
+        assert!(!changes.accounts().is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+//! Contracts the OP Stack preinstalls at fixed addresses, which common tooling expects to find on
+//! every chain.
+//!
+//! Their code is part of the L2 contract genesis generated by the OP Stack deployment tooling.
+//! Preinstalls missing from it can be provided through the `alloc` section of the genesis config.
+//! A genesis config file that leaves any of them without code is rejected, unless it sets
+//! `allowMissingPreinstalls`, which the node warns about when it loads the config.
+//!
+//! The L2 contract genesis bundled for local development predates CreateX and EntryPoint v0.7, and
+//! their runtime bytecode is not vendored in this repository, so the bundled genesis config still
+//! allows them to be missing. Networks provide their code from the deployment tooling output.
+
+use {crate::config::GenesisConfig, alloy::primitives::address, moved_shared::primitives::Address};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preinstall {
+    pub name: &'static str,
+    pub address: Address,
+}
+
+pub const PREINSTALLS: [Preinstall; 7] = [
+    Preinstall {
+        name: "Multicall3",
+        address: address!("cA11bde05977b3631167028862bE2a173976CA11"),
+    },
+    Preinstall {
+        name: "Create2Deployer",
+        address: address!("13b0D85CcB8bf860b6b79AF3029fCA081AE9beF2"),
+    },
+    Preinstall {
+        name: "CreateX",
+        address: address!("ba5Ed099633D3B313e4D5F7bdc1305d3c28ba5Ed"),
+    },
+    Preinstall {
+        name: "DeterministicDeploymentProxy",
+        address: address!("4e59b44847b379578588920cA78FbF26c0B4956C"),
+    },
+    Preinstall {
+        name: "Permit2",
+        address: address!("000000000022D473030F116dDEE9F6B43aC78BA3"),
+    },
+    Preinstall {
+        name: "EntryPoint_v060",
+        address: address!("5FF137D4b0FDCD49DcA30c7CF57E578a026d2789"),
+    },
+    Preinstall {
+        name: "EntryPoint_v070",
+        address: address!("0000000071727De22E5E9d8BAf0edAc6f37da032"),
+    },
+];
+
+/// Returns the preinstalls that have no code in the genesis state described by `config`.
+pub fn missing_preinstalls(config: &GenesisConfig) -> Vec<Preinstall> {
+    let has_code = |address: &Address| {
+        let l2_account = config.l2_contract_genesis.alloc.get(address);
+        let allocated = config.alloc.get(address);
+        l2_account
+            .into_iter()
+            .chain(allocated)
+            .any(|account| account.code.as_ref().is_some_and(|code| !code.is_empty()))
+    };
+
+    PREINSTALLS
+        .into_iter()
+        .filter(|preinstall| !has_code(&preinstall.address))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::genesis::GenesisAccount};
+
+    #[test]
+    fn test_allocated_code_counts_as_preinstalled() {
+        let mut config = GenesisConfig::default();
+        let missing = missing_preinstalls(&config);
+        assert!(!missing.is_empty());
+
+        for preinstall in &missing {
+            config.alloc.insert(
+                preinstall.address,
+                GenesisAccount {
+                    code: Some(vec![0x00].into()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        assert!(missing_preinstalls(&config).is_empty());
+    }
+
+    #[test]
+    fn test_default_l2_genesis_contains_preinstalls() {
+        let missing = missing_preinstalls(&GenesisConfig::default())
+            .into_iter()
+            .map(|preinstall| preinstall.name)
+            .collect::<Vec<_>>();
+
+        // Not included by the contracts version the test genesis was generated with
+        assert_eq!(missing, ["CreateX", "EntryPoint_v070"]);
+    }
+}
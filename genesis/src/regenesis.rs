@@ -0,0 +1,565 @@
+//! Tooling for planned regenesis events, where a new chain starts from the state of an existing
+//! one.
+//!
+//! A regenesis goes through the following steps:
+//! 1. [`export`] the state of the old chain at some height into a genesis image,
+//! 2. transform the image, e.g. with [`remap_addresses`] or [`upgrade_framework`],
+//! 3. [`write_image`] and point the genesis config at it and its state root with
+//!    [`update_config`].
+//!
+//! The state trie is keyed by hashes, so the export walks every leaf of the trie and maps its key
+//! back through the recorded [preimages](moved_state::preimage). The old chain must therefore have
+//! recorded the preimages of every block since its genesis. The EVM storage tries are copied node
+//! by node, as their roots are all the state trie commits to.
+
+use {
+    crate::{
+        MovedVm, SerdeAccountChanges, SerdeAllChanges, SerdeChanges, SerdeOp,
+        config::GenesisConfigFile, framework,
+    },
+    alloy::{primitives::Address, rlp::Decodable},
+    alloy_trie::{
+        EMPTY_ROOT_HASH, Nibbles,
+        nodes::{RlpNode, TrieNode},
+    },
+    anyhow::{Context, anyhow, bail, ensure},
+    aptos_types::{
+        access_path,
+        state_store::state_key::{StateKey, inner::StateKeyInner},
+    },
+    bytes::Bytes,
+    move_core_types::{
+        account_address::AccountAddress,
+        effects::{ChangeSet, Op},
+        ident_str,
+        language_storage::StructTag,
+    },
+    move_table_extension::{TableChange, TableChangeSet, TableHandle, TableResolver},
+    move_vm_types::resolver::MoveResolver,
+    moved_evm_ext::{
+        state::{Account, StorageTrieChanges, StorageTrieRepository, StorageTriesChanges},
+        type_utils::account_info_struct_tag,
+    },
+    moved_shared::primitives::{B256, ToEthAddress},
+    moved_state::{InMemoryState, State},
+    std::{
+        collections::{BTreeMap, BTreeSet, HashMap},
+        fs,
+        path::Path,
+    },
+};
+
+/// Reads a bcs encoded genesis image, as produced by [`write_image`] or the genesis image build.
+pub fn read_image(path: impl AsRef<Path>) -> anyhow::Result<SerdeAllChanges> {
+    let path = path.as_ref();
+    let contents = fs::read(path).context(format!("Path: {path:?}"))?;
+
+    bcs::from_bytes(&contents).context("Genesis image should be bcs encoded")
+}
+
+pub fn write_image(path: impl AsRef<Path>, image: &SerdeAllChanges) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let contents = bcs::to_bytes(image)?;
+
+    fs::write(path, contents).context(format!("Path: {path:?}"))
+}
+
+/// Builds a genesis image holding every module, resource, table item and EVM account of the state
+/// under `state_root`, along with the storage tries of the EVM accounts.
+///
+/// Pass the `state_node` lookup, `resolver` and `evm_storage` of the old chain at the height the new
+/// chain should start from. The `preimage` of each state trie key is looked up by its hash, which
+/// fails the export if it is not known.
+pub fn export(
+    state_root: B256,
+    state_node: impl Fn(B256) -> Option<Vec<u8>>,
+    preimage: impl Fn(B256) -> anyhow::Result<Option<Vec<u8>>>,
+    resolver: &(impl MoveResolver + TableResolver),
+    evm_storage: &impl StorageTrieRepository,
+) -> anyhow::Result<SerdeAllChanges> {
+    let mut changes = ChangeSet::new();
+    let mut table_changes = TableChangeSet::default();
+    let mut storage_changes = StorageTriesChanges::empty();
+    let mut keys = Vec::new();
+    walk_trie(
+        state_root,
+        |hash| Ok(state_node(hash)),
+        |_, _| (),
+        |key| keys.push(key),
+    )?;
+
+    for key in keys {
+        let preimage =
+            preimage(key)?.ok_or_else(|| anyhow!("No preimage is recorded for state key {key}"))?;
+
+        // The key of an EVM account is its address, see `TreeKey`
+        if preimage.len() == Address::len_bytes() {
+            let address = Address::from_slice(&preimage);
+            let tag = account_info_struct_tag(&address);
+            let bytes = read_resource(resolver, &tag.address, &tag)?
+                .ok_or_else(|| anyhow!("EVM account {address} has no value"))?;
+            let account = Account::try_deserialize(&bytes)
+                .ok_or_else(|| anyhow!("EVM account {address} should be RLP encoded"))?;
+            let root = account.inner.storage_root;
+            if root != EMPTY_ROOT_HASH {
+                let mut trie_diff = HashMap::new();
+                walk_trie(
+                    root,
+                    |hash| {
+                        evm_storage
+                            .trie_node(&address, hash)
+                            .map_err(|e| anyhow!("Failed to read storage of {address}: {e:?}"))
+                    },
+                    |hash, node| {
+                        trie_diff.insert(hash, node.to_vec());
+                    },
+                    |_| (),
+                )?;
+                storage_changes = storage_changes.with_trie_changes(
+                    address,
+                    StorageTrieChanges {
+                        root,
+                        trie_diff,
+                        slots: Vec::new(),
+                    },
+                );
+            }
+            changes
+                .add_resource_op(tag.address, tag, Op::New(bytes))
+                .expect("State keys are unique");
+            continue;
+        }
+
+        let state_key = StateKey::decode(&preimage)
+            .map_err(|e| anyhow!("Preimage of {key} is not a state key: {e:?}"))?;
+        match state_key.inner() {
+            StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+                access_path::Path::Code(id) => {
+                    let bytes = resolver
+                        .get_module(&id)
+                        .map_err(|e| anyhow!("Failed to read module {id}: {e:?}"))?
+                        .ok_or_else(|| anyhow!("Module {id} has no value"))?;
+                    changes
+                        .add_module_op(id, Op::New(bytes))
+                        .expect("State keys are unique");
+                }
+                access_path::Path::Resource(tag) => {
+                    let address = access_path.address;
+                    let bytes = read_resource(resolver, &address, &tag)?
+                        .ok_or_else(|| anyhow!("Resource {tag} of {address} has no value"))?;
+                    changes
+                        .add_resource_op(address, tag, Op::New(bytes))
+                        .expect("State keys are unique");
+                }
+                access_path::Path::ResourceGroup(tag) => {
+                    bail!("Resource groups are not supported: {tag}")
+                }
+            },
+            StateKeyInner::TableItem { handle, key } => {
+                let handle = TableHandle(handle.0);
+                let bytes = resolver
+                    .resolve_table_entry_bytes_with_layout(&handle, key, None)
+                    .map_err(|e| anyhow!("Failed to read table item of {handle:?}: {e:?}"))?
+                    .ok_or_else(|| anyhow!("Table item of {handle:?} has no value"))?;
+                table_changes
+                    .changes
+                    .entry(handle)
+                    .or_insert_with(|| TableChange {
+                        entries: BTreeMap::new(),
+                    })
+                    .entries
+                    .insert(key.clone(), Op::New(bytes));
+            }
+            StateKeyInner::Raw(_) => bail!("Raw state keys are not supported"),
+        }
+    }
+
+    Ok(SerdeAllChanges::new(
+        changes.into(),
+        table_changes.into(),
+        storage_changes.into(),
+    ))
+}
+
+fn read_resource(
+    resolver: &impl MoveResolver,
+    address: &AccountAddress,
+    tag: &StructTag,
+) -> anyhow::Result<Option<Bytes>> {
+    let (bytes, _) = resolver
+        .get_resource_bytes_with_metadata_and_layout(address, tag, &[], None)
+        .map_err(|e| anyhow!("Failed to read resource {tag} of {address}: {e:?}"))?;
+
+    Ok(bytes)
+}
+
+/// Visits the trie under `root`, fetching each node by its hash with `node`.
+///
+/// Every fetched node is passed to `fetched` and the key of every leaf to `leaf`.
+fn walk_trie(
+    root: B256,
+    node: impl Fn(B256) -> anyhow::Result<Option<Vec<u8>>>,
+    mut fetched: impl FnMut(B256, &[u8]),
+    mut leaf: impl FnMut(B256),
+) -> anyhow::Result<()> {
+    if root == EMPTY_ROOT_HASH {
+        return Ok(());
+    }
+    let mut pending = vec![(Nibbles::default(), RlpNode::word_rlp(&root))];
+
+    while let Some((path, child)) = pending.pop() {
+        // Children shorter than a hash are embedded in their parent
+        let encoded = match child.as_hash() {
+            Some(hash) => {
+                let encoded = node(hash)?.ok_or_else(|| anyhow!("Trie node {hash} is missing"))?;
+                fetched(hash, &encoded);
+                encoded
+            }
+            None => child.to_vec(),
+        };
+
+        match TrieNode::decode(&mut encoded.as_slice())
+            .map_err(|e| anyhow!("Invalid trie node under {path:?}: {e}"))?
+        {
+            TrieNode::Branch(branch) => {
+                let mut children = branch.stack.into_iter();
+                for nibble in 0..16 {
+                    if branch.state_mask.is_bit_set(nibble) {
+                        let mut path = path.clone();
+                        path.push(nibble);
+                        let child = children.next().expect("Branch has a child per set bit");
+                        pending.push((path, child));
+                    }
+                }
+            }
+            TrieNode::Extension(extension) => {
+                pending.push((path.join(&extension.key), extension.child))
+            }
+            TrieNode::Leaf(node) => {
+                let key = path.join(&node.key).pack();
+                ensure!(
+                    key.len() == B256::len_bytes(),
+                    "Trie key {key:?} is not a hash"
+                );
+                leaf(B256::from_slice(&key));
+            }
+            TrieNode::EmptyRoot => (),
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves the resources of each account in `remapping` from the old address to the new one.
+///
+/// Resource values are copied as is, so addresses embedded within them are not rewritten. Module
+/// bytecode contains its own address, so accounts holding modules cannot be remapped. Neither can
+/// EVM accounts, which are resources of `EVM_NATIVE_ADDRESS` named after their address, nor
+/// accounts that are objects or own any, such as the primary fungible stores holding their
+/// balances, as object addresses are derived from their owners.
+pub fn remap_addresses(
+    mut image: SerdeAllChanges,
+    remapping: &BTreeMap<AccountAddress, AccountAddress>,
+) -> anyhow::Result<SerdeAllChanges> {
+    let mut moved = BTreeMap::new();
+    let object_owners: BTreeSet<_> = image
+        .changes
+        .accounts
+        .values()
+        .filter_map(
+            |account| match account.resources.get(&object_core_struct_tag())? {
+                SerdeOp::New(bytes) | SerdeOp::Modify(bytes) => object_owner(bytes),
+                SerdeOp::Delete => None,
+            },
+        )
+        .collect();
+
+    for (from, to) in remapping {
+        let evm_account = account_info_struct_tag(&from.to_eth_address());
+        ensure!(
+            !image
+                .changes
+                .accounts
+                .get(&evm_account.address)
+                .is_some_and(|account| account.resources.contains_key(&evm_account)),
+            "Account {from} is an EVM account and cannot be remapped"
+        );
+        ensure!(
+            !object_owners.contains(from),
+            "Account {from} owns objects and cannot be remapped"
+        );
+        let Some(account) = image.changes.accounts.remove(from) else {
+            continue;
+        };
+        ensure!(
+            account.modules.is_empty(),
+            "Account {from} holds modules and cannot be remapped"
+        );
+        ensure!(
+            !account.resources.contains_key(&object_core_struct_tag()),
+            "Account {from} is an object and cannot be remapped"
+        );
+        moved.insert(*to, account);
+    }
+
+    for (to, account) in moved {
+        ensure!(
+            !image.changes.accounts.contains_key(&to),
+            "Remapping target {to} is already in use"
+        );
+        image.changes.accounts.insert(to, account);
+    }
+
+    Ok(image)
+}
+
+fn object_core_struct_tag() -> StructTag {
+    StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("object").into(),
+        name: ident_str!("ObjectCore").into(),
+        type_args: Vec::new(),
+    }
+}
+
+/// Reads the owner of an `ObjectCore`, whose BCS encoding starts with a `u64` GUID creation number
+/// followed by the owner address.
+fn object_owner(bytes: &[u8]) -> Option<AccountAddress> {
+    let owner = bytes.get(8..8 + AccountAddress::LENGTH)?;
+
+    AccountAddress::from_bytes(owner).ok()
+}
+
+/// Replaces the framework modules in `image` with the ones bundled with this binary.
+///
+/// Framework accounts keep their existing resources. Resources the new framework initializes that
+/// are missing from the image are added.
+pub fn upgrade_framework(mut image: SerdeAllChanges, vm: &MovedVm) -> SerdeAllChanges {
+    let mut state = InMemoryState::default();
+    let framework: SerdeChanges<Bytes, Bytes> = framework::init_state(vm, &mut state).into();
+
+    for (address, new) in framework.accounts {
+        let account =
+            image
+                .changes
+                .accounts
+                .entry(address)
+                .or_insert_with(|| SerdeAccountChanges {
+                    modules: BTreeMap::new(),
+                    resources: BTreeMap::new(),
+                });
+
+        if !new.modules.is_empty() {
+            account.modules = new.modules;
+        }
+        for (tag, op) in new.resources {
+            account.resources.entry(tag).or_insert(op);
+        }
+    }
+
+    image
+}
+
+/// Computes the state root the chain has after applying `image` at genesis.
+pub fn state_root(image: &SerdeAllChanges) -> anyhow::Result<B256> {
+    let mut state = InMemoryState::default();
+    state
+        .apply_with_tables(image.changes.clone().into(), image.tables.clone().into())
+        .map_err(|e| anyhow!("Genesis image should apply to an empty state: {e:?}"))?;
+
+    Ok(state.state_root())
+}
+
+/// Points `config` at `image` written to `image_path` and at its state root, so the node starts
+/// from it as its genesis state.
+pub fn update_config(
+    mut config: GenesisConfigFile,
+    image: &SerdeAllChanges,
+    image_path: &Path,
+) -> anyhow::Result<GenesisConfigFile> {
+    config.initial_state_root = Some(state_root(image)?);
+    config.genesis_image = Some(image_path.to_path_buf());
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        eth_trie::DB,
+        move_core_types::language_storage::ModuleId,
+        moved_evm_ext::state::InMemoryStorageTrieRepository,
+        moved_state::preimage::{InMemoryTriePreimages, TriePreimages, state_preimages},
+    };
+
+    fn resource_tag() -> StructTag {
+        StructTag {
+            address: AccountAddress::ONE,
+            module: ident_str!("counter").into(),
+            name: ident_str!("Counter").into(),
+            type_args: Vec::new(),
+        }
+    }
+
+    fn image_with(changes: ChangeSet) -> SerdeAllChanges {
+        SerdeAllChanges::new(
+            changes.into(),
+            TableChangeSet::default().into(),
+            StorageTriesChanges::empty().into(),
+        )
+    }
+
+    fn export_state(
+        state: &InMemoryState,
+        preimages: &InMemoryTriePreimages,
+    ) -> anyhow::Result<SerdeAllChanges> {
+        export(
+            state.state_root(),
+            |hash| state.db().get(hash.as_slice()).unwrap(),
+            |key| Ok(preimages.preimage(key).unwrap()),
+            state.resolver(),
+            &InMemoryStorageTrieRepository::new(),
+        )
+    }
+
+    #[test]
+    fn test_export_reads_values_at_the_given_state() {
+        let account = AccountAddress::from_hex_literal("0xabc").unwrap();
+        let new_account = AccountAddress::from_hex_literal("0xdef").unwrap();
+        let preimages = InMemoryTriePreimages::default();
+        let mut changes = ChangeSet::new();
+        changes
+            .add_resource_op(account, resource_tag(), Op::New(Bytes::from_static(&[1])))
+            .unwrap();
+        preimages
+            .extend(state_preimages(&changes).collect())
+            .unwrap();
+        let mut state = InMemoryState::default();
+        state.apply(changes).unwrap();
+        let mut update = ChangeSet::new();
+        update
+            .add_resource_op(
+                account,
+                resource_tag(),
+                Op::Modify(Bytes::from_static(&[2])),
+            )
+            .unwrap();
+        update
+            .add_resource_op(
+                new_account,
+                resource_tag(),
+                Op::New(Bytes::from_static(&[3])),
+            )
+            .unwrap();
+        preimages
+            .extend(state_preimages(&update).collect())
+            .unwrap();
+        state.apply(update).unwrap();
+
+        let exported = export_state(&state, &preimages).unwrap();
+        let resource = &exported.changes.accounts[&account].resources[&resource_tag()];
+        let new_resource = &exported.changes.accounts[&new_account].resources[&resource_tag()];
+
+        assert_eq!(resource, &SerdeOp::New(Bytes::from_static(&[2])));
+        assert_eq!(new_resource, &SerdeOp::New(Bytes::from_static(&[3])));
+        assert_eq!(state_root(&exported).unwrap(), state.state_root());
+    }
+
+    #[test]
+    fn test_export_rejects_key_without_preimage() {
+        let mut changes = ChangeSet::new();
+        changes
+            .add_resource_op(
+                AccountAddress::ONE,
+                resource_tag(),
+                Op::New(Bytes::from_static(&[1])),
+            )
+            .unwrap();
+        let mut state = InMemoryState::default();
+        state.apply(changes).unwrap();
+
+        let result = export_state(&state, &InMemoryTriePreimages::default());
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_remap_addresses_moves_resources_to_new_address() {
+        let from = AccountAddress::from_hex_literal("0xabc").unwrap();
+        let to = AccountAddress::from_hex_literal("0xdef").unwrap();
+        let mut changes = ChangeSet::new();
+        changes
+            .add_resource_op(from, resource_tag(), Op::New(Bytes::from_static(&[1])))
+            .unwrap();
+        let image = image_with(changes);
+
+        let remapped = remap_addresses(image, &BTreeMap::from([(from, to)])).unwrap();
+
+        assert!(!remapped.changes.accounts.contains_key(&from));
+        assert!(
+            remapped.changes.accounts[&to]
+                .resources
+                .contains_key(&resource_tag())
+        );
+    }
+
+    #[test]
+    fn test_remap_addresses_rejects_evm_accounts() {
+        let from = AccountAddress::from_hex_literal("0xabc").unwrap();
+        let to = AccountAddress::from_hex_literal("0xdef").unwrap();
+        let tag = account_info_struct_tag(&from.to_eth_address());
+        let mut changes = ChangeSet::new();
+        changes
+            .add_resource_op(tag.address, tag, Op::New(Bytes::from_static(&[1])))
+            .unwrap();
+        let image = image_with(changes);
+
+        let result = remap_addresses(image, &BTreeMap::from([(from, to)]));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_remap_addresses_rejects_object_owners() {
+        let from = AccountAddress::from_hex_literal("0xabc").unwrap();
+        let to = AccountAddress::from_hex_literal("0xdef").unwrap();
+        let object = AccountAddress::from_hex_literal("0x123").unwrap();
+        let mut object_core = 0u64.to_le_bytes().to_vec();
+        object_core.extend(from.to_vec());
+        let mut changes = ChangeSet::new();
+        changes
+            .add_resource_op(from, resource_tag(), Op::New(Bytes::from_static(&[1])))
+            .unwrap();
+        changes
+            .add_resource_op(
+                object,
+                object_core_struct_tag(),
+                Op::New(object_core.into()),
+            )
+            .unwrap();
+        let image = image_with(changes);
+
+        let result = remap_addresses(image, &BTreeMap::from([(from, to)]));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_remap_addresses_rejects_accounts_with_modules() {
+        let from = AccountAddress::from_hex_literal("0xabc").unwrap();
+        let to = AccountAddress::from_hex_literal("0xdef").unwrap();
+        let mut changes = ChangeSet::new();
+        changes
+            .add_module_op(
+                ModuleId::new(from, ident_str!("counter").into()),
+                Op::New(Bytes::from_static(&[0xa1, 0x1c, 0xeb, 0x0b])),
+            )
+            .unwrap();
+        let image = image_with(changes);
+
+        let result = remap_addresses(image, &BTreeMap::from([(from, to)]));
+
+        assert!(result.is_err());
+    }
+}
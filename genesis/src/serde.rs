@@ -24,13 +24,13 @@ pub enum SerdeOp<T> {
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, serde::Serialize, serde::Deserialize)]
 pub struct SerdeAccountChanges<Module, Resource> {
-    modules: BTreeMap<Identifier, SerdeOp<Module>>,
-    resources: BTreeMap<StructTag, SerdeOp<Resource>>,
+    pub(crate) modules: BTreeMap<Identifier, SerdeOp<Module>>,
+    pub(crate) resources: BTreeMap<StructTag, SerdeOp<Resource>>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, serde::Serialize, serde::Deserialize)]
 pub struct SerdeChanges<Module, Resource> {
-    accounts: BTreeMap<AccountAddress, SerdeAccountChanges<Module, Resource>>,
+    pub(crate) accounts: BTreeMap<AccountAddress, SerdeAccountChanges<Module, Resource>>,
 }
 
 impl From<ChangeSet> for SerdeChanges<Bytes, Bytes> {
@@ -260,6 +260,7 @@ impl From<SerdeEvmStorageTrieChanges> for StorageTrieChanges {
         Self {
             root: value.root,
             trie_diff: value.trie_diff.into_iter().collect(),
+            slots: Vec::new(),
         }
     }
 }
@@ -25,12 +25,32 @@ impl MovedVm {
         );
         let mut natives = aptos_natives_with_builder(&mut builder, false);
         moved_evm_ext::append_evm_natives(&mut natives, &builder);
-        let config = VMConfig {
-            paranoid_type_checks: true,
+        let mut vm_config = VMConfig {
+            paranoid_type_checks: config.move_vm.paranoid_type_checks,
             use_loader_v2: true,
             ..Default::default()
         };
-        let env = RuntimeEnvironment::new_with_config(natives, config);
+        let limits = &config.move_vm.verifier;
+        let verifier = &mut vm_config.verifier_config;
+        verifier.max_basic_blocks = limits.max_basic_blocks.or(verifier.max_basic_blocks);
+        verifier.max_loop_depth = limits.max_loop_depth.or(verifier.max_loop_depth);
+        verifier.max_function_parameters = limits
+            .max_function_parameters
+            .or(verifier.max_function_parameters);
+        verifier.max_type_nodes = limits.max_type_nodes.or(verifier.max_type_nodes);
+        verifier.max_function_definitions = limits
+            .max_function_definitions
+            .or(verifier.max_function_definitions);
+        verifier.max_struct_definitions = limits
+            .max_struct_definitions
+            .or(verifier.max_struct_definitions);
+        verifier.max_per_fun_meter_units = limits
+            .max_per_function_meter_units
+            .or(verifier.max_per_fun_meter_units);
+        verifier.max_per_mod_meter_units = limits
+            .max_per_module_meter_units
+            .or(verifier.max_per_mod_meter_units);
+        let env = RuntimeEnvironment::new_with_config(natives, vm_config);
         Self { env }
     }
 }
@@ -53,3 +73,37 @@ impl CreateMoveVm for MovedVm {
         Ok(vm)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::config::{MoveVmParameters, VerifierLimits},
+    };
+
+    #[test]
+    fn test_vm_applies_configured_limits_over_defaults() {
+        let config = GenesisConfig {
+            move_vm: MoveVmParameters {
+                paranoid_type_checks: false,
+                verifier: VerifierLimits {
+                    max_basic_blocks: Some(64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let default_vm_config = VMConfig::default();
+
+        let vm = MovedVm::new(&config);
+        let vm_config = vm.runtime_environment().vm_config();
+
+        assert!(!vm_config.paranoid_type_checks);
+        assert_eq!(vm_config.verifier_config.max_basic_blocks, Some(64));
+        assert_eq!(
+            vm_config.verifier_config.max_loop_depth,
+            default_vm_config.verifier_config.max_loop_depth
+        );
+    }
+}
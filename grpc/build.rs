@@ -0,0 +1,10 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use the bundled compiler, so that building does not depend on a system wide `protoc`
+    let protoc = protoc_bin_vendored::protoc_bin_path()?;
+    // SAFETY: The build script is single threaded
+    unsafe { std::env::set_var("PROTOC", protoc) };
+
+    tonic_build::configure().compile_protos(&["proto/moved/v1/move_state.proto"], &["proto"])?;
+
+    Ok(())
+}
@@ -0,0 +1,474 @@
+//! gRPC API giving backend services typed access to the Move state.
+//!
+//! The service is defined in `proto/moved/v1/move_state.proto`. It complements the JSON-RPC API
+//! with resource, module and view function access in protobuf, and lets clients subscribe to events
+//! instead of polling for new blocks.
+
+use {
+    crate::pb::{
+        CallViewRequest, CallViewResponse, Event, GetEventsRequest, GetEventsResponse,
+        GetModuleRequest, GetModuleResponse, GetResourceRequest, GetResourceResponse,
+        SubscribeEventsRequest,
+        move_state_server::{MoveState, MoveStateServer},
+    },
+    alloy::eips::BlockNumberOrTag,
+    move_core_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        language_storage::{ModuleId, StructTag, TypeTag},
+    },
+    moved_api::{jsonrpc::JsonRpcError, simulate},
+    moved_app::{ApplicationReader, Dependencies, DependenciesThreadSafe},
+    moved_shared::error::Error,
+    std::{future::Future, net::SocketAddr, str::FromStr, time::Duration},
+    tokio::sync::mpsc,
+    tokio_stream::wrappers::ReceiverStream,
+    tonic::{Request, Response, Status},
+};
+
+pub mod pb {
+    tonic::include_proto!("moved.v1");
+}
+
+/// Widest block range a single [`MoveState::get_events`] request may cover.
+pub const MAX_EVENTS_RANGE: u64 = 1_000;
+/// Events buffered for a subscriber before the subscription waits for it to catch up.
+const SUBSCRIPTION_BUFFER: usize = 1_024;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct MoveStateService<D: Dependencies> {
+    app: ApplicationReader<D>,
+}
+
+impl<D: Dependencies> MoveStateService<D> {
+    pub fn new(app: ApplicationReader<D>) -> Self {
+        Self { app }
+    }
+
+    fn known_height(&self, height: Option<u64>) -> Result<BlockNumberOrTag, Status> {
+        match height {
+            Some(height) if height > self.app.block_number() => {
+                Err(Status::not_found(format!("Block {height} is not known")))
+            }
+            Some(height) => Ok(BlockNumberOrTag::Number(height)),
+            None => Ok(BlockNumberOrTag::Latest),
+        }
+    }
+}
+
+/// Serves the [`MoveState`] service on `addr` until `shutdown` completes.
+pub async fn serve<D: DependenciesThreadSafe>(
+    addr: SocketAddr,
+    app: ApplicationReader<D>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), tonic::transport::Error>
+where
+    ApplicationReader<D>: Send + Sync + 'static,
+{
+    tonic::transport::Server::builder()
+        .add_service(MoveStateServer::new(MoveStateService::new(app)))
+        .serve_with_shutdown(addr, shutdown)
+        .await
+}
+
+#[tonic::async_trait]
+impl<D: DependenciesThreadSafe> MoveState for MoveStateService<D>
+where
+    ApplicationReader<D>: Send + Sync + 'static,
+{
+    type SubscribeEventsStream = ReceiverStream<Result<Event, Status>>;
+
+    async fn get_resource(
+        &self,
+        request: Request<GetResourceRequest>,
+    ) -> Result<Response<GetResourceResponse>, Status> {
+        let request = request.into_inner();
+        let address = parse_address(&request.address)?;
+        let tag = StructTag::from_str(&request.struct_tag)
+            .map_err(|e| Status::invalid_argument(format!("Invalid struct tag: {e}")))?;
+        let height = self.known_height(request.height)?;
+        let value = simulate::with_timeout(&self.app, move |app| {
+            app.resource_by_height(address, &tag, height)
+        })
+        .await
+        .map_err(timeout_error)?;
+
+        Ok(Response::new(GetResourceResponse { value }))
+    }
+
+    async fn get_module(
+        &self,
+        request: Request<GetModuleRequest>,
+    ) -> Result<Response<GetModuleResponse>, Status> {
+        let request = request.into_inner();
+        let id = ModuleId::new(
+            parse_address(&request.address)?,
+            parse_identifier(&request.name)?,
+        );
+        let height = self.known_height(request.height)?;
+
+        Ok(Response::new(GetModuleResponse {
+            bytecode: self.app.module_by_height(&id, height),
+        }))
+    }
+
+    async fn call_view(
+        &self,
+        request: Request<CallViewRequest>,
+    ) -> Result<Response<CallViewResponse>, Status> {
+        let request = request.into_inner();
+        let (module, function) = parse_function(&request.function)?;
+        let ty_args = request
+            .type_args
+            .iter()
+            .map(|tag| {
+                TypeTag::from_str(tag)
+                    .map_err(|e| Status::invalid_argument(format!("Invalid type argument: {e}")))
+            })
+            .collect::<Result<_, _>>()?;
+        let height = self.known_height(request.height)?;
+
+        let return_values = simulate::with_timeout(&self.app, move |app| {
+            app.view(module, function, ty_args, request.args, height)
+        })
+        .await
+        .map_err(timeout_error)?
+        .map_err(view_error)?
+        .into_iter()
+        .map(|value| value.bytes)
+        .collect();
+
+        Ok(Response::new(CallViewResponse { return_values }))
+    }
+
+    async fn get_events(
+        &self,
+        request: Request<GetEventsRequest>,
+    ) -> Result<Response<GetEventsResponse>, Status> {
+        let GetEventsRequest {
+            from_height,
+            to_height,
+        } = request.into_inner();
+        if from_height > to_height {
+            return Err(Status::invalid_argument("Empty block range"));
+        }
+        if to_height - from_height >= MAX_EVENTS_RANGE {
+            return Err(Status::invalid_argument(format!(
+                "Block range is limited to {MAX_EVENTS_RANGE} blocks"
+            )));
+        }
+        let to_height = to_height.min(self.app.block_number());
+
+        let events = (from_height..=to_height)
+            .flat_map(|height| events_at(&self.app, height))
+            .collect();
+
+        Ok(Response::new(GetEventsResponse { events }))
+    }
+
+    async fn subscribe_events(
+        &self,
+        request: Request<SubscribeEventsRequest>,
+    ) -> Result<Response<Self::SubscribeEventsStream>, Status> {
+        let app = self.app.clone();
+        let mut next = request
+            .into_inner()
+            .from_height
+            .unwrap_or_else(|| app.block_number() + 1);
+        let (tx, rx) = mpsc::channel(SUBSCRIPTION_BUFFER);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                // The subscriber has gone away while no events were sent to it
+                if tx.is_closed() {
+                    return;
+                }
+
+                while next <= app.block_number() {
+                    for event in events_at(&app, next) {
+                        // The subscriber has gone away
+                        if tx.send(Ok(event)).await.is_err() {
+                            return;
+                        }
+                    }
+                    next += 1;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+/// Collects the events emitted by the transactions of the block at `height`.
+fn events_at(app: &ApplicationReader<impl Dependencies>, height: u64) -> Vec<Event> {
+    let Some(block) = app.block_by_height(BlockNumberOrTag::Number(height), false) else {
+        return Vec::new();
+    };
+
+    block
+        .block
+        .transactions
+        .hashes()
+        .filter_map(|hash| app.transaction_receipt(hash))
+        .flat_map(|receipt| receipt.inner.inner.logs().to_vec())
+        .map(|log| Event {
+            height,
+            transaction_hash: log.transaction_hash.unwrap_or_default().to_vec(),
+            log_index: log.log_index.unwrap_or_default(),
+            address: log.inner.address.to_vec(),
+            topics: log
+                .inner
+                .topics()
+                .iter()
+                .map(|topic| topic.to_vec())
+                .collect(),
+            data: log.inner.data.data.to_vec(),
+        })
+        .collect()
+}
+
+/// Reports an execution that ran out of the simulation timeout of the JSON-RPC API.
+fn timeout_error(e: JsonRpcError) -> Status {
+    Status::deadline_exceeded(e.message)
+}
+
+/// Blames the client for a view call that fails because of its request or the function it called.
+fn view_error(e: Error) -> Status {
+    match e {
+        Error::User(e) => Status::invalid_argument(e.to_string()),
+        Error::InvalidTransaction(e) => Status::invalid_argument(e.to_string()),
+        Error::InvariantViolation(e) => Status::internal(e.to_string()),
+    }
+}
+
+fn parse_address(bytes: &[u8]) -> Result<AccountAddress, Status> {
+    AccountAddress::from_bytes(bytes)
+        .map_err(|e| Status::invalid_argument(format!("Invalid address: {e}")))
+}
+
+fn parse_identifier(name: &str) -> Result<Identifier, Status> {
+    Identifier::new(name).map_err(|e| Status::invalid_argument(format!("Invalid name: {e}")))
+}
+
+/// Splits a fully qualified function name like `0x1::coin::balance` into its module and name.
+fn parse_function(name: &str) -> Result<(ModuleId, Identifier), Status> {
+    let [address, module, function] = name.split("::").collect::<Vec<_>>()[..] else {
+        return Err(Status::invalid_argument(format!(
+            "Expected a function name of the form address::module::function, got {name}"
+        )));
+    };
+    let address = AccountAddress::from_hex_literal(address)
+        .map_err(|e| Status::invalid_argument(format!("Invalid address: {e}")))?;
+
+    Ok((
+        ModuleId::new(address, parse_identifier(module)?),
+        parse_identifier(function)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        alloy::primitives::hex,
+        move_core_types::ident_str,
+        moved_app::{GasEstimateCache, GasPriceOracle, MemPool, ResponseCache, TestDependencies},
+        moved_blockchain::{
+            block::{
+                Block, BlockRepository, Header, InMemoryBlockQueries, InMemoryBlockRepository,
+            },
+            in_memory::shared_memory,
+            payload::InMemoryPayloadQueries,
+            receipt::{InMemoryReceiptQueries, receipt_memory},
+            state::InMemoryStateQueries,
+            transaction::InMemoryTransactionQueries,
+        },
+        moved_evm_ext::state::InMemoryStorageTrieRepository,
+        moved_execution::MovedBaseTokenAccounts,
+        moved_genesis::config::GenesisConfig,
+        moved_shared::primitives::{B256, U256},
+        moved_state::{InMemoryState, resource_index::InMemoryResourceIndex},
+        tonic::Code,
+    };
+
+    /// Serves the state of a chain that has nothing but its genesis block.
+    fn create_service() -> MoveStateService<TestDependencies> {
+        let genesis_config = GenesisConfig::default();
+        let genesis_block = Block::new(
+            Header {
+                state_root: genesis_config.initial_state_root,
+                ..Default::default()
+            },
+            Vec::new(),
+        )
+        .with_hash(B256::new(hex!(
+            "e56ec7ba741931e8c55b7f654a6e56ed61cf8b8279bf5e3ef6ac86a11eb33a9d"
+        )))
+        .with_value(U256::ZERO);
+
+        let (memory_reader, mut memory) = shared_memory::new();
+        InMemoryBlockRepository::new()
+            .add(&mut memory, genesis_block)
+            .unwrap();
+        let trie_db = InMemoryState::create_db();
+        let resource_index = InMemoryResourceIndex::default();
+        let mut state =
+            InMemoryState::new(trie_db.clone()).with_resource_index(resource_index.clone());
+        let state_queries = InMemoryStateQueries::new(
+            memory_reader.clone(),
+            trie_db,
+            genesis_config.initial_state_root,
+        )
+        .with_resource_index(resource_index);
+        let mut evm_storage = InMemoryStorageTrieRepository::new();
+        let (changes, table_changes, evm_storage_changes) = moved_genesis_image::load();
+        moved_genesis::apply(
+            changes,
+            table_changes,
+            evm_storage_changes,
+            &genesis_config,
+            &mut state,
+            &mut evm_storage,
+        );
+        let (receipt_memory_reader, _) = receipt_memory::new();
+
+        MoveStateService::new(ApplicationReader {
+            genesis_config: genesis_config.clone(),
+            base_token: MovedBaseTokenAccounts::new(AccountAddress::ONE),
+            block_queries: InMemoryBlockQueries,
+            payload_queries: InMemoryPayloadQueries::new(),
+            receipt_queries: InMemoryReceiptQueries::new(),
+            receipt_memory: receipt_memory_reader,
+            storage: memory_reader,
+            state_queries,
+            evm_storage,
+            transaction_queries: InMemoryTransactionQueries::new(),
+            gas_price_oracle: GasPriceOracle::new(genesis_config.gas_price_oracle.clone()),
+            gas_estimates: GasEstimateCache::default(),
+            responses: ResponseCache::default(),
+            l1_fee: U256::ZERO,
+            mem_pool: MemPool::default(),
+            payload_expiry: Default::default(),
+            peer_count: Default::default(),
+        })
+    }
+
+    fn view_request(function: &str, args: Vec<Vec<u8>>) -> Request<CallViewRequest> {
+        Request::new(CallViewRequest {
+            function: function.into(),
+            type_args: Vec::new(),
+            args,
+            height: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_call_view_returns_bcs_values() {
+        let service = create_service();
+        let address = AccountAddress::new([0x11; 32]);
+
+        let response = service
+            .call_view(view_request(
+                "0x1::account::exists_at",
+                vec![address.to_vec()],
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.into_inner().return_values, vec![vec![0]]);
+    }
+
+    #[tokio::test]
+    async fn test_call_view_rejects_function_that_is_not_a_view() {
+        let service = create_service();
+        // A vector of bytes is its length followed by the bytes
+        let argument = b"\x03umi".to_vec();
+
+        let status = service
+            .call_view(view_request("0x1::string::utf8", vec![argument]))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_call_view_rejects_malformed_arguments() {
+        let service = create_service();
+
+        let status = service
+            .call_view(view_request("0x1::account::exists_at", vec![vec![0x11]]))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_get_resource_above_latest_block_is_not_found() {
+        let service = create_service();
+
+        let status = service
+            .get_resource(Request::new(GetResourceRequest {
+                address: AccountAddress::ONE.to_vec(),
+                struct_tag: "0x1::account::Account".into(),
+                height: Some(1),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_get_module_reads_framework_bytecode() {
+        let service = create_service();
+
+        let response = service
+            .get_module(Request::new(GetModuleRequest {
+                address: AccountAddress::ONE.to_vec(),
+                name: "account".into(),
+                height: Some(0),
+            }))
+            .await
+            .unwrap();
+
+        assert!(response.into_inner().bytecode.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_get_events_rejects_range_above_limit() {
+        let service = create_service();
+
+        let status = service
+            .get_events(Request::new(GetEventsRequest {
+                from_height: 0,
+                to_height: MAX_EVENTS_RANGE,
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_parse_function_splits_module_and_name() {
+        let (module, function) = parse_function("0x1::coin::balance").unwrap();
+
+        assert_eq!(
+            module,
+            ModuleId::new(AccountAddress::ONE, ident_str!("coin").into())
+        );
+        assert_eq!(function.as_str(), "balance");
+    }
+
+    #[test]
+    fn test_parse_function_rejects_partial_names() {
+        assert!(parse_function("0x1::coin").is_err());
+        assert!(parse_function("coin::balance::extra::parts").is_err());
+    }
+}
@@ -10,6 +10,7 @@ use {
         payload::PayloadId,
     },
     moved_shared::primitives::{Address, Bytes, B256, B64, U256, U64},
+    std::time::Instant,
 };
 
 lazy_static! {
@@ -59,6 +60,9 @@ pub const GENESIS: ExtendedBlock = {
             },
             transactions: Vec::new(),
         },
+        size: 0,
+        rejected_transactions: Vec::new(),
+        l1_origin: None,
     }
 };
 
@@ -75,11 +79,13 @@ pub fn blocks_1000() -> impl Iterator<Item = Command> {
                     "4200000000000000000000000000000000000011"
                 )),
                 withdrawals: Vec::new(),
-                parent_beacon_block_root: B256::ZERO,
+                parent_beacon_block_root: Some(B256::ZERO),
                 transactions: vec![tx.clone()],
                 gas_limit: U64::from_limbs([30000000u64]),
+                eip1559_params: None,
             },
             payload_id: PayloadId::from_limbs([i]),
+            requested_at: Instant::now(),
         }
     })
 }
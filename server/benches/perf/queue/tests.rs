@@ -5,7 +5,7 @@ use {
         Throughput,
     },
     moved_app::{Application, DependenciesThreadSafe},
-    moved_genesis::config::GenesisConfig,
+    moved_genesis::{config::GenesisConfig, genesis_header},
     moved_server::initialize_app,
     std::process::Termination,
     tokio::runtime::Runtime,
@@ -47,7 +47,7 @@ fn bench_build_1000_blocks_with_queue_size(bencher: &mut Criterion) -> impl Term
         .into_iter()
         .rev()
     {
-        let (mut app, _app_reader) = initialize_app(GenesisConfig::default());
+        let (mut app, _app_reader) = initialize_app(GenesisConfig::default(), genesis_header);
 
         app.genesis_update(input::GENESIS);
 
@@ -0,0 +1,138 @@
+//! JSON bodies of HTTP responses that are sent while being serialized.
+//!
+//! A full block with hundreds of transactions or a long list of logs encodes to megabytes of JSON.
+//! Rather than encoding the whole response before sending anything, it is written in chunks on a
+//! blocking thread and each chunk is sent as soon as it is full. Only a few chunks are buffered
+//! ahead of the client, so serialization waits for a slow client instead of piling up memory.
+//!
+//! The status of the response is sent before its body, so a value that fails to serialize midway
+//! cannot turn it into an error response anymore. The body then ends with an error instead, which
+//! aborts the response rather than ending it as if it were complete.
+
+use {
+    futures_util::stream,
+    serde::Serialize,
+    std::io,
+    tokio::sync::mpsc,
+    warp::hyper::{body::Bytes, Body},
+};
+
+/// Size of the chunks that a body is sent in.
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Most chunks serialized ahead of the client reading them.
+const MAX_PENDING_CHUNKS: usize = 4;
+
+/// Serializes `value` as JSON into a body streamed to the client.
+///
+/// A response smaller than a chunk is sent in one piece.
+pub fn json(value: impl Serialize + Send + 'static) -> Body {
+    let (sender, receiver) = mpsc::channel(MAX_PENDING_CHUNKS);
+
+    tokio::task::spawn_blocking(move || {
+        let mut writer = ChunkWriter::new(sender);
+        let result = serde_json::to_writer(&mut writer, &value)
+            .map_err(io::Error::from)
+            .and_then(|()| writer.send());
+        match result {
+            // The client went away, there is no one left to tell
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => (),
+            Err(e) => {
+                println!("WARN: failed to serialize a response: {e:?}");
+                writer.sender.blocking_send(Err(e)).ok();
+            }
+            Ok(()) => (),
+        }
+    });
+
+    Body::wrap_stream(stream::unfold(receiver, |mut receiver| async move {
+        let chunk = receiver.recv().await?;
+        Some((chunk, receiver))
+    }))
+}
+
+struct ChunkWriter {
+    chunk: Vec<u8>,
+    sender: mpsc::Sender<io::Result<Bytes>>,
+}
+
+impl ChunkWriter {
+    fn new(sender: mpsc::Sender<io::Result<Bytes>>) -> Self {
+        Self {
+            chunk: Vec::with_capacity(CHUNK_SIZE),
+            sender,
+        }
+    }
+
+    /// Sends the current chunk, waiting if the client is behind.
+    fn send(&mut self) -> io::Result<()> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+
+        let chunk = std::mem::replace(&mut self.chunk, Vec::with_capacity(CHUNK_SIZE));
+        self.sender
+            .blocking_send(Ok(Bytes::from(chunk)))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Response body was dropped"))
+    }
+}
+
+impl io::Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.chunk.extend_from_slice(buf);
+        if self.chunk.len() >= CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde::{ser::Error, Serializer},
+        serde_json::json,
+    };
+
+    /// Serializes as a long list of numbers that fails once more than a chunk is written.
+    struct FailingMidway;
+
+    impl Serialize for FailingMidway {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeSeq;
+
+            let mut seq = serializer.serialize_seq(None)?;
+            for i in 0..CHUNK_SIZE {
+                seq.serialize_element(&i)?;
+            }
+            Err(S::Error::custom("failed midway"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streamed_body_is_the_json_of_the_value() {
+        let value = json!({
+            "id": 1,
+            "logs": (0..10_000).map(|i| format!("log {i}")).collect::<Vec<_>>(),
+        });
+
+        let body = hyper::body::to_bytes(json(value.clone())).await.unwrap();
+
+        assert!(body.len() > CHUNK_SIZE);
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            value
+        );
+    }
+
+    #[tokio::test]
+    async fn test_body_that_fails_to_serialize_midway_ends_with_error() {
+        let result = hyper::body::to_bytes(json(FailingMidway)).await;
+
+        assert!(result.is_err());
+    }
+}
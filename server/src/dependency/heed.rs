@@ -1,11 +1,10 @@
 use {
     crate::dependency::shared::*,
-    moved_app::{Application, ApplicationReader, CommandActor},
+    moved_app::{Application, ApplicationReader},
     moved_genesis::config::GenesisConfig,
-    moved_state::State,
     moved_storage_heed::{
-        block, evm, evm_storage_trie, heed::EnvOpenOptions, payload, receipt, state, transaction,
-        trie,
+        block, evm, evm_storage_trie, heed::EnvOpenOptions, payload, preimage, receipt,
+        resource_index, state, transaction, trie,
     },
 };
 
@@ -17,20 +16,28 @@ pub fn create(
     Application<HeedDependencies>,
     ApplicationReader<HeedDependencies>,
 ) {
-    (
-        Application::new(HeedDependencies, genesis_config),
-        ApplicationReader::new(HeedDependencies, genesis_config),
-    )
+    let app = Application::new(HeedDependencies, genesis_config);
+    let reader = ApplicationReader::new(HeedDependencies, genesis_config)
+        .with_mem_pool(app.mem_pool.clone())
+        .with_payload_expiry(app.payload_expiry.clone());
+
+    (app, reader)
+}
+
+/// The preimages of the state trie keys recorded in the database.
+pub fn trie_preimages() -> preimage::HeedTriePreimages {
+    preimage::HeedTriePreimages::new(db())
 }
 
 pub struct HeedDependencies;
 
+impl_state_root_recorder!(HeedDependencies);
+
 impl moved_app::Dependencies for HeedDependencies {
     type BlockQueries = block::HeedBlockQueries;
     type BlockRepository = block::HeedBlockRepository;
     type OnPayload = moved_app::OnPayload<Application<Self>>;
-    type OnTx = moved_app::OnTx<Application<Self>>;
-    type OnTxBatch = moved_app::OnTxBatch<Application<Self>>;
+    type PayloadJobRepository = payload::HeedPayloadJobRepository;
     type PayloadQueries = payload::HeedPayloadQueries;
     type ReceiptQueries = receipt::HeedReceiptQueries;
     type ReceiptRepository = receipt::HeedReceiptRepository;
@@ -43,6 +50,7 @@ impl moved_app::Dependencies for HeedDependencies {
     type StorageTrieRepository = evm::HeedStorageTrieRepository;
     type TransactionQueries = transaction::HeedTransactionQueries;
     type TransactionRepository = transaction::HeedTransactionRepository;
+    type TransactionJournal = transaction::HeedTransactionJournal;
 
     fn block_queries() -> Self::BlockQueries {
         block::HeedBlockQueries
@@ -56,17 +64,27 @@ impl moved_app::Dependencies for HeedDependencies {
         &|state, id, hash| state.payload_queries.add_block_hash(id, hash).unwrap()
     }
 
-    fn on_tx() -> &'static Self::OnTx {
-        CommandActor::on_tx_noop()
+    fn block_execution_observers() -> moved_app::BlockExecutionObservers<Self> {
+        let mut observers: moved_app::BlockExecutionObservers<Self> =
+            vec![Box::new(StateRootRecorder)];
+        if is_trie_consistency_check_enabled() {
+            observers.push(Box::new(moved_app::TrieConsistencyChecker::default()));
+        }
+        if is_trie_preimage_recording_enabled() {
+            observers.push(Box::new(moved_app::TriePreimageRecorder::new(
+                trie_preimages(),
+            )));
+        }
+        if let Some(dir) = execution_witness_dir() {
+            let export = moved_app::ExecutionWitnessDir::new(dir)
+                .expect("Execution witness directory should be writable");
+            observers.push(Box::new(moved_app::ExecutionWitnessRecorder::new(export)));
+        }
+        observers
     }
 
-    fn on_tx_batch() -> &'static Self::OnTxBatch {
-        &|state| {
-            state
-                .state_queries
-                .push_state_root(state.state.state_root())
-                .unwrap()
-        }
+    fn payload_job_repository() -> Self::PayloadJobRepository {
+        payload::HeedPayloadJobRepository::new(db())
     }
 
     fn payload_queries() -> Self::PayloadQueries {
@@ -117,6 +135,10 @@ impl moved_app::Dependencies for HeedDependencies {
         transaction::HeedTransactionRepository
     }
 
+    fn transaction_journal() -> Self::TransactionJournal {
+        transaction::HeedTransactionJournal::new(db())
+    }
+
     impl_shared!();
 }
 
@@ -134,7 +156,7 @@ fn db() -> &'static moved_storage_heed::Env {
 }
 
 fn create_db() -> moved_storage_heed::Env {
-    assert_eq!(moved_storage_heed::DATABASES.len(), 11);
+    assert_eq!(moved_storage_heed::DATABASES.len(), 15);
 
     let path = "db";
 
@@ -161,6 +183,12 @@ fn create_db() -> moved_storage_heed::Env {
         let _: block::HeightDb = env
             .create_database(&mut transaction, Some(block::HEIGHT_DB))
             .expect("Database should be new");
+        let _: block::ForkchoiceDb = env
+            .create_database(&mut transaction, Some(block::FORKCHOICE_DB))
+            .expect("Database should be new");
+        let _: block::TransactionCountDb = env
+            .create_database(&mut transaction, Some(block::TRANSACTION_COUNT_DB))
+            .expect("Database should be new");
         let _: state::Db = env
             .create_database(&mut transaction, Some(state::DB))
             .expect("Database should be new");
@@ -182,12 +210,24 @@ fn create_db() -> moved_storage_heed::Env {
         let _: transaction::Db = env
             .create_database(&mut transaction, Some(transaction::DB))
             .expect("Database should be new");
+        let _: transaction::JournalDb = env
+            .create_database(&mut transaction, Some(transaction::JOURNAL_DB))
+            .expect("Database should be new");
         let _: receipt::Db = env
             .create_database(&mut transaction, Some(receipt::DB))
             .expect("Database should be new");
         let _: payload::Db = env
             .create_database(&mut transaction, Some(payload::DB))
             .expect("Database should be new");
+        let _: payload::JobDb<moved_app::Payload> = env
+            .create_database(&mut transaction, Some(payload::JOB_DB))
+            .expect("Database should be new");
+        let _: preimage::Db = env
+            .create_database(&mut transaction, Some(preimage::DB))
+            .expect("Database should be new");
+        let _: resource_index::Db = env
+            .create_database(&mut transaction, Some(resource_index::DB))
+            .expect("Database should be new");
 
         transaction.commit().expect("Transaction should succeed");
     }
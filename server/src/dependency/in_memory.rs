@@ -15,11 +15,19 @@ pub fn create(
 ) {
     let deps = InMemoryDependencies::new();
     let reader_deps = deps.reader();
+    let app = Application::new(deps, genesis_config);
+    let reader = ApplicationReader::new(reader_deps, genesis_config)
+        .with_mem_pool(app.mem_pool.clone())
+        .with_payload_expiry(app.payload_expiry.clone());
 
-    (
-        Application::new(deps, genesis_config),
-        ApplicationReader::new(reader_deps, genesis_config),
-    )
+    (app, reader)
+}
+
+/// The preimages of the state trie keys.
+///
+/// They are kept in memory, so a new instance only knows the preimages it records itself.
+pub fn trie_preimages() -> moved_state::preimage::InMemoryTriePreimages {
+    moved_state::preimage::InMemoryTriePreimages::default()
 }
 
 pub struct InMemoryDependencies {
@@ -28,13 +36,18 @@ pub struct InMemoryDependencies {
     receipt_memory_reader: moved_blockchain::receipt::ReceiptMemoryReader,
     receipt_memory: Option<moved_blockchain::receipt::ReceiptMemory>,
     trie_db: Arc<moved_state::InMemoryTrieDb>,
+    resource_index: moved_state::resource_index::InMemoryResourceIndex,
 }
 
 impl InMemoryDependencies {
     pub fn new() -> Self {
-        let (memory_reader, memory) = moved_blockchain::in_memory::shared_memory::new();
-        let (receipt_memory_reader, receipt_memory) =
+        let (memory_reader, mut memory) = moved_blockchain::in_memory::shared_memory::new();
+        let (receipt_memory_reader, mut receipt_memory) =
             moved_blockchain::receipt::receipt_memory::new();
+        if let Some(retained_blocks) = retained_blocks() {
+            let window = memory.set_retained_blocks(retained_blocks);
+            receipt_memory.set_eviction_window(window);
+        }
 
         Self {
             memory_reader,
@@ -42,6 +55,7 @@ impl InMemoryDependencies {
             receipt_memory_reader,
             receipt_memory: Some(receipt_memory),
             trie_db: moved_state::InMemoryState::create_db(),
+            resource_index: Default::default(),
         }
     }
 
@@ -55,10 +69,26 @@ impl InMemoryDependencies {
             receipt_memory_reader: self.receipt_memory_reader.clone(),
             receipt_memory: None,
             trie_db: self.trie_db.clone(),
+            resource_index: self.resource_index.clone(),
         }
     }
 }
 
+/// Number of most recent blocks kept in memory along with their transactions and receipts, set in
+/// the `IN_MEMORY_RETAINED_BLOCKS` env var. All of them are kept if it is not set.
+///
+/// The same blocks are kept by every store, which are at least the last 256 ones and every block
+/// from the safe and finalized ones, see `BlockMemory::set_retained_blocks`.
+fn retained_blocks() -> Option<u64> {
+    std::env::var("IN_MEMORY_RETAINED_BLOCKS")
+        .ok()
+        .map(|blocks| {
+            blocks
+                .parse()
+                .expect("IN_MEMORY_RETAINED_BLOCKS should be a positive number")
+        })
+}
+
 impl Default for InMemoryDependencies {
     fn default() -> Self {
         Self::new()
@@ -69,8 +99,8 @@ impl moved_app::Dependencies for InMemoryDependencies {
     type BlockQueries = moved_blockchain::block::InMemoryBlockQueries;
     type BlockRepository = moved_blockchain::block::InMemoryBlockRepository;
     type OnPayload = moved_app::OnPayload<Application<Self>>;
-    type OnTx = moved_app::OnTx<Application<Self>>;
-    type OnTxBatch = moved_app::OnTxBatch<Application<Self>>;
+    type PayloadJobRepository =
+        moved_blockchain::payload::InMemoryPayloadJobRepository<moved_app::Payload>;
     type PayloadQueries = moved_blockchain::payload::InMemoryPayloadQueries;
     type ReceiptQueries = moved_blockchain::receipt::InMemoryReceiptQueries;
     type ReceiptRepository = moved_blockchain::receipt::InMemoryReceiptRepository;
@@ -83,6 +113,7 @@ impl moved_app::Dependencies for InMemoryDependencies {
     type StorageTrieRepository = moved_evm_ext::state::InMemoryStorageTrieRepository;
     type TransactionQueries = moved_blockchain::transaction::InMemoryTransactionQueries;
     type TransactionRepository = moved_blockchain::transaction::InMemoryTransactionRepository;
+    type TransactionJournal = moved_blockchain::transaction::InMemoryTransactionJournal;
 
     fn block_queries() -> Self::BlockQueries {
         moved_blockchain::block::InMemoryBlockQueries
@@ -96,12 +127,30 @@ impl moved_app::Dependencies for InMemoryDependencies {
         CommandActor::on_payload_in_memory()
     }
 
-    fn on_tx() -> &'static Self::OnTx {
-        CommandActor::on_tx_in_memory()
+    fn block_execution_observers() -> moved_app::BlockExecutionObservers<Self> {
+        let mut observers: moved_app::BlockExecutionObservers<Self> =
+            vec![Box::new(moved_app::StatePruner)];
+        if retained_blocks().is_some() {
+            observers.push(Box::new(moved_app::MemoryUsageReporter));
+        }
+        if is_trie_consistency_check_enabled() {
+            observers.push(Box::new(moved_app::TrieConsistencyChecker::default()));
+        }
+        if is_trie_preimage_recording_enabled() {
+            observers.push(Box::new(moved_app::TriePreimageRecorder::new(
+                trie_preimages(),
+            )));
+        }
+        if let Some(dir) = execution_witness_dir() {
+            let export = moved_app::ExecutionWitnessDir::new(dir)
+                .expect("Execution witness directory should be writable");
+            observers.push(Box::new(moved_app::ExecutionWitnessRecorder::new(export)));
+        }
+        observers
     }
 
-    fn on_tx_batch() -> &'static Self::OnTxBatch {
-        CommandActor::on_tx_batch_in_memory()
+    fn payload_job_repository() -> Self::PayloadJobRepository {
+        moved_blockchain::payload::InMemoryPayloadJobRepository::new()
     }
 
     fn payload_queries() -> Self::PayloadQueries {
@@ -138,6 +187,7 @@ impl moved_app::Dependencies for InMemoryDependencies {
 
     fn state(&self) -> Self::State {
         moved_state::InMemoryState::new(self.trie_db.clone())
+            .with_resource_index(self.resource_index.clone())
     }
 
     fn state_queries(&self, genesis_config: &GenesisConfig) -> Self::StateQueries {
@@ -146,6 +196,7 @@ impl moved_app::Dependencies for InMemoryDependencies {
             self.trie_db.clone(),
             genesis_config.initial_state_root,
         )
+        .with_resource_index(self.resource_index.clone())
     }
 
     fn storage_trie_repository() -> Self::StorageTrieRepository {
@@ -160,5 +211,9 @@ impl moved_app::Dependencies for InMemoryDependencies {
         moved_blockchain::transaction::InMemoryTransactionRepository::new()
     }
 
+    fn transaction_journal() -> Self::TransactionJournal {
+        moved_blockchain::transaction::InMemoryTransactionJournal::new()
+    }
+
     impl_shared!();
 }
@@ -1,8 +1,7 @@
 use {
     crate::dependency::shared::*,
-    moved_app::{Application, ApplicationReader, CommandActor},
+    moved_app::{Application, ApplicationReader},
     moved_genesis::config::GenesisConfig,
-    moved_state::State,
 };
 
 pub type Dependency = RocksDbDependencies;
@@ -13,20 +12,48 @@ pub fn create(
     Application<RocksDbDependencies>,
     ApplicationReader<RocksDbDependencies>,
 ) {
-    (
-        Application::new(RocksDbDependencies, genesis_config),
-        ApplicationReader::new(RocksDbDependencies, genesis_config),
-    )
+    let app = Application::new(RocksDbDependencies, genesis_config);
+    let reader = ApplicationReader::new(RocksDbDependencies, genesis_config)
+        .with_mem_pool(app.mem_pool.clone())
+        .with_payload_expiry(app.payload_expiry.clone());
+
+    (app, reader)
+}
+
+/// The preimages of the state trie keys recorded in the database.
+pub fn trie_preimages() -> moved_storage_rocksdb::preimage::RocksDbTriePreimages {
+    moved_storage_rocksdb::preimage::RocksDbTriePreimages::new(db())
+}
+
+/// Creates only the [`ApplicationReader`], which is all a read replica needs.
+///
+/// See [`is_read_replica`].
+pub fn create_reader(genesis_config: &GenesisConfig) -> ApplicationReader<RocksDbDependencies> {
+    ApplicationReader::new(RocksDbDependencies, genesis_config)
+}
+
+/// Whether this process serves queries from the datadir of another op-move process instead of
+/// building blocks on its own.
+///
+/// Enabled by setting the `READ_REPLICA` env var to `1`.
+pub fn is_read_replica() -> bool {
+    std::env::var("READ_REPLICA").as_ref().map(String::as_str) == Ok("1")
+}
+
+/// Makes the writes of the primary process visible to this read replica.
+pub fn catch_up_with_primary() -> Result<(), moved_storage_rocksdb::rocksdb::Error> {
+    db().try_catch_up_with_primary()
 }
 
 pub struct RocksDbDependencies;
 
+impl_state_root_recorder!(RocksDbDependencies);
+
 impl moved_app::Dependencies for RocksDbDependencies {
     type BlockQueries = moved_storage_rocksdb::block::RocksDbBlockQueries;
     type BlockRepository = moved_storage_rocksdb::block::RocksDbBlockRepository;
     type OnPayload = moved_app::OnPayload<Application<Self>>;
-    type OnTx = moved_app::OnTx<Application<Self>>;
-    type OnTxBatch = moved_app::OnTxBatch<Application<Self>>;
+    type PayloadJobRepository = moved_storage_rocksdb::payload::RocksDbPayloadJobRepository;
     type PayloadQueries = moved_storage_rocksdb::payload::RocksDbPayloadQueries;
     type ReceiptQueries = moved_storage_rocksdb::receipt::RocksDbReceiptQueries;
     type ReceiptRepository = moved_storage_rocksdb::receipt::RocksDbReceiptRepository;
@@ -39,6 +66,7 @@ impl moved_app::Dependencies for RocksDbDependencies {
     type StorageTrieRepository = moved_storage_rocksdb::evm::RocksDbStorageTrieRepository;
     type TransactionQueries = moved_storage_rocksdb::transaction::RocksDbTransactionQueries;
     type TransactionRepository = moved_storage_rocksdb::transaction::RocksDbTransactionRepository;
+    type TransactionJournal = moved_storage_rocksdb::transaction::RocksDbTransactionJournal;
 
     fn block_queries() -> Self::BlockQueries {
         moved_storage_rocksdb::block::RocksDbBlockQueries
@@ -52,17 +80,27 @@ impl moved_app::Dependencies for RocksDbDependencies {
         &|state, id, hash| state.payload_queries.add_block_hash(id, hash).unwrap()
     }
 
-    fn on_tx() -> &'static Self::OnTx {
-        CommandActor::on_tx_noop()
+    fn block_execution_observers() -> moved_app::BlockExecutionObservers<Self> {
+        let mut observers: moved_app::BlockExecutionObservers<Self> =
+            vec![Box::new(StateRootRecorder)];
+        if is_trie_consistency_check_enabled() {
+            observers.push(Box::new(moved_app::TrieConsistencyChecker::default()));
+        }
+        if is_trie_preimage_recording_enabled() {
+            observers.push(Box::new(moved_app::TriePreimageRecorder::new(
+                trie_preimages(),
+            )));
+        }
+        if let Some(dir) = execution_witness_dir() {
+            let export = moved_app::ExecutionWitnessDir::new(dir)
+                .expect("Execution witness directory should be writable");
+            observers.push(Box::new(moved_app::ExecutionWitnessRecorder::new(export)));
+        }
+        observers
     }
 
-    fn on_tx_batch() -> &'static Self::OnTxBatch {
-        &|state| {
-            state
-                .state_queries
-                .push_state_root(state.state.state_root())
-                .unwrap()
-        }
+    fn payload_job_repository() -> Self::PayloadJobRepository {
+        moved_storage_rocksdb::payload::RocksDbPayloadJobRepository::new(db())
     }
 
     fn payload_queries() -> Self::PayloadQueries {
@@ -117,6 +155,10 @@ impl moved_app::Dependencies for RocksDbDependencies {
         moved_storage_rocksdb::transaction::RocksDbTransactionRepository
     }
 
+    fn transaction_journal() -> Self::TransactionJournal {
+        moved_storage_rocksdb::transaction::RocksDbTransactionJournal::new(db())
+    }
+
     impl_shared!();
 }
 
@@ -138,6 +180,10 @@ fn db() -> &'static moved_storage_rocksdb::RocksDb {
 fn create_db() -> moved_storage_rocksdb::RocksDb {
     let path = "db";
 
+    if is_read_replica() {
+        return open_secondary_db(path);
+    }
+
     if std::env::var("PURGE").as_ref().map(String::as_str) == Ok("1") {
         let _ = std::fs::remove_dir_all(path);
     }
@@ -149,3 +195,21 @@ fn create_db() -> moved_storage_rocksdb::RocksDb {
     moved_storage_rocksdb::RocksDb::open_cf(&options, path, moved_storage_rocksdb::COLUMN_FAMILIES)
         .expect("Database should open in db dir")
 }
+
+fn open_secondary_db(path: &str) -> moved_storage_rocksdb::RocksDb {
+    // Every secondary instance needs its own directory for its info logs
+    let secondary_path =
+        std::env::temp_dir().join(format!("op-move-replica-{}", std::process::id()));
+
+    let mut options = moved_storage_rocksdb::rocksdb::Options::default();
+    // Secondary instance has to keep all the files open to follow the primary
+    options.set_max_open_files(-1);
+
+    moved_storage_rocksdb::RocksDb::open_cf_as_secondary(
+        &options,
+        std::path::Path::new(path),
+        secondary_path.as_path(),
+        moved_storage_rocksdb::COLUMN_FAMILIES,
+    )
+    .expect("Database should open as secondary of the db dir")
+}
@@ -10,10 +10,12 @@ macro_rules! impl_shared {
             moved_blockchain::block::MovedBlockHash
         }
 
-        fn base_gas_fee() -> Self::BaseGasFee {
+        fn base_gas_fee(genesis_config: &GenesisConfig) -> Self::BaseGasFee {
             moved_blockchain::block::Eip1559GasFee::new(
-                crate::EIP1559_ELASTICITY_MULTIPLIER,
-                crate::EIP1559_BASE_FEE_MAX_CHANGE_DENOMINATOR,
+                genesis_config.fees.eip1559_elasticity_multiplier,
+                moved_shared::primitives::U256::from(
+                    genesis_config.fees.eip1559_base_fee_max_change_denominator,
+                ),
             )
         }
 
@@ -32,3 +34,55 @@ macro_rules! impl_shared {
 }
 
 pub(crate) use impl_shared;
+
+/// Defines the `StateRootRecorder` observer of the persistent `$dependencies`, which stores the
+/// state root after each transaction batch, making it queryable by block height.
+#[cfg(any(feature = "storage-lmdb", feature = "storage-rocksdb"))]
+macro_rules! impl_state_root_recorder {
+    ($dependencies:ty) => {
+        /// Stores the state root after each transaction batch, making it queryable by block
+        /// height.
+        struct StateRootRecorder;
+
+        impl moved_app::BlockExecutionObserver<$dependencies> for StateRootRecorder {
+            fn on_tx_batch(&self, app: &mut moved_app::Application<$dependencies>) {
+                app.state_queries
+                    .push_state_root(moved_state::State::state_root(&app.state))
+                    .unwrap()
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "storage-lmdb", feature = "storage-rocksdb"))]
+pub(crate) use impl_state_root_recorder;
+
+/// Whether the state trie is verified against the state resolver after each block, see
+/// [`moved_app::TrieConsistencyChecker`].
+///
+/// Enabled by setting the `CHECK_TRIE_CONSISTENCY` env var to `1`.
+pub fn is_trie_consistency_check_enabled() -> bool {
+    std::env::var("CHECK_TRIE_CONSISTENCY")
+        .as_ref()
+        .map(String::as_str)
+        == Ok("1")
+}
+
+/// Whether the preimages of the keys written to the state trie and the EVM storage tries are
+/// recorded for debug tooling, see [`moved_app::TriePreimageRecorder`].
+///
+/// Enabled by setting the `RECORD_TRIE_PREIMAGES` env var to `1`.
+pub fn is_trie_preimage_recording_enabled() -> bool {
+    std::env::var("RECORD_TRIE_PREIMAGES")
+        .as_ref()
+        .map(String::as_str)
+        == Ok("1")
+}
+
+/// Directory to export an execution witness of each block to, see
+/// [`moved_app::ExecutionWitnessRecorder`].
+///
+/// Set by the `EXECUTION_WITNESS_DIR` env var, which enables the export.
+pub fn execution_witness_dir() -> Option<std::path::PathBuf> {
+    std::env::var_os("EXECUTION_WITNESS_DIR").map(Into::into)
+}
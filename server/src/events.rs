@@ -0,0 +1,592 @@
+//! Push delivery of chain updates over WebSocket.
+//!
+//! The WebSocket server on port 8546 serves `eth_subscribe` and `eth_unsubscribe` as in geth, with
+//! the subscriptions:
+//! * `newHeads`, notified with the header of every committed block,
+//! * `logs`, notified with every log of a committed block that matches the addresses and topics of
+//!   an optional filter object,
+//! * `newPendingTransactions`, notified with the hash of every transaction accepted into the
+//!   mem-pool.
+//!
+//! Move events are emitted as EVM logs too, but a log only keeps a hash of the event type, so the
+//! events of a generic type cannot be filtered regardless of their type arguments. The server also
+//! serves `umi_subscribeEvents` for them, which takes a filter object with the optional fields:
+//! * `structTag` of the events, such as `0x1::coin::CoinDeposit<0x1::aptos_coin::AptosCoin>`. A tag
+//!   that ends in `<*>` or has no type arguments matches the events of any type arguments,
+//! * `account` that sent the transactions emitting the events.
+//!
+//! The result is a subscription ID. Every matching event of a committed block is then sent in a
+//! `umi_subscription` notification holding its bcs encoding, and its JSON rendering if the type of
+//! the event can be resolved. `umi_unsubscribeEvents` with the subscription ID ends a subscription.
+
+use {
+    alloy::{
+        primitives::Bytes,
+        rpc::types::{Filter, Header, Log},
+    },
+    futures_util::{SinkExt, StreamExt},
+    move_core_types::language_storage::{StructTag, TypeTag},
+    moved_api::{
+        json_utils::{self, get_field, get_params_list, move_value_to_json, parse_params_1},
+        jsonrpc::{JsonRpcError, JsonRpcResponse},
+    },
+    moved_app::{
+        Application, ApplicationReader, BlockExecutionObserver, CommittedBlock, Dependencies,
+        DependenciesThreadSafe, ExecutionExtension,
+    },
+    moved_blockchain::{block::BlockResponse, receipt::log_matches},
+    moved_shared::primitives::{Address, B256},
+    std::{collections::HashMap, str::FromStr, sync::Arc},
+    tokio::sync::broadcast::{self, error::RecvError},
+    warp::{
+        ws::{Message, WebSocket, Ws},
+        Filter as _, Rejection, Reply,
+    },
+};
+
+/// Number of notifications buffered for a connection that is slower to receive them. A connection
+/// that falls further behind misses the notifications it skips.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// An update of the chain that subscriptions are notified of.
+#[derive(Debug, Clone)]
+pub enum Notification {
+    Block(Arc<BlockNotification>),
+    /// The hash of a transaction accepted into the mem-pool.
+    PendingTransaction(B256),
+}
+
+/// A committed block, along with its EVM logs and Move events.
+#[derive(Debug)]
+pub struct BlockNotification {
+    pub header: Header,
+    pub logs: Vec<Log>,
+    pub events: Vec<CommittedEvent>,
+}
+
+/// A Move event of a committed block.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommittedEvent {
+    pub block_number: u64,
+    pub transaction_hash: B256,
+    #[serde(rename = "type")]
+    pub type_tag: String,
+    pub bcs: Bytes,
+    /// The event with named fields, or `None` if its type cannot be resolved.
+    pub json: Option<serde_json::Value>,
+    #[serde(skip)]
+    pub struct_tag: StructTag,
+    #[serde(skip)]
+    pub sender: Address,
+}
+
+/// Publishes every committed block to the WebSocket connections.
+pub struct EventBroadcast<D: DependenciesThreadSafe> {
+    app_reader: ApplicationReader<D>,
+    sender: broadcast::Sender<Notification>,
+}
+
+impl<D: DependenciesThreadSafe> EventBroadcast<D> {
+    pub fn new(app_reader: ApplicationReader<D>) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+
+        Self { app_reader, sender }
+    }
+
+    pub fn sender(&self) -> broadcast::Sender<Notification> {
+        self.sender.clone()
+    }
+}
+
+impl<D: DependenciesThreadSafe> ExecutionExtension for EventBroadcast<D> {
+    fn name(&self) -> &str {
+        "move-events"
+    }
+
+    fn on_block_committed(&mut self, block: &CommittedBlock) {
+        // Decoding the events and logs is wasted while there are no connections
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+
+        let senders: HashMap<_, _> = block
+            .receipts
+            .iter()
+            .map(|receipt| (receipt.inner.transaction_hash, receipt.inner.from))
+            .collect();
+        let block_number = block.block.block.header.number;
+        let app_reader = &self.app_reader;
+        let events = block
+            .events
+            .iter()
+            .flat_map(|(transaction_hash, events)| {
+                let sender = senders.get(transaction_hash).copied().unwrap_or_default();
+                events.iter().map(move |event| CommittedEvent {
+                    block_number,
+                    transaction_hash: *transaction_hash,
+                    type_tag: event.type_tag.to_canonical_string(),
+                    bcs: event.data.clone().into(),
+                    json: app_reader
+                        .annotate_value(
+                            &TypeTag::Struct(Box::new(event.type_tag.clone())),
+                            &event.data,
+                        )
+                        .map(|(value, layout)| move_value_to_json(value, Some(&layout))),
+                    struct_tag: event.type_tag.clone(),
+                    sender,
+                })
+            })
+            .collect::<Vec<_>>();
+        let header = BlockResponse::from_block_with_transaction_hashes(block.block.clone())
+            .block
+            .header;
+        let logs = block
+            .receipts
+            .iter()
+            .flat_map(|receipt| receipt.inner.inner.logs())
+            .cloned()
+            .collect();
+        let block = BlockNotification {
+            header,
+            logs,
+            events,
+        };
+
+        // Sending fails only once every connection has closed in the meantime
+        self.sender.send(Notification::Block(Arc::new(block))).ok();
+    }
+}
+
+/// Publishes the transactions accepted into the mem-pool to the WebSocket connections.
+pub struct PendingTransactionBroadcast {
+    sender: broadcast::Sender<Notification>,
+}
+
+impl PendingTransactionBroadcast {
+    pub fn new(sender: broadcast::Sender<Notification>) -> Self {
+        Self { sender }
+    }
+}
+
+impl<D: Dependencies> BlockExecutionObserver<D> for PendingTransactionBroadcast {
+    fn on_tx_accepted(&self, _app: &mut Application<D>, tx_hash: B256) {
+        // Sending fails while there are no connections
+        self.sender
+            .send(Notification::PendingTransaction(tx_hash))
+            .ok();
+    }
+}
+
+/// What a subscription of a connection is notified of.
+#[derive(Debug, Clone)]
+enum Subscription {
+    MoveEvents(EventFilter),
+    NewHeads,
+    Logs(Box<Filter>),
+    PendingTransactions,
+}
+
+impl Subscription {
+    /// Parses the params of `eth_subscribe`.
+    fn parse(request: &serde_json::Value) -> Result<Self, JsonRpcError> {
+        let params = get_params_list(request);
+
+        match (params.first().and_then(|kind| kind.as_str()), params.get(1)) {
+            (Some("newHeads"), None) => Ok(Self::NewHeads),
+            (Some("newPendingTransactions"), None) => Ok(Self::PendingTransactions),
+            (Some("logs"), None) => Ok(Self::Logs(Box::default())),
+            (Some("logs"), Some(filter)) => Ok(Self::Logs(json_utils::deserialize(filter)?)),
+            (kind, _) => Err(JsonRpcError::without_data(
+                -32602,
+                format!("Unsupported subscription: {}", kind.unwrap_or_default()),
+            )),
+        }
+    }
+
+    /// Renders the messages that the subscription with `id` is sent for the `notification`.
+    fn messages(&self, id: &str, notification: &Notification) -> Vec<String> {
+        match (self, notification) {
+            (Self::MoveEvents(filter), Notification::Block(block)) => block
+                .events
+                .iter()
+                .filter(|event| filter.matches(event))
+                .map(|event| message("umi_subscription", id, event))
+                .collect(),
+            (Self::NewHeads, Notification::Block(block)) => {
+                vec![message("eth_subscription", id, &block.header)]
+            }
+            (Self::Logs(filter), Notification::Block(block)) => block
+                .logs
+                .iter()
+                .filter(|log| log_matches(filter, log))
+                .map(|log| message("eth_subscription", id, log))
+                .collect(),
+            (Self::PendingTransactions, Notification::PendingTransaction(tx_hash)) => {
+                vec![message("eth_subscription", id, tx_hash)]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn message(method: &str, id: &str, result: &impl serde::Serialize) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": {
+            "subscription": id,
+            "result": result,
+        },
+    })
+    .to_string()
+}
+
+/// Selects the events delivered to a subscription.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventFilter {
+    struct_tag: Option<StructTag>,
+    /// Whether the events of the `struct_tag` match with any type arguments.
+    any_type_args: bool,
+    account: Option<Address>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventFilterRequest {
+    struct_tag: Option<String>,
+    account: Option<Address>,
+}
+
+impl EventFilter {
+    fn parse(request: EventFilterRequest) -> Result<Self, JsonRpcError> {
+        let (struct_tag, any_type_args) = match &request.struct_tag {
+            Some(tag) => {
+                let (tag, any_type_args) = match tag.strip_suffix("<*>") {
+                    Some(tag) => (tag, true),
+                    None => (tag.as_str(), !tag.contains('<')),
+                };
+                let tag = StructTag::from_str(tag).map_err(|e| {
+                    JsonRpcError::without_data(-32602, format!("Invalid struct tag {tag}: {e}"))
+                })?;
+                (Some(tag), any_type_args)
+            }
+            None => (None, true),
+        };
+
+        Ok(Self {
+            struct_tag,
+            any_type_args,
+            account: request.account,
+        })
+    }
+
+    pub fn matches(&self, event: &CommittedEvent) -> bool {
+        let type_matches = self.struct_tag.as_ref().is_none_or(|tag| {
+            if self.any_type_args {
+                tag.address == event.struct_tag.address
+                    && tag.module == event.struct_tag.module
+                    && tag.name == event.struct_tag.name
+            } else {
+                tag == &event.struct_tag
+            }
+        });
+        let account_matches = self.account.is_none_or(|account| account == event.sender);
+
+        type_matches && account_matches
+    }
+}
+
+/// The WebSocket route serving subscriptions out of the notifications published on `sender`.
+pub fn route(
+    sender: broadcast::Sender<Notification>,
+) -> impl warp::Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    warp::path::end().and(warp::ws()).map(move |ws: Ws| {
+        let notifications = sender.subscribe();
+        ws.on_upgrade(move |socket| serve_connection(socket, notifications))
+    })
+}
+
+async fn serve_connection(socket: WebSocket, mut notifications: broadcast::Receiver<Notification>) {
+    let (mut sink, mut stream) = socket.split();
+    let mut subscriptions = HashMap::new();
+    let mut last_id = 0u64;
+
+    loop {
+        tokio::select! {
+            message = stream.next() => {
+                let Some(Ok(message)) = message else {
+                    break;
+                };
+                // Pings are answered by warp, and there are no binary requests
+                let Ok(request) = message.to_str() else {
+                    continue;
+                };
+                let response = handle_request(request, &mut subscriptions, &mut last_id);
+                let response = serde_json::to_string(&response).expect("Response should serialize");
+                if sink.send(Message::text(response)).await.is_err() {
+                    break;
+                }
+            }
+            notification = notifications.recv() => {
+                let notification = match notification {
+                    Ok(notification) => notification,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                for (id, subscription) in &subscriptions {
+                    for message in subscription.messages(id, &notification) {
+                        if sink.send(Message::text(message)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn handle_request(
+    request: &str,
+    subscriptions: &mut HashMap<String, Subscription>,
+    last_id: &mut u64,
+) -> JsonRpcResponse {
+    let request: serde_json::Value = match serde_json::from_str(request) {
+        Ok(request) => request,
+        Err(e) => {
+            return JsonRpcResponse {
+                id: serde_json::Value::Null,
+                jsonrpc: "2.0".into(),
+                result: None,
+                error: Some(JsonRpcError::without_data(-32700, e.to_string())),
+            };
+        }
+    };
+    let id = get_field(&request, "id");
+    let jsonrpc = get_field(&request, "jsonrpc");
+
+    let mut subscribe = |subscription| {
+        *last_id += 1;
+        let id = format!("{last_id:#x}");
+        subscriptions.insert(id.clone(), subscription);
+        serde_json::Value::from(id)
+    };
+
+    let result: Result<serde_json::Value, _> = match get_field(&request, "method").as_str() {
+        Some("eth_subscribe") => Subscription::parse(&request).map(subscribe),
+        Some("umi_subscribeEvents") => parse_params_1(request)
+            .and_then(EventFilter::parse)
+            .map(|filter| subscribe(Subscription::MoveEvents(filter))),
+        Some("eth_unsubscribe" | "umi_unsubscribeEvents") => parse_params_1::<String>(request)
+            .map(|subscription| subscriptions.remove(&subscription).is_some().into()),
+        other => Err(JsonRpcError::without_data(
+            -32601,
+            format!("Unsupported method: {}", other.unwrap_or_default()),
+        )),
+    };
+
+    match result {
+        Ok(result) => JsonRpcResponse {
+            id,
+            jsonrpc,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JsonRpcResponse {
+            id,
+            jsonrpc,
+            result: None,
+            error: Some(e),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(struct_tag: &str, sender: Address) -> CommittedEvent {
+        CommittedEvent {
+            block_number: 1,
+            transaction_hash: B256::ZERO,
+            type_tag: struct_tag.to_owned(),
+            bcs: Bytes::new(),
+            json: None,
+            struct_tag: StructTag::from_str(struct_tag).unwrap(),
+            sender,
+        }
+    }
+
+    fn filter(struct_tag: Option<&str>, account: Option<Address>) -> EventFilter {
+        EventFilter::parse(EventFilterRequest {
+            struct_tag: struct_tag.map(str::to_owned),
+            account,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_filter_matches_struct_tag_with_type_args() {
+        let event = event(
+            "0x1::coin::Deposit<0x1::aptos_coin::AptosCoin>",
+            Address::ZERO,
+        );
+        let matches = |struct_tag| filter(Some(struct_tag), None).matches(&event);
+
+        assert!(matches("0x1::coin::Deposit<0x1::aptos_coin::AptosCoin>"));
+        assert!(!matches("0x1::coin::Deposit<0x1::string::String>"));
+    }
+
+    #[test]
+    fn test_filter_matches_struct_tag_with_any_type_args() {
+        let event = event(
+            "0x1::coin::Deposit<0x1::aptos_coin::AptosCoin>",
+            Address::ZERO,
+        );
+        let matches = |struct_tag| filter(Some(struct_tag), None).matches(&event);
+
+        assert!(matches("0x1::coin::Deposit<*>"));
+        assert!(matches("0x1::coin::Deposit"));
+        assert!(!matches("0x1::coin::Withdraw<*>"));
+        assert!(!matches("0x2::coin::Deposit<*>"));
+    }
+
+    #[test]
+    fn test_filter_matches_account() {
+        let account = Address::repeat_byte(1);
+        let filter = filter(None, Some(account));
+
+        assert!(filter.matches(&event("0x1::coin::Deposit", account)));
+        assert!(!filter.matches(&event("0x1::coin::Deposit", Address::repeat_byte(2))));
+    }
+
+    #[test]
+    fn test_invalid_struct_tag_is_rejected() {
+        let request = EventFilterRequest {
+            struct_tag: Some("coin::Deposit".to_owned()),
+            account: None,
+        };
+
+        assert!(EventFilter::parse(request).is_err());
+    }
+
+    #[test]
+    fn test_subscription_ends_on_unsubscribe() {
+        let mut subscriptions = HashMap::new();
+        let mut last_id = 0;
+
+        let response = handle_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"umi_subscribeEvents","params":[{"structTag":"0x1::coin::Deposit<*>"}]}"#,
+            &mut subscriptions,
+            &mut last_id,
+        );
+        let subscription = response.result.unwrap();
+        assert_eq!(subscriptions.len(), 1);
+
+        let response = handle_request(
+            &format!(
+                r#"{{"jsonrpc":"2.0","id":2,"method":"umi_unsubscribeEvents","params":[{subscription}]}}"#
+            ),
+            &mut subscriptions,
+            &mut last_id,
+        );
+        assert_eq!(response.result, Some(true.into()));
+        assert!(subscriptions.is_empty());
+    }
+
+    fn subscribe(
+        params: &str,
+        subscriptions: &mut HashMap<String, Subscription>,
+    ) -> JsonRpcResponse {
+        handle_request(
+            &format!(r#"{{"jsonrpc":"2.0","id":1,"method":"eth_subscribe","params":{params}}}"#),
+            subscriptions,
+            &mut 0,
+        )
+    }
+
+    fn log(address: Address, topic: B256) -> Log {
+        Log {
+            inner: alloy::primitives::Log::new_unchecked(address, vec![topic], Bytes::new()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_eth_subscribe_accepts_supported_kinds() {
+        let mut subscriptions = HashMap::new();
+
+        for params in [
+            r#"["newHeads"]"#,
+            r#"["newPendingTransactions"]"#,
+            r#"["logs"]"#,
+            r#"["logs",{"address":"0x0101010101010101010101010101010101010101"}]"#,
+        ] {
+            let response = subscribe(params, &mut subscriptions);
+            assert!(response.error.is_none(), "{params} should be accepted");
+        }
+
+        let response = subscribe(r#"["syncing"]"#, &mut subscriptions);
+        assert_eq!(response.error.unwrap().code, -32602);
+    }
+
+    #[test]
+    fn test_eth_subscription_ends_on_eth_unsubscribe() {
+        let mut subscriptions = HashMap::new();
+        let mut last_id = 0;
+
+        let response = handle_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"eth_subscribe","params":["newHeads"]}"#,
+            &mut subscriptions,
+            &mut last_id,
+        );
+        let subscription = response.result.unwrap();
+
+        let response = handle_request(
+            &format!(
+                r#"{{"jsonrpc":"2.0","id":2,"method":"eth_unsubscribe","params":[{subscription}]}}"#
+            ),
+            &mut subscriptions,
+            &mut last_id,
+        );
+        assert_eq!(response.result, Some(true.into()));
+        assert!(subscriptions.is_empty());
+    }
+
+    #[test]
+    fn test_logs_subscription_is_sent_matching_logs_only() {
+        let address = Address::repeat_byte(1);
+        let block = Notification::Block(Arc::new(BlockNotification {
+            header: Header::default(),
+            logs: vec![
+                log(address, B256::repeat_byte(1)),
+                log(Address::repeat_byte(2), B256::repeat_byte(1)),
+            ],
+            events: Vec::new(),
+        }));
+        let subscription = Subscription::Logs(Box::new(Filter::new().address(address)));
+
+        let messages = subscription.messages("0x1", &block);
+
+        assert_eq!(messages.len(), 1);
+        let message: serde_json::Value = serde_json::from_str(&messages[0]).unwrap();
+        assert_eq!(message["method"], "eth_subscription");
+        assert_eq!(
+            message["params"]["result"]["address"],
+            serde_json::json!(address)
+        );
+    }
+
+    #[test]
+    fn test_pending_transactions_are_sent_to_their_subscriptions_only() {
+        let pending = Notification::PendingTransaction(B256::repeat_byte(1));
+
+        assert_eq!(
+            Subscription::PendingTransactions
+                .messages("0x1", &pending)
+                .len(),
+            1
+        );
+        assert!(Subscription::NewHeads.messages("0x1", &pending).is_empty());
+    }
+}
@@ -0,0 +1,807 @@
+//! Firehose extractor mode.
+//!
+//! When the `FIREHOSE` env var is set to `1`, every committed block is printed to stdout using the
+//! Firehose console reader protocol, encoded as an `sf.ethereum.type.v2.Block` protobuf. Running
+//! the node under `firecore` then makes the chain available over the Firehose gRPC API, and to
+//! substreams and The Graph on top of it.
+//!
+//! Blocks are emitted at the extended detail level, with the call tree of every transaction. Each
+//! transaction is executed again with tracing to find the EVM calls made from its Move calls. The
+//! root call of a transaction stands for the Move call it makes, and the EVM calls are nested below
+//! it. The MoveVM does not attribute logs to the calls that emitted them, so all the logs of a
+//! transaction are attached to its root call. So are its balance, nonce and storage changes, which
+//! are found by comparing the state before and after the transaction, without their reasons.
+//!
+//! Emission starts after the latest block known at startup, unless `FIREHOSE_START_BLOCK` sets a
+//! different first block.
+//!
+//! The op-node may reorg blocks that are not yet safe. When an emitted block is no longer part of
+//! the chain, the blocks that replace it are emitted from its height on, and the console reader
+//! undoes the blocks of the abandoned fork as their parents no longer link up. Reorgs deeper than
+//! the [`REORG_DEPTH`] emitted blocks that are remembered are not detected.
+
+use {
+    alloy::{
+        consensus::{Transaction, TxReceipt},
+        primitives::{Log as PrimitiveLog, B256, U256},
+        rpc::types::BlockTransactions,
+    },
+    base64::{prelude::BASE64_STANDARD, Engine},
+    moved_api::schema::BlockNumberOrTag,
+    moved_app::{ApplicationReader, DependenciesThreadSafe, StateChanges},
+    moved_blockchain::{
+        block::BlockResponse, receipt::TransactionReceipt, transaction::TransactionResponse,
+    },
+    moved_evm_ext::{EvmCallFrame, EvmCallKind},
+    moved_execution::TransactionTrace,
+    prost::Message,
+    std::{collections::VecDeque, time::Duration},
+};
+
+const PROTOCOL_VERSION: &str = "3.0";
+const BLOCK_TYPE: &str = "sf.ethereum.type.v2.Block";
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Number of the most recently emitted blocks that are checked for being reorged.
+const REORG_DEPTH: usize = 256;
+
+/// The subset of the `sf.ethereum.type.v2` protobuf schema emitted by this node.
+///
+/// Field tags follow `sf/ethereum/type/v2/type.proto` of the firehose-ethereum repository.
+mod pb {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Block {
+        #[prost(int32, tag = "1")]
+        pub ver: i32,
+        #[prost(bytes = "vec", tag = "2")]
+        pub hash: Vec<u8>,
+        #[prost(uint64, tag = "3")]
+        pub number: u64,
+        #[prost(uint64, tag = "4")]
+        pub size: u64,
+        #[prost(message, optional, tag = "5")]
+        pub header: Option<BlockHeader>,
+        #[prost(message, repeated, tag = "10")]
+        pub transaction_traces: Vec<TransactionTrace>,
+        #[prost(int32, tag = "12")]
+        pub detail_level: i32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct BlockHeader {
+        #[prost(bytes = "vec", tag = "1")]
+        pub parent_hash: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub uncle_hash: Vec<u8>,
+        #[prost(bytes = "vec", tag = "3")]
+        pub coinbase: Vec<u8>,
+        #[prost(bytes = "vec", tag = "4")]
+        pub state_root: Vec<u8>,
+        #[prost(bytes = "vec", tag = "5")]
+        pub transactions_root: Vec<u8>,
+        #[prost(bytes = "vec", tag = "6")]
+        pub receipt_root: Vec<u8>,
+        #[prost(bytes = "vec", tag = "7")]
+        pub logs_bloom: Vec<u8>,
+        #[prost(message, optional, tag = "8")]
+        pub difficulty: Option<BigInt>,
+        #[prost(uint64, tag = "9")]
+        pub number: u64,
+        #[prost(uint64, tag = "10")]
+        pub gas_limit: u64,
+        #[prost(uint64, tag = "11")]
+        pub gas_used: u64,
+        #[prost(message, optional, tag = "12")]
+        pub timestamp: Option<Timestamp>,
+        #[prost(bytes = "vec", tag = "13")]
+        pub extra_data: Vec<u8>,
+        #[prost(bytes = "vec", tag = "14")]
+        pub mix_hash: Vec<u8>,
+        #[prost(uint64, tag = "15")]
+        pub nonce: u64,
+        #[prost(bytes = "vec", tag = "16")]
+        pub hash: Vec<u8>,
+        #[prost(message, optional, tag = "18")]
+        pub base_fee_per_gas: Option<BigInt>,
+        #[prost(bytes = "vec", tag = "19")]
+        pub withdrawals_root: Vec<u8>,
+        #[prost(uint64, optional, tag = "22")]
+        pub blob_gas_used: Option<u64>,
+        #[prost(uint64, optional, tag = "23")]
+        pub excess_blob_gas: Option<u64>,
+        #[prost(bytes = "vec", tag = "24")]
+        pub parent_beacon_root: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TransactionTrace {
+        #[prost(bytes = "vec", tag = "1")]
+        pub to: Vec<u8>,
+        #[prost(uint64, tag = "2")]
+        pub nonce: u64,
+        #[prost(message, optional, tag = "3")]
+        pub gas_price: Option<BigInt>,
+        #[prost(uint64, tag = "4")]
+        pub gas_limit: u64,
+        #[prost(message, optional, tag = "5")]
+        pub value: Option<BigInt>,
+        #[prost(bytes = "vec", tag = "6")]
+        pub input: Vec<u8>,
+        #[prost(uint64, tag = "10")]
+        pub gas_used: u64,
+        #[prost(message, optional, tag = "11")]
+        pub max_fee_per_gas: Option<BigInt>,
+        #[prost(int32, tag = "12")]
+        pub r#type: i32,
+        #[prost(message, optional, tag = "13")]
+        pub max_priority_fee_per_gas: Option<BigInt>,
+        #[prost(uint32, tag = "20")]
+        pub index: u32,
+        #[prost(bytes = "vec", tag = "21")]
+        pub hash: Vec<u8>,
+        #[prost(bytes = "vec", tag = "22")]
+        pub from: Vec<u8>,
+        #[prost(int32, tag = "30")]
+        pub status: i32,
+        #[prost(message, optional, tag = "31")]
+        pub receipt: Option<TransactionReceipt>,
+        #[prost(message, repeated, tag = "32")]
+        pub calls: Vec<Call>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Call {
+        #[prost(uint32, tag = "1")]
+        pub index: u32,
+        #[prost(uint32, tag = "2")]
+        pub parent_index: u32,
+        #[prost(uint32, tag = "3")]
+        pub depth: u32,
+        #[prost(int32, tag = "4")]
+        pub call_type: i32,
+        #[prost(bytes = "vec", tag = "5")]
+        pub caller: Vec<u8>,
+        #[prost(bytes = "vec", tag = "6")]
+        pub address: Vec<u8>,
+        #[prost(message, optional, tag = "7")]
+        pub value: Option<BigInt>,
+        #[prost(uint64, tag = "8")]
+        pub gas_limit: u64,
+        #[prost(uint64, tag = "9")]
+        pub gas_consumed: u64,
+        #[prost(bool, tag = "10")]
+        pub status_failed: bool,
+        #[prost(string, tag = "11")]
+        pub failure_reason: String,
+        #[prost(bool, tag = "12")]
+        pub status_reverted: bool,
+        #[prost(bytes = "vec", tag = "13")]
+        pub return_data: Vec<u8>,
+        #[prost(bytes = "vec", tag = "14")]
+        pub input: Vec<u8>,
+        #[prost(bool, tag = "15")]
+        pub executed_code: bool,
+        #[prost(message, repeated, tag = "21")]
+        pub storage_changes: Vec<StorageChange>,
+        #[prost(message, repeated, tag = "22")]
+        pub balance_changes: Vec<BalanceChange>,
+        #[prost(message, repeated, tag = "24")]
+        pub nonce_changes: Vec<NonceChange>,
+        #[prost(message, repeated, tag = "25")]
+        pub logs: Vec<Log>,
+        #[prost(bool, tag = "30")]
+        pub state_reverted: bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct StorageChange {
+        #[prost(bytes = "vec", tag = "1")]
+        pub address: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub key: Vec<u8>,
+        #[prost(bytes = "vec", tag = "3")]
+        pub old_value: Vec<u8>,
+        #[prost(bytes = "vec", tag = "4")]
+        pub new_value: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct BalanceChange {
+        #[prost(bytes = "vec", tag = "1")]
+        pub address: Vec<u8>,
+        #[prost(message, optional, tag = "2")]
+        pub old_value: Option<BigInt>,
+        #[prost(message, optional, tag = "3")]
+        pub new_value: Option<BigInt>,
+        #[prost(int32, tag = "4")]
+        pub reason: i32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct NonceChange {
+        #[prost(bytes = "vec", tag = "1")]
+        pub address: Vec<u8>,
+        #[prost(uint64, tag = "2")]
+        pub old_value: u64,
+        #[prost(uint64, tag = "3")]
+        pub new_value: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TransactionReceipt {
+        #[prost(bytes = "vec", tag = "1")]
+        pub state_root: Vec<u8>,
+        #[prost(uint64, tag = "2")]
+        pub cumulative_gas_used: u64,
+        #[prost(bytes = "vec", tag = "3")]
+        pub logs_bloom: Vec<u8>,
+        #[prost(message, repeated, tag = "4")]
+        pub logs: Vec<Log>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Log {
+        #[prost(bytes = "vec", tag = "1")]
+        pub address: Vec<u8>,
+        #[prost(bytes = "vec", repeated, tag = "2")]
+        pub topics: Vec<Vec<u8>>,
+        #[prost(bytes = "vec", tag = "3")]
+        pub data: Vec<u8>,
+        #[prost(uint32, tag = "4")]
+        pub index: u32,
+        #[prost(uint32, tag = "6")]
+        pub block_index: u32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct BigInt {
+        #[prost(bytes = "vec", tag = "1")]
+        pub bytes: Vec<u8>,
+    }
+
+    /// Same encoding as `google.protobuf.Timestamp`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Timestamp {
+        #[prost(int64, tag = "1")]
+        pub seconds: i64,
+        #[prost(int32, tag = "2")]
+        pub nanos: i32,
+    }
+
+    pub const DETAIL_LEVEL_EXTENDED: i32 = 0;
+    pub const CALL_TYPE_CALL: i32 = 1;
+    pub const CALL_TYPE_CALLCODE: i32 = 2;
+    pub const CALL_TYPE_DELEGATE: i32 = 3;
+    pub const CALL_TYPE_STATIC: i32 = 4;
+    pub const CALL_TYPE_CREATE: i32 = 5;
+    pub const STATUS_SUCCEEDED: i32 = 1;
+    pub const STATUS_FAILED: i32 = 2;
+    pub const BALANCE_CHANGE_REASON_UNKNOWN: i32 = 0;
+}
+
+/// Returns `true` if the node should run in Firehose extractor mode.
+///
+/// Enabled by setting the `FIREHOSE` env var to `1`.
+pub fn is_enabled() -> bool {
+    std::env::var("FIREHOSE").as_ref().map(String::as_str) == Ok("1")
+}
+
+/// Prints every committed block to stdout in the Firehose console reader format, forever.
+pub async fn run(app: ApplicationReader<impl DependenciesThreadSafe>) {
+    let mut next = match std::env::var("FIREHOSE_START_BLOCK") {
+        Ok(height) => height
+            .parse()
+            .expect("FIREHOSE_START_BLOCK should be a block number"),
+        Err(_) => app.block_number() + 1,
+    };
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    // Heights and hashes of the latest emitted blocks, oldest first
+    let mut emitted = VecDeque::new();
+
+    println!("FIRE INIT {PROTOCOL_VERSION} {BLOCK_TYPE}");
+
+    loop {
+        interval.tick().await;
+
+        let reorged = reorged_blocks(&emitted, |height| {
+            app.block_by_height(BlockNumberOrTag::Number(height), false)
+                .map(|block| block.block.header.hash)
+        });
+        if reorged > 0 {
+            // The blocks of the new fork are emitted from the first reorged height on
+            next = emitted[emitted.len() - reorged].0;
+            emitted.truncate(emitted.len() - reorged);
+        }
+
+        loop {
+            // Tracing executes the transactions again, which must not hold up the async runtime
+            let reader = app.clone();
+            let line = tokio::task::spawn_blocking(move || block_line(&reader, next))
+                .await
+                .expect("Firehose block should be read");
+            let Some((hash, line)) = line else {
+                break;
+            };
+
+            println!("{line}");
+            emitted.push_back((next, hash));
+            if emitted.len() > REORG_DEPTH {
+                emitted.pop_front();
+            }
+            next += 1;
+        }
+    }
+}
+
+/// Counts the newest `emitted` blocks that the chain no longer contains, given the `hash` of the
+/// block it holds at each height.
+fn reorged_blocks(emitted: &VecDeque<(u64, B256)>, hash: impl Fn(u64) -> Option<B256>) -> usize {
+    emitted
+        .iter()
+        .rev()
+        .take_while(|(height, emitted_hash)| hash(*height) != Some(*emitted_hash))
+        .count()
+}
+
+/// Formats the `FIRE BLOCK` line of the block at `height` along with its hash, if it is committed.
+fn block_line(
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
+    height: u64,
+) -> Option<(B256, String)> {
+    let block = app.block_by_height(BlockNumberOrTag::Number(height), true)?;
+    let receipts = read_receipts(app, &block);
+    let traces = read_traces(app, &block);
+    let lib = app
+        .block_by_height(BlockNumberOrTag::Finalized, false)
+        .map_or(0, |block| block.block.header.number);
+
+    Some((
+        block.block.header.hash,
+        console_line(&block, &receipts, &traces, lib),
+    ))
+}
+
+fn read_receipts(
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
+    block: &BlockResponse,
+) -> Vec<TransactionReceipt> {
+    block
+        .block
+        .transactions
+        .hashes()
+        .map(|hash| {
+            app.transaction_receipt(hash)
+                .expect("Receipts are committed before their block")
+        })
+        .collect()
+}
+
+/// Traces every transaction of `block` along with its state changes. A transaction that cannot be
+/// traced only gets its root call, without state changes.
+fn read_traces(
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
+    block: &BlockResponse,
+) -> Vec<Option<(TransactionTrace, StateChanges)>> {
+    block
+        .block
+        .transactions
+        .hashes()
+        .map(
+            |hash| match app.trace_transaction_with_state_changes(hash) {
+                Some(Ok(traced)) => Some(traced),
+                Some(Err(e)) => {
+                    println!("WARN: failed to trace transaction {hash} for Firehose: {e:?}");
+                    None
+                }
+                None => None,
+            },
+        )
+        .collect()
+}
+
+/// Formats the `FIRE BLOCK` line announcing `block` with the last irreversible block `lib`.
+fn console_line(
+    block: &BlockResponse,
+    receipts: &[TransactionReceipt],
+    traces: &[Option<(TransactionTrace, StateChanges)>],
+    lib: u64,
+) -> String {
+    let header = &block.block.header;
+    let payload = to_proto(block, receipts, traces).encode_to_vec();
+
+    format!(
+        "FIRE BLOCK {} {} {} {} {} {} {}",
+        header.number,
+        hex::encode(header.hash),
+        header.number.saturating_sub(1),
+        hex::encode(header.parent_hash),
+        lib.min(header.number),
+        u128::from(header.timestamp) * 1_000_000_000,
+        BASE64_STANDARD.encode(payload),
+    )
+}
+
+fn to_proto(
+    block: &BlockResponse,
+    receipts: &[TransactionReceipt],
+    traces: &[Option<(TransactionTrace, StateChanges)>],
+) -> pb::Block {
+    let header = &block.block.header;
+    let transactions = match &block.block.transactions {
+        BlockTransactions::Full(transactions) => transactions.as_slice(),
+        _ => &[],
+    };
+    let mut block_log_index = 0;
+    let transaction_traces = transactions
+        .iter()
+        .zip(receipts)
+        .zip(traces)
+        .map(|((tx, receipt), trace)| {
+            let trace = transaction_trace(tx, receipt, trace.as_ref(), block_log_index);
+            block_log_index += receipt.inner.inner.logs().len() as u32;
+            trace
+        })
+        .collect();
+
+    pb::Block {
+        ver: 4,
+        hash: header.hash.to_vec(),
+        number: header.number,
+        size: header.size.unwrap_or_default().saturating_to(),
+        header: Some(pb::BlockHeader {
+            parent_hash: header.parent_hash.to_vec(),
+            uncle_hash: header.ommers_hash.to_vec(),
+            coinbase: header.beneficiary.to_vec(),
+            state_root: header.state_root.to_vec(),
+            transactions_root: header.transactions_root.to_vec(),
+            receipt_root: header.receipts_root.to_vec(),
+            logs_bloom: header.logs_bloom.to_vec(),
+            difficulty: Some(big_int(header.difficulty)),
+            number: header.number,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            timestamp: Some(pb::Timestamp {
+                seconds: header.timestamp as i64,
+                nanos: 0,
+            }),
+            extra_data: header.extra_data.to_vec(),
+            mix_hash: header.mix_hash.to_vec(),
+            nonce: u64::from_be_bytes(header.nonce.0),
+            hash: header.hash.to_vec(),
+            base_fee_per_gas: header.base_fee_per_gas.map(|fee| big_int(U256::from(fee))),
+            withdrawals_root: header
+                .withdrawals_root
+                .map(|root| root.to_vec())
+                .unwrap_or_default(),
+            blob_gas_used: header.blob_gas_used,
+            excess_blob_gas: header.excess_blob_gas,
+            parent_beacon_root: header
+                .parent_beacon_block_root
+                .map(|root| root.to_vec())
+                .unwrap_or_default(),
+        }),
+        transaction_traces,
+        detail_level: pb::DETAIL_LEVEL_EXTENDED,
+    }
+}
+
+fn transaction_trace(
+    tx: &TransactionResponse,
+    receipt: &TransactionReceipt,
+    traced: Option<&(TransactionTrace, StateChanges)>,
+    first_log_index: u32,
+) -> pb::TransactionTrace {
+    let trace = traced.map(|(trace, _)| trace);
+    let state_changes = traced.map(|(_, state_changes)| state_changes);
+    let envelope = &receipt.inner.inner;
+    let logs: Vec<_> = envelope
+        .logs()
+        .iter()
+        .enumerate()
+        .map(|(index, log)| log_proto(&log.inner, index as u32, first_log_index))
+        .collect();
+    let failed = !envelope.status();
+    let mut calls = vec![pb::Call {
+        index: 1,
+        parent_index: 0,
+        depth: 0,
+        call_type: if tx.to().is_some() {
+            pb::CALL_TYPE_CALL
+        } else {
+            pb::CALL_TYPE_CREATE
+        },
+        caller: receipt.inner.from.to_vec(),
+        address: tx
+            .to()
+            .or(receipt.inner.contract_address)
+            .map(|address| address.to_vec())
+            .unwrap_or_default(),
+        value: Some(big_int(tx.value())),
+        gas_limit: tx.gas_limit(),
+        gas_consumed: receipt.inner.gas_used,
+        status_failed: failed,
+        failure_reason: trace
+            .and_then(|trace| trace.calls.iter().find_map(|call| call.error.clone()))
+            .unwrap_or_default(),
+        status_reverted: failed,
+        return_data: Vec::new(),
+        input: tx.input().to_vec(),
+        executed_code: trace.is_some_and(|trace| !trace.calls.is_empty()),
+        storage_changes: state_changes.map_or_else(Vec::new, storage_changes),
+        balance_changes: state_changes.map_or_else(Vec::new, balance_changes),
+        nonce_changes: state_changes.map_or_else(Vec::new, nonce_changes),
+        logs: logs.clone(),
+        state_reverted: failed,
+    }];
+    for frame in trace.iter().flat_map(|trace| &trace.calls) {
+        push_evm_calls(&mut calls, &frame.evm_calls, 1, failed);
+    }
+
+    pb::TransactionTrace {
+        to: tx.to().map(|to| to.to_vec()).unwrap_or_default(),
+        nonce: tx.nonce(),
+        gas_price: Some(big_int(U256::from(receipt.inner.effective_gas_price))),
+        gas_limit: tx.gas_limit(),
+        value: Some(big_int(tx.value())),
+        input: tx.input().to_vec(),
+        gas_used: receipt.inner.gas_used,
+        max_fee_per_gas: Some(big_int(U256::from(tx.max_fee_per_gas()))),
+        r#type: i32::from(tx.ty()),
+        max_priority_fee_per_gas: tx
+            .max_priority_fee_per_gas()
+            .map(|fee| big_int(U256::from(fee))),
+        index: receipt.inner.transaction_index.unwrap_or_default() as u32,
+        hash: receipt.inner.transaction_hash.to_vec(),
+        from: receipt.inner.from.to_vec(),
+        status: if envelope.status() {
+            pb::STATUS_SUCCEEDED
+        } else {
+            pb::STATUS_FAILED
+        },
+        receipt: Some(pb::TransactionReceipt {
+            state_root: Vec::new(),
+            cumulative_gas_used: envelope.cumulative_gas_used(),
+            logs_bloom: envelope.bloom().to_vec(),
+            logs,
+        }),
+        calls,
+    }
+}
+
+/// Appends the `frames` made from the call at `parent_index` to `calls`, each followed by the
+/// calls it made in turn, as Firehose orders them.
+fn push_evm_calls(
+    calls: &mut Vec<pb::Call>,
+    frames: &[EvmCallFrame],
+    parent_index: u32,
+    parent_reverted: bool,
+) {
+    let depth = calls[parent_index as usize - 1].depth + 1;
+
+    for frame in frames {
+        let index = calls.len() as u32 + 1;
+        let failed = frame.error.is_some();
+        let reverted = failed || parent_reverted;
+        calls.push(pb::Call {
+            index,
+            parent_index,
+            depth,
+            call_type: match frame.kind {
+                EvmCallKind::Call => pb::CALL_TYPE_CALL,
+                EvmCallKind::CallCode => pb::CALL_TYPE_CALLCODE,
+                EvmCallKind::DelegateCall => pb::CALL_TYPE_DELEGATE,
+                EvmCallKind::StaticCall => pb::CALL_TYPE_STATIC,
+                EvmCallKind::Create | EvmCallKind::Create2 => pb::CALL_TYPE_CREATE,
+            },
+            caller: frame.from.to_vec(),
+            address: frame.to.map(|to| to.to_vec()).unwrap_or_default(),
+            value: Some(big_int(frame.value)),
+            gas_limit: frame.gas,
+            gas_consumed: frame.gas_used,
+            status_failed: failed,
+            failure_reason: frame.error.clone().unwrap_or_default(),
+            status_reverted: reverted,
+            return_data: frame.output.to_vec(),
+            input: frame.input.to_vec(),
+            executed_code: true,
+            storage_changes: Vec::new(),
+            balance_changes: Vec::new(),
+            nonce_changes: Vec::new(),
+            logs: Vec::new(),
+            state_reverted: reverted,
+        });
+        push_evm_calls(calls, &frame.calls, index, reverted);
+    }
+}
+
+fn storage_changes(state_changes: &StateChanges) -> Vec<pb::StorageChange> {
+    state_changes
+        .storage
+        .iter()
+        .map(|change| pb::StorageChange {
+            address: change.address.to_vec(),
+            key: change.key.to_be_bytes::<32>().to_vec(),
+            old_value: change.old_value.to_be_bytes::<32>().to_vec(),
+            new_value: change.new_value.to_be_bytes::<32>().to_vec(),
+        })
+        .collect()
+}
+
+fn balance_changes(state_changes: &StateChanges) -> Vec<pb::BalanceChange> {
+    state_changes
+        .balances
+        .iter()
+        .map(|change| pb::BalanceChange {
+            address: change.address.to_vec(),
+            old_value: Some(big_int(change.old_value)),
+            new_value: Some(big_int(change.new_value)),
+            reason: pb::BALANCE_CHANGE_REASON_UNKNOWN,
+        })
+        .collect()
+}
+
+fn nonce_changes(state_changes: &StateChanges) -> Vec<pb::NonceChange> {
+    state_changes
+        .nonces
+        .iter()
+        .map(|change| pb::NonceChange {
+            address: change.address.to_vec(),
+            old_value: change.old_value,
+            new_value: change.new_value,
+        })
+        .collect()
+}
+
+fn log_proto(log: &PrimitiveLog, index: u32, first_log_index: u32) -> pb::Log {
+    pb::Log {
+        address: log.address.to_vec(),
+        topics: log.topics().iter().map(|topic| topic.to_vec()).collect(),
+        data: log.data.data.to_vec(),
+        index,
+        block_index: first_log_index + index,
+    }
+}
+
+/// Big-endian bytes without leading zeros, as firehose-ethereum encodes its `BigInt`.
+fn big_int(value: U256) -> pb::BigInt {
+    pb::BigInt {
+        bytes: value.to_be_bytes_trimmed_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_int_has_no_leading_zeros() {
+        assert_eq!(big_int(U256::ZERO).bytes, Vec::<u8>::new());
+        assert_eq!(big_int(U256::from(0x0102)).bytes, vec![0x01, 0x02]);
+    }
+
+    #[test]
+    fn test_console_line_announces_block() {
+        let block = BlockResponse::from_block_with_transaction_hashes(
+            moved_blockchain::block::Block::default()
+                .with_hash(alloy::primitives::B256::repeat_byte(0xab))
+                .with_value(U256::ZERO),
+        );
+
+        let line = console_line(&block, &[], &[], 0);
+        let fields = line.split(' ').collect::<Vec<_>>();
+
+        assert_eq!(fields[..2], ["FIRE", "BLOCK"]);
+        assert_eq!(fields[2], "0");
+        assert_eq!(fields[3], "ab".repeat(32));
+        assert_eq!(fields.len(), 9);
+
+        let payload = BASE64_STANDARD.decode(fields[8]).unwrap();
+        let decoded = pb::Block::decode(payload.as_slice()).unwrap();
+        assert_eq!(decoded.hash, vec![0xab; 32]);
+        assert_eq!(decoded.detail_level, pb::DETAIL_LEVEL_EXTENDED);
+    }
+
+    #[test]
+    fn test_reorged_blocks_are_counted_down_to_common_ancestor() {
+        let emitted: VecDeque<_> = (0..4)
+            .map(|height| (height, B256::with_last_byte(height as u8)))
+            .collect();
+        // Blocks 2 and 3 were replaced, and the new chain has no block 3 yet
+        let hash = |height| match height {
+            0 | 1 => Some(B256::with_last_byte(height as u8)),
+            2 => Some(B256::repeat_byte(0xff)),
+            _ => None,
+        };
+
+        assert_eq!(reorged_blocks(&emitted, hash), 2);
+        assert_eq!(
+            reorged_blocks(&emitted.range(..2).copied().collect(), hash),
+            0
+        );
+    }
+
+    #[test]
+    fn test_state_changes_are_encoded_for_root_call() {
+        let address = alloy::primitives::Address::repeat_byte(1);
+        let state_changes = StateChanges {
+            balances: vec![moved_app::BalanceChange {
+                address,
+                old_value: U256::from(0x0102),
+                new_value: U256::ZERO,
+            }],
+            nonces: vec![moved_app::NonceChange {
+                address,
+                old_value: 1,
+                new_value: 2,
+            }],
+            storage: vec![moved_app::StorageChange {
+                address,
+                key: U256::from(3),
+                old_value: U256::ZERO,
+                new_value: U256::from(4),
+            }],
+        };
+
+        let balance = &balance_changes(&state_changes)[0];
+        assert_eq!(balance.address, address.to_vec());
+        assert_eq!(balance.old_value, Some(big_int(U256::from(0x0102))));
+        assert_eq!(balance.new_value, Some(big_int(U256::ZERO)));
+        assert_eq!(nonce_changes(&state_changes)[0].new_value, 2);
+        // Storage keys and values are full 32-byte words
+        let storage = &storage_changes(&state_changes)[0];
+        assert_eq!(storage.key, B256::with_last_byte(3).to_vec());
+        assert_eq!(storage.new_value, B256::with_last_byte(4).to_vec());
+    }
+
+    fn evm_frame(kind: EvmCallKind, error: Option<&str>, calls: Vec<EvmCallFrame>) -> EvmCallFrame {
+        EvmCallFrame {
+            kind,
+            from: alloy::primitives::Address::repeat_byte(1),
+            to: Some(alloy::primitives::Address::repeat_byte(2)),
+            value: U256::ZERO,
+            gas: 100,
+            gas_used: 10,
+            input: Default::default(),
+            output: Default::default(),
+            error: error.map(str::to_owned),
+            calls,
+        }
+    }
+
+    #[test]
+    fn test_evm_calls_are_nested_below_their_parents() {
+        let frames = vec![
+            evm_frame(
+                EvmCallKind::Call,
+                Some("execution reverted"),
+                vec![evm_frame(EvmCallKind::DelegateCall, None, Vec::new())],
+            ),
+            evm_frame(EvmCallKind::Create2, None, Vec::new()),
+        ];
+        let mut calls = vec![pb::Call {
+            index: 1,
+            ..Default::default()
+        }];
+
+        push_evm_calls(&mut calls, &frames, 1, false);
+
+        let tree: Vec<_> = calls
+            .iter()
+            .map(|call| (call.index, call.parent_index, call.depth, call.call_type))
+            .collect();
+        assert_eq!(
+            tree,
+            vec![
+                (1, 0, 0, 0),
+                (2, 1, 1, pb::CALL_TYPE_CALL),
+                (3, 2, 2, pb::CALL_TYPE_DELEGATE),
+                (4, 1, 1, pb::CALL_TYPE_CREATE),
+            ]
+        );
+        assert!(calls[1].status_failed);
+        assert_eq!(calls[1].failure_reason, "execution reverted");
+        // The state of a call is reverted along with its parent
+        assert!(!calls[2].status_failed);
+        assert!(calls[2].state_reverted);
+        assert!(!calls[3].state_reverted);
+    }
+}
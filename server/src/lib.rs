@@ -1,14 +1,12 @@
-#[cfg(test)]
-use moved_blockchain::block::{Block, BlockHash, ExtendedBlock, Header};
 use {
     crate::mirror::MirrorLog,
     clap::Parser,
     flate2::read::GzDecoder,
     jsonwebtoken::{DecodingKey, Validation},
     moved_api::method_name::MethodName,
-    moved_app::{Application, ApplicationReader, Command, CommandQueue, Dependencies},
+    moved_app::{Application, ApplicationReader, CommandQueue, DependenciesThreadSafe},
     moved_blockchain::{
-        block::BlockQueries,
+        block::{Block, BlockHash, BlockQueries, ExtendedBlock, Header},
         payload::{NewPayloadId, StatePayloadId},
     },
     moved_genesis::config::GenesisConfig,
@@ -16,7 +14,7 @@ use {
     once_cell::sync::Lazy,
     std::{
         fs,
-        io::Read,
+        io::{Read, Write},
         net::{Ipv4Addr, SocketAddr, SocketAddrV4},
         time::SystemTime,
     },
@@ -32,17 +30,44 @@ use {
     },
 };
 
+mod body;
 mod dependency;
-mod geth_genesis;
+mod events;
+#[cfg(feature = "firehose")]
+mod firehose;
 mod mirror;
+#[cfg(feature = "p2p")]
+mod p2p;
+mod regenesis;
+#[cfg(all(feature = "storage-rocksdb", not(feature = "storage-lmdb")))]
+mod replica;
+mod state_sync;
+#[cfg(any(feature = "stream-kafka", feature = "stream-nats"))]
+mod stream;
+mod verify;
 
 #[cfg(test)]
 mod tests;
 
 #[derive(Parser)]
-struct Args {
+pub struct Args {
+    /// Path to a file with the hex encoded JWT secret, unless it is set in `JWT_SECRET`.
     #[arg(short, long)]
-    jwtsecret: String,
+    pub jwtsecret: Option<String>,
+    /// Name of a bundled chain specification or path to a genesis config file, overriding
+    /// `GENESIS_CONFIG`.
+    #[arg(long)]
+    pub chain: Option<String>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(clap::Subcommand)]
+pub enum Command {
+    /// Exports the state at a height into the genesis image of a new chain.
+    Regenesis(regenesis::RegenesisArgs),
+    /// Copies the state at a block of a peer instead of executing the blocks up to it.
+    SyncState(state_sync::SyncStateArgs),
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -50,41 +75,126 @@ struct Claims {
     iat: u64,
 }
 
-const EIP1559_ELASTICITY_MULTIPLIER: u64 = 6;
-const EIP1559_BASE_FEE_MAX_CHANGE_DENOMINATOR: U256 = U256::from_limbs([250, 0, 0, 0]);
 const JWT_VALID_DURATION_IN_SECS: u64 = 60;
+/// Used unless a different path is set in the `GENESIS_CONFIG` env var
+const DEFAULT_GENESIS_CONFIG_PATH: &str = "genesis-config.json";
 /// JWT secret key is either passed in as an env var `JWT_SECRET` or file path arg `--jwtsecret`
 static JWTSECRET: Lazy<Vec<u8>> = Lazy::new(|| {
     let mut jwt = std::env::var("JWT_SECRET").unwrap_or_default();
     if jwt.is_empty() {
-        let args = Args::parse();
-        jwt = fs::read_to_string(args.jwtsecret).expect("JWT file should exist");
+        let path = Args::parse()
+            .jwtsecret
+            .expect("JWT secret should be set in JWT_SECRET or --jwtsecret");
+        jwt = fs::read_to_string(path).expect("JWT file should exist");
     }
     hex::decode(jwt).expect("JWT secret should be a hex string")
 });
 
-pub async fn run(max_buffered_commands: u32) {
-    // TODO: genesis should come from a file (path specified by CLI)
-    let genesis_config = GenesisConfig {
-        chain_id: 42069,
-        l2_contract_genesis: serde_json::from_reader(
-            &fs::File::open(
-                "src/tests/optimism/packages/contracts-bedrock/deployments/genesis.json",
-            )
-            .expect("L2 contract genesis file should exist and be readable"),
-        )
-        .expect("Path should point to JSON encoded L2 contract `Genesis` struct"),
-        ..Default::default()
-    };
+/// Runs the `command` against the given `chain`, or the one in the genesis config file if there
+/// is none.
+pub async fn run_command(command: Command, chain: Option<String>) -> anyhow::Result<()> {
+    match command {
+        Command::Regenesis(args) => regenesis::run(genesis_config(chain), args),
+        Command::SyncState(args) => state_sync::run(genesis_config(chain), args).await,
+    }
+}
+
+fn genesis_config(chain: Option<String>) -> GenesisConfig {
+    match chain {
+        Some(chain) => moved_genesis::chain_spec::resolve(&chain)
+            .expect("Chain should be bundled or have a valid genesis config file"),
+        None => {
+            let genesis_config_path = std::env::var("GENESIS_CONFIG")
+                .unwrap_or_else(|_| DEFAULT_GENESIS_CONFIG_PATH.to_string());
+            GenesisConfig::from_file(&genesis_config_path)
+                .expect("Genesis config file should exist and be valid")
+        }
+    }
+}
+
+/// Starts the node on the given `chain`, or the one in the genesis config file if there is none.
+pub async fn run(max_buffered_commands: u32, chain: Option<String>) {
+    let genesis_config = genesis_config(chain);
+    for preinstall in moved_genesis::missing_preinstalls(&genesis_config) {
+        println!(
+            "WARN: genesis has no code for the {} preinstall at {}",
+            preinstall.name, preinstall.address
+        );
+    }
+
+    #[cfg(all(feature = "storage-rocksdb", not(feature = "storage-lmdb")))]
+    if dependency::is_read_replica() {
+        return replica::run(genesis_config).await;
+    }
 
-    let (mut app, app_reader) = initialize_app(genesis_config);
+    let (mut app, app_reader) = initialize_app(genesis_config, moved_genesis::genesis_header);
+    let event_broadcast = events::EventBroadcast::new(app_reader.clone());
+    let event_sender = event_broadcast.sender();
+    app.observers
+        .push(Box::new(moved_app::ExtensionObserver::new(vec![Box::new(
+            event_broadcast,
+        )])));
+    app.observers
+        .push(Box::new(events::PendingTransactionBroadcast::new(
+            event_sender.clone(),
+        )));
     let (queue, state) = moved_app::create(&mut app, max_buffered_commands);
 
+    #[cfg(any(feature = "stream-kafka", feature = "stream-nats"))]
+    if let Some(config) = stream::Config::from_env().expect("Stream config should be valid") {
+        tokio::spawn(stream::run(config, app_reader.clone()));
+    }
+
+    #[cfg(feature = "firehose")]
+    if firehose::is_enabled() {
+        tokio::spawn(firehose::run(app_reader.clone()));
+    }
+
+    if let Some(config) = verify::Config::from_env() {
+        let verify_server_addr = config.addr;
+        let verify_route = verify::route(app_reader.clone(), config);
+        tokio::spawn(
+            warp::serve(verify_route)
+                .bind_with_graceful_shutdown(verify_server_addr, queue.shutdown_listener())
+                .1,
+        );
+    }
+
+    #[cfg(feature = "grpc")]
+    {
+        let grpc_server_addr = match std::env::var("GRPC_LISTEN") {
+            Ok(addr) => addr
+                .parse()
+                .expect("GRPC_LISTEN should be a socket address"),
+            Err(_) => SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 9090)),
+        };
+        let grpc = moved_grpc::serve(
+            grpc_server_addr,
+            app_reader.clone(),
+            queue.shutdown_listener(),
+        );
+        tokio::spawn(async move {
+            if let Err(e) = grpc.await {
+                println!("WARN: gRPC server stopped: {e:?}");
+            }
+        });
+    }
+
+    #[cfg(feature = "p2p")]
+    let rpc_queue = match p2p::Config::from_env().expect("P2P config should be valid") {
+        Some(config) => {
+            p2p::start(config, queue.clone(), app_reader.clone()).expect("P2P network should start")
+        }
+        None => queue.clone(),
+    };
+    #[cfg(not(feature = "p2p"))]
+    let rpc_queue = queue.clone();
+
     moved_app::run(
         state,
         tokio::spawn(async move {
             let http_app_reader = app_reader.clone();
-            let http_cmd_queue = queue.clone();
+            let http_cmd_queue = rpc_queue;
             let http_server_addr =
                 SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 8545));
             let mut content_type = HeaderMap::new();
@@ -106,6 +216,9 @@ pub async fn run(max_buffered_commands: u32) {
                 .with(warp::reply::with::headers(content_type))
                 .with(warp::cors().allow_any_origin());
 
+            let ws_server_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 8546));
+            let ws_route = events::route(event_sender);
+
             let auth_cmd_queue = queue.clone();
             let auth_server_addr =
                 SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 8551));
@@ -134,6 +247,9 @@ pub async fn run(max_buffered_commands: u32) {
                 warp::serve(auth_route)
                     .bind_with_graceful_shutdown(auth_server_addr, queue.shutdown_listener())
                     .1,
+                warp::serve(ws_route)
+                    .bind_with_graceful_shutdown(ws_server_addr, queue.shutdown_listener())
+                    .1,
             );
         }),
     )
@@ -141,8 +257,11 @@ pub async fn run(max_buffered_commands: u32) {
     .unwrap();
 }
 
+/// Creates the application and, on first start, applies the genesis state and inserts the genesis
+/// block with the header that `genesis_header` builds.
 pub fn initialize_app(
     genesis_config: GenesisConfig,
+    genesis_header: impl FnOnce(&GenesisConfig) -> Header,
 ) -> (
     Application<dependency::Dependency>,
     ApplicationReader<dependency::Dependency>,
@@ -155,48 +274,75 @@ pub fn initialize_app(
         .unwrap()
         .is_none()
     {
-        let (genesis_changes, table_changes, evm_storage_changes) = {
-            #[cfg(test)]
-            {
-                moved_genesis_image::load()
-            }
-            #[cfg(not(test))]
-            {
-                moved_genesis::build(
+        let (genesis_changes, table_changes, evm_storage_changes) =
+            match &genesis_config.genesis_image {
+                Some(path) => {
+                    let image = moved_genesis::regenesis::read_image(path)
+                        .expect("Genesis image should be readable");
+                    (
+                        image.changes.into(),
+                        image.tables.into(),
+                        image.evm_storage.into(),
+                    )
+                }
+                #[cfg(test)]
+                None => moved_genesis_image::load(),
+                #[cfg(not(test))]
+                None => moved_genesis::build(
                     &moved_genesis::MovedVm::new(&genesis_config),
                     &genesis_config,
                     &app.evm_storage,
-                )
-            }
-        };
-        moved_genesis::apply(
-            genesis_changes,
-            table_changes,
-            evm_storage_changes,
-            &genesis_config,
-            &mut app.state,
-            &mut app.evm_storage,
-        );
+                ),
+            };
+        let diagnostics = moved_genesis::diagnostics::Diagnostics::from_env()
+            .expect("State root diagnostics config should be valid");
+        match diagnostics {
+            Some(diagnostics) => moved_genesis::apply_with_diagnostics(
+                genesis_changes,
+                table_changes,
+                evm_storage_changes,
+                &genesis_config,
+                &mut app.state,
+                &mut app.evm_storage,
+                &diagnostics,
+            ),
+            None => moved_genesis::apply(
+                genesis_changes,
+                table_changes,
+                evm_storage_changes,
+                &genesis_config,
+                &mut app.state,
+                &mut app.evm_storage,
+            ),
+        }
+
+        let genesis_block = create_genesis_block(&app.block_hash, genesis_header(&genesis_config));
+        app.genesis_update(genesis_block);
     }
 
+    app.restore_mem_pool();
+    app.resume_payload_jobs();
+
     (app, app_reader)
 }
 
-#[cfg(test)]
-fn create_genesis_block(
-    block_hash: &impl BlockHash,
-    genesis_config: &GenesisConfig,
-) -> ExtendedBlock {
-    let genesis_header = Header {
-        state_root: genesis_config.initial_state_root,
-        ..Default::default()
-    };
+fn create_genesis_block(block_hash: &impl BlockHash, genesis_header: Header) -> ExtendedBlock {
     let hash = block_hash.block_hash(&genesis_header);
     let genesis_block = Block::new(genesis_header, Vec::new());
 
     genesis_block.with_hash(hash).with_value(U256::ZERO)
 }
 
+/// Lets tests query the genesis state at height 0, which the op-geth compatible header of
+/// [`moved_genesis::genesis_header`] does not point to.
+#[cfg(test)]
+fn test_genesis_header(genesis_config: &GenesisConfig) -> Header {
+    Header {
+        state_root: genesis_config.initial_state_root,
+        ..Default::default()
+    }
+}
+
 pub fn validate_jwt() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
     warp::header::<String>("authorization").and_then(|token: String| async move {
         // Token is embedded as a string in the form of `Bearer the.actual.token`
@@ -227,12 +373,16 @@ async fn mirror(
     port: &str,
     is_allowed: impl Fn(&MethodName) -> bool,
     payload_id: &impl NewPayloadId,
-    app: ApplicationReader<impl Dependencies>,
+    app: ApplicationReader<impl DependenciesThreadSafe>,
 ) -> Result<warp::reply::Response, Rejection> {
     let (path, query, method, headers, body) = request;
 
-    // Handle load balancer health check with a simple response
+    // Handle load balancer health check with a simple response, turning the node away while its
+    // command queue is saturated
     if method == Method::GET {
+        if queue.load().is_saturated() {
+            return Ok(StatusCode::SERVICE_UNAVAILABLE.into_response());
+        }
         return Ok(StatusCode::OK.into_response());
     }
 
@@ -241,38 +391,37 @@ async fn mirror(
         .map(|x| x.to_str().unwrap().contains("gzip"))
         .unwrap_or(false);
     let request: Result<serde_json::Value, _> = serde_json::from_slice(&body);
-    let (geth_response_parts, geth_response_bytes, parsed_geth_response) =
-        match proxy(path, query, method, headers.clone(), body, port).await {
-            Ok(response) => {
-                let (parts, body) = response.into_parts();
-                let raw_bytes = hyper::body::to_bytes(body)
-                    .await
-                    .expect("Failed to get geth response");
-                let bytes = if is_zipped {
-                    match try_decompress(&raw_bytes) {
-                        Ok(x) => x,
-                        Err(e) => {
-                            println!("WARN: gz decompression failed: {e:?}");
-                            let body = hyper::Body::from(raw_bytes);
-                            return Ok(Response::from_parts(parts, body));
-                        }
-                    }
-                } else {
-                    raw_bytes.to_vec()
-                };
-                match serde_json::from_slice::<serde_json::Value>(&bytes) {
-                    Ok(parsed_response) => (parts, raw_bytes, parsed_response),
-                    Err(_) => {
-                        println!("Request: {:?}", &request);
-                        println!("headers: {headers:?}");
-                        println!("WARN: op-geth non-json response: {:?}", bytes);
-                        let body = hyper::Body::from(bytes);
+    let parsed_geth_response = match proxy(path, query, method, headers.clone(), body, port).await {
+        Ok(response) => {
+            let (parts, body) = response.into_parts();
+            let raw_bytes = hyper::body::to_bytes(body)
+                .await
+                .expect("Failed to get geth response");
+            let bytes = if is_zipped {
+                match try_decompress(&raw_bytes) {
+                    Ok(x) => x,
+                    Err(e) => {
+                        println!("WARN: gz decompression failed: {e:?}");
+                        let body = hyper::Body::from(raw_bytes);
                         return Ok(Response::from_parts(parts, body));
                     }
                 }
+            } else {
+                raw_bytes.to_vec()
+            };
+            match serde_json::from_slice::<serde_json::Value>(&bytes) {
+                Ok(parsed_response) => parsed_response,
+                Err(_) => {
+                    println!("Request: {:?}", &request);
+                    println!("headers: {headers:?}");
+                    println!("WARN: op-geth non-json response: {:?}", bytes);
+                    let body = hyper::Body::from(bytes);
+                    return Ok(Response::from_parts(parts, body));
+                }
             }
-            Err(e) => return Err(e),
-        };
+        }
+        Err(e) => return Err(e),
+    };
 
     let request = request.expect("geth responded, so body must have been JSON");
     let op_move_response =
@@ -284,20 +433,13 @@ async fn mirror(
         op_move_response: &op_move_response,
         port,
     };
-    println!("{}", serde_json::to_string(&log).unwrap());
-
-    // TODO: this is a hack because we currently can't compute the genesis
-    // hash expected by op-node.
-    if geth_genesis::is_genesis_block_request(&request).unwrap_or(false) {
-        let block = geth_genesis::extract_genesis_block(&parsed_geth_response)
-            .expect("Must get genesis from geth");
-        queue.send(Command::GenesisUpdate { block }).await;
-        let body = hyper::Body::from(geth_response_bytes);
-        return Ok(Response::from_parts(geth_response_parts, body));
-    }
+    // Written out as it is serialized, so that a large response is never held as one string
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer(&mut stdout, &log).unwrap();
+    writeln!(stdout).unwrap();
+    drop(stdout);
 
-    let body = hyper::Body::from(serde_json::to_vec(&op_move_response).unwrap());
-    Ok(Response::new(body))
+    Ok(Response::new(body::json(op_move_response)))
 }
 
 async fn proxy(
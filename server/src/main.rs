@@ -1,7 +1,24 @@
+use {clap::Parser, std::num::NonZeroU32};
+
+/// Used unless a different size is set in the `MAX_BUFFERED_COMMANDS` env var
+const DEFAULT_MAX_BUFFERED_COMMANDS: u32 = 1_000;
+
 #[tokio::main]
 async fn main() {
-    // TODO: think about channel size bound
-    let max_buffered_commands = 1_000;
+    let max_buffered_commands = std::env::var("MAX_BUFFERED_COMMANDS")
+        .map(|size| {
+            size.parse::<NonZeroU32>()
+                .expect("MAX_BUFFERED_COMMANDS should be a positive number")
+                .get()
+        })
+        .unwrap_or(DEFAULT_MAX_BUFFERED_COMMANDS);
+
+    let args = moved_server::Args::parse();
+    if let Some(command) = args.command {
+        return moved_server::run_command(command, args.chain)
+            .await
+            .expect("Command should complete successfully");
+    }
 
-    moved_server::run(max_buffered_commands).await;
+    moved_server::run(max_buffered_commands, args.chain).await;
 }
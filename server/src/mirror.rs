@@ -1,9 +1,12 @@
-use {moved_api::jsonrpc::JsonRpcResponse, serde::Serialize};
+use {
+    moved_api::jsonrpc::{JsonRpcResponse, JsonRpcResult},
+    serde::Serialize,
+};
 
 #[derive(Debug, Serialize)]
 pub struct MirrorLog<'a> {
     pub request: &'a serde_json::Value,
     pub geth_response: &'a serde_json::Value,
-    pub op_move_response: &'a JsonRpcResponse,
+    pub op_move_response: &'a JsonRpcResponse<JsonRpcResult>,
     pub port: &'a str,
 }
@@ -0,0 +1,551 @@
+//! Gossip of transactions and blocks between a sequencer and its replicas over libp2p.
+//!
+//! Enabled by setting the `P2P_LISTEN` env var to the multiaddr to listen on, such as
+//! `/ip4/0.0.0.0/tcp/9222`. Other env vars configure the node:
+//! * `P2P_ROLE` is either `sequencer` or `replica`,
+//! * `P2P_PEERS` is a comma separated list of multiaddrs to dial on startup,
+//! * `P2P_KEY` is the hex encoded ed25519 secret key of the node, a random one is used if missing,
+//! * `P2P_SEQUENCER` is the peer ID of the sequencer, required by replicas.
+//!
+//! The sequencer publishes every block it seals, as the payload attributes that rebuild it. A
+//! replica builds the block from them as soon as it extends its head, under the payload ID that
+//! op-node later derives for the same block from L1, which then finds the block already built.
+//! Blocks that don't extend the head of the replica are dropped and left to op-node derivation.
+//!
+//! Replicas publish the transactions sent to them over JSON-RPC instead of adding them to their own
+//! mem-pool, so that the blocks they build hold the exact transactions of the sequencer's blocks.
+//! The sequencer adds the transactions it receives to its mem-pool.
+//!
+//! This is unrelated to the read replica mode, replicas here run their own op-node and database.
+
+use {
+    alloy::{
+        consensus::transaction::TxEnvelope,
+        eips::eip2718::{Decodable2718, Encodable2718},
+        primitives::keccak256,
+    },
+    anyhow::Context,
+    libp2p::{
+        futures::StreamExt,
+        gossipsub::{self, IdentTopic, MessageAuthenticity},
+        identity::Keypair,
+        noise,
+        swarm::SwarmEvent,
+        tcp, yamux, Multiaddr, PeerId, Swarm,
+    },
+    moved_api::schema::BlockNumberOrTag,
+    moved_app::{
+        ApplicationReader, Command, CommandQueue, Dependencies, Payload, ToPayloadIdInput,
+    },
+    moved_blockchain::payload::{NewPayloadId, PayloadResponse, StatePayloadId},
+    moved_shared::primitives::{B256, B64},
+    serde::{Deserialize, Serialize},
+    std::{
+        str::FromStr,
+        time::{Duration, Instant},
+    },
+    tokio::sync::{broadcast, mpsc},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const IDLE_CONNECTION_TIMEOUT: Duration = Duration::from_secs(60);
+/// Messages waiting to be published or imported before the senders wait.
+const CHANNEL_CAPACITY: usize = 1_024;
+/// Length of the Holocene `extraData`, a version byte followed by the EIP-1559 parameters.
+const HOLOCENE_EXTRA_DATA_LEN: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Sequencer,
+    Replica,
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sequencer" => Ok(Self::Sequencer),
+            "replica" => Ok(Self::Replica),
+            other => anyhow::bail!("Unknown P2P role {other}, expected sequencer or replica"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub role: Role,
+    pub listen: Multiaddr,
+    pub peers: Vec<Multiaddr>,
+    pub keypair: Keypair,
+    pub sequencer: Option<PeerId>,
+}
+
+impl Config {
+    /// Reads the P2P configuration from the env, returning `None` if gossip is disabled.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(listen) = std::env::var("P2P_LISTEN") else {
+            return Ok(None);
+        };
+
+        let role: Role = std::env::var("P2P_ROLE")
+            .context("P2P_ROLE should be set")?
+            .parse()?;
+        let peers = std::env::var("P2P_PEERS")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|peer| !peer.is_empty())
+            .map(|peer| peer.parse().context("Invalid P2P peer address"))
+            .collect::<anyhow::Result<_>>()?;
+        let keypair = match std::env::var("P2P_KEY") {
+            Ok(key) => Keypair::ed25519_from_bytes(hex::decode(key)?)?,
+            Err(_) => Keypair::generate_ed25519(),
+        };
+        let sequencer = match std::env::var("P2P_SEQUENCER") {
+            Ok(peer_id) => Some(peer_id.parse()?),
+            Err(_) => None,
+        };
+        if role == Role::Replica && sequencer.is_none() {
+            anyhow::bail!("P2P_SEQUENCER should be set on replicas");
+        }
+
+        Ok(Some(Self {
+            role,
+            listen: listen.parse().context("Invalid P2P listen address")?,
+            peers,
+            keypair,
+            sequencer,
+        }))
+    }
+}
+
+/// A block sealed by the sequencer, as it is gossiped to replicas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GossipBlock {
+    pub block_hash: B256,
+    pub parent_hash: B256,
+    pub attributes: Payload,
+}
+
+impl From<PayloadResponse> for GossipBlock {
+    fn from(value: PayloadResponse) -> Self {
+        let payload = value.execution_payload;
+
+        Self {
+            block_hash: payload.block_hash,
+            parent_hash: payload.parent_hash,
+            attributes: Payload {
+                timestamp: payload.timestamp,
+                prev_randao: payload.prev_randao,
+                suggested_fee_recipient: payload.fee_recipient,
+                withdrawals: payload.withdrawals,
+                parent_beacon_block_root: value.parent_beacon_block_root,
+                eip1559_params: eip1559_params(&payload.extra_data),
+                transactions: payload.transactions,
+                gas_limit: payload.gas_limit,
+            },
+        }
+    }
+}
+
+/// Recovers the EIP-1559 parameters from a Holocene block `extraData`.
+fn eip1559_params(extra_data: &[u8]) -> Option<B64> {
+    (extra_data.len() == HOLOCENE_EXTRA_DATA_LEN).then(|| B64::from_slice(&extra_data[1..]))
+}
+
+struct Topics {
+    blocks: IdentTopic,
+    transactions: IdentTopic,
+}
+
+impl Topics {
+    fn new(chain_id: u64) -> Self {
+        Self {
+            blocks: IdentTopic::new(format!("/op-move/{chain_id}/blocks/v1")),
+            transactions: IdentTopic::new(format!("/op-move/{chain_id}/transactions/v1")),
+        }
+    }
+}
+
+/// Joins the gossip network configured by `config`.
+///
+/// Returns the queue that the JSON-RPC API should send its commands through. On replicas, it
+/// publishes transactions instead of adding them to the mem-pool.
+pub fn start(
+    config: Config,
+    queue: CommandQueue,
+    app: ApplicationReader<impl Dependencies + 'static>,
+) -> anyhow::Result<CommandQueue> {
+    let swarm = create_swarm(&config)?;
+    println!(
+        "P2P node {} listening on {}",
+        swarm.local_peer_id(),
+        config.listen
+    );
+    let (transactions_tx, transactions_rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+    let rpc_queue = match config.role {
+        Role::Sequencer => queue.clone(),
+        Role::Replica => intercept_transactions(queue.clone(), transactions_tx),
+    };
+
+    tokio::spawn(run(config, swarm, queue, app, transactions_rx));
+
+    Ok(rpc_queue)
+}
+
+fn create_swarm(config: &Config) -> anyhow::Result<Swarm<gossipsub::Behaviour>> {
+    let mut swarm = libp2p::SwarmBuilder::with_existing_identity(config.keypair.clone())
+        .with_tokio()
+        .with_tcp(
+            tcp::Config::default(),
+            noise::Config::new,
+            yamux::Config::default,
+        )?
+        .with_behaviour(|key| {
+            let gossip_config = gossipsub::ConfigBuilder::default()
+                .validation_mode(gossipsub::ValidationMode::Strict)
+                // The same transaction or block published twice is only delivered once
+                .message_id_fn(|message| gossipsub::MessageId::from(keccak256(&message.data)))
+                .build()?;
+
+            Ok(gossipsub::Behaviour::new(
+                MessageAuthenticity::Signed(key.clone()),
+                gossip_config,
+            )?)
+        })?
+        .with_swarm_config(|c| c.with_idle_connection_timeout(IDLE_CONNECTION_TIMEOUT))
+        .build();
+
+    swarm.listen_on(config.listen.clone())?;
+    for peer in &config.peers {
+        swarm.dial(peer.clone())?;
+    }
+
+    Ok(swarm)
+}
+
+/// Creates a [`CommandQueue`] forwarding every command to `queue`, except for transactions, which
+/// are sent to `transactions` to be published.
+fn intercept_transactions(
+    queue: CommandQueue,
+    transactions: mpsc::Sender<TxEnvelope>,
+) -> CommandQueue {
+    let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let (killshot, _) = broadcast::channel(1);
+    let intercepting_queue = CommandQueue::new(tx, killshot);
+
+    let shutdown = intercepting_queue.shutdown_listener();
+    let shutdown_queue = queue.clone();
+    tokio::spawn(async move {
+        shutdown.await;
+        shutdown_queue.shutdown();
+    });
+
+    tokio::spawn(async move {
+        while let Some(command) = rx.recv().await {
+            match command {
+                Command::AddTransaction { tx } => {
+                    if transactions.send(tx).await.is_err() {
+                        queue.shutdown();
+                    }
+                }
+                command => queue.send(command).await,
+            }
+        }
+    });
+
+    intercepting_queue
+}
+
+async fn run(
+    config: Config,
+    mut swarm: Swarm<gossipsub::Behaviour>,
+    queue: CommandQueue,
+    app: ApplicationReader<impl Dependencies + 'static>,
+    mut transactions: mpsc::Receiver<TxEnvelope>,
+) {
+    let topics = Topics::new(app.chain_id());
+    let gossip = swarm.behaviour_mut();
+    gossip
+        .subscribe(&topics.transactions)
+        .expect("Transaction topic should be subscribable");
+    gossip
+        .subscribe(&topics.blocks)
+        .expect("Block topic should be subscribable");
+
+    let (blocks_tx, blocks_rx) = mpsc::channel(CHANNEL_CAPACITY);
+    if config.role == Role::Replica {
+        tokio::spawn(import_blocks(blocks_rx, queue.clone(), app.clone()));
+    }
+
+    let mut next_height = app.block_number() + 1;
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        tokio::select! {
+            event = swarm.select_next_some() => {
+                let message = match event {
+                    SwarmEvent::Behaviour(gossipsub::Event::Message { message, .. }) => message,
+                    SwarmEvent::ConnectionEstablished { .. }
+                    | SwarmEvent::ConnectionClosed { .. } => {
+                        app.peer_count.set(swarm.connected_peers().count() as u64);
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                if message.topic == topics.transactions.hash() && config.role == Role::Sequencer {
+                    match TxEnvelope::decode_2718(&mut message.data.as_slice()) {
+                        // Peers are not trusted to have checked the transactions they gossip
+                        Ok(tx) => match app.validate_transaction(&tx) {
+                            Ok(_) => {
+                                // A shed transaction is lost like any gossip that never arrives
+                                let command = Command::AddTransaction { tx };
+                                queue.send_or_shed(command).await.ok();
+                            }
+                            Err(e) => println!(
+                                "WARN: gossiped transaction {} is rejected: {e}",
+                                tx.tx_hash()
+                            ),
+                        },
+                        Err(e) => println!("WARN: invalid gossiped transaction: {e:?}"),
+                    }
+                } else if message.topic == topics.blocks.hash()
+                    && config.role == Role::Replica
+                    && message.source == config.sequencer
+                {
+                    match serde_json::from_slice::<GossipBlock>(&message.data) {
+                        Ok(block) => {
+                            blocks_tx.send(block).await.ok();
+                        }
+                        Err(e) => println!("WARN: invalid gossiped block: {e:?}"),
+                    }
+                }
+            }
+            Some(tx) = transactions.recv() => {
+                publish(&mut swarm, &topics.transactions, tx.encoded_2718());
+            }
+            _ = interval.tick(), if config.role == Role::Sequencer => {
+                while next_height <= app.block_number() {
+                    let block = app
+                        .block_by_height(BlockNumberOrTag::Number(next_height), false)
+                        .and_then(|block| app.payload_by_block_hash(block.block.header.hash));
+                    if let Some(payload) = block {
+                        let message = serde_json::to_vec(&GossipBlock::from(payload))
+                            .expect("Block should serialize to JSON");
+                        publish(&mut swarm, &topics.blocks, message);
+                    }
+                    next_height += 1;
+                }
+            }
+        }
+    }
+}
+
+fn publish(swarm: &mut Swarm<gossipsub::Behaviour>, topic: &IdentTopic, message: Vec<u8>) {
+    if let Err(e) = swarm.behaviour_mut().publish(topic.clone(), message) {
+        println!("WARN: failed to gossip on {topic}: {e:?}");
+    }
+}
+
+/// Builds the gossiped blocks that extend the head of the chain, one at a time.
+async fn import_blocks(
+    mut blocks: mpsc::Receiver<GossipBlock>,
+    queue: CommandQueue,
+    app: ApplicationReader<impl Dependencies>,
+) {
+    while let Some(block) = blocks.recv().await {
+        let head = app
+            .block_by_height(BlockNumberOrTag::Latest, false)
+            .map(|head| head.block.header.hash);
+        if head != Some(block.parent_hash) {
+            continue;
+        }
+
+        let payload_id =
+            StatePayloadId.new_payload_id(block.attributes.to_payload_id_input(&block.parent_hash));
+        queue
+            .send(Command::StartBlockBuild {
+                payload_attributes: block.attributes,
+                payload_id,
+                requested_at: Instant::now(),
+            })
+            .await;
+        queue.wait_for_pending_commands().await;
+
+        if app.block_by_hash(block.block_hash, false).is_none() {
+            println!(
+                "WARN: gossiped block {} was built with a different hash",
+                block.block_hash
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        alloy::{
+            consensus::{SignableTransaction, TxEip1559},
+            network::TxSignerSync,
+            primitives::{Address, TxKind, U256, U64},
+            signers::local::PrivateKeySigner,
+        },
+    };
+
+    const CHAIN_ID: u64 = 42069;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn config(role: Role, peers: Vec<Multiaddr>, sequencer: Option<PeerId>) -> Config {
+        Config {
+            role,
+            listen: "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+            peers,
+            keypair: Keypair::generate_ed25519(),
+            sequencer,
+        }
+    }
+
+    /// Connects a sequencer and a replica, returning their swarms once both have subscribed to
+    /// every topic of the other.
+    async fn connected_swarms(
+        topics: &Topics,
+    ) -> (Swarm<gossipsub::Behaviour>, Swarm<gossipsub::Behaviour>) {
+        let sequencer_config = config(Role::Sequencer, Vec::new(), None);
+        let sequencer_id = sequencer_config.keypair.public().to_peer_id();
+        let mut sequencer = create_swarm(&sequencer_config).unwrap();
+        let address = loop {
+            if let SwarmEvent::NewListenAddr { address, .. } = sequencer.select_next_some().await {
+                break address;
+            }
+        };
+        let mut replica =
+            create_swarm(&config(Role::Replica, vec![address], Some(sequencer_id))).unwrap();
+
+        for swarm in [&mut sequencer, &mut replica] {
+            swarm.behaviour_mut().subscribe(&topics.blocks).unwrap();
+            swarm
+                .behaviour_mut()
+                .subscribe(&topics.transactions)
+                .unwrap();
+        }
+
+        let (mut sequencer_subscriptions, mut replica_subscriptions) = (0, 0);
+        while sequencer_subscriptions < 2 || replica_subscriptions < 2 {
+            tokio::select! {
+                event = sequencer.select_next_some() => {
+                    if let SwarmEvent::Behaviour(gossipsub::Event::Subscribed { .. }) = event {
+                        sequencer_subscriptions += 1;
+                    }
+                }
+                event = replica.select_next_some() => {
+                    if let SwarmEvent::Behaviour(gossipsub::Event::Subscribed { .. }) = event {
+                        replica_subscriptions += 1;
+                    }
+                }
+            }
+        }
+
+        (sequencer, replica)
+    }
+
+    /// Drives both swarms until `receiver` gets a message, returning it.
+    async fn receive(
+        sender: &mut Swarm<gossipsub::Behaviour>,
+        receiver: &mut Swarm<gossipsub::Behaviour>,
+    ) -> gossipsub::Message {
+        loop {
+            tokio::select! {
+                _ = sender.select_next_some() => {}
+                event = receiver.select_next_some() => {
+                    if let SwarmEvent::Behaviour(gossipsub::Event::Message { message, .. }) = event {
+                        return message;
+                    }
+                }
+            }
+        }
+    }
+
+    fn create_transaction() -> TxEnvelope {
+        let signer = PrivateKeySigner::random();
+        let mut tx = TxEip1559 {
+            chain_id: CHAIN_ID,
+            nonce: 3,
+            gas_limit: 21_000,
+            max_fee_per_gas: 2,
+            max_priority_fee_per_gas: 1,
+            to: TxKind::Call(Address::repeat_byte(0x11)),
+            value: U256::from(5),
+            access_list: Default::default(),
+            input: Default::default(),
+        };
+        let signature = signer.sign_transaction_sync(&mut tx).unwrap();
+
+        TxEnvelope::Eip1559(tx.into_signed(signature))
+    }
+
+    #[tokio::test]
+    async fn test_transaction_gossiped_by_replica_reaches_sequencer() {
+        let topics = Topics::new(CHAIN_ID);
+        let (mut sequencer, mut replica) = connected_swarms(&topics).await;
+        let tx = create_transaction();
+
+        publish(&mut replica, &topics.transactions, tx.encoded_2718());
+        let message = tokio::time::timeout(TIMEOUT, receive(&mut replica, &mut sequencer))
+            .await
+            .expect("Transaction should be gossiped to the sequencer");
+
+        assert_eq!(message.topic, topics.transactions.hash());
+        let received = TxEnvelope::decode_2718(&mut message.data.as_slice()).unwrap();
+        assert_eq!(received, tx);
+    }
+
+    #[tokio::test]
+    async fn test_block_gossiped_by_sequencer_reaches_replica() {
+        let topics = Topics::new(CHAIN_ID);
+        let (mut sequencer, mut replica) = connected_swarms(&topics).await;
+        let block = GossipBlock {
+            block_hash: B256::repeat_byte(1),
+            parent_hash: B256::repeat_byte(2),
+            attributes: Payload {
+                timestamp: U64::from(1_000),
+                gas_limit: U64::from(30_000_000),
+                transactions: vec![create_transaction().encoded_2718().into()],
+                eip1559_params: Some(B64::repeat_byte(3)),
+                ..Default::default()
+            },
+        };
+
+        let message = serde_json::to_vec(&block).unwrap();
+        publish(&mut sequencer, &topics.blocks, message);
+        let message = tokio::time::timeout(TIMEOUT, receive(&mut sequencer, &mut replica))
+            .await
+            .expect("Block should be gossiped to the replica");
+
+        assert_eq!(message.topic, topics.blocks.hash());
+        assert_eq!(message.source, Some(*sequencer.local_peer_id()));
+        let received: GossipBlock = serde_json::from_slice(&message.data).unwrap();
+        assert_eq!(received.block_hash, block.block_hash);
+        assert_eq!(received.parent_hash, block.parent_hash);
+        assert_eq!(received.attributes, block.attributes);
+    }
+
+    #[test]
+    fn test_role_is_parsed_by_name() {
+        assert_eq!("sequencer".parse::<Role>().unwrap(), Role::Sequencer);
+        assert_eq!("replica".parse::<Role>().unwrap(), Role::Replica);
+        assert!("validator".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn test_eip1559_params_are_recovered_from_holocene_extra_data() {
+        let params = [0, 0, 0, 0xfa, 0, 0, 0, 0x06];
+        let extra_data = [&[0u8][..], &params].concat();
+
+        assert_eq!(eip1559_params(&extra_data), Some(B64::from(params)));
+        assert_eq!(eip1559_params(&[]), None);
+    }
+}
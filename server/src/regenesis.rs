@@ -0,0 +1,217 @@
+//! The `regenesis` subcommand, which exports the state of this chain at some height into the
+//! genesis image of a new chain.
+//!
+//! The state trie only holds hashed keys, so the export needs the preimages recorded while the
+//! blocks were executed, see `RECORD_TRIE_PREIMAGES`. The keys written by genesis are never
+//! recorded, so their preimages come from building the genesis state again. The node should be
+//! stopped while the state is exported from its database.
+
+use {
+    crate::dependency,
+    alloy::eips::BlockNumberOrTag,
+    anyhow::{anyhow, Context},
+    move_core_types::account_address::AccountAddress,
+    moved_app::ApplicationReader,
+    moved_blockchain::state::StateQueries,
+    moved_evm_ext::state::InMemoryStorageTrieRepository,
+    moved_genesis::{
+        config::{GenesisConfig, GenesisConfigFile},
+        regenesis, MovedVm,
+    },
+    moved_state::preimage::{state_preimages, table_preimages, TriePreimages},
+    std::{
+        collections::{BTreeMap, HashMap},
+        fs,
+        path::PathBuf,
+    },
+};
+
+#[derive(Debug, clap::Args)]
+pub struct RegenesisArgs {
+    /// Height of the block whose state the new chain starts from.
+    #[arg(long)]
+    pub height: u64,
+    /// Path to write the genesis image of the new chain to.
+    #[arg(long)]
+    pub image: PathBuf,
+    /// Path to a genesis config file to point at the state root of the new image.
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Replaces the framework modules with the ones bundled with this binary.
+    #[arg(long)]
+    pub upgrade_framework: bool,
+    /// Moves the resources of an account to a new address, given as `FROM=TO`.
+    #[arg(long, value_parser = parse_remapping)]
+    pub remap: Vec<(AccountAddress, AccountAddress)>,
+}
+
+/// Exports the state at `args.height` of the chain configured by `genesis_config`.
+pub fn run(genesis_config: GenesisConfig, args: RegenesisArgs) -> anyhow::Result<()> {
+    let (_, reader) = dependency::create(&genesis_config);
+
+    export_image(
+        &genesis_config,
+        &reader,
+        &dependency::trie_preimages(),
+        args,
+    )
+}
+
+fn export_image(
+    genesis_config: &GenesisConfig,
+    reader: &ApplicationReader<dependency::Dependency>,
+    recorded_preimages: &impl TriePreimages,
+    args: RegenesisArgs,
+) -> anyhow::Result<()> {
+    let state_root = reader
+        .block_by_height(BlockNumberOrTag::Number(args.height), false)
+        .ok_or_else(|| anyhow!("Block {} is not known", args.height))?
+        .block
+        .header
+        .state_root;
+
+    let vm = MovedVm::new(genesis_config);
+    // A chain that has started from a genesis image has its genesis keys in that image
+    let genesis_preimages: HashMap<_, _> = match &genesis_config.genesis_image {
+        Some(path) => {
+            let image = regenesis::read_image(path)?;
+            state_preimages(&image.changes.into())
+                .chain(table_preimages(&image.tables.into()))
+                .collect()
+        }
+        None => {
+            let (changes, table_changes, _) =
+                moved_genesis::build(&vm, genesis_config, &InMemoryStorageTrieRepository::new());
+            state_preimages(&changes)
+                .chain(table_preimages(&table_changes))
+                .collect()
+        }
+    };
+
+    let mut image = regenesis::export(
+        state_root,
+        |hash| reader.state_queries.trie_node(hash),
+        |key| match genesis_preimages.get(&key) {
+            Some(preimage) => Ok(Some(preimage.clone())),
+            None => recorded_preimages
+                .preimage(key)
+                .map_err(|e| anyhow!("Failed to read the preimage of {key}: {e:?}")),
+        },
+        &reader.state_queries.resolver_at(args.height),
+        &reader.evm_storage,
+    )?;
+
+    if !args.remap.is_empty() {
+        let remapping: BTreeMap<_, _> = args.remap.into_iter().collect();
+        image = regenesis::remap_addresses(image, &remapping)?;
+    }
+    if args.upgrade_framework {
+        image = regenesis::upgrade_framework(image, &vm);
+    }
+    regenesis::write_image(&args.image, &image)?;
+
+    if let Some(path) = args.config {
+        let data = fs::read_to_string(&path).context(format!("Path: {path:?}"))?;
+        let config: GenesisConfigFile = serde_json::from_str(&data)
+            .context(format!("Genesis config should be valid JSON: {path:?}"))?;
+        // The config resolves its paths against its own directory, which the image need not be in
+        let image_path =
+            fs::canonicalize(&args.image).context(format!("Path: {:?}", args.image))?;
+        let config = regenesis::update_config(config, &image, &image_path)?;
+        fs::write(&path, serde_json::to_string_pretty(&config)?)
+            .context(format!("Path: {path:?}"))?;
+    }
+
+    println!(
+        "INFO: exported the state of block {} into {:?}",
+        args.height, args.image
+    );
+
+    Ok(())
+}
+
+fn parse_remapping(value: &str) -> anyhow::Result<(AccountAddress, AccountAddress)> {
+    let (from, to) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Remapping should be given as FROM=TO"))?;
+
+    Ok((from.parse()?, to.parse()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{initialize_app, test_genesis_header},
+        alloy::primitives::{b256, U256},
+        moved_genesis::L2_TO_L1_MESSAGE_PASSER,
+        moved_state::preimage::InMemoryTriePreimages,
+    };
+
+    #[test]
+    fn test_node_starts_from_exported_image() {
+        let genesis_config = GenesisConfig::default();
+        let (_app, reader) = initialize_app(genesis_config.clone(), test_genesis_header);
+        let dir = tempfile::tempdir().unwrap();
+        let image_path = dir.path().join("genesis.bin");
+        let config_path = dir.path().join("genesis-config.json");
+        fs::write(
+            &config_path,
+            r#"{"chainId": 1, "l2ContractGenesis": "l2-genesis.json"}"#,
+        )
+        .unwrap();
+        let args = RegenesisArgs {
+            height: 0,
+            image: image_path.clone(),
+            config: Some(config_path.clone()),
+            upgrade_framework: false,
+            remap: Vec::new(),
+        };
+
+        export_image(
+            &genesis_config,
+            &reader,
+            &InMemoryTriePreimages::default(),
+            args,
+        )
+        .unwrap();
+
+        let config: GenesisConfigFile =
+            serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        let new_config = GenesisConfig {
+            initial_state_root: config.initial_state_root.unwrap(),
+            genesis_image: config.genesis_image,
+            ..genesis_config
+        };
+        let (_new_app, new_reader) = initialize_app(new_config, test_genesis_header);
+
+        let height = BlockNumberOrTag::Number(0);
+        // The implementation slot of the EIP-1967 proxy in front of the predeploy
+        let slot = U256::from_be_bytes(
+            b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc").0,
+        );
+        let code = reader.evm_code_by_height(L2_TO_L1_MESSAGE_PASSER, height);
+        assert!(code.is_some());
+        assert_eq!(
+            new_reader.evm_code_by_height(L2_TO_L1_MESSAGE_PASSER, height),
+            code
+        );
+        assert_eq!(
+            new_reader.storage_by_height(L2_TO_L1_MESSAGE_PASSER, slot, height),
+            reader.storage_by_height(L2_TO_L1_MESSAGE_PASSER, slot, height)
+        );
+    }
+
+    #[test]
+    fn test_parse_remapping_reads_both_addresses() {
+        let (from, to) = parse_remapping("0x1234=0x5678").unwrap();
+
+        assert_eq!(from, AccountAddress::from_hex_literal("0x1234").unwrap());
+        assert_eq!(to, AccountAddress::from_hex_literal("0x5678").unwrap());
+    }
+
+    #[test]
+    fn test_parse_remapping_rejects_missing_target() {
+        assert!(parse_remapping("0x1234").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! Read replica mode of the server.
+//!
+//! A read replica opens the RocksDB datadir of a running op-move process as a secondary instance
+//! and serves the query part of the JSON-RPC API, so that RPC capacity can be scaled independently
+//! of the block building process. It never writes to the datadir and does not accept any commands.
+//!
+//! The replica periodically catches up with the primary. The primary persists a block only after
+//! its state root, receipts and transactions, so the block height marker always points to a block
+//! that can be fully served. A replica that keeps failing to catch up shuts down instead of serving
+//! an ever more stale view of the chain.
+
+use {
+    crate::{body, dependency},
+    moved_api::method_name::MethodName,
+    moved_app::{ApplicationReader, CommandQueue, DependenciesThreadSafe},
+    moved_blockchain::payload::StatePayloadId,
+    moved_genesis::config::GenesisConfig,
+    std::{
+        fmt::Debug,
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+        time::Duration,
+    },
+    tokio::sync::{broadcast, mpsc},
+    warp::{
+        http::{header::CONTENT_TYPE, HeaderValue, Method, StatusCode},
+        hyper::{body::Bytes, Response},
+        Filter, Rejection, Reply,
+    },
+};
+
+const CATCH_UP_INTERVAL: Duration = Duration::from_millis(100);
+/// Number of catch up attempts in a row that can fail before the replica shuts down. Single
+/// failures are retried, as the primary may be in the middle of compacting its datadir.
+const MAX_CATCH_UP_FAILURES: u32 = 50;
+
+pub async fn run(genesis_config: GenesisConfig) {
+    let app_reader = dependency::create_reader(&genesis_config);
+    let queue = detached_queue();
+
+    tokio::spawn(follow_primary(
+        dependency::catch_up_with_primary,
+        CATCH_UP_INTERVAL,
+        queue.clone(),
+    ));
+
+    let http_server_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 8545));
+    let http_queue = queue.clone();
+    let http_route = warp::any()
+        .map(move || (http_queue.clone(), app_reader.clone()))
+        .and(warp::method())
+        .and(warp::body::bytes())
+        .and_then(|(queue, app_reader), method, body| serve(queue, method, body, app_reader))
+        .with(warp::cors().allow_any_origin());
+
+    warp::serve(http_route)
+        .bind_with_graceful_shutdown(http_server_addr, queue.shutdown_listener())
+        .1
+        .await;
+}
+
+/// Calls `catch_up` every `interval` until it fails [`MAX_CATCH_UP_FAILURES`] times in a row, at
+/// which point the replica is shut down through the `queue`.
+async fn follow_primary<E: Debug + Send + 'static>(
+    catch_up: fn() -> Result<(), E>,
+    interval: Duration,
+    queue: CommandQueue,
+) {
+    let mut interval = tokio::time::interval(interval);
+    let mut failures = 0;
+
+    while failures < MAX_CATCH_UP_FAILURES {
+        interval.tick().await;
+
+        match tokio::task::spawn_blocking(catch_up).await {
+            Ok(Ok(())) => failures = 0,
+            Ok(Err(e)) => {
+                failures += 1;
+                println!("WARN: replica failed to catch up with the primary: {e:?}");
+            }
+            Err(e) => {
+                failures += 1;
+                println!("WARN: replica catch up task failed: {e}");
+            }
+        }
+    }
+
+    println!("WARN: replica failed to catch up {failures} times in a row, shutting down");
+    queue.shutdown();
+}
+
+/// Creates a [`CommandQueue`] that is not connected to any actor.
+///
+/// Only query methods are allowed in replica mode, so nothing is ever sent through it.
+fn detached_queue() -> CommandQueue {
+    let (ktx, _) = broadcast::channel(1);
+    let (tx, _) = mpsc::channel(1);
+
+    CommandQueue::new(tx, ktx)
+}
+
+async fn serve(
+    queue: CommandQueue,
+    method: Method,
+    body: Bytes,
+    app: ApplicationReader<impl DependenciesThreadSafe>,
+) -> Result<warp::reply::Response, Rejection> {
+    // Handle load balancer health check with a simple response
+    if method == Method::GET {
+        return Ok(StatusCode::OK.into_response());
+    }
+
+    let request: serde_json::Value =
+        serde_json::from_slice(&body).map_err(|_| warp::reject::reject())?;
+    let response = moved_api::request::handle(
+        request,
+        queue,
+        MethodName::is_query_api,
+        &StatePayloadId,
+        app,
+    )
+    .await;
+
+    let mut response = Response::new(body::json(response));
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::sync::atomic::{AtomicU32, Ordering},
+    };
+
+    #[tokio::test]
+    async fn test_replica_shuts_down_after_repeated_catch_up_failures() {
+        static ATTEMPTS: AtomicU32 = AtomicU32::new(0);
+        let queue = detached_queue();
+        let shutdown = queue.shutdown_listener();
+
+        follow_primary(
+            || {
+                ATTEMPTS.fetch_add(1, Ordering::Relaxed);
+                Err("primary is gone")
+            },
+            Duration::from_millis(1),
+            queue,
+        )
+        .await;
+
+        shutdown.await;
+        assert_eq!(ATTEMPTS.load(Ordering::Relaxed), MAX_CATCH_UP_FAILURES);
+    }
+
+    #[tokio::test]
+    async fn test_replica_retries_single_catch_up_failures() {
+        static ATTEMPTS: AtomicU32 = AtomicU32::new(0);
+        let queue = detached_queue();
+        let mut shutdown = queue.shutdown_listener();
+
+        let follow = tokio::spawn(follow_primary(
+            || {
+                // Every other attempt fails, which never adds up to a shutdown
+                match ATTEMPTS.fetch_add(1, Ordering::Relaxed) % 2 {
+                    0 => Err("primary is compacting"),
+                    _ => Ok(()),
+                }
+            },
+            Duration::from_millis(1),
+            queue,
+        ));
+
+        tokio::time::sleep(Duration::from_millis(10 * u64::from(MAX_CATCH_UP_FAILURES))).await;
+
+        assert!(ATTEMPTS.load(Ordering::Relaxed) > MAX_CATCH_UP_FAILURES);
+        let shut_down = tokio::time::timeout(Duration::from_millis(1), &mut shutdown).await;
+        assert!(shut_down.is_err());
+        follow.abort();
+    }
+}
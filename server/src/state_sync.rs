@@ -0,0 +1,174 @@
+//! The `sync-state` subcommand, which copies the state at a block of a peer into the database
+//! instead of executing every block up to it.
+//!
+//! The state trie is fetched node by node from the `umi_getTrieNodes` method of the peer, starting
+//! from the state root of the block. The storage tries of the EVM accounts found in it are fetched
+//! the same way once the peer tells their addresses through `umi_getStorageAccounts`. The node
+//! should be stopped while the state is copied into its database.
+
+use {
+    crate::dependency,
+    alloy::{
+        eips::BlockNumberOrTag,
+        primitives::{Address, Bytes, B256},
+        providers::{Provider, ProviderBuilder},
+        transports::http::reqwest::Url,
+    },
+    anyhow::{anyhow, ensure, Context},
+    eth_trie::DB,
+    moved_api::methods::{
+        get_storage_accounts::MAX_STORAGE_ACCOUNTS, get_trie_nodes::MAX_TRIE_NODES,
+    },
+    moved_blockchain::state::TrieSync,
+    moved_evm_ext::state::{StorageTrieChanges, StorageTrieRepository, StorageTriesChanges},
+    moved_genesis::config::GenesisConfig,
+    moved_state::State,
+    std::{
+        collections::HashMap,
+        convert::Infallible,
+        sync::{Arc, RwLock},
+    },
+};
+
+#[derive(Debug, clap::Args)]
+pub struct SyncStateArgs {
+    /// JSON-RPC endpoint of the node to copy the state from.
+    #[arg(long)]
+    pub url: Url,
+    /// Height of the block whose state is copied, the latest block of the peer if not given.
+    #[arg(long)]
+    pub height: Option<u64>,
+}
+
+/// Copies the state of the block at `args.height` of the peer at `args.url`.
+pub async fn run(genesis_config: GenesisConfig, args: SyncStateArgs) -> anyhow::Result<()> {
+    let (app, _) = dependency::create(&genesis_config);
+    let peer = ProviderBuilder::new().on_http(args.url);
+    let height = args
+        .height
+        .map_or(BlockNumberOrTag::Latest, BlockNumberOrTag::Number);
+    let block = peer
+        .get_block_by_number(height)
+        .await?
+        .ok_or_else(|| anyhow!("Block {height} is not known to the peer"))?;
+    let state_root = block.header.state_root;
+
+    let storage_roots = sync_trie(&peer, TrieSync::new(state_root, app.state.db()), None).await?;
+
+    for chunk in storage_roots.chunks(MAX_STORAGE_ACCOUNTS) {
+        let key_hashes: Vec<_> = chunk.iter().map(|(key_hash, _)| *key_hash).collect();
+        let accounts: Vec<Option<Address>> = peer
+            .raw_request("umi_getStorageAccounts".into(), (key_hashes,))
+            .await?;
+
+        for ((key_hash, storage_root), account) in chunk.iter().zip(accounts) {
+            let account = account
+                .ok_or_else(|| anyhow!("Peer does not know the account with key {key_hash}"))?;
+            let nodes = Arc::new(StorageTrieNodes::new(&app.evm_storage, account));
+            sync_trie(
+                &peer,
+                TrieSync::new(*storage_root, nodes.clone()),
+                Some(account),
+            )
+            .await?;
+
+            let trie_diff = nodes.fetched.write().unwrap().drain().collect();
+            let changes = StorageTrieChanges {
+                root: *storage_root,
+                trie_diff,
+                slots: Vec::new(),
+            };
+            app.evm_storage
+                .apply(StorageTriesChanges::empty().with_trie_changes(account, changes))
+                .context(format!("Failed to store the storage trie of {account}"))?;
+        }
+    }
+
+    println!(
+        "INFO: copied the state of block {} with root {state_root}",
+        block.header.number
+    );
+
+    Ok(())
+}
+
+/// Fetches the nodes `sync` asks for from the `peer` until the trie is complete, returning the
+/// storage roots found in it.
+///
+/// The nodes come from the storage trie of `account` if it is given, otherwise from the state trie.
+async fn sync_trie<D: DB>(
+    peer: &impl Provider,
+    mut sync: TrieSync<D>,
+    account: Option<Address>,
+) -> anyhow::Result<Vec<(B256, B256)>> {
+    let mut storage_roots = Vec::new();
+
+    while !sync.is_complete() {
+        let hashes = sync.pending(MAX_TRIE_NODES);
+        let nodes: Vec<Option<Bytes>> = peer
+            .raw_request("umi_getTrieNodes".into(), (hashes.clone(), account))
+            .await?;
+        let nodes: Vec<_> = nodes.into_iter().flatten().map(Vec::from).collect();
+        ensure!(
+            !nodes.is_empty(),
+            "Peer does not have any of the {} trie nodes requested",
+            hashes.len()
+        );
+
+        sync.process(nodes)?;
+        storage_roots.extend(sync.take_storage_roots());
+    }
+
+    Ok(storage_roots)
+}
+
+/// Collects the fetched nodes of the storage trie of an account, reading the nodes it already has
+/// from the `storage` so that they are not fetched again.
+///
+/// The collected nodes are written along with the root of the trie once it is complete, as the
+/// storage of an account is only ever written that way.
+struct StorageTrieNodes<'a, S> {
+    storage: &'a S,
+    account: Address,
+    fetched: RwLock<HashMap<B256, Vec<u8>>>,
+}
+
+impl<'a, S> StorageTrieNodes<'a, S> {
+    fn new(storage: &'a S, account: Address) -> Self {
+        Self {
+            storage,
+            account,
+            fetched: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S: StorageTrieRepository + Sync> DB for StorageTrieNodes<'_, S> {
+    type Error = Infallible;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        let hash = B256::from_slice(key);
+        if let Some(node) = self.fetched.read().unwrap().get(&hash) {
+            return Ok(Some(node.clone()));
+        }
+
+        Ok(self.storage.trie_node(&self.account, hash).ok().flatten())
+    }
+
+    fn insert(&self, key: &[u8], value: Vec<u8>) -> Result<(), Self::Error> {
+        self.fetched
+            .write()
+            .unwrap()
+            .insert(B256::from_slice(key), value);
+        Ok(())
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), Self::Error> {
+        self.fetched.write().unwrap().remove(&B256::from_slice(key));
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,434 @@
+//! Streaming export of committed blocks to a message broker.
+//!
+//! When the `STREAM_URL` env var is set, every committed block is published as a JSON message
+//! holding the block with its full transactions and their receipts. Move events are part of the
+//! receipts, as they are emitted as EVM logs. The URL scheme selects the broker:
+//! * `kafka://host:port[,host:port...]` publishes to the Kafka topic named by `STREAM_TOPIC`,
+//! * `nats://host:port` publishes to the NATS JetStream subject named by `STREAM_TOPIC`.
+//!
+//! Delivery is at-least-once. The heights and hashes of the last blocks acknowledged by the broker
+//! are stored in the file named by `STREAM_CURSOR`, and publishing resumes after them on restart. A
+//! block may be published twice if the node stops between the acknowledgement and the cursor
+//! update, so every message is keyed by its block hash for consumers to deduplicate.
+//!
+//! The op-node may reorg blocks that are not yet safe. When a published block is no longer part of
+//! the chain, an `undo` message carrying its number and hash is published for it, newest first,
+//! before the blocks that replace it. Reorgs deeper than the [`REORG_DEPTH`] blocks remembered by
+//! the cursor are not detected.
+//!
+//! The node only keeps a bounded number of recent blocks and receipts in memory. If the stream
+//! falls further behind than that, the blocks it still has to publish are gone, so it publishes an
+//! `error` message with the number of the first missing block and stops.
+//!
+//! Each broker client is behind its own feature, `stream-kafka` or `stream-nats`.
+
+use {
+    anyhow::Context,
+    moved_api::schema::{BlockNumberOrTag, GetBlockResponse},
+    moved_app::{ApplicationReader, Dependencies},
+    moved_blockchain::receipt::TransactionReceipt,
+    moved_shared::primitives::B256,
+    serde::Serialize,
+    std::{fs, path::PathBuf, time::Duration},
+};
+
+const DEFAULT_TOPIC: &str = "op-move.blocks";
+const DEFAULT_CURSOR_PATH: &str = "stream-cursor";
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+/// Number of the most recently published blocks that are checked for being reorged.
+pub const REORG_DEPTH: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Kafka { brokers: String },
+    Nats { url: String },
+}
+
+impl Target {
+    pub fn parse(url: &str) -> anyhow::Result<Self> {
+        if let Some(brokers) = url.strip_prefix("kafka://") {
+            Ok(Self::Kafka {
+                brokers: brokers.to_owned(),
+            })
+        } else if url.starts_with("nats://") {
+            Ok(Self::Nats {
+                url: url.to_owned(),
+            })
+        } else {
+            anyhow::bail!("Unsupported stream URL {url}, expected a kafka:// or nats:// scheme")
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub target: Target,
+    pub topic: String,
+    pub cursor_path: PathBuf,
+}
+
+impl Config {
+    /// Reads the stream configuration from the env, returning `None` if streaming is disabled.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(url) = std::env::var("STREAM_URL") else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            target: Target::parse(&url)?,
+            topic: std::env::var("STREAM_TOPIC").unwrap_or_else(|_| DEFAULT_TOPIC.to_owned()),
+            cursor_path: std::env::var("STREAM_CURSOR")
+                .unwrap_or_else(|_| DEFAULT_CURSOR_PATH.to_owned())
+                .into(),
+        }))
+    }
+}
+
+/// A committed block as it is published to the stream.
+#[derive(Debug, Serialize)]
+pub struct BlockMessage {
+    pub block: GetBlockResponse,
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+/// A message of the stream, tagged by its `type`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StreamMessage {
+    Block(BlockMessage),
+    /// A previously published block that is no longer part of the chain.
+    Undo {
+        number: u64,
+        hash: B256,
+    },
+    /// The stream ends because block `number` can no longer be published.
+    Error {
+        number: u64,
+        message: String,
+    },
+}
+
+/// A block acknowledged by the broker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Published {
+    pub height: u64,
+    pub hash: B256,
+}
+
+/// Position of the stream, the last blocks that the broker has acknowledged, oldest first.
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    path: PathBuf,
+}
+
+impl Cursor {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn load(&self) -> anyhow::Result<Vec<Published>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).context(format!("Path: {:?}", self.path)),
+        };
+
+        contents
+            .lines()
+            .map(|line| {
+                let (height, hash) = line.split_once(' ').context("Invalid stream cursor")?;
+                Ok(Published {
+                    height: height.parse().context("Invalid stream cursor")?,
+                    hash: hash.parse().context("Invalid stream cursor")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Replaces the cursor atomically, so a crash never leaves it half written.
+    pub fn store(&self, published: &[Published]) -> anyhow::Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        let contents = published
+            .iter()
+            .map(|block| format!("{} {}\n", block.height, block.hash))
+            .collect::<String>();
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, &self.path).context(format!("Path: {:?}", self.path))
+    }
+}
+
+/// Counts the newest `published` blocks that the chain no longer contains, given the `hash` of
+/// the block it holds at each height.
+pub fn reorged_blocks(published: &[Published], hash: impl Fn(u64) -> Option<B256>) -> usize {
+    published
+        .iter()
+        .rev()
+        .take_while(|block| hash(block.height) != Some(block.hash))
+        .count()
+}
+
+/// Reads the block at `height` with its receipts, or `None` if it is not committed yet.
+///
+/// Fails if the block or one of its receipts was committed but is no longer held in memory.
+pub fn read_block(
+    app: &ApplicationReader<impl Dependencies>,
+    height: u64,
+) -> anyhow::Result<Option<BlockMessage>> {
+    let Some(block) = app.block_by_height(BlockNumberOrTag::Number(height), true) else {
+        anyhow::ensure!(
+            height > app.block_number(),
+            "Block {height} is no longer held by the node"
+        );
+        return Ok(None);
+    };
+    let receipts = block
+        .block
+        .transactions
+        .hashes()
+        .map(|hash| {
+            app.transaction_receipt(hash).with_context(|| {
+                format!(
+                    "Receipt of transaction {hash} in block {height} is no longer held by the node"
+                )
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(Some(BlockMessage {
+        block: block.into(),
+        receipts,
+    }))
+}
+
+enum Sink {
+    #[cfg(feature = "stream-kafka")]
+    Kafka {
+        producer: rdkafka::producer::FutureProducer,
+        topic: String,
+    },
+    #[cfg(feature = "stream-nats")]
+    Nats {
+        jetstream: async_nats::jetstream::Context,
+        subject: String,
+    },
+}
+
+impl Sink {
+    async fn connect(config: &Config) -> anyhow::Result<Self> {
+        match &config.target {
+            #[cfg(feature = "stream-kafka")]
+            Target::Kafka { brokers } => {
+                let producer = rdkafka::ClientConfig::new()
+                    .set("bootstrap.servers", brokers)
+                    .set("acks", "all")
+                    .set("enable.idempotence", "true")
+                    .create()?;
+                Ok(Self::Kafka {
+                    producer,
+                    topic: config.topic.clone(),
+                })
+            }
+            #[cfg(feature = "stream-nats")]
+            Target::Nats { url } => {
+                let client = async_nats::connect(url).await?;
+                Ok(Self::Nats {
+                    jetstream: async_nats::jetstream::new(client),
+                    subject: config.topic.clone(),
+                })
+            }
+            #[allow(unreachable_patterns)]
+            target => anyhow::bail!("Support for {target:?} is not enabled in this build"),
+        }
+    }
+
+    /// Publishes the `payload` and waits until the broker acknowledges it.
+    async fn publish(&self, key: &str, payload: Vec<u8>) -> anyhow::Result<()> {
+        match self {
+            #[cfg(feature = "stream-kafka")]
+            Self::Kafka { producer, topic } => {
+                let record = rdkafka::producer::FutureRecord::to(topic)
+                    .key(key)
+                    .payload(&payload);
+                producer
+                    .send(record, rdkafka::util::Timeout::Never)
+                    .await
+                    .map_err(|(e, _)| e)?;
+                Ok(())
+            }
+            #[cfg(feature = "stream-nats")]
+            Self::Nats { jetstream, subject } => {
+                // JetStream drops messages with a recently seen ID, deduplicating retries
+                let mut headers = async_nats::HeaderMap::new();
+                headers.insert("Nats-Msg-Id", key);
+                jetstream
+                    .publish_with_headers(subject.clone(), headers, payload.into())
+                    .await?
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Publishes committed blocks to the stream configured by `config`, until it falls behind the
+/// blocks held by the node.
+///
+/// Starts after the block in the stored cursor, or from genesis if there is none. A failed publish
+/// is retried until it succeeds, so the stream never skips a block.
+pub async fn run(config: Config, app: ApplicationReader<impl Dependencies>) {
+    let sink = Sink::connect(&config)
+        .await
+        .expect("Stream broker should be reachable");
+    let cursor = Cursor::new(config.cursor_path);
+    let mut published = cursor.load().expect("Stream cursor should be readable");
+    let mut next = published.last().map_or(0, |block| block.height + 1);
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let reorged = reorged_blocks(&published, |height| {
+            app.block_by_height(BlockNumberOrTag::Number(height), false)
+                .map(|block| block.block.header.hash)
+        });
+        for _ in 0..reorged {
+            let block = published.pop().expect("Reorged blocks are published");
+            let message = StreamMessage::Undo {
+                number: block.height,
+                hash: block.hash,
+            };
+            publish(&sink, &format!("undo-{}", block.hash), &message).await;
+            cursor
+                .store(&published)
+                .expect("Stream cursor should be writable");
+            next = block.height;
+        }
+
+        loop {
+            let message = match read_block(&app, next) {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(e) => {
+                    println!("ERROR: the stream stops before block {next}: {e:#}");
+                    let message = StreamMessage::Error {
+                        number: next,
+                        message: format!("{e:#}"),
+                    };
+                    publish(&sink, &format!("error-{next}"), &message).await;
+                    return;
+                }
+            };
+            let hash = message.block.block.header.hash;
+            publish(&sink, &hash.to_string(), &StreamMessage::Block(message)).await;
+
+            published.push(Published { height: next, hash });
+            if published.len() > REORG_DEPTH {
+                published.remove(0);
+            }
+            cursor
+                .store(&published)
+                .expect("Stream cursor should be writable");
+            next += 1;
+        }
+    }
+}
+
+/// Publishes the `message`, retrying until the broker acknowledges it.
+async fn publish(sink: &Sink, key: &str, message: &StreamMessage) {
+    let payload = serde_json::to_vec(message).expect("Message should serialize to JSON");
+
+    while let Err(e) = sink.publish(key, payload.clone()).await {
+        println!("WARN: failed to publish {key} to the stream: {e:?}");
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_is_selected_by_url_scheme() {
+        assert_eq!(
+            Target::parse("kafka://a:9092,b:9092").unwrap(),
+            Target::Kafka {
+                brokers: "a:9092,b:9092".to_owned()
+            }
+        );
+        assert_eq!(
+            Target::parse("nats://localhost:4222").unwrap(),
+            Target::Nats {
+                url: "nats://localhost:4222".to_owned()
+            }
+        );
+        assert!(Target::parse("http://localhost").is_err());
+    }
+
+    #[test]
+    fn test_cursor_resumes_from_stored_blocks() {
+        let path = std::env::temp_dir().join(format!("stream-cursor-{}", std::process::id()));
+        let cursor = Cursor::new(path.clone());
+        assert_eq!(cursor.load().unwrap(), Vec::new());
+        let published = vec![
+            Published {
+                height: 41,
+                hash: B256::repeat_byte(1),
+            },
+            Published {
+                height: 42,
+                hash: B256::repeat_byte(2),
+            },
+        ];
+
+        cursor.store(&published).unwrap();
+
+        assert_eq!(Cursor::new(path.clone()).load().unwrap(), published);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_reorged_blocks_are_counted_down_to_common_ancestor() {
+        let published: Vec<_> = (0..4)
+            .map(|height| Published {
+                height,
+                hash: B256::with_last_byte(height as u8),
+            })
+            .collect();
+        // Blocks 2 and 3 were replaced, and the new chain has no block 3 yet
+        let hash = |height| match height {
+            0 | 1 => Some(B256::with_last_byte(height as u8)),
+            2 => Some(B256::repeat_byte(0xff)),
+            _ => None,
+        };
+
+        assert_eq!(reorged_blocks(&published, hash), 2);
+        assert_eq!(reorged_blocks(&published[..2], hash), 0);
+    }
+
+    #[test]
+    fn test_undo_message_is_tagged() {
+        let message = StreamMessage::Undo {
+            number: 3,
+            hash: B256::ZERO,
+        };
+
+        let json = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(json["type"], "undo");
+        assert_eq!(json["number"], 3);
+    }
+
+    #[test]
+    fn test_error_message_is_tagged() {
+        let message = StreamMessage::Error {
+            number: 7,
+            message: "Block 7 is no longer held by the node".to_owned(),
+        };
+
+        let json = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["number"], 7);
+    }
+}
@@ -54,7 +54,7 @@ async fn test_evm_contracts() -> anyhow::Result<()> {
         }
 
         let block = ctx.get_block_by_number(3).await.unwrap();
-        let expected_hash = block.0.header.hash;
+        let expected_hash = block.block.header.hash;
 
         // 3. Call `getBlockHash` function in block with 4 <= height <= 259
         for block_height in 4..=259 {
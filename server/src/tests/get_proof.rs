@@ -11,7 +11,7 @@ async fn test_get_proof() -> anyhow::Result<()> {
     TestContext::run(|mut ctx| async move {
         let block_hash = ctx.produce_block().await.unwrap();
         let block = ctx.get_block_by_number(1).await.unwrap();
-        let state_root = block.0.header.state_root;
+        let state_root = block.block.header.state_root;
 
         let request = serde_json::json!({
             "jsonrpc": "2.0",
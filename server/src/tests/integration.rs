@@ -2,6 +2,7 @@ use {
     alloy::{
         contract::CallBuilder,
         dyn_abi::EventExt,
+        eips::BlockNumberOrTag,
         network::{EthereumWallet, TransactionBuilder},
         primitives::{address, utils::parse_ether, Address, B256, U256},
         providers::{Provider, ProviderBuilder},
@@ -33,6 +34,7 @@ use {
 
 const GETH_START_IN_SECS: u64 = 1; // 1 seconds to kick off L1 geth in dev mode
 const L2_RPC_URL: &str = "http://localhost:8545";
+const OP_GETH_RPC_URL: &str = "http://localhost:9545";
 const OP_BRIDGE_IN_SECS: u64 = 2 * 60; // Allow up to two minutes for bridging
 const OP_BRIDGE_POLL_IN_SECS: u64 = 5;
 const OP_START_IN_SECS: u64 = 20;
@@ -78,10 +80,11 @@ async fn test_on_ethereum() -> Result<()> {
 
     // 8. Start op-move to accept requests from the sequencer
     let op_move_runtime = Runtime::new()?;
-    op_move_runtime.spawn(crate::run(1_000));
+    op_move_runtime.spawn(crate::run(1_000, None));
 
     // 9. In separate threads run op-node, op-batcher, op-proposer
     let (op_node, op_batcher, op_proposer) = run_op()?;
+    check_genesis_hash().await?;
 
     // 10. Test out the OP bridge
     use_optimism_bridge().await?;
@@ -597,6 +600,23 @@ fn cleanup_processes(processes: Vec<Child>) -> Result<()> {
     Ok(())
 }
 
+/// The op-node rejects the chain unless op-move computes the same genesis block as op-geth.
+async fn check_genesis_hash() -> Result<()> {
+    let op_geth = ProviderBuilder::new().on_http(Url::parse(OP_GETH_RPC_URL)?);
+    let op_move = ProviderBuilder::new().on_http(Url::parse(L2_RPC_URL)?);
+
+    let expected = op_geth
+        .get_block_by_number(BlockNumberOrTag::Number(0))
+        .await?
+        .context("op-geth should have a genesis block")?;
+    let actual = op_move
+        .get_block_by_number(BlockNumberOrTag::Number(0))
+        .await?
+        .context("op-move should have a genesis block")?;
+    assert_eq!(actual.header.hash, expected.header.hash);
+    Ok(())
+}
+
 async fn get_code_size(address: Address) -> Result<usize> {
     let provider = ProviderBuilder::new().on_http(Url::parse(&var("L1_RPC_URL")?)?);
     let bytecode = provider.get_code_at(address).await?;
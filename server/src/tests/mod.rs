@@ -5,7 +5,8 @@ mod payload;
 mod test_context;
 
 use {
-    crate::{validate_jwt, Claims},
+    crate::{initialize_app, validate_jwt, Claims},
+    alloy::eips::BlockNumberOrTag,
     aptos_types::transaction::{EntryFunction, TransactionPayload},
     jsonwebtoken::{EncodingKey, Header},
     move_core_types::{
@@ -13,9 +14,24 @@ use {
         ident_str,
         language_storage::{ModuleId, StructTag, TypeTag},
     },
+    moved_genesis::config::GenesisConfig,
     std::time::SystemTime,
 };
 
+#[test]
+fn test_initialize_app_inserts_op_geth_compatible_genesis_block() {
+    let genesis_config = GenesisConfig::default();
+    let expected_header = moved_genesis::genesis_header(&genesis_config);
+
+    let (_app, reader) = initialize_app(genesis_config, moved_genesis::genesis_header);
+
+    let genesis_block = reader
+        .block_by_height(BlockNumberOrTag::Number(0), false)
+        .expect("Genesis block should be inserted");
+    assert_eq!(genesis_block.block.header.hash, expected_header.hash_slow());
+    assert_eq!(genesis_block.block.header.inner, expected_header);
+}
+
 #[tokio::test]
 async fn test_authorized_request() -> anyhow::Result<()> {
     std::env::set_var("JWT_SECRET", "00");
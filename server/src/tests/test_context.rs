@@ -1,13 +1,13 @@
 use {
-    crate::{create_genesis_block, dependency, initialize_app},
+    crate::{dependency, initialize_app, test_genesis_header},
     alloy::{
         consensus::transaction::TxEnvelope,
         eips::Encodable2718,
         primitives::{hex, B256},
     },
     moved_api::schema::{ForkchoiceUpdatedResponseV1, GetBlockResponse, GetPayloadResponseV3},
-    moved_app::{ApplicationReader, CommandQueue, Dependencies},
-    moved_blockchain::{payload::StatePayloadId, receipt::TransactionReceipt},
+    moved_app::{ApplicationReader, CommandQueue, DependenciesThreadSafe},
+    moved_blockchain::{block::BlockQueries, payload::StatePayloadId, receipt::TransactionReceipt},
     moved_genesis::config::GenesisConfig,
     serde::de::DeserializeOwned,
     std::future::Future,
@@ -30,12 +30,15 @@ impl TestContext {
         FU: FnMut(Self) -> F + Send,
     {
         let genesis_config = GenesisConfig::default();
-        let (mut app, reader) = initialize_app(genesis_config.clone());
+        let (mut app, reader) = initialize_app(genesis_config.clone(), test_genesis_header);
 
-        let genesis_block = create_genesis_block(&app.block_hash, &genesis_config);
+        let genesis_block = app
+            .block_queries
+            .latest(&app.storage_reader)
+            .unwrap()
+            .expect("Genesis block should be inserted");
         let head = genesis_block.hash;
         let timestamp = genesis_block.block.header.timestamp;
-        app.genesis_update(genesis_block);
 
         let (queue, state) = moved_app::create(&mut app, 10);
 
@@ -163,7 +166,7 @@ impl TestContext {
 pub async fn handle_request<T: DeserializeOwned>(
     request: serde_json::Value,
     queue: &CommandQueue,
-    app: ApplicationReader<impl Dependencies>,
+    app: ApplicationReader<impl DependenciesThreadSafe>,
 ) -> anyhow::Result<T> {
     let response = moved_api::request::handle(
         request.clone(),
@@ -178,6 +181,7 @@ pub async fn handle_request<T: DeserializeOwned>(
         anyhow::bail!("Error response from request {request:?}: {error:?}");
     }
 
-    let result: T = serde_json::from_value(response.result.expect("If not error then has result"))?;
+    let result = response.result.expect("If not error then has result");
+    let result: T = serde_json::from_value(serde_json::to_value(result)?)?;
     Ok(result)
 }
@@ -0,0 +1,638 @@
+//! Verification of the source code that deployed Move modules and EVM contracts were built from.
+//!
+//! When the `VERIFICATION_DIR` env var is set, an HTTP server rebuilds submitted sources and
+//! compares the result with the code in the latest state. It listens on `127.0.0.1:8547` unless
+//! another address is set in `VERIFICATION_LISTEN`:
+//! * `POST /verify/move` takes the files of a Move package by their path in the package, such as
+//!   `Move.toml` and `sources/coin.move`, along with its build options. Every module of the package
+//!   must match the deployed module of the same ID. The package can only depend on the bundled
+//!   framework packages, which are built from the sources this binary was built with.
+//! * `POST /verify/solidity` takes Solidity sources with the compiler settings of the standard JSON
+//!   input and the name of the contract deployed at `address`. It needs the path of a `solc` binary
+//!   in the `SOLC` env var.
+//!
+//! A verified source is stored as JSON in `VERIFICATION_DIR`, for explorers to read from
+//! `GET /verification/move/{address}/{module}` and `GET /verification/evm/{address}`. The trailing
+//! metadata that solc appends to the bytecode holds the hash of the sources, so a Solidity source
+//! that only differs in its metadata is stored as a partial match. Contracts with immutable
+//! variables do not match, since their deployed bytecode holds values set by the constructor.
+
+use {
+    alloy::{eips::BlockNumberOrTag, hex},
+    anyhow::Context,
+    aptos_framework::{path_in_crate, BuildOptions, BuiltPackage},
+    move_binary_format::CompiledModule,
+    move_core_types::{account_address::AccountAddress, language_storage::ModuleId},
+    moved_app::{ApplicationReader, DependenciesThreadSafe},
+    moved_shared::primitives::Address,
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeMap,
+        fs,
+        io::Write,
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+        path::{Component, Path, PathBuf},
+        process::{Command, Stdio},
+    },
+    tempfile::TempDir,
+    warp::{http::StatusCode, Filter, Rejection, Reply},
+};
+
+/// Framework packages that verified packages can depend on, by their name and their directory in
+/// the `aptos-framework` crate.
+const FRAMEWORK_PACKAGES: [(&str, &str); 5] = [
+    ("MoveStdlib", "move-stdlib"),
+    ("AptosStdlib", "aptos-stdlib"),
+    ("AptosFramework", "aptos-framework"),
+    ("AptosToken", "aptos-token"),
+    ("AptosTokenObjects", "aptos-token-objects"),
+];
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub addr: SocketAddr,
+    pub dir: PathBuf,
+    pub solc: Option<PathBuf>,
+}
+
+impl Config {
+    /// Reads the verification configuration from the env, returning `None` if it is disabled.
+    pub fn from_env() -> Option<Self> {
+        let dir = std::env::var("VERIFICATION_DIR").ok()?;
+        let solc = std::env::var("SOLC").ok().map(PathBuf::from);
+        let addr = match std::env::var("VERIFICATION_LISTEN") {
+            Ok(addr) => addr
+                .parse()
+                .expect("VERIFICATION_LISTEN should be a socket address"),
+            Err(_) => SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8547)),
+        };
+
+        Some(Self {
+            addr,
+            dir: dir.into(),
+            solc,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveSource {
+    /// Contents of the package files by their path relative to the package root.
+    pub files: BTreeMap<String, String>,
+    #[serde(default)]
+    pub build_options: MoveBuildOptions,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveBuildOptions {
+    #[serde(default)]
+    pub named_addresses: BTreeMap<String, AccountAddress>,
+    pub bytecode_version: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SoliditySource {
+    pub address: Address,
+    /// Name of the deployed contract, prefixed with its source path and a colon if the name is
+    /// not unique among the sources.
+    pub contract_name: String,
+    /// Contents of the Solidity files by their path.
+    pub sources: BTreeMap<String, String>,
+    /// The `settings` of the solc standard JSON input, such as `optimizer` and `evmVersion`.
+    #[serde(default)]
+    pub settings: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "language", rename_all = "camelCase")]
+pub enum Source {
+    Move(MoveSource),
+    Solidity(SoliditySource),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Match {
+    /// The rebuilt code is the same as the deployed one.
+    Full,
+    /// The rebuilt code is the same as the deployed one except for the compiler metadata.
+    Partial,
+}
+
+/// A stored verification of the code deployed at some address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Verification {
+    #[serde(rename = "match")]
+    pub match_kind: Match,
+    /// Height of the block whose state the rebuilt code was compared with.
+    pub block_number: u64,
+    pub source: Source,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The source is not acceptable, such as a file path outside the package.
+    InvalidSource(String),
+    /// Solidity sources cannot be verified as no `solc` binary is configured.
+    SolidityUnsupported,
+    Build(String),
+    NotDeployed(String),
+    Mismatch(String),
+    Internal(anyhow::Error),
+}
+
+impl Error {
+    fn message(&self) -> String {
+        match self {
+            Self::InvalidSource(message) => format!("Invalid source: {message}"),
+            Self::SolidityUnsupported => "Solidity verification is not enabled".into(),
+            Self::Build(message) => format!("Build failed: {message}"),
+            Self::NotDeployed(target) => format!("No code is deployed for {target}"),
+            Self::Mismatch(target) => format!("Built code differs from the deployed {target}"),
+            Self::Internal(e) => format!("Internal error: {e:?}"),
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::SolidityUnsupported => StatusCode::NOT_IMPLEMENTED,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(value: anyhow::Error) -> Self {
+        Self::Internal(value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyResponse {
+    #[serde(rename = "match")]
+    pub match_kind: Match,
+    pub block_number: u64,
+    /// The verified Move modules as `address::name`, or the verified EVM contract address.
+    pub verified: Vec<String>,
+}
+
+/// Stores verifications as JSON files, one per verified module or contract.
+#[derive(Debug, Clone)]
+pub struct Store {
+    dir: PathBuf,
+}
+
+impl Store {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn save(&self, key: &str, verification: &Verification) -> anyhow::Result<()> {
+        let path = self.dir.join(format!("{key}.json"));
+        let contents = serde_json::to_vec_pretty(verification)?;
+        fs::create_dir_all(&self.dir).context(format!("Path: {:?}", self.dir))?;
+
+        fs::write(&path, contents).context(format!("Path: {path:?}"))
+    }
+
+    pub fn load(&self, key: &str) -> anyhow::Result<Option<Verification>> {
+        let path = self.dir.join(format!("{key}.json"));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read(&path).context(format!("Path: {path:?}"))?;
+
+        Ok(Some(serde_json::from_slice(&contents)?))
+    }
+}
+
+fn module_key(id: &ModuleId) -> String {
+    format!("move-{}-{}", id.address().to_hex(), id.name())
+}
+
+fn contract_key(address: &Address) -> String {
+    format!("evm-{}", hex::encode(address))
+}
+
+pub fn verify_move(
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
+    store: &Store,
+    source: MoveSource,
+) -> Result<VerifyResponse, Error> {
+    let block_number = app.block_number();
+    let package_dir = write_package(&source.files)?;
+    let options = BuildOptions {
+        named_addresses: source.build_options.named_addresses.clone(),
+        bytecode_version: source.build_options.bytecode_version,
+        ..BuildOptions::default()
+    };
+    let built = BuiltPackage::build(package_dir.path().to_path_buf(), options)
+        .map_err(|e| Error::Build(format!("{e:#}")))?;
+
+    let mut ids = Vec::new();
+    for code in built.extract_code() {
+        let id = CompiledModule::deserialize(&code)
+            .context("Built module should deserialize")?
+            .self_id();
+        let deployed = app
+            .module_by_height(&id, BlockNumberOrTag::Number(block_number))
+            .ok_or_else(|| Error::NotDeployed(id.to_string()))?;
+        if deployed != code {
+            return Err(Error::Mismatch(id.to_string()));
+        }
+        ids.push(id);
+    }
+    if ids.is_empty() {
+        return Err(Error::InvalidSource("The package has no modules".into()));
+    }
+
+    let verification = Verification {
+        match_kind: Match::Full,
+        block_number,
+        source: Source::Move(source),
+    };
+    for id in &ids {
+        store.save(&module_key(id), &verification)?;
+    }
+
+    Ok(VerifyResponse {
+        match_kind: Match::Full,
+        block_number,
+        verified: ids.iter().map(ToString::to_string).collect(),
+    })
+}
+
+/// Writes the package files into a new temporary directory, which is removed once dropped.
+///
+/// The dependencies of the package are pointed at the bundled framework.
+fn write_package(files: &BTreeMap<String, String>) -> Result<TempDir, Error> {
+    for path in files.keys() {
+        if !is_package_path(Path::new(path)) {
+            return Err(Error::InvalidSource(format!(
+                "{path} is not a path inside the package"
+            )));
+        }
+    }
+    let manifest = files
+        .get("Move.toml")
+        .ok_or_else(|| Error::InvalidSource("Move.toml is missing".into()))?;
+    let manifest = framework_manifest(manifest)?;
+
+    let dir = tempfile::tempdir().context("Creating the package directory failed")?;
+    for (path, contents) in files {
+        let contents = match path.as_str() {
+            "Move.toml" => &manifest,
+            _ => contents,
+        };
+        let path = dir.path().join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!("Path: {parent:?}"))?;
+        }
+        fs::write(&path, contents).context(format!("Path: {path:?}"))?;
+    }
+
+    Ok(dir)
+}
+
+/// Replaces the dependencies of the package `manifest` with the bundled framework packages of the
+/// same name, failing if it depends on any other package.
+///
+/// Otherwise, a package could make the server read its local files or fetch any git repository.
+fn framework_manifest(manifest: &str) -> Result<String, Error> {
+    let mut manifest: toml::Table = toml::from_str(manifest)
+        .map_err(|e| Error::InvalidSource(format!("Move.toml is not valid: {e}")))?;
+
+    for section in ["dependencies", "dev-dependencies"] {
+        let Some(dependencies) = manifest.get_mut(section) else {
+            continue;
+        };
+        let dependencies = dependencies.as_table_mut().ok_or_else(|| {
+            Error::InvalidSource(format!("{section} of Move.toml is not a table"))
+        })?;
+
+        for (name, dependency) in dependencies.iter_mut() {
+            let (_, dir) = FRAMEWORK_PACKAGES
+                .iter()
+                .find(|(package, _)| package == name)
+                .ok_or_else(|| {
+                    Error::InvalidSource(format!("{name} is not a bundled framework package"))
+                })?;
+            let local = path_in_crate(*dir).to_string_lossy().into_owned();
+            *dependency = toml::Value::Table(toml::Table::from_iter([(
+                "local".to_string(),
+                toml::Value::String(local),
+            )]));
+        }
+    }
+
+    Ok(manifest.to_string())
+}
+
+fn is_package_path(path: &Path) -> bool {
+    path.components().next().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+pub fn verify_solidity(
+    app: &ApplicationReader<impl DependenciesThreadSafe>,
+    store: &Store,
+    solc: Option<&Path>,
+    source: SoliditySource,
+) -> Result<VerifyResponse, Error> {
+    let solc = solc.ok_or(Error::SolidityUnsupported)?;
+    let block_number = app.block_number();
+    let deployed = app
+        .evm_code_by_height(source.address, BlockNumberOrTag::Number(block_number))
+        .ok_or_else(|| Error::NotDeployed(source.address.to_string()))?;
+    let built = compile_solidity(solc, &source)?;
+
+    let match_kind = if built == deployed {
+        Match::Full
+    } else if strip_metadata(&built) == strip_metadata(&deployed) {
+        Match::Partial
+    } else {
+        return Err(Error::Mismatch(source.address.to_string()));
+    };
+    let key = contract_key(&source.address);
+    let address = source.address.to_string();
+    let verification = Verification {
+        match_kind,
+        block_number,
+        source: Source::Solidity(source),
+    };
+    store.save(&key, &verification)?;
+
+    Ok(VerifyResponse {
+        match_kind,
+        block_number,
+        verified: vec![address],
+    })
+}
+
+/// Compiles the sources with solc, returning the deployed bytecode of the named contract.
+fn compile_solidity(solc: &Path, source: &SoliditySource) -> Result<Vec<u8>, Error> {
+    let mut settings = source.settings.clone();
+    settings.insert(
+        "outputSelection".into(),
+        serde_json::json!({ "*": { "*": ["evm.deployedBytecode.object"] } }),
+    );
+    let sources: serde_json::Map<_, _> = source
+        .sources
+        .iter()
+        .map(|(path, content)| (path.clone(), serde_json::json!({ "content": content })))
+        .collect();
+    let input = serde_json::json!({
+        "language": "Solidity",
+        "sources": sources,
+        "settings": settings,
+    });
+
+    let mut child = Command::new(solc)
+        .arg("--standard-json")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .context(format!("Path: {solc:?}"))?;
+    child
+        .stdin
+        .take()
+        .expect("Stdin of solc should be piped")
+        .write_all(input.to_string().as_bytes())
+        .context("Writing solc input failed")?;
+    let output = child.wait_with_output().context("solc did not run")?;
+    let output: serde_json::Value =
+        serde_json::from_slice(&output.stdout).context("solc output should be JSON")?;
+
+    let errors: Vec<_> = output["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|error| error["severity"] == "error")
+        .filter_map(|error| error["formattedMessage"].as_str())
+        .collect();
+    if !errors.is_empty() {
+        return Err(Error::Build(errors.join("\n")));
+    }
+
+    let (path, name) = match source.contract_name.rsplit_once(':') {
+        Some((path, name)) => (Some(path), name),
+        None => (None, source.contract_name.as_str()),
+    };
+    let mut found = output["contracts"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(contract_path, _)| path.is_none_or(|path| path == contract_path.as_str()))
+        .filter_map(|(_, contracts)| contracts.get(name));
+    let contract = found.next().ok_or_else(|| {
+        Error::InvalidSource(format!(
+            "No contract {} in the sources",
+            source.contract_name
+        ))
+    })?;
+    if found.next().is_some() {
+        return Err(Error::InvalidSource(format!(
+            "More than one contract is named {name}, prefix it with its source path"
+        )));
+    }
+    let bytecode = contract["evm"]["deployedBytecode"]["object"]
+        .as_str()
+        .unwrap_or_default();
+
+    hex::decode(bytecode).map_err(|e| Error::Build(format!("Unlinked bytecode: {e}")))
+}
+
+/// Removes the CBOR encoded metadata that solc appends to the bytecode, which ends with its length
+/// as a big-endian `u16`.
+fn strip_metadata(code: &[u8]) -> &[u8] {
+    let Some((rest, len)) = code.split_last_chunk::<2>() else {
+        return code;
+    };
+    let len = u16::from_be_bytes(*len) as usize;
+    if len > rest.len() {
+        return code;
+    }
+
+    &rest[..rest.len() - len]
+}
+
+pub fn route(
+    app_reader: ApplicationReader<impl DependenciesThreadSafe>,
+    config: Config,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let store = Store::new(config.dir);
+    let context = {
+        let store = store.clone();
+        warp::any().map(move || (app_reader.clone(), store.clone(), config.solc.clone()))
+    };
+
+    let verify_move_route = warp::path!("verify" / "move")
+        .and(warp::post())
+        .and(context.clone())
+        .and(warp::body::json())
+        .and_then(|(app, store, _), source: MoveSource| {
+            respond(move || verify_move(&app, &store, source))
+        });
+    let verify_solidity_route = warp::path!("verify" / "solidity")
+        .and(warp::post())
+        .and(context)
+        .and(warp::body::json())
+        .and_then(|(app, store, solc), source: SoliditySource| {
+            respond(move || verify_solidity(&app, &store, solc.as_deref(), source))
+        });
+
+    let store = warp::any().map(move || store.clone());
+    let module_route = warp::path!("verification" / "move" / String / String)
+        .and(warp::get())
+        .and(store.clone())
+        .map(|address: String, name: String, store: Store| {
+            let key = AccountAddress::from_hex_literal(&address)
+                .ok()
+                .zip(name.parse().ok())
+                .map(|(address, name)| module_key(&ModuleId::new(address, name)));
+            stored(&store, key)
+        });
+    let contract_route = warp::path!("verification" / "evm" / String)
+        .and(warp::get())
+        .and(store)
+        .map(|address: String, store: Store| {
+            let key = address.parse().ok().map(|address| contract_key(&address));
+            stored(&store, key)
+        });
+
+    verify_move_route
+        .or(verify_solidity_route)
+        .or(module_route)
+        .or(contract_route)
+        .with(warp::cors().allow_any_origin())
+}
+
+/// Runs a verification, which compiles and reads the state, outside of the async runtime.
+async fn respond(
+    verify: impl FnOnce() -> Result<VerifyResponse, Error> + Send + 'static,
+) -> Result<warp::reply::Response, Rejection> {
+    let result = tokio::task::spawn_blocking(verify)
+        .await
+        .unwrap_or_else(|e| Err(Error::Internal(e.into())));
+
+    Ok(match result {
+        Ok(response) => warp::reply::json(&response).into_response(),
+        Err(e) => {
+            let body = warp::reply::json(&serde_json::json!({ "error": e.message() }));
+            warp::reply::with_status(body, e.status()).into_response()
+        }
+    })
+}
+
+fn stored(store: &Store, key: Option<String>) -> warp::reply::Response {
+    let Some(key) = key else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    match store.load(&key) {
+        Ok(Some(verification)) => warp::reply::json(&verification).into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            println!("WARN: failed to read verification {key}: {e:?}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::primitives::address};
+
+    #[test]
+    fn test_strip_metadata_removes_trailing_cbor() {
+        let code = [0x60, 0x80, 0xa1, 0x64, 0x00, 0x02];
+
+        assert_eq!(strip_metadata(&code), [0x60, 0x80]);
+    }
+
+    #[test]
+    fn test_strip_metadata_keeps_code_shorter_than_its_metadata_length() {
+        let code = [0x60, 0xff, 0xff];
+
+        assert_eq!(strip_metadata(&code), code);
+    }
+
+    #[test]
+    fn test_package_paths_must_stay_inside_package() {
+        assert!(is_package_path(Path::new("sources/coin.move")));
+        assert!(is_package_path(Path::new("Move.toml")));
+        assert!(!is_package_path(Path::new("../Move.toml")));
+        assert!(!is_package_path(Path::new("/etc/passwd")));
+        assert!(!is_package_path(Path::new("")));
+    }
+
+    #[test]
+    fn test_framework_dependencies_point_at_bundled_framework() {
+        let manifest = r#"
+            [package]
+            name = "Coin"
+
+            [dependencies.AptosFramework]
+            git = "https://github.com/aptos-labs/aptos-core.git"
+            rev = "mainnet"
+            subdir = "aptos-move/framework/aptos-framework"
+        "#;
+
+        let manifest: toml::Table = toml::from_str(&framework_manifest(manifest).unwrap()).unwrap();
+
+        let dependency = &manifest["dependencies"]["AptosFramework"];
+        let local = path_in_crate("aptos-framework")
+            .to_string_lossy()
+            .into_owned();
+        assert_eq!(dependency.get("local"), Some(&toml::Value::String(local)));
+        assert_eq!(dependency.get("git"), None);
+    }
+
+    #[test]
+    fn test_dependencies_other_than_framework_are_rejected() {
+        for manifest in [
+            "[dependencies]\nSecrets = { local = \"/etc\" }",
+            "[dev-dependencies]\nLib = { git = \"https://example.com/lib.git\", rev = \"main\" }",
+        ] {
+            let result = framework_manifest(manifest);
+
+            assert!(
+                matches!(result, Err(Error::InvalidSource(_))),
+                "{manifest} is accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn test_store_loads_saved_verification() {
+        let dir = TempDir::new().unwrap();
+        let store = Store::new(dir.path().join("verifications"));
+        let contract = address!("4200000000000000000000000000000000000042");
+        let verification = Verification {
+            match_kind: Match::Partial,
+            block_number: 7,
+            source: Source::Solidity(SoliditySource {
+                address: contract,
+                contract_name: "Token".into(),
+                sources: BTreeMap::from([("Token.sol".into(), "contract Token {}".into())]),
+                settings: Default::default(),
+            }),
+        };
+
+        store.save(&contract_key(&contract), &verification).unwrap();
+
+        assert_eq!(
+            store.load(&contract_key(&contract)).unwrap(),
+            Some(verification)
+        );
+        assert_eq!(store.load(&contract_key(&Address::ZERO)).unwrap(), None);
+    }
+}
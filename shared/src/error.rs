@@ -9,9 +9,11 @@
 //! [`Display`] trait, they serve only an informative purpose and a human-readable representation.   
 
 use {
-    alloy::consensus::TxType,
+    crate::primitives::U256,
+    alloy::{consensus::TxType, eips::BlockNumberOrTag},
     move_binary_format::errors::{PartialVMError, VMError},
-    move_core_types::language_storage::TypeTag,
+    move_core_types::language_storage::{ModuleId, TypeTag},
+    std::fmt,
     thiserror::Error,
 };
 
@@ -76,6 +78,54 @@ pub enum UserError {
     L2ContractCallFailure,
     #[error("EVM contract creation failure")]
     EvmContractCreationFailure,
+    /// The EVM execution failed, with its output being the revert data if there is any.
+    #[error("Execution reverted")]
+    EvmReverted(Vec<u8>),
+    /// A Move module aborted the execution.
+    #[error("{0}")]
+    MoveAbort(MoveAbort),
+    /// The deposit breaks the rules for deposited transactions, so it fails before execution.
+    #[error("Invalid deposit: {0}")]
+    InvalidDeposit(InvalidDepositCause),
+    /// The block to execute on is not known, such as a `safe` block before any is marked.
+    #[error("Block not found: {0}")]
+    BlockNotFound(BlockNumberOrTag),
+}
+
+/// The rule of deposited transactions that a deposit breaks.
+///
+/// Deposits are derived from L1 and cannot be dropped, so breaking one of these rules fails the
+/// deposit in the same way that op-geth does, rather than rejecting it.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum InvalidDepositCause {
+    #[error("system transactions are not supported since Regolith")]
+    SystemTransaction,
+    #[error("intrinsic gas too low: given={given} required={required}")]
+    InsufficientIntrinsicGas { required: u64, given: u64 },
+}
+
+/// An abort raised by a Move module, with the meaning of its code if the module describes it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAbort {
+    pub module: ModuleId,
+    pub code: u64,
+    /// Name of the error constant of the code, from the error map of the module.
+    pub reason_name: Option<String>,
+    /// Doc comment of the error constant of the code, from the error map of the module.
+    pub description: Option<String>,
+    /// Output of the EVM execution that failed before the abort, such as its revert data. Aborts
+    /// that follow a failed EVM call usually happen because of it.
+    pub evm_output: Option<Vec<u8>>,
+}
+
+impl fmt::Display for MoveAbort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let module = self.module.short_str_lossless();
+        match &self.reason_name {
+            Some(name) => write!(f, "{name} in {module}"),
+            None => write!(f, "Abort code {} in {module}", self.code),
+        }
+    }
 }
 
 /// The error caused by invalid transaction input parameter.
@@ -107,6 +157,16 @@ pub enum InvalidTransactionCause {
     DisallowedEntryFunctionType(TypeTag),
     #[error("Insufficient intrinsic gas")]
     InsufficientIntrinsicGas,
+    #[error("Gas limit too high: given={given} max={max}")]
+    GasLimitTooHigh { max: u64, given: u64 },
+    #[error("Insufficient funds: balance={balance} cost={cost}")]
+    InsufficientFunds { balance: U256, cost: U256 },
+    #[error("Max fee per gas below base fee: given={given} base_fee={base_fee}")]
+    FeeBelowBaseFee { base_fee: u128, given: u128 },
+    #[error("Max fee per gas below minimum: given={given} min={min}")]
+    FeeBelowMinimum { min: u128, given: u128 },
+    #[error("Priority fee per gas below minimum: given={given} min={min}")]
+    PriorityFeeBelowMinimum { min: u128, given: u128 },
     #[error("String must be UTF-8 encoded bytes")]
     InvalidString,
     #[error("Option is a Move Vector with 0 or 1 elements")]
@@ -117,6 +177,13 @@ pub enum InvalidTransactionCause {
     FailedToPayL1Fee,
     #[error("Failed to pay L2 fee")]
     FailedToPayL2Fee,
+    #[error("Executing message does not match an initiating message")]
+    InvalidExecutingMessage,
+    #[error("Executing messages from other chains cannot be validated")]
+    UnvalidatedExecutingMessage,
+    /// The function called by a view request is missing or not marked `#[view]`.
+    #[error("Function is not a view function")]
+    NotViewFunction,
 }
 
 impl From<InvalidTransactionCause> for Error {
@@ -283,6 +350,54 @@ mod tests {
         InvalidTransactionCause::InsufficientIntrinsicGas,
         "Insufficient intrinsic gas"
     )]
+    #[test_case(
+        InvalidTransactionCause::GasLimitTooHigh { max: 30_000_000, given: 30_000_001 },
+        "Gas limit too high: given=30000001 max=30000000"
+    )]
+    #[test_case(
+        InvalidTransactionCause::InsufficientFunds { balance: U256::from(1), cost: U256::from(2) },
+        "Insufficient funds: balance=1 cost=2"
+    )]
+    #[test_case(
+        InvalidTransactionCause::FeeBelowBaseFee { base_fee: 7, given: 6 },
+        "Max fee per gas below base fee: given=6 base_fee=7"
+    )]
+    #[test_case(
+        InvalidTransactionCause::FeeBelowMinimum { min: 7, given: 6 },
+        "Max fee per gas below minimum: given=6 min=7"
+    )]
+    #[test_case(UserError::EvmReverted(vec![0xab]), "Execution reverted")]
+    #[test_case(
+        UserError::BlockNotFound(BlockNumberOrTag::Safe),
+        "Block not found: safe"
+    )]
+    #[test_case(
+        UserError::InvalidDeposit(InvalidDepositCause::InsufficientIntrinsicGas {
+            required: 21_000,
+            given: 1,
+        }),
+        "Invalid deposit: intrinsic gas too low: given=1 required=21000"
+    )]
+    #[test_case(
+        UserError::MoveAbort(MoveAbort {
+            module: ModuleId::new(AccountAddress::ONE, "coin".parse().unwrap()),
+            code: 0x10006,
+            reason_name: Some("EINSUFFICIENT_BALANCE".into()),
+            description: Some("Not enough coins to complete transaction".into()),
+            evm_output: None,
+        }),
+        "EINSUFFICIENT_BALANCE in 0x1::coin"
+    )]
+    #[test_case(
+        UserError::MoveAbort(MoveAbort {
+            module: ModuleId::new(AccountAddress::ONE, "coin".parse().unwrap()),
+            code: 0x10006,
+            reason_name: None,
+            description: None,
+            evm_output: None,
+        }),
+        "Abort code 65542 in 0x1::coin"
+    )]
     fn test_error_converts_and_displays(actual: impl Into<Error>, expected: impl Into<String>) {
         let actual = actual.into().to_string();
         let expected = expected.into();
@@ -96,6 +96,18 @@ pub fn with_rpc_logs(
     receipt: &OpReceiptEnvelope,
     logs: Vec<alloy::rpc::types::Log>,
 ) -> OpReceiptEnvelope<alloy::rpc::types::Log> {
+    with_logs(receipt, logs)
+}
+
+/// Inverse of [`with_rpc_logs`], recovering the receipt that is a leaf of the receipts trie.
+pub fn with_primitive_logs(
+    receipt: &OpReceiptEnvelope<alloy::rpc::types::Log>,
+) -> OpReceiptEnvelope {
+    let logs = receipt.logs().iter().map(|log| log.inner.clone()).collect();
+    with_logs(receipt, logs)
+}
+
+fn with_logs<T, U>(receipt: &OpReceiptEnvelope<T>, logs: Vec<U>) -> OpReceiptEnvelope<U> {
     match receipt {
         OpReceiptEnvelope::Legacy(receipt_with_bloom) => {
             OpReceiptEnvelope::Legacy(ReceiptWithBloom {
@@ -0,0 +1,68 @@
+use {
+    aptos_types::state_store::state_key::StateKey,
+    criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main},
+    move_core_types::{account_address::AccountAddress, ident_str, language_storage::StructTag},
+    moved_shared::primitives::KeyHashable,
+    moved_state::nodes::{DEFAULT_KEY_HASH_CACHE_CAPACITY, KeyHashCache, TreeKey},
+    std::hint::black_box,
+};
+
+/// Number of distinct keys hashed in each iteration, which all fit in a default cache.
+const KEYS: usize = 1000;
+
+fn resource_keys() -> Vec<TreeKey> {
+    let tag = StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("fungible_asset").into(),
+        name: ident_str!("FungibleStore").into(),
+        type_args: Vec::new(),
+    };
+
+    (0..KEYS as u64)
+        .map(|i| {
+            let address = AccountAddress::from_hex_literal(&format!("{i:#x}")).unwrap();
+            TreeKey::StateKey(StateKey::resource(&address, &tag).unwrap())
+        })
+        .collect()
+}
+
+fn bench_key_hash(c: &mut Criterion) {
+    let keys = resource_keys();
+    let mut group = c.benchmark_group("Hash trie keys");
+    group.throughput(Throughput::Elements(KEYS as u64));
+
+    group.bench_function("computed", |b| {
+        b.iter(|| {
+            for key in &keys {
+                black_box(key.key_hash());
+            }
+        })
+    });
+    group.bench_function("cached", |b| {
+        let cache = KeyHashCache::new(DEFAULT_KEY_HASH_CACHE_CAPACITY);
+        for key in &keys {
+            cache.key_hash(key);
+        }
+
+        b.iter(|| {
+            for key in &keys {
+                black_box(cache.key_hash(key));
+            }
+        })
+    });
+    group.bench_function("cache misses", |b| {
+        b.iter_batched(
+            || KeyHashCache::new(DEFAULT_KEY_HASH_CACHE_CAPACITY),
+            |cache| {
+                for key in &keys {
+                    black_box(cache.key_hash(key));
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_key_hash);
+criterion_main!(benches);
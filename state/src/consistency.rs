@@ -0,0 +1,158 @@
+//! Checks that the merkle trie of a [`State`] holds the same values as its resolver.
+//!
+//! The trie and the resolver are written separately when changes are applied, so a bug in either
+//! makes them diverge silently. It only shows as a state root mismatch once the diverging value is
+//! read and written back, which can be many blocks later.
+
+use {
+    crate::{State, module_tree_entry, resource_tree_entry},
+    eth_trie::{EthTrie, Trie, TrieError},
+    move_core_types::{
+        account_address::AccountAddress,
+        effects::ChangeSet,
+        language_storage::{ModuleId, StructTag},
+    },
+    move_vm_types::resolver::{ModuleResolver, ResourceResolver},
+    moved_shared::primitives::{B256, KeyHashable},
+    std::collections::BTreeSet,
+};
+
+/// A key of the state as the resolver reads it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ResolverKey {
+    Module(ModuleId),
+    Resource(AccountAddress, StructTag),
+}
+
+/// Collects the keys that `changes` write to.
+pub fn touched_keys(changes: &ChangeSet) -> BTreeSet<ResolverKey> {
+    changes
+        .accounts()
+        .iter()
+        .flat_map(|(address, account)| {
+            let modules = account
+                .modules()
+                .keys()
+                .map(|name| ResolverKey::Module(ModuleId::new(*address, name.clone())));
+            let resources = account
+                .resources()
+                .keys()
+                .map(|tag| ResolverKey::Resource(*address, tag.clone()));
+            modules.chain(resources)
+        })
+        .collect()
+}
+
+/// Returns the `keys` whose value in the state trie of `state` differs from the one its resolver
+/// returns.
+pub fn inconsistent_keys<'a>(
+    state: &impl State,
+    keys: impl IntoIterator<Item = &'a ResolverKey>,
+) -> Result<Vec<ResolverKey>, TrieError> {
+    let root = state.state_root();
+    let trie = if root == B256::ZERO {
+        EthTrie::new(state.db())
+    } else {
+        EthTrie::from(state.db(), root)?
+    };
+    let resolver = state.resolver();
+    let mut inconsistent = Vec::new();
+
+    for key in keys {
+        // Reading from the resolver fails only on a broken storage, which is a divergence too
+        let (tree_key, tree_value) = match key {
+            ResolverKey::Module(id) => match resolver.get_module(id) {
+                Ok(value) => module_tree_entry(id.address(), id.name(), value),
+                Err(_) => {
+                    inconsistent.push(key.clone());
+                    continue;
+                }
+            },
+            ResolverKey::Resource(address, tag) => {
+                match resolver.get_resource_bytes_with_metadata_and_layout(address, tag, &[], None)
+                {
+                    Ok((value, _)) => resource_tree_entry(address, tag, value),
+                    Err(_) => {
+                        inconsistent.push(key.clone());
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let expected = tree_value.serialize();
+        let actual = trie.get(tree_key.key_hash().0.as_slice())?;
+        // An empty value is never stored, as inserting one removes the key from the trie
+        if actual.as_deref().unwrap_or_default() != expected.as_ref() {
+            inconsistent.push(key.clone());
+        }
+    }
+
+    Ok(inconsistent)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{InMemoryState, InsertChangeSetIntoMerkleTrie},
+        bytes::Bytes,
+        move_core_types::{effects::Op, ident_str},
+        std::sync::Arc,
+    };
+
+    fn resource_tag() -> StructTag {
+        StructTag {
+            address: AccountAddress::ONE,
+            module: ident_str!("counter").into(),
+            name: ident_str!("Counter").into(),
+            type_args: Vec::new(),
+        }
+    }
+
+    fn changes(value: u8) -> ChangeSet {
+        let mut changes = ChangeSet::new();
+        changes
+            .add_resource_op(
+                AccountAddress::ONE,
+                resource_tag(),
+                Op::New(Bytes::from(vec![value])),
+            )
+            .unwrap();
+        changes
+    }
+
+    #[test]
+    fn test_applied_changes_are_consistent() {
+        let mut state = InMemoryState::default();
+        let changes = changes(1);
+
+        state.apply(changes.clone()).unwrap();
+        let keys = touched_keys(&changes);
+
+        assert_eq!(inconsistent_keys(&state, &keys).unwrap(), []);
+    }
+
+    #[test]
+    fn test_trie_written_apart_from_resolver_is_inconsistent() {
+        let db = InMemoryState::create_db();
+        let mut state = InMemoryState::new(db.clone());
+        state.apply(changes(1)).unwrap();
+
+        // Writes a different value into the trie only, at the root the state is at
+        let mut trie = EthTrie::from(Arc::clone(&db), state.state_root()).unwrap();
+        let root = trie
+            .insert_change_set_into_merkle_trie(&changes(2))
+            .unwrap();
+        let diverged = InMemoryState {
+            current_state_root: Some(root),
+            ..state
+        };
+        let keys = touched_keys(&changes(2));
+
+        assert_eq!(
+            inconsistent_keys(&diverged, &keys).unwrap(),
+            [ResolverKey::Resource(AccountAddress::ONE, resource_tag())]
+        );
+    }
+}
@@ -1,10 +1,16 @@
+pub mod consistency;
 pub mod nodes;
+pub mod preimage;
+pub mod resource_index;
+pub mod witness;
 
 use {
     alloy::hex::FromHex,
-    aptos_types::state_store::{state_key::StateKey, state_value::StateValue},
+    aptos_types::state_store::{
+        state_key::StateKey, state_value::StateValue, table::TableHandle as StateTableHandle,
+    },
     bytes::Bytes,
-    eth_trie::{DB, EthTrie, MemoryDB, Trie, TrieError},
+    eth_trie::{DB, EthTrie, MemDBError, Trie, TrieError},
     move_binary_format::errors::{Location, PartialVMError, VMResult},
     move_core_types::{
         account_address::AccountAddress,
@@ -12,13 +18,18 @@ use {
         identifier::IdentStr,
         language_storage::{ModuleId, StructTag},
     },
-    move_table_extension::{TableChangeSet, TableResolver},
+    move_table_extension::{TableChangeSet, TableHandle, TableResolver},
     move_vm_test_utils::InMemoryStorage,
     move_vm_types::{code::ModuleBytesStorage, resolver::MoveResolver},
     moved_evm_ext::{EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE, type_utils::ACCOUNT_INFO_PREFIX},
-    moved_shared::primitives::{Address, B256, KeyHashable},
-    nodes::{TreeKey, TreeValue},
-    std::{collections::HashMap, fmt::Debug, sync::Arc},
+    moved_shared::primitives::{Address, B256, KeyHash, KeyHashable},
+    nodes::{KeyHashCache, TreeKey, TreeValue},
+    resource_index::InMemoryResourceIndex,
+    std::{
+        collections::HashMap,
+        fmt::Debug,
+        sync::{Arc, RwLock},
+    },
 };
 
 // TODO: Should change `State` interface to return `Result`.
@@ -68,6 +79,8 @@ pub struct InMemoryState {
     resolver: InMemoryStorage,
     db: Arc<InMemoryTrieDb>,
     current_state_root: Option<B256>,
+    key_hashes: KeyHashCache,
+    resource_index: InMemoryResourceIndex,
 }
 
 impl Default for InMemoryState {
@@ -76,15 +89,60 @@ impl Default for InMemoryState {
     }
 }
 
-pub type InMemoryTrieDb = MemoryDB;
+/// The nodes of the state trie, kept in memory by their hashes.
+///
+/// Like a [`eth_trie::MemoryDB`] that is not light, it ignores the removals of a trie commit, so
+/// the nodes of past state roots stay readable. They are only dropped by [`Self::retain`], once the
+/// state they belong to is no longer needed.
+#[derive(Debug, Default)]
+pub struct InMemoryTrieDb {
+    nodes: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl InMemoryTrieDb {
+    pub fn len(&self) -> usize {
+        self.nodes.read().unwrap().len()
+    }
 
-impl InMemoryState {
-    // Per `eth-trie` docs: If "light" is true, the data is deleted from the database
-    // at the time of submission.
-    const IS_LIGHT: bool = false;
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every node whose hash `keep` rejects, returning how many were removed.
+    pub fn retain(&self, keep: impl Fn(B256) -> bool) -> usize {
+        let mut nodes = self.nodes.write().unwrap();
+        let len = nodes.len();
+        nodes.retain(|hash, _| keep(B256::from_slice(hash)));
+
+        len - nodes.len()
+    }
+}
+
+impl DB for InMemoryTrieDb {
+    type Error = MemDBError;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.nodes.read().unwrap().get(key).cloned())
+    }
 
+    fn insert(&self, key: &[u8], value: Vec<u8>) -> Result<(), Self::Error> {
+        self.nodes.write().unwrap().insert(key.to_vec(), value);
+        Ok(())
+    }
+
+    fn remove(&self, _key: &[u8]) -> Result<(), Self::Error> {
+        // A commit removes the nodes it replaced, which past state roots still point to
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl InMemoryState {
     pub fn create_db() -> Arc<InMemoryTrieDb> {
-        Arc::new(MemoryDB::new(Self::IS_LIGHT))
+        Arc::new(InMemoryTrieDb::default())
     }
 
     pub fn new(db: Arc<InMemoryTrieDb>) -> Self {
@@ -92,9 +150,18 @@ impl InMemoryState {
             resolver: InMemoryStorage::new(),
             db,
             current_state_root: None,
+            key_hashes: KeyHashCache::default(),
+            resource_index: InMemoryResourceIndex::default(),
         }
     }
 
+    /// Records the resources written to the state in `resource_index`, which can be shared with
+    /// the queries listing them.
+    pub fn with_resource_index(mut self, resource_index: InMemoryResourceIndex) -> Self {
+        self.resource_index = resource_index;
+        self
+    }
+
     fn tree(&self) -> EthTrie<InMemoryTrieDb> {
         let db = self.db.clone();
         match self.current_state_root {
@@ -110,9 +177,10 @@ impl State for InMemoryState {
     fn apply(&mut self, changes: ChangeSet) -> Result<(), Self::Err> {
         self.current_state_root.replace(
             self.tree()
-                .insert_change_set_into_merkle_trie(&changes)
+                .insert_change_set_into_merkle_trie_with_key_hashes(&changes, &self.key_hashes)
                 .expect(IN_MEMORY_EXPECT_MSG),
         );
+        self.resource_index.extend(&changes);
         self.resolver.apply(changes)?;
         Ok(())
     }
@@ -122,11 +190,17 @@ impl State for InMemoryState {
         changes: ChangeSet,
         table_changes: TableChangeSet,
     ) -> Result<(), Self::Err> {
+        let mut tree = self.tree();
+        tree.insert_change_set_into_merkle_trie_with_key_hashes(&changes, &self.key_hashes)
+            .expect(IN_MEMORY_EXPECT_MSG);
         self.current_state_root.replace(
-            self.tree()
-                .insert_change_set_into_merkle_trie(&changes)
-                .expect(IN_MEMORY_EXPECT_MSG),
+            tree.insert_change_set_into_merkle_trie_with_key_hashes(
+                &table_changes,
+                &self.key_hashes,
+            )
+            .expect(IN_MEMORY_EXPECT_MSG),
         );
+        self.resource_index.extend(&changes);
         self.resolver.apply_extended(changes, table_changes)?;
         Ok(())
     }
@@ -149,7 +223,15 @@ pub trait InsertChangeSetIntoMerkleTrie {
 
     fn insert_change_set_into_merkle_trie(
         &mut self,
-        change_set: &ChangeSet,
+        change_set: &impl ToTreeValues,
+    ) -> Result<B256, Self::Err>;
+
+    /// Same as [`Self::insert_change_set_into_merkle_trie`], looking the hashes of the keys up in
+    /// `key_hashes`.
+    fn insert_change_set_into_merkle_trie_with_key_hashes(
+        &mut self,
+        change_set: &impl ToTreeValues,
+        key_hashes: &KeyHashCache,
     ) -> Result<B256, Self::Err>;
 }
 
@@ -158,18 +240,34 @@ impl<D: DB> InsertChangeSetIntoMerkleTrie for EthTrie<D> {
 
     fn insert_change_set_into_merkle_trie(
         &mut self,
-        change_set: &ChangeSet,
+        change_set: &impl ToTreeValues,
     ) -> Result<B256, Self::Err> {
-        let values = change_set.to_tree_values();
+        insert_tree_values(self, change_set, TreeKey::key_hash)
+    }
 
-        for (k, v) in values {
-            let key_bytes = k.key_hash();
-            let value_bytes = v.serialize();
-            self.insert(key_bytes.0.as_slice(), &value_bytes)?;
-        }
+    fn insert_change_set_into_merkle_trie_with_key_hashes(
+        &mut self,
+        change_set: &impl ToTreeValues,
+        key_hashes: &KeyHashCache,
+    ) -> Result<B256, Self::Err> {
+        insert_tree_values(self, change_set, |key| key_hashes.key_hash(key))
+    }
+}
 
-        self.root_hash()
+fn insert_tree_values<D: DB>(
+    trie: &mut EthTrie<D>,
+    change_set: &impl ToTreeValues,
+    key_hash: impl Fn(&TreeKey) -> KeyHash,
+) -> Result<B256, TrieError> {
+    let values = change_set.to_tree_values();
+
+    for (k, v) in values {
+        let key_bytes = key_hash(&k);
+        let value_bytes = v.serialize();
+        trie.insert(key_bytes.0.as_slice(), &value_bytes)?;
     }
+
+    trie.root_hash()
 }
 
 /// Converts itself to a set of updates for a merkle patricia trie.
@@ -218,43 +316,85 @@ impl ToTreeValues for ChangeSet {
                 changes
                     .modules()
                     .iter()
-                    .map(move |(k, v)| {
-                        let value = v.clone().ok().map(StateValue::new_legacy);
-                        let key = StateKey::module(address, k.as_ident_str());
-
-                        (
-                            TreeKey::StateKey(key),
-                            value
-                                .map(TreeValue::StateValue)
-                                .unwrap_or(TreeValue::Deleted),
-                        )
-                    })
-                    .chain(changes.resources().iter().map(move |(k, v)| {
-                        let value = if is_evm_storage_or_account_key(k) {
-                            v.clone()
-                                .ok()
-                                .map(TreeValue::Evm)
-                                .unwrap_or(TreeValue::Deleted)
-                        } else {
-                            v.clone()
-                                .ok()
-                                .map(StateValue::new_legacy)
-                                .map(TreeValue::StateValue)
-                                .unwrap_or(TreeValue::Deleted)
-                        };
-                        let key = if let Some(address) = evm_key_address(k) {
-                            TreeKey::Evm(address)
-                        } else {
-                            TreeKey::StateKey(StateKey::resource(address, k).unwrap())
-                        };
-
-                        (key, value)
-                    }))
+                    .map(move |(k, v)| module_tree_entry(address, k, v.clone().ok()))
+                    .chain(
+                        changes
+                            .resources()
+                            .iter()
+                            .map(move |(k, v)| resource_tree_entry(address, k, v.clone().ok())),
+                    )
+            })
+            .collect::<HashMap<_, _>>()
+    }
+}
+
+impl ToTreeValues for TableChangeSet {
+    fn to_tree_values(&self) -> HashMap<TreeKey, TreeValue> {
+        self.changes
+            .iter()
+            .flat_map(|(handle, change)| {
+                change
+                    .entries
+                    .iter()
+                    .map(move |(k, v)| table_item_tree_entry(handle, k, v.clone().ok()))
             })
             .collect::<HashMap<_, _>>()
     }
 }
 
+/// Maps the bytecode of a module, or `None` if it is deleted, to its entry in the state trie.
+pub(crate) fn module_tree_entry(
+    address: &AccountAddress,
+    name: &IdentStr,
+    value: Option<Bytes>,
+) -> (TreeKey, TreeValue) {
+    let key = StateKey::module(address, name);
+    let value = value
+        .map(StateValue::new_legacy)
+        .map(TreeValue::StateValue)
+        .unwrap_or(TreeValue::Deleted);
+
+    (TreeKey::StateKey(key), value)
+}
+
+/// Maps the bytes of a resource, or `None` if it is deleted, to its entry in the state trie.
+pub(crate) fn resource_tree_entry(
+    address: &AccountAddress,
+    tag: &StructTag,
+    value: Option<Bytes>,
+) -> (TreeKey, TreeValue) {
+    let value = if is_evm_storage_or_account_key(tag) {
+        value.map(TreeValue::Evm).unwrap_or(TreeValue::Deleted)
+    } else {
+        value
+            .map(StateValue::new_legacy)
+            .map(TreeValue::StateValue)
+            .unwrap_or(TreeValue::Deleted)
+    };
+    let key = if let Some(address) = evm_key_address(tag) {
+        TreeKey::Evm(address)
+    } else {
+        TreeKey::StateKey(StateKey::resource(address, tag).unwrap())
+    };
+
+    (key, value)
+}
+
+/// Maps the bytes of a table item, or `None` if it is deleted, to its entry in the state trie.
+pub(crate) fn table_item_tree_entry(
+    handle: &TableHandle,
+    key: &[u8],
+    value: Option<Bytes>,
+) -> (TreeKey, TreeValue) {
+    let key = StateKey::table_item(&StateTableHandle(handle.0), key);
+    let value = value
+        .map(StateValue::new_legacy)
+        .map(TreeValue::StateValue)
+        .unwrap_or(TreeValue::Deleted);
+
+    (TreeKey::StateKey(key), value)
+}
+
 pub fn evm_key_address(k: &StructTag) -> Option<Address> {
     if k.address == EVM_NATIVE_ADDRESS && k.module.as_ident_str() == EVM_NATIVE_MODULE {
         k.name
@@ -1,9 +1,16 @@
 use {
     aptos_types::state_store::{state_key::StateKey, state_value::StateValue},
-    moved_shared::primitives::{Address, KeyHash, KeyHashable},
-    std::borrow::Cow,
+    moved_shared::primitives::{Address, B256, KeyHash, KeyHashable},
+    std::{
+        borrow::Cow,
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    },
 };
 
+/// Number of key hashes a [`KeyHashCache`] keeps by default.
+pub const DEFAULT_KEY_HASH_CACHE_CAPACITY: usize = 4096;
+
 /// Type representing the keys used in the state trie.
 ///
 /// The EVM native is designed such that all EVM state is represented
@@ -24,16 +31,73 @@ pub enum TreeKey {
     Evm(Address),
 }
 
+impl TreeKey {
+    /// The bytes hashed into the key of the trie.
+    pub fn preimage(&self) -> &[u8] {
+        match self {
+            Self::StateKey(key) => key.encoded(),
+            Self::Evm(address) => address.as_slice(),
+        }
+    }
+}
+
 impl KeyHashable for TreeKey {
     fn key_hash(&self) -> KeyHash {
-        match self {
-            Self::StateKey(key) => {
-                let bytes = key.encoded();
-                KeyHash(alloy::primitives::keccak256(bytes))
-            }
-            Self::Evm(address) => KeyHash(alloy::primitives::keccak256(address)),
+        KeyHash(alloy::primitives::keccak256(self.preimage()))
+    }
+}
+
+/// Remembers the hashes of trie keys by their preimage, shared by all of its clones.
+///
+/// Building a block or serving queries reads the same accounts and resources over and over, each
+/// time hashing their keys anew. Once the cache holds `capacity` hashes it starts over, which keeps
+/// it small without tracking how recently each key was used.
+#[derive(Debug, Clone)]
+pub struct KeyHashCache {
+    hashes: Arc<RwLock<HashMap<Vec<u8>, B256>>>,
+    capacity: usize,
+}
+
+impl Default for KeyHashCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_KEY_HASH_CACHE_CAPACITY)
+    }
+}
+
+impl KeyHashCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            hashes: Arc::new(RwLock::new(HashMap::new())),
+            capacity,
         }
     }
+
+    /// Returns the same hash as [`KeyHashable::key_hash`] of `key`, computing it only if it is not
+    /// cached.
+    pub fn key_hash(&self, key: &TreeKey) -> KeyHash {
+        let preimage = key.preimage();
+
+        if let Some(hash) = self.hashes.read().unwrap().get(preimage) {
+            return KeyHash(*hash);
+        }
+
+        let hash = key.key_hash();
+        let mut hashes = self.hashes.write().unwrap();
+        if hashes.len() >= self.capacity {
+            hashes.clear();
+        }
+        hashes.insert(preimage.to_vec(), hash.0);
+
+        hash
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 /// Type representing the values used in the state trie.
@@ -57,3 +121,39 @@ impl TreeValue {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::primitives::address};
+
+    #[test]
+    fn test_cached_key_hash_equals_computed_one() {
+        let cache = KeyHashCache::default();
+        let key = TreeKey::Evm(address!("8fd379246834eac74b8419ffda202cf8051f7a03"));
+
+        let computed = cache.key_hash(&key);
+        let cached = cache.key_hash(&key);
+
+        assert_eq!(computed.0, key.key_hash().0);
+        assert_eq!(cached.0, computed.0);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_full_cache_starts_over() {
+        let cache = KeyHashCache::new(2);
+        let keys = [
+            address!("0000000000000000000000000000000000000001"),
+            address!("0000000000000000000000000000000000000002"),
+            address!("0000000000000000000000000000000000000003"),
+        ]
+        .map(TreeKey::Evm);
+
+        for key in &keys {
+            cache.key_hash(key);
+        }
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.key_hash(&keys[2]).0, keys[2].key_hash().0);
+    }
+}
@@ -0,0 +1,171 @@
+//! Preimages of the hashed keys of the state trie and the EVM storage tries.
+//!
+//! The nibbles of a trie key are the keccak-256 hash of what the key stands for, so a key read off
+//! a trie node tells nothing about the value under it. Recording each preimage as its key is
+//! written lets debug tooling map keys back, e.g. to find what a differing state root is made of.
+//! The preimage of a key is one of:
+//! * an encoded [`StateKey`] of a Move resource or module,
+//! * a 20 byte EVM account address,
+//! * a 32 byte big endian index of an EVM storage slot.
+//!
+//! [`StateKey`]: aptos_types::state_store::state_key::StateKey
+
+use {
+    crate::ToTreeValues,
+    alloy::primitives::keccak256,
+    move_core_types::effects::ChangeSet,
+    move_table_extension::TableChangeSet,
+    moved_evm_ext::state::StorageTriesChanges,
+    moved_shared::primitives::{B256, KeyHashable},
+    std::{
+        collections::HashMap,
+        convert::Infallible,
+        fmt::Debug,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// A trie key along with the bytes it is the hash of.
+pub type Preimage = (B256, Vec<u8>);
+
+/// Stores preimages of trie keys, see the [module documentation](self).
+pub trait TriePreimages {
+    type Err: Debug;
+
+    fn extend(&self, preimages: Vec<Preimage>) -> Result<(), Self::Err>;
+
+    /// Returns the bytes that hash to `key`, if they were recorded.
+    fn preimage(&self, key: B256) -> Result<Option<Vec<u8>>, Self::Err>;
+}
+
+/// Collects the preimages of the state trie keys that `changes` write to.
+pub fn state_preimages(changes: &ChangeSet) -> impl Iterator<Item = Preimage> {
+    changes
+        .to_tree_values()
+        .into_keys()
+        .map(|key| (key.key_hash().0, key.preimage().to_vec()))
+}
+
+/// Collects the preimages of the state trie keys that the table items of `changes` write to.
+pub fn table_preimages(changes: &TableChangeSet) -> impl Iterator<Item = Preimage> {
+    changes
+        .to_tree_values()
+        .into_keys()
+        .map(|key| (key.key_hash().0, key.preimage().to_vec()))
+}
+
+/// Collects the preimages of the EVM storage trie keys that `changes` write to.
+pub fn storage_preimages(changes: &StorageTriesChanges) -> impl Iterator<Item = Preimage> {
+    changes.tries.values().flat_map(|trie| {
+        trie.slots.iter().map(|index| {
+            let preimage = index.to_be_bytes::<32>().to_vec();
+            (keccak256(&preimage), preimage)
+        })
+    })
+}
+
+/// Keeps the preimages in memory, shared by all of its clones.
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryTriePreimages {
+    preimages: Arc<RwLock<HashMap<B256, Vec<u8>>>>,
+}
+
+impl TriePreimages for InMemoryTriePreimages {
+    type Err = Infallible;
+
+    fn extend(&self, preimages: Vec<Preimage>) -> Result<(), Self::Err> {
+        self.preimages.write().unwrap().extend(preimages);
+        Ok(())
+    }
+
+    fn preimage(&self, key: B256) -> Result<Option<Vec<u8>>, Self::Err> {
+        Ok(self.preimages.read().unwrap().get(&key).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{InMemoryState, State},
+        alloy::primitives::address,
+        aptos_types::state_store::state_key::StateKey,
+        bytes::Bytes,
+        eth_trie::{EthTrie, Trie},
+        move_core_types::{
+            account_address::AccountAddress, effects::Op, ident_str, language_storage::StructTag,
+        },
+        moved_evm_ext::{
+            EVM_NATIVE_ADDRESS, state::StorageTrieChanges, type_utils::account_info_struct_tag,
+        },
+        moved_shared::primitives::U256,
+    };
+
+    #[test]
+    fn test_state_preimages_hash_to_keys_of_the_trie() {
+        let tag = StructTag {
+            address: AccountAddress::ONE,
+            module: ident_str!("counter").into(),
+            name: ident_str!("Counter").into(),
+            type_args: Vec::new(),
+        };
+        let evm_address = address!("8fd379246834eac74b8419ffda202cf8051f7a03");
+        let mut changes = ChangeSet::new();
+        changes
+            .add_resource_op(
+                AccountAddress::ONE,
+                tag.clone(),
+                Op::New(Bytes::from_static(&[1])),
+            )
+            .unwrap();
+        changes
+            .add_resource_op(
+                EVM_NATIVE_ADDRESS,
+                account_info_struct_tag(&evm_address),
+                Op::New(Bytes::from_static(&[2])),
+            )
+            .unwrap();
+        let mut state = InMemoryState::default();
+        state.apply(changes.clone()).unwrap();
+        let trie = EthTrie::from(state.db(), state.state_root()).unwrap();
+
+        let preimages: HashMap<_, _> = state_preimages(&changes).collect();
+
+        assert_eq!(preimages.len(), 2);
+        for (key, preimage) in &preimages {
+            assert_eq!(*key, keccak256(preimage));
+            assert!(trie.get(key.as_slice()).unwrap().is_some());
+        }
+        let state_key = StateKey::resource(&AccountAddress::ONE, &tag).unwrap();
+        assert!(preimages.values().any(|p| p == state_key.encoded()));
+        assert!(preimages.values().any(|p| p == evm_address.as_slice()));
+    }
+
+    #[test]
+    fn test_storage_preimages_are_slot_indices() {
+        let changes = StorageTriesChanges::empty().with_trie_changes(
+            address!("8fd379246834eac74b8419ffda202cf8051f7a03"),
+            StorageTrieChanges {
+                root: B256::ZERO,
+                trie_diff: HashMap::new(),
+                slots: vec![U256::from(7)],
+            },
+        );
+
+        let preimages: Vec<_> = storage_preimages(&changes).collect();
+
+        let index = U256::from(7).to_be_bytes::<32>();
+        assert_eq!(preimages, [(keccak256(index), index.to_vec())]);
+    }
+
+    #[test]
+    fn test_in_memory_preimages_are_found_by_key() {
+        let preimages = InMemoryTriePreimages::default();
+        let key = keccak256([1, 2, 3]);
+
+        preimages.extend(vec![(key, vec![1, 2, 3])]).unwrap();
+
+        assert_eq!(preimages.preimage(key).unwrap(), Some(vec![1, 2, 3]));
+        assert_eq!(preimages.preimage(B256::ZERO).unwrap(), None);
+    }
+}
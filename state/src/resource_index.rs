@@ -0,0 +1,182 @@
+//! An index of the resources stored under each account.
+//!
+//! The keys of the state trie are hashes, so the trie cannot tell which resources an account holds.
+//! Each [`State`] records the struct tags of the resources it writes in the index instead, which
+//! lists them per account in the order of their [`index_key`]s.
+//!
+//! Entries are never removed, as an older state version still holds a resource that is deleted
+//! later on. Readers check that a listed resource exists in the state version they read. The EVM
+//! accounts and storage are kept as resources too, but they are left out of the index.
+//!
+//! A database that predates the index only has the resources written since it was added, so the
+//! index of a database is only complete if it has recorded the genesis state, see [`COMPLETE_KEY`].
+//!
+//! [`State`]: crate::State
+
+use {
+    crate::is_evm_storage_or_account_key,
+    move_core_types::{
+        account_address::AccountAddress, effects::ChangeSet, language_storage::StructTag,
+    },
+    std::{
+        collections::BTreeMap,
+        convert::Infallible,
+        fmt::Debug,
+        ops::Bound,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// Marks an index that has recorded every resource since genesis, as it is written along with the
+/// first state root.
+///
+/// It sorts before every [`index_key`], as those start with a whole address.
+pub const COMPLETE_KEY: [u8; 1] = [0];
+
+/// Lists the resources recorded for each account, see the [module documentation](self).
+pub trait ResourceIndex {
+    type Err: Debug;
+
+    /// Whether the index has recorded every resource since genesis, which is not the case for a
+    /// database that predates it.
+    fn is_complete(&self) -> Result<bool, Self::Err>;
+
+    /// Returns up to `limit` struct tags of the resources recorded under `account`, starting after
+    /// the one of `after` if it is given.
+    fn resources(
+        &self,
+        account: &AccountAddress,
+        after: Option<&StructTag>,
+        limit: usize,
+    ) -> Result<Vec<StructTag>, Self::Err>;
+}
+
+/// Collects the resources of Move accounts that `changes` write to.
+pub fn written_resources(
+    changes: &ChangeSet,
+) -> impl Iterator<Item = (&AccountAddress, &StructTag)> {
+    changes.accounts().iter().flat_map(|(address, account)| {
+        account
+            .resources()
+            .keys()
+            .filter(|tag| !is_evm_storage_or_account_key(tag))
+            .map(move |tag| (address, tag))
+    })
+}
+
+/// The key of the resource of type `tag` under `account`, which is the address followed by the
+/// bcs encoded tag so that the resources of an account are next to each other.
+pub fn index_key(account: &AccountAddress, tag: &StructTag) -> Vec<u8> {
+    let mut key = account.to_vec();
+    key.extend(bcs::to_bytes(tag).expect("Struct tag should serialize"));
+    key
+}
+
+/// Reads the struct tag back from a key made by [`index_key`].
+pub fn tag_from_index_key(key: &[u8]) -> StructTag {
+    bcs::from_bytes(&key[AccountAddress::LENGTH..]).expect("Index key should hold a struct tag")
+}
+
+/// Keeps the index in memory, shared by all of its clones.
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryResourceIndex {
+    resources: Arc<RwLock<BTreeMap<Vec<u8>, StructTag>>>,
+}
+
+impl InMemoryResourceIndex {
+    /// Records the resources that `changes` write to.
+    pub fn extend(&self, changes: &ChangeSet) {
+        self.resources.write().unwrap().extend(
+            written_resources(changes).map(|(account, tag)| (index_key(account, tag), tag.clone())),
+        );
+    }
+}
+
+impl ResourceIndex for InMemoryResourceIndex {
+    type Err = Infallible;
+
+    /// The index is built along with the state it is kept in memory with.
+    fn is_complete(&self) -> Result<bool, Self::Err> {
+        Ok(true)
+    }
+
+    fn resources(
+        &self,
+        account: &AccountAddress,
+        after: Option<&StructTag>,
+        limit: usize,
+    ) -> Result<Vec<StructTag>, Self::Err> {
+        let start = match after {
+            Some(tag) => Bound::Excluded(index_key(account, tag)),
+            None => Bound::Included(account.to_vec()),
+        };
+
+        Ok(self
+            .resources
+            .read()
+            .unwrap()
+            .range((start, Bound::Unbounded))
+            .take_while(|(key, _)| key.starts_with(account.as_ref()))
+            .take(limit)
+            .map(|(_, tag)| tag.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        bytes::Bytes,
+        move_core_types::{effects::Op, ident_str},
+        moved_evm_ext::type_utils::account_info_struct_tag,
+    };
+
+    fn tag(name: &str) -> StructTag {
+        StructTag {
+            address: AccountAddress::ONE,
+            module: ident_str!("counter").into(),
+            name: name.parse().unwrap(),
+            type_args: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_resources_are_listed_per_account_in_pages() {
+        let account = AccountAddress::new([0x11; 32]);
+        let other = AccountAddress::new([0x22; 32]);
+        let mut changes = ChangeSet::new();
+        for name in ["A", "B", "C"] {
+            changes
+                .add_resource_op(account, tag(name), Op::New(Bytes::from_static(&[1])))
+                .unwrap();
+        }
+        changes
+            .add_resource_op(other, tag("D"), Op::New(Bytes::from_static(&[1])))
+            .unwrap();
+        let index = InMemoryResourceIndex::default();
+        index.extend(&changes);
+
+        let first = index.resources(&account, None, 2).unwrap();
+        let second = index.resources(&account, first.last(), 2).unwrap();
+
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 1);
+        let mut listed = [first, second].concat();
+        listed.sort();
+        assert_eq!(listed, vec![tag("A"), tag("B"), tag("C")]);
+        assert_eq!(index.resources(&other, None, 10).unwrap(), vec![tag("D")]);
+    }
+
+    #[test]
+    fn test_evm_accounts_are_left_out() {
+        let evm_address = alloy::primitives::address!("8fd379246834eac74b8419ffda202cf8051f7a03");
+        let tag = account_info_struct_tag(&evm_address);
+        let mut changes = ChangeSet::new();
+        changes
+            .add_resource_op(tag.address, tag.clone(), Op::New(Bytes::from_static(&[1])))
+            .unwrap();
+
+        assert_eq!(written_resources(&changes).count(), 0);
+    }
+}
@@ -0,0 +1,453 @@
+//! Recording of the state that block execution reads, the groundwork of stateless execution
+//! witnesses.
+//!
+//! A witness holds just enough of the state for a block to be executed without access to the rest
+//! of it: the trie nodes on the paths to the keys that the block reads or writes, and the code it
+//! runs. The reads are recorded by wrapping the resolver and the EVM storage tries that the
+//! transactions are executed on with a shared [`ReadRecorder`].
+
+use {
+    crate::{consistency::ResolverKey, module_tree_entry, nodes::TreeKey, resource_tree_entry},
+    alloy::consensus::EMPTY_ROOT_HASH,
+    bytes::Bytes,
+    eth_trie::{DB, EthTrie, Trie, TrieError},
+    move_binary_format::errors::PartialVMError,
+    move_core_types::{
+        account_address::AccountAddress,
+        language_storage::{ModuleId, StructTag},
+        metadata::Metadata,
+        value::MoveTypeLayout,
+    },
+    move_table_extension::{TableHandle, TableResolver},
+    move_vm_types::resolver::{ModuleResolver, ResourceResolver},
+    moved_evm_ext::{
+        EVM_NATIVE_ADDRESS, EVM_NATIVE_MODULE,
+        state::{
+            self, BoxedTrieDb, DbWithRoot, StorageTrie, StorageTrieRepository, StorageTriesChanges,
+        },
+        type_utils::CODE_HASH_PREFIX,
+    },
+    moved_shared::primitives::{Address, B256, KeyHashable},
+    moved_trie::StagingEthTrieDb,
+    std::{
+        collections::BTreeSet,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// State read by executed transactions.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StateReads {
+    /// Keys of the modules and resources read from the state.
+    pub keys: BTreeSet<ResolverKey>,
+    /// Bytecode of the Move modules and the EVM contracts read.
+    pub codes: BTreeSet<Bytes>,
+    /// Encoded nodes read from the EVM storage tries.
+    pub storage_nodes: BTreeSet<Bytes>,
+}
+
+impl StateReads {
+    pub fn extend(&mut self, other: StateReads) {
+        self.keys.extend(other.keys);
+        self.codes.extend(other.codes);
+        self.storage_nodes.extend(other.storage_nodes);
+    }
+}
+
+/// Collects the [`StateReads`] of every [`RecordingResolver`] and
+/// [`RecordingStorageTrieRepository`] it is given to.
+#[derive(Debug, Default, Clone)]
+pub struct ReadRecorder {
+    reads: Arc<Mutex<StateReads>>,
+}
+
+impl ReadRecorder {
+    /// Returns the reads recorded so far, leaving none behind.
+    pub fn take(&self) -> StateReads {
+        std::mem::take(&mut *self.reads.lock().unwrap())
+    }
+
+    fn record(&self, f: impl FnOnce(&mut StateReads)) {
+        f(&mut self.reads.lock().unwrap())
+    }
+}
+
+/// Reads the `state` as is, recording the keys read if there is a `recorder`.
+///
+/// Table entries are not recorded, as tables are not part of the state trie.
+pub struct RecordingResolver<'a, S> {
+    state: &'a S,
+    recorder: Option<&'a ReadRecorder>,
+}
+
+impl<'a, S> RecordingResolver<'a, S> {
+    pub fn new(state: &'a S, recorder: Option<&'a ReadRecorder>) -> Self {
+        Self { state, recorder }
+    }
+}
+
+impl<S: ModuleResolver> ModuleResolver for RecordingResolver<'_, S> {
+    fn get_module_metadata(&self, module_id: &ModuleId) -> Vec<Metadata> {
+        self.state.get_module_metadata(module_id)
+    }
+
+    fn get_module(&self, id: &ModuleId) -> Result<Option<Bytes>, PartialVMError> {
+        let module = self.state.get_module(id)?;
+
+        if let Some(recorder) = self.recorder {
+            recorder.record(|reads| {
+                reads.keys.insert(ResolverKey::Module(id.clone()));
+                reads.codes.extend(module.clone());
+            });
+        }
+
+        Ok(module)
+    }
+}
+
+impl<S: ResourceResolver> ResourceResolver for RecordingResolver<'_, S> {
+    fn get_resource_bytes_with_metadata_and_layout(
+        &self,
+        address: &AccountAddress,
+        struct_tag: &StructTag,
+        metadata: &[Metadata],
+        layout: Option<&MoveTypeLayout>,
+    ) -> Result<(Option<Bytes>, usize), PartialVMError> {
+        let (value, len) = self
+            .state
+            .get_resource_bytes_with_metadata_and_layout(address, struct_tag, metadata, layout)?;
+
+        if let Some(recorder) = self.recorder {
+            // EVM code is kept as a serialized `vector<u8>`
+            let code = value
+                .as_deref()
+                .filter(|_| is_evm_code_key(struct_tag))
+                .and_then(|value| bcs::from_bytes::<Vec<u8>>(value).ok());
+            recorder.record(|reads| {
+                reads
+                    .keys
+                    .insert(ResolverKey::Resource(*address, struct_tag.clone()));
+                reads.codes.extend(code.map(Bytes::from));
+            });
+        }
+
+        Ok((value, len))
+    }
+}
+
+impl<S: TableResolver> TableResolver for RecordingResolver<'_, S> {
+    fn resolve_table_entry_bytes_with_layout(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+        maybe_layout: Option<&MoveTypeLayout>,
+    ) -> Result<Option<Bytes>, PartialVMError> {
+        self.state
+            .resolve_table_entry_bytes_with_layout(handle, key, maybe_layout)
+    }
+}
+
+fn is_evm_code_key(tag: &StructTag) -> bool {
+    tag.address == EVM_NATIVE_ADDRESS
+        && tag.module.as_ident_str() == EVM_NATIVE_MODULE
+        && tag.name.as_str().starts_with(CODE_HASH_PREFIX)
+}
+
+/// Opens the EVM storage tries of the `inner` repository, recording the trie nodes read from them
+/// if there is a `recorder`.
+///
+/// Nodes that earlier transactions of the same block have written are recorded too, once they are
+/// read back.
+pub struct RecordingStorageTrieRepository<'a, S> {
+    inner: &'a S,
+    recorder: Option<&'a ReadRecorder>,
+}
+
+impl<'a, S> RecordingStorageTrieRepository<'a, S> {
+    pub fn new(inner: &'a S, recorder: Option<&'a ReadRecorder>) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+impl<S: StorageTrieRepository> StorageTrieRepository for RecordingStorageTrieRepository<'_, S> {
+    fn for_account(&self, account: &Address) -> state::Result<StorageTrie> {
+        let mut trie = self.inner.for_account(account)?;
+
+        match self.recorder {
+            Some(recorder) => {
+                let root = trie.root_hash()?;
+                recording_trie(trie, root, recorder)
+            }
+            None => Ok(trie),
+        }
+    }
+
+    fn for_account_with_root(
+        &self,
+        account: &Address,
+        storage_root: &B256,
+    ) -> state::Result<StorageTrie> {
+        let trie = self.inner.for_account_with_root(account, storage_root)?;
+
+        match self.recorder {
+            Some(recorder) => recording_trie(trie, *storage_root, recorder),
+            None => Ok(trie),
+        }
+    }
+
+    fn trie_node(&self, account: &Address, hash: B256) -> state::Result<Option<Vec<u8>>> {
+        self.inner.trie_node(account, hash)
+    }
+
+    fn accounts(&self) -> state::Result<Vec<Address>> {
+        self.inner.accounts()
+    }
+
+    fn apply(&self, changes: StorageTriesChanges) -> state::Result<()> {
+        self.inner.apply(changes)
+    }
+}
+
+/// Reopens `trie` at `root` on top of its DB wrapped in a [`RecordingTrieDb`].
+fn recording_trie(
+    trie: StorageTrie,
+    root: B256,
+    recorder: &ReadRecorder,
+) -> state::Result<StorageTrie> {
+    let db = Arc::new(StagingEthTrieDb::new(BoxedTrieDb::new(RecordingTrieDb {
+        db: trie.0.db.clone(),
+        recorder: recorder.clone(),
+    })));
+
+    Ok(if root == EMPTY_ROOT_HASH {
+        StorageTrie::new(db)
+    } else {
+        StorageTrie::from(db, root)?
+    })
+}
+
+/// Records every node read from the wrapped `db`.
+struct RecordingTrieDb {
+    db: Arc<StagingEthTrieDb<BoxedTrieDb>>,
+    recorder: ReadRecorder,
+}
+
+impl DB for RecordingTrieDb {
+    type Error = state::Error;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        let node = self.db.get(key)?;
+
+        if let Some(node) = &node {
+            self.recorder.record(|reads| {
+                reads.storage_nodes.insert(Bytes::copy_from_slice(node));
+            });
+        }
+
+        Ok(node)
+    }
+
+    fn insert(&self, key: &[u8], value: Vec<u8>) -> Result<(), Self::Error> {
+        self.db.insert(key, value)
+    }
+
+    fn insert_batch(&self, keys: Vec<Vec<u8>>, values: Vec<Vec<u8>>) -> Result<(), Self::Error> {
+        self.db.insert_batch(keys, values)
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), Self::Error> {
+        self.db.remove(key)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.db.flush()
+    }
+}
+
+impl DbWithRoot for RecordingTrieDb {
+    fn root(&self) -> Result<Option<B256>, Self::Error> {
+        self.db.root()
+    }
+
+    fn put_root(&self, root: B256) -> Result<(), Self::Error> {
+        self.db.put_root(root)
+    }
+}
+
+/// Collects the nodes of the state trie with `root` on the paths to each of the `keys`, which are
+/// what the trie needs to read those keys or to write to them.
+///
+/// Deleting a key can collapse a branch into its remaining child, whose node is not collected
+/// unless it is on the path to another key.
+pub fn state_trie_nodes<'a>(
+    db: Arc<impl DB>,
+    root: B256,
+    keys: impl IntoIterator<Item = &'a ResolverKey>,
+) -> Result<BTreeSet<Bytes>, TrieError> {
+    // An empty state has no trie to be read
+    if root == B256::ZERO {
+        return Ok(BTreeSet::new());
+    }
+
+    let mut trie = EthTrie::from(db, root)?;
+    let mut nodes = BTreeSet::new();
+
+    for key in keys {
+        let proof = trie.get_proof(tree_key(key).key_hash().0.as_slice())?;
+        nodes.extend(proof.into_iter().map(Bytes::from));
+    }
+
+    Ok(nodes)
+}
+
+fn tree_key(key: &ResolverKey) -> TreeKey {
+    match key {
+        ResolverKey::Module(id) => module_tree_entry(id.address(), id.name(), None).0,
+        ResolverKey::Resource(address, tag) => resource_tree_entry(address, tag, None).0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{InMemoryState, State},
+        alloy::primitives::{address, keccak256},
+        eth_trie::MemoryDB,
+        move_core_types::{
+            effects::{ChangeSet, Op},
+            ident_str,
+        },
+        moved_evm_ext::{
+            state::InMemoryStorageTrieRepository,
+            type_utils::{account_info_struct_tag, code_hash_struct_tag},
+        },
+        moved_shared::primitives::U256,
+    };
+
+    fn counter_tag() -> StructTag {
+        StructTag {
+            address: AccountAddress::ONE,
+            module: ident_str!("counter").into(),
+            name: ident_str!("Counter").into(),
+            type_args: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_recording_resolver_records_keys_and_code_read() {
+        let module_id = ModuleId::new(AccountAddress::ONE, ident_str!("counter").into());
+        let code_tag = code_hash_struct_tag(&keccak256([0x60, 0x80]));
+        let mut changes = ChangeSet::new();
+        changes
+            .add_module_op(module_id.clone(), Op::New(Bytes::from_static(&[1, 2])))
+            .unwrap();
+        changes
+            .add_resource_op(
+                EVM_NATIVE_ADDRESS,
+                code_tag.clone(),
+                Op::New(bcs::to_bytes(&vec![0x60u8, 0x80]).unwrap().into()),
+            )
+            .unwrap();
+        let mut state = InMemoryState::default();
+        state.apply(changes).unwrap();
+        let recorder = ReadRecorder::default();
+        let resolver = RecordingResolver::new(state.resolver(), Some(&recorder));
+
+        resolver.get_module(&module_id).unwrap();
+        resolver
+            .get_resource_bytes_with_metadata_and_layout(&EVM_NATIVE_ADDRESS, &code_tag, &[], None)
+            .unwrap();
+        resolver
+            .get_resource_bytes_with_metadata_and_layout(
+                &AccountAddress::ONE,
+                &counter_tag(),
+                &[],
+                None,
+            )
+            .unwrap();
+
+        let reads = recorder.take();
+        assert_eq!(
+            reads.keys,
+            BTreeSet::from([
+                ResolverKey::Module(module_id),
+                ResolverKey::Resource(EVM_NATIVE_ADDRESS, code_tag),
+                ResolverKey::Resource(AccountAddress::ONE, counter_tag()),
+            ])
+        );
+        assert_eq!(
+            reads.codes,
+            BTreeSet::from([
+                Bytes::from_static(&[1, 2]),
+                Bytes::from_static(&[0x60, 0x80])
+            ])
+        );
+        assert_eq!(recorder.take(), StateReads::default());
+    }
+
+    #[test]
+    fn test_state_trie_nodes_suffice_to_read_keys() {
+        let evm_address = address!("8fd379246834eac74b8419ffda202cf8051f7a03");
+        let mut changes = ChangeSet::new();
+        changes
+            .add_resource_op(
+                AccountAddress::ONE,
+                counter_tag(),
+                Op::New(Bytes::from_static(&[1])),
+            )
+            .unwrap();
+        changes
+            .add_resource_op(
+                EVM_NATIVE_ADDRESS,
+                account_info_struct_tag(&evm_address),
+                Op::New(Bytes::from_static(&[2])),
+            )
+            .unwrap();
+        let mut state = InMemoryState::default();
+        state.apply(changes).unwrap();
+        let key = ResolverKey::Resource(AccountAddress::ONE, counter_tag());
+
+        let nodes = state_trie_nodes(state.db(), state.state_root(), [&key]).unwrap();
+
+        let witness_db = Arc::new(MemoryDB::new(false));
+        for node in nodes {
+            witness_db
+                .insert(keccak256(&node).as_slice(), node.to_vec())
+                .unwrap();
+        }
+        let witness_trie = EthTrie::from(witness_db, state.state_root()).unwrap();
+        let state_trie = EthTrie::from(state.db(), state.state_root()).unwrap();
+        let key_hash = tree_key(&key).key_hash();
+        assert_eq!(
+            witness_trie.get(key_hash.0.as_slice()).unwrap(),
+            state_trie.get(key_hash.0.as_slice()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_recording_storage_repository_records_nodes_read() {
+        let account = address!("8fd379246834eac74b8419ffda202cf8051f7a03");
+        let repository = InMemoryStorageTrieRepository::new();
+        let mut trie = repository.for_account(&account).unwrap();
+        trie.insert(&U256::from(1), &U256::from(2)).unwrap();
+        trie.insert(&U256::from(3), &U256::from(4)).unwrap();
+        let changes = trie.commit().unwrap();
+        let root = changes.root;
+        repository
+            .apply(StorageTriesChanges::empty().with_trie_changes(account, changes))
+            .unwrap();
+        let recorder = ReadRecorder::default();
+        let recording = RecordingStorageTrieRepository::new(&repository, Some(&recorder));
+
+        let value = recording
+            .for_account(&account)
+            .unwrap()
+            .get(&U256::from(3))
+            .unwrap();
+
+        assert_eq!(value, Some(U256::from(4)));
+        let nodes = recorder.take().storage_nodes;
+        assert!(nodes.iter().any(|node| keccak256(node) == root));
+    }
+}
@@ -1,11 +1,16 @@
 use {
-    crate::{block, evm_storage_trie, payload, receipt, state, transaction, trie},
+    crate::{
+        block, evm_storage_trie, payload, preimage, receipt, resource_index, state, transaction,
+        trie,
+    },
     heed::{BytesDecode, BytesEncode, RoTxn, RwTxn, types::LazyDecode},
 };
 
-pub const DATABASES: [&str; 11] = [
+pub const DATABASES: [&str; 17] = [
     block::DB,
     block::HEIGHT_DB,
+    block::FORKCHOICE_DB,
+    block::TRANSACTION_COUNT_DB,
     state::DB,
     state::HEIGHT_DB,
     trie::DB,
@@ -13,8 +18,12 @@ pub const DATABASES: [&str; 11] = [
     evm_storage_trie::DB,
     evm_storage_trie::ROOT_DB,
     transaction::DB,
+    transaction::JOURNAL_DB,
     receipt::DB,
     payload::DB,
+    payload::JOB_DB,
+    preimage::DB,
+    resource_index::DB,
 ];
 
 #[derive(Debug)]
@@ -46,6 +55,29 @@ impl<Key, Value> HeedDb<Key, Value> {
         self.0.get(txn, key)
     }
 
+    pub fn delete<'a>(&self, txn: &mut RwTxn, key: &'a Key::EItem) -> heed::Result<bool>
+    where
+        Key: BytesEncode<'a>,
+    {
+        self.0.delete(txn, key)
+    }
+
+    pub fn range<'a, 'txn, R>(
+        &self,
+        txn: &'txn RoTxn,
+        range: &'a R,
+    ) -> heed::Result<heed::RoRange<'txn, Key, Value>>
+    where
+        Key: BytesEncode<'a>,
+        R: std::ops::RangeBounds<Key::EItem>,
+    {
+        self.0.range(txn, range)
+    }
+
+    pub fn iter<'txn>(&self, txn: &'txn RoTxn) -> heed::Result<heed::RoIter<'txn, Key, Value>> {
+        self.0.iter(txn)
+    }
+
     pub fn last<'txn>(&self, txn: &'txn RoTxn) -> heed::Result<Option<(Key::DItem, Value::DItem)>>
     where
         Key: BytesDecode<'txn>,
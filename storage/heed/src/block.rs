@@ -2,11 +2,15 @@ use {
     crate::{
         all::HeedDb,
         generic::{EncodableB256, EncodableU64, SerdeJson},
+        payload::HeedPayloadExt,
         transaction::HeedTransactionExt,
     },
     heed::RoTxn,
-    moved_blockchain::block::{BlockQueries, BlockRepository, BlockResponse, ExtendedBlock},
-    moved_shared::primitives::B256,
+    moved_blockchain::{
+        block::{BlockQueries, BlockRepository, BlockResponse, ExtendedBlock, RejectedTransaction},
+        payload::PayloadId,
+    },
+    moved_shared::primitives::{B256, ToU64},
 };
 
 pub type Key = EncodableB256;
@@ -15,10 +19,20 @@ pub type Db = heed::Database<Key, Value>;
 pub type HeightKey = EncodableU64;
 pub type HeightValue = EncodableB256;
 pub type HeightDb = heed::Database<HeightKey, HeightValue>;
+pub type ForkchoiceKey = EncodableU64;
+pub type ForkchoiceValue = EncodableU64;
+pub type ForkchoiceDb = heed::Database<ForkchoiceKey, ForkchoiceValue>;
+pub type TransactionCountKey = EncodableB256;
+pub type TransactionCountValue = EncodableU64;
+pub type TransactionCountDb = heed::Database<TransactionCountKey, TransactionCountValue>;
 pub type EncodableBlock = SerdeJson<ExtendedBlock>;
 
 pub const DB: &str = "block";
 pub const HEIGHT_DB: &str = "height";
+pub const FORKCHOICE_DB: &str = "forkchoice";
+pub const TRANSACTION_COUNT_DB: &str = "block_transaction_count";
+pub const SAFE_KEY: u64 = 0;
+pub const FINALIZED_KEY: u64 = 1;
 
 #[derive(Debug)]
 pub struct HeedBlockRepository;
@@ -38,6 +52,10 @@ impl BlockRepository for HeedBlockRepository {
 
         db.put(&mut transaction, &block.block.header.number, &block.hash)?;
 
+        let db = env.block_transaction_count_database(&transaction)?;
+
+        db.put(&mut transaction, &block.hash, &block.transaction_count())?;
+
         transaction.commit()
     }
 
@@ -66,6 +84,42 @@ impl BlockRepository for HeedBlockRepository {
 
         Ok(response.transpose()?.flatten())
     }
+
+    fn remove_payload_id(
+        &mut self,
+        env: &mut Self::Storage,
+        payload_id: PayloadId,
+        block_hash: B256,
+    ) -> Result<(), Self::Err> {
+        let mut transaction = env.write_txn()?;
+
+        let db = env.payload_database(&transaction)?;
+
+        let id = payload_id.to_u64();
+        if db.get(&transaction, &id)? == Some(block_hash) {
+            db.delete(&mut transaction, &id)?;
+        }
+
+        transaction.commit()
+    }
+
+    fn mark_safe(&mut self, env: &mut Self::Storage, height: u64) -> Result<(), Self::Err> {
+        mark(env, SAFE_KEY, height)
+    }
+
+    fn mark_finalized(&mut self, env: &mut Self::Storage, height: u64) -> Result<(), Self::Err> {
+        mark(env, FINALIZED_KEY, height)
+    }
+}
+
+fn mark(env: &heed::Env, key: u64, height: u64) -> Result<(), heed::Error> {
+    let mut transaction = env.write_txn()?;
+
+    let db = env.forkchoice_database(&transaction)?;
+
+    db.put(&mut transaction, &key, &height)?;
+
+    transaction.commit()
 }
 
 #[derive(Debug, Clone)]
@@ -142,12 +196,115 @@ impl BlockQueries for HeedBlockQueries {
 
         Ok(pair.map(|(height, _hash)| height))
     }
+
+    fn safe(&self, env: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+        marker(env, SAFE_KEY)
+    }
+
+    fn finalized(&self, env: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+        marker(env, FINALIZED_KEY)
+    }
+
+    fn rejected_transactions(
+        &self,
+        env: &Self::Storage,
+        height: u64,
+    ) -> Result<Option<Vec<RejectedTransaction>>, Self::Err> {
+        let transaction = env.read_txn()?;
+
+        let db = env.block_height_database(&transaction)?;
+
+        let block = db
+            .get(&transaction, &height)?
+            .map(|hash| env.block_database(&transaction)?.get(&transaction, &hash))
+            .transpose()?
+            .flatten();
+
+        transaction.commit()?;
+
+        Ok(block.map(|block| block.rejected_transactions))
+    }
+
+    fn transaction_count_by_hash(
+        &self,
+        env: &Self::Storage,
+        hash: B256,
+    ) -> Result<Option<u64>, Self::Err> {
+        let transaction = env.read_txn()?;
+
+        let count = transaction_count(env, &transaction, &hash)?;
+
+        transaction.commit()?;
+
+        Ok(count)
+    }
+
+    fn transaction_count_by_height(
+        &self,
+        env: &Self::Storage,
+        height: u64,
+    ) -> Result<Option<u64>, Self::Err> {
+        let transaction = env.read_txn()?;
+
+        let db = env.block_height_database(&transaction)?;
+
+        let count = db
+            .get(&transaction, &height)?
+            .map(|hash| transaction_count(env, &transaction, &hash))
+            .transpose()?
+            .flatten();
+
+        transaction.commit()?;
+
+        Ok(count)
+    }
+}
+
+fn transaction_count(
+    env: &heed::Env,
+    transaction: &RoTxn,
+    hash: &B256,
+) -> Result<Option<u64>, heed::Error> {
+    let db = env.block_transaction_count_database(transaction)?;
+
+    if let Some(count) = db.get(transaction, hash)? {
+        return Ok(Some(count));
+    }
+
+    // Blocks added before their counts were stored are counted from their transaction hashes
+    let db = env.block_database(transaction)?;
+
+    Ok(db
+        .get(transaction, hash)?
+        .map(|block| block.transaction_count()))
+}
+
+fn marker(env: &heed::Env, key: u64) -> Result<Option<u64>, heed::Error> {
+    let transaction = env.read_txn()?;
+
+    let db = env.forkchoice_database(&transaction)?;
+
+    let height = db.get(&transaction, &key)?;
+
+    transaction.commit()?;
+
+    Ok(height)
 }
 
 pub trait HeedBlockExt {
     fn block_database(&self, rtxn: &RoTxn) -> heed::Result<HeedDb<Key, Value>>;
 
     fn block_height_database(&self, rtxn: &RoTxn) -> heed::Result<HeedDb<HeightKey, HeightValue>>;
+
+    fn forkchoice_database(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<HeedDb<ForkchoiceKey, ForkchoiceValue>>;
+
+    fn block_transaction_count_database(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<HeedDb<TransactionCountKey, TransactionCountValue>>;
 }
 
 impl HeedBlockExt for heed::Env {
@@ -166,4 +323,26 @@ impl HeedBlockExt for heed::Env {
 
         Ok(HeedDb(db))
     }
+
+    fn forkchoice_database(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<HeedDb<ForkchoiceKey, ForkchoiceValue>> {
+        let db: ForkchoiceDb = self
+            .open_database(rtxn, Some(FORKCHOICE_DB))?
+            .expect("Forkchoice database should exist");
+
+        Ok(HeedDb(db))
+    }
+
+    fn block_transaction_count_database(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<HeedDb<TransactionCountKey, TransactionCountValue>> {
+        let db: TransactionCountDb = self
+            .open_database(rtxn, Some(TRANSACTION_COUNT_DB))?
+            .expect("Block transaction count database should exist");
+
+        Ok(HeedDb(db))
+    }
 }
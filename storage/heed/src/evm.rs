@@ -1,5 +1,5 @@
 use {
-    crate::evm_storage_trie::HeedEthStorageTrieDb,
+    crate::evm_storage_trie::{HeedEthStorageTrieDb, HeedStorageTrieExt},
     eth_trie::{DB, TrieError},
     moved_evm_ext::state::{self, BoxedTrieDb, DbWithRoot, EthTrieDbWithLocalError, StorageTrieDb},
     moved_shared::primitives::{Address, B256},
@@ -53,6 +53,27 @@ impl StorageTrieDb for HeedStorageTrieRepository {
             EthTrieDbWithLocalError::new(EthTrieDbWithHeedError::new(db)),
         )))
     }
+
+    fn accounts(&self) -> Result<Vec<Address>, state::Error> {
+        let transaction = self.env.read_txn().map_err(Error)?;
+
+        let db = self
+            .env
+            .storage_root_database(&transaction)
+            .map_err(Error)?;
+
+        let accounts = db
+            .iter(&transaction)
+            .and_then(|iter| {
+                iter.map(|entry| entry.map(|(account, _)| account))
+                    .collect()
+            })
+            .map_err(Error)?;
+
+        transaction.commit().map_err(Error)?;
+
+        Ok(accounts)
+    }
 }
 
 pub struct EthTrieDbWithHeedError<T: DB>(pub T);
@@ -9,7 +9,9 @@ pub mod evm;
 pub mod evm_storage_trie;
 pub mod generic;
 pub mod payload;
+pub mod preimage;
 pub mod receipt;
+pub mod resource_index;
 pub mod state;
 pub mod transaction;
 pub mod trie;
@@ -2,19 +2,24 @@ use {
     crate::{
         all::HeedDb,
         block::HeedBlockExt,
-        generic::{EncodableB256, EncodableU64},
+        generic::{EncodableB256, EncodableU64, SerdeJson},
         transaction::HeedTransactionExt,
     },
     heed::RoTxn,
-    moved_blockchain::payload::{PayloadId, PayloadQueries, PayloadResponse},
+    moved_blockchain::payload::{PayloadId, PayloadJobRepository, PayloadQueries, PayloadResponse},
     moved_shared::primitives::{B256, ToU64},
+    serde::{Serialize, de::DeserializeOwned},
 };
 
 pub type Key = EncodableU64;
 pub type Value = EncodableB256;
 pub type Db = heed::Database<Key, Value>;
+pub type JobKey = EncodableU64;
+pub type JobValue<A> = SerdeJson<A>;
+pub type JobDb<A> = heed::Database<JobKey, JobValue<A>>;
 
 pub const DB: &str = "payload";
+pub const JOB_DB: &str = "payload_job";
 
 #[derive(Debug, Clone)]
 pub struct HeedPayloadQueries {
@@ -91,8 +96,65 @@ impl PayloadQueries for HeedPayloadQueries {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct HeedPayloadJobRepository {
+    env: &'static heed::Env,
+}
+
+impl HeedPayloadJobRepository {
+    pub fn new(env: &'static heed::Env) -> Self {
+        Self { env }
+    }
+}
+
+impl<A: Serialize + DeserializeOwned + 'static> PayloadJobRepository<A>
+    for HeedPayloadJobRepository
+{
+    type Err = heed::Error;
+
+    fn add(&mut self, id: PayloadId, attributes: &A) -> Result<(), Self::Err> {
+        let mut transaction = self.env.write_txn()?;
+
+        let db = self.env.payload_job_database::<A>(&transaction)?;
+
+        db.put(&mut transaction, &id.to_u64(), attributes)?;
+
+        transaction.commit()
+    }
+
+    fn remove(&mut self, id: PayloadId) -> Result<(), Self::Err> {
+        let mut transaction = self.env.write_txn()?;
+
+        let db = self.env.payload_job_database::<A>(&transaction)?;
+
+        db.delete(&mut transaction, &id.to_u64())?;
+
+        transaction.commit()
+    }
+
+    fn pending(&self) -> Result<Vec<(PayloadId, A)>, Self::Err> {
+        let transaction = self.env.read_txn()?;
+
+        let db = self.env.payload_job_database::<A>(&transaction)?;
+
+        let jobs = db
+            .iter(&transaction)?
+            .map(|v| v.map(|(id, attributes)| (PayloadId::from(id), attributes)))
+            .collect::<Result<Vec<_>, _>>();
+
+        transaction.commit()?;
+
+        jobs
+    }
+}
+
 pub trait HeedPayloadExt {
     fn payload_database(&self, rtxn: &RoTxn) -> heed::Result<HeedDb<Key, Value>>;
+
+    fn payload_job_database<A: 'static>(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<HeedDb<JobKey, JobValue<A>>>;
 }
 
 impl HeedPayloadExt for heed::Env {
@@ -103,4 +165,15 @@ impl HeedPayloadExt for heed::Env {
 
         Ok(HeedDb(db))
     }
+
+    fn payload_job_database<A: 'static>(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<HeedDb<JobKey, JobValue<A>>> {
+        let db: JobDb<A> = self
+            .open_database(rtxn, Some(JOB_DB))?
+            .expect("Payload job database should exist");
+
+        Ok(HeedDb(db))
+    }
 }
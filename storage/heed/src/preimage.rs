@@ -0,0 +1,69 @@
+use {
+    crate::{
+        all::HeedDb,
+        generic::{EncodableB256, EncodableBytes},
+    },
+    heed::RoTxn,
+    moved_shared::primitives::B256,
+    moved_state::preimage::{Preimage, TriePreimages},
+};
+
+pub type Key = EncodableB256;
+pub type Value = EncodableBytes;
+pub type Db = heed::Database<Key, Value>;
+
+pub const DB: &str = "trie_preimage";
+
+/// Preimages of trie keys, stored under the key they hash to.
+#[derive(Debug, Clone)]
+pub struct HeedTriePreimages {
+    env: &'static heed::Env,
+}
+
+impl HeedTriePreimages {
+    pub fn new(env: &'static heed::Env) -> Self {
+        Self { env }
+    }
+}
+
+impl TriePreimages for HeedTriePreimages {
+    type Err = heed::Error;
+
+    fn extend(&self, preimages: Vec<Preimage>) -> Result<(), Self::Err> {
+        let mut transaction = self.env.write_txn()?;
+
+        let db = self.env.preimage_database(&transaction)?;
+
+        preimages
+            .into_iter()
+            .try_for_each(|(key, preimage)| db.put(&mut transaction, &key, preimage.as_slice()))?;
+
+        transaction.commit()
+    }
+
+    fn preimage(&self, key: B256) -> Result<Option<Vec<u8>>, Self::Err> {
+        let transaction = self.env.read_txn()?;
+
+        let db = self.env.preimage_database(&transaction)?;
+
+        let preimage = db.get(&transaction, &key)?.map(<[u8]>::to_vec);
+
+        transaction.commit()?;
+
+        Ok(preimage)
+    }
+}
+
+pub trait HeedPreimageExt {
+    fn preimage_database(&self, rtxn: &RoTxn) -> heed::Result<HeedDb<Key, Value>>;
+}
+
+impl HeedPreimageExt for heed::Env {
+    fn preimage_database(&self, rtxn: &RoTxn) -> heed::Result<HeedDb<Key, Value>> {
+        let db: Db = self
+            .open_database(rtxn, Some(DB))?
+            .expect("Preimage database should exist");
+
+        Ok(HeedDb(db))
+    }
+}
@@ -1,11 +1,12 @@
 use {
     crate::{
         all::HeedDb,
+        block::HeedBlockExt,
         generic::{EncodableB256, SerdeJson},
     },
     heed::RoTxn,
     moved_blockchain::receipt::{
-        ExtendedReceipt, ReceiptQueries, ReceiptRepository, TransactionReceipt,
+        ExtendedReceipt, ReceiptQueries, ReceiptRepository, TransactionReceipt, VmStatus,
     },
     moved_shared::primitives::B256,
 };
@@ -75,6 +76,67 @@ impl ReceiptQueries for HeedReceiptQueries {
 
         Ok(response?.map(TransactionReceipt::from))
     }
+
+    fn vm_status_by_transaction_hash(
+        &self,
+        env: &Self::Storage,
+        transaction_hash: B256,
+    ) -> Result<Option<VmStatus>, Self::Err> {
+        let transaction = env.read_txn()?;
+
+        let db = env.receipt_database(&transaction)?;
+
+        let response = db.get(&transaction, &transaction_hash);
+
+        transaction.commit()?;
+
+        Ok(response?.and_then(|receipt| receipt.vm_status))
+    }
+
+    fn by_block_hash(
+        &self,
+        env: &Self::Storage,
+        block_hash: B256,
+    ) -> Result<Vec<TransactionReceipt>, Self::Err> {
+        let transaction = env.read_txn()?;
+
+        let db = env.block_database(&transaction)?;
+
+        let Some(block) = db.get(&transaction, &block_hash)? else {
+            transaction.commit()?;
+
+            return Ok(Vec::new());
+        };
+
+        let db = env.receipt_database(&transaction)?;
+
+        let receipts = block
+            .transaction_hashes()
+            .filter_map(|hash| db.get(&transaction, &hash).transpose())
+            .map(|receipt| receipt.map(TransactionReceipt::from))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        transaction.commit()?;
+
+        Ok(receipts)
+    }
+
+    fn by_block_height(
+        &self,
+        env: &Self::Storage,
+        block_height: u64,
+    ) -> Result<Vec<TransactionReceipt>, Self::Err> {
+        let transaction = env.read_txn()?;
+
+        let db = env.block_height_database(&transaction)?;
+
+        let hash = db.get(&transaction, &block_height)?;
+
+        transaction.commit()?;
+
+        hash.map(|hash| self.by_block_hash(env, hash))
+            .unwrap_or(Ok(Vec::new()))
+    }
 }
 
 pub trait HeedReceiptExt {
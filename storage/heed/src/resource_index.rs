@@ -0,0 +1,111 @@
+use {
+    crate::{all::HeedDb, generic::EncodableBytes},
+    heed::{RoTxn, RwTxn, types::Unit},
+    move_core_types::{
+        account_address::AccountAddress, effects::ChangeSet, language_storage::StructTag,
+    },
+    moved_state::resource_index::{
+        COMPLETE_KEY, ResourceIndex, index_key, tag_from_index_key, written_resources,
+    },
+    std::ops::Bound,
+};
+
+pub type Key = EncodableBytes;
+pub type Value = Unit;
+pub type Db = heed::Database<Key, Value>;
+
+pub const DB: &str = "resource_index";
+
+/// The resources of each account, stored under their index keys with no value.
+#[derive(Debug, Clone)]
+pub struct HeedResourceIndex<'db> {
+    env: &'db heed::Env,
+}
+
+impl<'db> HeedResourceIndex<'db> {
+    pub fn new(env: &'db heed::Env) -> Self {
+        Self { env }
+    }
+
+    /// Records the resources that `changes` write to as part of `transaction`, marking the index
+    /// as complete if they are the ones of the genesis state.
+    pub(crate) fn extend(
+        &self,
+        transaction: &mut RwTxn,
+        changes: &ChangeSet,
+        is_genesis: bool,
+    ) -> Result<(), heed::Error> {
+        let db = self.env.resource_index_database(transaction)?;
+
+        written_resources(changes)
+            .try_for_each(|(account, tag)| db.put(transaction, &index_key(account, tag), &()))?;
+
+        if is_genesis {
+            db.put(transaction, COMPLETE_KEY.as_slice(), &())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ResourceIndex for HeedResourceIndex<'_> {
+    type Err = heed::Error;
+
+    fn is_complete(&self) -> Result<bool, Self::Err> {
+        let transaction = self.env.read_txn()?;
+
+        let db = self.env.resource_index_database(&transaction)?;
+
+        let is_complete = db.get(&transaction, COMPLETE_KEY.as_slice())?.is_some();
+
+        transaction.commit()?;
+
+        Ok(is_complete)
+    }
+
+    fn resources(
+        &self,
+        account: &AccountAddress,
+        after: Option<&StructTag>,
+        limit: usize,
+    ) -> Result<Vec<StructTag>, Self::Err> {
+        let transaction = self.env.read_txn()?;
+
+        let db = self.env.resource_index_database(&transaction)?;
+
+        let start = after.map(|tag| index_key(account, tag));
+        let range = match &start {
+            Some(key) => (Bound::Excluded(key.as_slice()), Bound::Unbounded),
+            None => (Bound::Included(account.as_ref()), Bound::Unbounded),
+        };
+
+        let tags = db
+            .range(&transaction, &range)?
+            .map(|entry| entry.map(|(key, _)| key))
+            .take_while(|key| {
+                key.as_ref()
+                    .map_or(true, |key| key.starts_with(account.as_ref()))
+            })
+            .take(limit)
+            .map(|key| key.map(tag_from_index_key))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        transaction.commit()?;
+
+        Ok(tags)
+    }
+}
+
+pub trait HeedResourceIndexExt {
+    fn resource_index_database(&self, rtxn: &RoTxn) -> heed::Result<HeedDb<Key, Value>>;
+}
+
+impl HeedResourceIndexExt for heed::Env {
+    fn resource_index_database(&self, rtxn: &RoTxn) -> heed::Result<HeedDb<Key, Value>> {
+        let db: Db = self
+            .open_database(rtxn, Some(DB))?
+            .expect("Resource index database should exist");
+
+        Ok(HeedDb(db))
+    }
+}
@@ -3,9 +3,11 @@ use {
         all::HeedDb,
         generic::{EncodableB256, SerdeJson},
     },
+    alloy::consensus::TxEnvelope,
     heed::RoTxn,
     moved_blockchain::transaction::{
-        ExtendedTransaction, TransactionQueries, TransactionRepository, TransactionResponse,
+        ExtendedTransaction, TransactionJournal, TransactionQueries, TransactionRepository,
+        TransactionResponse,
     },
     moved_shared::primitives::B256,
 };
@@ -14,8 +16,12 @@ pub type Key = EncodableB256;
 pub type Value = EncodableTransaction;
 pub type Db = heed::Database<Key, Value>;
 pub type EncodableTransaction = SerdeJson<ExtendedTransaction>;
+pub type JournalKey = EncodableB256;
+pub type JournalValue = SerdeJson<TxEnvelope>;
+pub type JournalDb = heed::Database<JournalKey, JournalValue>;
 
 pub const DB: &str = "transaction";
+pub const JOURNAL_DB: &str = "transaction_journal";
 
 #[derive(Debug)]
 pub struct HeedTransactionRepository;
@@ -65,8 +71,62 @@ impl TransactionQueries for HeedTransactionQueries {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct HeedTransactionJournal {
+    env: &'static heed::Env,
+}
+
+impl HeedTransactionJournal {
+    pub fn new(env: &'static heed::Env) -> Self {
+        Self { env }
+    }
+}
+
+impl TransactionJournal for HeedTransactionJournal {
+    type Err = heed::Error;
+
+    fn add(&mut self, tx_hash: B256, tx: &TxEnvelope) -> Result<(), Self::Err> {
+        let mut transaction = self.env.write_txn()?;
+
+        let db = self.env.transaction_journal_database(&transaction)?;
+
+        db.put(&mut transaction, &tx_hash, tx)?;
+
+        transaction.commit()
+    }
+
+    fn remove(&mut self, tx_hashes: impl IntoIterator<Item = B256>) -> Result<(), Self::Err> {
+        let mut transaction = self.env.write_txn()?;
+
+        let db = self.env.transaction_journal_database(&transaction)?;
+
+        tx_hashes
+            .into_iter()
+            .try_for_each(|tx_hash| db.delete(&mut transaction, &tx_hash).map(|_| ()))?;
+
+        transaction.commit()
+    }
+
+    fn pending(&self) -> Result<Vec<(B256, TxEnvelope)>, Self::Err> {
+        let transaction = self.env.read_txn()?;
+
+        let db = self.env.transaction_journal_database(&transaction)?;
+
+        let transactions = db.iter(&transaction)?.collect::<Result<Vec<_>, _>>();
+
+        transaction.commit()?;
+
+        transactions
+    }
+}
+
 pub trait HeedTransactionExt {
     fn transaction_database(&self, rtxn: &RoTxn) -> heed::Result<HeedDb<Key, Value>>;
+
+    fn transaction_journal_database(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<HeedDb<JournalKey, JournalValue>>;
 }
 
 impl HeedTransactionExt for heed::Env {
@@ -77,4 +137,15 @@ impl HeedTransactionExt for heed::Env {
 
         Ok(HeedDb(db))
     }
+
+    fn transaction_journal_database(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<HeedDb<JournalKey, JournalValue>> {
+        let db: JournalDb = self
+            .open_database(rtxn, Some(JOURNAL_DB))?
+            .expect("Transaction journal database should exist");
+
+        Ok(HeedDb(db))
+    }
 }
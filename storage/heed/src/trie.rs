@@ -2,9 +2,11 @@ use {
     crate::{
         all::HeedDb,
         generic::{EncodableB256, EncodableBytes, EncodableU64},
+        resource_index::HeedResourceIndex,
     },
     eth_trie::{DB, EthTrie, TrieError},
     heed::RoTxn,
+    move_core_types::effects::ChangeSet,
     moved_shared::primitives::B256,
     std::sync::Arc,
 };
@@ -28,6 +30,10 @@ impl<'db> HeedEthTrieDb<'db> {
         Self { env }
     }
 
+    pub(crate) fn resource_index(&self) -> HeedResourceIndex<'db> {
+        HeedResourceIndex::new(self.env)
+    }
+
     pub fn root(&self) -> Result<Option<B256>, heed::Error> {
         let transaction = self.env.read_txn()?;
 
@@ -49,6 +55,26 @@ impl<'db> HeedEthTrieDb<'db> {
 
         transaction.commit()
     }
+
+    /// Same as [`Self::put_root`], but also records the resources that the `changes` committed to
+    /// by `root` write to in the same transaction, so that the resource index keeps up with the
+    /// state.
+    pub fn put_root_with_resources(
+        &self,
+        root: B256,
+        changes: &ChangeSet,
+    ) -> Result<(), heed::Error> {
+        let mut transaction = self.env.write_txn()?;
+
+        let db = self.env.trie_root_database(&transaction)?;
+
+        let is_genesis = db.get(&transaction, &ROOT_KEY)?.is_none();
+        db.put(&mut transaction, &ROOT_KEY, &root)?;
+        self.resource_index()
+            .extend(&mut transaction, changes, is_genesis)?;
+
+        transaction.commit()
+    }
 }
 
 impl DB for HeedEthTrieDb<'_> {
@@ -1,8 +1,12 @@
-use crate::{block, evm_storage_trie, payload, receipt, state, transaction, trie};
+use crate::{
+    block, evm_storage_trie, payload, preimage, receipt, resource_index, state, transaction, trie,
+};
 
-pub const COLUMN_FAMILIES: [&str; 11] = [
+pub const COLUMN_FAMILIES: [&str; 17] = [
     block::BLOCK_COLUMN_FAMILY,
     block::HEIGHT_COLUMN_FAMILY,
+    block::FORKCHOICE_COLUMN_FAMILY,
+    block::TRANSACTION_COUNT_COLUMN_FAMILY,
     state::COLUMN_FAMILY,
     state::HEIGHT_COLUMN_FAMILY,
     trie::TRIE_COLUMN_FAMILY,
@@ -10,8 +14,12 @@ pub const COLUMN_FAMILIES: [&str; 11] = [
     evm_storage_trie::TRIE_COLUMN_FAMILY,
     evm_storage_trie::ROOT_COLUMN_FAMILY,
     transaction::COLUMN_FAMILY,
+    transaction::JOURNAL_COLUMN_FAMILY,
     receipt::COLUMN_FAMILY,
     payload::COLUMN_FAMILY,
+    payload::JOB_COLUMN_FAMILY,
+    preimage::COLUMN_FAMILY,
+    resource_index::COLUMN_FAMILY,
 ];
 
 #[cfg(test)]
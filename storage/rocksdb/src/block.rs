@@ -1,10 +1,11 @@
 use {
     crate::{
         generic::{FromKey, FromValue, ToKey, ToValue},
-        transaction,
+        payload, transaction,
     },
     moved_blockchain::{
-        block::{BlockQueries, BlockRepository, BlockResponse, ExtendedBlock},
+        block::{BlockQueries, BlockRepository, BlockResponse, ExtendedBlock, RejectedTransaction},
+        payload::PayloadId,
         transaction::ExtendedTransaction,
     },
     moved_shared::primitives::B256,
@@ -13,6 +14,10 @@ use {
 
 pub const BLOCK_COLUMN_FAMILY: &str = "block";
 pub const HEIGHT_COLUMN_FAMILY: &str = "height";
+pub const FORKCHOICE_COLUMN_FAMILY: &str = "forkchoice";
+pub const TRANSACTION_COUNT_COLUMN_FAMILY: &str = "block_transaction_count";
+pub const SAFE_KEY: &str = "safe";
+pub const FINALIZED_KEY: &str = "finalized";
 
 #[derive(Debug)]
 pub struct RocksDbBlockRepository;
@@ -30,6 +35,11 @@ impl BlockRepository for RocksDbBlockRepository {
             block.block.header.number.to_key(),
             block.hash,
         );
+        batch.put_cf(
+            &transaction_count_cf(db),
+            block.hash,
+            block.transaction_count().to_key(),
+        );
 
         db.write(batch)
     }
@@ -49,6 +59,30 @@ impl BlockRepository for RocksDbBlockRepository {
             .transpose()?
             .flatten())
     }
+
+    fn remove_payload_id(
+        &mut self,
+        db: &mut Self::Storage,
+        payload_id: PayloadId,
+        block_hash: B256,
+    ) -> Result<(), Self::Err> {
+        let cf = payload::cf(db);
+        let same_payload = db
+            .get_pinned_cf(&cf, payload_id.to_key())?
+            .is_some_and(|hash| hash.as_ref() == block_hash.as_slice());
+        if same_payload {
+            db.delete_cf(&cf, payload_id.to_key())?;
+        }
+        Ok(())
+    }
+
+    fn mark_safe(&mut self, db: &mut Self::Storage, height: u64) -> Result<(), Self::Err> {
+        db.put_cf(&forkchoice_cf(db), SAFE_KEY, height.to_key())
+    }
+
+    fn mark_finalized(&mut self, db: &mut Self::Storage, height: u64) -> Result<(), Self::Err> {
+        db.put_cf(&forkchoice_cf(db), FINALIZED_KEY, height.to_key())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -108,6 +142,60 @@ impl BlockQueries for RocksDbBlockQueries {
             .transpose()?
             .map(|(height, _)| u64::from_key(height.as_ref())))
     }
+
+    fn safe(&self, db: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+        marker(db, SAFE_KEY)
+    }
+
+    fn finalized(&self, db: &Self::Storage) -> Result<Option<u64>, Self::Err> {
+        marker(db, FINALIZED_KEY)
+    }
+
+    fn rejected_transactions(
+        &self,
+        db: &Self::Storage,
+        height: u64,
+    ) -> Result<Option<Vec<RejectedTransaction>>, Self::Err> {
+        let Some(hash) = db.get_pinned_cf(&height_cf(db), height.to_key())? else {
+            return Ok(None);
+        };
+
+        Ok(db
+            .get_pinned_cf(&block_cf(db), hash)?
+            .map(|v| ExtendedBlock::from_value(v.as_ref()).rejected_transactions))
+    }
+
+    fn transaction_count_by_hash(
+        &self,
+        db: &Self::Storage,
+        hash: B256,
+    ) -> Result<Option<u64>, Self::Err> {
+        if let Some(count) = db.get_pinned_cf(&transaction_count_cf(db), hash)? {
+            return Ok(Some(u64::from_key(count.as_ref())));
+        }
+
+        // Blocks added before their counts were stored are counted from their transaction hashes
+        Ok(db
+            .get_pinned_cf(&block_cf(db), hash)?
+            .map(|v| ExtendedBlock::from_value(v.as_ref()).transaction_count()))
+    }
+
+    fn transaction_count_by_height(
+        &self,
+        db: &Self::Storage,
+        height: u64,
+    ) -> Result<Option<u64>, Self::Err> {
+        db.get_pinned_cf(&height_cf(db), height.to_key())?
+            .map(|hash| B256::new(hash.as_ref().try_into().unwrap()))
+            .map(|hash| self.transaction_count_by_hash(db, hash))
+            .unwrap_or(Ok(None))
+    }
+}
+
+fn marker(db: &RocksDb, key: &str) -> Result<Option<u64>, rocksdb::Error> {
+    Ok(db
+        .get_pinned_cf(&forkchoice_cf(db), key)?
+        .map(|height| u64::from_key(height.as_ref())))
 }
 
 pub(crate) fn block_cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
@@ -115,7 +203,17 @@ pub(crate) fn block_cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
         .expect("Column family should exist")
 }
 
-fn height_cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
+pub(crate) fn height_cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
     db.cf_handle(HEIGHT_COLUMN_FAMILY)
         .expect("Column family should exist")
 }
+
+fn forkchoice_cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
+    db.cf_handle(FORKCHOICE_COLUMN_FAMILY)
+        .expect("Column family should exist")
+}
+
+fn transaction_count_cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
+    db.cf_handle(TRANSACTION_COUNT_COLUMN_FAMILY)
+        .expect("Column family should exist")
+}
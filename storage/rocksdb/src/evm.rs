@@ -1,9 +1,10 @@
 use {
-    crate::evm_storage_trie::RocksEthStorageTrieDb,
+    crate::evm_storage_trie::{ROOT_COLUMN_FAMILY, RocksEthStorageTrieDb},
     eth_trie::{DB, TrieError},
     moved_evm_ext::state::{self, BoxedTrieDb, DbWithRoot, EthTrieDbWithLocalError, StorageTrieDb},
     moved_shared::primitives::{Address, B256},
     moved_trie::StagingEthTrieDb,
+    rocksdb::IteratorMode,
     std::{
         error,
         fmt::{Display, Formatter},
@@ -30,6 +31,22 @@ impl StorageTrieDb for RocksDbStorageTrieRepository {
             EthTrieDbWithLocalError::new(EthTrieWithRocksDbError::new(db)),
         )))
     }
+
+    fn accounts(&self) -> Result<Vec<Address>, state::Error> {
+        let cf = self
+            .db
+            .cf_handle(ROOT_COLUMN_FAMILY)
+            .expect("Column family should exist");
+
+        self.db
+            .iterator_cf(&cf, IteratorMode::Start)
+            .map(|entry| {
+                entry
+                    .map(|(account, _)| Address::from_slice(&account))
+                    .map_err(|e| Error(e).into())
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -4,7 +4,9 @@ pub mod evm;
 pub mod evm_storage_trie;
 pub mod generic;
 pub mod payload;
+pub mod preimage;
 pub mod receipt;
+pub mod resource_index;
 pub mod state;
 pub mod transaction;
 pub mod trie;
@@ -12,6 +14,7 @@ pub mod trie;
 pub use {
     all::COLUMN_FAMILIES,
     block::RocksDbBlockRepository,
+    resource_index::RocksDbResourceIndex,
     rocksdb::{self, DB as RocksDb},
     state::{RocksDbState, RocksDbStateQueries},
     trie::{ROOT_KEY, RocksEthTrieDb},
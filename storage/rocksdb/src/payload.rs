@@ -1,19 +1,21 @@
 use {
     crate::{
         block::block_cf,
-        generic::{FromValue, ToKey},
+        generic::{FromKey, FromValue, ToKey, ToValue},
         transaction,
     },
     moved_blockchain::{
         block::ExtendedBlock,
-        payload::{PayloadId, PayloadQueries, PayloadResponse},
+        payload::{PayloadId, PayloadJobRepository, PayloadQueries, PayloadResponse},
         transaction::ExtendedTransaction,
     },
     moved_shared::primitives::B256,
-    rocksdb::{AsColumnFamilyRef, DB as RocksDb},
+    rocksdb::{AsColumnFamilyRef, DB as RocksDb, IteratorMode},
+    serde::{Serialize, de::DeserializeOwned},
 };
 
 pub const COLUMN_FAMILY: &str = "payload";
+pub const JOB_COLUMN_FAMILY: &str = "payload_job";
 
 impl ToKey for PayloadId {
     fn to_key(&self) -> impl AsRef<[u8]> {
@@ -83,7 +85,50 @@ impl PayloadQueries for RocksDbPayloadQueries {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct RocksDbPayloadJobRepository {
+    db: &'static RocksDb,
+}
+
+impl RocksDbPayloadJobRepository {
+    pub fn new(db: &'static RocksDb) -> Self {
+        Self { db }
+    }
+}
+
+impl<A: Serialize + DeserializeOwned> PayloadJobRepository<A> for RocksDbPayloadJobRepository {
+    type Err = rocksdb::Error;
+
+    fn add(&mut self, id: PayloadId, attributes: &A) -> Result<(), Self::Err> {
+        self.db
+            .put_cf(&job_cf(self.db), id.to_key(), attributes.to_value())
+    }
+
+    fn remove(&mut self, id: PayloadId) -> Result<(), Self::Err> {
+        self.db.delete_cf(&job_cf(self.db), id.to_key())
+    }
+
+    fn pending(&self) -> Result<Vec<(PayloadId, A)>, Self::Err> {
+        self.db
+            .iterator_cf(&job_cf(self.db), IteratorMode::Start)
+            .map(|item| {
+                item.map(|(id, attributes)| {
+                    (
+                        PayloadId::from(u64::from_key(id.as_ref())),
+                        A::from_value(attributes.as_ref()),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
 pub(crate) fn cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
     db.cf_handle(COLUMN_FAMILY)
         .expect("Column family should exist")
 }
+
+fn job_cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
+    db.cf_handle(JOB_COLUMN_FAMILY)
+        .expect("Column family should exist")
+}
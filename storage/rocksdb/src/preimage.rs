@@ -0,0 +1,44 @@
+use {
+    moved_shared::primitives::B256,
+    moved_state::preimage::{Preimage, TriePreimages},
+    rocksdb::{AsColumnFamilyRef, DB as RocksDb, WriteBatchWithTransaction},
+};
+
+pub const COLUMN_FAMILY: &str = "trie_preimage";
+
+/// Preimages of trie keys, stored under the key they hash to.
+#[derive(Debug, Clone)]
+pub struct RocksDbTriePreimages {
+    db: &'static RocksDb,
+}
+
+impl RocksDbTriePreimages {
+    pub fn new(db: &'static RocksDb) -> Self {
+        Self { db }
+    }
+}
+
+impl TriePreimages for RocksDbTriePreimages {
+    type Err = rocksdb::Error;
+
+    fn extend(&self, preimages: Vec<Preimage>) -> Result<(), Self::Err> {
+        let cf = cf(self.db);
+
+        self.db.write(preimages.into_iter().fold(
+            WriteBatchWithTransaction::<false>::default(),
+            |mut batch, (key, preimage)| {
+                batch.put_cf(&cf, key, preimage);
+                batch
+            },
+        ))
+    }
+
+    fn preimage(&self, key: B256) -> Result<Option<Vec<u8>>, Self::Err> {
+        self.db.get_cf(&cf(self.db), key)
+    }
+}
+
+fn cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
+    db.cf_handle(COLUMN_FAMILY)
+        .expect("Column family should exist")
+}
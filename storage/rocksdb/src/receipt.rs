@@ -1,7 +1,13 @@
 use {
-    crate::generic::{FromValue, ToValue},
-    moved_blockchain::receipt::{
-        ExtendedReceipt, ReceiptQueries, ReceiptRepository, TransactionReceipt,
+    crate::{
+        block,
+        generic::{FromValue, ToKey, ToValue},
+    },
+    moved_blockchain::{
+        block::ExtendedBlock,
+        receipt::{
+            ExtendedReceipt, ReceiptQueries, ReceiptRepository, TransactionReceipt, VmStatus,
+        },
     },
     moved_shared::primitives::B256,
     rocksdb::{AsColumnFamilyRef, DB as RocksDb, WriteBatchWithTransaction},
@@ -57,6 +63,51 @@ impl ReceiptQueries for RocksDbReceiptQueries {
             .get_pinned_cf(&cf, transaction_hash)?
             .map(|v| ExtendedReceipt::from_value(v.as_ref()).into()))
     }
+
+    fn vm_status_by_transaction_hash(
+        &self,
+        db: &Self::Storage,
+        transaction_hash: B256,
+    ) -> Result<Option<VmStatus>, Self::Err> {
+        let cf = cf(db);
+
+        Ok(db
+            .get_pinned_cf(&cf, transaction_hash)?
+            .and_then(|v| ExtendedReceipt::from_value(v.as_ref()).vm_status))
+    }
+
+    fn by_block_hash(
+        &self,
+        db: &Self::Storage,
+        block_hash: B256,
+    ) -> Result<Vec<TransactionReceipt>, Self::Err> {
+        let Some(block) = db
+            .get_pinned_cf(&block::block_cf(db), block_hash)?
+            .map(|v| ExtendedBlock::from_value(v.as_ref()))
+        else {
+            return Ok(Vec::new());
+        };
+        let keys = block.transaction_hashes().collect::<Vec<B256>>();
+
+        db.batched_multi_get_cf(&cf(db), keys.iter(), false)
+            .into_iter()
+            .filter_map(|v| {
+                v.map(|v| v.map(|v| ExtendedReceipt::from_value(v.as_ref()).into()))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    fn by_block_height(
+        &self,
+        db: &Self::Storage,
+        block_height: u64,
+    ) -> Result<Vec<TransactionReceipt>, Self::Err> {
+        db.get_pinned_cf(&block::height_cf(db), block_height.to_key())?
+            .map(|hash| B256::new(hash.as_ref().try_into().unwrap()))
+            .map(|hash| self.by_block_hash(db, hash))
+            .unwrap_or(Ok(Vec::new()))
+    }
 }
 
 fn cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
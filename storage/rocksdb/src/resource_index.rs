@@ -0,0 +1,84 @@
+use {
+    move_core_types::{
+        account_address::AccountAddress, effects::ChangeSet, language_storage::StructTag,
+    },
+    moved_state::resource_index::{
+        COMPLETE_KEY, ResourceIndex, index_key, tag_from_index_key, written_resources,
+    },
+    rocksdb::{
+        AsColumnFamilyRef, DB as RocksDb, Direction, IteratorMode, WriteBatchWithTransaction,
+    },
+};
+
+pub const COLUMN_FAMILY: &str = "resource_index";
+
+/// The resources of each account, stored under their index keys with no value.
+#[derive(Debug, Clone)]
+pub struct RocksDbResourceIndex<'db> {
+    db: &'db RocksDb,
+}
+
+impl<'db> RocksDbResourceIndex<'db> {
+    pub fn new(db: &'db RocksDb) -> Self {
+        Self { db }
+    }
+
+    /// Records the resources that `changes` write to in `batch`, marking the index as complete if
+    /// they are the ones of the genesis state.
+    pub(crate) fn extend(
+        &self,
+        batch: &mut WriteBatchWithTransaction<false>,
+        changes: &ChangeSet,
+        is_genesis: bool,
+    ) {
+        let cf = cf(self.db);
+
+        for (account, tag) in written_resources(changes) {
+            batch.put_cf(&cf, index_key(account, tag), []);
+        }
+
+        if is_genesis {
+            batch.put_cf(&cf, COMPLETE_KEY, []);
+        }
+    }
+}
+
+impl ResourceIndex for RocksDbResourceIndex<'_> {
+    type Err = rocksdb::Error;
+
+    fn is_complete(&self) -> Result<bool, Self::Err> {
+        Ok(self.db.get_cf(&cf(self.db), COMPLETE_KEY)?.is_some())
+    }
+
+    fn resources(
+        &self,
+        account: &AccountAddress,
+        after: Option<&StructTag>,
+        limit: usize,
+    ) -> Result<Vec<StructTag>, Self::Err> {
+        let start = match after {
+            Some(tag) => index_key(account, tag),
+            None => account.to_vec(),
+        };
+
+        self.db
+            .iterator_cf(&cf(self.db), IteratorMode::From(&start, Direction::Forward))
+            .map(|entry| entry.map(|(key, _)| key))
+            .skip_while(|key| {
+                key.as_ref()
+                    .is_ok_and(|key| key.as_ref() == start.as_slice())
+            })
+            .take_while(|key| {
+                key.as_ref()
+                    .map_or(true, |key| key.starts_with(account.as_ref()))
+            })
+            .take(limit)
+            .map(|key| key.map(|key| tag_from_index_key(&key)))
+            .collect()
+    }
+}
+
+fn cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
+    db.cf_handle(COLUMN_FAMILY)
+        .expect("Column family should exist")
+}
@@ -1,24 +1,27 @@
 use {
     crate::{
-        RocksDb, RocksEthTrieDb,
+        RocksDb, RocksDbResourceIndex, RocksEthTrieDb,
         generic::{FromKey, ToKey},
         trie::FromOptRoot,
     },
     eth_trie::{DB, EthTrie, TrieError},
-    move_core_types::{account_address::AccountAddress, effects::ChangeSet},
+    move_core_types::{
+        account_address::AccountAddress, effects::ChangeSet, language_storage::StructTag,
+    },
     move_table_extension::{TableChangeSet, TableResolver},
     move_vm_types::resolver::MoveResolver,
     moved_blockchain::state::{
-        Balance, BlockHeight, EthTrieResolver, Nonce, ProofResponse, StateQueries,
-        proof_from_trie_and_resolver,
+        AccountView, Balance, BlockHeight, EthTrieResolver, Nonce, ProofResponse, StateQueries,
+        proof_from_trie_and_resolver, read_account_nonce, resources_from_index,
+        storage_from_resolver,
     },
     moved_evm_ext::state::StorageTrieRepository,
-    moved_execution::{
-        quick_get_eth_balance, quick_get_nonce,
-        transaction::{L2_HIGHEST_ADDRESS, L2_LOWEST_ADDRESS},
-    },
+    moved_execution::read_eth_balance,
     moved_shared::primitives::{B256, ToEthAddress, U256},
-    moved_state::{InsertChangeSetIntoMerkleTrie, State},
+    moved_state::{
+        InsertChangeSetIntoMerkleTrie, State, ToTreeValues, nodes::KeyHashCache,
+        resource_index::ResourceIndex,
+    },
     rocksdb::{AsColumnFamilyRef, WriteBatchWithTransaction},
     std::sync::Arc,
 };
@@ -32,6 +35,7 @@ pub struct RocksDbState<'db> {
     db: Arc<RocksEthTrieDb<'db>>,
     resolver: EthTrieResolver<RocksEthTrieDb<'db>>,
     state_root: Option<B256>,
+    key_hashes: KeyHashCache,
 }
 
 impl<'db> RocksDbState<'db> {
@@ -40,17 +44,34 @@ impl<'db> RocksDbState<'db> {
             .root()
             .expect("Database should be able to fetch state root");
 
+        let key_hashes = KeyHashCache::default();
+
         Self {
-            resolver: EthTrieResolver::new(EthTrie::from_opt_root(db.clone(), state_root)),
+            resolver: EthTrieResolver::with_key_hashes(
+                EthTrie::from_opt_root(db.clone(), state_root),
+                key_hashes.clone(),
+            ),
             state_root,
             db,
+            key_hashes,
         }
     }
 
-    fn persist_state_root(&self) -> Result<(), rocksdb::Error> {
-        self.state_root
-            .map(|root| self.db.put_root(root))
-            .unwrap_or(Ok(()))
+    /// Writes the state root along with the resources that `changes` write to, so that the
+    /// resource index never falls behind the state.
+    fn persist_state_root(&self, changes: &ChangeSet) -> Result<(), TrieError> {
+        self.db
+            .put_root_with_resources(self.state_root(), changes)
+            .map_err(|e| TrieError::DB(e.to_string()))
+    }
+
+    fn insert(&mut self, values: &impl ToTreeValues) -> Result<(), TrieError> {
+        let mut tree = self.tree();
+        let root =
+            tree.insert_change_set_into_merkle_trie_with_key_hashes(values, &self.key_hashes)?;
+        self.state_root.replace(root);
+        self.resolver = EthTrieResolver::with_key_hashes(tree, self.key_hashes.clone());
+        Ok(())
     }
 
     fn tree(&self) -> EthTrie<RocksEthTrieDb<'db>> {
@@ -62,20 +83,18 @@ impl State for RocksDbState<'_> {
     type Err = TrieError;
 
     fn apply(&mut self, changes: ChangeSet) -> Result<(), Self::Err> {
-        let mut tree = self.tree();
-        let root = tree.insert_change_set_into_merkle_trie(&changes)?;
-        self.state_root.replace(root);
-        self.resolver = EthTrieResolver::new(tree);
-        self.persist_state_root().unwrap();
-        Ok(())
+        self.insert(&changes)?;
+        self.persist_state_root(&changes)
     }
 
     fn apply_with_tables(
         &mut self,
         changes: ChangeSet,
-        _table_changes: TableChangeSet,
+        table_changes: TableChangeSet,
     ) -> Result<(), Self::Err> {
-        self.apply(changes)
+        self.insert(&changes)?;
+        self.insert(&table_changes)?;
+        self.persist_state_root(&changes)
     }
 
     fn db(&self) -> Arc<impl DB> {
@@ -96,6 +115,7 @@ pub struct RocksDbStateQueries<'db> {
     db: &'db RocksDb,
     trie_db: Arc<RocksEthTrieDb<'db>>,
     genesis_state_root: B256,
+    key_hashes: KeyHashCache,
 }
 
 impl<'db> RocksDbStateQueries<'db> {
@@ -108,6 +128,7 @@ impl<'db> RocksDbStateQueries<'db> {
             db,
             trie_db,
             genesis_state_root,
+            key_hashes: KeyHashCache::default(),
         }
     }
 
@@ -153,7 +174,10 @@ impl<'db> RocksDbStateQueries<'db> {
         &self,
         height: BlockHeight,
     ) -> Result<impl MoveResolver + TableResolver, rocksdb::Error> {
-        Ok(EthTrieResolver::new(self.tree(height)?))
+        Ok(EthTrieResolver::with_key_hashes(
+            self.tree(height)?,
+            self.key_hashes.clone(),
+        ))
     }
 
     fn height_cf(&self) -> impl AsColumnFamilyRef + use<'_> {
@@ -172,24 +196,47 @@ impl<'db> RocksDbStateQueries<'db> {
 impl StateQueries for RocksDbStateQueries<'_> {
     fn balance_at(
         &self,
-        evm_storage: &impl StorageTrieRepository,
+        _evm_storage: &impl StorageTrieRepository,
         account: AccountAddress,
         height: BlockHeight,
     ) -> Option<Balance> {
         let resolver = self.resolver(height).ok()?;
 
-        Some(quick_get_eth_balance(&account, &resolver, evm_storage))
+        read_eth_balance(&account, &resolver).ok()
     }
 
     fn nonce_at(
         &self,
-        evm_storage: &impl StorageTrieRepository,
+        _evm_storage: &impl StorageTrieRepository,
         account: AccountAddress,
         height: BlockHeight,
     ) -> Option<Nonce> {
         let resolver = self.resolver(height).ok()?;
 
-        Some(quick_get_nonce(&account, &resolver, evm_storage))
+        read_account_nonce(&account, &resolver).ok()
+    }
+
+    fn account_at(
+        &self,
+        _evm_storage: &impl StorageTrieRepository,
+        account: AccountAddress,
+        height: BlockHeight,
+    ) -> Option<AccountView> {
+        let resolver = self.resolver(height).ok()?;
+
+        AccountView::read(&account, &resolver).ok()
+    }
+
+    fn storage_at(
+        &self,
+        evm_storage: &impl StorageTrieRepository,
+        account: AccountAddress,
+        index: U256,
+        height: BlockHeight,
+    ) -> Option<U256> {
+        let resolver = self.resolver(height).ok()?;
+
+        storage_from_resolver(account.to_eth_address(), index, &resolver, evm_storage)
     }
 
     fn proof_at(
@@ -200,19 +247,34 @@ impl StateQueries for RocksDbStateQueries<'_> {
         height: BlockHeight,
     ) -> Option<ProofResponse> {
         let address = account.to_eth_address();
-
-        // Only L2 contract addresses supported at this time
-        if address < L2_LOWEST_ADDRESS || L2_HIGHEST_ADDRESS < address {
-            return None;
-        }
-
         let mut tree = self.tree(height).ok()?;
         let resolver = self.resolver(height).ok()?;
 
         proof_from_trie_and_resolver(address, storage_slots, &mut tree, &resolver, evm_storage)
     }
 
+    fn resources_at(
+        &self,
+        account: AccountAddress,
+        after: Option<&StructTag>,
+        limit: usize,
+        height: BlockHeight,
+    ) -> Option<Vec<(StructTag, Vec<u8>)>> {
+        let resolver = self.resolver(height).ok()?;
+        let index = RocksDbResourceIndex::new(self.db);
+
+        resources_from_index(&index, &resolver, &account, after, limit)
+    }
+
+    fn is_resource_index_complete(&self) -> bool {
+        matches!(RocksDbResourceIndex::new(self.db).is_complete(), Ok(true))
+    }
+
     fn resolver_at(&self, height: BlockHeight) -> impl MoveResolver + TableResolver + '_ {
         self.resolver(height).unwrap()
     }
+
+    fn trie_node(&self, hash: B256) -> Option<Vec<u8>> {
+        self.trie_db.get(hash.as_slice()).ok().flatten()
+    }
 }
@@ -1,13 +1,16 @@
 use {
     crate::generic::{FromValue, ToValue},
+    alloy::consensus::TxEnvelope,
     moved_blockchain::transaction::{
-        ExtendedTransaction, TransactionQueries, TransactionRepository, TransactionResponse,
+        ExtendedTransaction, TransactionJournal, TransactionQueries, TransactionRepository,
+        TransactionResponse,
     },
     moved_shared::primitives::B256,
-    rocksdb::{AsColumnFamilyRef, DB as RocksDb, WriteBatchWithTransaction},
+    rocksdb::{AsColumnFamilyRef, DB as RocksDb, IteratorMode, WriteBatchWithTransaction},
 };
 
 pub const COLUMN_FAMILY: &str = "transaction";
+pub const JOURNAL_COLUMN_FAMILY: &str = "transaction_journal";
 
 #[derive(Debug)]
 pub struct RocksDbTransactionRepository;
@@ -53,6 +56,51 @@ impl TransactionQueries for RocksDbTransactionQueries {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct RocksDbTransactionJournal {
+    db: &'static RocksDb,
+}
+
+impl RocksDbTransactionJournal {
+    pub fn new(db: &'static RocksDb) -> Self {
+        Self { db }
+    }
+}
+
+impl TransactionJournal for RocksDbTransactionJournal {
+    type Err = rocksdb::Error;
+
+    fn add(&mut self, tx_hash: B256, tx: &TxEnvelope) -> Result<(), Self::Err> {
+        self.db.put_cf(&journal_cf(self.db), tx_hash, tx.to_value())
+    }
+
+    fn remove(&mut self, tx_hashes: impl IntoIterator<Item = B256>) -> Result<(), Self::Err> {
+        let cf = journal_cf(self.db);
+
+        self.db.write(tx_hashes.into_iter().fold(
+            WriteBatchWithTransaction::<false>::default(),
+            |mut batch, tx_hash| {
+                batch.delete_cf(&cf, tx_hash);
+                batch
+            },
+        ))
+    }
+
+    fn pending(&self) -> Result<Vec<(B256, TxEnvelope)>, Self::Err> {
+        self.db
+            .iterator_cf(&journal_cf(self.db), IteratorMode::Start)
+            .map(|item| {
+                item.map(|(tx_hash, tx)| {
+                    (
+                        B256::from_slice(tx_hash.as_ref()),
+                        TxEnvelope::from_value(tx.as_ref()),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
 pub(crate) fn cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
     db.cf_handle(COLUMN_FAMILY)
         .expect("Column family should exist")
@@ -142,3 +190,8 @@ mod tests {
         assert_eq!(actual_transaction, expected_transaction);
     }
 }
+
+fn journal_cf(db: &RocksDb) -> impl AsColumnFamilyRef + use<'_> {
+    db.cf_handle(JOURNAL_COLUMN_FAMILY)
+        .expect("Column family should exist")
+}
@@ -1,5 +1,7 @@
 use {
+    crate::resource_index::RocksDbResourceIndex,
     eth_trie::{DB, EthTrie, TrieError},
+    move_core_types::effects::ChangeSet,
     moved_shared::primitives::B256,
     rocksdb::{AsColumnFamilyRef, DB as RocksDb, WriteBatchWithTransaction},
     std::sync::Arc,
@@ -29,6 +31,26 @@ impl<'db> RocksEthTrieDb<'db> {
         self.db.put_cf(self.root_cf(), ROOT_KEY, root.as_slice())
     }
 
+    /// Same as [`Self::put_root`], but also records the resources that the `changes` committed to
+    /// by `root` write to in the same batch, so that the resource index keeps up with the state.
+    pub fn put_root_with_resources(
+        &self,
+        root: B256,
+        changes: &ChangeSet,
+    ) -> Result<(), rocksdb::Error> {
+        let is_genesis = self.root()?.is_none();
+        let mut batch = WriteBatchWithTransaction::<false>::default();
+        batch.put_cf(self.root_cf(), ROOT_KEY, root.as_slice());
+        self.resource_index()
+            .extend(&mut batch, changes, is_genesis);
+
+        self.db.write(batch)
+    }
+
+    pub(crate) fn resource_index(&self) -> RocksDbResourceIndex<'db> {
+        RocksDbResourceIndex::new(self.db)
+    }
+
     fn cf(&self) -> &impl AsColumnFamilyRef {
         self.db
             .cf_handle(TRIE_COLUMN_FAMILY)
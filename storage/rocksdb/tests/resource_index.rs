@@ -0,0 +1,57 @@
+use {
+    move_core_types::{
+        account_address::AccountAddress,
+        effects::{ChangeSet, Op},
+        ident_str,
+        language_storage::StructTag,
+    },
+    moved_shared::primitives::B256,
+    moved_state::resource_index::ResourceIndex,
+    moved_storage_rocksdb::{RocksDbResourceIndex, RocksEthTrieDb},
+};
+
+mod common;
+
+fn changes_writing(account: AccountAddress, tag: &StructTag) -> ChangeSet {
+    let mut changes = ChangeSet::new();
+    changes
+        .add_resource_op(account, tag.clone(), Op::New(vec![1].into()))
+        .unwrap();
+    changes
+}
+
+#[test]
+fn test_resource_index_is_only_complete_if_it_records_genesis() {
+    let account = AccountAddress::new([0x11; 32]);
+    let tag = StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("counter").into(),
+        name: ident_str!("Counter").into(),
+        type_args: Vec::new(),
+    };
+
+    // The resources are recorded along with the first state root
+    let rocks = common::create_db();
+    let db = RocksEthTrieDb::new(&rocks);
+    db.put_root_with_resources(B256::repeat_byte(1), &changes_writing(account, &tag))
+        .unwrap();
+    let index = RocksDbResourceIndex::new(&rocks);
+
+    assert!(index.is_complete().unwrap());
+    assert_eq!(
+        index.resources(&account, None, 10).unwrap(),
+        vec![tag.clone()]
+    );
+    drop(rocks);
+
+    // The database had a state before its resources were recorded
+    let rocks = common::create_db();
+    let db = RocksEthTrieDb::new(&rocks);
+    db.put_root(B256::repeat_byte(1)).unwrap();
+    db.put_root_with_resources(B256::repeat_byte(2), &changes_writing(account, &tag))
+        .unwrap();
+    let index = RocksDbResourceIndex::new(&rocks);
+
+    assert!(!index.is_complete().unwrap());
+    assert_eq!(index.resources(&account, None, 10).unwrap(), vec![tag]);
+}
@@ -0,0 +1,71 @@
+use {
+    eth_trie::DB,
+    std::{
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// A [`DB`] implementation that remembers every node read from the `inner` storage.
+///
+/// Trie nodes are addressed by their hash, so a cached node never goes stale. Walking the trie
+/// for many keys through this database reads the nodes on their shared path only once, which is
+/// what makes proving a batch of keys cheaper than proving each key on its own.
+#[derive(Debug)]
+pub struct CachedEthTrieDb<D> {
+    pub inner: Arc<D>,
+    nodes: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl<D: DB> CachedEthTrieDb<D> {
+    pub fn new(inner: Arc<D>) -> Self {
+        Self {
+            inner,
+            nodes: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<D: DB> DB for CachedEthTrieDb<D> {
+    type Error = D::Error;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        if let Some(value) = self.nodes.read().unwrap().get(key) {
+            return Ok(Some(value.clone()));
+        }
+
+        let value = self.inner.get(key)?;
+        if let Some(value) = &value {
+            self.nodes
+                .write()
+                .unwrap()
+                .insert(key.to_vec(), value.clone());
+        }
+
+        Ok(value)
+    }
+
+    fn insert(&self, key: &[u8], value: Vec<u8>) -> Result<(), Self::Error> {
+        self.nodes.write().unwrap().remove(key);
+        self.inner.insert(key, value)
+    }
+
+    fn insert_batch(&self, keys: Vec<Vec<u8>>, values: Vec<Vec<u8>>) -> Result<(), Self::Error> {
+        {
+            let mut nodes = self.nodes.write().unwrap();
+            for key in &keys {
+                nodes.remove(key);
+            }
+        }
+        self.inner.insert_batch(keys, values)
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), Self::Error> {
+        self.nodes.write().unwrap().remove(key);
+        self.inner.remove(key)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
@@ -1,3 +1,4 @@
-pub use staging::StagingEthTrieDb;
+pub use {cached::CachedEthTrieDb, staging::StagingEthTrieDb};
 
+mod cached;
 mod staging;